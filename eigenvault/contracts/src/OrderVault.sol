@@ -90,7 +90,8 @@ contract OrderVault is IOrderVault {
             deadline: deadline,
             timestamp: block.timestamp,
             retrieved: false,
-            expired: false
+            expired: false,
+            cancelled: false
         });
 
         // Store the order
@@ -116,6 +117,7 @@ contract OrderVault is IOrderVault {
         
         require(!vaultOrder.retrieved, "Order already retrieved");
         require(!vaultOrder.expired, "Order expired");
+        require(!vaultOrder.cancelled, "Order cancelled");
         require(block.timestamp <= vaultOrder.deadline, "Order deadline passed");
 
         // Mark as retrieved
@@ -152,6 +154,26 @@ contract OrderVault is IOrderVault {
         emit OrderExpired(orderId, vaultOrder.trader, block.timestamp);
     }
 
+    /// @inheritdoc IOrderVault
+    function cancelOrder(bytes32 orderId) external validOrderId(orderId) {
+        VaultOrder storage vaultOrder = vaultOrders[orderId];
+
+        require(msg.sender == vaultOrder.trader, "Not order trader");
+        require(!vaultOrder.cancelled, "Order already cancelled");
+        require(!vaultOrder.expired, "Order expired");
+        require(block.timestamp <= vaultOrder.deadline, "Order deadline passed");
+
+        vaultOrder.cancelled = true;
+
+        // Retrieved orders already left the active set; operators holding them drop
+        // them when they see the event
+        if (!vaultOrder.retrieved) {
+            _removeFromActiveOrders(orderId);
+        }
+
+        emit OrderCancelled(orderId, msg.sender, block.timestamp);
+    }
+
     /// @inheritdoc IOrderVault
     function getVaultOrder(bytes32 orderId) 
         external 
@@ -177,6 +199,7 @@ contract OrderVault is IOrderVault {
         
         valid = !vaultOrder.retrieved && 
                 !vaultOrder.expired && 
+                !vaultOrder.cancelled && 
                 block.timestamp <= vaultOrder.deadline;
         
         return (exists, valid);
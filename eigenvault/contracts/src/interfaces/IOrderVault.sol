@@ -26,6 +26,13 @@ interface IOrderVault {
         uint256 timestamp
     );
 
+    /// @notice Emitted when the trader cancels an order
+    event OrderCancelled(
+        bytes32 indexed orderId,
+        address indexed trader,
+        uint256 timestamp
+    );
+
     /// @notice Structure for vault storage metadata
     struct VaultOrder {
         bytes32 orderId;
@@ -35,6 +42,7 @@ interface IOrderVault {
         uint256 timestamp;
         bool retrieved;
         bool expired;
+        bool cancelled;
     }
 
     /// @notice Store an encrypted order in the vault
@@ -58,6 +66,10 @@ interface IOrderVault {
     /// @param orderId The order identifier
     function expireOrder(bytes32 orderId) external;
 
+    /// @notice Cancel an order (trader only), including one an operator already retrieved
+    /// @param orderId The order identifier
+    function cancelOrder(bytes32 orderId) external;
+
     /// @notice Get vault order metadata
     /// @param orderId The order identifier
     /// @return vaultOrder The vault order details
@@ -0,0 +1,7 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // The gRPC admin API is only part of the full operator
+    #[cfg(feature = "full")]
+    tonic_build::configure()
+        .compile(&["proto/eigenvault/operator/v1/admin.proto"], &["proto"])?;
+    Ok(())
+}
@@ -0,0 +1,289 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::config::AccountingConfig;
+use crate::matching::{Decimal, OrderMatch, TraderRebate};
+use crate::metrics::metrics;
+use crate::sim::clock;
+use crate::storage::SubmissionKind;
+use super::report::ProfitAndLoss;
+
+const WEI_PER_GWEI: u128 = 1_000_000_000;
+
+/// Submitted tasks remembered while waiting for confirmation
+const MAX_SUBMITTED_TASKS: usize = 10_000;
+
+/// Submitted tasks older than this are assumed never to confirm
+const SUBMITTED_TASK_TTL_SECS: u64 = 24 * 3600;
+
+/// Pool label for tasks booked without any match
+const NO_POOL: &str = "none";
+
+/// One confirmed task as booked in the earnings ledger
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EarningsEntry {
+    pub timestamp: u64,
+    pub task_id: String,
+    /// Absent from entries booked before task types were recorded
+    #[serde(default)]
+    pub kind: Option<SubmissionKind>,
+    /// One transaction per registration that responded
+    pub tx_hashes: Vec<String>,
+    pub reward_wei: u128,
+    pub gas_wei: u128,
+    /// Matching fees by pool, in the pool's quote token
+    pub fees: BTreeMap<String, Decimal>,
+}
+
+impl EarningsEntry {
+    pub fn task_type(&self) -> &'static str {
+        self.kind.as_ref().map_or("unknown", SubmissionKind::label)
+    }
+
+    /// Reward and gas split evenly across the pools the task matched in
+    pub fn by_pool(&self) -> Vec<(String, ProfitAndLoss)> {
+        let pools: Vec<&str> = if self.fees.is_empty() {
+            vec![NO_POOL]
+        } else {
+            self.fees.keys().map(String::as_str).collect()
+        };
+
+        let share = pools.len() as u128;
+        pools.into_iter()
+            .map(|pool_key| (pool_key.to_string(), ProfitAndLoss {
+                tasks: 1,
+                reward_wei: self.reward_wei / share,
+                gas_wei: self.gas_wei / share,
+                fees: self.fees.get(pool_key).copied().unwrap_or_default(),
+            }))
+            .collect()
+    }
+}
+
+/// A task response awaiting confirmation
+#[derive(Debug, Clone)]
+pub struct SubmittedTask {
+    pub kind: SubmissionKind,
+    pub tx_hashes: Vec<String>,
+    pub fees: BTreeMap<String, Decimal>,
+    submitted_at: u64,
+}
+
+impl SubmittedTask {
+    pub fn new(kind: SubmissionKind, tx_hashes: Vec<String>, fees: BTreeMap<String, Decimal>) -> Self {
+        Self { kind, tx_hashes, fees, submitted_at: clock::unix_secs() }
+    }
+}
+
+/// Books the reward, matching fees and gas of every confirmed task into an append-only ledger.
+///
+/// Submitted tasks are only held in memory, so tasks confirmed after a restart aren't booked.
+pub struct Accountant {
+    config: AccountingConfig,
+    ledger: Option<Mutex<File>>,
+    submitted: Mutex<HashMap<String, SubmittedTask>>,
+}
+
+impl Accountant {
+    /// Open or create the ledger at `config.path`; without a ledger the fee schedule still
+    /// applies to rebates
+    pub fn open(config: AccountingConfig) -> Result<Self> {
+        if !config.enabled {
+            return Ok(Self { config, ledger: None, submitted: Mutex::new(HashMap::new()) });
+        }
+
+        let path = Path::new(&config.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)
+            .with_context(|| format!("Failed to open earnings ledger {}", config.path))?;
+
+        Ok(Self {
+            config,
+            ledger: Some(Mutex::new(file)),
+            submitted: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Accountant that books nothing
+    pub fn disabled() -> Self {
+        Self {
+            config: AccountingConfig { enabled: false, ..AccountingConfig::default() },
+            ledger: None,
+            submitted: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Matching fees earned on `matches` after their rebates, by pool
+    pub fn fees(&self, matches: &[OrderMatch]) -> BTreeMap<String, Decimal> {
+        let mut fees = BTreeMap::new();
+        for order_match in matches {
+            let rebated: Decimal = order_match.rebates.iter().map(|rebate| rebate.amount).sum();
+            *fees.entry(order_match.pool_key.clone()).or_default() += self.fee(order_match).saturating_sub(rebated);
+        }
+        fees
+    }
+
+    /// Attach to each match the share of its fee returned to the buyer and the seller
+    pub fn apply_rebates(&self, matches: &mut [OrderMatch]) {
+        if self.config.rebate_percent == 0 {
+            return;
+        }
+
+        for order_match in matches {
+            // Half of `rebate_percent` each, in basis points of the fee
+            let amount = self.fee(order_match).mul_bps(self.config.rebate_percent * 50).unwrap_or_default();
+            order_match.rebates = [&order_match.buy_order.trader, &order_match.sell_order.trader].into_iter()
+                .map(|trader| TraderRebate { trader: trader.clone(), amount })
+                .collect();
+        }
+    }
+
+    /// Fee in the quote token; a match too large to price earns none rather than a wrong one
+    fn fee(&self, order_match: &OrderMatch) -> Decimal {
+        order_match.matched_amount.checked_mul(order_match.matched_price)
+            .and_then(|quote| quote.mul_bps(self.config.fee_bps))
+            .unwrap_or_default()
+    }
+
+    /// Hold on to a task response until its confirmation is booked
+    pub fn submitted(&self, task_id: &str, task: SubmittedTask) {
+        if self.ledger.is_none() {
+            return;
+        }
+
+        let mut submitted = self.submitted.lock().unwrap();
+        if submitted.len() >= MAX_SUBMITTED_TASKS {
+            let now = clock::unix_secs();
+            submitted.retain(|_, task| task.submitted_at + SUBMITTED_TASK_TTL_SECS > now);
+        }
+        submitted.insert(task_id.to_string(), task);
+    }
+
+    /// The task submitted as `task_id`, if it is still waiting to be booked
+    pub fn take_submitted(&self, task_id: &str) -> Option<SubmittedTask> {
+        self.submitted.lock().unwrap().remove(task_id)
+    }
+
+    /// Append the confirmed `task`, whose transactions cost `gas_wei`
+    pub fn book(&self, task_id: &str, task: SubmittedTask, gas_wei: u128) -> Result<EarningsEntry> {
+        let entry = EarningsEntry {
+            timestamp: clock::unix_secs(),
+            task_id: task_id.to_string(),
+            kind: Some(task.kind),
+            reward_wei: u128::from(self.config.task_reward_gwei) * WEI_PER_GWEI * task.tx_hashes.len() as u128,
+            gas_wei,
+            fees: task.fees,
+            tx_hashes: task.tx_hashes,
+        };
+
+        if let Some(ledger) = &self.ledger {
+            let mut line = serde_json::to_vec(&entry)?;
+            line.push(b'\n');
+            ledger.lock().map_err(|_| anyhow!("Earnings ledger lock poisoned"))?.write_all(&line)?;
+        }
+
+        // Running P&L since start; the ledger keeps the full history
+        for (pool_key, pnl) in entry.by_pool() {
+            metrics().pnl_gwei
+                .with_label_values(&[&pool_key, entry.task_type()])
+                .add((pnl.net_wei() / WEI_PER_GWEI as i128) as i64);
+        }
+        Ok(entry)
+    }
+
+    /// Every entry of the ledger at `path`
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Vec<EarningsEntry>> {
+        let file = File::open(path.as_ref())
+            .with_context(|| format!("No earnings ledger at {:?}", path.as_ref()))?;
+
+        let mut entries = Vec::new();
+        for (line_number, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            entries.push(serde_json::from_str(&line)
+                .map_err(|e| anyhow!("Malformed earnings entry on line {}: {}", line_number + 1, e))?);
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matching::{Order, OrderType};
+    use std::sync::Arc;
+    use tempfile::TempDir;
+
+    fn order_match(pool_key: &str, amount: u64, price: u64) -> OrderMatch {
+        let (amount, price) = (Decimal::from(amount), Decimal::from(price));
+        let order = |order_type| Arc::new(Order::new("order".to_string(), "trader".to_string(), pool_key.to_string(), order_type, amount, price, 0));
+        OrderMatch {
+            match_id: "match".to_string(),
+            buy_order: order(OrderType::Buy),
+            sell_order: order(OrderType::Sell),
+            matched_price: price,
+            matched_amount: amount,
+            timestamp: 0,
+            pool_key: pool_key.to_string(),
+            rebates: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_confirmed_tasks_are_booked() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("earnings.jsonl");
+        let config = AccountingConfig {
+            path: path.display().to_string(),
+            task_reward_gwei: 1_000_000,
+            fee_bps: 10,
+            ..AccountingConfig::default()
+        };
+        let accountant = Accountant::open(config)?;
+
+        let fees = accountant.fees(&[order_match("ETH_USDC_3000", 2, 2000), order_match("ETH_USDC_3000", 1, 2000)]);
+        assert_eq!(fees["ETH_USDC_3000"], Decimal::from(6));
+        accountant.submitted("task_1", SubmittedTask::new(SubmissionKind::TaskResponse, vec!["0x1".to_string(), "0x2".to_string()], fees));
+
+        let task = accountant.take_submitted("task_1").unwrap();
+        assert!(accountant.take_submitted("task_1").is_none());
+        accountant.book("task_1", task, 42)?;
+        drop(accountant);
+
+        let entries = Accountant::read(&path)?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].reward_wei, 2_000_000_000_000_000);
+        assert_eq!(entries[0].gas_wei, 42);
+        assert_eq!(entries[0].task_type(), "task_response");
+        Ok(())
+    }
+
+    #[test]
+    fn test_rebates_come_out_of_fees() -> Result<()> {
+        let config = AccountingConfig { enabled: false, fee_bps: 10, rebate_percent: 50, ..AccountingConfig::default() };
+        let accountant = Accountant::open(config)?;
+
+        let mut matches = vec![order_match("ETH_USDC_3000", 2, 2000)];
+        accountant.apply_rebates(&mut matches);
+        assert_eq!(matches[0].rebates.len(), 2);
+        assert_eq!(matches[0].rebates[0].amount, Decimal::ONE);
+        assert_eq!(accountant.fees(&matches)["ETH_USDC_3000"], Decimal::from(2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_disabled_accountant_holds_nothing() {
+        let accountant = Accountant::disabled();
+        accountant.submitted("task_1", SubmittedTask::new(SubmissionKind::MatchingProof, vec!["0x1".to_string()], BTreeMap::new()));
+        assert!(accountant.take_submitted("task_1").is_none());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod ledger;
+pub mod report;
+
+pub use ledger::{Accountant, EarningsEntry, SubmittedTask};
+pub use report::{parse_period, EarningsReport};
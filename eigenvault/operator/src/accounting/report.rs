@@ -0,0 +1,171 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+
+use crate::bootstrap::format_ether;
+use crate::matching::Decimal;
+use super::EarningsEntry;
+
+/// Task rewards against the gas spent earning them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProfitAndLoss {
+    pub tasks: usize,
+    pub reward_wei: u128,
+    pub gas_wei: u128,
+    /// Matching fees in the pool's quote token; not summed across pools
+    pub fees: Decimal,
+}
+
+impl ProfitAndLoss {
+    pub fn net_wei(&self) -> i128 {
+        self.reward_wei as i128 - self.gas_wei as i128
+    }
+
+    fn add(&mut self, other: &ProfitAndLoss) {
+        self.tasks += other.tasks;
+        self.reward_wei += other.reward_wei;
+        self.gas_wei += other.gas_wei;
+        self.fees += other.fees;
+    }
+}
+
+/// Totals over the ledger entries of a reporting period
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EarningsReport {
+    pub since: u64,
+    pub tasks: usize,
+    pub transactions: usize,
+    pub reward_wei: u128,
+    pub gas_wei: u128,
+    /// Matching fees by pool, in the pool's quote token
+    pub fees: BTreeMap<String, Decimal>,
+    /// P&L by pool, with each task's reward and gas split evenly across its pools
+    pub by_pool: BTreeMap<String, ProfitAndLoss>,
+    /// P&L by task type
+    pub by_task_type: BTreeMap<String, ProfitAndLoss>,
+}
+
+impl EarningsReport {
+    /// Totals of the `entries` booked at or after `since`
+    pub fn new(entries: &[EarningsEntry], since: u64) -> Self {
+        let mut report = Self { since, ..Self::default() };
+        for entry in entries.iter().filter(|entry| entry.timestamp >= since) {
+            report.tasks += 1;
+            report.transactions += entry.tx_hashes.len();
+            report.reward_wei += entry.reward_wei;
+            report.gas_wei += entry.gas_wei;
+            for (pool_key, fee) in &entry.fees {
+                *report.fees.entry(pool_key.clone()).or_default() += *fee;
+            }
+            for (pool_key, pnl) in entry.by_pool() {
+                report.by_pool.entry(pool_key).or_default().add(&pnl);
+            }
+            report.by_task_type.entry(entry.task_type().to_string()).or_default().add(&ProfitAndLoss {
+                tasks: 1,
+                reward_wei: entry.reward_wei,
+                gas_wei: entry.gas_wei,
+                fees: Decimal::ZERO,
+            });
+        }
+        report
+    }
+
+    pub fn print(&self) {
+        let since = chrono::DateTime::from_timestamp(self.since as i64, 0)
+            .map(|time| time.format("%Y-%m-%d %H:%M UTC").to_string())
+            .unwrap_or_else(|| self.since.to_string());
+
+        println!("Earnings since {}", since);
+        println!("  Tasks:         {} ({} transactions)", self.tasks, self.transactions);
+        println!("  Task rewards:  {} ETH", format_ether(self.reward_wei));
+        println!("  Gas spent:     {} ETH", format_ether(self.gas_wei));
+        println!("  Net:           {} ETH", format_net(self.reward_wei as i128 - self.gas_wei as i128));
+        if self.tasks == 0 {
+            return;
+        }
+
+        println!("  Net by pool (ETH; fees in quote token):");
+        for (pool_key, pnl) in &self.by_pool {
+            println!("    {:<24} {:>6} tasks  {:>14}  fees {:.6}", pool_key, pnl.tasks, format_net(pnl.net_wei()), pnl.fees);
+        }
+        println!("  Net by task type (ETH):");
+        for (task_type, pnl) in &self.by_task_type {
+            println!("    {:<24} {:>6} tasks  {:>14}", task_type, pnl.tasks, format_net(pnl.net_wei()));
+        }
+    }
+}
+
+fn format_net(net_wei: i128) -> String {
+    if net_wei < 0 {
+        format!("-{}", format_ether(net_wei.unsigned_abs()))
+    } else {
+        format_ether(net_wei as u128)
+    }
+}
+
+/// Parse a reporting period such as `30d`, `12h` or `2w` into seconds
+pub fn parse_period(period: &str) -> Result<u64> {
+    let invalid = || anyhow::anyhow!("Invalid period '{}', expected e.g. 12h, 30d or 2w", period);
+    let period = period.trim();
+    let unit = period.chars().last().ok_or_else(invalid)?;
+    let unit_secs = match unit {
+        'h' => 3600,
+        'd' => 24 * 3600,
+        'w' => 7 * 24 * 3600,
+        _ => return Err(invalid()),
+    };
+    let count: u64 = period[..period.len() - 1].parse().map_err(|_| invalid())?;
+    count.checked_mul(unit_secs).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SubmissionKind;
+
+    fn entry(timestamp: u64, fee: u64) -> EarningsEntry {
+        EarningsEntry {
+            timestamp,
+            task_id: format!("task_{}", timestamp),
+            kind: Some(SubmissionKind::TaskResponse),
+            tx_hashes: vec!["0x1".to_string()],
+            reward_wei: 100,
+            gas_wei: 30,
+            fees: BTreeMap::from([("ETH_USDC_3000".to_string(), Decimal::from(fee))]),
+        }
+    }
+
+    #[test]
+    fn test_report_covers_period_only() {
+        let report = EarningsReport::new(&[entry(10, 1), entry(20, 2), entry(30, 4)], 20);
+        assert_eq!(report.tasks, 2);
+        assert_eq!(report.reward_wei, 200);
+        assert_eq!(report.gas_wei, 60);
+        assert_eq!(report.fees["ETH_USDC_3000"], Decimal::from(6));
+    }
+
+    #[test]
+    fn test_pnl_splits_tasks_across_pools() {
+        let mut shared = entry(10, 1);
+        shared.fees.insert("WBTC_USDC_3000".to_string(), Decimal::from(3));
+        let mut proof = entry(20, 0);
+        proof.kind = Some(SubmissionKind::MatchingProof);
+        proof.gas_wei = 130;
+
+        let report = EarningsReport::new(&[shared, proof], 0);
+        assert_eq!(report.by_pool["ETH_USDC_3000"].net_wei(), 35 - 30);
+        assert_eq!(report.by_pool["WBTC_USDC_3000"].net_wei(), 35);
+        assert_eq!(report.by_pool["WBTC_USDC_3000"].fees, Decimal::from(3));
+        assert_eq!(report.by_task_type["task_response"].net_wei(), 70);
+        assert_eq!(report.by_task_type["matching_proof"].net_wei(), -30);
+    }
+
+    #[test]
+    fn test_parse_period() {
+        assert_eq!(parse_period("30d").unwrap(), 30 * 24 * 3600);
+        assert_eq!(parse_period("12h").unwrap(), 12 * 3600);
+        assert_eq!(parse_period("2w").unwrap(), 14 * 24 * 3600);
+        assert!(parse_period("30").is_err());
+        assert!(parse_period("d").is_err());
+        assert!(parse_period("-1d").is_err());
+    }
+}
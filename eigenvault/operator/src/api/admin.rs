@@ -0,0 +1,100 @@
+use axum::{extract::State, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::config::{ConfigPatch, RuntimeSettings};
+use crate::matching::screening;
+use crate::telemetry;
+use super::auth::ControlAccess;
+use super::control::PeerCommand;
+use super::ApiState;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogFilter {
+    pub directives: String,
+}
+
+pub async fn get_log_filter() -> Result<Json<LogFilter>, (StatusCode, String)> {
+    let directives = telemetry::filter::current()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    Ok(Json(LogFilter { directives }))
+}
+
+/// Swap the tracing filter without restarting, e.g. `{"directives": "eigenvault_operator::networking=trace,info"}`
+pub async fn set_log_filter(ControlAccess(caller): ControlAccess, Json(filter): Json<LogFilter>) -> Result<Json<LogFilter>, (StatusCode, String)> {
+    telemetry::filter::update(&filter.directives)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    info!("Log filter changed to '{}' via admin API by {}", filter.directives, caller.name);
+    Ok(Json(filter))
+}
+
+#[cfg(feature = "chaos")]
+pub async fn get_faults() -> Json<crate::config::ChaosConfig> {
+    Json(crate::chaos::current())
+}
+
+/// Change injected faults on the fly, e.g. `{"enabled": true, "drop_percent": 5, "rpc_error_percent": 20}`;
+/// omitted fields are zero
+#[cfg(feature = "chaos")]
+pub async fn set_faults(
+    ControlAccess(caller): ControlAccess,
+    Json(faults): Json<crate::config::ChaosConfig>,
+) -> Result<Json<crate::config::ChaosConfig>, (StatusCode, String)> {
+    faults.validate()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    crate::chaos::configure(&faults);
+    info!("Fault injection changed via admin API by {}: {:?}", caller.name, faults);
+    Ok(Json(faults))
+}
+
+pub async fn get_config(State(state): State<ApiState>) -> Json<RuntimeSettings> {
+    Json(RuntimeSettings::from(&*state.settings.read().await))
+}
+
+/// Apply a partial update, e.g. `{"matching_interval_ms": 250, "max_peers": 40}`;
+/// `pools` and `routes` replace every per-pool override and route and `screening` reloads the screening plugin
+pub async fn update_config(
+    State(state): State<ApiState>,
+    ControlAccess(caller): ControlAccess,
+    Json(patch): Json<ConfigPatch>,
+) -> Result<Json<RuntimeSettings>, (StatusCode, String)> {
+    // Held for the whole update so concurrent changes can't interleave
+    let mut settings = state.settings.write().await;
+
+    let mut candidate = settings.clone();
+    patch.apply(&mut candidate);
+    candidate.validate()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    let screen = match &patch.screening {
+        Some(screening) => Some(screening::from_config(screening).map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?),
+        None => None,
+    };
+
+    state.matching.update_config(candidate.matching.clone()).await
+        .map_err(|e| (StatusCode::CONFLICT, e.to_string()))?;
+    state.matching.update_pool_overrides(candidate.pools.clone()).await;
+    state.matching.update_routes(candidate.routes.clone()).await;
+
+    let limits = PeerCommand::SetLimits {
+        min_peers: candidate.networking.min_peers,
+        max_peers: candidate.networking.max_peers,
+    };
+    if let Err(e) = state.control.manage_peers(limits).await {
+        // Keep components consistent with the settings we still report
+        state.matching.restore_config(settings.matching.clone()).await;
+        state.matching.update_pool_overrides(settings.pools.clone()).await;
+        state.matching.update_routes(settings.routes.clone()).await;
+        error!("Config update rejected by P2P network, rolled back: {:?}", e);
+        return Err((StatusCode::CONFLICT, e.to_string()));
+    }
+
+    if let Some(screen) = screen {
+        state.matching.update_screen(screen).await;
+    }
+    *settings = candidate;
+    info!("Runtime config changed via admin API by {}: {:?}", caller.name, patch);
+    Ok(Json(RuntimeSettings::from(&*settings)))
+}
@@ -0,0 +1,161 @@
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use tracing::{debug, warn};
+
+use crate::config::{ApiConfig, ApiRole};
+use super::ApiState;
+
+/// Authenticated API caller, attached to the request extensions
+#[derive(Debug, Clone)]
+pub struct Caller {
+    pub name: String,
+    pub role: ApiRole,
+}
+
+impl Caller {
+    pub fn can_control(&self) -> bool {
+        self.role == ApiRole::Control
+    }
+}
+
+/// API keys accepted by the HTTP and gRPC surfaces
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeys {
+    keys: HashMap<String, Caller>,
+}
+
+impl ApiKeys {
+    pub fn from_config(config: &ApiConfig) -> Self {
+        let mut keys = HashMap::new();
+
+        if let Some(token) = &config.admin_token {
+            keys.insert(token.clone(), Caller { name: "admin".to_string(), role: ApiRole::Control });
+        }
+        for api_key in &config.api_keys {
+            keys.insert(api_key.key.clone(), Caller { name: api_key.name.clone(), role: api_key.role });
+        }
+
+        Self { keys }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn authenticate(&self, key: &str) -> Option<&Caller> {
+        self.keys.get(key)
+    }
+}
+
+/// Reject requests without a known API key; the caller's role is checked by `ControlAccess`
+pub async fn require_key(State(state): State<ApiState>, mut request: Request, next: Next) -> Response {
+    if state.keys.is_empty() {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let caller = match presented_key(&request).and_then(|key| state.keys.authenticate(&key)) {
+        Some(caller) => caller.clone(),
+        None => return StatusCode::UNAUTHORIZED.into_response(),
+    };
+
+    debug!("API request {} {} by {}", request.method(), request.uri().path(), caller.name);
+    request.extensions_mut().insert(caller);
+    next.run(request).await
+}
+
+/// Key from `Authorization: Bearer`, `X-API-Key` or, for browser WebSocket upgrades, `?access_token=`
+fn presented_key(request: &Request) -> Option<String> {
+    let headers = request.headers();
+
+    if let Some(token) = headers.get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+    {
+        return Some(token.to_string());
+    }
+
+    if let Some(key) = headers.get("x-api-key").and_then(|value| value.to_str().ok()) {
+        return Some(key.to_string());
+    }
+
+    request.uri().query().and_then(|query| {
+        query.split('&')
+            .find_map(|pair| pair.strip_prefix("access_token="))
+            .map(str::to_string)
+    })
+}
+
+/// Extractor for handlers that change operator state; requires the control role
+pub struct ControlAccess(pub Caller);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ControlAccess {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        match parts.extensions.get::<Caller>() {
+            Some(caller) if caller.can_control() => Ok(ControlAccess(caller.clone())),
+            Some(_) => Err((StatusCode::FORBIDDEN, "API key is read-only")),
+            None => Err((StatusCode::UNAUTHORIZED, "Missing API key")),
+        }
+    }
+}
+
+/// Admin surfaces should only be reachable beyond loopback over TLS
+pub fn warn_if_exposed(surface: &str, address: &SocketAddr, tls: bool) {
+    if !address.ip().is_loopback() && !tls {
+        warn!("{} listens on {} without TLS; API keys are sent in plain text", surface, address);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ApiKeyConfig;
+
+    #[test]
+    fn test_keys_resolve_roles() {
+        let config = ApiConfig {
+            admin_token: Some("admin-secret".to_string()),
+            api_keys: vec![ApiKeyConfig {
+                name: "grafana".to_string(),
+                key: "read-secret".to_string(),
+                role: ApiRole::ReadOnly,
+            }],
+            ..ApiConfig::default()
+        };
+        let keys = ApiKeys::from_config(&config);
+
+        assert!(keys.authenticate("admin-secret").unwrap().can_control());
+        assert!(!keys.authenticate("read-secret").unwrap().can_control());
+        assert!(keys.authenticate("unknown").is_none());
+        assert!(ApiKeys::from_config(&ApiConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_presented_key_sources() {
+        let bearer = Request::builder()
+            .header(header::AUTHORIZATION, "Bearer abc")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let api_key = Request::builder()
+            .header("x-api-key", "def")
+            .body(axum::body::Body::empty())
+            .unwrap();
+        let query = Request::builder()
+            .uri("/api/v1/events/ws?types=match_found&access_token=ghi")
+            .body(axum::body::Body::empty())
+            .unwrap();
+
+        assert_eq!(presented_key(&bearer).as_deref(), Some("abc"));
+        assert_eq!(presented_key(&api_key).as_deref(), Some("def"));
+        assert_eq!(presented_key(&query).as_deref(), Some("ghi"));
+    }
+}
@@ -0,0 +1,137 @@
+use anyhow::{anyhow, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::{mpsc, oneshot, Mutex, MutexGuard};
+use tracing::info;
+
+use crate::config::PoolFilterConfig;
+use crate::matching::OrderCancellation;
+use crate::networking::PeerList;
+
+/// Externally generated proof to relay on-chain for a task
+#[derive(Debug)]
+pub struct ProofSubmission {
+    pub task_id: String,
+    pub proof: Vec<u8>,
+    pub result_hash: String,
+    pub signatures: Vec<u8>,
+    /// Receives the transaction hash once submitted
+    pub reply: oneshot::Sender<Result<String>>,
+}
+
+/// Connectivity change requested through the admin API
+#[derive(Debug, Clone)]
+pub enum PeerCommand {
+    List,
+    Ban(String),
+    Unban(String),
+    Connect(String),
+    SetLimits { min_peers: usize, max_peers: usize },
+    /// Pools to advertise in gossip handshakes
+    SetPools(PoolFilterConfig),
+    /// Lower a peer's reputation, as when an operator it gossiped for is slashed
+    Penalize { peer_id: String, penalty: f64 },
+}
+
+/// Peer command routed to the task that owns the P2P network
+#[derive(Debug)]
+pub struct PeerRequest {
+    pub command: PeerCommand,
+    /// Receives the peer list after the command is applied
+    pub reply: oneshot::Sender<Result<PeerList>>,
+}
+
+/// Runtime switches operators can flip through the admin interfaces
+pub struct OperatorControl {
+    matching_paused: AtomicBool,
+    proof_sender: mpsc::UnboundedSender<ProofSubmission>,
+    proof_receiver: Mutex<mpsc::UnboundedReceiver<ProofSubmission>>,
+    peer_sender: mpsc::UnboundedSender<PeerRequest>,
+    peer_receiver: Mutex<mpsc::UnboundedReceiver<PeerRequest>>,
+    cancellation_sender: mpsc::UnboundedSender<OrderCancellation>,
+    cancellation_receiver: Mutex<mpsc::UnboundedReceiver<OrderCancellation>>,
+}
+
+impl Default for OperatorControl {
+    fn default() -> Self {
+        let (proof_sender, proof_receiver) = mpsc::unbounded_channel();
+        let (peer_sender, peer_receiver) = mpsc::unbounded_channel();
+        let (cancellation_sender, cancellation_receiver) = mpsc::unbounded_channel();
+
+        Self {
+            matching_paused: AtomicBool::new(false),
+            proof_sender,
+            proof_receiver: Mutex::new(proof_receiver),
+            peer_sender,
+            peer_receiver: Mutex::new(peer_receiver),
+            cancellation_sender,
+            cancellation_receiver: Mutex::new(cancellation_receiver),
+        }
+    }
+}
+
+impl OperatorControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop pulling orders into matching; queued orders are kept
+    pub fn pause_matching(&self) {
+        if !self.matching_paused.swap(true, Ordering::SeqCst) {
+            info!("Matching paused by operator");
+        }
+    }
+
+    pub fn resume_matching(&self) {
+        if self.matching_paused.swap(false, Ordering::SeqCst) {
+            info!("Matching resumed by operator");
+        }
+    }
+
+    pub fn is_matching_paused(&self) -> bool {
+        self.matching_paused.load(Ordering::SeqCst)
+    }
+
+    /// Hand a proof to the task that owns the Ethereum client and wait for its transaction hash
+    pub async fn submit_proof(&self, task_id: String, proof: Vec<u8>, result_hash: String, signatures: Vec<u8>) -> Result<String> {
+        let (reply, response) = oneshot::channel();
+
+        self.proof_sender
+            .send(ProofSubmission { task_id, proof, result_hash, signatures, reply })
+            .map_err(|_| anyhow!("Operator is not accepting proof submissions"))?;
+
+        response.await.map_err(|_| anyhow!("Proof submission was dropped"))?
+    }
+
+    /// Receiving end of proof submissions, held by the task that relays them; a restarted task reacquires it
+    pub async fn proof_submissions(&self) -> MutexGuard<'_, mpsc::UnboundedReceiver<ProofSubmission>> {
+        self.proof_receiver.lock().await
+    }
+
+    /// Apply a peer command on the P2P task and return the resulting peer list
+    pub async fn manage_peers(&self, command: PeerCommand) -> Result<PeerList> {
+        let (reply, response) = oneshot::channel();
+
+        self.peer_sender
+            .send(PeerRequest { command, reply })
+            .map_err(|_| anyhow!("Operator is not accepting peer commands"))?;
+
+        response.await.map_err(|_| anyhow!("Peer command was dropped"))?
+    }
+
+    /// Receiving end of peer commands, held by the task that owns the P2P network
+    pub async fn peer_commands(&self) -> MutexGuard<'_, mpsc::UnboundedReceiver<PeerRequest>> {
+        self.peer_receiver.lock().await
+    }
+
+    /// Queue a cancellation accepted through the API for gossip to the rest of the committee
+    pub fn announce_cancellation(&self, cancellation: OrderCancellation) -> Result<()> {
+        self.cancellation_sender
+            .send(cancellation)
+            .map_err(|_| anyhow!("Operator is not gossiping cancellations"))
+    }
+
+    /// Receiving end of announced cancellations, held by the task that owns the P2P network
+    pub async fn cancellations(&self) -> MutexGuard<'_, mpsc::UnboundedReceiver<OrderCancellation>> {
+        self.cancellation_receiver.lock().await
+    }
+}
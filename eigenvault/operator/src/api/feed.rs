@@ -0,0 +1,82 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    response::Response,
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, warn};
+
+use crate::eventbus::EventEnvelope;
+use super::ApiState;
+
+/// Event types streamed when the client doesn't choose
+const DEFAULT_TYPES: [&str; 4] = ["match_found", "task_created", "proof_submitted", "task_completed"];
+
+#[derive(Debug, Deserialize)]
+pub struct FeedQuery {
+    /// Comma separated event types, e.g. `match_found,task_completed`
+    pub types: Option<String>,
+}
+
+/// GET /api/v1/events/ws - push operator events to the client as JSON text frames
+pub async fn events_ws(
+    ws: WebSocketUpgrade,
+    State(state): State<ApiState>,
+    Query(query): Query<FeedQuery>,
+) -> Response {
+    let types: HashSet<String> = match query.types {
+        Some(types) => types.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect(),
+        None => DEFAULT_TYPES.iter().map(|t| t.to_string()).collect(),
+    };
+
+    // Subscribe before upgrading so no event slips through during the handshake
+    let receiver = state.events.subscribe();
+    ws.on_upgrade(move |socket| stream_events(socket, receiver, types))
+}
+
+async fn stream_events(
+    mut socket: WebSocket,
+    mut receiver: broadcast::Receiver<EventEnvelope>,
+    types: HashSet<String>,
+) {
+    debug!("WebSocket event subscriber connected for {:?}", types);
+
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(envelope) => {
+                    if !types.contains(envelope.event.topic()) {
+                        continue;
+                    }
+
+                    let text = match serde_json::to_string(&envelope) {
+                        Ok(text) => text,
+                        Err(e) => {
+                            warn!("Failed to serialize event {}: {:?}", envelope.event_id, e);
+                            continue;
+                        }
+                    };
+
+                    if socket.send(Message::Text(text)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("WebSocket subscriber lagging, skipped {} events", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by axum; other client frames are ignored
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    debug!("WebSocket event subscriber disconnected");
+}
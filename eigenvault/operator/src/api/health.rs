@@ -0,0 +1,161 @@
+use anyhow::Result;
+use axum::{extract::State, http::StatusCode, Json};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tokio::sync::RwLock;
+
+use super::ApiState;
+
+pub const ETHEREUM: &str = "ethereum";
+pub const P2P: &str = "p2p";
+pub const MATCHING: &str = "matching";
+pub const PROVER: &str = "prover";
+
+/// Latest health check outcome for a single component
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentHealth {
+    pub healthy: bool,
+    pub last_checked: u64,
+    pub error: Option<String>,
+}
+
+/// Aggregated health report served by `/healthz` and `/readyz`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub status: String,
+    pub ready: bool,
+    pub stale: bool,
+    pub components: BTreeMap<String, Option<ComponentHealth>>,
+    /// Supervisor restarts by operator task
+    #[serde(default)]
+    pub restarts: BTreeMap<String, u64>,
+}
+
+/// Shared view of component health, updated by the operator's health loop
+pub struct HealthRegistry {
+    components: RwLock<BTreeMap<String, Option<ComponentHealth>>>,
+    restarts: RwLock<BTreeMap<String, u64>>,
+    /// Results older than this are considered stale (health loop stuck)
+    max_age_seconds: u64,
+}
+
+impl HealthRegistry {
+    pub fn new(components: &[&str], max_age_seconds: u64) -> Self {
+        let components = components.iter()
+            .map(|name| (name.to_string(), None))
+            .collect();
+
+        Self {
+            components: RwLock::new(components),
+            restarts: RwLock::new(BTreeMap::new()),
+            max_age_seconds,
+        }
+    }
+
+    /// Registry tracking the core operator components
+    pub fn for_operator(check_interval_seconds: u64) -> Self {
+        Self::new(&[ETHEREUM, P2P, MATCHING, PROVER], check_interval_seconds * 3)
+    }
+
+    /// Record the outcome of a component health check
+    pub async fn report(&self, component: &str, result: &Result<()>) {
+        let health = ComponentHealth {
+            healthy: result.is_ok(),
+            last_checked: chrono::Utc::now().timestamp() as u64,
+            error: result.as_ref().err().map(|e| e.to_string()),
+        };
+
+        self.components.write().await.insert(component.to_string(), Some(health));
+    }
+
+    /// Count a supervisor restart of an operator task
+    pub async fn record_restart(&self, task: &str) {
+        *self.restarts.write().await.entry(task.to_string()).or_insert(0) += 1;
+    }
+
+    pub async fn snapshot(&self) -> HealthReport {
+        let components = self.components.read().await.clone();
+        let now = chrono::Utc::now().timestamp() as u64;
+
+        let all_checked = components.values().all(|health| health.is_some());
+        let all_healthy = components.values()
+            .all(|health| health.as_ref().map(|h| h.healthy).unwrap_or(false));
+        let stale = components.values()
+            .flatten()
+            .any(|health| now.saturating_sub(health.last_checked) > self.max_age_seconds);
+
+        let ready = all_checked && all_healthy && !stale;
+        let status = if !all_checked {
+            "starting"
+        } else if ready {
+            "ok"
+        } else {
+            "degraded"
+        };
+
+        HealthReport {
+            status: status.to_string(),
+            ready,
+            stale,
+            components,
+            restarts: self.restarts.read().await.clone(),
+        }
+    }
+}
+
+/// Liveness: the process is up and its health loop is still running
+pub async fn healthz(State(state): State<ApiState>) -> (StatusCode, Json<HealthReport>) {
+    let report = state.health.snapshot().await;
+    let code = if report.stale {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    (code, Json(report))
+}
+
+/// Readiness: every component has passed its latest health check
+pub async fn readyz(State(state): State<ApiState>) -> (StatusCode, Json<HealthReport>) {
+    let report = state.health.snapshot().await;
+    let code = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_not_ready_until_all_checked() {
+        let registry = HealthRegistry::for_operator(30);
+        assert_eq!(registry.snapshot().await.status, "starting");
+
+        registry.report(ETHEREUM, &Ok(())).await;
+        registry.report(P2P, &Ok(())).await;
+        registry.report(MATCHING, &Ok(())).await;
+        assert!(!registry.snapshot().await.ready);
+
+        registry.report(PROVER, &Ok(())).await;
+        let report = registry.snapshot().await;
+        assert!(report.ready);
+        assert_eq!(report.status, "ok");
+    }
+
+    #[tokio::test]
+    async fn test_unhealthy_component_degrades() {
+        let registry = HealthRegistry::new(&[ETHEREUM, P2P], 90);
+        registry.report(ETHEREUM, &Ok(())).await;
+        registry.report(P2P, &Err(anyhow::anyhow!("No active peers connected"))).await;
+
+        let report = registry.snapshot().await;
+        assert!(!report.ready);
+        assert_eq!(report.status, "degraded");
+
+        let p2p = report.components[P2P].as_ref().unwrap();
+        assert_eq!(p2p.error.as_deref(), Some("No active peers connected"));
+    }
+}
@@ -0,0 +1,189 @@
+use axum::{extract::State, Extension, Json};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::auth::Caller;
+use super::ApiState;
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const INTERNAL_ERROR: i64 = -32603;
+/// Server-defined: the API key lacks the role the method needs
+pub const FORBIDDEN: i64 = -32001;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcRequest {
+    pub jsonrpc: String,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+    /// Absent for notifications
+    pub id: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcResponse {
+    pub jsonrpc: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+    pub id: Value,
+}
+
+impl RpcResponse {
+    fn result(id: Value, result: Value) -> Self {
+        Self { jsonrpc: "2.0".to_string(), result: Some(result), error: None, id }
+    }
+
+    fn error(id: Value, code: i64, message: impl Into<String>) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            result: None,
+            error: Some(RpcError { code, message: message.into() }),
+            id,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SubmitProofParams {
+    task_id: String,
+    /// Hex encoded, optionally 0x-prefixed
+    proof: String,
+    result_hash: String,
+    #[serde(default)]
+    signatures: Option<String>,
+}
+
+/// POST /rpc - JSON-RPC 2.0 endpoint, accepting single and batch requests
+pub async fn handle(State(state): State<ApiState>, Extension(caller): Extension<Caller>, Json(body): Json<Value>) -> Json<Value> {
+    match body {
+        Value::Array(requests) if !requests.is_empty() => {
+            let mut responses = Vec::new();
+            for request in requests {
+                if let Some(response) = handle_one(&state, &caller, request).await {
+                    responses.push(response);
+                }
+            }
+            if responses.is_empty() {
+                // A batch of notifications gets no response
+                return Json(Value::Null);
+            }
+            Json(json!(responses))
+        }
+        Value::Array(_) => Json(json!(RpcResponse::error(Value::Null, INVALID_REQUEST, "Empty batch"))),
+        request => Json(match handle_one(&state, &caller, request).await {
+            Some(response) => json!(response),
+            None => Value::Null,
+        }),
+    }
+}
+
+/// Handle one request; notifications produce no response
+async fn handle_one(state: &ApiState, caller: &Caller, request: Value) -> Option<RpcResponse> {
+    let request: RpcRequest = match serde_json::from_value(request) {
+        Ok(request) => request,
+        Err(e) => return Some(RpcResponse::error(Value::Null, INVALID_REQUEST, e.to_string())),
+    };
+
+    if request.jsonrpc != "2.0" {
+        return Some(RpcResponse::error(request.id.unwrap_or(Value::Null), INVALID_REQUEST, "jsonrpc must be \"2.0\""));
+    }
+
+    let id = request.id.clone();
+    let response = match dispatch(state, caller, &request.method, request.params).await {
+        Ok(result) => RpcResponse::result(id.clone().unwrap_or(Value::Null), result),
+        Err((code, message)) => RpcResponse::error(id.clone().unwrap_or(Value::Null), code, message),
+    };
+
+    id.map(|_| response)
+}
+
+async fn dispatch(state: &ApiState, caller: &Caller, method: &str, params: Value) -> Result<Value, (i64, String)> {
+    match method {
+        "operator_status" => {
+            let status = state.status.snapshot().await;
+            Ok(json!({
+                "updated_at": status.updated_at,
+                "last_processed_block": status.last_processed_block,
+                "proof_queue_depth": status.proof_queue_depth,
+                "matching_paused": state.control.is_matching_paused(),
+                "active_peers": status.peers.iter().filter(|peer| peer.is_active).count(),
+                "pending_tasks": status.pending_tasks.len(),
+                "recent_submissions": status.recent_submissions,
+            }))
+        }
+        "operator_peers" => to_value(state.status.snapshot().await.peers),
+        "operator_pendingOrders" => {
+            // Per-pool aggregates only; individual orders stay private
+            let stats = state.matching.get_order_book_stats().await
+                .map_err(|e| (INTERNAL_ERROR, e.to_string()))?;
+            to_value(stats)
+        }
+        "operator_submitProof" => {
+            if !caller.can_control() {
+                return Err((FORBIDDEN, "operator_submitProof requires the control role".to_string()));
+            }
+            let params: SubmitProofParams = serde_json::from_value(params)
+                .map_err(|e| (INVALID_PARAMS, e.to_string()))?;
+            let proof = decode_hex(&params.proof)?;
+            let signatures = params.signatures.as_deref().map(decode_hex).transpose()?.unwrap_or_default();
+
+            let tx_hash = state.control
+                .submit_proof(params.task_id, proof, params.result_hash, signatures)
+                .await
+                .map_err(|e| (INTERNAL_ERROR, e.to_string()))?;
+            Ok(json!({ "tx_hash": tx_hash }))
+        }
+        _ => Err((METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+    }
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, (i64, String)> {
+    serde_json::to_value(value).map_err(|e| (INTERNAL_ERROR, e.to_string()))
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, (i64, String)> {
+    hex::decode(value.trim_start_matches("0x")).map_err(|e| (INVALID_PARAMS, format!("Invalid hex: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_has_no_id() {
+        let request: RpcRequest = serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "method": "operator_status",
+        })).unwrap();
+
+        assert!(request.id.is_none());
+        assert_eq!(request.params, Value::Null);
+    }
+
+    #[test]
+    fn test_error_response_shape() {
+        let response = RpcResponse::error(json!(1), METHOD_NOT_FOUND, "Method not found: nope");
+        let value = serde_json::to_value(response).unwrap();
+
+        assert_eq!(value["error"]["code"], METHOD_NOT_FOUND);
+        assert!(value.get("result").is_none());
+        assert_eq!(value["id"], 1);
+    }
+
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(decode_hex("0x0102").unwrap(), vec![1, 2]);
+        assert_eq!(decode_hex("zz").unwrap_err().0, INVALID_PARAMS);
+    }
+}
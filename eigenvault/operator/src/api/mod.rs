@@ -0,0 +1,18 @@
+pub mod admin;
+pub mod auth;
+pub mod control;
+pub mod feed;
+pub mod health;
+pub mod jsonrpc;
+pub mod peers;
+pub mod pools;
+pub mod public;
+pub mod rest;
+pub mod server;
+pub mod status;
+
+pub use auth::{ApiKeys, Caller};
+pub use control::OperatorControl;
+pub use health::{ComponentHealth, HealthRegistry, HealthReport};
+pub use server::{serve, ApiState};
+pub use status::{OperatorStatus, StatusBoard};
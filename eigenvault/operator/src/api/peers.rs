@@ -0,0 +1,49 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::networking::PeerList;
+use super::auth::ControlAccess;
+use super::control::PeerCommand;
+use super::ApiState;
+
+type ApiResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectRequest {
+    /// `host:port` of the peer's P2P listener
+    pub address: String,
+}
+
+/// GET /admin/peers
+pub async fn list_peers(State(state): State<ApiState>) -> ApiResult<PeerList> {
+    run(&state, PeerCommand::List).await
+}
+
+/// POST /admin/peers/:peer_id/ban
+pub async fn ban_peer(State(state): State<ApiState>, _access: ControlAccess, Path(peer_id): Path<String>) -> ApiResult<PeerList> {
+    run(&state, PeerCommand::Ban(peer_id)).await
+}
+
+/// DELETE /admin/peers/:peer_id/ban
+pub async fn unban_peer(State(state): State<ApiState>, _access: ControlAccess, Path(peer_id): Path<String>) -> ApiResult<PeerList> {
+    run(&state, PeerCommand::Unban(peer_id)).await
+}
+
+/// POST /admin/peers/connect
+pub async fn connect_peer(State(state): State<ApiState>, _access: ControlAccess, Json(request): Json<ConnectRequest>) -> ApiResult<PeerList> {
+    if request.address.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "address is required".to_string()));
+    }
+
+    run(&state, PeerCommand::Connect(request.address)).await
+}
+
+async fn run(state: &ApiState, command: PeerCommand) -> ApiResult<PeerList> {
+    state.control.manage_peers(command).await
+        .map(Json)
+        .map_err(|e| (StatusCode::BAD_GATEWAY, e.to_string()))
+}
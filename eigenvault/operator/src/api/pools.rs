@@ -0,0 +1,61 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use tracing::{error, info};
+
+use crate::config::PoolFilterConfig;
+use super::auth::ControlAccess;
+use super::control::PeerCommand;
+use super::ApiState;
+
+type ApiResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+/// GET /admin/pools
+pub async fn list_pools(State(state): State<ApiState>) -> Json<PoolFilterConfig> {
+    Json(state.settings.read().await.pool_filter.clone())
+}
+
+/// POST /admin/pools/:pool_key
+pub async fn join_pool(State(state): State<ApiState>, ControlAccess(caller): ControlAccess, Path(pool_key): Path<String>) -> ApiResult<PoolFilterConfig> {
+    let pool_filter = update(&state, |pool_filter| {
+        pool_filter.join(&pool_key);
+        Ok(())
+    }).await?;
+
+    info!("Pool {} joined via admin API by {}", pool_key, caller.name);
+    Ok(Json(pool_filter))
+}
+
+/// DELETE /admin/pools/:pool_key
+pub async fn leave_pool(State(state): State<ApiState>, ControlAccess(caller): ControlAccess, Path(pool_key): Path<String>) -> ApiResult<PoolFilterConfig> {
+    let pool_filter = update(&state, |pool_filter| pool_filter.leave(&pool_key)).await?;
+
+    info!("Pool {} left via admin API by {}", pool_key, caller.name);
+    Ok(Json(pool_filter))
+}
+
+/// Apply `change` to the pool filter, then hand it to the P2P network for gossip handshakes
+/// and to the matching engine, which stops or starts matching the pool on its next round
+async fn update(
+    state: &ApiState,
+    change: impl FnOnce(&mut PoolFilterConfig) -> anyhow::Result<()>,
+) -> Result<PoolFilterConfig, (StatusCode, String)> {
+    // Held for the whole update so concurrent changes can't interleave
+    let mut settings = state.settings.write().await;
+
+    let mut candidate = settings.clone();
+    change(&mut candidate.pool_filter)
+        .and_then(|()| candidate.validate())
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    if let Err(e) = state.control.manage_peers(PeerCommand::SetPools(candidate.pool_filter.clone())).await {
+        error!("Pool change rejected by P2P network: {:?}", e);
+        return Err((StatusCode::CONFLICT, e.to_string()));
+    }
+    state.matching.update_pool_filter(candidate.pool_filter.clone()).await;
+
+    *settings = candidate;
+    Ok(settings.pool_filter.clone())
+}
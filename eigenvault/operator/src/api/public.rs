@@ -0,0 +1,230 @@
+use anyhow::Result;
+use axum::{
+    extract::{ConnectInfo, Path, Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::{info, warn};
+
+use crate::config::PublicStatsConfig;
+use crate::eventbus::OperatorEvent;
+use crate::matching::{Decimal, OrderCancellation, OrderKeyDocument, OrderLifecycle, OrderReceipt};
+use super::ApiState;
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Aggregates safe to publish; nothing here identifies an order or trader
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicStats {
+    pub uptime_seconds: u64,
+    pub total_matches: u64,
+    pub total_volume: Decimal,
+    pub active_pools: usize,
+    pub active_peers: usize,
+    pub last_processed_block: u64,
+}
+
+/// Status of one order, for the trader holding its commitment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderStatus {
+    pub commitment: String,
+    pub status: OrderLifecycle,
+}
+
+/// Fixed-window request counter per client IP
+pub struct RateLimiter {
+    limit: u32,
+    clients: Mutex<HashMap<IpAddr, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_minute: u32) -> Self {
+        Self {
+            limit: requests_per_minute,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a request from `ip`; false once it exceeded the limit for the current window
+    pub fn allow(&self, ip: IpAddr) -> bool {
+        let now = Instant::now();
+        let mut clients = self.clients.lock().unwrap();
+
+        // Drop expired windows so the map doesn't grow with every client seen
+        if clients.len() > 10_000 {
+            clients.retain(|_, (started, _)| now.duration_since(*started) < RATE_WINDOW);
+        }
+
+        let (started, count) = clients.entry(ip).or_insert((now, 0));
+        if now.duration_since(*started) >= RATE_WINDOW {
+            *started = now;
+            *count = 0;
+        }
+        *count += 1;
+
+        *count <= self.limit
+    }
+}
+
+async fn rate_limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !limiter.allow(client.ip()) {
+        return StatusCode::TOO_MANY_REQUESTS.into_response();
+    }
+
+    next.run(request).await
+}
+
+/// GET /stats
+pub async fn stats(State(state): State<ApiState>) -> Json<PublicStats> {
+    let status = state.status.snapshot().await;
+    let now = chrono::Utc::now().timestamp() as u64;
+
+    Json(PublicStats {
+        uptime_seconds: now.saturating_sub(status.started_at),
+        total_matches: status.total_matches,
+        total_volume: status.total_volume,
+        active_pools: status.pools.len(),
+        active_peers: status.peers.iter().filter(|peer| peer.is_active).count(),
+        last_processed_block: status.last_processed_block,
+    })
+}
+
+/// GET /orders/:commitment
+pub async fn order_status(State(state): State<ApiState>, Path(commitment): Path<String>) -> Result<Json<OrderStatus>, StatusCode> {
+    // Only the status of the exact commitment is returned; any order this operator hasn't
+    // seen gets the same 404, so nothing is learned about other orders
+    let status = state.matching.tracker().status(&commitment).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(OrderStatus { commitment, status }))
+}
+
+/// GET /orders/:commitment/receipt
+pub async fn order_receipt(State(state): State<ApiState>, Path(commitment): Path<String>) -> Result<Json<OrderReceipt>, StatusCode> {
+    state.matching.tracker().receipt(&commitment).map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+/// POST /orders/:commitment/cancel, with the trader's signed `OrderCancellation` as the body
+pub async fn cancel_order(
+    State(state): State<ApiState>,
+    Path(commitment): Path<String>,
+    Json(cancellation): Json<OrderCancellation>,
+) -> Result<Json<OrderStatus>, StatusCode> {
+    if !cancellation.commitment.trim_start_matches("0x").eq_ignore_ascii_case(commitment.trim_start_matches("0x")) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let order = match state.matching.cancel_order(&cancellation).await {
+        Ok(Some(order)) => order,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            warn!("Refused cancellation of {}: {}", commitment, e);
+            return Err(StatusCode::FORBIDDEN);
+        }
+    };
+
+    state.events.publish(OperatorEvent::OrderCancelled { order_id: order.id });
+    // The rest of the committee drops the order too; this operator's cancellation stands regardless
+    if let Err(e) = state.control.announce_cancellation(cancellation) {
+        warn!("Failed to gossip cancellation of {}: {}", commitment, e);
+    }
+    Ok(Json(OrderStatus { commitment, status: OrderLifecycle::Cancelled }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookRequest {
+    pub url: String,
+}
+
+/// POST /orders/:commitment/webhook - call `url` when the order is matched and when it settles
+pub async fn register_webhook(
+    State(state): State<ApiState>,
+    Path(commitment): Path<String>,
+    Json(request): Json<WebhookRequest>,
+) -> Result<StatusCode, StatusCode> {
+    let notifier = state.notifier.as_ref().ok_or(StatusCode::NOT_FOUND)?;
+    let tracker = state.matching.tracker();
+    match tracker.status(&commitment) {
+        Some(OrderLifecycle::Pending | OrderLifecycle::PartiallyMatched | OrderLifecycle::Matched) => {}
+        Some(_) => return Err(StatusCode::CONFLICT),
+        None => return Err(StatusCode::NOT_FOUND),
+    }
+
+    let deadline = tracker.deadline(&commitment).ok_or(StatusCode::NOT_FOUND)?;
+    notifier.register(&commitment, &request.url, deadline).map_err(|e| {
+        warn!("Refused webhook for {}: {}", commitment, e);
+        StatusCode::UNPROCESSABLE_ENTITY
+    })?;
+    Ok(StatusCode::CREATED)
+}
+
+/// GET /keys/orders
+pub async fn order_key(State(state): State<ApiState>) -> Result<Json<OrderKeyDocument>, StatusCode> {
+    state.order_key.as_deref().cloned().map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+fn cors(allowed_origins: &[String]) -> CorsLayer {
+    let origins = if allowed_origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(allowed_origins.iter().filter_map(|origin| HeaderValue::from_str(origin).ok()))
+    };
+
+    CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers([header::CONTENT_TYPE])
+        .allow_origin(origins)
+}
+
+pub fn router(config: &PublicStatsConfig, state: ApiState) -> Router {
+    let limiter = Arc::new(RateLimiter::new(config.requests_per_minute));
+
+    Router::new()
+        .route("/stats", get(stats))
+        .route("/orders/:commitment", get(order_status))
+        .route("/orders/:commitment/receipt", get(order_receipt))
+        .route("/orders/:commitment/cancel", post(cancel_order))
+        .route("/orders/:commitment/webhook", post(register_webhook))
+        .route("/keys/orders", get(order_key))
+        .route_layer(middleware::from_fn_with_state(limiter, rate_limit))
+        .layer(cors(&config.allowed_origins))
+        .with_state(state)
+}
+
+/// Serve the unauthenticated stats endpoint on its own listener
+pub async fn serve(config: PublicStatsConfig, state: ApiState) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(&config.listen_address).await?;
+    info!("Public stats listening on http://{}/stats", config.listen_address);
+
+    let app = router(&config, state);
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter_is_per_client() {
+        let limiter = RateLimiter::new(2);
+        let first: IpAddr = "10.0.0.1".parse().unwrap();
+        let second: IpAddr = "10.0.0.2".parse().unwrap();
+
+        assert!(limiter.allow(first));
+        assert!(limiter.allow(first));
+        assert!(!limiter.allow(first));
+        assert!(limiter.allow(second));
+    }
+}
@@ -0,0 +1,112 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::ethereum::TaskInfo;
+use crate::matching::{Decimal, OrderBookDepth, OrderBookStats};
+use crate::metrics::metrics;
+use super::status::SubmissionSummary;
+use super::ApiState;
+
+const DEFAULT_DEPTH_LEVELS: usize = 10;
+const MAX_DEPTH_LEVELS: usize = 100;
+
+type ApiResult<T> = Result<Json<T>, (StatusCode, String)>;
+
+#[derive(Debug, Deserialize)]
+pub struct DepthQuery {
+    pub levels: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchStats {
+    pub recent_matches: usize,
+    pub total_volume: Decimal,
+    pub average_price: Decimal,
+    pub pending_orders: usize,
+    pub matching_paused: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskStatus {
+    pub task_id: String,
+    /// Set while the task is still open on-chain
+    pub task: Option<TaskInfo>,
+    /// Our response to the task, if one was submitted recently
+    pub submission: Option<SubmissionSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofQueue {
+    pub in_flight: u64,
+    pub completed: u64,
+    pub average_duration_seconds: Option<f64>,
+}
+
+/// GET /api/v1/pools/:pool_key/depth?levels=N
+pub async fn pool_depth(
+    State(state): State<ApiState>,
+    Path(pool_key): Path<String>,
+    Query(query): Query<DepthQuery>,
+) -> ApiResult<OrderBookDepth> {
+    let levels = query.levels.unwrap_or(DEFAULT_DEPTH_LEVELS).min(MAX_DEPTH_LEVELS);
+
+    match state.matching.get_depth(&pool_key, levels).await {
+        Ok(Some(depth)) => Ok(Json(depth)),
+        Ok(None) => Err((StatusCode::NOT_FOUND, format!("No pending orders for pool {}", pool_key))),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+/// GET /api/v1/pools/:pool_key/stats
+pub async fn pool_stats(State(state): State<ApiState>, Path(pool_key): Path<String>) -> ApiResult<OrderBookStats> {
+    match state.matching.get_pool_stats(&pool_key).await {
+        Ok(Some(stats)) => Ok(Json(stats)),
+        Ok(None) => Err((StatusCode::NOT_FOUND, format!("No pending orders for pool {}", pool_key))),
+        Err(e) => Err((StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+    }
+}
+
+/// GET /api/v1/matching/stats
+pub async fn match_stats(State(state): State<ApiState>) -> ApiResult<MatchStats> {
+    let result = state.matching.get_matching_stats().await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    // Only aggregates leave the operator; unmatched orders stay private
+    Ok(Json(MatchStats {
+        recent_matches: result.matches.len(),
+        total_volume: result.total_volume,
+        average_price: result.average_price,
+        pending_orders: result.unmatched_orders.len(),
+        matching_paused: state.control.is_matching_paused(),
+    }))
+}
+
+/// GET /api/v1/tasks/:task_id
+pub async fn task_status(State(state): State<ApiState>, Path(task_id): Path<String>) -> ApiResult<TaskStatus> {
+    let status = state.status.snapshot().await;
+
+    let task = status.pending_tasks.into_iter().find(|task| task.task_id == task_id);
+    let submission = status.recent_submissions.into_iter().find(|s| s.task_id == task_id);
+
+    if task.is_none() && submission.is_none() {
+        return Err((StatusCode::NOT_FOUND, format!("Unknown task {}", task_id)));
+    }
+
+    Ok(Json(TaskStatus { task_id, task, submission }))
+}
+
+/// GET /api/v1/proofs/queue
+pub async fn proof_queue() -> Json<ProofQueue> {
+    let durations = &metrics().proof_duration_seconds;
+    let completed = durations.get_sample_count();
+
+    Json(ProofQueue {
+        in_flight: metrics().proofs_in_flight.get().max(0) as u64,
+        completed,
+        average_duration_seconds: (completed > 0).then(|| durations.get_sample_sum() / completed as f64),
+    })
+}
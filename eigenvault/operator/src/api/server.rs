@@ -0,0 +1,94 @@
+use anyhow::Result;
+use axum::{middleware, routing::{get, post}, Router};
+use axum_server::tls_rustls::RustlsConfig;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::info;
+
+use crate::config::{ApiConfig, Settings};
+use crate::eventbus::EventPublisher;
+use crate::matching::{MatchingEngine, OrderKeyDocument};
+use crate::notify::WebhookNotifier;
+use crate::storage::Store;
+use super::{admin, auth, feed, health, jsonrpc, peers, pools, rest, status, ApiKeys, HealthRegistry, OperatorControl, StatusBoard};
+
+/// State shared with every API handler
+#[derive(Clone)]
+pub struct ApiState {
+    pub health: Arc<HealthRegistry>,
+    pub status: Arc<StatusBoard>,
+    pub control: Arc<OperatorControl>,
+    pub matching: Arc<MatchingEngine>,
+    pub events: EventPublisher,
+    pub store: Arc<Store>,
+    pub keys: Arc<ApiKeys>,
+    /// Effective settings, including changes made through `/admin/config`
+    pub settings: Arc<RwLock<Settings>>,
+    /// Committee order key served to traders, if `order_key.path` is set
+    pub order_key: Option<Arc<OrderKeyDocument>>,
+    /// Trader webhooks, if `notifications` is enabled
+    pub notifier: Option<Arc<WebhookNotifier>>,
+}
+
+pub fn router(state: ApiState) -> Router {
+    let admin = Router::new()
+        .route("/log-filter", get(admin::get_log_filter).put(admin::set_log_filter))
+        .route("/status", get(status::status))
+        .route("/config", get(admin::get_config).patch(admin::update_config))
+        .route("/peers", get(peers::list_peers))
+        .route("/peers/connect", post(peers::connect_peer))
+        .route("/peers/:peer_id/ban", post(peers::ban_peer).delete(peers::unban_peer))
+        .route("/pools", get(pools::list_pools))
+        .route("/pools/:pool_key", post(pools::join_pool).delete(pools::leave_pool));
+    #[cfg(feature = "chaos")]
+    let admin = admin.route("/chaos", get(admin::get_faults).put(admin::set_faults));
+    let admin = admin.route_layer(middleware::from_fn_with_state(state.clone(), auth::require_key));
+
+    // Read-only dashboard endpoints accept any API key
+    let rest = Router::new()
+        .route("/pools/:pool_key/depth", get(rest::pool_depth))
+        .route("/pools/:pool_key/stats", get(rest::pool_stats))
+        .route("/matching/stats", get(rest::match_stats))
+        .route("/tasks/:task_id", get(rest::task_status))
+        .route("/proofs/queue", get(rest::proof_queue))
+        .route("/events/ws", get(feed::events_ws))
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_key));
+
+    let rpc = Router::new()
+        .route("/", post(jsonrpc::handle))
+        .route_layer(middleware::from_fn_with_state(state.clone(), auth::require_key));
+
+    Router::new()
+        .route("/healthz", get(health::healthz))
+        .route("/readyz", get(health::readyz))
+        .nest("/admin", admin)
+        .nest("/api/v1", rest)
+        .nest("/rpc", rpc)
+        .with_state(state)
+}
+
+/// Serve the operator HTTP API on the configured address, over TLS when configured
+pub async fn serve(config: ApiConfig, state: ApiState) -> Result<()> {
+    let address: SocketAddr = config.listen_address.parse()?;
+    auth::warn_if_exposed("Operator API", &address, config.tls.is_some());
+
+    match &config.tls {
+        Some(tls) => {
+            let tls_config = RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path).await?;
+            info!("Operator API listening on https://{}", address);
+
+            axum_server::bind_rustls(address, tls_config)
+                .serve(router(state).into_make_service())
+                .await?;
+        }
+        None => {
+            let listener = tokio::net::TcpListener::bind(address).await?;
+            info!("Operator API listening on http://{}", address);
+
+            axum::serve(listener, router(state)).await?;
+        }
+    }
+
+    Ok(())
+}
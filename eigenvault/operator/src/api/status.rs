@@ -0,0 +1,160 @@
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use tokio::sync::RwLock;
+
+use crate::ethereum::TaskInfo;
+use crate::matching::{Decimal, OrderBookStats, OrderMatch};
+use crate::networking::PeerStats;
+use crate::storage::SubmissionKind;
+use super::ApiState;
+
+/// Number of recent matches and submissions kept for display
+const RECENT_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchSummary {
+    pub match_id: String,
+    pub pool_key: String,
+    pub matched_amount: Decimal,
+    pub matched_price: Decimal,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmissionSummary {
+    pub task_id: String,
+    pub tx_hash: String,
+    pub kind: SubmissionKind,
+    pub submitted_at: u64,
+    pub confirmed: bool,
+}
+
+/// Point-in-time view of the running operator
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OperatorStatus {
+    pub started_at: u64,
+    pub updated_at: u64,
+    pub last_processed_block: u64,
+    pub peers: Vec<PeerStats>,
+    pub pools: Vec<OrderBookStats>,
+    pub proof_queue_depth: u64,
+    /// Tasks assigned to this operator that are still open on-chain
+    pub pending_tasks: Vec<TaskInfo>,
+    pub recent_matches: VecDeque<MatchSummary>,
+    pub recent_submissions: VecDeque<SubmissionSummary>,
+    /// Matches and matched amount since the operator started
+    pub total_matches: u64,
+    pub total_volume: Decimal,
+}
+
+/// Live operator status, updated by the operator loops and read by the API
+pub struct StatusBoard {
+    status: RwLock<OperatorStatus>,
+}
+
+impl Default for StatusBoard {
+    fn default() -> Self {
+        Self {
+            status: RwLock::new(OperatorStatus {
+                started_at: chrono::Utc::now().timestamp() as u64,
+                ..OperatorStatus::default()
+            }),
+        }
+    }
+}
+
+impl StatusBoard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn snapshot(&self) -> OperatorStatus {
+        self.status.read().await.clone()
+    }
+
+    /// Refresh the periodically sampled fields
+    pub async fn update(
+        &self,
+        last_processed_block: u64,
+        pools: Vec<OrderBookStats>,
+        proof_queue_depth: u64,
+        pending_tasks: Vec<TaskInfo>,
+    ) {
+        let mut status = self.status.write().await;
+        status.updated_at = chrono::Utc::now().timestamp() as u64;
+        status.last_processed_block = last_processed_block;
+        status.pools = pools;
+        status.proof_queue_depth = proof_queue_depth;
+        status.pending_tasks = pending_tasks;
+    }
+
+    /// Refreshed by the P2P loop, which owns the network
+    pub async fn update_peers(&self, peers: Vec<PeerStats>) {
+        let mut status = self.status.write().await;
+        status.updated_at = chrono::Utc::now().timestamp() as u64;
+        status.peers = peers;
+    }
+
+    pub async fn record_match(&self, order_match: &OrderMatch) {
+        let mut status = self.status.write().await;
+        status.total_matches += 1;
+        status.total_volume += order_match.matched_amount;
+        push_recent(&mut status.recent_matches, MatchSummary {
+            match_id: order_match.match_id.clone(),
+            pool_key: order_match.pool_key.clone(),
+            matched_amount: order_match.matched_amount,
+            matched_price: order_match.matched_price,
+            timestamp: order_match.timestamp,
+        });
+    }
+
+    pub async fn record_submission(&self, task_id: &str, tx_hash: &str, kind: SubmissionKind) {
+        let mut status = self.status.write().await;
+        push_recent(&mut status.recent_submissions, SubmissionSummary {
+            task_id: task_id.to_string(),
+            tx_hash: tx_hash.to_string(),
+            kind,
+            submitted_at: chrono::Utc::now().timestamp() as u64,
+            confirmed: false,
+        });
+    }
+
+    pub async fn confirm_submission(&self, task_id: &str) {
+        let mut status = self.status.write().await;
+        for submission in status.recent_submissions.iter_mut() {
+            if submission.task_id == task_id {
+                submission.confirmed = true;
+            }
+        }
+    }
+}
+
+fn push_recent<T>(items: &mut VecDeque<T>, item: T) {
+    items.push_front(item);
+    items.truncate(RECENT_LIMIT);
+}
+
+pub async fn status(State(state): State<ApiState>) -> Json<OperatorStatus> {
+    Json(state.status.snapshot().await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_recent_submissions_bounded_and_confirmed() {
+        let board = StatusBoard::new();
+
+        for i in 0..(RECENT_LIMIT + 5) {
+            board.record_submission(&format!("task_{}", i), "0xabc", SubmissionKind::TaskResponse).await;
+        }
+        board.confirm_submission(&format!("task_{}", RECENT_LIMIT + 4)).await;
+
+        let status = board.snapshot().await;
+        assert_eq!(status.recent_submissions.len(), RECENT_LIMIT);
+        assert!(status.recent_submissions[0].confirmed);
+        assert!(!status.recent_submissions[1].confirmed);
+    }
+}
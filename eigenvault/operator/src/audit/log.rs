@@ -0,0 +1,246 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{error, info};
+
+/// Hash that precedes the first entry of every chain
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionKind {
+    OperatorRegistration,
+    /// Operator left the AVS through its stake registry
+    OperatorDeregistration,
+    TaskResponse,
+    VaultExecution,
+    /// Operator metadata URI updated through the AVS
+    MetadataUpdate,
+    /// Committee order key pointer updated through the AVS
+    OrderKeyUpdate,
+    /// Stake deposited into an EigenLayer strategy
+    StakeDeposit,
+    /// Encrypted order stored in the OrderVault by a trader
+    OrderSubmission,
+    /// Operator state upgraded to a new binary version; not an on-chain transaction
+    StateUpgrade,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// Transaction was signed and broadcast
+    Submitted,
+    Confirmed,
+    /// Local change applied without a transaction
+    Applied,
+    Failed { error: String },
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub sequence: u64,
+    pub timestamp: u64,
+    pub kind: TransactionKind,
+    /// Task or order the transaction acts on
+    pub subject: String,
+    /// SHA-256 of the signed calldata
+    pub payload_digest: String,
+    pub tx_hash: Option<String>,
+    pub outcome: AuditOutcome,
+    pub prev_hash: String,
+    pub entry_hash: String,
+}
+
+impl AuditEntry {
+    /// Hash over every field except `entry_hash`, chained to `prev_hash`
+    fn compute_hash(&self) -> Result<String> {
+        let mut unhashed = self.clone();
+        unhashed.entry_hash = String::new();
+
+        let mut hasher = Sha256::new();
+        hasher.update(self.prev_hash.as_bytes());
+        hasher.update(&serde_json::to_vec(&unhashed)?);
+        Ok(hex::encode(hasher.finalize()))
+    }
+}
+
+struct ChainHead {
+    file: File,
+    next_sequence: u64,
+    last_hash: String,
+}
+
+/// Append-only, hash-chained record of every transaction the operator signs.
+///
+/// Entries are stored one JSON object per line. Each entry commits to the
+/// previous one, so edits or deletions anywhere in the file break `verify`.
+pub struct AuditLog {
+    path: PathBuf,
+    head: Mutex<ChainHead>,
+}
+
+impl AuditLog {
+    /// Open or create the log, verifying the existing chain first
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let entries = if path.exists() { Self::verify(&path)? } else { vec![] };
+        let (next_sequence, last_hash) = match entries.last() {
+            Some(entry) => (entry.sequence + 1, entry.entry_hash.clone()),
+            None => (0, GENESIS_HASH.to_string()),
+        };
+
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        info!("Audit log opened at {:?} with {} entries", path, entries.len());
+
+        Ok(Self {
+            path,
+            head: Mutex::new(ChainHead { file, next_sequence, last_hash }),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an entry and fsync it before returning
+    pub fn record(
+        &self,
+        kind: TransactionKind,
+        subject: &str,
+        payload: &[u8],
+        tx_hash: Option<&str>,
+        outcome: AuditOutcome,
+    ) -> Result<AuditEntry> {
+        let mut head = self.head.lock().map_err(|_| anyhow!("Audit log lock poisoned"))?;
+
+        let mut entry = AuditEntry {
+            sequence: head.next_sequence,
+            timestamp: chrono::Utc::now().timestamp() as u64,
+            kind,
+            subject: subject.to_string(),
+            payload_digest: hex::encode(Sha256::digest(payload)),
+            tx_hash: tx_hash.map(str::to_string),
+            outcome,
+            prev_hash: head.last_hash.clone(),
+            entry_hash: String::new(),
+        };
+        entry.entry_hash = entry.compute_hash()?;
+
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        head.file.write_all(&line)?;
+        head.file.sync_data()?;
+
+        head.next_sequence += 1;
+        head.last_hash = entry.entry_hash.clone();
+
+        Ok(entry)
+    }
+
+    /// Record an entry, logging rather than propagating write failures so an
+    /// audit problem never masks the outcome of the transaction itself
+    pub fn record_or_log(
+        &self,
+        kind: TransactionKind,
+        subject: &str,
+        payload: &[u8],
+        tx_hash: Option<&str>,
+        outcome: AuditOutcome,
+    ) {
+        if let Err(e) = self.record(kind, subject, payload, tx_hash, outcome) {
+            error!("Failed to write audit log entry for {}: {:?}", subject, e);
+        }
+    }
+
+    /// Read and verify the whole chain, returning its entries
+    pub fn verify<P: AsRef<Path>>(path: P) -> Result<Vec<AuditEntry>> {
+        let reader = BufReader::new(File::open(path.as_ref())?);
+        let mut entries = Vec::new();
+        let mut expected_prev = GENESIS_HASH.to_string();
+
+        for (line_number, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let entry: AuditEntry = serde_json::from_str(&line)
+                .map_err(|e| anyhow!("Malformed audit entry on line {}: {}", line_number + 1, e))?;
+
+            if entry.sequence != entries.len() as u64 {
+                return Err(anyhow!("Audit sequence gap at line {}", line_number + 1));
+            }
+            if entry.prev_hash != expected_prev || entry.compute_hash()? != entry.entry_hash {
+                return Err(anyhow!("Audit chain broken at sequence {}", entry.sequence));
+            }
+
+            expected_prev = entry.entry_hash.clone();
+            entries.push(entry);
+        }
+
+        Ok(entries)
+    }
+
+    /// Verify the chain and write it as a pretty-printed JSON array for review
+    pub fn export<P: AsRef<Path>, Q: AsRef<Path>>(path: P, output: Q) -> Result<usize> {
+        let entries = Self::verify(path)?;
+        std::fs::write(output, serde_json::to_vec_pretty(&entries)?)?;
+        Ok(entries.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_chain_survives_reopen() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("audit.log");
+
+        let log = AuditLog::open(&path)?;
+        log.record(TransactionKind::TaskResponse, "task_1", b"calldata", Some("0xabc"), AuditOutcome::Submitted)?;
+        drop(log);
+
+        let log = AuditLog::open(&path)?;
+        let entry = log.record(TransactionKind::TaskResponse, "task_1", b"calldata", Some("0xabc"), AuditOutcome::Confirmed)?;
+        assert_eq!(entry.sequence, 1);
+
+        let entries = AuditLog::verify(&path)?;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].prev_hash, entries[0].entry_hash);
+        assert_eq!(entries[0].prev_hash, GENESIS_HASH);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tampering_detected() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let path = temp_dir.path().join("audit.log");
+
+        let log = AuditLog::open(&path)?;
+        log.record(TransactionKind::VaultExecution, "order_1", b"proof", Some("0x1"), AuditOutcome::Submitted)?;
+        log.record(TransactionKind::VaultExecution, "order_2", b"proof", Some("0x2"), AuditOutcome::Submitted)?;
+        drop(log);
+
+        let contents = std::fs::read_to_string(&path)?;
+        std::fs::write(&path, contents.replace("order_1", "order_9"))?;
+
+        assert!(AuditLog::verify(&path).is_err());
+        assert!(AuditLog::open(&path).is_err());
+
+        Ok(())
+    }
+}
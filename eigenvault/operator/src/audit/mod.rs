@@ -0,0 +1,3 @@
+pub mod log;
+
+pub use log::{AuditEntry, AuditLog, AuditOutcome, TransactionKind};
@@ -0,0 +1,70 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::BackpressureConfig;
+use crate::matching::MatchingEngine;
+use crate::metrics::metrics;
+
+/// Why intake is paused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Saturation {
+    PendingOrders { pending: usize, limit: usize },
+    Proving { in_flight: i64, limit: i64 },
+}
+
+/// Shared flow control: intake loops check it before pulling in more work so a
+/// saturated matching queue or prover slows ingestion instead of growing memory
+pub struct FlowControl {
+    config: BackpressureConfig,
+    matching: Arc<MatchingEngine>,
+}
+
+impl FlowControl {
+    pub fn new(config: BackpressureConfig, matching: Arc<MatchingEngine>) -> Self {
+        Self { config, matching }
+    }
+
+    pub fn pause(&self) -> Duration {
+        Duration::from_millis(self.config.pause_ms)
+    }
+
+    /// Current saturation, if any
+    pub async fn saturation(&self) -> Option<Saturation> {
+        let pending = self.matching.pending_count().await;
+        let max_pending_orders = self.matching.config().await.max_pending_orders;
+        let in_flight = metrics().proofs_in_flight.get();
+
+        check(&self.config, pending, max_pending_orders, in_flight)
+    }
+}
+
+fn check(config: &BackpressureConfig, pending: usize, max_pending_orders: usize, in_flight: i64) -> Option<Saturation> {
+    let limit = max_pending_orders.saturating_mul(config.pending_orders_high_water_percent as usize) / 100;
+    if pending >= limit.max(1) {
+        return Some(Saturation::PendingOrders { pending, limit });
+    }
+
+    if in_flight >= config.max_proofs_in_flight {
+        return Some(Saturation::Proving { in_flight, limit: config.max_proofs_in_flight });
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_limits() {
+        let config = BackpressureConfig {
+            pending_orders_high_water_percent: 90,
+            max_proofs_in_flight: 2,
+            pause_ms: 10,
+        };
+
+        assert_eq!(check(&config, 89, 100, 0), None);
+        assert_eq!(check(&config, 90, 100, 0), Some(Saturation::PendingOrders { pending: 90, limit: 90 }));
+        assert_eq!(check(&config, 0, 100, 2), Some(Saturation::Proving { in_flight: 2, limit: 2 }));
+    }
+}
@@ -0,0 +1,3 @@
+pub mod flow;
+
+pub use flow::{FlowControl, Saturation};
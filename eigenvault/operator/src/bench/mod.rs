@@ -0,0 +1,203 @@
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+use crate::config::{MatchingConfig, Settings};
+use crate::matching::{Decimal, DecryptedOrder, MatchingEngine, OrderMatch, OrderType, TimeInForce};
+use crate::proofs::ZKProver;
+use crate::sim::clock;
+
+/// Orders per synthetic pool; matching cost grows with the square of a pool's depth
+const ORDERS_PER_POOL: usize = 1000;
+
+pub struct BenchOptions {
+    pub orders: usize,
+    /// Matches per proof
+    pub batch_sizes: Vec<usize>,
+    /// Proofs generated per batch size
+    pub iterations: usize,
+}
+
+pub struct MatchingRun {
+    pub orders: usize,
+    pub pools: usize,
+    pub matches: usize,
+    pub elapsed: Duration,
+}
+
+pub struct ProofRun {
+    pub batch_size: usize,
+    pub mean: Duration,
+    pub max: Duration,
+    pub proof_bytes: usize,
+}
+
+pub struct SerializationRun {
+    pub subject: &'static str,
+    pub format: &'static str,
+    pub items: usize,
+    pub bytes: usize,
+    pub encode: Duration,
+    pub decode: Duration,
+}
+
+pub struct BenchReport {
+    pub matching: MatchingRun,
+    pub proofs: Vec<ProofRun>,
+    pub serialization: Vec<SerializationRun>,
+}
+
+impl BenchReport {
+    pub fn print(&self) {
+        let matching = &self.matching;
+        println!("Matching");
+        println!(
+            "  {} orders in {} pools -> {} matches in {:.3}s ({:.0} orders/s)",
+            matching.orders, matching.pools, matching.matches,
+            matching.elapsed.as_secs_f64(), per_second(matching.orders, matching.elapsed),
+        );
+
+        println!();
+        println!("Proof generation");
+        println!("  {:>8} {:>12} {:>12} {:>12}", "batch", "mean", "max", "proof size");
+        for run in &self.proofs {
+            println!(
+                "  {:>8} {:>12} {:>12} {:>12}",
+                run.batch_size, format!("{:.2?}", run.mean), format!("{:.2?}", run.max), format!("{} B", run.proof_bytes),
+            );
+        }
+
+        println!();
+        println!("Serialization");
+        println!("  {:<8} {:<8} {:>8} {:>12} {:>12} {:>12}", "subject", "format", "items", "bytes/item", "encode/item", "decode/item");
+        for run in &self.serialization {
+            let items = run.items.max(1) as u32;
+            println!(
+                "  {:<8} {:<8} {:>8} {:>12} {:>12} {:>12}",
+                run.subject, run.format, run.items, run.bytes / items as usize,
+                format!("{:.2?}", run.encode / items), format!("{:.2?}", run.decode / items),
+            );
+        }
+    }
+}
+
+/// Time matching, proving and serialization on synthetic orders with the configured settings
+pub async fn run(settings: &Settings, options: &BenchOptions) -> Result<BenchReport> {
+    let orders = synthetic_orders(options.orders);
+
+    // Synthetic orders are unsigned
+    let config = MatchingConfig { require_trader_signatures: false, ..settings.matching.clone() };
+    let engine = MatchingEngine::new(config).await?;
+    let started = Instant::now();
+    let matches = engine.find_matches(orders.clone()).await?;
+    let matching = MatchingRun {
+        orders: orders.len(),
+        pools: orders.len().div_ceil(ORDERS_PER_POOL),
+        matches: matches.len(),
+        elapsed: started.elapsed(),
+    };
+    if matches.is_empty() {
+        return Err(anyhow::anyhow!("No matches found in {} orders; nothing to prove", orders.len()));
+    }
+
+    let prover = ZKProver::new(settings.proofs.clone()).await?;
+    let mut proofs = Vec::new();
+    for &batch_size in &options.batch_sizes {
+        let batch: Vec<OrderMatch> = matches.iter().cycle().take(batch_size).cloned().collect();
+        let mut timings = Vec::new();
+        let mut proof_bytes = 0;
+
+        for _ in 0..options.iterations.max(1) {
+            let started = Instant::now();
+            let proof = prover.generate_matching_proof(&batch, &batch[0].pool_key).await?;
+            timings.push(started.elapsed());
+            proof_bytes = proof.proof_data.len();
+        }
+
+        proofs.push(ProofRun {
+            batch_size,
+            mean: timings.iter().sum::<Duration>() / timings.len() as u32,
+            max: timings.iter().copied().max().unwrap_or_default(),
+            proof_bytes,
+        });
+    }
+
+    let proof = prover.generate_matching_proof(&matches[..1], &matches[0].pool_key).await?;
+    let mut serialization = Vec::new();
+    serialization.extend(serialization_runs("orders", &orders)?);
+    serialization.extend(serialization_runs("matches", &matches)?);
+    serialization.extend(serialization_runs("proof", std::slice::from_ref(&proof))?);
+
+    Ok(BenchReport { matching, proofs, serialization })
+}
+
+/// Crossing buys and sells spread over pools of `ORDERS_PER_POOL` orders
+fn synthetic_orders(count: usize) -> Vec<DecryptedOrder> {
+    let deadline = clock::unix_secs() + 3600;
+
+    (0..count).map(|i| {
+        let (order_type, price) = if i % 2 == 0 {
+            (OrderType::Buy, Decimal::from(2000 + (i % 10) as u64))
+        } else {
+            (OrderType::Sell, Decimal::from(1995 + (i % 10) as u64))
+        };
+        DecryptedOrder {
+            id: format!("bench_{}", i),
+            trader: format!("bench_trader_{}", i % 50),
+            pool_key: format!("BENCH_POOL_{}", i / ORDERS_PER_POOL),
+            order_type,
+            amount: Decimal::from(1 + (i % 7) as u64),
+            price,
+            deadline,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        }
+    }).collect()
+}
+
+/// Encode and decode `items` as JSON and bincode
+fn serialization_runs<T: Serialize + DeserializeOwned>(subject: &'static str, items: &[T]) -> Result<Vec<SerializationRun>> {
+    let started = Instant::now();
+    let json = serde_json::to_vec(items)?;
+    let json_encode = started.elapsed();
+    let started = Instant::now();
+    let _: Vec<T> = serde_json::from_slice(&json)?;
+    let json_decode = started.elapsed();
+
+    let started = Instant::now();
+    let binary = bincode::serialize(items)?;
+    let bincode_encode = started.elapsed();
+    let started = Instant::now();
+    let _: Vec<T> = bincode::deserialize(&binary)?;
+    let bincode_decode = started.elapsed();
+
+    Ok(vec![
+        SerializationRun { subject, format: "json", items: items.len(), bytes: json.len(), encode: json_encode, decode: json_decode },
+        SerializationRun { subject, format: "bincode", items: items.len(), bytes: binary.len(), encode: bincode_encode, decode: bincode_decode },
+    ])
+}
+
+fn per_second(count: usize, elapsed: Duration) -> f64 {
+    count as f64 / elapsed.as_secs_f64().max(f64::EPSILON)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bench_report() -> Result<()> {
+        let options = BenchOptions { orders: 200, batch_sizes: vec![1, 4], iterations: 2 };
+        let report = run(&Settings::default(), &options).await?;
+
+        assert_eq!(report.matching.orders, 200);
+        assert!(report.matching.matches > 0);
+        assert_eq!(report.proofs.iter().map(|run| run.batch_size).collect::<Vec<_>>(), vec![1, 4]);
+        assert_eq!(report.serialization.len(), 6);
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::info;
+
+use crate::audit::AuditLog;
+use crate::config::Settings;
+use crate::ethereum::{EthereumClient, JsonRpc};
+
+pub const HOLESKY_CHAIN_ID: u64 = 17000;
+
+/// Left on top of the stake for the deposit and registration transactions
+const GAS_RESERVE_WEI: u128 = 50_000_000_000_000_000;
+
+const WEI_PER_ETH: u128 = 1_000_000_000_000_000_000;
+
+/// How often the balance is re-checked while waiting for funds
+const FUNDING_POLL: Duration = Duration::from_secs(10);
+
+pub struct BootstrapOptions {
+    /// Faucet that accepts `{"address": ...}`; without one the operator address is funded by hand
+    pub faucet_url: Option<String>,
+    /// EigenLayer strategy the stake is deposited into
+    pub strategy: String,
+    pub stake_wei: u128,
+    /// Give up waiting for funds after this long
+    pub funding_timeout: Duration,
+    /// Log the deposit and registration instead of broadcasting them
+    pub dry_run: bool,
+}
+
+/// Fund the operator, deposit stake and register it with the AVS on Holesky
+pub async fn run(config: &Settings, config_path: &Path, options: &BootstrapOptions) -> Result<()> {
+    let rpc = JsonRpc::new(&config.ethereum.rpc_url);
+    let chain_id = quantity(&rpc.call("eth_chainId", json!([])).await?)?;
+    if chain_id != HOLESKY_CHAIN_ID as u128 {
+        return Err(anyhow::anyhow!(
+            "bootstrap-testnet only runs against Holesky (chain {}); {} is chain {}",
+            HOLESKY_CHAIN_ID, config.ethereum.rpc_url, chain_id
+        ));
+    }
+
+    let operator = &config.ethereum.operator_address;
+    let needed = options.stake_wei + GAS_RESERVE_WEI;
+    let balance = balance(&rpc, operator).await?;
+    info!("Operator {} holds {} ETH", operator, format_ether(balance));
+
+    if balance < needed {
+        match &options.faucet_url {
+            Some(faucet_url) => request_funds(faucet_url, operator).await?,
+            None => println!(
+                "Send at least {} ETH to {} from a Holesky faucet; waiting up to {}s...",
+                format_ether(needed - balance), operator, options.funding_timeout.as_secs(),
+            ),
+        }
+        wait_for_funds(&rpc, operator, needed, options.funding_timeout).await?;
+    }
+
+    let audit_log = if config.audit.enabled {
+        Some(Arc::new(AuditLog::open(&config.audit.path)?))
+    } else {
+        None
+    };
+    let mut client = EthereumClient::new(config.ethereum.clone()).await?;
+    if let Some(audit_log) = audit_log {
+        client.set_audit_log(audit_log);
+    }
+    client.set_dry_run(options.dry_run);
+
+    let tx_hash = client.deposit_stake(&options.strategy, options.stake_wei).await?;
+    println!("Deposited {} ETH into strategy {}: {}", format_ether(options.stake_wei), options.strategy, tx_hash);
+
+    let operator_id = client.register_operator().await?;
+    println!("Registered operator {} with the AVS, operator ID {}", operator, operator_id);
+
+    print_next_steps(config_path);
+    Ok(())
+}
+
+async fn request_funds(faucet_url: &str, address: &str) -> Result<()> {
+    info!("Requesting testnet funds for {} from {}", address, faucet_url);
+    reqwest::Client::new()
+        .post(faucet_url)
+        .json(&json!({ "address": address }))
+        .timeout(Duration::from_secs(30))
+        .send()
+        .await?
+        .error_for_status()
+        .with_context(|| format!("Faucet {} refused the request", faucet_url))?;
+    Ok(())
+}
+
+async fn wait_for_funds(rpc: &JsonRpc, address: &str, needed: u128, timeout: Duration) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let balance = balance(rpc, address).await?;
+        if balance >= needed {
+            info!("Operator funded with {} ETH", format_ether(balance));
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(anyhow::anyhow!(
+                "{} still holds {} of the {} ETH needed after {}s",
+                address, format_ether(balance), format_ether(needed), timeout.as_secs()
+            ));
+        }
+        tokio::time::sleep(FUNDING_POLL).await;
+    }
+}
+
+async fn balance(rpc: &JsonRpc, address: &str) -> Result<u128> {
+    quantity(&rpc.call("eth_getBalance", json!([address, "latest"])).await?)
+}
+
+fn print_next_steps(config_path: &Path) {
+    let config = config_path.display();
+    println!();
+    println!("Next steps:");
+    println!("  1. Describe the operator in the `metadata` section and publish it:");
+    println!("       eigenvault-operator publish-metadata --config {} --output operator.json", config);
+    println!("  2. Check the setup before going live:");
+    println!("       eigenvault-operator validate-config --config {}", config);
+    println!("  3. Start the operator and watch it:");
+    println!("       eigenvault-operator start --config {}", config);
+    println!("       eigenvault-operator status --config {}", config);
+}
+
+/// Parse a decimal ETH amount such as `0.5` into wei without going through floats
+pub fn parse_ether(amount: &str) -> Result<u128> {
+    let invalid = || anyhow::anyhow!("Invalid ETH amount '{}'", amount);
+    let (whole, fraction) = amount.trim().split_once('.').unwrap_or((amount.trim(), ""));
+    if (whole.is_empty() && fraction.is_empty()) || fraction.len() > 18 {
+        return Err(invalid());
+    }
+
+    let whole: u128 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| invalid())? };
+    let fraction: u128 = if fraction.is_empty() {
+        0
+    } else {
+        format!("{:0<18}", fraction).parse().map_err(|_| invalid())?
+    };
+    whole.checked_mul(WEI_PER_ETH)
+        .and_then(|wei| wei.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
+/// Format wei as a decimal ETH amount without trailing zeros
+pub fn format_ether(wei: u128) -> String {
+    let fraction = format!("{:018}", wei % WEI_PER_ETH);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        (wei / WEI_PER_ETH).to_string()
+    } else {
+        format!("{}.{}", wei / WEI_PER_ETH, fraction)
+    }
+}
+
+fn quantity(value: &Value) -> Result<u128> {
+    value.as_str()
+        .and_then(|hex| u128::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+        .ok_or_else(|| anyhow::anyhow!("Expected a hex quantity, got {}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ether_amounts() {
+        assert_eq!(parse_ether("1").unwrap(), WEI_PER_ETH);
+        assert_eq!(parse_ether("0.05").unwrap(), GAS_RESERVE_WEI);
+        assert_eq!(parse_ether(".5").unwrap(), WEI_PER_ETH / 2);
+        assert!(parse_ether("1.2.3").is_err());
+        assert!(parse_ether("0.0000000000000000001").is_err());
+        assert!(parse_ether("").is_err());
+
+        assert_eq!(format_ether(WEI_PER_ETH + GAS_RESERVE_WEI), "1.05");
+        assert_eq!(format_ether(3 * WEI_PER_ETH), "3");
+        assert_eq!(quantity(&json!("0x4268")).unwrap(), 17000);
+    }
+}
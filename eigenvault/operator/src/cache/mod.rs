@@ -0,0 +1,155 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::time::Duration;
+
+use crate::metrics::metrics;
+use crate::sim::clock;
+
+struct Entry<V> {
+    value: V,
+    expires_at: Duration,
+    /// Position in `BoundedCache::recency`
+    used: u64,
+}
+
+/// Least-recently-used cache holding at most `capacity` entries, each for at most `ttl`.
+///
+/// Inserting into a full cache drops the entry used longest ago. Entries past their TTL are
+/// never returned and are dropped once they reach the least recently used end. Both kinds of
+/// eviction count towards `cache_evictions_total` under the cache's name.
+pub struct BoundedCache<K, V> {
+    name: &'static str,
+    capacity: usize,
+    ttl: Duration,
+    entries: HashMap<K, Entry<V>>,
+    /// Keys by when they were last used, least recent first
+    recency: BTreeMap<u64, K>,
+    next_use: u64,
+}
+
+impl<K: Eq + Hash + Clone, V> BoundedCache<K, V> {
+    pub fn new(name: &'static str, capacity: usize, ttl: Duration) -> Self {
+        Self {
+            name,
+            capacity,
+            ttl,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            next_use: 0,
+        }
+    }
+
+    /// Entries held, including expired ones not dropped yet
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        let now = clock::elapsed();
+        self.entries.get(key).is_some_and(|entry| entry.expires_at > now)
+    }
+
+    /// Value for `key`, which then counts as the most recently used
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let now = clock::elapsed();
+        let entry = self.entries.get_mut(key).filter(|entry| entry.expires_at > now)?;
+        self.recency.remove(&entry.used);
+        entry.used = self.next_use;
+        self.next_use += 1;
+        self.recency.insert(entry.used, key.clone());
+        Some(&mut entry.value)
+    }
+
+    /// Insert or replace `key`, evicting expired entries and then, if full, the least recently used
+    pub fn insert(&mut self, key: K, value: V) {
+        self.evict_expired();
+
+        let used = self.next_use;
+        self.next_use += 1;
+        let entry = Entry { value, expires_at: clock::elapsed() + self.ttl, used };
+        if let Some(previous) = self.entries.insert(key.clone(), entry) {
+            self.recency.remove(&previous.used);
+        }
+        self.recency.insert(used, key);
+
+        while self.entries.len() > self.capacity {
+            self.evict_oldest("capacity");
+        }
+    }
+
+    /// Unexpired values, least recently used first
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        let now = clock::elapsed();
+        self.recency.values()
+            .map(|key| &self.entries[key])
+            .filter(move |entry| entry.expires_at > now)
+            .map(|entry| &entry.value)
+    }
+
+    /// Every held value, in no particular order
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut V> {
+        self.entries.values_mut().map(|entry| &mut entry.value)
+    }
+
+    /// Change the limits, evicting what no longer fits; entries keep the TTL they were inserted with
+    pub fn resize(&mut self, capacity: usize, ttl: Duration) {
+        self.capacity = capacity;
+        self.ttl = ttl;
+        while self.entries.len() > self.capacity {
+            self.evict_oldest("capacity");
+        }
+    }
+
+    fn evict_expired(&mut self) {
+        let now = clock::elapsed();
+        while let Some((_, key)) = self.recency.first_key_value() {
+            if self.entries[key].expires_at > now {
+                break;
+            }
+            self.evict_oldest("expired");
+        }
+    }
+
+    fn evict_oldest(&mut self, reason: &str) {
+        if let Some((_, key)) = self.recency.pop_first() {
+            self.entries.remove(&key);
+            metrics().cache_evictions.with_label_values(&[self.name, reason]).inc();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_cache_evicts_least_recently_used() {
+        let mut cache = BoundedCache::new("test", 2, Duration::from_secs(60));
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        *cache.get_mut(&"a").unwrap() += 10;
+        cache.insert("c", 3);
+
+        assert!(!cache.contains_key(&"b"));
+        assert_eq!(cache.values().copied().collect::<Vec<_>>(), vec![11, 3]);
+
+        cache.resize(1, Duration::from_secs(60));
+        assert_eq!(cache.values().copied().collect::<Vec<_>>(), vec![3]);
+    }
+
+    #[test]
+    fn test_expired_entries_hidden_then_dropped() {
+        let mut cache = BoundedCache::new("test", 10, Duration::ZERO);
+        cache.insert("a", 1);
+        assert!(!cache.contains_key(&"a"));
+        assert!(cache.get_mut(&"a").is_none());
+        assert_eq!(cache.values().count(), 0);
+
+        cache.insert("b", 2);
+        assert_eq!(cache.len(), 1);
+    }
+}
@@ -0,0 +1,86 @@
+use anyhow::{anyhow, Result};
+use rand::Rng;
+use std::sync::RwLock;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::config::ChaosConfig;
+use crate::sim::entropy;
+
+/// Faults in effect; `None` while injection is off
+static FAULTS: RwLock<Option<ChaosConfig>> = RwLock::new(None);
+
+/// Replace the injected faults; a disabled config turns injection off
+pub fn configure(config: &ChaosConfig) {
+    if config.enabled {
+        warn!("Fault injection active: {:?}", config);
+    }
+    *FAULTS.write().unwrap() = config.enabled.then(|| config.clone());
+}
+
+pub fn current() -> ChaosConfig {
+    active().unwrap_or_default()
+}
+
+/// Hold an outbound gossip message for the configured latency
+pub async fn network_delay() {
+    if let Some(faults) = active() {
+        sleep(faults.latency_ms, faults.jitter_ms).await;
+    }
+}
+
+/// Whether to discard the outbound gossip message about to be sent
+pub fn drop_message() -> bool {
+    active().is_some_and(|faults| roll(faults.drop_percent))
+}
+
+/// Whether to deliver the next inbound message after the one queued behind it
+pub fn reorder_message() -> bool {
+    active().is_some_and(|faults| roll(faults.reorder_percent))
+}
+
+/// Delay a chain RPC call and possibly fail it in place of the node
+pub async fn rpc_fault(method: &str) -> Result<()> {
+    let Some(faults) = active() else {
+        return Ok(());
+    };
+
+    sleep(faults.rpc_latency_ms, faults.jitter_ms).await;
+    if roll(faults.rpc_error_percent) {
+        debug!("Injecting failure into RPC call {}", method);
+        return Err(anyhow!("Injected RPC failure: {}", method));
+    }
+    Ok(())
+}
+
+fn active() -> Option<ChaosConfig> {
+    FAULTS.read().unwrap().clone()
+}
+
+/// Draws come from `sim::entropy`, so a seeded simulation injects the same faults every run
+fn roll(percent: f64) -> bool {
+    percent > 0.0 && entropy::with_rng(|rng| rng.gen::<f64>() * 100.0 < percent)
+}
+
+async fn sleep(base_ms: u64, jitter_ms: u64) {
+    let jitter = if jitter_ms > 0 {
+        entropy::with_rng(|rng| rng.gen_range(0..=jitter_ms))
+    } else {
+        0
+    };
+    let delay = Duration::from_millis(base_ms + jitter);
+    if !delay.is_zero() {
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roll_bounds() {
+        assert!(!roll(0.0));
+        assert!((0..100).all(|_| roll(100.0)));
+    }
+}
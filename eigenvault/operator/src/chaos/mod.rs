@@ -0,0 +1,9 @@
+#[cfg(feature = "chaos")]
+mod injector;
+#[cfg(not(feature = "chaos"))]
+mod noop;
+
+#[cfg(feature = "chaos")]
+pub use injector::{configure, current, drop_message, network_delay, reorder_message, rpc_fault};
+#[cfg(not(feature = "chaos"))]
+pub use noop::{configure, drop_message, network_delay, reorder_message, rpc_fault};
@@ -0,0 +1,21 @@
+use anyhow::Result;
+
+use crate::config::ChaosConfig;
+
+// Builds without the `chaos` feature compile every hook down to nothing
+
+pub fn configure(_config: &ChaosConfig) {}
+
+pub async fn network_delay() {}
+
+pub fn drop_message() -> bool {
+    false
+}
+
+pub fn reorder_message() -> bool {
+    false
+}
+
+pub async fn rpc_fault(_method: &str) -> Result<()> {
+    Ok(())
+}
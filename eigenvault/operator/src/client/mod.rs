@@ -0,0 +1,286 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::info;
+
+use crate::config::{EthereumConfig, Secret};
+use crate::ethereum::contracts::EigenVaultContracts;
+use crate::ethereum::ContractApi;
+use crate::matching::privacy::{EncryptedOrderData, EncryptionKeys};
+use crate::matching::signing::{self, OrderCancellation, OrderDomain, OrderMessage};
+use crate::matching::{Decimal, EncryptionManager, OrderKeyDocument, OrderType, TimeInForce};
+use crate::sim::clock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl From<Side> for OrderType {
+    fn from(side: Side) -> Self {
+        match side {
+            Side::Buy => OrderType::Buy,
+            Side::Sell => OrderType::Sell,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Tif {
+    /// Rest in the book until the deadline
+    Gtd,
+    /// Fill what crosses in the next matching round, drop the rest
+    Ioc,
+    /// Fill in full in the next matching round or not at all
+    Fok,
+}
+
+impl From<Tif> for TimeInForce {
+    fn from(tif: Tif) -> Self {
+        match tif {
+            Tif::Gtd => TimeInForce::Gtd,
+            Tif::Ioc => TimeInForce::Ioc,
+            Tif::Fok => TimeInForce::Fok,
+        }
+    }
+}
+
+/// An order as the trader states it, before encryption
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlainOrder {
+    pub trader: String,
+    pub pool_key: String,
+    pub order_type: OrderType,
+    pub amount: Decimal,
+    pub price: Decimal,
+    pub deadline: u64,
+    /// Replay nonce, unique per trader and counting from 1
+    #[serde(default)]
+    pub trader_nonce: Option<u64>,
+    /// Good til date when absent
+    #[serde(default)]
+    pub time_in_force: Option<TimeInForce>,
+}
+
+/// An encrypted order, ready for the OrderVault
+#[derive(Debug, Clone)]
+pub struct SealedOrder {
+    /// bytes32 id, derived from the commitment
+    pub order_id: String,
+    pub trader: String,
+    pub commitment: String,
+    pub encrypted_order: Vec<u8>,
+    pub deadline: u64,
+}
+
+/// Encrypts orders for the operator committee and stores them in the OrderVault
+pub struct TraderClient<A = EigenVaultContracts> {
+    encryption: EncryptionManager,
+    contracts: A,
+    domain: OrderDomain,
+    /// Trader key orders are signed with; unsigned orders are only accepted by operators that allow them
+    signer: Option<Secret>,
+}
+
+impl TraderClient {
+    /// Connect to and verify the contracts in `config`, and fetch the committee key from
+    /// `key_url`, or from wherever the service manager says it is published
+    pub async fn connect(config: &EthereumConfig, key_url: Option<&str>) -> Result<Self> {
+        let contracts = EigenVaultContracts::new(
+            &config.rpc_url,
+            &config.eigenvault_hook_address,
+            &config.service_manager_address,
+            &config.order_vault_address,
+        ).await?;
+        contracts.verify_contracts(config.expected_chain_id).await?;
+        let keys = match key_url {
+            Some(key_url) => fetch_committee_key(key_url).await?,
+            None => discover_committee_key(&contracts).await?,
+        };
+        let domain = OrderDomain {
+            chain_id: contracts.get_chain_id().await?,
+            verifying_contract: config.order_vault_address.clone(),
+        };
+
+        Self::new(keys, contracts, domain)
+    }
+}
+
+impl<A: ContractApi> TraderClient<A> {
+    pub fn new(keys: EncryptionKeys, contracts: A, domain: OrderDomain) -> Result<Self> {
+        Ok(Self { encryption: EncryptionManager::from_keys(keys)?, contracts, domain, signer: None })
+    }
+
+    /// Sign orders with `private_key`, which must belong to the trader of every order sealed
+    pub fn with_signer(mut self, private_key: Secret) -> Self {
+        self.signer = Some(private_key);
+        self
+    }
+
+    /// Encrypt `order` under a fresh nonce, with the commitment operators verify
+    pub fn seal(&self, order: &PlainOrder) -> Result<SealedOrder> {
+        if order.amount.is_zero() || order.price.is_zero() {
+            return Err(anyhow::anyhow!("Order amount and price must be positive"));
+        }
+        if order.deadline <= clock::unix_secs() {
+            return Err(anyhow::anyhow!("Order deadline {} has already passed", order.deadline));
+        }
+
+        let mut payload = EncryptedOrderData {
+            trader: order.trader.clone(),
+            pool_key: order.pool_key.clone(),
+            order_type: order.order_type.clone(),
+            amount: order.amount,
+            price: order.price,
+            deadline: order.deadline,
+            nonce: EncryptionManager::generate_nonce(),
+            commitment: String::new(),
+            signature: None,
+            trader_nonce: order.trader_nonce,
+            time_in_force: order.time_in_force,
+        };
+        payload.commitment = self.encryption.generate_commitment(&payload)?;
+        if let Some(private_key) = &self.signer {
+            let signer = signing::signer_address(private_key.expose())?;
+            if !signer.eq_ignore_ascii_case(&order.trader) {
+                return Err(anyhow::anyhow!("Signing key belongs to {}, not trader {}", signer, order.trader));
+            }
+            payload.signature = Some(self.domain.sign(&OrderMessage::from(&payload), private_key.expose())?);
+        }
+
+        Ok(SealedOrder {
+            order_id: format!("0x{}", payload.commitment),
+            trader: order.trader.clone(),
+            encrypted_order: self.encryption.encrypt_order(&payload)?,
+            commitment: payload.commitment,
+            deadline: order.deadline,
+        })
+    }
+
+    /// Seal `order` and store it in the OrderVault, returning it with the transaction hash
+    pub async fn submit(&self, order: &PlainOrder) -> Result<(SealedOrder, String)> {
+        let sealed = self.seal(order)?;
+        let tx_hash = self.contracts
+            .store_order(&sealed.order_id, &sealed.trader, &sealed.encrypted_order, sealed.deadline)
+            .await?;
+
+        info!("Stored order {} for {} in {}", sealed.order_id, sealed.trader, tx_hash);
+        Ok((sealed, tx_hash))
+    }
+}
+
+/// Ask the operator whose public listener is at `operator_url` to drop a pending order
+pub async fn cancel_order(operator_url: &str, cancellation: &OrderCancellation) -> Result<()> {
+    let url = format!("{}/orders/{}/cancel", operator_url.trim_end_matches('/'), cancellation.commitment);
+    let response = reqwest::Client::new()
+        .post(&url)
+        .json(cancellation)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await?;
+
+    match response.status() {
+        status if status.is_success() => Ok(()),
+        reqwest::StatusCode::NOT_FOUND => Err(anyhow::anyhow!("Order {} is not pending at {}", cancellation.commitment, operator_url)),
+        status => Err(anyhow::anyhow!("Cancellation of {} refused by {}: {}", cancellation.commitment, operator_url, status)),
+    }
+}
+
+/// Fetch the committee key from the URI the service manager points traders at
+pub async fn discover_committee_key(contracts: &impl ContractApi) -> Result<EncryptionKeys> {
+    let (uri, version) = contracts.get_order_key_uri().await?
+        .ok_or_else(|| anyhow::anyhow!("No order key is published on chain"))?;
+    info!("Using order key version {} from {}", version, uri);
+    fetch_committee_key(&uri).await
+}
+
+/// Download the committee's order key
+pub async fn fetch_committee_key(url: &str) -> Result<EncryptionKeys> {
+    // Only the order key is needed; anything private published by mistake is never read
+    Ok(fetch_order_key(url).await?.into())
+}
+
+/// Download the published order key document at `url`
+pub async fn fetch_order_key(url: &str) -> Result<OrderKeyDocument> {
+    reqwest::Client::new()
+        .get(url)
+        .timeout(Duration::from_secs(10))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .with_context(|| format!("Invalid committee key document at {}", url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethereum::{ChainClient, EthereumEvent, MockChain};
+
+    // Second anvil development account
+    const TRADER_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    fn domain() -> OrderDomain {
+        OrderDomain { chain_id: 31337, verifying_contract: "0x3456789012345678901234567890123456789012".to_string() }
+    }
+
+    fn order() -> PlainOrder {
+        PlainOrder {
+            trader: "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type: OrderType::Sell,
+            amount: "2.5".parse().unwrap(),
+            price: Decimal::from(2001),
+            deadline: clock::unix_secs() + 3600,
+            trader_nonce: Some(1),
+            time_in_force: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submitted_order_decrypts_for_operators() -> Result<()> {
+        let operator = EncryptionManager::new()?;
+        let keys = operator.export_keys()?;
+        let chain = MockChain::new();
+        let client = TraderClient::new(keys, chain.clone(), domain())?.with_signer(Secret::new(TRADER_KEY));
+
+        let (sealed, _) = client.submit(&order()).await?;
+
+        let events: Vec<EthereumEvent> = chain.listen_for_events().await?.into_iter().map(|chain_event| chain_event.event).collect();
+        let encrypted_order = match events.as_slice() {
+            [EthereumEvent::OrderStored { order_id, encrypted_order, .. }] if *order_id == sealed.order_id => encrypted_order,
+            other => panic!("Unexpected events {:?}", other),
+        };
+        let decrypted = operator.decrypt_order(encrypted_order, sealed.order_id.clone())?;
+        assert_eq!(decrypted.order_type, OrderType::Sell);
+        assert_eq!(decrypted.amount, "2.5".parse::<Decimal>()?);
+        assert_eq!(decrypted.price, Decimal::from(2001));
+        assert_eq!(decrypted.trader_nonce, Some(1));
+        domain().verify(&OrderMessage::from(&decrypted), decrypted.signature.as_deref().unwrap())?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_key_discovery_follows_on_chain_pointer() -> Result<()> {
+        let chain = MockChain::new();
+        assert!(discover_committee_key(&chain).await.is_err());
+
+        let uri = "http://operator.example.com/keys/orders";
+        chain.update_order_key_uri(uri, 2).await?;
+        assert_eq!(chain.get_order_key_uri().await?, Some((uri.to_string(), 2)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_seal_unsigned_and_expired_orders() -> Result<()> {
+        let client = TraderClient::new(EncryptionManager::new()?.export_keys()?, MockChain::new(), domain())?;
+        let expired = PlainOrder { deadline: clock::unix_secs() - 1, ..order() };
+
+        assert!(client.seal(&expired).is_err());
+        assert!(client.seal(&order())?.encrypted_order.len() > 12);
+        Ok(())
+    }
+}
@@ -1,17 +1,31 @@
 use anyhow::Result;
+use blst::min_pk::{AggregateSignature, PublicKey as BlsPublicKey, SecretKey as BlsSecretKey, Signature as BlsSignature};
+use blst::BLST_ERROR;
 use secp256k1::{SecretKey, PublicKey, Secp256k1};
 use rand::rngs::OsRng;
+use rand::RngCore;
 use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 use sha3::Digest; // Add this import for digest functionality
 
+/// Ciphersuite of BLS signatures: public keys in G1, signatures in G2, and proofs of possession
+/// guarding aggregates of one message against rogue keys
+const BLS_SIGNATURE_DST: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+const BLS_POP_DST: &[u8] = b"BLS_POP_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OperatorKeys {
     pub ethereum_private_key: String,
     pub ethereum_public_key: String,
     pub ethereum_address: String,
+    /// BLS12-381 key pair; empty in key files written before BLS keys were generated
+    #[serde(default)]
     pub bls_private_key: String,
+    #[serde(default)]
     pub bls_public_key: String,
+    /// Proof of possession of the BLS key, hex; published with the public key in the committee
+    #[serde(default)]
+    pub bls_possession_proof: String,
     pub encryption_private_key: String,
     pub encryption_public_key: String,
 }
@@ -33,8 +47,8 @@ impl KeyManager {
         // Generate Ethereum keys
         let ethereum_keys = self.generate_ethereum_keys()?;
         
-        // Generate BLS keys (simplified - in production would use proper BLS library)
         let bls_keys = self.generate_bls_keys()?;
+        let bls_possession_proof = self.prove_bls_possession(&bls_keys.0)?;
         
         // Generate encryption keys
         let encryption_keys = self.generate_encryption_keys()?;
@@ -45,6 +59,7 @@ impl KeyManager {
             ethereum_address: ethereum_keys.2,
             bls_private_key: bls_keys.0,
             bls_public_key: bls_keys.1,
+            bls_possession_proof: format!("0x{}", hex::encode(bls_possession_proof)),
             encryption_private_key: encryption_keys.0,
             encryption_public_key: encryption_keys.1,
         };
@@ -75,16 +90,13 @@ impl KeyManager {
     }
 
     fn generate_bls_keys(&self) -> Result<(String, String)> {
-        // Simplified BLS key generation
-        // In production, would use a proper BLS library like blstrs
-        let mut rng = OsRng;
-        let secret_key = SecretKey::new(&mut rng);
-        let public_key = PublicKey::from_secret_key(&self.secp, &secret_key);
+        let mut ikm = [0u8; 32];
+        OsRng.fill_bytes(&mut ikm);
+        let secret_key = BlsSecretKey::key_gen(&ikm, &[]).map_err(bls_error)?;
+        let private_key = format!("0x{}", hex::encode(secret_key.to_bytes()));
+        let public_key = self.bls_public_key(&private_key)?;
 
-        Ok((
-            hex::encode(secret_key.secret_bytes()),
-            hex::encode(public_key.serialize()),
-        ))
+        Ok((private_key, public_key))
     }
 
     fn generate_encryption_keys(&self) -> Result<(String, String)> {
@@ -136,11 +148,12 @@ impl KeyManager {
             &keys.encryption_private_key,
         ).await?;
 
-        // Create public keys file
+        // Create public keys file; the proof of possession lets others aggregate under the BLS key
         let public_keys = serde_json::json!({
             "ethereum_address": keys.ethereum_address,
             "ethereum_public_key": keys.ethereum_public_key,
             "bls_public_key": keys.bls_public_key,
+            "bls_proof_of_possession": format!("0x{}", hex::encode(self.prove_bls_possession(&keys.bls_private_key)?)),
             "encryption_public_key": keys.encryption_public_key,
         });
 
@@ -153,9 +166,11 @@ impl KeyManager {
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
-            let mut perms = tokio::fs::metadata(output_dir.join("ethereum_private_key.txt")).await?.permissions();
-            perms.set_mode(0o600);
-            tokio::fs::set_permissions(output_dir.join("ethereum_private_key.txt"), perms).await?;
+            for private_key_file in ["ethereum_private_key.txt", "bls_private_key.txt"] {
+                let mut perms = tokio::fs::metadata(output_dir.join(private_key_file)).await?.permissions();
+                perms.set_mode(0o600);
+                tokio::fs::set_permissions(output_dir.join(private_key_file), perms).await?;
+            }
         }
 
         Ok(())
@@ -191,6 +206,15 @@ impl KeyManager {
             return Ok(false);
         }
 
+        // Key files from before BLS keys were generated have none to check
+        if !keys.bls_private_key.is_empty() {
+            let computed_bls_public_key = self.bls_public_key(&keys.bls_private_key)?;
+            let expected_bls_public_key = keys.bls_public_key.strip_prefix("0x").unwrap_or(&keys.bls_public_key);
+            if computed_bls_public_key[2..] != *expected_bls_public_key {
+                return Ok(false);
+            }
+        }
+
         Ok(true)
     }
 
@@ -218,6 +242,40 @@ impl KeyManager {
         Ok(result)
     }
 
+    /// Sign a 32-byte `digest` as is, such as an EIP-712 hash, returning `r || s || v` with
+    /// `v` of 27 or 28 as Solidity's `ecrecover` expects
+    pub fn sign_digest(&self, digest: &[u8; 32], private_key: &str) -> Result<Vec<u8>> {
+        let private_key = private_key.strip_prefix("0x").unwrap_or(private_key);
+        let secret_key = SecretKey::from_slice(&hex::decode(private_key)?)?;
+        let message = secp256k1::Message::from_digest_slice(digest)?;
+
+        let signature = self.secp.sign_ecdsa_recoverable(&message, &secret_key);
+        let (recovery_id, signature_bytes) = signature.serialize_compact();
+
+        let mut result = signature_bytes.to_vec();
+        result.push(27 + recovery_id.to_i32() as u8);
+        Ok(result)
+    }
+
+    /// Compressed BLS public key of `private_key`, hex with `0x`
+    pub fn bls_public_key(&self, private_key: &str) -> Result<String> {
+        Ok(format!("0x{}", hex::encode(bls_secret_key(private_key)?.sk_to_pk().compress())))
+    }
+
+    /// BLS signature over `message`, 96 bytes compressed; signatures of one message by several
+    /// keys aggregate into one, see `verify_bls_aggregate`
+    pub fn sign_bls(&self, message: &[u8], private_key: &str) -> Result<Vec<u8>> {
+        let secret_key = bls_secret_key(private_key)?;
+        Ok(secret_key.sign(message, BLS_SIGNATURE_DST, &[]).compress().to_vec())
+    }
+
+    /// Proof that the holder of `private_key` owns its public key, published with it: a rogue
+    /// key derived from others' keys has none, so it can't forge their part of an aggregate
+    pub fn prove_bls_possession(&self, private_key: &str) -> Result<Vec<u8>> {
+        let secret_key = bls_secret_key(private_key)?;
+        Ok(secret_key.sign(&secret_key.sk_to_pk().compress(), BLS_POP_DST, &[]).compress().to_vec())
+    }
+
     pub fn encrypt_data(&self, data: &[u8], public_key: &str) -> Result<Vec<u8>> {
         use aes_gcm::{
             aead::{Aead, AeadCore, KeyInit, OsRng},
@@ -268,6 +326,51 @@ impl KeyManager {
     }
 }
 
+/// Whether `proof` proves possession of the BLS key `public_key`; check it before counting the
+/// key toward any aggregate
+pub fn verify_bls_possession(public_key: &str, proof: &[u8]) -> Result<bool> {
+    let public_key = bls_public_key(public_key)?;
+    let proof = BlsSignature::sig_validate(proof, true).map_err(bls_error)?;
+    Ok(proof.verify(true, &public_key.compress(), BLS_POP_DST, &[], &public_key, false) == BLST_ERROR::BLST_SUCCESS)
+}
+
+/// Combine BLS signatures, each over the same message, into one of the same size
+pub fn aggregate_bls_signatures(signatures: &[Vec<u8>]) -> Result<Vec<u8>> {
+    let signatures = signatures.iter()
+        .map(|signature| BlsSignature::sig_validate(signature, true).map_err(bls_error))
+        .collect::<Result<Vec<_>>>()?;
+    let signatures: Vec<&BlsSignature> = signatures.iter().collect();
+    let aggregate = AggregateSignature::aggregate(&signatures, false).map_err(bls_error)?;
+    Ok(aggregate.to_signature().compress().to_vec())
+}
+
+/// Whether `signature` aggregates a signature over `message` by every one of `public_keys`,
+/// whose proofs of possession were already checked. A single key checks a plain signature.
+pub fn verify_bls_aggregate(message: &[u8], signature: &[u8], public_keys: &[String]) -> Result<bool> {
+    if public_keys.is_empty() {
+        return Ok(false);
+    }
+    let public_keys = public_keys.iter().map(|public_key| bls_public_key(public_key)).collect::<Result<Vec<_>>>()?;
+    let public_keys: Vec<&BlsPublicKey> = public_keys.iter().collect();
+    let signature = BlsSignature::sig_validate(signature, true).map_err(bls_error)?;
+    Ok(signature.fast_aggregate_verify(true, message, BLS_SIGNATURE_DST, &public_keys) == BLST_ERROR::BLST_SUCCESS)
+}
+
+fn bls_secret_key(private_key: &str) -> Result<BlsSecretKey> {
+    let private_key = private_key.strip_prefix("0x").unwrap_or(private_key);
+    BlsSecretKey::from_bytes(&hex::decode(private_key)?).map_err(bls_error)
+}
+
+/// Public key checked to be in the group and not the identity, which would sign anything
+fn bls_public_key(public_key: &str) -> Result<BlsPublicKey> {
+    let public_key = public_key.strip_prefix("0x").unwrap_or(public_key);
+    BlsPublicKey::key_validate(&hex::decode(public_key)?).map_err(bls_error)
+}
+
+fn bls_error(error: BLST_ERROR) -> anyhow::Error {
+    anyhow::anyhow!("invalid BLS key or signature: {:?}", error)
+}
+
 impl Default for KeyManager {
     fn default() -> Self {
         Self::new()
@@ -289,8 +392,12 @@ mod tests {
         
         assert!(keys.ethereum_private_key.starts_with("0x"));
         assert!(keys.ethereum_address.starts_with("0x"));
-        assert!(!keys.bls_private_key.is_empty());
+        assert_eq!(keys.bls_public_key.len(), 2 + 96);
         assert!(!keys.encryption_private_key.is_empty());
+
+        let public_keys: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(output_path.join("public_keys.json")).unwrap()).unwrap();
+        let proof = hex::decode(public_keys["bls_proof_of_possession"].as_str().unwrap().trim_start_matches("0x")).unwrap();
+        assert!(verify_bls_possession(&keys.bls_public_key, &proof).unwrap());
     }
 
     #[tokio::test]
@@ -328,6 +435,62 @@ mod tests {
         assert_eq!(signature.len(), 65); // 64 bytes signature + 1 byte recovery id
     }
 
+    #[test]
+    fn test_digest_signature_recovers_signer() {
+        use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+
+        let key_manager = KeyManager::new();
+        let (private_key, _, address) = key_manager.generate_ethereum_keys().unwrap();
+        let digest = [7u8; 32];
+
+        let signature = key_manager.sign_digest(&digest, &private_key).unwrap();
+        let recovery_id = RecoveryId::from_i32(signature[64] as i32 - 27).unwrap();
+        let recoverable = RecoverableSignature::from_compact(&signature[..64], recovery_id).unwrap();
+        let message = secp256k1::Message::from_digest_slice(&digest).unwrap();
+        let signer = Secp256k1::new().recover_ecdsa(&message, &recoverable).unwrap();
+
+        assert_eq!(format!("0x{}", hex::encode(key_manager.public_key_to_address(&signer).unwrap())), address);
+    }
+
+    #[test]
+    fn test_bls_signatures_aggregate() {
+        let key_manager = KeyManager::new();
+        let committee: Vec<(String, String)> = (0..3).map(|_| key_manager.generate_bls_keys().unwrap()).collect();
+        let public_keys: Vec<String> = committee.iter().map(|(_, public_key)| public_key.clone()).collect();
+        let message = b"task result digest";
+
+        let signatures: Vec<Vec<u8>> = committee.iter()
+            .map(|(private_key, _)| key_manager.sign_bls(message, private_key).unwrap())
+            .collect();
+        assert!(verify_bls_aggregate(message, &signatures[0], &public_keys[..1]).unwrap());
+
+        let aggregate = aggregate_bls_signatures(&signatures).unwrap();
+        assert_eq!(aggregate.len(), 96);
+        assert!(verify_bls_aggregate(message, &aggregate, &public_keys).unwrap());
+        // Every signer counted has to have signed, and signed this message
+        assert!(!verify_bls_aggregate(message, &aggregate, &public_keys[..2]).unwrap());
+        assert!(!verify_bls_aggregate(b"another result", &aggregate, &public_keys).unwrap());
+        let partial = aggregate_bls_signatures(&signatures[..2]).unwrap();
+        assert!(!verify_bls_aggregate(message, &partial, &public_keys).unwrap());
+        assert!(!verify_bls_aggregate(message, &aggregate, &[]).unwrap());
+    }
+
+    #[test]
+    fn test_bls_proof_of_possession() {
+        let key_manager = KeyManager::new();
+        let (private_key, public_key) = key_manager.generate_bls_keys().unwrap();
+        let (_, other_public_key) = key_manager.generate_bls_keys().unwrap();
+
+        let proof = key_manager.prove_bls_possession(&private_key).unwrap();
+        assert!(verify_bls_possession(&public_key, &proof).unwrap());
+        assert!(!verify_bls_possession(&other_public_key, &proof).unwrap());
+        // A signature over the key bytes under the signing ciphersuite is no proof
+        let signature = key_manager.sign_bls(&hex::decode(&public_key[2..]).unwrap(), &private_key).unwrap();
+        assert!(!verify_bls_possession(&public_key, &signature).unwrap());
+        // Nor is the identity a key
+        assert!(verify_bls_possession(&format!("0xc0{}", "00".repeat(47)), &proof).is_err());
+    }
+
     #[test]
     fn test_data_encryption() {
         let key_manager = KeyManager::new();
@@ -0,0 +1,241 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::secrets::Secret;
+use super::settings::{Profile, Settings};
+
+/// Environment variables that override a config field, by dotted path
+pub const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("ETHEREUM_RPC_URL", "ethereum.rpc_url"),
+    ("OPERATOR_ADDRESS", "ethereum.operator_address"),
+    ("OPERATOR_PRIVATE_KEY", "ethereum.private_key"),
+    ("SERVICE_MANAGER_ADDRESS", "ethereum.service_manager_address"),
+    ("LISTEN_PORT", "networking.listen_port"),
+    ("BOOTSTRAP_PEERS", "networking.bootstrap_peers"),
+    ("CIRCUIT_PATH", "proofs.circuit_path"),
+];
+
+/// Layer an effective config value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    Default,
+    Profile(Profile),
+    File(PathBuf),
+    Env(&'static str),
+    Cli,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Default => write!(f, "default"),
+            Origin::Profile(profile) => match profile.to_possible_value() {
+                Some(value) => write!(f, "profile {}", value.get_name()),
+                None => write!(f, "profile {:?}", profile),
+            },
+            Origin::File(path) => write!(f, "file {}", path.display()),
+            Origin::Env(var) => write!(f, "env {}", var),
+            Origin::Cli => write!(f, "cli --set"),
+        }
+    }
+}
+
+/// Effective settings plus the origin of every value.
+///
+/// Layers apply lowest first: built-in defaults < profile preset < config
+/// file < environment < CLI `--set key=value` flags.
+#[derive(Debug, Clone)]
+pub struct Layered {
+    pub settings: Settings,
+    pub origins: BTreeMap<String, Origin>,
+}
+
+impl Layered {
+    pub fn load(path: &Path, profile: Option<Profile>, overrides: &[String]) -> Result<Self> {
+        let defaults = serde_json::to_value(Settings::default())?;
+        let mut layers = Layers {
+            origins: leaves(&defaults).into_iter().map(|(path, _)| (path, Origin::Default)).collect(),
+            document: defaults,
+        };
+
+        let base = match profile {
+            Some(profile) => {
+                let preset = Settings::for_profile(profile);
+                let changed = changed_from(&serde_json::to_value(&preset)?, &layers.document);
+                layers.overlay(changed, Origin::Profile(profile));
+                preset
+            }
+            None => Settings::default(),
+        };
+
+        let file = Settings::read_migrated(path, &base)?;
+        layers.overlay(file, Origin::File(path.to_path_buf()));
+
+        for (var, field) in ENV_OVERRIDES {
+            if let Ok(raw) = std::env::var(var) {
+                let value = typed(layers.get(field), &raw)?;
+                layers.overlay(nested(field, value), Origin::Env(var));
+            }
+        }
+
+        for assignment in overrides {
+            let (field, raw) = assignment.split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Expected key=value, got {:?}", assignment))?;
+            let value = typed(layers.get(field), raw)?;
+            layers.overlay(nested(field, value), Origin::Cli);
+        }
+
+        let mut settings: Settings = serde_json::from_value(layers.document)?;
+        settings.resolve_secrets()?;
+        Ok(Self { settings, origins: layers.origins })
+    }
+
+    /// Every effective value by dotted path, private keys redacted unless they are references
+    pub fn values(&self) -> Result<Vec<(String, Value)>> {
+        let document = serde_json::to_value(&self.settings)?;
+        Ok(leaves(&document).into_iter()
+            .map(|(path, value)| {
+                let literal_key = path.ends_with("private_key")
+                    && value.as_str().is_some_and(|key| !key.is_empty() && !Secret::new(key).is_reference());
+                let value = if literal_key { Value::from("<redacted>") } else { value.clone() };
+                (path, value)
+            })
+            .collect())
+    }
+}
+
+struct Layers {
+    document: Value,
+    origins: BTreeMap<String, Origin>,
+}
+
+impl Layers {
+    fn get(&self, path: &str) -> Option<&Value> {
+        path.split('.').try_fold(&self.document, |value, key| value.get(key))
+    }
+
+    fn overlay(&mut self, layer: Value, origin: Origin) {
+        for (path, _) in leaves(&layer) {
+            self.origins.insert(path, origin.clone());
+        }
+        merge_values(&mut self.document, layer);
+    }
+}
+
+/// Overlay `overlay` onto `base`; tables merge key by key, anything else replaces
+fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Dotted paths of every scalar, list and empty table in `value`
+fn leaves(value: &Value) -> Vec<(String, &Value)> {
+    fn walk<'a>(prefix: String, value: &'a Value, out: &mut Vec<(String, &'a Value)>) {
+        match value {
+            Value::Object(fields) if !fields.is_empty() => {
+                for (key, field) in fields {
+                    let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                    walk(path, field, out);
+                }
+            }
+            _ => out.push((prefix, value)),
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(String::new(), value, &mut out);
+    out
+}
+
+/// The parts of `layer` that differ from `base`
+fn changed_from(layer: &Value, base: &Value) -> Value {
+    match (layer, base) {
+        (Value::Object(layer), Value::Object(base)) => Value::Object(
+            layer.iter()
+                .filter_map(|(key, value)| match base.get(key) {
+                    Some(existing) if existing == value => None,
+                    Some(existing @ Value::Object(_)) => Some((key.clone(), changed_from(value, existing))),
+                    _ => Some((key.clone(), value.clone())),
+                })
+                .collect(),
+        ),
+        _ => layer.clone(),
+    }
+}
+
+/// `value` placed at the dotted `path`
+fn nested(path: &str, value: Value) -> Value {
+    path.rsplit('.').fold(value, |value, key| {
+        Value::Object(std::iter::once((key.to_string(), value)).collect())
+    })
+}
+
+/// Parse a raw string as the type of the value it replaces; lists are comma-separated
+fn typed(current: Option<&Value>, raw: &str) -> Result<Value> {
+    let invalid = |kind: &str| anyhow::anyhow!("Expected {}, got {:?}", kind, raw);
+
+    Ok(match current {
+        Some(Value::String(_)) => Value::from(raw),
+        Some(Value::Bool(_)) => Value::from(raw.parse::<bool>().map_err(|_| invalid("true or false"))?),
+        Some(Value::Number(number)) if number.is_f64() => Value::from(raw.parse::<f64>().map_err(|_| invalid("a number"))?),
+        Some(Value::Number(_)) => Value::from(raw.parse::<i64>().map_err(|_| invalid("an integer"))?),
+        Some(Value::Array(_)) => Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(Value::from)
+                .collect(),
+        ),
+        // Unset options and new table entries
+        _ => serde_json::from_str(raw).unwrap_or_else(|_| Value::from(raw)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_layer_precedence_and_origins() -> Result<()> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("config.yaml");
+        std::fs::write(&file_path, "version: 1\nnetworking:\n  max_peers: 7\n  min_peers: 2\n")?;
+
+        let overrides = vec!["networking.min_peers=4".to_string(), "matching.algorithm=sell_price".to_string()];
+        let layered = Layered::load(&file_path, Some(Profile::Prod), &overrides)?;
+
+        assert_eq!(layered.settings.networking.max_peers, 7);
+        assert_eq!(layered.settings.networking.min_peers, 4);
+        assert_eq!(layered.settings.ethereum.confirmation_blocks, 12);
+        assert_eq!(layered.origins["networking.max_peers"], Origin::File(file_path.clone()));
+        assert_eq!(layered.origins["networking.min_peers"], Origin::Cli);
+        assert_eq!(layered.origins["ethereum.confirmation_blocks"], Origin::Profile(Profile::Prod));
+        assert_eq!(layered.origins["matching.max_slippage_bps"], Origin::Default);
+        Ok(())
+    }
+
+    #[test]
+    fn test_typed_values() -> Result<()> {
+        assert_eq!(typed(Some(&Value::from("x")), "0x12")?, Value::from("0x12"));
+        assert_eq!(typed(Some(&Value::from(9000)), "9100")?, Value::from(9100u64));
+        assert_eq!(typed(Some(&Value::Array(vec![])), "a:1, b:2")?, serde_json::json!(["a:1", "b:2"]));
+        assert!(typed(Some(&Value::from(true)), "maybe").is_err());
+        Ok(())
+    }
+}
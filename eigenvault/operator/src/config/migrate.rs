@@ -0,0 +1,156 @@
+use anyhow::Result;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+/// A single forward migration of the config file schema.
+///
+/// `apply` edits the raw document; `defaults` is the serialized base layer
+/// (the built-in defaults or the selected profile) for filling new fields.
+pub struct ConfigMigration {
+    pub version: u32,
+    pub description: &'static str,
+    pub apply: fn(&mut Value, &Value) -> Result<()>,
+}
+
+/// Every config schema version this binary knows about, in ascending order
+pub const CONFIG_MIGRATIONS: &[ConfigMigration] = &[
+    ConfigMigration {
+        version: 1,
+        description: "Versioned schema: fill required fields missing from older files",
+        apply: fill_missing,
+    },
+    ConfigMigration {
+        version: 2,
+        description: "Token decimals per pool: fill matching.base_decimals and matching.quote_decimals",
+        apply: fill_missing,
+    },
+];
+
+/// Config schema version written by this binary
+pub const CONFIG_VERSION: u32 = 2;
+
+/// Schema version of a raw config document; files predating versioning are v0
+pub fn document_version(document: &Value) -> Result<u32> {
+    match document.get("version") {
+        Some(version) => version.as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| anyhow::anyhow!("Invalid config version: {}", version)),
+        None => Ok(0),
+    }
+}
+
+/// Bring a raw config document up to the latest version in `migrations`.
+///
+/// Returns the version the document was at if anything changed.
+pub fn migrate(document: &mut Value, defaults: &Value, migrations: &[ConfigMigration]) -> Result<Option<u32>> {
+    let target = migrations.last().map(|migration| migration.version).unwrap_or(0);
+    let current = document_version(document)?;
+
+    if current > target {
+        return Err(anyhow::anyhow!(
+            "Config version {} is newer than this binary supports ({}); refusing to downgrade",
+            current, target
+        ));
+    }
+
+    let pending: Vec<&ConfigMigration> = migrations.iter()
+        .filter(|migration| migration.version > current)
+        .collect();
+
+    if pending.is_empty() {
+        return Ok(None);
+    }
+
+    for migration in pending {
+        info!("Migrating config to version {}: {}", migration.version, migration.description);
+        (migration.apply)(document, defaults)?;
+    }
+
+    let fields = document.as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Config must be a table"))?;
+    fields.insert("version".to_string(), Value::from(target));
+
+    Ok(Some(current))
+}
+
+/// Keep the pre-migration file next to it as `<file>.v<version>.bak`
+pub fn backup(path: &Path, version: u32) -> Result<PathBuf> {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".v{}.bak", version));
+    let backup = PathBuf::from(backup);
+
+    std::fs::copy(path, &backup)?;
+    Ok(backup)
+}
+
+/// Back up `path` and write the migrated document in its place. A read-only
+/// config is not an error; the migration is simply redone on every start.
+pub fn persist(path: &Path, from: u32, contents: &str) {
+    let result = backup(path, from).and_then(|backup| {
+        std::fs::write(path, contents)?;
+        Ok(backup)
+    });
+
+    match result {
+        Ok(backup) => info!("Migrated {:?} from config version {}; previous file kept at {:?}", path, from, backup),
+        Err(e) => warn!("Config {:?} migrated in memory only, could not rewrite it: {:?}", path, e),
+    }
+}
+
+/// v1, v2: copy every key missing from the document out of `defaults`. Lists
+/// are left alone and unset optional values are not written out.
+fn fill_missing(document: &mut Value, defaults: &Value) -> Result<()> {
+    if let (Value::Object(document), Value::Object(defaults)) = (document, defaults) {
+        for (key, default) in defaults {
+            match document.get_mut(key) {
+                Some(existing) => fill_missing(existing, default)?,
+                // Filled field by field, so its unset options stay out too
+                None if default.is_object() => {
+                    let mut table = Value::Object(Default::default());
+                    fill_missing(&mut table, default)?;
+                    document.insert(key.clone(), table);
+                }
+                None if !default.is_null() => {
+                    document.insert(key.clone(), default.clone());
+                }
+                None => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_unversioned_document_is_filled() -> Result<()> {
+        let mut document = json!({ "matching": { "max_pending_orders": 5 } });
+        let defaults = json!({
+            "version": 1,
+            "matching": { "max_pending_orders": 1000, "matching_interval_ms": 100 },
+            "api": { "admin_token": null },
+        });
+
+        assert_eq!(migrate(&mut document, &defaults, CONFIG_MIGRATIONS)?, Some(0));
+        assert_eq!(document, json!({
+            "version": CONFIG_VERSION,
+            "matching": { "max_pending_orders": 5, "matching_interval_ms": 100 },
+            "api": {},
+        }));
+
+        // Already current: nothing to do
+        assert_eq!(migrate(&mut document, &defaults, CONFIG_MIGRATIONS)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let mut document = json!({ "version": CONFIG_VERSION + 1 });
+        assert!(migrate(&mut document, &json!({}), CONFIG_MIGRATIONS).is_err());
+        assert_eq!(CONFIG_MIGRATIONS.last().map(|migration| migration.version), Some(CONFIG_VERSION));
+    }
+}
@@ -3,10 +3,17 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 pub mod keys;
+pub mod layers;
+pub mod migrate;
+pub mod runtime;
+pub mod secrets;
 pub mod settings;
 
 pub use keys::KeyManager;
-pub use settings::{Settings, EthereumConfig, MatchingConfig, NetworkingConfig, ProofConfig};
+pub use layers::Layered;
+pub use runtime::{ConfigPatch, RuntimeSettings};
+pub use secrets::Secret;
+pub use settings::{Settings, ConfigFormat, Profile, EthereumConfig, ChainCacheConfig, TransactionConfig, EigenLayerConfig, MatchingConfig, MatchingAlgorithm, PoolOverrides, RouteConfig, PoolFilterConfig, MetadataConfig, OrderKeyConfig, NotificationConfig, ScreeningConfig, ScreeningPlugin, NetworkingConfig, ProofConfig, EventBusConfig, EventBusBackend, StorageConfig, MetricsConfig, ApiConfig, ApiKeyConfig, ApiRole, TlsConfig, TelemetryConfig, LoggingConfig, LogFormat, LogRotation, AuditConfig, AccountingConfig, StakeTopUpConfig, ReregistrationConfig, QuorumConfig, CommitteeMember, SlashingConfig, SchedulerConfig, RiskConfig, HaConfig, LatencyConfig, GrpcConfig, IpcConfig, PublicStatsConfig, ShutdownConfig, SupervisorConfig, RuntimeConfig, ProofTuningConfig, BackpressureConfig, ChaosConfig, IdentityConfig, PRIMARY_IDENTITY};
 
 // Re-export unified config
 pub type Config = Settings;
@@ -54,7 +61,7 @@ mod tests {
         
         // Set valid addresses
         config.ethereum.operator_address = "0x1234567890123456789012345678901234567890".to_string();
-        config.ethereum.private_key = "0x1234567890123456789012345678901234567890123456789012345678901234".to_string();
+        config.ethereum.private_key = "0x1234567890123456789012345678901234567890123456789012345678901234".into();
         
         assert!(config.validate().is_ok());
         Ok(())
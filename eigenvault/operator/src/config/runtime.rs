@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{PoolOverrides, RouteConfig, ScreeningConfig, Settings};
+
+/// Settings that can change while the operator runs; changes are not written back to the config file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeSettings {
+    pub matching_interval_ms: u64,
+    pub price_tolerance_bps: u64,
+    pub max_slippage_bps: u64,
+    pub max_pending_orders: usize,
+    pub order_timeout_seconds: u64,
+    pub min_peers: usize,
+    pub max_peers: usize,
+    pub pools: BTreeMap<String, PoolOverrides>,
+    pub routes: BTreeMap<String, RouteConfig>,
+    pub screening: ScreeningConfig,
+}
+
+impl From<&Settings> for RuntimeSettings {
+    fn from(settings: &Settings) -> Self {
+        Self {
+            matching_interval_ms: settings.matching.matching_interval_ms,
+            price_tolerance_bps: settings.matching.price_tolerance_bps,
+            max_slippage_bps: settings.matching.max_slippage_bps,
+            max_pending_orders: settings.matching.max_pending_orders,
+            order_timeout_seconds: settings.matching.order_timeout_seconds,
+            min_peers: settings.networking.min_peers,
+            max_peers: settings.networking.max_peers,
+            pools: settings.pools.clone(),
+            routes: settings.routes.clone(),
+            screening: settings.screening.clone(),
+        }
+    }
+}
+
+/// Partial update of `RuntimeSettings`; absent fields keep their value
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigPatch {
+    pub matching_interval_ms: Option<u64>,
+    pub price_tolerance_bps: Option<u64>,
+    pub max_slippage_bps: Option<u64>,
+    pub max_pending_orders: Option<usize>,
+    pub order_timeout_seconds: Option<u64>,
+    pub min_peers: Option<usize>,
+    pub max_peers: Option<usize>,
+    /// Replaces every pool override
+    pub pools: Option<BTreeMap<String, PoolOverrides>>,
+    /// Replaces every cross-pool route
+    pub routes: Option<BTreeMap<String, RouteConfig>>,
+    /// Replaces the whole screening section
+    pub screening: Option<ScreeningConfig>,
+}
+
+impl ConfigPatch {
+    pub fn apply(&self, settings: &mut Settings) {
+        let matching = &mut settings.matching;
+        if let Some(value) = self.matching_interval_ms {
+            matching.matching_interval_ms = value;
+        }
+        if let Some(value) = self.price_tolerance_bps {
+            matching.price_tolerance_bps = value;
+        }
+        if let Some(value) = self.max_slippage_bps {
+            matching.max_slippage_bps = value;
+        }
+        if let Some(value) = self.max_pending_orders {
+            matching.max_pending_orders = value;
+        }
+        if let Some(value) = self.order_timeout_seconds {
+            matching.order_timeout_seconds = value;
+        }
+
+        let networking = &mut settings.networking;
+        if let Some(value) = self.min_peers {
+            networking.min_peers = value;
+        }
+        if let Some(value) = self.max_peers {
+            networking.max_peers = value;
+        }
+
+        if let Some(pools) = &self.pools {
+            settings.pools = pools.clone();
+        }
+        if let Some(routes) = &self.routes {
+            settings.routes = routes.clone();
+        }
+        if let Some(screening) = &self.screening {
+            settings.screening = screening.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patch_only_changes_given_fields() {
+        let mut settings = Settings::default();
+        let before = RuntimeSettings::from(&settings);
+
+        let patch: ConfigPatch = serde_json::from_str(r#"{"matching_interval_ms": 250, "max_peers": 40}"#).unwrap();
+        patch.apply(&mut settings);
+
+        let after = RuntimeSettings::from(&settings);
+        assert_eq!(after.matching_interval_ms, 250);
+        assert_eq!(after.max_peers, 40);
+        assert_eq!(after.min_peers, before.min_peers);
+        assert_eq!(after.price_tolerance_bps, before.price_tolerance_bps);
+    }
+
+    #[test]
+    fn test_patch_rejects_unknown_fields() {
+        assert!(serde_json::from_str::<ConfigPatch>(r#"{"private_key": "0x00"}"#).is_err());
+    }
+}
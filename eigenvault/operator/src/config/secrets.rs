@@ -0,0 +1,129 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Environment variable holding the password for `keystore:` references
+pub const KEYSTORE_PASSWORD_ENV: &str = "EIGENVAULT_KEYSTORE_PASSWORD";
+
+const REDACTED: &str = "<redacted>";
+
+/// Secret config value, written either as a literal or as a reference:
+/// `env:VAR`, `file:/path` or `keystore:/path/to/keystore.json`.
+///
+/// Serializes back to what was written in the config, so a resolved value is
+/// never persisted, and never shows its value in `Debug` output.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret {
+    source: String,
+    resolved: Option<String>,
+}
+
+impl Secret {
+    pub fn new(source: impl Into<String>) -> Self {
+        Self { source: source.into(), resolved: None }
+    }
+
+    /// The secret value; a reference that hasn't been resolved yet yields its own text
+    pub fn expose(&self) -> &str {
+        self.resolved.as_deref().unwrap_or(&self.source)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.expose().is_empty()
+    }
+
+    pub fn is_reference(&self) -> bool {
+        ["env:", "file:", "keystore:"].iter().any(|prefix| self.source.starts_with(prefix))
+    }
+
+    /// Look up the value behind a reference; literals are left as they are
+    pub fn resolve(&mut self) -> Result<()> {
+        if let Some(var) = self.source.strip_prefix("env:") {
+            let value = std::env::var(var)
+                .with_context(|| format!("Environment variable {} is not set", var))?;
+            self.resolved = Some(value.trim().to_string());
+        } else if let Some(path) = self.source.strip_prefix("file:") {
+            let value = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read secret file {}", path))?;
+            self.resolved = Some(value.trim().to_string());
+        } else if let Some(path) = self.source.strip_prefix("keystore:") {
+            let password = std::env::var(KEYSTORE_PASSWORD_ENV)
+                .with_context(|| format!("{} must be set to unlock {}", KEYSTORE_PASSWORD_ENV, path))?;
+            let key = eth_keystore::decrypt_key(path, password)
+                .map_err(|e| anyhow::anyhow!("Failed to decrypt keystore {}: {}", path, e))?;
+            self.resolved = Some(format!("0x{}", hex::encode(key)));
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(source: &str) -> Self {
+        Self::new(source)
+    }
+}
+
+impl From<String> for Secret {
+    fn from(source: String) -> Self {
+        Self::new(source)
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_reference() {
+            write!(f, "Secret({:?})", self.source)
+        } else {
+            write!(f, "Secret({})", REDACTED)
+        }
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_env_reference() -> Result<()> {
+        std::env::set_var("EIGENVAULT_TEST_SECRET", "0xabc\n");
+        let mut secret = Secret::new("env:EIGENVAULT_TEST_SECRET");
+        secret.resolve()?;
+
+        assert_eq!(secret.expose(), "0xabc");
+        // The reference, not the value, is what gets written back
+        assert_eq!(serde_json::to_string(&secret)?, "\"env:EIGENVAULT_TEST_SECRET\"");
+        Ok(())
+    }
+
+    #[test]
+    fn test_file_reference() -> Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        std::fs::write(file.path(), "0xdef\n")?;
+        let mut secret = Secret::new(format!("file:{}", file.path().display()));
+        secret.resolve()?;
+
+        assert_eq!(secret.expose(), "0xdef");
+        assert!(Secret::new("env:EIGENVAULT_TEST_MISSING").resolve().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_literal_is_redacted_in_debug() {
+        let secret = Secret::new("0x1234");
+
+        assert_eq!(format!("{:?}", secret), "Secret(<redacted>)");
+        assert_eq!(secret.expose(), "0x1234");
+    }
+}
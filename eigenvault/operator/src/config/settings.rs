@@ -1,144 +1,1376 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
+use tracing::warn;
+
+use super::keys::verify_bls_possession;
+use super::layers::Layered;
+use super::migrate::{self, CONFIG_MIGRATIONS, CONFIG_VERSION};
+use super::secrets::Secret;
+use crate::matching::{routing, Decimal};
+
+/// On-disk config format, chosen by file extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Yaml,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// YAML for `.yaml`/`.yml`, TOML otherwise
+    pub fn of(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("yaml") | Some("yml") => ConfigFormat::Yaml,
+            _ => ConfigFormat::Toml,
+        }
+    }
+}
+
+/// Preset used as the base layer under the config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Profile {
+    Dev,
+    Testnet,
+    Prod,
+}
+
+/// Placeholder key written by `init`
+const ZERO_PRIVATE_KEY: &str = "0x0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Name of the identity configured in the `ethereum` section
+pub const PRIMARY_IDENTITY: &str = "primary";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
+    /// Config schema version; older files are migrated on load
+    #[serde(default)]
+    pub version: u32,
     pub ethereum: EthereumConfig,
     pub matching: MatchingConfig,
     pub networking: NetworkingConfig,
     pub proofs: ProofConfig,
+    #[serde(default)]
+    pub event_bus: EventBusConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub api: ApiConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
+    #[serde(default)]
+    pub latency: LatencyConfig,
+    #[serde(default)]
+    pub grpc: GrpcConfig,
+    #[serde(default)]
+    pub ipc: IpcConfig,
+    #[serde(default)]
+    pub public_stats: PublicStatsConfig,
+    #[serde(default)]
+    pub shutdown: ShutdownConfig,
+    #[serde(default)]
+    pub supervisor: SupervisorConfig,
+    #[serde(default)]
+    pub runtime: RuntimeConfig,
+    #[serde(default)]
+    pub proof_tuning: ProofTuningConfig,
+    #[serde(default)]
+    pub backpressure: BackpressureConfig,
+    #[serde(default)]
+    pub chaos: ChaosConfig,
+    /// Matching overrides by pool key, e.g. `[pools."ETH_USDC_3000"]`
+    #[serde(default)]
+    pub pools: BTreeMap<String, PoolOverrides>,
+    /// Cross-pool routes by pair, e.g. `[routes."ETH_USDC"]`; used while `matching.enable_cross_pool_matching` is set
+    #[serde(default)]
+    pub routes: BTreeMap<String, RouteConfig>,
+    #[serde(default)]
+    pub pool_filter: PoolFilterConfig,
+    #[serde(default)]
+    pub metadata: MetadataConfig,
+    #[serde(default)]
+    pub order_key: OrderKeyConfig,
+    #[serde(default)]
+    pub notifications: NotificationConfig,
+    #[serde(default)]
+    pub screening: ScreeningConfig,
+    #[serde(default)]
+    pub accounting: AccountingConfig,
+    #[serde(default)]
+    pub stake_top_up: StakeTopUpConfig,
+    #[serde(default)]
+    pub reregistration: ReregistrationConfig,
+    #[serde(default)]
+    pub quorum: QuorumConfig,
+    #[serde(default)]
+    pub slashing: SlashingConfig,
+    #[serde(default)]
+    pub scheduler: SchedulerConfig,
+    #[serde(default)]
+    pub risk: RiskConfig,
+    #[serde(default)]
+    pub ha: HaConfig,
+    /// Further registrations served by this process besides `ethereum.operator_address`
+    #[serde(default)]
+    pub identities: Vec<IdentityConfig>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EthereumConfig {
     pub rpc_url: String,
     pub operator_address: String,
-    pub private_key: String,
+    pub private_key: Secret,
+    /// BLS key matching results are signed with, so the committee's signatures on a result
+    /// aggregate into one; results carry only the ECDSA signature when unset
+    #[serde(default)]
+    pub bls_private_key: Option<Secret>,
     pub service_manager_address: String,
     pub eigenvault_hook_address: String,
     pub order_vault_address: String,
     pub gas_limit: u64,
     pub gas_price: u64,
     pub confirmation_blocks: u64,
+    /// Committee key document `submit-order` encrypts orders with
+    #[serde(default)]
+    pub committee_key_url: Option<String>,
+    /// Chain the RPC must report at startup; any chain is accepted when unset
+    #[serde(default)]
+    pub expected_chain_id: Option<u64>,
+    #[serde(default)]
+    pub cache: ChainCacheConfig,
+    #[serde(default)]
+    pub transactions: TransactionConfig,
+    #[serde(default)]
+    pub eigenlayer: EigenLayerConfig,
+}
+
+/// How long chain reads are served from memory before they are read again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChainCacheConfig {
+    pub enabled: bool,
+    /// Operator stakes and the AVS minimum stake
+    pub stake_ttl_seconds: u64,
+    /// Task details by task ID
+    pub task_ttl_seconds: u64,
+}
+
+/// How signed transactions are priced and replaced while they wait to be mined
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TransactionConfig {
+    /// Resend with a higher gas price once a transaction has been pending this long
+    pub replace_after_seconds: u64,
+    /// Gas price increase per replacement; nodes refuse replacements below 10
+    pub gas_bump_percent: u64,
+    pub max_replacements: u32,
+    /// Gas price replacements never go past, in wei
+    pub max_gas_price: u64,
+    /// Give up on a transaction not confirmed within this long
+    pub confirm_timeout_seconds: u64,
+}
+
+/// EigenLayer contracts the operator registers with the AVS through
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EigenLayerConfig {
+    /// AVSDirectory that computes the registration digest operators sign
+    pub avs_directory: String,
+    /// ECDSAStakeRegistry of the EigenVault AVS that registrations are submitted to
+    pub stake_registry: String,
+    /// DelegationManager whose withdrawal delay `deregister` waits out
+    pub delegation_manager: String,
+    /// How long a registration signature stays valid
+    pub registration_expiry_seconds: u64,
+}
+
+/// Operator identity sharing the P2P and matching infrastructure; RPC and contracts come from `ethereum`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentityConfig {
+    pub name: String,
+    pub operator_address: String,
+    pub private_key: Secret,
+    #[serde(default)]
+    pub bls_private_key: Option<Secret>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchingConfig {
+    pub max_pending_orders: usize,
+    pub matching_interval_ms: u64,
+    pub price_tolerance_bps: u64,
+    pub max_slippage_bps: u64,
+    pub order_timeout_seconds: u64,
+    pub enable_cross_pool_matching: bool,
+    /// Orders below this amount are left unmatched
+    #[serde(default)]
+    pub min_order_size: Decimal,
+    /// Fills below this amount are not matched, so no proof is spent on dust
+    #[serde(default)]
+    pub min_fill_amount: Decimal,
+    /// Decimals of the pool's base token; orders with finer amounts are rejected
+    pub base_decimals: u8,
+    /// Decimals of the pool's quote token; orders with finer prices are rejected
+    pub quote_decimals: u8,
+    #[serde(default)]
+    pub algorithm: MatchingAlgorithm,
+    /// Reject orders without an EIP-712 signature from their trader, as by default; signed orders
+    /// are always verified
+    #[serde(default = "MatchingConfig::signatures_required")]
+    pub require_trader_signatures: bool,
+    /// Most a batch close is randomly delayed past the start of its window; 0 matches on the interval
+    #[serde(default)]
+    pub batch_jitter_ms: u64,
+    /// Seed shared by the committee the jitter is drawn from, so every member closes the same batches
+    #[serde(default)]
+    pub batch_jitter_seed: Secret,
+    /// Most recent matches kept for stats and the status API
+    pub recent_matches_limit: usize,
+    /// How long a match is kept among the recent ones
+    pub recent_matches_ttl_seconds: u64,
+}
+
+/// How the execution price of a match is chosen
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchingAlgorithm {
+    /// Halfway between the buy and sell limit prices
+    #[default]
+    Midpoint,
+    /// The buyer's limit price
+    BuyPrice,
+    /// The seller's limit price
+    SellPrice,
+}
+
+/// Per-pool matching settings; unset fields inherit from `MatchingConfig`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PoolOverrides {
+    pub matching_interval_ms: Option<u64>,
+    pub price_tolerance_bps: Option<u64>,
+    pub max_slippage_bps: Option<u64>,
+    pub min_order_size: Option<Decimal>,
+    pub min_fill_amount: Option<Decimal>,
+    pub base_decimals: Option<u8>,
+    pub quote_decimals: Option<u8>,
+    pub algorithm: Option<MatchingAlgorithm>,
+}
+
+impl MatchingConfig {
+    fn signatures_required() -> bool {
+        true
+    }
+
+    /// Effective settings for a pool with the given overrides
+    pub fn for_pool(&self, overrides: Option<&PoolOverrides>) -> MatchingConfig {
+        let mut config = self.clone();
+        if let Some(overrides) = overrides {
+            if let Some(value) = overrides.matching_interval_ms {
+                config.matching_interval_ms = value;
+            }
+            if let Some(value) = overrides.price_tolerance_bps {
+                config.price_tolerance_bps = value;
+            }
+            if let Some(value) = overrides.max_slippage_bps {
+                config.max_slippage_bps = value;
+            }
+            if let Some(value) = overrides.min_order_size {
+                config.min_order_size = value;
+            }
+            if let Some(value) = overrides.min_fill_amount {
+                config.min_fill_amount = value;
+            }
+            if let Some(value) = overrides.base_decimals {
+                config.base_decimals = value;
+            }
+            if let Some(value) = overrides.quote_decimals {
+                config.quote_decimals = value;
+            }
+            if let Some(value) = overrides.algorithm {
+                config.algorithm = value;
+            }
+        }
+        config
+    }
+}
+
+/// Matching between the fee tiers of one pair; a pair without a route entry routes every tier it trades in
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RouteConfig {
+    /// Match the pair's pools only on their own
+    #[serde(default)]
+    pub disabled: bool,
+    /// Pools of the pair whose orders may match each other; empty routes all of them
+    #[serde(default)]
+    pub pools: Vec<String>,
+    /// Pool the route's matches settle in; the routed pool with the lowest fee tier if unset
+    #[serde(default)]
+    pub settlement_pool: Option<String>,
+    /// Matching settings for the route, over those of its settlement pool
+    #[serde(default)]
+    pub matching: PoolOverrides,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkingConfig {
+    pub listen_port: u16,
+    pub bootstrap_peers: Vec<String>,
+    pub min_peers: usize,
+    pub max_peers: usize,
+    pub connection_timeout_seconds: u64,
+    pub gossip_interval_ms: u64,
+    pub enable_encryption: bool,
+    /// Append every inbound peer message to this file, in the format `replay` reads
+    #[serde(default)]
+    pub record_path: Option<String>,
+    /// Most gossip messages remembered for deduplication
+    pub message_cache_size: usize,
+    /// How long a gossip message is remembered; a copy arriving later is handled as new
+    pub message_cache_ttl_seconds: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EventBusBackend {
+    Kafka,
+    Nats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventBusConfig {
+    pub enabled: bool,
+    pub backend: EventBusBackend,
+    /// Kafka bootstrap servers or NATS server URLs
+    pub servers: Vec<String>,
+    /// Prefix for topics/subjects, e.g. `eigenvault.order_received`
+    pub topic_prefix: String,
+    pub client_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageConfig {
+    pub data_dir: String,
+    /// fsync every checkpoint before acknowledging it
+    pub sync_writes: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    pub listen_address: String,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiConfig {
+    pub enabled: bool,
+    pub listen_address: String,
+    pub health_check_interval_seconds: u64,
+    /// Bearer token with the control role; protected routes are disabled when no key is configured
+    #[serde(default)]
+    pub admin_token: Option<String>,
+    #[serde(default)]
+    pub api_keys: Vec<ApiKeyConfig>,
+    /// Serve HTTPS instead of plain HTTP
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiRole {
+    /// Status, stats and event feeds
+    ReadOnly,
+    /// Everything, including pausing matching, peer management and proof submission
+    Control,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyConfig {
+    /// Shown in logs instead of the key
+    pub name: String,
+    pub key: String,
+    pub role: ApiRole,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// PEM encoded certificate chain
+    pub cert_path: String,
+    /// PEM encoded private key
+    pub key_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrpcConfig {
+    pub enabled: bool,
+    pub listen_address: String,
+    #[serde(default)]
+    pub tls: Option<TlsConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupervisorConfig {
+    /// Failures tolerated within the window before the operator shuts down
+    pub max_restarts: usize,
+    pub restart_window_seconds: u64,
+    /// Delay before the first restart, doubled for each further failure in the window
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+/// How matches found by the matching loop are batched into proofs, per pool
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofTuningConfig {
+    /// Adjust each pool's batch size and window from measured proof latency; off keeps the initial values
+    pub enabled: bool,
+    /// Share of the time left before a batch's earliest order deadline its proof may take
+    pub deadline_share: f64,
+    pub min_batch_size: usize,
+    pub max_batch_size: usize,
+    pub initial_batch_size: usize,
+    /// Longest a match waits for others to fill its batch
+    pub max_window_ms: u64,
+    pub initial_window_ms: u64,
+}
+
+/// Threads of the async runtime that networking, matching and the APIs run on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeConfig {
+    /// Async worker threads; 0 starts one per CPU core
+    pub worker_threads: usize,
+    /// Most threads kept for blocking work such as file and state store I/O
+    pub max_blocking_threads: usize,
+}
+
+/// Pools this operator serves; with an empty allowlist every pool not denied is served
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PoolFilterConfig {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+}
+
+impl PoolFilterConfig {
+    pub fn serves(&self, pool_key: &str) -> bool {
+        !self.deny.iter().any(|pool| pool == pool_key)
+            && (self.allow.is_empty() || self.allow.iter().any(|pool| pool == pool_key))
+    }
+
+    /// Serve `pool_key`, whether it was denied or left out of a non-empty allowlist
+    pub fn join(&mut self, pool_key: &str) {
+        self.deny.retain(|pool| pool != pool_key);
+        if !self.allow.is_empty() && !self.allow.iter().any(|pool| pool == pool_key) {
+            self.allow.push(pool_key.to_string());
+        }
+    }
+
+    /// Stop serving `pool_key`
+    pub fn leave(&mut self, pool_key: &str) -> Result<()> {
+        if self.allow.is_empty() {
+            if !self.deny.iter().any(|pool| pool == pool_key) {
+                self.deny.push(pool_key.to_string());
+            }
+            return Ok(());
+        }
+
+        // An empty allowlist serves every pool
+        if self.allow.len() == 1 && self.allow[0] == pool_key {
+            return Err(anyhow::anyhow!("{} is the only allowed pool; leaving it would serve every pool", pool_key));
+        }
+        self.allow.retain(|pool| pool != pool_key);
+        Ok(())
+    }
+
+    /// Handshake capabilities telling peers which pools to gossip to us
+    pub fn capabilities(&self) -> Vec<String> {
+        self.allow.iter().map(|pool| format!("pool:{}", pool))
+            .chain(self.deny.iter().map(|pool| format!("!pool:{}", pool)))
+            .collect()
+    }
+}
+
+/// Public operator profile, published with `publish-metadata`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetadataConfig {
+    /// Where the metadata JSON is hosted; this URI is what goes on chain
+    pub uri: String,
+    pub name: String,
+    pub website: String,
+    pub description: String,
+    /// PNG logo URL
+    pub logo: String,
+    pub twitter: String,
+    pub contact: String,
+}
+
+/// Committee order-encryption key, served to traders and published with `publish-order-key`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OrderKeyConfig {
+    /// Committee key file as written by `keygen`; empty to serve no key
+    pub path: String,
+    /// Bumped whenever the committee rotates its key
+    pub version: u64,
+    /// Where traders fetch the key, e.g. this operator's `/keys/orders`; this URI is what goes on chain
+    pub uri: String,
+}
+
+/// Webhooks traders register on the public listener to hear when their orders fill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    pub enabled: bool,
+    /// Registrations held at once across all orders
+    pub max_webhooks: usize,
+    pub timeout_ms: u64,
+    /// Deliveries tried per notification before it is dropped
+    pub max_attempts: u32,
+    /// Accept plain `http://` webhooks, for local testing
+    pub allow_http: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreeningPlugin {
+    /// Accept every order
+    #[default]
+    None,
+    /// Refuse orders from the addresses in `denylist` and `denylist_path`
+    Denylist,
+}
+
+/// Compliance screening of decrypted orders, for operators with regulatory obligations
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScreeningConfig {
+    pub plugin: ScreeningPlugin,
+    /// Trader addresses refused by the `denylist` plugin
+    pub denylist: Vec<String>,
+    /// File of further addresses, one per line with `#` comments; re-read whenever screening is updated
+    pub denylist_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackpressureConfig {
+    /// Pause event ingestion and gossip intake at this share of `matching.max_pending_orders`
+    pub pending_orders_high_water_percent: u64,
+    /// Pause intake while this many proofs are being generated
+    pub max_proofs_in_flight: i64,
+    /// How long intake waits before re-checking
+    pub pause_ms: u64,
+}
+
+/// Faults injected into gossip and chain RPC to exercise failure handling; only builds
+/// with the `chaos` feature act on it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChaosConfig {
+    pub enabled: bool,
+    /// Share of outbound gossip messages silently discarded
+    pub drop_percent: f64,
+    /// Delay before each outbound gossip message
+    pub latency_ms: u64,
+    /// Random extra delay, up to this much, on top of both latencies
+    pub jitter_ms: u64,
+    /// Share of inbound messages delivered after the one queued behind them
+    pub reorder_percent: f64,
+    /// Share of chain RPC calls that fail before reaching the node
+    pub rpc_error_percent: f64,
+    /// Delay before each chain RPC call
+    pub rpc_latency_ms: u64,
+}
+
+impl ChaosConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.enabled && !cfg!(feature = "chaos") {
+            return Err(anyhow::anyhow!("Fault injection requires a build with the `chaos` feature"));
+        }
+        for percent in [self.drop_percent, self.reorder_percent, self.rpc_error_percent] {
+            if !(0.0..=100.0).contains(&percent) {
+                return Err(anyhow::anyhow!("Fault rates must be between 0 and 100 percent"));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutdownConfig {
+    /// How long in-flight matching and proving may run after SIGINT/SIGTERM
+    pub drain_timeout_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicStatsConfig {
+    pub enabled: bool,
+    /// Separate from the admin API so only the stats endpoint is exposed
+    pub listen_address: String,
+    /// Per client IP
+    pub requests_per_minute: u32,
+    /// CORS origins; `*` allows any
+    pub allowed_origins: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcConfig {
+    pub enabled: bool,
+    /// Unix socket path, or named pipe name on Windows
+    pub endpoint: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    pub otlp_enabled: bool,
+    /// OTLP/gRPC collector endpoint
+    pub otlp_endpoint: String,
+    pub service_name: String,
+    /// Fraction of new traces to sample (0.0 - 1.0)
+    pub sample_ratio: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Hourly,
+    Daily,
+    Never,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    pub format: LogFormat,
+    pub file_enabled: bool,
+    pub directory: String,
+    pub file_name: String,
+    pub rotation: LogRotation,
+    /// Roll the file once it exceeds this size (0 disables size-based rotation)
+    pub max_file_size_mb: u64,
+    /// Number of rotated files to keep
+    pub max_files: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditConfig {
+    pub enabled: bool,
+    /// Append-only, hash-chained log of signed transactions
+    pub path: String,
+}
+
+/// Earnings ledger `earnings` reports from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccountingConfig {
+    pub enabled: bool,
+    /// Append-only JSON lines, one per confirmed task
+    pub path: String,
+    /// AVS reward expected for each task response, per registration that sent one
+    pub task_reward_gwei: u64,
+    /// Matching fee on matched notional, booked in each pool's quote token
+    pub fee_bps: u64,
+    /// Share of each match's fee returned to its buyer and seller, half each, at settlement
+    pub rebate_percent: u64,
+}
+
+/// Deposits from a funding account whenever operator stake falls below the AVS minimum plus a buffer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StakeTopUpConfig {
+    pub enabled: bool,
+    /// EigenLayer strategy deposits go into
+    pub strategy: String,
+    /// Account deposits are sent from; its shares must be delegated to the operator
+    pub funding_address: String,
+    pub funding_private_key: Secret,
+    /// Stake kept above the AVS minimum, in gwei; falling below it triggers a top-up back to it
+    pub buffer_gwei: u64,
+    /// Largest single deposit, in gwei
+    pub max_deposit_gwei: u64,
+    /// Most the running operator deposits in any 24 hours, in gwei
+    pub max_daily_gwei: u64,
+    pub check_interval_seconds: u64,
+    /// Leave deposits to `top-up-stake`, which asks first; when off the operator deposits unattended
+    pub confirm: bool,
+}
+
+/// What the operator does after the AVS registry deregisters one of its identities
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReregistrationConfig {
+    /// Re-register automatically once health checks pass; when off the deregistration is only alerted
+    pub enabled: bool,
+    /// Wait after a deregistration, and between attempts, before re-registering
+    pub cooldown_seconds: u64,
+}
+
+/// How matching results gossiped by the committee are weighed against registered stake
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuorumConfig {
+    /// How often stake weights are read from the stake registry
+    pub sync_interval_seconds: u64,
+    /// Share of the total stake, in basis points, that must sign the same result
+    pub threshold_bps: u64,
+    /// Operators whose results are counted even before the stake registry weighs them; results
+    /// of signers neither listed here nor registered with the AVS are dropped
+    pub committee: Vec<CommitteeMember>,
+}
+
+/// An operator of the matching committee
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitteeMember {
+    pub operator: String,
+    /// BLS public key the member signs results with, from its key file. An agreed result is
+    /// submitted only once the BLS signatures of its signers listed with a key aggregate.
+    #[serde(default)]
+    pub bls_public_key: Option<String>,
+    /// Proof of possession of `bls_public_key`, hex; required with it
+    #[serde(default)]
+    pub bls_possession_proof: Option<String>,
+}
+
+/// Response to `OperatorSlashed` events of the service manager
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlashingConfig {
+    /// How often the service manager's logs are checked for slashings
+    pub poll_interval_seconds: u64,
+    /// Decline new tasks after one of this operator's identities is slashed, until restarted
+    pub pause_on_self_slash: bool,
+    /// Reputation taken from each peer that gossiped results for a slashed operator
+    pub peer_reputation_penalty: f64,
+}
+
+/// Order in which accepted tasks are worked on and when they are given up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    /// Time left before a task's deadline for its response to be submitted; work still running
+    /// after `deadline - deadline_margin_seconds` is cancelled
+    pub deadline_margin_seconds: u64,
+}
+
+/// Limits on the slashing exposure of task responses that can still be challenged
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskConfig {
+    /// Decline new tasks while one more response would exceed the limit
+    pub enabled: bool,
+    /// Penalty one faulty task response risks, in gwei
+    pub penalty_gwei: u64,
+    /// Most exposure accepted, in gwei
+    pub max_exposure_gwei: u64,
+    /// Most exposure accepted as a share of the operator's stake
+    pub max_bond_percent: u64,
+    /// How long after a task's deadline its responses can still be challenged
+    pub challenge_window_seconds: u64,
+}
+
+/// Active/standby failover: the instance holding the lease processes tasks, the others wait
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HaConfig {
+    pub enabled: bool,
+    /// Names this instance in the lease; unique across the primary and its standbys
+    pub instance_id: String,
+    /// Lease file on storage every instance shares, as is `storage.data_dir`. Instances lock
+    /// `<lease_path>.lock` while they update it, so the storage must support file locks.
+    pub lease_path: String,
+    /// How long a lease lasts without a heartbeat before a standby takes over
+    pub lease_seconds: u64,
+    /// How often the primary renews its lease and a standby checks it
+    pub heartbeat_seconds: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyConfig {
+    /// End-to-end budget from order receipt to on-chain confirmation
+    pub budget_seconds: f64,
+    /// Quantile compared against the budget for the SLO alert
+    pub slo_quantile: f64,
+    /// Number of recently completed orders the quantiles are computed over
+    pub window_size: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofConfig {
+    pub circuit_path: String,
+    pub proving_key_path: String,
+    pub verification_key_path: String,
+    pub max_proof_size: usize,
+    pub proof_timeout_seconds: u64,
+    pub enable_batch_proving: bool,
+    /// Threads proofs are generated on, apart from the async runtime; 0 uses its blocking pool
+    pub proving_threads: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            ethereum: EthereumConfig::default(),
+            matching: MatchingConfig::default(),
+            networking: NetworkingConfig::default(),
+            proofs: ProofConfig::default(),
+            event_bus: EventBusConfig::default(),
+            storage: StorageConfig::default(),
+            metrics: MetricsConfig::default(),
+            api: ApiConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            logging: LoggingConfig::default(),
+            audit: AuditConfig::default(),
+            latency: LatencyConfig::default(),
+            grpc: GrpcConfig::default(),
+            ipc: IpcConfig::default(),
+            public_stats: PublicStatsConfig::default(),
+            shutdown: ShutdownConfig::default(),
+            supervisor: SupervisorConfig::default(),
+            runtime: RuntimeConfig::default(),
+            proof_tuning: ProofTuningConfig::default(),
+            backpressure: BackpressureConfig::default(),
+            chaos: ChaosConfig::default(),
+            pools: BTreeMap::new(),
+            routes: BTreeMap::new(),
+            pool_filter: PoolFilterConfig::default(),
+            metadata: MetadataConfig::default(),
+            order_key: OrderKeyConfig::default(),
+            notifications: NotificationConfig::default(),
+            screening: ScreeningConfig::default(),
+            accounting: AccountingConfig::default(),
+            stake_top_up: StakeTopUpConfig::default(),
+            reregistration: ReregistrationConfig::default(),
+            quorum: QuorumConfig::default(),
+            slashing: SlashingConfig::default(),
+            scheduler: SchedulerConfig::default(),
+            risk: RiskConfig::default(),
+            ha: HaConfig::default(),
+            identities: Vec::new(),
+        }
+    }
+}
+
+impl Default for EthereumConfig {
+    fn default() -> Self {
+        Self {
+            rpc_url: "https://holesky.infura.io/v3/YOUR_PROJECT_ID".to_string(),
+            operator_address: "0x0000000000000000000000000000000000000000".to_string(),
+            private_key: Secret::new(ZERO_PRIVATE_KEY),
+            bls_private_key: None,
+            service_manager_address: "0x1234567890123456789012345678901234567890".to_string(),
+            eigenvault_hook_address: "0x2345678901234567890123456789012345678901".to_string(),
+            order_vault_address: "0x3456789012345678901234567890123456789012".to_string(),
+            gas_limit: 500_000,
+            gas_price: 20_000_000_000, // 20 gwei
+            confirmation_blocks: 3,
+            committee_key_url: None,
+            expected_chain_id: None,
+            cache: ChainCacheConfig::default(),
+            transactions: TransactionConfig::default(),
+            eigenlayer: EigenLayerConfig::default(),
+        }
+    }
+}
+
+impl Default for ChainCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            stake_ttl_seconds: 60,
+            task_ttl_seconds: 30,
+        }
+    }
+}
+
+impl Default for TransactionConfig {
+    fn default() -> Self {
+        Self {
+            replace_after_seconds: 60,
+            gas_bump_percent: 15,
+            max_replacements: 5,
+            max_gas_price: 500_000_000_000, // 500 gwei
+            confirm_timeout_seconds: 900,
+        }
+    }
+}
+
+impl Default for EigenLayerConfig {
+    fn default() -> Self {
+        Self {
+            avs_directory: String::new(),
+            stake_registry: String::new(),
+            delegation_manager: String::new(),
+            registration_expiry_seconds: 3600,
+        }
+    }
+}
+
+impl Default for MatchingConfig {
+    fn default() -> Self {
+        Self {
+            max_pending_orders: 1000,
+            matching_interval_ms: 100,
+            price_tolerance_bps: 10, // 0.1%
+            max_slippage_bps: 50, // 0.5%
+            order_timeout_seconds: 3600, // 1 hour
+            enable_cross_pool_matching: true,
+            min_order_size: Decimal::ZERO,
+            min_fill_amount: Decimal::ZERO,
+            base_decimals: 18,
+            quote_decimals: 18,
+            algorithm: MatchingAlgorithm::Midpoint,
+            require_trader_signatures: true,
+            batch_jitter_ms: 0,
+            batch_jitter_seed: Secret::default(),
+            recent_matches_limit: 100,
+            recent_matches_ttl_seconds: 3600, // 1 hour
+        }
+    }
+}
+
+impl Default for NetworkingConfig {
+    fn default() -> Self {
+        Self {
+            listen_port: 9000,
+            bootstrap_peers: vec![
+                "127.0.0.1:9001".to_string(),
+                "127.0.0.1:9002".to_string(),
+            ],
+            min_peers: 3,
+            max_peers: 50,
+            connection_timeout_seconds: 30,
+            gossip_interval_ms: 1000,
+            enable_encryption: true,
+            record_path: None,
+            message_cache_size: 10_000,
+            message_cache_ttl_seconds: 3600, // 1 hour
+        }
+    }
+}
+
+impl Default for ProofConfig {
+    fn default() -> Self {
+        Self {
+            circuit_path: "./circuits".to_string(),
+            proving_key_path: "./keys/proving.key".to_string(),
+            verification_key_path: "./keys/verification.key".to_string(),
+            max_proof_size: 1_048_576, // 1MB
+            proof_timeout_seconds: 300, // 5 minutes
+            enable_batch_proving: true,
+            proving_threads: 2,
+        }
+    }
+}
+
+impl Default for EventBusConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: EventBusBackend::Nats,
+            servers: vec!["nats://127.0.0.1:4222".to_string()],
+            topic_prefix: "eigenvault".to_string(),
+            client_id: "eigenvault-operator".to_string(),
+        }
+    }
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            data_dir: "./data".to_string(),
+            sync_writes: true,
+        }
+    }
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            listen_address: "0.0.0.0:9100".to_string(),
+            path: "/metrics".to_string(),
+        }
+    }
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            listen_address: "127.0.0.1:8080".to_string(),
+            health_check_interval_seconds: 30,
+            admin_token: None,
+            api_keys: Vec::new(),
+            tls: None,
+        }
+    }
+}
+
+impl ApiConfig {
+    /// Key the CLI tools use against the local API
+    pub fn control_token(&self) -> Option<String> {
+        self.admin_token.clone().or_else(|| {
+            self.api_keys.iter()
+                .find(|api_key| api_key.role == ApiRole::Control)
+                .map(|api_key| api_key.key.clone())
+        })
+    }
+
+    pub fn has_keys(&self) -> bool {
+        self.admin_token.is_some() || !self.api_keys.is_empty()
+    }
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_address: "127.0.0.1:50051".to_string(),
+            tls: None,
+        }
+    }
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            restart_window_seconds: 300,
+            initial_backoff_ms: 1_000,
+            max_backoff_ms: 60_000,
+        }
+    }
+}
+
+impl Default for ProofTuningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            deadline_share: 0.5,
+            min_batch_size: 1,
+            max_batch_size: 64,
+            // One match per proof, proved at once, until latency has been measured
+            initial_batch_size: 1,
+            max_window_ms: 2_000,
+            initial_window_ms: 0,
+        }
+    }
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        Self {
+            worker_threads: 0,
+            max_blocking_threads: 512,
+        }
+    }
+}
+
+impl Default for BackpressureConfig {
+    fn default() -> Self {
+        Self {
+            pending_orders_high_water_percent: 90,
+            max_proofs_in_flight: 8,
+            pause_ms: 250,
+        }
+    }
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            drain_timeout_seconds: 30,
+        }
+    }
+}
+
+impl Default for OrderKeyConfig {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            version: 1,
+            uri: String::new(),
+        }
+    }
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_webhooks: 10_000,
+            timeout_ms: 5_000,
+            max_attempts: 3,
+            allow_http: false,
+        }
+    }
+}
+
+impl Default for AccountingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: "./data/earnings.jsonl".to_string(),
+            task_reward_gwei: 0,
+            fee_bps: 0,
+            rebate_percent: 0,
+        }
+    }
+}
+
+impl Default for StakeTopUpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strategy: String::new(),
+            funding_address: String::new(),
+            funding_private_key: Secret::default(),
+            buffer_gwei: 1_000_000_000,       // 1 ETH
+            max_deposit_gwei: 5_000_000_000,  // 5 ETH
+            max_daily_gwei: 10_000_000_000,   // 10 ETH
+            check_interval_seconds: 3600,
+            confirm: true,
+        }
+    }
+}
+
+impl Default for ReregistrationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cooldown_seconds: 3600,
+        }
+    }
+}
+
+impl Default for QuorumConfig {
+    fn default() -> Self {
+        Self {
+            sync_interval_seconds: 60,
+            threshold_bps: 6667,
+            committee: Vec::new(),
+        }
+    }
+}
+
+impl Default for SlashingConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_seconds: 30,
+            pause_on_self_slash: true,
+            peer_reputation_penalty: 5.0,
+        }
+    }
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            deadline_margin_seconds: 30,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MatchingConfig {
-    pub max_pending_orders: usize,
-    pub matching_interval_ms: u64,
-    pub price_tolerance_bps: u64,
-    pub max_slippage_bps: u64,
-    pub order_timeout_seconds: u64,
-    pub enable_cross_pool_matching: bool,
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            penalty_gwei: 1_000_000_000,        // 1 ETH
+            max_exposure_gwei: 10_000_000_000,  // 10 ETH
+            max_bond_percent: 50,
+            challenge_window_seconds: 86400,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NetworkingConfig {
-    pub listen_port: u16,
-    pub bootstrap_peers: Vec<String>,
-    pub min_peers: usize,
-    pub max_peers: usize,
-    pub connection_timeout_seconds: u64,
-    pub gossip_interval_ms: u64,
-    pub enable_encryption: bool,
+impl Default for HaConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            instance_id: String::new(),
+            lease_path: "./shared/ha.lease".to_string(),
+            lease_seconds: 15,
+            heartbeat_seconds: 5,
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ProofConfig {
-    pub circuit_path: String,
-    pub proving_key_path: String,
-    pub verification_key_path: String,
-    pub max_proof_size: usize,
-    pub proof_timeout_seconds: u64,
-    pub enable_batch_proving: bool,
+impl Default for PublicStatsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_address: "0.0.0.0:8081".to_string(),
+            requests_per_minute: 60,
+            allowed_origins: vec!["*".to_string()],
+        }
+    }
 }
 
-impl Default for Settings {
+impl Default for IpcConfig {
     fn default() -> Self {
+        let endpoint = if cfg!(windows) {
+            r"\\.\pipe\eigenvault-operator"
+        } else {
+            "./data/operator.sock"
+        };
+
         Self {
-            ethereum: EthereumConfig::default(),
-            matching: MatchingConfig::default(),
-            networking: NetworkingConfig::default(),
-            proofs: ProofConfig::default(),
+            enabled: true,
+            endpoint: endpoint.to_string(),
         }
     }
 }
 
-impl Default for EthereumConfig {
+impl Default for TelemetryConfig {
     fn default() -> Self {
         Self {
-            rpc_url: "https://holesky.infura.io/v3/YOUR_PROJECT_ID".to_string(),
-            operator_address: "0x0000000000000000000000000000000000000000".to_string(),
-            private_key: "0x0000000000000000000000000000000000000000000000000000000000000000".to_string(),
-            service_manager_address: "0x1234567890123456789012345678901234567890".to_string(),
-            eigenvault_hook_address: "0x2345678901234567890123456789012345678901".to_string(),
-            order_vault_address: "0x3456789012345678901234567890123456789012".to_string(),
-            gas_limit: 500_000,
-            gas_price: 20_000_000_000, // 20 gwei
-            confirmation_blocks: 3,
+            otlp_enabled: false,
+            otlp_endpoint: "http://localhost:4317".to_string(),
+            service_name: "eigenvault-operator".to_string(),
+            sample_ratio: 1.0,
         }
     }
 }
 
-impl Default for MatchingConfig {
+impl Default for LoggingConfig {
     fn default() -> Self {
         Self {
-            max_pending_orders: 1000,
-            matching_interval_ms: 100,
-            price_tolerance_bps: 10, // 0.1%
-            max_slippage_bps: 50, // 0.5%
-            order_timeout_seconds: 3600, // 1 hour
-            enable_cross_pool_matching: true,
+            format: LogFormat::Text,
+            file_enabled: false,
+            directory: "./logs".to_string(),
+            file_name: "operator.log".to_string(),
+            rotation: LogRotation::Daily,
+            max_file_size_mb: 100,
+            max_files: 7,
         }
     }
 }
 
-impl Default for NetworkingConfig {
+impl Default for AuditConfig {
     fn default() -> Self {
         Self {
-            listen_port: 9000,
-            bootstrap_peers: vec![
-                "127.0.0.1:9001".to_string(),
-                "127.0.0.1:9002".to_string(),
-            ],
-            min_peers: 3,
-            max_peers: 50,
-            connection_timeout_seconds: 30,
-            gossip_interval_ms: 1000,
-            enable_encryption: true,
+            enabled: true,
+            path: "./data/audit.log".to_string(),
         }
     }
 }
 
-impl Default for ProofConfig {
+impl Default for LatencyConfig {
     fn default() -> Self {
         Self {
-            circuit_path: "./circuits".to_string(),
-            proving_key_path: "./keys/proving.key".to_string(),
-            verification_key_path: "./keys/verification.key".to_string(),
-            max_proof_size: 1_048_576, // 1MB
-            proof_timeout_seconds: 300, // 5 minutes
-            enable_batch_proving: true,
+            budget_seconds: 120.0,
+            slo_quantile: 0.95,
+            window_size: 500,
         }
     }
 }
 
 impl Settings {
-    /// Load settings from TOML file
+    /// Load settings from a YAML (`.yaml`/`.yml`) or TOML file, over the
+    /// built-in defaults and under environment overrides
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let contents = std::fs::read_to_string(path)?;
-        let settings: Settings = toml::from_str(&contents)?;
-        Ok(settings)
+        Ok(Layered::load(path.as_ref(), None, &[])?.settings)
     }
 
-    /// Save settings to TOML file
+    /// Save settings in the format implied by the file extension
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
-        let contents = toml::to_string_pretty(self)?;
-        std::fs::write(path, contents)?;
+        let path = path.as_ref();
+        std::fs::write(path, self.to_string_as(ConfigFormat::of(path))?)?;
+        Ok(())
+    }
+
+    fn to_string_as(&self, format: ConfigFormat) -> Result<String> {
+        Ok(match format {
+            ConfigFormat::Yaml => serde_yaml::to_string(self)?,
+            ConfigFormat::Toml => toml::to_string_pretty(self)?,
+        })
+    }
+
+    /// Parse the config file and migrate it to `CONFIG_VERSION`. A migrated file is rewritten
+    /// after a backup with new fields filled from `defaults`; the document returned holds only
+    /// the fields the file sets.
+    pub(super) fn read_migrated(path: &Path, defaults: &Settings) -> Result<serde_json::Value> {
+        let contents = std::fs::read_to_string(path)?;
+        let mut file: serde_json::Value = match ConfigFormat::of(path) {
+            ConfigFormat::Yaml => serde_yaml::from_str(&contents)?,
+            ConfigFormat::Toml => toml::from_str(&contents)?,
+        };
+
+        // Migrated without filling in defaults, so only what the file sets is layered and
+        // reported as coming from it
+        let mut written = file.clone();
+        migrate::migrate(&mut written, &serde_json::Value::Object(Default::default()), CONFIG_MIGRATIONS)?;
+
+        let defaults = serde_json::to_value(defaults)?;
+        if let Some(from) = migrate::migrate(&mut file, &defaults, CONFIG_MIGRATIONS)? {
+            let migrated: Settings = serde_json::from_value(file)?;
+            migrate::persist(path, from, &migrated.to_string_as(ConfigFormat::of(path))?);
+        }
+        Ok(written)
+    }
+
+    /// Resolve `env:`, `file:` and `keystore:` references in secret fields
+    pub fn resolve_secrets(&mut self) -> Result<()> {
+        let secrets = std::iter::once(("ethereum.private_key".to_string(), &mut self.ethereum.private_key))
+            .chain(self.ethereum.bls_private_key.as_mut().map(|key| ("ethereum.bls_private_key".to_string(), key)))
+            .chain(self.identities.iter_mut().flat_map(|identity| {
+                let bls_field = format!("identities.{}.bls_private_key", identity.name);
+                std::iter::once((format!("identities.{}.private_key", identity.name), &mut identity.private_key))
+                    .chain(identity.bls_private_key.as_mut().map(|key| (bls_field, key)))
+            }))
+            .chain((self.matching.batch_jitter_ms > 0).then(|| {
+                ("matching.batch_jitter_seed".to_string(), &mut self.matching.batch_jitter_seed)
+            }))
+            .chain(self.stake_top_up.enabled.then(|| {
+                ("stake_top_up.funding_private_key".to_string(), &mut self.stake_top_up.funding_private_key)
+            }));
+
+        for (field, secret) in secrets {
+            secret.resolve().map_err(|e| anyhow::anyhow!("Failed to resolve {}: {}", field, e))?;
+            if !secret.is_reference() && !secret.is_empty() && secret.expose() != ZERO_PRIVATE_KEY {
+                warn!("{} is stored in plaintext; prefer an env:, file: or keystore: reference", field);
+            }
+        }
         Ok(())
     }
 
+    /// Ethereum config that signs as the stake top-up funding account
+    pub fn funding_config(&self) -> EthereumConfig {
+        let mut ethereum = self.ethereum.clone();
+        ethereum.operator_address = self.stake_top_up.funding_address.clone();
+        ethereum.private_key = self.stake_top_up.funding_private_key.clone();
+        ethereum
+    }
+
+    /// Ethereum config for every operator identity, the `ethereum` section's own first
+    pub fn identity_configs(&self) -> Vec<(String, EthereumConfig)> {
+        let mut configs = vec![(PRIMARY_IDENTITY.to_string(), self.ethereum.clone())];
+        for identity in &self.identities {
+            let mut ethereum = self.ethereum.clone();
+            ethereum.operator_address = identity.operator_address.clone();
+            ethereum.private_key = identity.private_key.clone();
+            ethereum.bls_private_key = identity.bls_private_key.clone();
+            configs.push((identity.name.clone(), ethereum));
+        }
+        configs
+    }
+
     /// Validate configuration
     pub fn validate(&self) -> Result<()> {
         // Validate Ethereum config
@@ -150,10 +1382,23 @@ impl Settings {
             return Err(anyhow::anyhow!("Valid operator address is required"));
         }
 
-        if self.ethereum.private_key.is_empty() || self.ethereum.private_key == "0x0000000000000000000000000000000000000000000000000000000000000000" {
+        if self.ethereum.private_key.is_empty() || self.ethereum.private_key.expose() == ZERO_PRIVATE_KEY {
             return Err(anyhow::anyhow!("Valid private key is required"));
         }
 
+        let transactions = &self.ethereum.transactions;
+        if transactions.gas_bump_percent < 10 {
+            return Err(anyhow::anyhow!("Transaction gas bump must be at least 10 percent for nodes to accept replacements"));
+        }
+
+        if transactions.max_gas_price < self.ethereum.gas_price {
+            return Err(anyhow::anyhow!("Transaction max gas price cannot be below ethereum.gas_price"));
+        }
+
+        if self.ethereum.eigenlayer.registration_expiry_seconds == 0 {
+            return Err(anyhow::anyhow!("EigenLayer registration expiry must be greater than 0"));
+        }
+
         // Validate matching config
         if self.matching.max_pending_orders == 0 {
             return Err(anyhow::anyhow!("Max pending orders must be greater than 0"));
@@ -163,6 +1408,43 @@ impl Settings {
             return Err(anyhow::anyhow!("Matching interval must be greater than 0"));
         }
 
+        if self.matching.batch_jitter_ms > 0 {
+            if self.matching.batch_jitter_seed.is_empty() {
+                return Err(anyhow::anyhow!("Batch jitter requires matching.batch_jitter_seed, shared by the committee"));
+            }
+            if self.matching.batch_jitter_ms >= self.matching.matching_interval_ms {
+                return Err(anyhow::anyhow!("Batch jitter must be shorter than the matching interval"));
+            }
+        }
+
+        for (pool, overrides) in &self.pools {
+            if overrides.matching_interval_ms == Some(0) {
+                return Err(anyhow::anyhow!("Matching interval for pool {} must be greater than 0", pool));
+            }
+            if overrides.matching_interval_ms.is_some_and(|interval| self.matching.batch_jitter_ms >= interval) {
+                return Err(anyhow::anyhow!("Batch jitter must be shorter than the matching interval of pool {}", pool));
+            }
+        }
+
+        for (pair, route) in &self.routes {
+            for pool in route.pools.iter().chain(&route.settlement_pool) {
+                if routing::pool_tier(pool).map(|(pool_pair, _)| pool_pair) != Some(pair.as_str()) {
+                    return Err(anyhow::anyhow!("Pool {} of route {} is not a fee tier of {}", pool, pair, pair));
+                }
+            }
+            if let Some(settlement_pool) = &route.settlement_pool {
+                if !route.pools.is_empty() && !route.pools.contains(settlement_pool) {
+                    return Err(anyhow::anyhow!("Settlement pool {} of route {} is not one of its pools", settlement_pool, pair));
+                }
+            }
+            if route.matching.matching_interval_ms == Some(0) {
+                return Err(anyhow::anyhow!("Matching interval for route {} must be greater than 0", pair));
+            }
+            if route.matching.matching_interval_ms.is_some_and(|interval| self.matching.batch_jitter_ms >= interval) {
+                return Err(anyhow::anyhow!("Batch jitter must be shorter than the matching interval of route {}", pair));
+            }
+        }
+
         // Validate networking config
         if self.networking.listen_port == 0 {
             return Err(anyhow::anyhow!("Listen port must be greater than 0"));
@@ -172,6 +1454,10 @@ impl Settings {
             return Err(anyhow::anyhow!("Min peers cannot be greater than max peers"));
         }
 
+        if self.networking.message_cache_size == 0 || self.matching.recent_matches_limit == 0 {
+            return Err(anyhow::anyhow!("Gossip message cache and recent matches limits must be greater than 0"));
+        }
+
         // Validate proof config
         if self.proofs.max_proof_size == 0 {
             return Err(anyhow::anyhow!("Max proof size must be greater than 0"));
@@ -181,52 +1467,214 @@ impl Settings {
             return Err(anyhow::anyhow!("Proof timeout must be greater than 0"));
         }
 
-        Ok(())
-    }
+        // Validate proof tuning config
+        let tuning = &self.proof_tuning;
+        if tuning.min_batch_size == 0
+            || !(tuning.min_batch_size..=tuning.max_batch_size).contains(&tuning.initial_batch_size)
+        {
+            return Err(anyhow::anyhow!("Proof batch sizes must satisfy 1 <= min <= initial <= max"));
+        }
+        if tuning.initial_window_ms > tuning.max_window_ms {
+            return Err(anyhow::anyhow!("Initial proof batching window cannot exceed the maximum"));
+        }
+        if !(tuning.deadline_share > 0.0 && tuning.deadline_share <= 1.0) {
+            return Err(anyhow::anyhow!("Proof deadline share must be in (0, 1]"));
+        }
 
-    /// Get environment-specific overrides
-    pub fn apply_env_overrides(&mut self) -> Result<()> {
-        use std::env;
+        // Validate runtime config
+        if self.runtime.max_blocking_threads == 0 {
+            return Err(anyhow::anyhow!("Runtime needs at least one blocking thread"));
+        }
+
+        // Validate storage config
+        if self.storage.data_dir.is_empty() {
+            return Err(anyhow::anyhow!("Storage data directory is required"));
+        }
+
+        // Validate metrics config
+        if self.metrics.enabled && !self.metrics.path.starts_with('/') {
+            return Err(anyhow::anyhow!("Metrics path must start with '/'"));
+        }
+
+        // Validate API config
+        if self.api.health_check_interval_seconds == 0 {
+            return Err(anyhow::anyhow!("Health check interval must be greater than 0"));
+        }
+
+        // The gRPC admin API has no unauthenticated mode
+        if self.grpc.enabled && !self.api.has_keys() {
+            return Err(anyhow::anyhow!("gRPC admin API requires api.admin_token or api.api_keys"));
+        }
+
+        // Validate API keys
+        let mut keys = std::collections::HashSet::new();
+        for api_key in &self.api.api_keys {
+            if api_key.key.is_empty() {
+                return Err(anyhow::anyhow!("API key '{}' cannot be empty", api_key.name));
+            }
+            if !keys.insert(api_key.key.as_str()) || self.api.admin_token.as_deref() == Some(api_key.key.as_str()) {
+                return Err(anyhow::anyhow!("API key '{}' is configured more than once", api_key.name));
+            }
+        }
+
+        for tls in [&self.api.tls, &self.grpc.tls].into_iter().flatten() {
+            if tls.cert_path.is_empty() || tls.key_path.is_empty() {
+                return Err(anyhow::anyhow!("TLS requires both cert_path and key_path"));
+            }
+        }
+
+        // Validate additional identities
+        let mut names = std::collections::HashSet::from([PRIMARY_IDENTITY]);
+        let mut addresses = std::collections::HashSet::from([self.ethereum.operator_address.to_lowercase()]);
+        for identity in &self.identities {
+            if identity.operator_address.is_empty() || identity.private_key.is_empty() {
+                return Err(anyhow::anyhow!("Identity '{}' requires an operator address and private key", identity.name));
+            }
+            if identity.name.is_empty() || !names.insert(identity.name.as_str()) {
+                return Err(anyhow::anyhow!("Identity names must be unique and non-empty: '{}'", identity.name));
+            }
+            if !addresses.insert(identity.operator_address.to_lowercase()) {
+                return Err(anyhow::anyhow!("Identity '{}' reuses operator address {}", identity.name, identity.operator_address));
+            }
+        }
+
+        if let Some(pool) = self.pool_filter.allow.iter().find(|pool| self.pool_filter.deny.contains(pool)) {
+            return Err(anyhow::anyhow!("Pool {} is both allowed and denied", pool));
+        }
+
+        if !(1..=100).contains(&self.backpressure.pending_orders_high_water_percent)
+            || self.backpressure.max_proofs_in_flight <= 0
+            || self.backpressure.pause_ms == 0
+        {
+            return Err(anyhow::anyhow!("Backpressure limits must be positive and the high-water mark at most 100%"));
+        }
+
+        self.chaos.validate()?;
+
+        if self.supervisor.initial_backoff_ms > self.supervisor.max_backoff_ms {
+            return Err(anyhow::anyhow!("Supervisor initial backoff cannot exceed max backoff"));
+        }
+
+        if self.public_stats.enabled && self.public_stats.requests_per_minute == 0 {
+            return Err(anyhow::anyhow!("Public stats rate limit must be greater than 0"));
+        }
+
+        if self.notifications.enabled && (self.notifications.max_webhooks == 0 || self.notifications.max_attempts == 0) {
+            return Err(anyhow::anyhow!("Notification webhook limit and delivery attempts must be greater than 0"));
+        }
+
+        if self.screening.plugin == ScreeningPlugin::Denylist
+            && self.screening.denylist.is_empty() && self.screening.denylist_path.is_empty() {
+            return Err(anyhow::anyhow!("Denylist screening needs a denylist or a denylist_path"));
+        }
+
+        if !self.order_key.path.is_empty() && self.order_key.version == 0 {
+            return Err(anyhow::anyhow!("Order key version must be greater than 0"));
+        }
+
+        if self.ipc.enabled && self.ipc.endpoint.is_empty() {
+            return Err(anyhow::anyhow!("IPC endpoint cannot be empty"));
+        }
+
+        // Validate telemetry config
+        if !(0.0..=1.0).contains(&self.telemetry.sample_ratio) {
+            return Err(anyhow::anyhow!("Trace sample ratio must be between 0 and 1"));
+        }
+
+        // Validate logging config
+        if self.logging.file_enabled && self.logging.max_files == 0 {
+            return Err(anyhow::anyhow!("Log rotation must keep at least one file"));
+        }
+
+        // Validate audit config
+        if self.audit.enabled && self.audit.path.is_empty() {
+            return Err(anyhow::anyhow!("Audit log path cannot be empty"));
+        }
+
+        if self.accounting.enabled && self.accounting.path.is_empty() {
+            return Err(anyhow::anyhow!("Earnings ledger path cannot be empty"));
+        }
+        if self.stake_top_up.enabled {
+            let top_up = &self.stake_top_up;
+            if top_up.strategy.is_empty() || top_up.funding_address.is_empty() || top_up.funding_private_key.is_empty() {
+                return Err(anyhow::anyhow!("Stake top-up needs a strategy, funding_address and funding_private_key"));
+            }
+            if top_up.max_deposit_gwei == 0 || top_up.max_daily_gwei < top_up.max_deposit_gwei {
+                return Err(anyhow::anyhow!("Stake top-up max_deposit_gwei must be greater than 0 and at most max_daily_gwei"));
+            }
+            if top_up.check_interval_seconds == 0 {
+                return Err(anyhow::anyhow!("Stake top-up check interval must be greater than 0"));
+            }
+        }
+
+        if self.reregistration.enabled && self.reregistration.cooldown_seconds == 0 {
+            return Err(anyhow::anyhow!("Re-registration cooldown must be greater than 0"));
+        }
 
-        // Ethereum overrides
-        if let Ok(rpc_url) = env::var("ETHEREUM_RPC_URL") {
-            self.ethereum.rpc_url = rpc_url;
+        if self.quorum.sync_interval_seconds == 0 || !(1..=10_000).contains(&self.quorum.threshold_bps) {
+            return Err(anyhow::anyhow!("Quorum sync interval must be greater than 0 and threshold_bps between 1 and 10000"));
         }
 
-        if let Ok(operator_address) = env::var("OPERATOR_ADDRESS") {
-            self.ethereum.operator_address = operator_address;
+        for member in &self.quorum.committee {
+            let Some(public_key) = &member.bls_public_key else {
+                continue;
+            };
+            let proof = member.bls_possession_proof.as_deref().unwrap_or_default();
+            let proof = hex::decode(proof.strip_prefix("0x").unwrap_or(proof))?;
+            if !verify_bls_possession(public_key, &proof)? {
+                return Err(anyhow::anyhow!("BLS key of committee member {} needs a valid bls_possession_proof", member.operator));
+            }
         }
 
-        if let Ok(private_key) = env::var("OPERATOR_PRIVATE_KEY") {
-            self.ethereum.private_key = private_key;
+        if self.slashing.poll_interval_seconds == 0 || !(0.0..=10.0).contains(&self.slashing.peer_reputation_penalty) {
+            return Err(anyhow::anyhow!("Slashing poll interval must be greater than 0 and peer_reputation_penalty between 0 and 10"));
         }
 
-        if let Ok(service_manager) = env::var("SERVICE_MANAGER_ADDRESS") {
-            self.ethereum.service_manager_address = service_manager;
+        if self.risk.enabled && (self.risk.penalty_gwei == 0 || !(1..=100).contains(&self.risk.max_bond_percent)) {
+            return Err(anyhow::anyhow!("Risk penalty must be greater than 0 and max_bond_percent between 1 and 100"));
         }
 
-        // Networking overrides
-        if let Ok(listen_port) = env::var("LISTEN_PORT") {
-            if let Ok(port) = listen_port.parse::<u16>() {
-                self.networking.listen_port = port;
+        if self.ha.enabled {
+            if self.ha.instance_id.is_empty() || self.ha.lease_path.is_empty() {
+                return Err(anyhow::anyhow!("Failover requires ha.instance_id and ha.lease_path"));
             }
+            if self.ha.heartbeat_seconds == 0 || self.ha.heartbeat_seconds * 2 > self.ha.lease_seconds {
+                return Err(anyhow::anyhow!("Failover lease must last at least two heartbeats"));
+            }
+        }
+
+        if self.accounting.fee_bps > 10_000 {
+            return Err(anyhow::anyhow!("Matching fee cannot exceed 10000 bps"));
+        }
+        if self.accounting.rebate_percent > 100 {
+            return Err(anyhow::anyhow!("Fee rebate cannot exceed 100 percent"));
         }
 
-        if let Ok(bootstrap_peers) = env::var("BOOTSTRAP_PEERS") {
-            self.networking.bootstrap_peers = bootstrap_peers
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .collect();
+        // Validate latency config
+        if self.latency.budget_seconds <= 0.0 || self.latency.window_size == 0 {
+            return Err(anyhow::anyhow!("Latency budget and window size must be greater than 0"));
+        }
+        if !(0.0..=1.0).contains(&self.latency.slo_quantile) {
+            return Err(anyhow::anyhow!("Latency SLO quantile must be between 0 and 1"));
         }
 
-        // Proof config overrides
-        if let Ok(circuit_path) = env::var("CIRCUIT_PATH") {
-            self.proofs.circuit_path = circuit_path;
+        // Validate event bus config
+        if self.event_bus.enabled && self.event_bus.servers.is_empty() {
+            return Err(anyhow::anyhow!("Event bus requires at least one server"));
         }
 
         Ok(())
     }
 
+    /// Get environment-specific overrides
+    pub fn for_profile(profile: Profile) -> Self {
+        match profile {
+            Profile::Dev => Self::development(),
+            Profile::Testnet => Self::testnet(),
+            Profile::Prod => Self::production(),
+        }
+    }
+
     /// Create development configuration
     pub fn development() -> Self {
         let mut config = Self::default();
@@ -299,9 +1747,21 @@ mod tests {
         assert!(settings.proofs.max_proof_size > 0);
     }
 
+    /// Defaults with the operator identity validation requires filled in
+    fn valid_settings() -> Settings {
+        Settings {
+            ethereum: EthereumConfig {
+                operator_address: "0x1111111111111111111111111111111111111111".to_string(),
+                private_key: "0x1111111111111111111111111111111111111111111111111111111111111111".into(),
+                ..EthereumConfig::default()
+            },
+            ..Settings::default()
+        }
+    }
+
     #[test]
     fn test_settings_validation() {
-        let mut settings = Settings::default();
+        let mut settings = valid_settings();
         
         // Valid settings should pass
         assert!(settings.validate().is_ok());
@@ -311,9 +1771,65 @@ mod tests {
         assert!(settings.validate().is_err());
         
         // Reset and test invalid matching config
-        settings = Settings::default();
+        settings = valid_settings();
         settings.matching.max_pending_orders = 0;
         assert!(settings.validate().is_err());
+        
+        // Trace sampling ratio must be a fraction
+        settings = valid_settings();
+        settings.telemetry.sample_ratio = 1.5;
+        assert!(settings.validate().is_err());
+
+        // Routes only take fee tiers of their own pair
+        settings = valid_settings();
+        let route = RouteConfig { pools: vec!["ETH_USDC_500".to_string(), "ETH_USDC_3000".to_string()], ..RouteConfig::default() };
+        settings.routes.insert("ETH_USDC".to_string(), route.clone());
+        assert!(settings.validate().is_ok());
+        settings.routes.insert("WBTC_USDC".to_string(), route);
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_committee_bls_keys_need_a_proof_of_possession() -> Result<()> {
+        let key_manager = crate::config::KeyManager::new();
+        let private_key = format!("0x{}", "11".repeat(32));
+        let mut settings = valid_settings();
+        settings.quorum.committee.push(CommitteeMember {
+            operator: "0x2222222222222222222222222222222222222222".to_string(),
+            bls_public_key: Some(key_manager.bls_public_key(&private_key)?),
+            bls_possession_proof: None,
+        });
+        assert!(settings.validate().is_err());
+
+        // A proof for another key doesn't do
+        let other_proof = key_manager.prove_bls_possession(&format!("0x{}", "22".repeat(32)))?;
+        settings.quorum.committee[0].bls_possession_proof = Some(format!("0x{}", hex::encode(other_proof)));
+        assert!(settings.validate().is_err());
+
+        let proof = key_manager.prove_bls_possession(&private_key)?;
+        settings.quorum.committee[0].bls_possession_proof = Some(format!("0x{}", hex::encode(proof)));
+        settings.validate()
+    }
+
+    #[test]
+    fn test_identity_configs() {
+        let mut settings = valid_settings();
+        settings.identities.push(IdentityConfig {
+            name: "second".to_string(),
+            operator_address: "0x2222222222222222222222222222222222222222".to_string(),
+            private_key: "0x22".into(),
+            bls_private_key: None,
+        });
+
+        let configs = settings.identity_configs();
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].0, PRIMARY_IDENTITY);
+        assert_eq!(configs[1].1.operator_address, "0x2222222222222222222222222222222222222222");
+        assert_eq!(configs[1].1.rpc_url, settings.ethereum.rpc_url);
+
+        // Two identities cannot share a registration
+        settings.identities[0].operator_address = settings.ethereum.operator_address.clone();
+        assert!(settings.validate().is_err());
     }
 
     #[test]
@@ -332,6 +1848,95 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_save_load_yaml_settings() -> Result<()> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("config.yaml");
+        
+        let mut original_settings = Settings::default();
+        original_settings.matching.max_pending_orders = 4321;
+        original_settings.save(&file_path)?;
+        
+        assert!(std::fs::read_to_string(&file_path)?.contains("max_pending_orders: 4321"));
+        assert_eq!(Settings::load(&file_path)?.matching.max_pending_orders, 4321);
+        
+        Ok(())
+    }
+
+    #[test]
+    fn test_example_config_loads() -> Result<()> {
+        let example = Path::new(env!("CARGO_MANIFEST_DIR")).join("config.example.yaml");
+        let settings = Settings::load(example)?;
+        
+        assert_eq!(settings.networking.listen_port, 9000);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pool_filter() {
+        let filter = PoolFilterConfig {
+            allow: vec!["ETH_USDC_3000".to_string(), "WBTC_USDC_3000".to_string()],
+            deny: vec!["WBTC_USDC_3000".to_string()],
+        };
+        
+        assert!(filter.serves("ETH_USDC_3000"));
+        assert!(!filter.serves("WBTC_USDC_3000"));
+        assert!(!filter.serves("PEPE_ETH_10000"));
+        assert!(PoolFilterConfig::default().serves("PEPE_ETH_10000"));
+        
+        let settings = Settings { pool_filter: filter, ..valid_settings() };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_pool_join_and_leave() {
+        let mut filter = PoolFilterConfig::default();
+        filter.leave("PEPE_ETH_10000").unwrap();
+        assert!(!filter.serves("PEPE_ETH_10000"));
+        filter.join("PEPE_ETH_10000");
+        assert_eq!(filter, PoolFilterConfig::default());
+
+        let mut filter = PoolFilterConfig { allow: vec!["ETH_USDC_3000".to_string()], deny: vec![] };
+        filter.join("WBTC_USDC_3000");
+        assert!(filter.serves("WBTC_USDC_3000"));
+        filter.leave("ETH_USDC_3000").unwrap();
+        assert!(!filter.serves("ETH_USDC_3000"));
+        assert!(filter.leave("WBTC_USDC_3000").is_err());
+    }
+
+    #[test]
+    fn test_unversioned_config_is_migrated() -> Result<()> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("config.yaml");
+        let original = "ethereum:\n  rpc_url: \"http://node:8545\"\nmatching:\n  max_pending_orders: 42\n";
+        std::fs::write(&file_path, original)?;
+        
+        let settings = Settings::load(&file_path)?;
+        
+        assert_eq!(settings.version, CONFIG_VERSION);
+        assert_eq!(settings.matching.max_pending_orders, 42);
+        assert_eq!(settings.matching.matching_interval_ms, MatchingConfig::default().matching_interval_ms);
+        assert_eq!(std::fs::read_to_string(dir.path().join("config.yaml.v0.bak"))?, original);
+        assert!(std::fs::read_to_string(&file_path)?.contains(&format!("version: {}", CONFIG_VERSION)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_is_base_layer() -> Result<()> {
+        let dir = tempdir()?;
+        let file_path = dir.path().join("config.yaml");
+        std::fs::write(&file_path, "ethereum:\n  rpc_url: \"http://node:8545\"\nnetworking:\n  max_peers: 7\n")?;
+        
+        let settings = Layered::load(&file_path, Some(Profile::Prod), &[])?.settings;
+        
+        // File values win, everything else comes from the production preset
+        assert_eq!(settings.ethereum.rpc_url, "http://node:8545");
+        assert_eq!(settings.networking.max_peers, 7);
+        assert_eq!(settings.networking.min_peers, 10);
+        assert_eq!(settings.ethereum.confirmation_blocks, 12);
+        Ok(())
+    }
+
     #[test]
     fn test_development_config() {
         let config = Settings::development();
@@ -346,4 +1951,16 @@ mod tests {
         assert!(config.networking.min_peers >= 10);
         assert!(config.matching.price_tolerance_bps <= 10);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_chaos_validation() {
+        let mut chaos = ChaosConfig { drop_percent: 150.0, ..Default::default() };
+        assert!(chaos.validate().is_err());
+
+        chaos.drop_percent = 5.0;
+        assert!(chaos.validate().is_ok());
+
+        chaos.enabled = true;
+        assert_eq!(chaos.validate().is_ok(), cfg!(feature = "chaos"));
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::process::{Child, Command};
+use tracing::{debug, info};
+
+use crate::ethereum::JsonRpc;
+
+/// Init code deploying a stub whose runtime (`PUSH1 0 PUSH1 0 RETURN`) accepts any call
+const STUB_INIT_CODE: &str = "0x6005600c60003960056000f360006000f3";
+
+/// Local chain the devnet runs against: a spawned anvil, or an existing node
+pub struct Anvil {
+    pub rpc: JsonRpc,
+    /// Killed when the devnet is dropped
+    _process: Option<Child>,
+}
+
+impl Anvil {
+    /// Start `anvil` on `port` and wait until it answers
+    pub async fn spawn(port: u16, chain_id: u64) -> Result<Self> {
+        info!("Starting anvil on port {}", port);
+        let process = Command::new("anvil")
+            .args(["--port", &port.to_string(), "--chain-id", &chain_id.to_string(), "--silent"])
+            .kill_on_drop(true)
+            .spawn()
+            .context("Failed to start anvil; install Foundry or pass --rpc-url to attach to a running node")?;
+
+        let anvil = Self {
+            rpc: JsonRpc::new(&format!("http://127.0.0.1:{}", port)),
+            _process: Some(process),
+        };
+        anvil.wait_ready().await?;
+        Ok(anvil)
+    }
+
+    /// Use an already running node
+    pub async fn attach(rpc_url: &str) -> Result<Self> {
+        info!("Attaching to {}", rpc_url);
+        let anvil = Self { rpc: JsonRpc::new(rpc_url), _process: None };
+        anvil.wait_ready().await?;
+        Ok(anvil)
+    }
+
+    async fn wait_ready(&self) -> Result<()> {
+        for _ in 0..50 {
+            if self.rpc.call("eth_chainId", json!([])).await.is_ok() {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        Err(anyhow::anyhow!("{} did not become ready", self.rpc.url()))
+    }
+
+    /// Deploy a stub contract from an unlocked account and return its address
+    pub async fn deploy_stub(&self, from: &str) -> Result<String> {
+        let tx_hash = self.rpc.call("eth_sendTransaction", json!([{ "from": from, "data": STUB_INIT_CODE }])).await?;
+
+        // anvil mines every transaction immediately; an attached node may take a few blocks
+        for _ in 0..50 {
+            let receipt = self.rpc.call("eth_getTransactionReceipt", json!([tx_hash])).await?;
+            if let Some(address) = receipt.get("contractAddress").and_then(Value::as_str) {
+                debug!("Deployed stub at {} in {}", address, tx_hash);
+                return Ok(address.to_string());
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+        Err(anyhow::anyhow!("Deployment {} was not mined", tx_hash))
+    }
+}
@@ -0,0 +1,141 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::config::Settings;
+use crate::matching::journal::{self, JournalEntry, OrderJournal};
+use crate::matching::{Decimal, DecryptedOrder, OrderType, TimeInForce};
+
+pub mod anvil;
+
+pub use anvil::Anvil;
+
+/// Chain id of the spawned anvil
+pub const DEVNET_CHAIN_ID: u64 = 31337;
+
+/// anvil's first default account, used to deploy the stub contracts
+const DEPLOYER_ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+/// anvil's second default account; its well-known key is only ever funded on local chains
+const OPERATOR_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+const OPERATOR_PRIVATE_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+/// Pool the seeded orders are placed in
+const DEVNET_POOL: &str = "ETH_USDC_3000";
+
+pub struct DevnetOptions {
+    /// Attach to this node instead of spawning anvil
+    pub rpc_url: Option<String>,
+    pub port: u16,
+    /// Generated config, state and audit log live here
+    pub dir: PathBuf,
+    /// Buy/sell pairs seeded into the pending queue
+    pub order_pairs: usize,
+}
+
+/// A local chain with stub contracts and an operator config pointing at it
+pub struct Devnet {
+    pub config_path: PathBuf,
+    /// Keeps a spawned anvil alive for as long as the devnet
+    _anvil: Anvil,
+}
+
+impl Devnet {
+    /// Start or attach to the chain, deploy the contracts, write the config and seed orders
+    pub async fn start(options: DevnetOptions) -> Result<Self> {
+        let anvil = match &options.rpc_url {
+            Some(rpc_url) => Anvil::attach(rpc_url).await?,
+            None => Anvil::spawn(options.port, DEVNET_CHAIN_ID).await?,
+        };
+
+        let service_manager = anvil.deploy_stub(DEPLOYER_ADDRESS).await?;
+        let hook = anvil.deploy_stub(DEPLOYER_ADDRESS).await?;
+        let vault = anvil.deploy_stub(DEPLOYER_ADDRESS).await?;
+        info!("Deployed service manager {}, hook {}, vault {}", service_manager, hook, vault);
+
+        std::fs::create_dir_all(&options.dir)?;
+        let mut config = devnet_config(&options.dir);
+        config.ethereum.rpc_url = anvil.rpc.url().to_string();
+        config.ethereum.service_manager_address = service_manager;
+        config.ethereum.eigenvault_hook_address = hook;
+        config.ethereum.order_vault_address = vault;
+        config.validate()?;
+
+        let config_path = options.dir.join("devnet.yaml");
+        config.save(&config_path)?;
+
+        let journal_path = journal::journal_path(Path::new(&config.storage.data_dir));
+        let (mut journal, _) = OrderJournal::open(&journal_path, config.storage.sync_writes)?;
+        let seeded: Vec<JournalEntry> = seed_orders(options.order_pairs).into_iter()
+            .map(|order| JournalEntry::Add { order })
+            .collect();
+        journal.append(&seeded)?;
+        info!("Seeded {} orders; operator config written to {:?}", options.order_pairs * 2, config_path);
+
+        Ok(Self { config_path, _anvil: anvil })
+    }
+}
+
+/// Development settings with every path inside `dir` and no remote peers
+fn devnet_config(dir: &Path) -> Settings {
+    let path = |name: &str| dir.join(name).to_string_lossy().into_owned();
+
+    let mut config = Settings::development();
+    config.ethereum.operator_address = OPERATOR_ADDRESS.to_string();
+    config.ethereum.private_key = OPERATOR_PRIVATE_KEY.into();
+    config.ethereum.confirmation_blocks = 1;
+    config.networking.bootstrap_peers = Vec::new();
+    config.storage.data_dir = path("data");
+    config.audit.path = path("audit.log");
+    config.ipc.endpoint = path("operator.sock");
+    config
+}
+
+/// Crossing buy/sell pairs so the first matching rounds have work to do
+pub fn seed_orders(pairs: usize) -> Vec<DecryptedOrder> {
+    let deadline = chrono::Utc::now().timestamp() as u64 + 3600;
+
+    (0..pairs).flat_map(|i| {
+        let price = Decimal::from(2000 + i as u64);
+        let order = |side: &str, order_type: OrderType, price: Decimal| DecryptedOrder {
+            id: format!("devnet_{}_{}", side, i),
+            trader: format!("devnet_trader_{}_{}", side, i),
+            pool_key: DEVNET_POOL.to_string(),
+            order_type,
+            amount: Decimal::from(2 + i as u64).div_int(2),
+            price,
+            deadline,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        };
+        [order("buy", OrderType::Buy, price + Decimal::ONE), order("sell", OrderType::Sell, price)]
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_orders_cross() {
+        let orders = seed_orders(3);
+
+        assert_eq!(orders.len(), 6);
+        for pair in orders.chunks(2) {
+            assert!(pair[0].price >= pair[1].price);
+            assert_eq!(pair[0].amount, pair[1].amount);
+        }
+    }
+
+    #[test]
+    fn test_devnet_config_is_valid() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = devnet_config(dir.path());
+
+        assert!(config.validate().is_ok());
+        assert!(config.storage.data_dir.starts_with(&*dir.path().to_string_lossy()));
+    }
+}
@@ -0,0 +1,104 @@
+use serde_json::{json, Value};
+use std::path::Path;
+
+use crate::config::Settings;
+use crate::ethereum::JsonRpc;
+use super::Report;
+
+/// Run every check against `config`; network checks are skipped if the RPC is unreachable
+pub async fn run(config: &Settings) -> Report {
+    let mut report = Report::default();
+
+    match config.validate() {
+        Ok(()) => report.pass("config", "Settings are valid"),
+        Err(e) => report.fail("config", e.to_string()),
+    }
+
+    check_files(config, &mut report);
+
+    let rpc = JsonRpc::new(&config.ethereum.rpc_url);
+    if check_rpc(&rpc, &mut report).await {
+        check_contracts(config, &rpc, &mut report).await;
+    }
+
+    report
+}
+
+fn check_files(config: &Settings, report: &mut Report) {
+    let required = [
+        ("proofs.circuit_path", config.proofs.circuit_path.as_str()),
+        ("proofs.proving_key_path", config.proofs.proving_key_path.as_str()),
+        ("proofs.verification_key_path", config.proofs.verification_key_path.as_str()),
+    ];
+    for (field, path) in required {
+        check_path(report, field, path, true);
+    }
+
+    for tls in [&config.api.tls, &config.grpc.tls].into_iter().flatten() {
+        check_path(report, "tls.cert_path", &tls.cert_path, true);
+        check_path(report, "tls.key_path", &tls.key_path, true);
+    }
+
+    // Created on first start, so only worth a warning
+    check_path(report, "storage.data_dir", &config.storage.data_dir, false);
+    if config.audit.enabled {
+        check_path(report, "audit.path", &config.audit.path, false);
+    }
+    if config.accounting.enabled {
+        check_path(report, "accounting.path", &config.accounting.path, false);
+    }
+}
+
+fn check_path(report: &mut Report, field: &str, path: &str, required: bool) {
+    if Path::new(path).exists() {
+        report.pass("files", format!("{} exists: {}", field, path));
+    } else if required {
+        report.fail("files", format!("{} not found: {}", field, path));
+    } else {
+        report.warn("files", format!("{} does not exist yet and will be created: {}", field, path));
+    }
+}
+
+async fn check_rpc(rpc: &JsonRpc, report: &mut Report) -> bool {
+    let chain_id = match rpc.call("eth_chainId", json!([])).await {
+        Ok(chain_id) => chain_id,
+        Err(e) => {
+            report.fail("ethereum", format!("RPC unreachable at {}: {}", rpc.url, e));
+            return false;
+        }
+    };
+
+    match rpc.call("eth_blockNumber", json!([])).await {
+        Ok(block) => report.pass("ethereum", format!(
+            "RPC reachable at {} (chain {}, block {})",
+            rpc.url, hex_quantity(&chain_id), hex_quantity(&block),
+        )),
+        Err(e) => report.fail("ethereum", format!("eth_blockNumber failed: {}", e)),
+    }
+    true
+}
+
+async fn check_contracts(config: &Settings, rpc: &JsonRpc, report: &mut Report) {
+    let contracts = [
+        ("service manager", &config.ethereum.service_manager_address),
+        ("hook", &config.ethereum.eigenvault_hook_address),
+        ("order vault", &config.ethereum.order_vault_address),
+    ];
+
+    for (name, address) in contracts {
+        match rpc.call("eth_getCode", json!([address, "latest"])).await {
+            Ok(Value::String(code)) if code.len() > 2 => {
+                report.pass("contracts", format!("{} at {} has {} bytes of code", name, address, (code.len() - 2) / 2));
+            }
+            Ok(_) => report.fail("contracts", format!("No contract code at {} address {}", name, address)),
+            Err(e) => report.fail("contracts", format!("eth_getCode for {} failed: {}", name, e)),
+        }
+    }
+}
+
+fn hex_quantity(value: &Value) -> String {
+    value.as_str()
+        .and_then(|hex| u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| value.to_string())
+}
@@ -0,0 +1,5 @@
+pub mod checks;
+pub mod report;
+
+pub use checks::run;
+pub use report::{Check, Level, Report};
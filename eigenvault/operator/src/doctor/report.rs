@@ -0,0 +1,96 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Level::Pass => "PASS",
+            Level::Warn => "WARN",
+            Level::Fail => "FAIL",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Outcome of a single check
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub category: &'static str,
+    pub level: Level,
+    pub message: String,
+}
+
+/// Checks in the order they ran
+#[derive(Debug, Default)]
+pub struct Report {
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    pub fn pass(&mut self, category: &'static str, message: impl Into<String>) {
+        self.push(category, Level::Pass, message);
+    }
+
+    pub fn warn(&mut self, category: &'static str, message: impl Into<String>) {
+        self.push(category, Level::Warn, message);
+    }
+
+    pub fn fail(&mut self, category: &'static str, message: impl Into<String>) {
+        self.push(category, Level::Fail, message);
+    }
+
+    fn push(&mut self, category: &'static str, level: Level, message: impl Into<String>) {
+        self.checks.push(Check { category, level, message: message.into() });
+    }
+
+    pub fn count(&self, level: Level) -> usize {
+        self.checks.iter().filter(|check| check.level == level).count()
+    }
+
+    /// Worst level seen; `Pass` for an empty report
+    pub fn worst(&self) -> Level {
+        self.checks.iter().map(|check| check.level).max().unwrap_or(Level::Pass)
+    }
+
+    pub fn print(&self) {
+        let mut category = "";
+        for check in &self.checks {
+            if check.category != category {
+                category = check.category;
+                println!("{}", category);
+            }
+            println!("  [{}] {}", check.level, check.message);
+        }
+
+        println!();
+        println!(
+            "{} passed, {} warnings, {} failed",
+            self.count(Level::Pass), self.count(Level::Warn), self.count(Level::Fail),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worst_level() {
+        let mut report = Report::default();
+        assert_eq!(report.worst(), Level::Pass);
+
+        report.pass("config", "ok");
+        report.warn("files", "missing");
+        assert_eq!(report.worst(), Level::Warn);
+
+        report.fail("ethereum", "down");
+        assert_eq!(report.worst(), Level::Fail);
+        assert_eq!(report.count(Level::Pass), 1);
+    }
+}
@@ -0,0 +1,131 @@
+use anyhow::Result;
+use sha3::{Digest, Keccak256};
+
+/// Size of an ABI word
+const WORD: usize = 32;
+
+/// Argument of a contract call
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Address([u8; 20]),
+    Uint(u128),
+    Bytes32([u8; 32]),
+    Bytes(Vec<u8>),
+    /// Struct argument, encoded like its members' own tuple
+    Tuple(Vec<Token>),
+}
+
+impl Token {
+    fn is_dynamic(&self) -> bool {
+        match self {
+            Token::Bytes(_) => true,
+            Token::Tuple(members) => members.iter().any(Token::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// Bytes the token takes in the head of an enclosing tuple
+    fn head_len(&self) -> usize {
+        match self {
+            Token::Tuple(members) if !self.is_dynamic() => members.iter().map(Token::head_len).sum(),
+            _ => WORD,
+        }
+    }
+}
+
+/// First four bytes of the keccak hash of a function signature such as `transfer(address,uint256)`
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = Keccak256::digest(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Calldata calling `signature` with `tokens`
+pub fn encode_call(signature: &str, tokens: &[Token]) -> Vec<u8> {
+    [selector(signature).as_slice(), &encode(tokens)].concat()
+}
+
+/// ABI encoding of `tokens` as a tuple: a head per token, holding static values in place and
+/// the offset of a tail for dynamic ones, which is length-prefixed and zero-padded for bytes
+pub fn encode(tokens: &[Token]) -> Vec<u8> {
+    let head_len: usize = tokens.iter().map(Token::head_len).sum();
+    let mut head = Vec::with_capacity(head_len);
+    let mut tail = Vec::new();
+
+    for token in tokens {
+        if token.is_dynamic() {
+            head.extend_from_slice(&uint_word((head_len + tail.len()) as u128));
+        }
+        match token {
+            Token::Address(address) => {
+                let mut word = [0u8; WORD];
+                word[12..].copy_from_slice(address);
+                head.extend_from_slice(&word);
+            }
+            Token::Uint(value) => head.extend_from_slice(&uint_word(*value)),
+            Token::Bytes32(value) => head.extend_from_slice(value),
+            Token::Bytes(bytes) => {
+                tail.extend_from_slice(&uint_word(bytes.len() as u128));
+                tail.extend_from_slice(bytes);
+                tail.resize(tail.len().div_ceil(WORD) * WORD, 0);
+            }
+            Token::Tuple(members) if token.is_dynamic() => tail.extend_from_slice(&encode(members)),
+            Token::Tuple(members) => head.extend_from_slice(&encode(members)),
+        }
+    }
+
+    head.extend_from_slice(&tail);
+    head
+}
+
+fn uint_word(value: u128) -> [u8; WORD] {
+    let mut word = [0u8; WORD];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// Parse a `0x`-prefixed 32-byte hex value such as a task or order ID
+pub fn bytes32(value: &str) -> Result<[u8; 32]> {
+    hex::decode(value.trim_start_matches("0x"))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("{} is not a bytes32", value))
+}
+
+/// Parse a `0x`-prefixed address
+pub fn address(value: &str) -> Result<[u8; 20]> {
+    hex::decode(value.trim_start_matches("0x"))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Invalid address {}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selector() {
+        assert_eq!(hex::encode(selector("transfer(address,uint256)")), "a9059cbb");
+    }
+
+    #[test]
+    fn test_dynamic_bytes_follow_heads() {
+        let encoded = encode(&[Token::Bytes32([7u8; 32]), Token::Bytes(vec![1, 2, 3]), Token::Uint(5)]);
+
+        assert_eq!(encoded.len(), 5 * WORD);
+        assert_eq!(&encoded[WORD..2 * WORD], &uint_word(3 * WORD as u128));
+        assert_eq!(&encoded[2 * WORD..3 * WORD], &uint_word(5));
+        assert_eq!(&encoded[3 * WORD..4 * WORD], &uint_word(3));
+        assert_eq!(&encoded[4 * WORD..4 * WORD + 4], &[1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn test_tuples_inline_when_static_and_offset_when_dynamic() {
+        let fixed = Token::Tuple(vec![Token::Uint(1), Token::Uint(2)]);
+        let dynamic = Token::Tuple(vec![Token::Bytes(vec![9]), Token::Uint(3)]);
+        let encoded = encode(&[fixed, dynamic, Token::Uint(4)]);
+
+        assert_eq!(&encoded[..2 * WORD], &[uint_word(1), uint_word(2)].concat()[..]);
+        assert_eq!(&encoded[2 * WORD..3 * WORD], &uint_word(4 * WORD as u128));
+        assert_eq!(&encoded[3 * WORD..4 * WORD], &uint_word(4));
+        assert_eq!(&encoded[4 * WORD..], &encode(&[Token::Bytes(vec![9]), Token::Uint(3)])[..]);
+    }
+}
@@ -0,0 +1,222 @@
+//! Typed calls into the EigenVault contracts. Each function mirrors one Solidity function of
+//! the same name, so passing the wrong arguments is a compile error instead of a revert.
+
+use super::abi::{self, Token};
+
+/// Encoded call to a contract function
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    /// Solidity signature, e.g. `orderVault()`
+    pub signature: &'static str,
+    pub data: Vec<u8>,
+}
+
+impl Call {
+    fn new(signature: &'static str, tokens: &[Token]) -> Self {
+        Self { signature, data: abi::encode_call(signature, tokens) }
+    }
+}
+
+/// `EigenVaultServiceManager`
+pub mod service_manager {
+    use super::*;
+
+    pub fn submit_task_response(task_id: [u8; 32], response: Vec<u8>, result_hash: [u8; 32]) -> Call {
+        Call::new("submitTaskResponse(bytes32,bytes,bytes32)", &[
+            Token::Bytes32(task_id),
+            Token::Bytes(response),
+            Token::Bytes32(result_hash),
+        ])
+    }
+
+    pub fn eigen_vault_hook() -> Call {
+        Call::new("eigenVaultHook()", &[])
+    }
+
+    pub fn order_vault() -> Call {
+        Call::new("orderVault()", &[])
+    }
+}
+
+/// `EigenVaultHook`
+pub mod hook {
+    use super::*;
+
+    pub fn execute_vault_order(order_id: [u8; 32], proof: Vec<u8>, signatures: Vec<u8>) -> Call {
+        Call::new("executeVaultOrder(bytes32,bytes,bytes)", &[
+            Token::Bytes32(order_id),
+            Token::Bytes(proof),
+            Token::Bytes(signatures),
+        ])
+    }
+
+    pub fn service_manager() -> Call {
+        Call::new("serviceManager()", &[])
+    }
+
+    pub fn order_vault() -> Call {
+        Call::new("orderVault()", &[])
+    }
+}
+
+/// `OrderVault`
+pub mod order_vault {
+    use super::*;
+
+    pub fn authorized_hooks(hook: [u8; 20]) -> Call {
+        Call::new("authorizedHooks(address)", &[Token::Address(hook)])
+    }
+}
+
+/// EigenLayer `AVSDirectory`
+pub mod avs_directory {
+    use super::*;
+
+    pub fn calculate_operator_avs_registration_digest_hash(
+        operator: [u8; 20],
+        avs: [u8; 20],
+        salt: [u8; 32],
+        expiry: u64,
+    ) -> Call {
+        Call::new("calculateOperatorAVSRegistrationDigestHash(address,address,bytes32,uint256)", &[
+            Token::Address(operator),
+            Token::Address(avs),
+            Token::Bytes32(salt),
+            Token::Uint(expiry as u128),
+        ])
+    }
+}
+
+/// EigenLayer middleware `ECDSAStakeRegistry`
+pub mod stake_registry {
+    use super::*;
+
+    /// `signature`, `salt` and `expiry` make up the `SignatureWithSaltAndExpiry` struct
+    pub fn register_operator_with_signature(
+        signature: Vec<u8>,
+        salt: [u8; 32],
+        expiry: u64,
+        signing_key: [u8; 20],
+    ) -> Call {
+        Call::new("registerOperatorWithSignature((bytes,bytes32,uint256),address)", &[
+            Token::Tuple(vec![Token::Bytes(signature), Token::Bytes32(salt), Token::Uint(expiry as u128)]),
+            Token::Address(signing_key),
+        ])
+    }
+
+    pub fn deregister_operator() -> Call {
+        Call::new("deregisterOperator()", &[])
+    }
+
+    pub fn get_operator_weight(operator: [u8; 20]) -> Call {
+        Call::new("getOperatorWeight(address)", &[Token::Address(operator)])
+    }
+
+    pub fn get_last_checkpoint_total_weight() -> Call {
+        Call::new("getLastCheckpointTotalWeight()", &[])
+    }
+
+    pub fn minimum_weight() -> Call {
+        Call::new("minimumWeight()", &[])
+    }
+}
+
+/// EigenLayer `DelegationManager`
+pub mod delegation_manager {
+    use super::*;
+
+    pub fn min_withdrawal_delay_blocks() -> Call {
+        Call::new("minWithdrawalDelayBlocks()", &[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+    use std::path::{Path, PathBuf};
+
+    fn contracts_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("..")
+    }
+
+    /// Signatures of the functions in the forge artifact of `contract`, with the selector solc
+    /// computed for each
+    fn forge_selectors(contract: &str) -> serde_json::Map<String, Value> {
+        let path = contracts_dir().join(format!("contracts/out/{0}.sol/{0}.json", contract));
+        let artifact: Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        artifact["methodIdentifiers"].as_object().unwrap().clone()
+    }
+
+    /// Signatures of the functions in the ABI that EigenLayer compiled `contract` to, as kept
+    /// in its Go bindings
+    fn eigenlayer_signatures(contract: &str) -> Vec<String> {
+        let path = contracts_dir().join(format!("lib/eigenlayer-contracts/pkg/bindings/{}/binding.go", contract));
+        let source = std::fs::read_to_string(&path).unwrap();
+        let quoted = source.lines()
+            .find_map(|line| line.trim().strip_prefix("ABI: "))
+            .unwrap()
+            .trim_end_matches(',');
+        let abi: Value = serde_json::from_str(&serde_json::from_str::<String>(quoted).unwrap()).unwrap();
+        abi.as_array().unwrap().iter()
+            .filter(|item| item["type"] == "function")
+            .map(|function| format!("{}({})", function["name"].as_str().unwrap(), parameter_types(&function["inputs"])))
+            .collect()
+    }
+
+    fn parameter_types(parameters: &Value) -> String {
+        let types: Vec<String> = parameters.as_array().unwrap().iter()
+            .map(|parameter| {
+                let kind = parameter["type"].as_str().unwrap();
+                match kind.strip_prefix("tuple") {
+                    Some(array) => format!("({}){}", parameter_types(&parameter["components"]), array),
+                    None => kind.to_string(),
+                }
+            })
+            .collect();
+        types.join(",")
+    }
+
+    #[test]
+    fn test_calls_match_the_compiled_contracts() {
+        let calls = [
+            ("EigenVaultServiceManager", service_manager::submit_task_response([0; 32], Vec::new(), [0; 32])),
+            ("EigenVaultServiceManager", service_manager::eigen_vault_hook()),
+            ("EigenVaultServiceManager", service_manager::order_vault()),
+            ("EigenVaultHook", hook::execute_vault_order([0; 32], Vec::new(), Vec::new())),
+            ("EigenVaultHook", hook::service_manager()),
+            ("EigenVaultHook", hook::order_vault()),
+            ("OrderVault", order_vault::authorized_hooks([0; 20])),
+        ];
+        for (contract, call) in calls {
+            let selectors = forge_selectors(contract);
+            let selector = selectors.get(call.signature)
+                .unwrap_or_else(|| panic!("{} has no function {}", contract, call.signature));
+            assert_eq!(selector.as_str(), Some(hex::encode(&call.data[..4]).as_str()), "{}", call.signature);
+        }
+
+        // No compiled ECDSAStakeRegistry ships with the middleware, so its calls go unchecked
+        let calls = [
+            ("AVSDirectory", avs_directory::calculate_operator_avs_registration_digest_hash([0; 20], [0; 20], [0; 32], 0)),
+            ("DelegationManager", delegation_manager::min_withdrawal_delay_blocks()),
+        ];
+        for (contract, call) in calls {
+            assert!(
+                eigenlayer_signatures(contract).iter().any(|signature| signature == call.signature),
+                "{} has no function {}", contract, call.signature,
+            );
+        }
+    }
+
+    #[test]
+    fn test_call_starts_with_selector_of_signature() {
+        let call = hook::execute_vault_order([1u8; 32], vec![2; 40], vec![3; 65]);
+
+        assert_eq!(&call.data[..4], &abi::selector(call.signature));
+        assert_eq!(&call.data[4..], &abi::encode(&[
+            Token::Bytes32([1u8; 32]),
+            Token::Bytes(vec![2; 40]),
+            Token::Bytes(vec![3; 65]),
+        ])[..]);
+    }
+}
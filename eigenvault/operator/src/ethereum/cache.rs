@@ -0,0 +1,300 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::future::join_all;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tracing::debug;
+
+use crate::config::ChainCacheConfig;
+use crate::metrics::metrics;
+use crate::sim::clock;
+use super::chain::ContractApi;
+use super::client::{SlashingEvent, TaskInfo, TransactionReceipt};
+use super::registration::OperatorRegistration;
+
+/// Values of one read, keyed by its argument, each fresh for `ttl` after it was read
+struct TtlMap<K, V> {
+    ttl: Duration,
+    entries: Mutex<HashMap<K, (V, Duration)>>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> TtlMap<K, V> {
+    fn new(ttl_seconds: u64) -> Self {
+        Self {
+            ttl: Duration::from_secs(ttl_seconds),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, key: &K) -> Option<V> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(key)
+            .filter(|(_, expires_at)| *expires_at > clock::elapsed())
+            .map(|(value, _)| value.clone())
+    }
+
+    fn insert(&self, key: K, value: V) {
+        self.entries.lock().unwrap().insert(key, (value, clock::elapsed() + self.ttl));
+    }
+
+    /// Keys read before whose values have gone stale
+    fn expired(&self) -> Vec<K> {
+        let now = clock::elapsed();
+        self.entries.lock().unwrap().iter()
+            .filter(|(_, (_, expires_at))| *expires_at <= now)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    fn prune(&self) {
+        let now = clock::elapsed();
+        self.entries.lock().unwrap().retain(|_, (_, expires_at)| *expires_at > now);
+    }
+
+    fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// `ContractApi` that answers repeated reads from memory.
+///
+/// Stakes and task details are kept for their configured TTL; the chain ID and hook address
+/// never change for a deployment and are read once. A stake read that misses refreshes every
+/// other stale stake in the same round of concurrent calls, so a cycle touching several
+/// operators costs one round trip. Writes always go through, and a deposit drops cached stakes.
+pub struct CachedContracts<A> {
+    inner: A,
+    enabled: bool,
+    chain_id: OnceCell<u64>,
+    hook_address: OnceCell<String>,
+    stakes: TtlMap<String, u128>,
+    minimum_stake: TtlMap<(), u128>,
+    total_stake: TtlMap<(), u128>,
+    tasks: TtlMap<String, TaskInfo>,
+}
+
+impl<A: ContractApi> CachedContracts<A> {
+    pub fn new(inner: A, config: &ChainCacheConfig) -> Self {
+        Self {
+            inner,
+            enabled: config.enabled,
+            chain_id: OnceCell::new(),
+            hook_address: OnceCell::new(),
+            stakes: TtlMap::new(config.stake_ttl_seconds),
+            minimum_stake: TtlMap::new(config.stake_ttl_seconds),
+            total_stake: TtlMap::new(config.stake_ttl_seconds),
+            tasks: TtlMap::new(config.task_ttl_seconds),
+        }
+    }
+
+    /// Serve `key` from `map` if fresh, else read it with `read` and remember it
+    async fn cached<K, V, F>(&self, read_name: &str, map: &TtlMap<K, V>, key: K, read: F) -> Result<V>
+    where
+        K: Eq + Hash + Clone,
+        V: Clone,
+        F: std::future::Future<Output = Result<V>>,
+    {
+        if let Some(value) = map.get(&key) {
+            record(read_name, "hit");
+            return Ok(value);
+        }
+        record(read_name, "miss");
+        let value = read.await?;
+        map.insert(key, value.clone());
+        Ok(value)
+    }
+}
+
+fn record(read_name: &str, result: &str) {
+    metrics().chain_cache_reads.with_label_values(&[read_name, result]).inc();
+}
+
+#[async_trait]
+impl<A: ContractApi> ContractApi for CachedContracts<A> {
+    async fn get_latest_block_number(&self) -> Result<u64> {
+        self.inner.get_latest_block_number().await
+    }
+
+    async fn get_chain_id(&self) -> Result<u64> {
+        if !self.enabled {
+            return self.inner.get_chain_id().await;
+        }
+        self.chain_id.get_or_try_init(|| self.inner.get_chain_id()).await.copied()
+    }
+
+    async fn registration_digest(&self, operator: &str, salt: [u8; 32], expiry: u64) -> Result<[u8; 32]> {
+        self.inner.registration_digest(operator, salt, expiry).await
+    }
+
+    async fn register_operator(&self, registration: &OperatorRegistration) -> Result<String> {
+        let tx_hash = self.inner.register_operator(registration).await?;
+        self.stakes.clear();
+        self.total_stake.clear();
+        Ok(tx_hash)
+    }
+
+    async fn deregister_operator(&self, operator: &str) -> Result<String> {
+        let tx_hash = self.inner.deregister_operator(operator).await?;
+        self.stakes.clear();
+        self.total_stake.clear();
+        Ok(tx_hash)
+    }
+
+    async fn withdrawal_delay_blocks(&self) -> Result<u64> {
+        self.inner.withdrawal_delay_blocks().await
+    }
+
+    async fn update_operator_metadata_uri(&self, uri: &str) -> Result<String> {
+        self.inner.update_operator_metadata_uri(uri).await
+    }
+
+    async fn update_order_key_uri(&self, uri: &str, version: u64) -> Result<String> {
+        self.inner.update_order_key_uri(uri, version).await
+    }
+
+    async fn get_order_key_uri(&self) -> Result<Option<(String, u64)>> {
+        self.inner.get_order_key_uri().await
+    }
+
+    async fn deposit_into_strategy(&self, strategy: &str, amount_wei: u128) -> Result<String> {
+        let tx_hash = self.inner.deposit_into_strategy(strategy, amount_wei).await?;
+        self.stakes.clear();
+        self.total_stake.clear();
+        Ok(tx_hash)
+    }
+
+    async fn submit_task_response(
+        &self,
+        task_id: &str,
+        matches_data: &[u8],
+        proof_data: &[u8],
+        operator_signature: &[u8],
+    ) -> Result<String> {
+        self.inner.submit_task_response(task_id, matches_data, proof_data, operator_signature).await
+    }
+
+    async fn execute_vault_order(&self, order_id: &str, proof: &[u8], signatures: &[u8]) -> Result<String> {
+        self.inner.execute_vault_order(order_id, proof, signatures).await
+    }
+
+    async fn store_order(&self, order_id: &str, trader: &str, encrypted_order: &[u8], deadline: u64) -> Result<String> {
+        self.inner.store_order(order_id, trader, encrypted_order, deadline).await
+    }
+
+    async fn get_task(&self, task_id: &str) -> Result<TaskInfo> {
+        if !self.enabled {
+            return self.inner.get_task(task_id).await;
+        }
+        // Tasks are only looked up while they are worked on, so stale ones are dropped
+        self.tasks.prune();
+        self.cached("task", &self.tasks, task_id.to_string(), self.inner.get_task(task_id)).await
+    }
+
+    async fn retrieve_order(&self, order_id: &str) -> Result<Vec<u8>> {
+        self.inner.retrieve_order(order_id).await
+    }
+
+    async fn get_operator_stake(&self, operator: &str) -> Result<u128> {
+        if !self.enabled {
+            return self.inner.get_operator_stake(operator).await;
+        }
+        if let Some(stake) = self.stakes.get(&operator.to_string()) {
+            record("operator_stake", "hit");
+            return Ok(stake);
+        }
+        record("operator_stake", "miss");
+
+        let mut operators = vec![operator.to_string()];
+        operators.extend(self.stakes.expired().into_iter().filter(|stale| stale != operator));
+        if operators.len() > 1 {
+            debug!("Refreshing {} stale operator stakes", operators.len());
+        }
+        let reads = join_all(operators.iter().map(|operator| self.inner.get_operator_stake(operator))).await;
+
+        let mut requested = None;
+        for (operator, read) in operators.into_iter().zip(reads) {
+            if let Ok(stake) = &read {
+                self.stakes.insert(operator, *stake);
+            }
+            requested.get_or_insert(read);
+        }
+        requested.expect("the requested operator is read first")
+    }
+
+    async fn get_minimum_stake(&self) -> Result<u128> {
+        if !self.enabled {
+            return self.inner.get_minimum_stake().await;
+        }
+        self.cached("minimum_stake", &self.minimum_stake, (), self.inner.get_minimum_stake()).await
+    }
+
+    async fn get_total_stake(&self) -> Result<u128> {
+        if !self.enabled {
+            return self.inner.get_total_stake().await;
+        }
+        self.cached("total_stake", &self.total_stake, (), self.inner.get_total_stake()).await
+    }
+
+    async fn get_hook_address(&self) -> Result<String> {
+        if !self.enabled {
+            return self.inner.get_hook_address().await;
+        }
+        self.hook_address.get_or_try_init(|| self.inner.get_hook_address()).await.cloned()
+    }
+
+    async fn get_transaction_receipt(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>> {
+        self.inner.get_transaction_receipt(tx_hash).await
+    }
+
+    async fn get_slashing_events(&self, from_block: u64, to_block: u64) -> Result<Vec<SlashingEvent>> {
+        self.inner.get_slashing_events(from_block, to_block).await
+    }
+
+    async fn get_pending_tasks_for_operator(&self, operator: &str) -> Result<Vec<TaskInfo>> {
+        self.inner.get_pending_tasks_for_operator(operator).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethereum::MockChain;
+
+    #[tokio::test]
+    async fn test_stakes_cached_until_deposit() -> Result<()> {
+        let chain = MockChain::new();
+        let cached = CachedContracts::new(chain.clone(), &ChainCacheConfig::default());
+        chain.set_minimum_stake(10);
+
+        assert_eq!(cached.get_minimum_stake().await?, 10);
+        chain.set_minimum_stake(20);
+        assert_eq!(cached.get_minimum_stake().await?, 10);
+
+        assert_eq!(cached.get_operator_stake("0xabc").await?, 0);
+        // A deposit elsewhere isn't seen until the stake expires, one through the cache is
+        chain.deposit_into_strategy("strategy", 5).await?;
+        assert_eq!(cached.get_operator_stake("0xabc").await?, 0);
+        cached.deposit_into_strategy("strategy", 5).await?;
+        assert_eq!(cached.get_operator_stake("0xabc").await?, 10);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_disabled_or_expired_reads_go_to_chain() -> Result<()> {
+        let chain = MockChain::new();
+        let expiring = CachedContracts::new(chain.clone(), &ChainCacheConfig { stake_ttl_seconds: 0, ..ChainCacheConfig::default() });
+        let disabled = CachedContracts::new(chain.clone(), &ChainCacheConfig { enabled: false, ..ChainCacheConfig::default() });
+        chain.set_minimum_stake(10);
+        assert_eq!(expiring.get_minimum_stake().await?, 10);
+        assert_eq!(disabled.get_minimum_stake().await?, 10);
+
+        chain.set_minimum_stake(20);
+        assert_eq!(expiring.get_minimum_stake().await?, 20);
+        assert_eq!(disabled.get_minimum_stake().await?, 20);
+        assert!(disabled.get_task("task_1").await.is_err());
+        Ok(())
+    }
+}
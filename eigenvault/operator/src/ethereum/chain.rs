@@ -0,0 +1,120 @@
+use anyhow::Result;
+use async_trait::async_trait;
+
+use crate::matching::OrderMatch;
+use crate::proofs::MatchingProof;
+use super::client::{SlashingEvent, TaskInfo, TransactionReceipt};
+use super::events::ChainEvent;
+use super::registration::OperatorRegistration;
+
+/// Contract calls behind `EthereumClient`: the EigenVault contract bindings
+/// in production, `MockChain` in tests
+#[async_trait]
+pub trait ContractApi: Send + Sync {
+    async fn get_latest_block_number(&self) -> Result<u64>;
+
+    async fn get_chain_id(&self) -> Result<u64>;
+
+    /// EIP-712 digest the AVSDirectory has `operator` sign to register with the AVS
+    async fn registration_digest(&self, operator: &str, salt: [u8; 32], expiry: u64) -> Result<[u8; 32]>;
+
+    async fn register_operator(&self, registration: &OperatorRegistration) -> Result<String>;
+
+    /// Remove `operator` from the AVS; it must be the account that signs
+    async fn deregister_operator(&self, operator: &str) -> Result<String>;
+
+    /// Blocks EigenLayer holds withdrawals for, during which an exited operator stays slashable
+    async fn withdrawal_delay_blocks(&self) -> Result<u64>;
+
+    async fn update_operator_metadata_uri(&self, uri: &str) -> Result<String>;
+
+    async fn update_order_key_uri(&self, uri: &str, version: u64) -> Result<String>;
+
+    /// Where the committee's current order key document is published, with its version
+    async fn get_order_key_uri(&self) -> Result<Option<(String, u64)>>;
+
+    async fn deposit_into_strategy(&self, strategy: &str, amount_wei: u128) -> Result<String>;
+
+    async fn submit_task_response(
+        &self,
+        task_id: &str,
+        matches_data: &[u8],
+        proof_data: &[u8],
+        operator_signature: &[u8],
+    ) -> Result<String>;
+
+    async fn execute_vault_order(&self, order_id: &str, proof: &[u8], signatures: &[u8]) -> Result<String>;
+
+    async fn store_order(&self, order_id: &str, trader: &str, encrypted_order: &[u8], deadline: u64) -> Result<String>;
+
+    async fn get_task(&self, task_id: &str) -> Result<TaskInfo>;
+
+    async fn retrieve_order(&self, order_id: &str) -> Result<Vec<u8>>;
+
+    /// Stake delegated to `operator`, in wei
+    async fn get_operator_stake(&self, operator: &str) -> Result<u128>;
+
+    /// Stake the AVS requires of each operator, in wei
+    async fn get_minimum_stake(&self) -> Result<u128>;
+
+    /// Stake of every operator registered with the AVS, in wei
+    async fn get_total_stake(&self) -> Result<u128>;
+
+    async fn get_hook_address(&self) -> Result<String>;
+
+    async fn get_transaction_receipt(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>>;
+
+    async fn get_slashing_events(&self, from_block: u64, to_block: u64) -> Result<Vec<SlashingEvent>>;
+
+    async fn get_pending_tasks_for_operator(&self, operator: &str) -> Result<Vec<TaskInfo>>;
+}
+
+/// Chain access used by the operator loops, so `Operator` can run against
+/// `MockChain` instead of a node
+#[async_trait]
+pub trait ChainClient: Send + Sync + 'static {
+    /// Events since the last call, in log order
+    async fn listen_for_events(&self) -> Result<Vec<ChainEvent>>;
+
+    fn last_processed_block(&self) -> u64;
+
+    /// Whether transactions are only logged; no confirmation will follow
+    fn is_dry_run(&self) -> bool;
+
+    async fn submit_matching_proof(
+        &self,
+        task_id: &str,
+        proof: Vec<u8>,
+        result_hash: &str,
+        operator_signatures: Vec<u8>,
+    ) -> Result<String>;
+
+    async fn submit_task_response(&self, task_id: &str, matches: Vec<OrderMatch>, proof: MatchingProof) -> Result<String>;
+
+    async fn retrieve_orders_for_task(&self, task_id: &str) -> Result<Vec<Vec<u8>>>;
+
+    async fn health_check(&self) -> Result<()>;
+
+    async fn get_pending_tasks(&self) -> Result<Vec<TaskInfo>>;
+
+    /// Register this client's operator with the AVS, waiting for confirmation, and return its
+    /// operator ID
+    async fn register_operator(&self) -> Result<String>;
+
+    /// Stake delegated to this client's operator, in wei
+    async fn operator_stake(&self) -> Result<u128>;
+
+    /// Stake delegated to `operator`, in wei
+    async fn stake_of(&self, operator: &str) -> Result<u128>;
+
+    /// Stake of every operator registered with the AVS, in wei
+    async fn total_stake(&self) -> Result<u128>;
+
+    async fn latest_block(&self) -> Result<u64>;
+
+    /// Operators slashed by the service manager in `from_block..=to_block`
+    async fn slashing_events(&self, from_block: u64, to_block: u64) -> Result<Vec<SlashingEvent>>;
+
+    /// Gas paid for the mined transaction `tx_hash`, in wei
+    async fn transaction_fee(&self, tx_hash: &str) -> Result<u128>;
+}
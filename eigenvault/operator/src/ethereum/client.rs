@@ -1,19 +1,32 @@
 use anyhow::Result;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tracing::{debug, info, warn, error};
-use tokio::time::{Duration, interval};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tracing::{debug, info, warn, instrument};
+use tokio::time::Duration;
 
+use crate::audit::{AuditLog, AuditOutcome, TransactionKind};
 use crate::config::EthereumConfig;
+use crate::sim::clock;
+use super::cache::CachedContracts;
+use super::chain::{ChainClient, ContractApi};
 use super::contracts::EigenVaultContracts;
-use super::events::{EthereumEvent, EventProcessor};
+use super::events::{ChainEvent, EventProcessor};
+use super::registration::{self, OperatorRegistration};
+use super::transactions::TransactionManager;
 
 /// Real Ethereum client for interacting with EigenVault contracts
-pub struct EthereumClient {
+pub struct EthereumClient<A = CachedContracts<EigenVaultContracts>> {
     config: EthereumConfig,
-    contracts: EigenVaultContracts,
+    contracts: A,
     event_processor: EventProcessor,
-    last_processed_block: u64,
+    /// Only advanced by the event listener task
+    last_processed_block: AtomicU64,
+    audit_log: Option<Arc<AuditLog>>,
+    /// Log transactions instead of broadcasting them
+    dry_run: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,15 +37,9 @@ pub struct ContractAddresses {
     pub pool_manager: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OperatorRegistration {
-    pub operator_address: String,
-    pub stake_amount: String,
-    pub registration_signature: String,
-    pub registration_timestamp: u64,
-}
-
 impl EthereumClient {
+    /// Client for the contracts configured in `config`, reached over its RPC endpoint with
+    /// repeated reads cached as set in `config.cache`
     pub async fn new(config: EthereumConfig) -> Result<Self> {
         info!("Initializing Ethereum client for RPC: {}", config.rpc_url);
         
@@ -42,10 +49,21 @@ impl EthereumClient {
             &config.eigenvault_hook_address,
             &config.service_manager_address,
             &config.order_vault_address,
-        ).await?;
+        ).await?
+        .with_transactions(TransactionManager::new(&config)?)
+        .with_eigenlayer(config.eigenlayer.clone());
+        contracts.verify_contracts(config.expected_chain_id).await?;
+        let contracts = CachedContracts::new(contracts, &config.cache);
 
+        Self::with_contracts(config, contracts).await
+    }
+}
+
+impl<A: ContractApi> EthereumClient<A> {
+    /// Client over any `ContractApi`, e.g. `MockChain` in tests
+    pub async fn with_contracts(config: EthereumConfig, contracts: A) -> Result<Self> {
         // Initialize event processor
-        let event_processor = EventProcessor::new(config.clone());
+        let event_processor = EventProcessor::new(config.clone()).await?;
 
         // Get latest block to start from
         let latest_block = contracts.get_latest_block_number().await?;
@@ -54,56 +72,220 @@ impl EthereumClient {
             config,
             contracts,
             event_processor,
-            last_processed_block: latest_block.saturating_sub(100), // Start 100 blocks ago
+            last_processed_block: AtomicU64::new(latest_block.saturating_sub(100)), // Start 100 blocks ago
+            audit_log: None,
+            dry_run: false,
         })
     }
 
     /// Listen for new events from EigenVault contracts
-    pub async fn listen_for_events(&mut self) -> Result<Vec<EthereumEvent>> {
+    pub async fn listen_for_events(&self) -> Result<Vec<ChainEvent>> {
         let current_block = self.contracts.get_latest_block_number().await?;
+        let last_processed_block = self.last_processed_block();
         
-        if current_block <= self.last_processed_block {
+        if current_block <= last_processed_block {
             // No new blocks to process
             return Ok(vec![]);
         }
 
         debug!(
             "Processing blocks {} to {}",
-            self.last_processed_block + 1,
+            last_processed_block + 1,
             current_block
         );
 
         let events = self.event_processor.get_events(
-            self.last_processed_block + 1,
+            last_processed_block + 1,
             current_block,
         ).await?;
 
-        self.last_processed_block = current_block;
+        self.last_processed_block.store(current_block, Ordering::SeqCst);
         
         info!("Found {} events in block range", events.len());
         Ok(events)
     }
 
-    /// Register operator with EigenVault AVS
-    pub async fn register_operator(&self) -> Result<()> {
+    /// Resume event processing after a durably checkpointed block
+    pub fn resume_from_block(&mut self, block: u64) {
+        info!("Resuming event processing from block {}", block + 1);
+        self.last_processed_block.store(block, Ordering::SeqCst);
+    }
+
+    /// Last block returned by `listen_for_events`
+    pub fn last_processed_block(&self) -> u64 {
+        self.last_processed_block.load(Ordering::SeqCst)
+    }
+
+    /// Record every signed transaction in `audit_log`
+    pub fn set_audit_log(&mut self, audit_log: Arc<AuditLog>) {
+        self.audit_log = Some(audit_log);
+    }
+
+    /// Process events, match and prove as usual but never broadcast a transaction
+    pub fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Log a transaction that would have been broadcast and return a placeholder hash
+    fn skip_broadcast(&self, kind: TransactionKind, subject: &str, payload: &[u8]) -> String {
+        info!(
+            "[dry-run] Would broadcast {:?} for {} ({} bytes of calldata)",
+            kind, subject, payload.len()
+        );
+        format!("dry-run:{}", subject)
+    }
+
+    fn audit(&self, kind: TransactionKind, subject: &str, payload: &[u8], tx_hash: Option<&str>, outcome: AuditOutcome) {
+        if let Some(audit_log) = &self.audit_log {
+            audit_log.record_or_log(kind, subject, payload, tx_hash, outcome);
+        }
+    }
+
+    /// Audit the broadcast of a transaction, passing the result through
+    fn audit_submission(&self, kind: TransactionKind, subject: &str, payload: &[u8], result: Result<String>) -> Result<String> {
+        match &result {
+            Ok(tx_hash) => self.audit(kind, subject, payload, Some(tx_hash), AuditOutcome::Submitted),
+            Err(e) => self.audit(kind, subject, payload, None, AuditOutcome::Failed { error: e.to_string() }),
+        }
+        result
+    }
+
+    /// Audit the confirmation of a broadcast transaction, passing the result through
+    fn audit_confirmation(&self, kind: TransactionKind, subject: &str, payload: &[u8], tx_hash: &str, result: Result<()>) -> Result<()> {
+        let outcome = match &result {
+            Ok(()) => AuditOutcome::Confirmed,
+            Err(e) => AuditOutcome::Failed { error: e.to_string() },
+        };
+        self.audit(kind, subject, payload, Some(tx_hash), outcome);
+        result
+    }
+
+    /// Register operator with EigenVault AVS, returning its EigenLayer operator ID
+    pub async fn register_operator(&self) -> Result<String> {
         info!("Registering operator with EigenVault AVS...");
 
-        // Generate registration signature
-        let registration_sig = self.generate_registration_signature().await?;
+        let registration = self.sign_registration().await?;
+        let operator = self.config.operator_address.clone();
+        let operator_id = registration::operator_id(&operator)?;
+        if self.dry_run {
+            self.skip_broadcast(TransactionKind::OperatorRegistration, &operator, &registration.signature);
+            return Ok(operator_id);
+        }
 
-        // Call service manager registration
-        let tx_hash = self.contracts.register_operator(registration_sig).await?;
+        let result = self.contracts.register_operator(&registration).await;
+        let tx_hash = self.audit_submission(TransactionKind::OperatorRegistration, &operator, &registration.signature, result)?;
         
         info!("Operator registration transaction: {}", tx_hash);
         
         // Wait for confirmation
-        self.wait_for_transaction_confirmation(&tx_hash, 5).await?;
+        let result = self.wait_for_transaction_confirmation(&tx_hash, 5).await;
+        self.audit_confirmation(TransactionKind::OperatorRegistration, &operator, &registration.signature, &tx_hash, result)?;
         
-        info!("Operator registration confirmed");
-        Ok(())
+        info!("Operator registration confirmed, operator ID {}", operator_id);
+        Ok(operator_id)
+    }
+
+    /// Deregister operator from EigenVault AVS
+    pub async fn deregister_operator(&self) -> Result<String> {
+        info!("Deregistering operator from EigenVault AVS...");
+        
+        let operator = self.config.operator_address.clone();
+        if self.dry_run {
+            return Ok(self.skip_broadcast(TransactionKind::OperatorDeregistration, &operator, &[]));
+        }
+        
+        let result = self.contracts.deregister_operator(&operator).await;
+        let tx_hash = self.audit_submission(TransactionKind::OperatorDeregistration, &operator, &[], result)?;
+        
+        let result = self.wait_for_transaction_confirmation(&tx_hash, 5).await;
+        self.audit_confirmation(TransactionKind::OperatorDeregistration, &operator, &[], &tx_hash, result)?;
+        
+        info!("Operator deregistration confirmed: {}", tx_hash);
+        Ok(tx_hash)
+    }
+
+    /// Wait until EigenLayer's withdrawal delay has passed since `tx_hash` was mined
+    pub async fn wait_for_withdrawal_delay(&self, tx_hash: &str) -> Result<()> {
+        let receipt = self.contracts.get_transaction_receipt(tx_hash).await?
+            .ok_or_else(|| anyhow::anyhow!("No receipt for transaction {}", tx_hash))?;
+        let delay = self.contracts.withdrawal_delay_blocks().await?;
+        let until_block = receipt.block_number + delay;
+        info!("Waiting out the withdrawal delay of {} blocks, until block {}", delay, until_block);
+        
+        loop {
+            let block = self.contracts.get_latest_block_number().await?;
+            if block >= until_block {
+                return Ok(());
+            }
+            debug!("At block {}, {} blocks of the withdrawal delay left", block, until_block - block);
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        }
+    }
+
+    /// Publish the operator's metadata URI on chain
+    pub async fn update_metadata_uri(&self, uri: &str) -> Result<String> {
+        info!("Publishing operator metadata URI {}", uri);
+        
+        let operator = self.config.operator_address.clone();
+        if self.dry_run {
+            return Ok(self.skip_broadcast(TransactionKind::MetadataUpdate, &operator, uri.as_bytes()));
+        }
+        
+        let result = self.contracts.update_operator_metadata_uri(uri).await;
+        let tx_hash = self.audit_submission(TransactionKind::MetadataUpdate, &operator, uri.as_bytes(), result)?;
+        
+        let result = self.wait_for_transaction_confirmation(&tx_hash, 5).await;
+        self.audit_confirmation(TransactionKind::MetadataUpdate, &operator, uri.as_bytes(), &tx_hash, result)?;
+        
+        info!("Operator metadata URI updated: {}", tx_hash);
+        Ok(tx_hash)
+    }
+
+    /// Publish where traders fetch version `version` of the committee order key
+    pub async fn update_order_key_uri(&self, uri: &str, version: u64) -> Result<String> {
+        info!("Publishing order key URI {} (version {})", uri, version);
+        
+        let operator = self.config.operator_address.clone();
+        let payload = format!("{}#{}", uri, version);
+        if self.dry_run {
+            return Ok(self.skip_broadcast(TransactionKind::OrderKeyUpdate, &operator, payload.as_bytes()));
+        }
+        
+        let result = self.contracts.update_order_key_uri(uri, version).await;
+        let tx_hash = self.audit_submission(TransactionKind::OrderKeyUpdate, &operator, payload.as_bytes(), result)?;
+        
+        let result = self.wait_for_transaction_confirmation(&tx_hash, 5).await;
+        self.audit_confirmation(TransactionKind::OrderKeyUpdate, &operator, payload.as_bytes(), &tx_hash, result)?;
+        
+        info!("Order key URI updated: {}", tx_hash);
+        Ok(tx_hash)
+    }
+
+    /// Deposit stake into an EigenLayer strategy so the operator has delegatable shares
+    pub async fn deposit_stake(&self, strategy: &str, amount_wei: u128) -> Result<String> {
+        info!("Depositing {} wei of stake into strategy {}", amount_wei, strategy);
+        
+        let amount = amount_wei.to_string();
+        if self.dry_run {
+            return Ok(self.skip_broadcast(TransactionKind::StakeDeposit, strategy, amount.as_bytes()));
+        }
+        
+        let result = self.contracts.deposit_into_strategy(strategy, amount_wei).await;
+        let tx_hash = self.audit_submission(TransactionKind::StakeDeposit, strategy, amount.as_bytes(), result)?;
+        
+        let result = self.wait_for_transaction_confirmation(&tx_hash, 5).await;
+        self.audit_confirmation(TransactionKind::StakeDeposit, strategy, amount.as_bytes(), &tx_hash, result)?;
+        
+        info!("Stake deposit confirmed: {}", tx_hash);
+        Ok(tx_hash)
     }
 
     /// Submit matching proof for a task
+    #[instrument(name = "tx.submit", skip(self, proof, operator_signatures))]
     pub async fn submit_matching_proof(
         &self,
         task_id: &str,
@@ -112,18 +294,23 @@ impl EthereumClient {
         operator_signatures: Vec<u8>,
     ) -> Result<String> {
         info!("Submitting matching proof for task: {}", task_id);
+        if self.dry_run {
+            return Ok(self.skip_broadcast(TransactionKind::TaskResponse, task_id, &proof));
+        }
 
-        let tx_hash = self.contracts.submit_task_response(
+        let result = self.contracts.submit_task_response(
             task_id,
             &proof, // matches_data
             &proof, // proof_data (using same for simplicity)
             &operator_signatures,
-        ).await?;
+        ).await;
+        let tx_hash = self.audit_submission(TransactionKind::TaskResponse, task_id, &proof, result)?;
 
         info!("Proof submission transaction: {}", tx_hash);
         
         // Wait for confirmation
-        self.wait_for_transaction_confirmation(&tx_hash, 3).await?;
+        let result = self.wait_for_transaction_confirmation(&tx_hash, 3).await;
+        self.audit_confirmation(TransactionKind::TaskResponse, task_id, &proof, &tx_hash, result)?;
         
         Ok(tx_hash)
     }
@@ -136,22 +323,28 @@ impl EthereumClient {
         signatures: Vec<u8>,
     ) -> Result<String> {
         info!("Executing vault order: {}", order_id);
+        if self.dry_run {
+            return Ok(self.skip_broadcast(TransactionKind::VaultExecution, order_id, &proof));
+        }
 
-        let tx_hash = self.contracts.execute_vault_order(
+        let result = self.contracts.execute_vault_order(
             order_id,
             &proof,
             &signatures,
-        ).await?;
+        ).await;
+        let tx_hash = self.audit_submission(TransactionKind::VaultExecution, order_id, &proof, result)?;
 
         info!("Order execution transaction: {}", tx_hash);
         
         // Wait for confirmation
-        self.wait_for_transaction_confirmation(&tx_hash, 3).await?;
+        let result = self.wait_for_transaction_confirmation(&tx_hash, 3).await;
+        self.audit_confirmation(TransactionKind::VaultExecution, order_id, &proof, &tx_hash, result)?;
         
         Ok(tx_hash)
     }
 
     /// Submit task response with proof and matches
+    #[instrument(name = "tx.submit", skip(self, matches, proof), fields(matches = matches.len()))]
     pub async fn submit_task_response(
         &self,
         task_id: &str,
@@ -163,14 +356,19 @@ impl EthereumClient {
         // Convert matches to serialized format for contract submission
         let matches_data = serde_json::to_vec(&matches)?;
         let proof_data = proof.proof_data;
+        let payload = [matches_data.as_slice(), proof_data.as_slice()].concat();
+        if self.dry_run {
+            return Ok(self.skip_broadcast(TransactionKind::TaskResponse, task_id, &payload));
+        }
         
         // Submit through the service manager contract
-        let tx_hash = self.contracts.submit_task_response(
+        let result = self.contracts.submit_task_response(
             task_id,
             &matches_data,
             &proof_data,
             &proof.operator_signature,
-        ).await?;
+        ).await;
+        let tx_hash = self.audit_submission(TransactionKind::TaskResponse, task_id, &payload, result)?;
         
         info!("Task response submitted: {}", tx_hash);
         Ok(tx_hash)
@@ -202,13 +400,22 @@ impl EthereumClient {
         Ok(encrypted_orders)
     }
 
+    pub async fn chain_id(&self) -> Result<u64> {
+        self.contracts.get_chain_id().await
+    }
+
     /// Check operator's current stake
-    pub async fn get_operator_stake(&self, operator: &str) -> Result<u64> {
+    pub async fn get_operator_stake(&self, operator: &str) -> Result<u128> {
         let stake = self.contracts.get_operator_stake(operator).await?;
         debug!("Operator {} stake: {}", operator, stake);
         Ok(stake)
     }
 
+    /// Stake the AVS requires of each operator, in wei
+    pub async fn minimum_stake(&self) -> Result<u128> {
+        self.contracts.get_minimum_stake().await
+    }
+
     /// Health check for Ethereum connection
     pub async fn health_check(&self) -> Result<()> {
         // Check if we can connect to the node
@@ -225,20 +432,6 @@ impl EthereumClient {
         Ok(())
     }
 
-    /// Monitor for slashing events
-    pub async fn monitor_slashing_events(&self) -> Result<Vec<SlashingEvent>> {
-        let events = self.contracts.get_slashing_events(
-            self.last_processed_block.saturating_sub(1000), // Look back 1000 blocks
-            self.last_processed_block,
-        ).await?;
-
-        if !events.is_empty() {
-            warn!("Detected {} slashing events", events.len());
-        }
-
-        Ok(events)
-    }
-
     /// Get pending tasks for this operator
     pub async fn get_pending_tasks(&self) -> Result<Vec<TaskInfo>> {
         let operator_address = &self.config.operator_address;
@@ -248,11 +441,22 @@ impl EthereumClient {
         Ok(tasks)
     }
 
+    /// Gas paid for the mined transaction `tx_hash`, in wei
+    pub async fn transaction_fee(&self, tx_hash: &str) -> Result<u128> {
+        self.contracts.get_transaction_receipt(tx_hash).await?
+            .map(|receipt| receipt.fee_wei())
+            .ok_or_else(|| anyhow::anyhow!("No receipt for transaction {}", tx_hash))
+    }
+
     /// Private helper methods
-    async fn generate_registration_signature(&self) -> Result<Vec<u8>> {
-        // In production, this would generate a proper EigenLayer registration signature
-        // For now, return a placeholder
-        Ok(vec![0u8; 64]) // 64-byte signature placeholder
+    /// Sign the AVSDirectory digest for registering this operator, under a fresh salt and
+    /// expiring after `eigenlayer.registration_expiry_seconds`
+    async fn sign_registration(&self) -> Result<OperatorRegistration> {
+        let operator = &self.config.operator_address;
+        let salt: [u8; 32] = rand::random();
+        let expiry = clock::unix_secs() + self.config.eigenlayer.registration_expiry_seconds;
+        let digest = self.contracts.registration_digest(operator, salt, expiry).await?;
+        OperatorRegistration::sign(operator, self.config.private_key.expose(), digest, salt, expiry)
     }
 
     async fn wait_for_transaction_confirmation(&self, tx_hash: &str, confirmations: u32) -> Result<()> {
@@ -292,12 +496,86 @@ impl EthereumClient {
     }
 }
 
-/// Slashing event information
+#[async_trait]
+impl<A: ContractApi + 'static> ChainClient for EthereumClient<A> {
+    async fn listen_for_events(&self) -> Result<Vec<ChainEvent>> {
+        EthereumClient::listen_for_events(self).await
+    }
+
+    fn last_processed_block(&self) -> u64 {
+        EthereumClient::last_processed_block(self)
+    }
+
+    fn is_dry_run(&self) -> bool {
+        EthereumClient::is_dry_run(self)
+    }
+
+    async fn submit_matching_proof(
+        &self,
+        task_id: &str,
+        proof: Vec<u8>,
+        result_hash: &str,
+        operator_signatures: Vec<u8>,
+    ) -> Result<String> {
+        EthereumClient::submit_matching_proof(self, task_id, proof, result_hash, operator_signatures).await
+    }
+
+    async fn submit_task_response(&self, task_id: &str, matches: Vec<crate::matching::OrderMatch>, proof: crate::proofs::MatchingProof) -> Result<String> {
+        EthereumClient::submit_task_response(self, task_id, matches, proof).await
+    }
+
+    async fn retrieve_orders_for_task(&self, task_id: &str) -> Result<Vec<Vec<u8>>> {
+        EthereumClient::retrieve_orders_for_task(self, task_id).await
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        EthereumClient::health_check(self).await
+    }
+
+    async fn get_pending_tasks(&self) -> Result<Vec<TaskInfo>> {
+        EthereumClient::get_pending_tasks(self).await
+    }
+
+    async fn register_operator(&self) -> Result<String> {
+        EthereumClient::register_operator(self).await
+    }
+
+    async fn operator_stake(&self) -> Result<u128> {
+        EthereumClient::get_operator_stake(self, &self.config.operator_address).await
+    }
+
+    async fn stake_of(&self, operator: &str) -> Result<u128> {
+        EthereumClient::get_operator_stake(self, operator).await
+    }
+
+    async fn total_stake(&self) -> Result<u128> {
+        self.contracts.get_total_stake().await
+    }
+
+    async fn latest_block(&self) -> Result<u64> {
+        self.contracts.get_latest_block_number().await
+    }
+
+    async fn slashing_events(&self, from_block: u64, to_block: u64) -> Result<Vec<SlashingEvent>> {
+        let events = self.contracts.get_slashing_events(from_block, to_block).await?;
+        if !events.is_empty() {
+            warn!("Detected {} slashing events in blocks {} to {}", events.len(), from_block, to_block);
+        }
+        Ok(events)
+    }
+
+    async fn transaction_fee(&self, tx_hash: &str) -> Result<u128> {
+        EthereumClient::transaction_fee(self, tx_hash).await
+    }
+}
+
+/// An `OperatorSlashed` event of the service manager
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SlashingEvent {
+    /// `0x`-prefixed, lowercase
     pub operator: String,
-    pub slash_amount: u64,
-    pub slash_type: u8,
+    pub amount_wei: u128,
+    pub reason: String,
     pub block_number: u64,
     pub transaction_hash: String,
 }
@@ -309,7 +587,7 @@ pub struct TaskInfo {
     pub orders_set_hash: String,
     pub deadline: u64,
     pub assigned_operators: Vec<String>,
-    pub minimum_stake: u64,
+    pub minimum_stake: u128,
     pub created_at: u64,
 }
 
@@ -320,6 +598,16 @@ pub struct TransactionReceipt {
     pub block_number: u64,
     pub confirmations: u32,
     pub status: bool,
+    pub gas_used: u64,
+    /// Wei paid per unit of gas
+    pub effective_gas_price: u64,
+}
+
+impl TransactionReceipt {
+    /// What the transaction cost its sender, in wei
+    pub fn fee_wei(&self) -> u128 {
+        u128::from(self.gas_used) * u128::from(self.effective_gas_price)
+    }
 }
 
 #[cfg(test)]
@@ -1,9 +1,23 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use sha3::{Digest, Keccak256};
 use std::collections::HashMap;
-use tracing::{debug, info, error};
+use std::sync::Arc;
+use serde_json::json;
+use tracing::{debug, info, warn};
 
+use super::abi::{self, Token};
+use super::bindings::{self, Call};
+use super::chain::ContractApi;
 use super::client::{TaskInfo, TransactionReceipt, SlashingEvent};
+use super::events;
+use super::registration::OperatorRegistration;
+use super::rpc::{quantity, JsonRpc};
+use super::transactions::TransactionManager;
+use crate::chaos;
+use crate::config::EigenLayerConfig;
+use crate::metrics::metrics;
 
 /// Contract manager for handling multiple contract interactions
 #[derive(Debug, Clone)]
@@ -56,10 +70,13 @@ pub enum ContractParameter {
 /// Real contract interfaces for EigenVault system
 #[derive(Debug, Clone)]
 pub struct EigenVaultContracts {
-    rpc_url: String,
+    rpc: JsonRpc,
     hook_address: String,
     service_manager_address: String,
     order_vault_address: String,
+    /// Signs the calls that change state; without one they fail
+    transactions: Option<Arc<TransactionManager>>,
+    eigenlayer: EigenLayerConfig,
     // In production, these would be actual ethers-rs contract instances
 }
 
@@ -73,66 +90,187 @@ impl EigenVaultContracts {
         info!("Initializing contract interfaces...");
         
         let contracts = Self {
-            rpc_url: rpc_url.to_string(),
+            rpc: JsonRpc::new(rpc_url),
             hook_address: hook_address.to_string(),
             service_manager_address: service_manager_address.to_string(),
             order_vault_address: order_vault_address.to_string(),
+            transactions: None,
+            eigenlayer: EigenLayerConfig::default(),
         };
-
-        // Verify contract addresses are valid
-        contracts.verify_contracts().await?;
         
         Ok(contracts)
     }
 
+    /// Send task responses and vault executions through `transactions`
+    pub fn with_transactions(mut self, transactions: TransactionManager) -> Self {
+        self.transactions = Some(Arc::new(transactions));
+        self
+    }
+
+    /// Register operators through the EigenLayer contracts in `eigenlayer`
+    pub fn with_eigenlayer(mut self, eigenlayer: EigenLayerConfig) -> Self {
+        self.eigenlayer = eigenlayer;
+        self
+    }
+
+    /// `address` of the EigenLayer contract configured as `ethereum.eigenlayer.<name>`
+    fn eigenlayer_address<'a>(name: &str, address: &'a str) -> Result<&'a str> {
+        if address.is_empty() {
+            anyhow::bail!("ethereum.eigenlayer.{} is not set", name);
+        }
+        Ok(address)
+    }
+
+    fn transactions(&self) -> Result<&TransactionManager> {
+        self.transactions.as_deref()
+            .ok_or_else(|| anyhow::anyhow!("No signing key configured for transactions"))
+    }
+}
+
+#[async_trait]
+impl ContractApi for EigenVaultContracts {
     /// Get the latest block number
-    pub async fn get_latest_block_number(&self) -> Result<u64> {
-        // In production, this would use ethers-rs to get the latest block
-        // For now, simulate with a reasonable block number
-        Ok(20000000) // Placeholder block number
+    async fn get_latest_block_number(&self) -> Result<u64> {
+        let _timer = metrics().rpc_latency_seconds.with_label_values(&["eth_blockNumber"]).start_timer();
+        quantity(&self.rpc.call("eth_blockNumber", json!([])).await?)
     }
 
     /// Get chain ID
-    pub async fn get_chain_id(&self) -> Result<u64> {
-        // Return chain ID based on network
-        if self.rpc_url.contains("holesky") {
-            Ok(17000) // Holesky testnet
-        } else if self.rpc_url.contains("unichain") {
-            Ok(1301) // Unichain Sepolia
-        } else {
-            Ok(1) // Mainnet
-        }
+    async fn get_chain_id(&self) -> Result<u64> {
+        let _timer = metrics().rpc_latency_seconds.with_label_values(&["eth_chainId"]).start_timer();
+        quantity(&self.rpc.call("eth_chainId", json!([])).await?)
     }
 
-    /// Register operator with service manager
-    pub async fn register_operator(&self, signature: Vec<u8>) -> Result<String> {
-        info!("Registering operator with service manager at: {}", self.service_manager_address);
+    /// Registration digest for the service manager, as computed by the AVSDirectory
+    async fn registration_digest(&self, operator: &str, salt: [u8; 32], expiry: u64) -> Result<[u8; 32]> {
+        let avs_directory = Self::eigenlayer_address("avs_directory", &self.eigenlayer.avs_directory)?;
+        let call = bindings::avs_directory::calculate_operator_avs_registration_digest_hash(
+            abi::address(operator)?,
+            abi::address(&self.service_manager_address)?,
+            salt,
+            expiry,
+        );
+        self.call_word(avs_directory, &call).await
+            .with_context(|| format!("AVSDirectory at {} does not answer {}", avs_directory, call.signature))
+    }
+
+    /// Register the operator with the AVS stake registry, signing task responses with its own key
+    async fn register_operator(&self, registration: &OperatorRegistration) -> Result<String> {
+        chaos::rpc_fault("registerOperatorWithSignature").await?;
+        let stake_registry = Self::eigenlayer_address("stake_registry", &self.eigenlayer.stake_registry)?;
+        info!("Registering operator {} with stake registry at: {}", registration.operator, stake_registry);
+        
+        let call = bindings::stake_registry::register_operator_with_signature(
+            registration.signature.clone(),
+            registration.salt,
+            registration.expiry,
+            abi::address(&registration.operator)?,
+        );
         
-        // In production, this would:
-        // 1. Create the transaction data for registerOperator()
-        // 2. Sign and submit the transaction
-        // 3. Return the transaction hash
+        let tx_hash = self.transactions()?.submit_and_confirm(stake_registry, call.data).await?;
+        info!("Operator registration transaction: {}", tx_hash);
+        
+        Ok(tx_hash)
+    }
+
+    /// Leave the AVS stake registry; EigenLayer takes the operator out of the AVS's quorum
+    async fn deregister_operator(&self, operator: &str) -> Result<String> {
+        chaos::rpc_fault("deregisterOperator").await?;
+        let stake_registry = Self::eigenlayer_address("stake_registry", &self.eigenlayer.stake_registry)?;
+        info!("Deregistering operator {} from stake registry at: {}", operator, stake_registry);
+        
+        let call = bindings::stake_registry::deregister_operator();
+        let tx_hash = self.transactions()?.submit_and_confirm(stake_registry, call.data).await?;
+        info!("Operator deregistration transaction: {}", tx_hash);
+        
+        Ok(tx_hash)
+    }
+
+    async fn withdrawal_delay_blocks(&self) -> Result<u64> {
+        let delegation_manager = Self::eigenlayer_address("delegation_manager", &self.eigenlayer.delegation_manager)?;
+        let call = bindings::delegation_manager::min_withdrawal_delay_blocks();
+        let word = self.call_word(delegation_manager, &call).await
+            .with_context(|| format!("DelegationManager at {} does not answer {}", delegation_manager, call.signature))?;
+        u64::try_from(uint(word)?)
+            .map_err(|_| anyhow::anyhow!("Withdrawal delay of {} out of range", hex::encode(word)))
+    }
+
+    /// Point the operator's metadata URI at `uri`; the service manager forwards it to EigenLayer
+    async fn update_operator_metadata_uri(&self, uri: &str) -> Result<String> {
+        chaos::rpc_fault("updateOperatorMetadataURI").await?;
+        info!("Updating operator metadata URI to {} via service manager at: {}", uri, self.service_manager_address);
+        let _timer = metrics().rpc_latency_seconds.with_label_values(&["updateOperatorMetadataURI"]).start_timer();
+        
+        // In production, this would call updateOperatorMetadataURI(string) on the service manager
+        
+        let tx_hash = format!("0x{:x}", rand::random::<u64>());
+        info!("Mock metadata update transaction: {}", tx_hash);
+        
+        Ok(tx_hash)
+    }
+
+    /// Point traders at version `version` of the committee order key, hosted at `uri`
+    async fn update_order_key_uri(&self, uri: &str, version: u64) -> Result<String> {
+        chaos::rpc_fault("updateOrderKeyURI").await?;
+        info!("Updating order key URI to {} (version {}) via service manager at: {}", uri, version, self.service_manager_address);
+        let _timer = metrics().rpc_latency_seconds.with_label_values(&["updateOrderKeyURI"]).start_timer();
+        
+        // In production, this would call updateOrderKeyURI(string,uint256) on the service manager
         
-        // For now, return a mock transaction hash
         let tx_hash = format!("0x{:x}", rand::random::<u64>());
-        info!("Mock registration transaction: {}", tx_hash);
+        info!("Mock order key update transaction: {}", tx_hash);
+        
+        Ok(tx_hash)
+    }
+
+    async fn get_order_key_uri(&self) -> Result<Option<(String, u64)>> {
+        chaos::rpc_fault("orderKeyURI").await?;
+        let _timer = metrics().rpc_latency_seconds.with_label_values(&["orderKeyURI"]).start_timer();
+        
+        // In production, this would call orderKeyURI() on the service manager; nothing is
+        // published until an operator runs `publish-order-key`
+        Ok(None)
+    }
+
+    /// Deposit `amount_wei` of the strategy's token through EigenLayer's strategy manager
+    async fn deposit_into_strategy(&self, strategy: &str, amount_wei: u128) -> Result<String> {
+        chaos::rpc_fault("depositIntoStrategy").await?;
+        info!("Depositing {} wei into strategy {}", amount_wei, strategy);
+        let _timer = metrics().rpc_latency_seconds.with_label_values(&["depositIntoStrategy"]).start_timer();
+        
+        // In production, this would approve the token and call depositIntoStrategy(strategy, token, amount)
+        
+        let tx_hash = format!("0x{:x}", rand::random::<u64>());
+        info!("Mock stake deposit transaction: {}", tx_hash);
         
         Ok(tx_hash)
     }
 
     /// Submit task response to service manager
-    pub async fn submit_task_response(
+    async fn submit_task_response(
         &self,
         task_id: &str,
         matches_data: &[u8],
         proof_data: &[u8],
         operator_signature: &[u8],
     ) -> Result<String> {
+        chaos::rpc_fault("submitTaskResponse").await?;
         info!("Submitting task response for task: {}", task_id);
         
-        // In production, this would call submitTaskResponse on the service manager
-        let tx_hash = format!("0x{:x}", rand::random::<u64>());
-        info!("Mock task response submission transaction: {}", tx_hash);
+        // The response carries matches, proof and signatures for the contract to decode
+        let response = abi::encode(&[
+            Token::Bytes(matches_data.to_vec()),
+            Token::Bytes(proof_data.to_vec()),
+            Token::Bytes(operator_signature.to_vec()),
+        ]);
+        let call = bindings::service_manager::submit_task_response(
+            abi::bytes32(task_id)?,
+            response,
+            Keccak256::digest(matches_data).into(),
+        );
+        
+        let tx_hash = self.transactions()?.submit_and_confirm(&self.service_manager_address, call.data).await?;
+        info!("Task response submission transaction: {}", tx_hash);
         
         Ok(tx_hash)
     }
@@ -140,25 +278,28 @@ impl EigenVaultContracts {
 
 
     /// Execute vault order via hook
-    pub async fn execute_vault_order(
+    async fn execute_vault_order(
         &self,
         order_id: &str,
         proof: &[u8],
         signatures: &[u8],
     ) -> Result<String> {
+        chaos::rpc_fault("executeVaultOrder").await?;
         info!("Executing vault order: {}", order_id);
         
-        // In production, this would call executeVaultOrder on the hook contract
+        let call = bindings::hook::execute_vault_order(abi::bytes32(order_id)?, proof.to_vec(), signatures.to_vec());
         
-        let tx_hash = format!("0x{:x}", rand::random::<u64>());
-        info!("Mock order execution transaction: {}", tx_hash);
+        let tx_hash = self.transactions()?.submit_and_confirm(&self.hook_address, call.data).await?;
+        info!("Order execution transaction: {}", tx_hash);
         
         Ok(tx_hash)
     }
 
     /// Get task details from service manager
-    pub async fn get_task(&self, task_id: &str) -> Result<TaskInfo> {
+    async fn get_task(&self, task_id: &str) -> Result<TaskInfo> {
+        chaos::rpc_fault("getTask").await?;
         debug!("Fetching task details for: {}", task_id);
+        let _timer = metrics().rpc_latency_seconds.with_label_values(&["getTask"]).start_timer();
         
         // In production, this would call getTask on the service manager
         
@@ -170,14 +311,30 @@ impl EigenVaultContracts {
                 "0x1234567890123456789012345678901234567890".to_string(),
                 "0x2345678901234567890123456789012345678901".to_string(),
             ],
-            minimum_stake: 32000000000000000000u64, // 32 ETH in wei
+            minimum_stake: 32_000_000_000_000_000_000, // 32 ETH in wei
             created_at: chrono::Utc::now().timestamp() as u64,
         })
     }
 
+    /// Store an encrypted order in the vault
+    async fn store_order(&self, order_id: &str, trader: &str, encrypted_order: &[u8], deadline: u64) -> Result<String> {
+        chaos::rpc_fault("storeOrder").await?;
+        info!("Storing order {} for {} ({} bytes, deadline {})", order_id, trader, encrypted_order.len(), deadline);
+        let _timer = metrics().rpc_latency_seconds.with_label_values(&["storeOrder"]).start_timer();
+        
+        // In production, this would call storeOrder(orderId, encryptedOrder, deadline) on the order vault
+        
+        let tx_hash = format!("0x{:x}", rand::random::<u64>());
+        info!("Mock order storage transaction: {}", tx_hash);
+        
+        Ok(tx_hash)
+    }
+
     /// Retrieve encrypted order from vault
-    pub async fn retrieve_order(&self, order_id: &str) -> Result<Vec<u8>> {
+    async fn retrieve_order(&self, order_id: &str) -> Result<Vec<u8>> {
+        chaos::rpc_fault("retrieveOrder").await?;
         debug!("Retrieving encrypted order: {}", order_id);
+        let _timer = metrics().rpc_latency_seconds.with_label_values(&["retrieveOrder"]).start_timer();
         
         // In production, this would call retrieveOrder on the order vault
         // and return the actual encrypted order data
@@ -185,24 +342,37 @@ impl EigenVaultContracts {
         Ok(format!("encrypted_order_data_{}", order_id).into_bytes())
     }
 
-    /// Get operator stake amount
-    pub async fn get_operator_stake(&self, operator: &str) -> Result<u64> {
+    /// Operator weight in the stake registry
+    async fn get_operator_stake(&self, operator: &str) -> Result<u128> {
         debug!("Getting stake for operator: {}", operator);
-        
-        // In production, this would query the EigenLayer strategy manager
-        // or stake registry to get the actual staked amount
-        
-        Ok(32000000000000000000u64) // 32 ETH in wei
+        let call = bindings::stake_registry::get_operator_weight(abi::address(operator)?);
+        self.read_stake_registry(&call).await
+    }
+
+    /// Minimum weight the stake registry admits operators with
+    async fn get_minimum_stake(&self) -> Result<u128> {
+        self.read_stake_registry(&bindings::stake_registry::minimum_weight()).await
+    }
+
+    /// Total weight at the stake registry's latest checkpoint
+    async fn get_total_stake(&self) -> Result<u128> {
+        self.read_stake_registry(&bindings::stake_registry::get_last_checkpoint_total_weight()).await
     }
 
     /// Get hook contract address
-    pub async fn get_hook_address(&self) -> Result<String> {
+    async fn get_hook_address(&self) -> Result<String> {
+        chaos::rpc_fault("hook").await?;
         Ok(self.hook_address.clone())
     }
 
     /// Get transaction receipt
-    pub async fn get_transaction_receipt(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>> {
+    async fn get_transaction_receipt(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>> {
+        chaos::rpc_fault("eth_getTransactionReceipt").await?;
         debug!("Getting receipt for transaction: {}", tx_hash);
+        if let Some(transactions) = &self.transactions {
+            return transactions.receipt(tx_hash).await;
+        }
+        let _timer = metrics().rpc_latency_seconds.with_label_values(&["eth_getTransactionReceipt"]).start_timer();
         
         // In production, this would query the actual transaction receipt
         
@@ -211,20 +381,33 @@ impl EigenVaultContracts {
             block_number: self.get_latest_block_number().await?,
             confirmations: 3,
             status: true,
+            gas_used: 0,
+            effective_gas_price: 0,
         }))
     }
 
-    /// Get slashing events in block range
-    pub async fn get_slashing_events(&self, from_block: u64, to_block: u64) -> Result<Vec<SlashingEvent>> {
+    /// `OperatorSlashed` events of the service manager in `from_block..=to_block`
+    async fn get_slashing_events(&self, from_block: u64, to_block: u64) -> Result<Vec<SlashingEvent>> {
+        chaos::rpc_fault("eth_getLogs").await?;
         debug!("Getting slashing events from block {} to {}", from_block, to_block);
         
-        // In production, this would query OperatorSlashed events from service manager
-        
-        Ok(vec![]) // No slashing events in normal operation
+        let filter = json!([{
+            "address": self.service_manager_address,
+            "fromBlock": format!("0x{:x}", from_block),
+            "toBlock": format!("0x{:x}", to_block),
+            "topics": [events::operator_slashed_topic()],
+        }]);
+        let logs = {
+            let _timer = metrics().rpc_latency_seconds.with_label_values(&["eth_getLogs"]).start_timer();
+            self.rpc.call("eth_getLogs", filter).await
+                .with_context(|| format!("eth_getLogs for slashings at {} failed", self.service_manager_address))?
+        };
+        events::parse_slashing_logs(&logs)
     }
 
     /// Get pending tasks for operator
-    pub async fn get_pending_tasks_for_operator(&self, operator: &str) -> Result<Vec<TaskInfo>> {
+    async fn get_pending_tasks_for_operator(&self, operator: &str) -> Result<Vec<TaskInfo>> {
+        chaos::rpc_fault("getPendingTasksForOperator").await?;
         debug!("Getting pending tasks for operator: {}", operator);
         
         // In production, this would query TaskCreated events and filter by assigned operators
@@ -236,80 +419,100 @@ impl EigenVaultContracts {
                 orders_set_hash: format!("0x{:x}", rand::random::<u64>()),
                 deadline: chrono::Utc::now().timestamp() as u64 + 1800, // 30 minutes from now
                 assigned_operators: vec![operator.to_string()],
-                minimum_stake: 32000000000000000000u64,
+                minimum_stake: 32_000_000_000_000_000_000,
                 created_at: chrono::Utc::now().timestamp() as u64,
             }
         ])
     }
+}
 
-    /// Verify all contracts are properly deployed and accessible
-    async fn verify_contracts(&self) -> Result<()> {
+impl EigenVaultContracts {
+    /// Check that the RPC is on `expected_chain_id`, that every configured address holds code,
+    /// and that the contracts there answer their view functions and point at each other
+    pub async fn verify_contracts(&self, expected_chain_id: Option<u64>) -> Result<()> {
         info!("Verifying contract deployments...");
-        
-        // In production, this would:
-        // 1. Check that each contract address has code deployed
-        // 2. Verify contract interfaces by calling view functions
-        // 3. Ensure contracts are on the expected network
-        
-        info!("Contract verification completed");
-        Ok(())
-    }
 
-    /// Get contract ABI for dynamic interaction
-    pub fn get_hook_abi(&self) -> &str {
-        // In production, return the actual EigenVaultHook ABI
-        r#"[
-            {
-                "type": "function",
-                "name": "executeVaultOrder",
-                "inputs": [
-                    {"type": "bytes32", "name": "orderId"},
-                    {"type": "bytes", "name": "proof"},
-                    {"type": "bytes", "name": "signatures"}
-                ],
-                "outputs": [],
-                "stateMutability": "nonpayable"
-            }
-        ]"#
-    }
-
-    pub fn get_service_manager_abi(&self) -> &str {
-        // In production, return the actual EigenVaultServiceManager ABI
-        r#"[
-            {
-                "type": "function",
-                "name": "submitTaskResponse",
-                "inputs": [
-                    {"type": "bytes32", "name": "taskId"},
-                    {"type": "bytes32", "name": "resultHash"},
-                    {"type": "bytes", "name": "zkProof"},
-                    {"type": "bytes", "name": "operatorSignatures"}
-                ],
-                "outputs": [],
-                "stateMutability": "nonpayable"
+        let chain_id = self.get_chain_id().await
+            .with_context(|| format!("Could not read the chain ID from {}", self.rpc.url()))?;
+        if let Some(expected) = expected_chain_id {
+            if chain_id != expected {
+                anyhow::bail!(
+                    "{} is on chain {} but ethereum.expected_chain_id is {}",
+                    self.rpc.url(), chain_id, expected
+                );
             }
-        ]"#
-    }
-
-    pub fn get_order_vault_abi(&self) -> &str {
-        // In production, return the actual OrderVault ABI
-        r#"[
-            {
-                "type": "function",
-                "name": "retrieveOrder",
-                "inputs": [
-                    {"type": "bytes32", "name": "orderId"}
-                ],
-                "outputs": [
-                    {"type": "bytes", "name": "encryptedOrder"}
-                ],
-                "stateMutability": "nonpayable"
+        }
+
+        for (name, address) in [
+            ("service manager", &self.service_manager_address),
+            ("EigenVault hook", &self.hook_address),
+            ("order vault", &self.order_vault_address),
+        ] {
+            let code = self.rpc.call("eth_getCode", json!([address, "latest"])).await?;
+            if code.as_str().is_none_or(|code| code.trim_start_matches("0x").is_empty()) {
+                anyhow::bail!("No {} contract deployed at {} on chain {}", name, address, chain_id);
             }
-        ]"#
+        }
+
+        self.expect_address("service manager", &self.service_manager_address, bindings::service_manager::eigen_vault_hook(), &self.hook_address).await?;
+        self.expect_address("service manager", &self.service_manager_address, bindings::service_manager::order_vault(), &self.order_vault_address).await?;
+        self.expect_address("EigenVault hook", &self.hook_address, bindings::hook::service_manager(), &self.service_manager_address).await?;
+        self.expect_address("EigenVault hook", &self.hook_address, bindings::hook::order_vault(), &self.order_vault_address).await?;
+
+        let call = bindings::order_vault::authorized_hooks(abi::address(&self.hook_address)?);
+        let authorized = self.call_word(&self.order_vault_address, &call).await
+            .with_context(|| format!("Order vault does not answer {}", call.signature))?;
+        if authorized[31] == 0 {
+            warn!("Order vault {} has not authorized hook {}", self.order_vault_address, self.hook_address);
+        }
+
+        info!("Contract verification completed on chain {}", chain_id);
+        Ok(())
     }
-}
 
+    /// Fail unless `call` on the contract at `address` returns `expected`
+    async fn expect_address(&self, name: &str, address: &str, call: Call, expected: &str) -> Result<()> {
+        let word = self.call_word(address, &call).await
+            .with_context(|| format!("The {} at {} does not answer {}", name, address, call.signature))?;
+        let actual = format!("0x{}", hex::encode(&word[12..]));
+        if !actual.eq_ignore_ascii_case(expected) {
+            anyhow::bail!(
+                "The {} at {} returns {} from {}, but {} is configured",
+                name, address, actual, call.signature, expected
+            );
+        }
+        Ok(())
+    }
 
+    /// Stake registry view returning a weight
+    async fn read_stake_registry(&self, call: &Call) -> Result<u128> {
+        let stake_registry = Self::eigenlayer_address("stake_registry", &self.eigenlayer.stake_registry)?;
+        let word = self.call_word(stake_registry, call).await
+            .with_context(|| format!("Stake registry at {} does not answer {}", stake_registry, call.signature))?;
+        uint(word)
+    }
+
+    /// First word returned by an `eth_call` of `call` on `to`
+    async fn call_word(&self, to: &str, call: &Call) -> Result<[u8; 32]> {
+        let _timer = metrics().rpc_latency_seconds.with_label_values(&["eth_call"]).start_timer();
+        let result = self.rpc.call(
+            "eth_call",
+            json!([{ "to": to, "data": format!("0x{}", hex::encode(&call.data)) }, "latest"]),
+        ).await?;
+        let bytes = hex::decode(result.as_str().unwrap_or_default().trim_start_matches("0x"))?;
+        bytes.get(..32)
+            .and_then(|word| word.try_into().ok())
+            .ok_or_else(|| anyhow::anyhow!("Call returned {} bytes, expected a word", bytes.len()))
+    }
+}
+
+/// Value of a uint256 word, which must fit in 128 bits
+fn uint(word: [u8; 32]) -> Result<u128> {
+    if word[..16].iter().any(|byte| *byte != 0) {
+        anyhow::bail!("uint256 0x{} does not fit in 128 bits", hex::encode(word));
+    }
+    Ok(u128::from_be_bytes(word[16..].try_into()?))
+}
 
 #[cfg(test)]
 mod tests {
@@ -1,9 +1,23 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
 use std::collections::HashMap;
 use tracing::{debug, info, warn};
 
 use crate::config::EthereumConfig;
+use crate::metrics::metrics;
+use super::client::SlashingEvent;
+use super::rpc::JsonRpc;
+
+/// Most blocks asked for in one `eth_getLogs`; providers commonly cap ranges near this
+pub const MAX_LOG_RANGE: u64 = 1000;
+
+/// Size of an ABI word, and of every log topic
+const WORD: usize = 32;
+
+/// Service manager event emitted when an operator is slashed; `operator` is indexed
+pub const OPERATOR_SLASHED: &str = "OperatorSlashed(address,uint256,string)";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventFilter {
@@ -26,12 +40,17 @@ pub struct ParsedEvent {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EventParam {
+    /// `0x`-prefixed, lowercase
     Address(String),
-    Uint(u64),
+    /// Any `uintN` up to 128 bits wide
+    Uint(u128),
     Bytes(Vec<u8>),
+    /// `0x`-prefixed hex; also the keccak hash standing in for an indexed dynamic value
     Bytes32(String),
     Bool(bool),
     String(String),
+    /// `T[]` of a static `T`
+    Array(Vec<EventParam>),
 }
 
 impl ParsedEvent {
@@ -48,7 +67,8 @@ impl ParsedEvent {
     /// Get uint parameter from event
     pub fn get_uint_param(&self, name: &str) -> Result<u64> {
         match self.parameters.get(name) {
-            Some(EventParam::Uint(n)) => Ok(*n),
+            Some(EventParam::Uint(n)) => u64::try_from(*n)
+                .map_err(|_| anyhow::anyhow!("Parameter {} value {} does not fit in 64 bits", name, n)),
             _ => Err(anyhow::anyhow!("Parameter {} not found or wrong type", name)),
         }
     }
@@ -73,6 +93,7 @@ impl ParsedEvent {
 /// Event listener for Ethereum contracts
 pub struct EventListener {
     config: EthereumConfig,
+    rpc: JsonRpc,
     contract_addresses: Vec<String>,
     event_signatures: HashMap<String, EventSignature>,
     last_processed_block: u64,
@@ -84,6 +105,7 @@ impl EventListener {
         
         let mut listener = Self {
             config: config.clone(),
+            rpc: JsonRpc::new(&config.rpc_url),
             contract_addresses: Vec::new(),
             event_signatures: HashMap::new(),
             last_processed_block: 0,
@@ -107,13 +129,14 @@ impl EventListener {
             "TaskCreated".to_string(),
             EventSignature {
                 name: "TaskCreated".to_string(),
-                signature: "TaskCreated(bytes32,bytes32,uint256)".to_string(),
-                signature_hash: self.keccak256("TaskCreated(bytes32,bytes32,uint256)".as_bytes()),
+                signature: "TaskCreated(bytes32,bytes32,uint256,address)".to_string(),
+                signature_hash: keccak256("TaskCreated(bytes32,bytes32,uint256,address)".as_bytes()),
                 indexed_params: vec![0, 1], // taskId and ordersSetHash are indexed
                 param_types: vec![
                     ("taskId".to_string(), "bytes32".to_string()),
                     ("ordersSetHash".to_string(), "bytes32".to_string()),
                     ("deadline".to_string(), "uint256".to_string()),
+                    ("assignedOperator".to_string(), "address".to_string()),
                 ],
             },
         );
@@ -123,8 +146,8 @@ impl EventListener {
             EventSignature {
                 name: "TaskCompleted".to_string(),
                 signature: "TaskCompleted(bytes32,bytes32,address)".to_string(),
-                signature_hash: self.keccak256("TaskCompleted(bytes32,bytes32,address)".as_bytes()),
-                indexed_params: vec![0, 2], // taskId and operator are indexed
+                signature_hash: keccak256("TaskCompleted(bytes32,bytes32,address)".as_bytes()),
+                indexed_params: vec![0], // taskId is indexed
                 param_types: vec![
                     ("taskId".to_string(), "bytes32".to_string()),
                     ("resultHash".to_string(), "bytes32".to_string()),
@@ -133,19 +156,36 @@ impl EventListener {
             },
         );
 
+        // Registry events; ejection for downtime also ends in a deregistration
+        self.event_signatures.insert(
+            "OperatorDeregistered".to_string(),
+            EventSignature {
+                name: "OperatorDeregistered".to_string(),
+                signature: "OperatorDeregistered(address,bytes32)".to_string(),
+                signature_hash: keccak256("OperatorDeregistered(address,bytes32)".as_bytes()),
+                indexed_params: vec![0, 1], // operator and operatorId are indexed
+                param_types: vec![
+                    ("operator".to_string(), "address".to_string()),
+                    ("operatorId".to_string(), "bytes32".to_string()),
+                ],
+            },
+        );
+
         // Hook events
         self.event_signatures.insert(
             "OrderRoutedToVault".to_string(),
             EventSignature {
                 name: "OrderRoutedToVault".to_string(),
-                signature: "OrderRoutedToVault(address,bytes32,bool,uint256,bytes32)".to_string(),
-                signature_hash: self.keccak256("OrderRoutedToVault(address,bytes32,bool,uint256,bytes32)".as_bytes()),
-                indexed_params: vec![0, 1], // trader and orderId are indexed
+                // PoolKey is (currency0, currency1, fee, tickSpacing, hooks)
+                signature: "OrderRoutedToVault(address,bytes32,(address,address,uint24,int24,address),bool,uint256,bytes32)".to_string(),
+                signature_hash: keccak256("OrderRoutedToVault(address,bytes32,(address,address,uint24,int24,address),bool,uint256,bytes32)".as_bytes()),
+                indexed_params: vec![0, 1, 2], // trader, orderId and poolKey are indexed
                 param_types: vec![
                     ("trader".to_string(), "address".to_string()),
                     ("orderId".to_string(), "bytes32".to_string()),
+                    ("poolKey".to_string(), "(address,address,uint24,int24,address)".to_string()),
                     ("zeroForOne".to_string(), "bool".to_string()),
-                    ("amount".to_string(), "uint256".to_string()),
+                    ("amountSpecified".to_string(), "uint256".to_string()),
                     ("commitment".to_string(), "bytes32".to_string()),
                 ],
             },
@@ -155,15 +195,15 @@ impl EventListener {
             "VaultOrderExecuted".to_string(),
             EventSignature {
                 name: "VaultOrderExecuted".to_string(),
-                signature: "VaultOrderExecuted(bytes32,address,uint256,uint256,bytes32)".to_string(),
-                signature_hash: self.keccak256("VaultOrderExecuted(bytes32,address,uint256,uint256,bytes32)".as_bytes()),
+                signature: "VaultOrderExecuted(bytes32,address,uint256,bytes32,address[])".to_string(),
+                signature_hash: keccak256("VaultOrderExecuted(bytes32,address,uint256,bytes32,address[])".as_bytes()),
                 indexed_params: vec![0, 1], // orderId and trader are indexed
                 param_types: vec![
                     ("orderId".to_string(), "bytes32".to_string()),
                     ("trader".to_string(), "address".to_string()),
                     ("amountIn".to_string(), "uint256".to_string()),
-                    ("amountOut".to_string(), "uint256".to_string()),
-                    ("proofHash".to_string(), "bytes32".to_string()),
+                    ("matchHash".to_string(), "bytes32".to_string()),
+                    ("operators".to_string(), "address[]".to_string()),
                 ],
             },
         );
@@ -174,7 +214,7 @@ impl EventListener {
             EventSignature {
                 name: "OrderStored".to_string(),
                 signature: "OrderStored(bytes32,address,bytes,uint256)".to_string(),
-                signature_hash: self.keccak256("OrderStored(bytes32,address,bytes,uint256)".as_bytes()),
+                signature_hash: keccak256("OrderStored(bytes32,address,bytes,uint256)".as_bytes()),
                 indexed_params: vec![0, 1], // orderId and trader are indexed
                 param_types: vec![
                     ("orderId".to_string(), "bytes32".to_string()),
@@ -185,18 +225,37 @@ impl EventListener {
             },
         );
 
+        self.event_signatures.insert(
+            "OrderCancelled".to_string(),
+            EventSignature {
+                name: "OrderCancelled".to_string(),
+                signature: "OrderCancelled(bytes32,address,uint256)".to_string(),
+                signature_hash: keccak256("OrderCancelled(bytes32,address,uint256)".as_bytes()),
+                indexed_params: vec![0, 1], // orderId and trader are indexed
+                param_types: vec![
+                    ("orderId".to_string(), "bytes32".to_string()),
+                    ("trader".to_string(), "address".to_string()),
+                    ("timestamp".to_string(), "uint256".to_string()),
+                ],
+            },
+        );
+
         info!("Loaded {} event signatures", self.event_signatures.len());
         Ok(())
     }
 
     /// Add contract addresses to monitor
     async fn add_contract_addresses(&mut self) -> Result<()> {
-        // Add addresses from config or defaults
-        self.contract_addresses.extend(vec![
-            "0x1234567890123456789012345678901234567890".to_string(), // Service Manager
-            "0x2345678901234567890123456789012345678901".to_string(), // EigenVault Hook
-            "0x3456789012345678901234567890123456789012".to_string(), // Order Vault
-        ]);
+        let addresses = [
+            self.config.service_manager_address.to_lowercase(),
+            self.config.eigenvault_hook_address.to_lowercase(),
+            self.config.order_vault_address.to_lowercase(),
+        ];
+        for address in addresses {
+            if !address.is_empty() {
+                self.add_contract_address(address);
+            }
+        }
         
         info!("Monitoring {} contract addresses", self.contract_addresses.len());
         Ok(())
@@ -208,15 +267,20 @@ impl EventListener {
         
         let mut all_events = Vec::new();
         
-        // Query events for each contract address
+        // Query events for each contract address, a bounded range at a time
         for contract_address in &self.contract_addresses {
-            let contract_events = self.get_contract_events(
-                contract_address,
-                from_block,
-                to_block,
-            ).await?;
-            
-            all_events.extend(contract_events);
+            let mut start = from_block;
+            while start <= to_block {
+                let end = to_block.min(start.saturating_add(MAX_LOG_RANGE - 1));
+                let contract_events = self.get_contract_events(
+                    contract_address,
+                    start,
+                    end,
+                ).await?;
+                
+                all_events.extend(contract_events);
+                start = end + 1;
+            }
         }
         
         // Sort events by block number and log index
@@ -233,7 +297,7 @@ impl EventListener {
         Ok(all_events)
     }
 
-    /// Get events for a specific contract
+    /// Get events for a specific contract, filtered to the registered event signatures
     async fn get_contract_events(
         &self,
         contract_address: &str,
@@ -242,13 +306,40 @@ impl EventListener {
     ) -> Result<Vec<ParsedEvent>> {
         debug!("Getting events for contract: {}", contract_address);
         
-        // Mock event retrieval - in production, this would use actual RPC calls
-        let mock_events = self.generate_mock_events(contract_address, from_block, to_block).await?;
+        // topic[0] may be any known signature hash
+        let signature_hashes: Vec<String> = self.event_signatures.values()
+            .map(|sig| format!("0x{}", hex::encode(&sig.signature_hash)))
+            .collect();
+        let filter = json!([{
+            "address": contract_address,
+            "fromBlock": format!("0x{:x}", from_block),
+            "toBlock": format!("0x{:x}", to_block),
+            "topics": [signature_hashes],
+        }]);
+        
+        let logs = {
+            let _timer = metrics().rpc_latency_seconds.with_label_values(&["eth_getLogs"]).start_timer();
+            self.rpc.call("eth_getLogs", filter).await
+                .with_context(|| format!("eth_getLogs for {} failed", contract_address))?
+        };
+        let logs = logs.as_array()
+            .ok_or_else(|| anyhow::anyhow!("eth_getLogs returned {} instead of an array", logs))?;
         
         let mut parsed_events = Vec::new();
         
-        for mock_event in mock_events {
-            match self.parse_log_entry(&mock_event).await {
+        for log in logs {
+            let log_entry = match RawLog::from_json(log) {
+                Ok(log_entry) => log_entry,
+                Err(e) => {
+                    warn!("Skipping malformed log from {}: {:?}", contract_address, e);
+                    continue;
+                }
+            };
+            // Logs dropped by a reorg are reported with removed set
+            if log_entry.removed {
+                continue;
+            }
+            match self.parse_log_entry(&log_entry).await {
                 Ok(Some(parsed)) => parsed_events.push(parsed),
                 Ok(None) => {}, // Unknown event, skip
                 Err(e) => warn!("Failed to parse log entry: {:?}", e),
@@ -258,107 +349,51 @@ impl EventListener {
         Ok(parsed_events)
     }
 
-    /// Generate mock events for testing
-    async fn generate_mock_events(
-        &self,
-        contract_address: &str,
-        from_block: u64,
-        to_block: u64,
-    ) -> Result<Vec<MockLogEntry>> {
-        let mut mock_events = Vec::new();
-        
-        // Generate some mock events based on current time and blocks
-        let current_time = chrono::Utc::now().timestamp() as u64;
-        
-        if current_time % 30 < 5 { // Generate events occasionally
-            // Mock TaskCreated event
-            if contract_address.ends_with("90") { // Service Manager
-                mock_events.push(MockLogEntry {
-                    address: contract_address.to_string(),
-                    topics: vec![
-                        hex::encode(self.keccak256("TaskCreated(bytes32,bytes32,uint256)".as_bytes())),
-                        format!("task_{}", current_time % 1000), // taskId
-                        format!("orders_hash_{}", current_time % 1000), // ordersSetHash  
-                    ],
-                    data: hex::encode((current_time + 3600).to_le_bytes()), // deadline
-                    block_number: from_block + 1,
-                    transaction_hash: format!("0x{:x}", current_time),
-                    log_index: 0,
-                });
-            }
-            
-            // Mock OrderStored event
-            if contract_address.ends_with("12") { // Order Vault
-                mock_events.push(MockLogEntry {
-                    address: contract_address.to_string(),
-                    topics: vec![
-                        hex::encode(self.keccak256("OrderStored(bytes32,address,bytes,uint256)".as_bytes())),
-                        format!("order_{}", current_time % 1000), // orderId
-                        format!("0x{:040x}", current_time % 1000000), // trader address
-                    ],
-                    data: hex::encode(format!("encrypted_order_data_{}", current_time).as_bytes()),
-                    block_number: from_block + 1,  
-                    transaction_hash: format!("0x{:x}", current_time + 1),
-                    log_index: 1,
-                });
-            }
-        }
-        
-        Ok(mock_events)
-    }
-
     /// Parse raw log entry into typed event
-    async fn parse_log_entry(&self, log_entry: &MockLogEntry) -> Result<Option<ParsedEvent>> {
-        if log_entry.topics.is_empty() {
-            return Ok(None);
-        }
-        
+    async fn parse_log_entry(&self, log_entry: &RawLog) -> Result<Option<ParsedEvent>> {
         // Find matching event signature by topic[0] (event signature hash)
-        let event_signature_hash = &log_entry.topics[0];
+        let Some(event_signature_hash) = log_entry.topics.first() else {
+            return Ok(None);
+        };
         
         let event_signature = self.event_signatures.values()
-            .find(|sig| hex::encode(&sig.signature_hash) == *event_signature_hash);
+            .find(|sig| &sig.signature_hash == event_signature_hash);
         
         let event_signature = match event_signature {
             Some(sig) => sig,
             None => {
-                debug!("Unknown event signature: {}", event_signature_hash);
+                debug!("Unknown event signature: 0x{}", hex::encode(event_signature_hash));
                 return Ok(None);
             }
         };
         
         debug!("Parsing event: {}", event_signature.name);
         
-        // Parse parameters
+        // Indexed parameters follow the signature hash in the topics, in declaration order
+        if log_entry.topics.len() != event_signature.indexed_params.len() + 1 {
+            return Err(anyhow::anyhow!(
+                "{} log has {} topics, expected {}",
+                event_signature.name, log_entry.topics.len(), event_signature.indexed_params.len() + 1,
+            ));
+        }
+        
         let mut parameters = HashMap::new();
         
-        // Parse indexed parameters from topics
-        let mut topic_index = 1; // Skip topic[0] which is event signature
-        for (param_index, indexed_param) in event_signature.indexed_params.iter().enumerate() {
-            if topic_index < log_entry.topics.len() {
-                let (param_name, param_type) = &event_signature.param_types[*indexed_param];
-                let topic_value = &log_entry.topics[topic_index];
-                
-                let param_value = self.decode_event_param(param_type, topic_value, true)?;
-                parameters.insert(param_name.clone(), param_value);
-                
-                topic_index += 1;
-            }
+        for (indexed_param, topic) in event_signature.indexed_params.iter().zip(&log_entry.topics[1..]) {
+            let (param_name, param_type) = &event_signature.param_types[*indexed_param];
+            parameters.insert(param_name.clone(), decode_topic(param_type, topic)?);
         }
         
-        // Parse non-indexed parameters from data
-        if !log_entry.data.is_empty() {
-            let data_bytes = hex::decode(&log_entry.data)?;
-            
-            // Find non-indexed parameters
-            for (param_index, (param_name, param_type)) in event_signature.param_types.iter().enumerate() {
-                if !event_signature.indexed_params.contains(&param_index) {
-                    // For simplicity, we'll decode based on position
-                    // In production, this would use proper ABI decoding
-                    let param_value = self.decode_event_param(param_type, &log_entry.data, false)?;
-                    parameters.insert(param_name.clone(), param_value);
-                }
-            }
+        // The rest are ABI-encoded together in the data
+        let (data_names, data_types): (Vec<&String>, Vec<&str>) = event_signature.param_types.iter()
+            .enumerate()
+            .filter(|(param_index, _)| !event_signature.indexed_params.contains(param_index))
+            .map(|(_, (param_name, param_type))| (param_name, param_type.as_str()))
+            .unzip();
+        let values = decode_data(&data_types, &log_entry.data)
+            .with_context(|| format!("Failed to decode {} data", event_signature.name))?;
+        for (param_name, value) in data_names.into_iter().zip(values) {
+            parameters.insert(param_name.clone(), value);
         }
         
         let parsed_event = ParsedEvent {
@@ -376,40 +411,6 @@ impl EventListener {
         Ok(Some(parsed_event))
     }
 
-    /// Decode event parameter based on type
-    fn decode_event_param(&self, param_type: &str, raw_value: &str, is_indexed: bool) -> Result<EventParam> {
-        match param_type {
-            "address" => Ok(EventParam::Address(raw_value.to_string())),
-            "bytes32" => Ok(EventParam::Bytes32(raw_value.to_string())),
-            "uint256" => {
-                // Simplified uint decoding
-                let numeric_value = raw_value.len() as u64; // Mock conversion
-                Ok(EventParam::Uint(numeric_value))
-            }
-            "bool" => {
-                let bool_value = !raw_value.is_empty();
-                Ok(EventParam::Bool(bool_value))
-            }
-            "bytes" => {
-                let bytes_value = hex::decode(raw_value).unwrap_or_default();
-                Ok(EventParam::Bytes(bytes_value))
-            }
-            "string" => Ok(EventParam::String(raw_value.to_string())),
-            _ => {
-                warn!("Unknown parameter type: {}", param_type);
-                Ok(EventParam::String(raw_value.to_string()))
-            }
-        }
-    }
-
-    /// Keccak256 hash (simplified)
-    fn keccak256(&self, data: &[u8]) -> Vec<u8> {
-        use sha2::{Sha256, Digest};
-        let mut hasher = Sha256::new();
-        hasher.update(data);
-        hasher.finalize().to_vec()
-    }
-
     /// Update last processed block
     pub fn update_last_processed_block(&mut self, block_number: u64) {
         self.last_processed_block = block_number;
@@ -445,15 +446,177 @@ struct EventSignature {
     param_types: Vec<(String, String)>, // (name, type)
 }
 
-/// Mock log entry for testing
+/// Keccak-256, as the EVM uses for event topics
+fn keccak256(data: &[u8]) -> Vec<u8> {
+    Keccak256::digest(data).to_vec()
+}
+
+/// Whether `param_type` is encoded out of line, behind an offset in its head word
+fn is_dynamic(param_type: &str) -> bool {
+    matches!(param_type, "bytes" | "string") || param_type.ends_with("[]")
+}
+
+/// Decode an indexed parameter. Dynamic and tuple values are only indexed by their keccak
+/// hash, which is all the topic holds.
+fn decode_topic(param_type: &str, topic: &[u8]) -> Result<EventParam> {
+    if is_dynamic(param_type) || param_type.starts_with('(') {
+        return Ok(EventParam::Bytes32(format!("0x{}", hex::encode(topic))));
+    }
+    decode_word(param_type, topic)
+}
+
+/// Decode a static value from its 32-byte word
+fn decode_word(param_type: &str, word: &[u8]) -> Result<EventParam> {
+    match param_type {
+        "address" => Ok(EventParam::Address(format!("0x{}", hex::encode(&word[12..])))),
+        "bool" => Ok(EventParam::Bool(word[WORD - 1] != 0)),
+        "bytes32" => Ok(EventParam::Bytes32(format!("0x{}", hex::encode(word)))),
+        uint if uint.starts_with("uint") => Ok(EventParam::Uint(word_to_u128(word)?)),
+        _ => Err(anyhow::anyhow!("Unsupported ABI type {}", param_type)),
+    }
+}
+
+fn word_to_u128(word: &[u8]) -> Result<u128> {
+    if word[..WORD - 16].iter().any(|byte| *byte != 0) {
+        return Err(anyhow::anyhow!("0x{} does not fit in 128 bits", hex::encode(word)));
+    }
+    Ok(u128::from_be_bytes(word[WORD - 16..].try_into()?))
+}
+
+/// Word of `data` starting at byte `at`
+fn word_at(data: &[u8], at: usize) -> Result<&[u8]> {
+    at.checked_add(WORD)
+        .and_then(|end| data.get(at..end))
+        .ok_or_else(|| anyhow::anyhow!("Data of {} bytes has no word at {}", data.len(), at))
+}
+
+/// Offset or length held in the word of `data` at byte `at`
+fn usize_at(data: &[u8], at: usize) -> Result<usize> {
+    Ok(usize::try_from(word_to_u128(word_at(data, at)?)?)?)
+}
+
+/// Decode a log's non-indexed parameters of `param_types`, in order. Each has a head word:
+/// static values sit in it, dynamic ones put there the offset of a length-prefixed tail.
+fn decode_data(param_types: &[&str], data: &[u8]) -> Result<Vec<EventParam>> {
+    param_types.iter().enumerate().map(|(index, param_type)| {
+        let head = index * WORD;
+        if !is_dynamic(param_type) {
+            return decode_word(param_type, word_at(data, head)?);
+        }
+        
+        let offset = usize_at(data, head)?;
+        let length = usize_at(data, offset)?;
+        let body = offset + WORD;
+        if let Some(element_type) = param_type.strip_suffix("[]") {
+            let elements = (0..length)
+                .map(|element| decode_word(element_type, word_at(data, body + element * WORD)?))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(EventParam::Array(elements));
+        }
+        
+        let bytes = body.checked_add(length)
+            .and_then(|end| data.get(body..end))
+            .ok_or_else(|| anyhow::anyhow!("{} of {} bytes overruns data of {} bytes", param_type, length, data.len()))?;
+        match *param_type {
+            "string" => Ok(EventParam::String(String::from_utf8(bytes.to_vec())?)),
+            _ => Ok(EventParam::Bytes(bytes.to_vec())),
+        }
+    }).collect()
+}
+
+/// Topic of `OPERATOR_SLASHED` logs, for an `eth_getLogs` filter
+pub fn operator_slashed_topic() -> String {
+    format!("0x{}", hex::encode(keccak256(OPERATOR_SLASHED.as_bytes())))
+}
+
+/// Slashings in an `eth_getLogs` result filtered to `OPERATOR_SLASHED`, in log order. Removed
+/// logs are skipped, and so are malformed ones with a warning.
+pub fn parse_slashing_logs(logs: &Value) -> Result<Vec<SlashingEvent>> {
+    let logs = logs.as_array()
+        .ok_or_else(|| anyhow::anyhow!("eth_getLogs returned {} instead of an array", logs))?;
+    
+    let mut events = Vec::new();
+    for log in logs {
+        match RawLog::from_json(log).and_then(|log| slashing_event(&log)) {
+            Ok(Some(event)) => events.push(event),
+            Ok(None) => {}
+            Err(e) => warn!("Skipping malformed OperatorSlashed log: {:?}", e),
+        }
+    }
+    Ok(events)
+}
+
+fn slashing_event(log: &RawLog) -> Result<Option<SlashingEvent>> {
+    if log.removed {
+        return Ok(None);
+    }
+    let [_, operator] = &log.topics[..] else {
+        return Err(anyhow::anyhow!("OperatorSlashed log has {} topics, expected 2", log.topics.len()));
+    };
+    let [EventParam::Uint(amount), EventParam::String(reason)] = &decode_data(&["uint256", "string"], &log.data)?[..] else {
+        return Err(anyhow::anyhow!("OperatorSlashed data does not decode to an amount and a reason"));
+    };
+    
+    Ok(Some(SlashingEvent {
+        operator: format!("0x{}", hex::encode(&operator[12..])),
+        amount_wei: *amount,
+        reason: reason.clone(),
+        block_number: log.block_number,
+        transaction_hash: log.transaction_hash.clone(),
+    }))
+}
+
+/// Log as returned by `eth_getLogs`
 #[derive(Debug, Clone)]
-struct MockLogEntry {
+struct RawLog {
     address: String,
-    topics: Vec<String>,
-    data: String,
+    topics: Vec<Vec<u8>>,
+    data: Vec<u8>,
     block_number: u64,
     transaction_hash: String,
     log_index: u64,
+    removed: bool,
+}
+
+impl RawLog {
+    fn from_json(log: &Value) -> Result<Self> {
+        let field = |name: &str| -> Result<&str> {
+            log.get(name)
+                .and_then(Value::as_str)
+                .ok_or_else(|| anyhow::anyhow!("Log has no {}", name))
+        };
+        let bytes = |value: &str| -> Result<Vec<u8>> {
+            hex::decode(value.trim_start_matches("0x"))
+                .with_context(|| format!("Log field {} is not hex", value))
+        };
+        let quantity = |name: &str| -> Result<u64> {
+            let value = field(name)?;
+            u64::from_str_radix(value.trim_start_matches("0x"), 16)
+                .with_context(|| format!("Log {} {} is not a hex quantity", name, value))
+        };
+        let topics = log.get("topics")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow::anyhow!("Log has no topics"))?
+            .iter()
+            .map(|topic| {
+                let topic = bytes(topic.as_str().unwrap_or_default())?;
+                if topic.len() != WORD {
+                    return Err(anyhow::anyhow!("Log topic 0x{} is not 32 bytes", hex::encode(&topic)));
+                }
+                Ok(topic)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            address: field("address")?.to_lowercase(),
+            topics,
+            data: bytes(field("data")?)?,
+            block_number: quantity("blockNumber")?,
+            transaction_hash: field("transactionHash")?.to_string(),
+            log_index: quantity("logIndex")?,
+            removed: log.get("removed").and_then(Value::as_bool).unwrap_or(false),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -475,6 +638,81 @@ mod tests {
         
         assert!(listener.event_signatures.contains_key("TaskCreated"));
         assert!(listener.event_signatures.contains_key("OrderStored"));
+        assert_eq!(listener.contract_addresses.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_get_logs_entry_parsed() {
+        let config = EthereumConfig::default();
+        let listener = EventListener::new(&config).await.unwrap();
+        let topic = format!("0x{}", hex::encode(&listener.event_signatures["TaskCreated"].signature_hash));
+        
+        let log = RawLog::from_json(&json!({
+            "address": config.service_manager_address,
+            "topics": [topic, format!("0x{:064x}", 7), format!("0x{:064x}", 8)],
+            "data": format!("0x{:064x}{:064x}", 3600, 0xabcdu32),
+            "blockNumber": "0x1b4",
+            "transactionHash": "0xabc",
+            "logIndex": "0x2",
+            "removed": false,
+        })).unwrap();
+        assert_eq!((log.block_number, log.log_index), (436, 2));
+        
+        let event = listener.parse_log_entry(&log).await.unwrap().unwrap();
+        assert_eq!(event.event_name, "TaskCreated");
+        assert_eq!(event.get_string_param("taskId").unwrap(), format!("0x{:064x}", 7));
+        assert_eq!(event.get_uint_param("deadline").unwrap(), 3600);
+        assert_eq!(event.get_string_param("assignedOperator").unwrap(), format!("0x{:040x}", 0xabcd));
+    }
+
+    #[test]
+    fn test_signature_hashes_are_keccak() {
+        assert_eq!(
+            hex::encode(keccak256(b"Transfer(address,address,uint256)")),
+            "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef",
+        );
+    }
+
+    #[test]
+    fn test_decode_data_follows_offsets() {
+        let word = |value: u64| format!("{:064x}", value);
+        // OrderStored's (bytes encryptedOrder, uint256 timestamp), then an address[] on its own
+        let order = hex::decode([word(0x40), word(1_700_000_000), word(3), format!("{:0<64}", "010203")].concat()).unwrap();
+        let values = decode_data(&["bytes", "uint256"], &order).unwrap();
+        assert!(matches!(&values[0], EventParam::Bytes(bytes) if bytes == &[1, 2, 3]));
+        assert!(matches!(values[1], EventParam::Uint(1_700_000_000)));
+        
+        let operators = hex::decode([word(0x20), word(2), word(0xa), word(0xb)].concat()).unwrap();
+        let values = decode_data(&["address[]"], &operators).unwrap();
+        assert!(matches!(&values[0], EventParam::Array(elements) if elements.len() == 2));
+        
+        // A length running past the end is an error, not a short read
+        let truncated = hex::decode([word(0x20), word(64), word(0)].concat()).unwrap();
+        assert!(decode_data(&["bytes"], &truncated).is_err());
+    }
+
+    #[test]
+    fn test_slashing_logs_parsed() {
+        use crate::ethereum::abi::{self, Token};
+        
+        // A string encodes like bytes
+        let data = abi::encode(&[Token::Uint(32_000_000_000_000_000_000), Token::Bytes(b"Misbehavior detected".to_vec())]);
+        let log = |removed: bool| json!({
+            "address": "0x1234",
+            "topics": [operator_slashed_topic(), format!("0x{:064x}", 0xabcd)],
+            "data": format!("0x{}", hex::encode(&data)),
+            "blockNumber": "0x10",
+            "transactionHash": "0xdef",
+            "logIndex": "0x0",
+            "removed": removed,
+        });
+        
+        let events = parse_slashing_logs(&json!([log(false), log(true)])).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].operator, format!("0x{:040x}", 0xabcd));
+        assert_eq!(events[0].amount_wei, 32_000_000_000_000_000_000);
+        assert_eq!(events[0].reason, "Misbehavior detected");
+        assert_eq!(events[0].block_number, 16);
     }
 
     #[test]
@@ -499,6 +737,20 @@ mod tests {
     }
 }
 
+/// Position of a log on chain, unique across all contracts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct LogId {
+    pub block_number: u64,
+    pub log_index: u64,
+}
+
+/// An event with the log it was decoded from
+#[derive(Debug, Clone)]
+pub struct ChainEvent {
+    pub log: LogId,
+    pub event: EthereumEvent,
+}
+
 /// Ethereum events that the operator needs to handle
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EthereumEvent {
@@ -512,6 +764,11 @@ pub enum EthereumEvent {
         trader: String,
         encrypted_order: Vec<u8>,
     },
+    /// The trader withdrew `order_id` from the vault
+    OrderCancelled {
+        order_id: String,
+        trader: String,
+    },
     ProofSubmitted {
         task_id: String,
         operator: String,
@@ -521,16 +778,39 @@ pub enum EthereumEvent {
         task_id: String,
         result_hash: String,
     },
+    /// The registry removed `operator` from the AVS, e.g. after an ejection
+    OperatorDeregistered {
+        operator: String,
+    },
+}
+
+impl EthereumEvent {
+    /// Whether the event brings in a new order, which waits while matching is saturated
+    pub fn is_order_intake(&self) -> bool {
+        matches!(self, EthereumEvent::OrderStored { .. })
+    }
+
+    /// Event name as emitted by the contracts
+    pub fn name(&self) -> &'static str {
+        match self {
+            EthereumEvent::TaskCreated { .. } => "TaskCreated",
+            EthereumEvent::OrderStored { .. } => "OrderStored",
+            EthereumEvent::OrderCancelled { .. } => "OrderCancelled",
+            EthereumEvent::ProofSubmitted { .. } => "ProofSubmitted",
+            EthereumEvent::TaskCompleted { .. } => "TaskCompleted",
+            EthereumEvent::OperatorDeregistered { .. } => "OperatorDeregistered",
+        }
+    }
 }
 
 /// Event processor that handles parsed events
 pub struct EventProcessor {
-    config: EthereumConfig,
+    listener: EventListener,
 }
 
 impl EventProcessor {
-    pub fn new(config: EthereumConfig) -> Self {
-        Self { config }
+    pub async fn new(config: EthereumConfig) -> Result<Self> {
+        Ok(Self { listener: EventListener::new(&config).await? })
     }
 
     /// Process parsed event and convert to EthereumEvent
@@ -538,7 +818,7 @@ impl EventProcessor {
         match parsed_event.event_name.as_str() {
             "TaskCreated" => {
                 let task_id = parsed_event.get_string_param("taskId")?;
-                let orders_hash = parsed_event.get_string_param("ordersHash")?;
+                let orders_hash = parsed_event.get_string_param("ordersSetHash")?;
                 let deadline = parsed_event.get_uint_param("deadline")?;
                 
                 Ok(EthereumEvent::TaskCreated {
@@ -558,6 +838,12 @@ impl EventProcessor {
                     encrypted_order,
                 })
             }
+            "OrderCancelled" => {
+                let order_id = parsed_event.get_string_param("orderId")?;
+                let trader = parsed_event.get_string_param("trader")?;
+                
+                Ok(EthereumEvent::OrderCancelled { order_id, trader })
+            }
             "ProofSubmitted" => {
                 let task_id = parsed_event.get_string_param("taskId")?;
                 let operator = parsed_event.get_string_param("operator")?;
@@ -578,15 +864,29 @@ impl EventProcessor {
                     result_hash,
                 })
             }
+            "OperatorDeregistered" => {
+                let operator = parsed_event.get_string_param("operator")?;
+                
+                Ok(EthereumEvent::OperatorDeregistered { operator })
+            }
             _ => Err(anyhow::anyhow!("Unknown event type: {}", parsed_event.event_name)),
         }
     }
 
     /// Get recent events from the blockchain
-    pub async fn get_events(&self, from_block: u64, to_block: u64) -> Result<Vec<EthereumEvent>> {
-        // In a real implementation, this would query the blockchain for events
-        // For now, return empty vector
-        info!("Getting events from block {} to {}", from_block, to_block);
-        Ok(vec![])
+    pub async fn get_events(&self, from_block: u64, to_block: u64) -> Result<Vec<ChainEvent>> {
+        let parsed_events = self.listener.get_events(from_block, to_block).await?;
+        
+        let mut events = Vec::with_capacity(parsed_events.len());
+        for parsed_event in parsed_events {
+            let event_name = parsed_event.event_name.clone();
+            let log = LogId { block_number: parsed_event.block_number, log_index: parsed_event.log_index };
+            match self.process_event(parsed_event) {
+                Ok(event) => events.push(ChainEvent { log, event }),
+                // Registered for filtering but not acted on, e.g. OrderRoutedToVault
+                Err(e) => debug!("Ignoring {} event: {:?}", event_name, e),
+            }
+        }
+        Ok(events)
     }
 }
\ No newline at end of file
@@ -0,0 +1,31 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::info;
+
+use crate::audit::AuditLog;
+use crate::config::Settings;
+use super::client::EthereumClient;
+
+/// One EigenLayer registration served by this process, with its own signing key
+pub struct OperatorIdentity<C = EthereumClient> {
+    pub name: String,
+    pub client: C,
+}
+
+impl OperatorIdentity {
+    /// Connect a client for every configured identity, the primary first
+    pub async fn connect_all(settings: &Settings, audit_log: Option<Arc<AuditLog>>) -> Result<Vec<Self>> {
+        let mut identities = Vec::new();
+
+        for (name, ethereum) in settings.identity_configs() {
+            info!("Loading operator identity '{}' ({})", name, ethereum.operator_address);
+            let mut client = EthereumClient::new(ethereum).await?;
+            if let Some(audit_log) = &audit_log {
+                client.set_audit_log(audit_log.clone());
+            }
+            identities.push(Self { name, client });
+        }
+
+        Ok(identities)
+    }
+}
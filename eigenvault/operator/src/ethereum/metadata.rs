@@ -0,0 +1,92 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::MetadataConfig;
+
+/// Operator metadata document in the format EigenLayer explorers read from
+/// the operator's metadata URI
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperatorMetadata {
+    pub name: String,
+    pub website: String,
+    pub description: String,
+    pub logo: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub twitter: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub contact: String,
+}
+
+impl OperatorMetadata {
+    /// Build the document, rejecting values explorers won't display
+    pub fn from_config(config: &MetadataConfig) -> Result<Self> {
+        if config.name.trim().is_empty() {
+            return Err(anyhow::anyhow!("metadata.name is required"));
+        }
+        for (field, uri) in [("metadata.uri", &config.uri), ("metadata.website", &config.website), ("metadata.logo", &config.logo)] {
+            if !uri.starts_with("https://") && !uri.starts_with("http://") {
+                return Err(anyhow::anyhow!("{} must be an http(s) URL, got {:?}", field, uri));
+            }
+        }
+        if !config.logo.to_ascii_lowercase().ends_with(".png") {
+            return Err(anyhow::anyhow!("metadata.logo must point to a PNG image"));
+        }
+
+        Ok(Self {
+            name: config.name.clone(),
+            website: config.website.clone(),
+            description: config.description.clone(),
+            logo: config.logo.clone(),
+            twitter: config.twitter.clone(),
+            contact: config.contact.clone(),
+        })
+    }
+
+    /// Compare against the document currently served at `uri`
+    pub async fn is_published_at(&self, uri: &str) -> Result<bool> {
+        let published: OperatorMetadata = reqwest::get(uri).await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(&published == self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> MetadataConfig {
+        MetadataConfig {
+            uri: "https://example.com/operator.json".to_string(),
+            name: "Example Operator".to_string(),
+            website: "https://example.com".to_string(),
+            description: "Runs EigenVault matching".to_string(),
+            logo: "https://example.com/logo.png".to_string(),
+            twitter: String::new(),
+            contact: "ops@example.com".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_metadata_document() -> Result<()> {
+        let metadata = OperatorMetadata::from_config(&config())?;
+        let document = serde_json::to_value(&metadata)?;
+
+        assert_eq!(document["name"], "Example Operator");
+        assert_eq!(document["contact"], "ops@example.com");
+        assert!(document.get("twitter").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_metadata_validation() {
+        let mut missing_name = config();
+        missing_name.name = " ".to_string();
+        assert!(OperatorMetadata::from_config(&missing_name).is_err());
+
+        let mut svg_logo = config();
+        svg_logo.logo = "https://example.com/logo.svg".to_string();
+        assert!(OperatorMetadata::from_config(&svg_logo).is_err());
+    }
+}
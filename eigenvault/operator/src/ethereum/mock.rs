@@ -0,0 +1,412 @@
+use anyhow::Result;
+use sha3::{Digest, Keccak256};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::audit::TransactionKind;
+use crate::matching::OrderMatch;
+use crate::proofs::MatchingProof;
+use super::chain::{ChainClient, ContractApi};
+use super::client::{SlashingEvent, TaskInfo, TransactionReceipt};
+use super::events::{ChainEvent, EthereumEvent, LogId};
+use super::registration::{self, OperatorRegistration};
+
+/// Chain id reported by `MockChain`, the same as a local anvil
+const MOCK_CHAIN_ID: u64 = 31337;
+
+/// Operator `MockChain` registers as a client
+const MOCK_OPERATOR: &str = "0x0000000000000000000000000000000000000001";
+
+/// How long `listen_for_events` waits for an event before returning empty, like a block time
+const MOCK_BLOCK_TIME: Duration = Duration::from_millis(100);
+
+/// A transaction `MockChain` recorded instead of broadcasting
+#[derive(Debug, Clone)]
+pub struct MockTransaction {
+    pub kind: TransactionKind,
+    pub subject: String,
+    pub payload: Vec<u8>,
+    pub tx_hash: String,
+}
+
+#[derive(Default)]
+struct MockState {
+    events: VecDeque<EthereumEvent>,
+    /// Logs delivered so far; numbers the next one so log ids never repeat
+    logs: u64,
+    tasks: BTreeMap<String, (TaskInfo, Vec<Vec<u8>>)>,
+    orders: BTreeMap<String, Vec<u8>>,
+    order_key: Option<(String, u64)>,
+    /// Everything deposited, credited to every operator
+    stake: u128,
+    minimum_stake: u128,
+    transactions: Vec<MockTransaction>,
+    slashings: Vec<SlashingEvent>,
+}
+
+/// In-memory chain: events and tasks are queued in, transactions are recorded
+/// and immediately confirmed. Clones share state, so a test can keep a handle
+/// on a mock it passed to a client.
+#[derive(Clone, Default)]
+pub struct MockChain {
+    block: Arc<AtomicU64>,
+    state: Arc<Mutex<MockState>>,
+    events_pushed: Arc<Notify>,
+}
+
+impl MockChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue an event for the next `listen_for_events`
+    pub fn push_event(&self, event: EthereumEvent) {
+        self.state.lock().unwrap().events.push_back(event);
+        self.events_pushed.notify_one();
+    }
+
+    /// Make a task pending, with the encrypted orders it references
+    #[cfg(test)]
+    pub fn add_task(&self, task: TaskInfo, orders: Vec<Vec<u8>>) {
+        self.state.lock().unwrap().tasks.insert(task.task_id.clone(), (task, orders));
+    }
+
+    /// Require `stake` wei of every operator
+    #[cfg(test)]
+    pub fn set_minimum_stake(&self, stake: u128) {
+        self.state.lock().unwrap().minimum_stake = stake;
+    }
+
+    /// Slash `operator` by `amount_wei` in a new block
+    #[cfg(test)]
+    pub fn slash(&self, operator: &str, amount_wei: u128) {
+        let block_number = self.block.fetch_add(1, Ordering::SeqCst) + 1;
+        self.state.lock().unwrap().slashings.push(SlashingEvent {
+            operator: operator.to_lowercase(),
+            amount_wei,
+            reason: "Misbehavior detected".to_string(),
+            block_number,
+            transaction_hash: format!("0x{:064x}", block_number),
+        });
+    }
+
+    /// Every transaction recorded so far, oldest first
+    pub fn transactions(&self) -> Vec<MockTransaction> {
+        self.state.lock().unwrap().transactions.clone()
+    }
+
+    /// Record a transaction in a new block and return its hash
+    fn record(&self, kind: TransactionKind, subject: &str, payload: &[u8]) -> String {
+        let block = self.block.fetch_add(1, Ordering::SeqCst) + 1;
+        let tx_hash = format!("0x{:064x}", block);
+        self.state.lock().unwrap().transactions.push(MockTransaction {
+            kind,
+            subject: subject.to_string(),
+            payload: payload.to_vec(),
+            tx_hash: tx_hash.clone(),
+        });
+        tx_hash
+    }
+
+    fn task(&self, task_id: &str) -> Option<(TaskInfo, Vec<Vec<u8>>)> {
+        self.state.lock().unwrap().tasks.get(task_id).cloned()
+    }
+}
+
+#[async_trait]
+impl ContractApi for MockChain {
+    async fn get_latest_block_number(&self) -> Result<u64> {
+        Ok(self.block.load(Ordering::SeqCst))
+    }
+
+    async fn get_chain_id(&self) -> Result<u64> {
+        Ok(MOCK_CHAIN_ID)
+    }
+
+    async fn registration_digest(&self, operator: &str, salt: [u8; 32], expiry: u64) -> Result<[u8; 32]> {
+        Ok(Keccak256::digest([operator.as_bytes(), &salt, &expiry.to_be_bytes()].concat()).into())
+    }
+
+    async fn register_operator(&self, registration: &OperatorRegistration) -> Result<String> {
+        Ok(self.record(TransactionKind::OperatorRegistration, &registration.operator, &registration.signature))
+    }
+
+    async fn deregister_operator(&self, operator: &str) -> Result<String> {
+        Ok(self.record(TransactionKind::OperatorDeregistration, operator, &[]))
+    }
+
+    async fn withdrawal_delay_blocks(&self) -> Result<u64> {
+        Ok(0)
+    }
+
+    async fn update_operator_metadata_uri(&self, uri: &str) -> Result<String> {
+        Ok(self.record(TransactionKind::MetadataUpdate, uri, uri.as_bytes()))
+    }
+
+    async fn update_order_key_uri(&self, uri: &str, version: u64) -> Result<String> {
+        let tx_hash = self.record(TransactionKind::OrderKeyUpdate, uri, &version.to_le_bytes());
+        self.state.lock().unwrap().order_key = Some((uri.to_string(), version));
+        Ok(tx_hash)
+    }
+
+    async fn get_order_key_uri(&self) -> Result<Option<(String, u64)>> {
+        Ok(self.state.lock().unwrap().order_key.clone())
+    }
+
+    async fn deposit_into_strategy(&self, strategy: &str, amount_wei: u128) -> Result<String> {
+        let tx_hash = self.record(TransactionKind::StakeDeposit, strategy, &amount_wei.to_le_bytes());
+        self.state.lock().unwrap().stake += amount_wei;
+        Ok(tx_hash)
+    }
+
+    async fn submit_task_response(
+        &self,
+        task_id: &str,
+        matches_data: &[u8],
+        proof_data: &[u8],
+        _operator_signature: &[u8],
+    ) -> Result<String> {
+        let payload = [matches_data, proof_data].concat();
+        Ok(self.record(TransactionKind::TaskResponse, task_id, &payload))
+    }
+
+    async fn execute_vault_order(&self, order_id: &str, proof: &[u8], _signatures: &[u8]) -> Result<String> {
+        Ok(self.record(TransactionKind::VaultExecution, order_id, proof))
+    }
+
+    async fn store_order(&self, order_id: &str, trader: &str, encrypted_order: &[u8], _deadline: u64) -> Result<String> {
+        let tx_hash = self.record(TransactionKind::OrderSubmission, order_id, encrypted_order);
+        self.state.lock().unwrap().orders.insert(order_id.to_string(), encrypted_order.to_vec());
+        self.push_event(EthereumEvent::OrderStored {
+            order_id: order_id.to_string(),
+            trader: trader.to_string(),
+            encrypted_order: encrypted_order.to_vec(),
+        });
+        Ok(tx_hash)
+    }
+
+    async fn get_task(&self, task_id: &str) -> Result<TaskInfo> {
+        self.task(task_id)
+            .map(|(task, _)| task)
+            .ok_or_else(|| anyhow::anyhow!("Unknown task {}", task_id))
+    }
+
+    async fn retrieve_order(&self, order_id: &str) -> Result<Vec<u8>> {
+        self.state.lock().unwrap().orders.get(order_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Unknown order {}", order_id))
+    }
+
+    async fn get_operator_stake(&self, _operator: &str) -> Result<u128> {
+        Ok(self.state.lock().unwrap().stake)
+    }
+
+    async fn get_minimum_stake(&self) -> Result<u128> {
+        Ok(self.state.lock().unwrap().minimum_stake)
+    }
+
+    /// Every operator holds all of the stake, so the total counts it once
+    async fn get_total_stake(&self) -> Result<u128> {
+        Ok(self.state.lock().unwrap().stake)
+    }
+
+    async fn get_hook_address(&self) -> Result<String> {
+        Ok(format!("0x{:040x}", 0))
+    }
+
+    async fn get_transaction_receipt(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>> {
+        let state = self.state.lock().unwrap();
+        let Some(position) = state.transactions.iter().position(|tx| tx.tx_hash == tx_hash) else {
+            return Ok(None);
+        };
+
+        // Recorded transactions never revert and are treated as final; gas is the intrinsic
+        // cost plus calldata at 1 gwei
+        Ok(Some(TransactionReceipt {
+            transaction_hash: tx_hash.to_string(),
+            block_number: position as u64 + 1,
+            confirmations: u32::MAX,
+            status: true,
+            gas_used: 21_000 + 16 * state.transactions[position].payload.len() as u64,
+            effective_gas_price: 1_000_000_000,
+        }))
+    }
+
+    async fn get_slashing_events(&self, from_block: u64, to_block: u64) -> Result<Vec<SlashingEvent>> {
+        Ok(self.state.lock().unwrap().slashings.iter()
+            .filter(|event| (from_block..=to_block).contains(&event.block_number))
+            .cloned()
+            .collect())
+    }
+
+    async fn get_pending_tasks_for_operator(&self, operator: &str) -> Result<Vec<TaskInfo>> {
+        Ok(self.state.lock().unwrap().tasks.values()
+            .map(|(task, _)| task)
+            .filter(|task| task.assigned_operators.iter().any(|assigned| assigned.eq_ignore_ascii_case(operator)))
+            .cloned()
+            .collect())
+    }
+}
+
+#[async_trait]
+impl ChainClient for MockChain {
+    async fn listen_for_events(&self) -> Result<Vec<ChainEvent>> {
+        let empty = self.state.lock().unwrap().events.is_empty();
+        if empty {
+            let _ = tokio::time::timeout(MOCK_BLOCK_TIME, self.events_pushed.notified()).await;
+        }
+        let block_number = self.block.load(Ordering::SeqCst);
+        let mut state = self.state.lock().unwrap();
+        let first = state.logs;
+        let events: Vec<ChainEvent> = state.events.drain(..)
+            .zip(first..)
+            .map(|(event, log_index)| ChainEvent { log: LogId { block_number, log_index }, event })
+            .collect();
+        state.logs += events.len() as u64;
+        Ok(events)
+    }
+
+    fn last_processed_block(&self) -> u64 {
+        self.block.load(Ordering::SeqCst)
+    }
+
+    fn is_dry_run(&self) -> bool {
+        false
+    }
+
+    async fn submit_matching_proof(
+        &self,
+        task_id: &str,
+        proof: Vec<u8>,
+        _result_hash: &str,
+        _operator_signatures: Vec<u8>,
+    ) -> Result<String> {
+        Ok(self.record(TransactionKind::TaskResponse, task_id, &proof))
+    }
+
+    async fn submit_task_response(&self, task_id: &str, matches: Vec<OrderMatch>, proof: MatchingProof) -> Result<String> {
+        let payload = [serde_json::to_vec(&matches)?, proof.proof_data].concat();
+        Ok(self.record(TransactionKind::TaskResponse, task_id, &payload))
+    }
+
+    async fn retrieve_orders_for_task(&self, task_id: &str) -> Result<Vec<Vec<u8>>> {
+        Ok(self.task(task_id).map(|(_, orders)| orders).unwrap_or_default())
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_pending_tasks(&self) -> Result<Vec<TaskInfo>> {
+        Ok(self.state.lock().unwrap().tasks.values().map(|(task, _)| task.clone()).collect())
+    }
+
+    async fn register_operator(&self) -> Result<String> {
+        let registration = OperatorRegistration {
+            operator: MOCK_OPERATOR.to_string(),
+            signature: vec![0u8; 65],
+            salt: [0u8; 32],
+            expiry: u64::MAX,
+        };
+        ContractApi::register_operator(self, &registration).await?;
+        registration::operator_id(MOCK_OPERATOR)
+    }
+
+    async fn operator_stake(&self) -> Result<u128> {
+        Ok(self.state.lock().unwrap().stake)
+    }
+
+    async fn stake_of(&self, operator: &str) -> Result<u128> {
+        self.get_operator_stake(operator).await
+    }
+
+    async fn total_stake(&self) -> Result<u128> {
+        self.get_total_stake().await
+    }
+
+    async fn latest_block(&self) -> Result<u64> {
+        self.get_latest_block_number().await
+    }
+
+    async fn slashing_events(&self, from_block: u64, to_block: u64) -> Result<Vec<SlashingEvent>> {
+        self.get_slashing_events(from_block, to_block).await
+    }
+
+    async fn transaction_fee(&self, tx_hash: &str) -> Result<u128> {
+        self.get_transaction_receipt(tx_hash).await?
+            .map(|receipt| receipt.fee_wei())
+            .ok_or_else(|| anyhow::anyhow!("No receipt for transaction {}", tx_hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EthereumConfig;
+    use crate::ethereum::EthereumClient;
+
+    fn task(task_id: &str, operator: &str) -> TaskInfo {
+        TaskInfo {
+            task_id: task_id.to_string(),
+            orders_set_hash: "0x00".to_string(),
+            deadline: 0,
+            assigned_operators: vec![operator.to_string()],
+            minimum_stake: 0,
+            created_at: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_over_mock_records_transactions() -> Result<()> {
+        let chain = MockChain::new();
+        let client = EthereumClient::with_contracts(EthereumConfig::default(), chain.clone()).await?;
+
+        let tx_hash = client.submit_matching_proof("task_1", vec![1, 2, 3], "0xabc", vec![0u8; 65]).await?;
+
+        let transactions = chain.transactions();
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].tx_hash, tx_hash);
+        assert_eq!(transactions[0].subject, "task_1");
+        assert_eq!(transactions[0].kind, TransactionKind::TaskResponse);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mock_tasks_and_events() -> Result<()> {
+        let chain = MockChain::new();
+        chain.add_task(task("task_1", "0xAbC"), vec![vec![7u8; 4]]);
+        chain.push_event(EthereumEvent::TaskCreated {
+            task_id: "task_1".to_string(),
+            orders_hash: "0x00".to_string(),
+            deadline: 0,
+        });
+
+        assert_eq!(ContractApi::get_pending_tasks_for_operator(&chain, "0xabc").await?.len(), 1);
+        assert!(ContractApi::get_pending_tasks_for_operator(&chain, "0xdef").await?.is_empty());
+        assert_eq!(chain.retrieve_orders_for_task("task_1").await?, vec![vec![7u8; 4]]);
+        assert!(chain.retrieve_orders_for_task("task_2").await?.is_empty());
+        assert_eq!(chain.listen_for_events().await?.len(), 1);
+        assert!(chain.listen_for_events().await?.is_empty());
+
+        chain.push_event(EthereumEvent::OperatorDeregistered { operator: "0xabc".to_string() });
+        assert_eq!(chain.listen_for_events().await?[0].log.log_index, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mock_slashings_by_block() -> Result<()> {
+        let chain = MockChain::new();
+        chain.slash("0xAbC", 5);
+        chain.slash("0xdef", 7);
+
+        let latest = chain.latest_block().await?;
+        assert_eq!(chain.slashing_events(0, latest).await?.len(), 2);
+        let last = chain.slashing_events(latest, latest).await?;
+        assert_eq!((last[0].operator.as_str(), last[0].amount_wei), ("0xdef", 7));
+        Ok(())
+    }
+}
@@ -1,7 +1,25 @@
+pub mod abi;
+pub mod bindings;
+pub mod cache;
+pub mod chain;
 pub mod client;
 pub mod contracts;
 pub mod events;
+pub mod identity;
+pub mod metadata;
+pub mod mock;
+pub mod registration;
+pub mod rpc;
+pub mod transactions;
 
-pub use client::EthereumClient;
-pub use events::{EthereumEvent, EventProcessor, EventListener, EventFilter, ParsedEvent};
+pub use cache::CachedContracts;
+pub use chain::{ChainClient, ContractApi};
+pub use client::{EthereumClient, TaskInfo};
+pub use identity::OperatorIdentity;
+pub use metadata::OperatorMetadata;
+pub use mock::MockChain;
+pub use registration::OperatorRegistration;
+pub use rpc::JsonRpc;
+pub use transactions::TransactionManager;
+pub use events::{ChainEvent, EthereumEvent, EventProcessor, EventListener, EventFilter, LogId, ParsedEvent};
 pub use contracts::{ContractManager, ContractCall, EigenVaultContracts};
\ No newline at end of file
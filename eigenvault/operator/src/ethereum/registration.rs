@@ -0,0 +1,33 @@
+use anyhow::Result;
+use sha3::{Digest, Keccak256};
+
+use crate::config::KeyManager;
+use super::abi;
+
+/// Operator's signed request to join the AVS, submitted to the stake registry
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatorRegistration {
+    pub operator: String,
+    /// Signature over the AVSDirectory registration digest, `r || s || v`
+    pub signature: Vec<u8>,
+    pub salt: [u8; 32],
+    /// Unix time after which the AVSDirectory rejects the signature
+    pub expiry: u64,
+}
+
+impl OperatorRegistration {
+    /// Sign the `digest` the AVSDirectory computed for `operator`, `salt` and `expiry`
+    pub fn sign(operator: &str, private_key: &str, digest: [u8; 32], salt: [u8; 32], expiry: u64) -> Result<Self> {
+        Ok(Self {
+            operator: operator.to_string(),
+            signature: KeyManager::new().sign_digest(&digest, private_key)?,
+            salt,
+            expiry,
+        })
+    }
+}
+
+/// ID EigenLayer tooling gives an ECDSA operator: the keccak hash of its address
+pub fn operator_id(operator: &str) -> Result<String> {
+    Ok(format!("0x{}", hex::encode(Keccak256::digest(abi::address(operator)?))))
+}
@@ -0,0 +1,51 @@
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::chaos;
+
+/// Minimal JSON-RPC client for the few raw calls made outside the contract bindings
+#[derive(Debug, Clone)]
+pub struct JsonRpc {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl JsonRpc {
+    pub fn new(url: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self { url: url.to_string(), client }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        chaos::rpc_fault(method).await?;
+        let response: Value = self.client.post(&self.url)
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let Some(error) = response.get("error") {
+            return Err(anyhow::anyhow!("{}", error));
+        }
+        response.get("result").cloned()
+            .ok_or_else(|| anyhow::anyhow!("Response has no result"))
+    }
+}
+
+/// Parse a `0x`-prefixed hex quantity such as a block number or chain ID
+pub fn quantity(value: &Value) -> Result<u64> {
+    let hex = value.as_str()
+        .ok_or_else(|| anyhow::anyhow!("Expected a hex quantity, got {}", value))?;
+    u64::from_str_radix(hex.trim_start_matches("0x"), 16)
+        .with_context(|| format!("{} is not a hex quantity", hex))
+}
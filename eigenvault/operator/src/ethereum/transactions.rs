@@ -0,0 +1,437 @@
+use anyhow::{Context, Result};
+use secp256k1::{Message, Secp256k1, SecretKey};
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+use std::fmt;
+use std::time::Duration;
+use tokio::sync::{Mutex, OnceCell};
+use tracing::{debug, info, warn};
+
+use crate::config::{EthereumConfig, TransactionConfig};
+use crate::matching::signing;
+use crate::metrics::metrics;
+use crate::sim::clock;
+use super::abi;
+use super::client::TransactionReceipt;
+use super::rpc::{quantity, JsonRpc};
+
+/// How often the receipts of a pending transaction are polled
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Headroom added to a gas estimate, in percent
+const GAS_ESTIMATE_MARGIN_PERCENT: u64 = 20;
+
+/// Gas of a plain transfer, all a cancellation needs
+const TRANSFER_GAS: u64 = 21_000;
+
+/// Legacy transaction calling a contract, signed with EIP-155 replay protection
+#[derive(Debug, Clone)]
+struct LegacyTransaction {
+    nonce: u64,
+    gas_price: u64,
+    gas_limit: u64,
+    to: [u8; 20],
+    value: u128,
+    data: Vec<u8>,
+}
+
+impl LegacyTransaction {
+    fn fields(&self) -> Vec<Vec<u8>> {
+        vec![
+            rlp_uint(self.nonce.into()),
+            rlp_uint(self.gas_price.into()),
+            rlp_uint(self.gas_limit.into()),
+            rlp_bytes(&self.to),
+            rlp_uint(self.value),
+            rlp_bytes(&self.data),
+        ]
+    }
+
+    /// Raw signed transaction for `chain_id` and its hash
+    fn sign(&self, chain_id: u64, key: &SecretKey) -> Result<(Vec<u8>, String)> {
+        let mut unsigned = self.fields();
+        unsigned.extend([rlp_uint(chain_id.into()), rlp_uint(0), rlp_uint(0)]);
+        let digest = Keccak256::digest(rlp_list(&unsigned));
+
+        let message = Message::from_digest_slice(&digest)?;
+        let (recovery_id, signature) = Secp256k1::signing_only()
+            .sign_ecdsa_recoverable(&message, key)
+            .serialize_compact();
+        let v = u128::from(chain_id) * 2 + 35 + recovery_id.to_i32() as u128;
+
+        let mut signed = self.fields();
+        signed.extend([
+            rlp_uint(v),
+            rlp_bytes(trim_leading_zeros(&signature[..32])),
+            rlp_bytes(trim_leading_zeros(&signature[32..])),
+        ]);
+        let raw = rlp_list(&signed);
+        let hash = format!("0x{}", hex::encode(Keccak256::digest(&raw)));
+        Ok((raw, hash))
+    }
+}
+
+/// A broadcast transaction and every replacement sent for it, all sharing its nonce
+struct PendingTransaction {
+    transaction: LegacyTransaction,
+    hashes: Vec<String>,
+    /// When the latest version was sent
+    sent_at: Duration,
+}
+
+/// Signs and sends the operator's transactions.
+///
+/// Nonces come from the node on first use and are then counted locally, so concurrent
+/// submissions never reuse one; after a failed broadcast the next submission reads the nonce
+/// again. A transaction left unmined for `replace_after_seconds` is replaced by the same
+/// transaction at a gas price `gas_bump_percent` higher, up to `max_replacements` times. One
+/// still unconfirmed after `confirm_timeout_seconds` is cancelled, so its nonce doesn't hold
+/// back every later submission.
+pub struct TransactionManager {
+    rpc: JsonRpc,
+    key: SecretKey,
+    address: String,
+    config: TransactionConfig,
+    /// Lowest gas price offered, whatever the node suggests
+    min_gas_price: u64,
+    /// Most gas a call may be estimated at
+    max_gas_limit: u64,
+    confirmations: u64,
+    chain_id: OnceCell<u64>,
+    /// Next nonce of `address`, unknown until read from the node
+    nonce: Mutex<Option<u64>>,
+}
+
+impl fmt::Debug for TransactionManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransactionManager")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TransactionManager {
+    /// Manager for the account of `config.private_key`, sending through `config.rpc_url`
+    pub fn new(config: &EthereumConfig) -> Result<Self> {
+        let private_key = config.private_key.expose();
+        let key = hex::decode(private_key.trim_start_matches("0x")).ok()
+            .and_then(|key| SecretKey::from_slice(&key).ok())
+            .context("ethereum.private_key is not a valid secp256k1 key")?;
+        let address = signing::signer_address(private_key)?;
+        if !address.eq_ignore_ascii_case(&config.operator_address) {
+            warn!("Transactions are signed by {}, not the configured operator address {}", address, config.operator_address);
+        }
+
+        Ok(Self {
+            rpc: JsonRpc::new(&config.rpc_url),
+            key,
+            address,
+            config: config.transactions.clone(),
+            min_gas_price: config.gas_price,
+            max_gas_limit: config.gas_limit,
+            confirmations: config.confirmation_blocks,
+            chain_id: OnceCell::new(),
+            nonce: Mutex::new(None),
+        })
+    }
+
+    /// Account transactions are sent from
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Send a call of `data` to `to`, replacing it while it stays unmined, and wait until
+    /// whichever version is mined has `confirmation_blocks` confirmations. One mined but still
+    /// short of them at the timeout is returned all the same: it has taken effect, and sending
+    /// it again would do it twice.
+    pub async fn submit_and_confirm(&self, to: &str, data: Vec<u8>) -> Result<String> {
+        let chain_id = self.chain_id().await?;
+        let mut pending = self.send(to, data, chain_id).await?;
+        let started = clock::elapsed();
+        let timeout = Duration::from_secs(self.config.confirm_timeout_seconds);
+        let replace_after = Duration::from_secs(self.config.replace_after_seconds);
+        let mut replacements = 0;
+
+        loop {
+            let mut mined = None;
+            for tx_hash in &pending.hashes {
+                let receipt = match self.receipt(tx_hash).await {
+                    Ok(Some(receipt)) => receipt,
+                    Ok(None) => continue,
+                    // The transaction is in flight either way; the next poll asks again
+                    Err(e) => {
+                        warn!("Failed to fetch the receipt of transaction {}: {:?}", tx_hash, e);
+                        continue;
+                    }
+                };
+                if !receipt.status {
+                    return Err(anyhow::anyhow!("Transaction {} reverted in block {}", tx_hash, receipt.block_number));
+                }
+                if u64::from(receipt.confirmations) >= self.confirmations {
+                    info!("Transaction {} confirmed with {} confirmations", tx_hash, receipt.confirmations);
+                    return Ok(tx_hash.clone());
+                }
+                mined = Some(tx_hash);
+            }
+
+            if clock::elapsed().saturating_sub(started) >= timeout {
+                if let Some(tx_hash) = mined {
+                    warn!(
+                        "Transaction {} is mined but short of {} confirmations after {}s",
+                        tx_hash, self.confirmations, timeout.as_secs(),
+                    );
+                    return Ok(tx_hash.clone());
+                }
+                self.cancel(&pending, chain_id).await;
+                return Err(anyhow::anyhow!(
+                    "Transaction {} not confirmed within {}s",
+                    pending.hashes.last().map(String::as_str).unwrap_or_default(),
+                    timeout.as_secs(),
+                ));
+            }
+            let stuck = clock::elapsed().saturating_sub(pending.sent_at) >= replace_after;
+            if mined.is_none() && stuck && replacements < self.config.max_replacements {
+                replacements += 1;
+                self.replace(&mut pending, chain_id).await;
+            }
+
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Receipt of `tx_hash` once it is mined, with confirmations counted up to the latest block
+    pub async fn receipt(&self, tx_hash: &str) -> Result<Option<TransactionReceipt>> {
+        let _timer = metrics().rpc_latency_seconds.with_label_values(&["eth_getTransactionReceipt"]).start_timer();
+        let receipt = self.rpc.call("eth_getTransactionReceipt", json!([tx_hash])).await?;
+        if receipt.is_null() {
+            return Ok(None);
+        }
+
+        let field = |name: &str| quantity(receipt.get(name).unwrap_or(&Value::Null));
+        let block_number = field("blockNumber")?;
+        let latest_block = quantity(&self.rpc.call("eth_blockNumber", json!([])).await?)?;
+
+        Ok(Some(TransactionReceipt {
+            transaction_hash: tx_hash.to_string(),
+            block_number,
+            confirmations: u32::try_from(latest_block.saturating_sub(block_number) + 1).unwrap_or(u32::MAX),
+            status: field("status")? == 1,
+            gas_used: field("gasUsed")?,
+            effective_gas_price: field("effectiveGasPrice")?,
+        }))
+    }
+
+    async fn chain_id(&self) -> Result<u64> {
+        self.chain_id
+            .get_or_try_init(|| async { quantity(&self.rpc.call("eth_chainId", json!([])).await?) })
+            .await
+            .copied()
+    }
+
+    /// Sign and broadcast a call with the account's next nonce
+    async fn send(&self, to: &str, data: Vec<u8>, chain_id: u64) -> Result<PendingTransaction> {
+        let to = abi::address(to)?;
+        let call = json!({
+            "from": self.address,
+            "to": format!("0x{}", hex::encode(to)),
+            "data": format!("0x{}", hex::encode(&data)),
+        });
+        let estimate = quantity(
+            &self.rpc.call("eth_estimateGas", json!([call])).await
+                .context("Gas estimation failed, the call would revert")?,
+        )?;
+        let gas_limit = estimate + estimate * GAS_ESTIMATE_MARGIN_PERCENT / 100;
+        if gas_limit > self.max_gas_limit {
+            return Err(anyhow::anyhow!("Call needs {} gas, above the {} gas limit", gas_limit, self.max_gas_limit));
+        }
+        let gas_price = quantity(&self.rpc.call("eth_gasPrice", json!([])).await?)?
+            .clamp(self.min_gas_price, self.config.max_gas_price);
+
+        let mut nonce = self.nonce.lock().await;
+        let next_nonce = match *nonce {
+            Some(next_nonce) => next_nonce,
+            None => quantity(&self.rpc.call("eth_getTransactionCount", json!([self.address, "pending"])).await?)?,
+        };
+        let transaction = LegacyTransaction { nonce: next_nonce, gas_price, gas_limit, to, value: 0, data };
+
+        match self.broadcast(&transaction, chain_id).await {
+            Ok(tx_hash) => {
+                *nonce = Some(next_nonce + 1);
+                debug!("Sent transaction {} with nonce {} at {} wei gas price", tx_hash, next_nonce, gas_price);
+                Ok(PendingTransaction { transaction, hashes: vec![tx_hash], sent_at: clock::elapsed() })
+            }
+            Err(e) => {
+                // The node may know of transactions this process didn't send
+                *nonce = None;
+                Err(e)
+            }
+        }
+    }
+
+    /// Resend `pending` at a higher gas price, keeping its nonce
+    async fn replace(&self, pending: &mut PendingTransaction, chain_id: u64) {
+        let current = pending.transaction.gas_price;
+        let gas_price = self.bumped_gas_price(current);
+        pending.sent_at = clock::elapsed();
+        if gas_price <= current {
+            warn!("Transaction with nonce {} is stuck at the maximum gas price", pending.transaction.nonce);
+            return;
+        }
+
+        let replacement = LegacyTransaction { gas_price, ..pending.transaction.clone() };
+        match self.broadcast(&replacement, chain_id).await {
+            Ok(tx_hash) => {
+                info!(
+                    "Replaced transaction {} with {} at {} wei gas price",
+                    pending.hashes.last().map(String::as_str).unwrap_or_default(), tx_hash, gas_price,
+                );
+                metrics().transaction_replacements.inc();
+                pending.transaction = replacement;
+                pending.hashes.push(tx_hash);
+            }
+            // Usually the previous version was mined meanwhile, which the next poll finds
+            Err(e) => warn!("Failed to replace transaction with nonce {}: {:?}", replacement.nonce, e),
+        }
+    }
+
+    /// Send a zero-value transfer to the account itself with the nonce of `pending`, outbidding
+    /// its latest version so that whichever of them is mined frees the nonce. If none can be
+    /// sent, the next submission reads the nonce from the node again.
+    async fn cancel(&self, pending: &PendingTransaction, chain_id: u64) {
+        let nonce = pending.transaction.nonce;
+        let result = match self.cancellation(&pending.transaction) {
+            Ok(Some(cancellation)) => self.broadcast(&cancellation, chain_id).await,
+            Ok(None) => Err(anyhow::anyhow!("its gas price is already the maximum")),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(tx_hash) => info!("Cancelling transaction with nonce {} with {}", nonce, tx_hash),
+            Err(e) => {
+                warn!("Failed to cancel transaction with nonce {}: {:?}", nonce, e);
+                *self.nonce.lock().await = None;
+            }
+        }
+    }
+
+    /// Transfer of nothing to the account itself replacing `stuck`, or `None` while `stuck` is
+    /// already at the maximum gas price
+    fn cancellation(&self, stuck: &LegacyTransaction) -> Result<Option<LegacyTransaction>> {
+        let gas_price = self.bumped_gas_price(stuck.gas_price);
+        if gas_price <= stuck.gas_price {
+            return Ok(None);
+        }
+        Ok(Some(LegacyTransaction {
+            nonce: stuck.nonce,
+            gas_price,
+            gas_limit: TRANSFER_GAS,
+            to: abi::address(&self.address)?,
+            value: 0,
+            data: Vec::new(),
+        }))
+    }
+
+    /// `current` raised by `gas_bump_percent`, but not past `max_gas_price`
+    fn bumped_gas_price(&self, current: u64) -> u64 {
+        (current + current * self.config.gas_bump_percent / 100 + 1).min(self.config.max_gas_price)
+    }
+
+    async fn broadcast(&self, transaction: &LegacyTransaction, chain_id: u64) -> Result<String> {
+        let (raw, tx_hash) = transaction.sign(chain_id, &self.key)?;
+        let _timer = metrics().rpc_latency_seconds.with_label_values(&["eth_sendRawTransaction"]).start_timer();
+        self.rpc.call("eth_sendRawTransaction", json!([format!("0x{}", hex::encode(raw))])).await?;
+        Ok(tx_hash)
+    }
+}
+
+/// `bytes` without their leading zeros, as RLP encodes integers
+fn trim_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+fn rlp_uint(value: u128) -> Vec<u8> {
+    rlp_bytes(trim_leading_zeros(&value.to_be_bytes()))
+}
+
+fn rlp_bytes(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return bytes.to_vec();
+    }
+    [rlp_length(bytes.len(), 0x80), bytes.to_vec()].concat()
+}
+
+fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload = items.concat();
+    [rlp_length(payload.len(), 0xc0), payload].concat()
+}
+
+fn rlp_length(length: usize, offset: u8) -> Vec<u8> {
+    if length < 56 {
+        return vec![offset + length as u8];
+    }
+    let length_bytes = length.to_be_bytes();
+    let length_bytes = trim_leading_zeros(&length_bytes);
+    [&[offset + 55 + length_bytes.len() as u8][..], length_bytes].concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eip155_example_transaction() -> Result<()> {
+        // The worked example from EIP-155
+        let transaction = LegacyTransaction {
+            nonce: 9,
+            gas_price: 20_000_000_000,
+            gas_limit: 21_000,
+            to: [0x35; 20],
+            value: 1_000_000_000_000_000_000,
+            data: Vec::new(),
+        };
+        let key = SecretKey::from_slice(&[0x46; 32])?;
+
+        let (raw, _) = transaction.sign(1, &key)?;
+        assert_eq!(
+            hex::encode(raw),
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_cancellation_outbids_the_stuck_transaction() -> Result<()> {
+        let mut config = crate::config::Settings::default().ethereum;
+        config.private_key = crate::config::Secret::new(format!("0x{}", "46".repeat(32)));
+        config.transactions.gas_bump_percent = 10;
+        config.transactions.max_gas_price = 30_000_000_000;
+        let manager = TransactionManager::new(&config)?;
+        let stuck = LegacyTransaction {
+            nonce: 7,
+            gas_price: 20_000_000_000,
+            gas_limit: 200_000,
+            to: [0x35; 20],
+            value: 0,
+            data: vec![0xab; 36],
+        };
+
+        let cancellation = manager.cancellation(&stuck)?.unwrap();
+        assert_eq!(cancellation.nonce, 7);
+        assert_eq!(cancellation.gas_price, 22_000_000_001);
+        assert_eq!(cancellation.gas_limit, TRANSFER_GAS);
+        assert_eq!(cancellation.to, abi::address(manager.address())?);
+        assert!(cancellation.data.is_empty());
+
+        let at_maximum = LegacyTransaction { gas_price: 30_000_000_000, ..stuck };
+        assert!(manager.cancellation(&at_maximum)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rlp_lengths() {
+        assert_eq!(rlp_uint(0), vec![0x80]);
+        assert_eq!(rlp_bytes(&[0x7f]), vec![0x7f]);
+        let long = rlp_bytes(&[1u8; 60]);
+        assert_eq!(&long[..2], &[0xb8, 60]);
+    }
+}
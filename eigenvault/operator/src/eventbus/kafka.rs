@@ -0,0 +1,49 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use std::time::Duration;
+use tracing::info;
+
+use crate::config::EventBusConfig;
+use super::EventSink;
+
+/// Kafka producer sink
+pub struct KafkaSink {
+    producer: FutureProducer,
+}
+
+impl KafkaSink {
+    pub fn new(config: &EventBusConfig) -> Result<Self> {
+        info!("Creating Kafka producer for brokers: {}", config.servers.join(","));
+
+        let producer: FutureProducer = ClientConfig::new()
+            .set("bootstrap.servers", config.servers.join(","))
+            .set("client.id", &config.client_id)
+            .set("message.timeout.ms", "5000")
+            .create()
+            .map_err(|e| anyhow::anyhow!("Failed to create Kafka producer: {:?}", e))?;
+
+        Ok(Self { producer })
+    }
+}
+
+#[async_trait]
+impl EventSink for KafkaSink {
+    async fn publish(&self, topic: &str, key: &str, payload: Vec<u8>) -> Result<()> {
+        let record = FutureRecord::to(topic)
+            .key(key)
+            .payload(&payload);
+
+        self.producer
+            .send(record, Duration::from_secs(0))
+            .await
+            .map_err(|(e, _)| anyhow::anyhow!("Kafka delivery failed: {:?}", e))?;
+
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+}
@@ -0,0 +1,7 @@
+pub mod kafka;
+pub mod nats;
+pub mod publisher;
+
+pub use kafka::KafkaSink;
+pub use nats::NatsSink;
+pub use publisher::{EventEnvelope, EventPublisher, EventSink, OperatorEvent};
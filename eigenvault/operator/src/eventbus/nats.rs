@@ -0,0 +1,42 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::config::EventBusConfig;
+use super::EventSink;
+
+/// NATS publisher sink
+pub struct NatsSink {
+    client: async_nats::Client,
+}
+
+impl NatsSink {
+    pub async fn connect(config: &EventBusConfig) -> Result<Self> {
+        info!("Connecting to NATS servers: {}", config.servers.join(","));
+
+        let client = async_nats::ConnectOptions::new()
+            .name(&config.client_id)
+            .connect(config.servers.join(","))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to connect to NATS: {:?}", e))?;
+
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl EventSink for NatsSink {
+    async fn publish(&self, topic: &str, _key: &str, payload: Vec<u8>) -> Result<()> {
+        // NATS has no partition keys; subjects carry the routing information
+        self.client
+            .publish(topic.to_string(), payload.into())
+            .await
+            .map_err(|e| anyhow::anyhow!("NATS publish failed: {:?}", e))?;
+
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+}
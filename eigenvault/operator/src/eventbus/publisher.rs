@@ -0,0 +1,294 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, info, warn};
+
+use crate::config::{EventBusBackend, EventBusConfig};
+use crate::matching::{Decimal, OrderReceipt};
+use super::{KafkaSink, NatsSink};
+use crate::sim::{clock, entropy};
+
+/// Structured events emitted to downstream consumers (risk systems, UIs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OperatorEvent {
+    TaskCreated {
+        task_id: String,
+        orders_hash: String,
+        deadline: u64,
+    },
+    OrderReceived {
+        order_id: String,
+        source: String,
+    },
+    MatchFound {
+        match_id: String,
+        pool_key: String,
+        buy_order_id: String,
+        sell_order_id: String,
+        matched_amount: Decimal,
+        matched_price: Decimal,
+    },
+    ProofSubmitted {
+        task_id: String,
+        proof_id: String,
+        tx_hash: String,
+    },
+    TaskCompleted {
+        task_id: String,
+        result_hash: String,
+    },
+    /// An order entered the pending set; the receipt is the trader's evidence of it
+    OrderAccepted {
+        receipt: OrderReceipt,
+    },
+    /// A pending order was withdrawn on its trader's signed request
+    OrderCancelled {
+        order_id: String,
+    },
+    /// The AVS registry removed one of this operator's identities
+    OperatorDeregistered {
+        identity: String,
+        operator: String,
+    },
+    /// The service manager slashed an operator; `identity` is set when it is one of this
+    /// operator's own
+    OperatorSlashed {
+        identity: Option<String>,
+        operator: String,
+        amount_wei: u128,
+        reason: String,
+    },
+}
+
+impl OperatorEvent {
+    /// Topic suffix used for this event type
+    pub fn topic(&self) -> &'static str {
+        match self {
+            OperatorEvent::TaskCreated { .. } => "task_created",
+            OperatorEvent::OrderReceived { .. } => "order_received",
+            OperatorEvent::MatchFound { .. } => "match_found",
+            OperatorEvent::ProofSubmitted { .. } => "proof_submitted",
+            OperatorEvent::TaskCompleted { .. } => "task_completed",
+            OperatorEvent::OrderAccepted { .. } => "order_accepted",
+            OperatorEvent::OrderCancelled { .. } => "order_cancelled",
+            OperatorEvent::OperatorDeregistered { .. } => "operator_deregistered",
+            OperatorEvent::OperatorSlashed { .. } => "operator_slashed",
+        }
+    }
+
+    /// Partitioning key so related events land on the same partition
+    pub fn key(&self) -> &str {
+        match self {
+            OperatorEvent::TaskCreated { task_id, .. } => task_id,
+            OperatorEvent::OrderReceived { order_id, .. } => order_id,
+            OperatorEvent::MatchFound { pool_key, .. } => pool_key,
+            OperatorEvent::ProofSubmitted { task_id, .. } => task_id,
+            OperatorEvent::TaskCompleted { task_id, .. } => task_id,
+            OperatorEvent::OrderAccepted { receipt } => &receipt.commitment,
+            OperatorEvent::OrderCancelled { order_id } => order_id,
+            OperatorEvent::OperatorDeregistered { operator, .. } => operator,
+            OperatorEvent::OperatorSlashed { operator, .. } => operator,
+        }
+    }
+}
+
+/// Envelope wrapping every published event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventEnvelope {
+    pub event_id: String,
+    pub operator_id: String,
+    pub timestamp: u64,
+    pub event: OperatorEvent,
+}
+
+/// Destination for published events
+#[async_trait]
+pub trait EventSink: Send + Sync {
+    /// Publish a serialized event to the given topic
+    async fn publish(&self, topic: &str, key: &str, payload: Vec<u8>) -> Result<()>;
+
+    /// Human readable sink name for logging
+    fn name(&self) -> &'static str;
+}
+
+/// Buffered events per in-process subscriber before it starts lagging
+const LOCAL_CHANNEL_CAPACITY: usize = 1024;
+
+/// Non-blocking publisher that forwards events to the configured sink
+/// from a background task so the order pipeline never waits on the broker.
+///
+/// Events are also broadcast in-process (e.g. to WebSocket clients) whether
+/// or not an external sink is configured.
+#[derive(Clone)]
+pub struct EventPublisher {
+    operator_id: String,
+    sender: Option<mpsc::UnboundedSender<EventEnvelope>>,
+    local: broadcast::Sender<EventEnvelope>,
+}
+
+impl EventPublisher {
+    /// Connect to the configured backend and start the forwarding task
+    pub async fn start(config: EventBusConfig, operator_id: String) -> Result<Self> {
+        if !config.enabled {
+            info!("Event bus disabled");
+            return Ok(Self::new(operator_id, None));
+        }
+
+        let sink: Box<dyn EventSink> = match config.backend {
+            EventBusBackend::Kafka => Box::new(KafkaSink::new(&config)?),
+            EventBusBackend::Nats => Box::new(NatsSink::connect(&config).await?),
+        };
+
+        info!("Publishing operator events to {} at {:?}", sink.name(), config.servers);
+        Ok(Self::with_sink(sink, config.topic_prefix, operator_id))
+    }
+
+    /// Start a publisher backed by an arbitrary sink
+    pub fn with_sink(sink: Box<dyn EventSink>, topic_prefix: String, operator_id: String) -> Self {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<EventEnvelope>();
+
+        tokio::spawn(async move {
+            while let Some(envelope) = receiver.recv().await {
+                let topic = format!("{}.{}", topic_prefix, envelope.event.topic());
+                let payload = match serde_json::to_vec(&envelope) {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        warn!("Failed to serialize event {}: {:?}", envelope.event_id, e);
+                        continue;
+                    }
+                };
+
+                if let Err(e) = sink.publish(&topic, envelope.event.key(), payload).await {
+                    warn!("Failed to publish event {} to {}: {:?}", envelope.event_id, topic, e);
+                } else {
+                    debug!("Published event {} to {}", envelope.event_id, topic);
+                }
+            }
+        });
+
+        Self::new(operator_id, Some(sender))
+    }
+
+    /// Publisher without an external sink
+    pub fn disabled() -> Self {
+        Self::new(String::new(), None)
+    }
+
+    fn new(operator_id: String, sender: Option<mpsc::UnboundedSender<EventEnvelope>>) -> Self {
+        let (local, _) = broadcast::channel(LOCAL_CHANNEL_CAPACITY);
+        Self { operator_id, sender, local }
+    }
+
+    /// Receive every event published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<EventEnvelope> {
+        self.local.subscribe()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.sender.is_some()
+    }
+
+    /// Queue an event for publication
+    pub fn publish(&self, event: OperatorEvent) {
+        let envelope = EventEnvelope {
+            event_id: entropy::uuid().to_string(),
+            operator_id: self.operator_id.clone(),
+            timestamp: clock::unix_secs(),
+            event,
+        };
+
+        // No local subscribers is the common case, not an error
+        let _ = self.local.send(envelope.clone());
+
+        let Some(sender) = &self.sender else {
+            return;
+        };
+
+        if sender.send(envelope).is_err() {
+            warn!("Event bus forwarding task has stopped, dropping event");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    struct RecordingSink {
+        published: Arc<Mutex<Vec<(String, String)>>>,
+    }
+
+    #[async_trait]
+    impl EventSink for RecordingSink {
+        async fn publish(&self, topic: &str, key: &str, _payload: Vec<u8>) -> Result<()> {
+            self.published.lock().await.push((topic.to_string(), key.to_string()));
+            Ok(())
+        }
+
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let event = OperatorEvent::TaskCompleted {
+            task_id: "task_1".to_string(),
+            result_hash: "0xabc".to_string(),
+        };
+
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "task_completed");
+        assert_eq!(json["task_id"], "task_1");
+    }
+
+    #[tokio::test]
+    async fn test_publisher_forwards_to_sink() {
+        let published = Arc::new(Mutex::new(Vec::new()));
+        let sink = RecordingSink { published: published.clone() };
+        let publisher = EventPublisher::with_sink(Box::new(sink), "eigenvault".to_string(), "op".to_string());
+
+        publisher.publish(OperatorEvent::OrderReceived {
+            order_id: "order_1".to_string(),
+            source: "ethereum".to_string(),
+        });
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+        let published = published.lock().await;
+        assert_eq!(published.len(), 1);
+        assert_eq!(published[0].0, "eigenvault.order_received");
+        assert_eq!(published[0].1, "order_1");
+    }
+
+    #[test]
+    fn test_disabled_publisher() {
+        let publisher = EventPublisher::disabled();
+        assert!(!publisher.is_enabled());
+
+        // Publishing on a disabled publisher is a no-op
+        publisher.publish(OperatorEvent::TaskCompleted {
+            task_id: "task_1".to_string(),
+            result_hash: "0xabc".to_string(),
+        });
+    }
+
+    #[tokio::test]
+    async fn test_local_subscribers_receive_events() {
+        let publisher = EventPublisher::disabled();
+        let mut receiver = publisher.subscribe();
+
+        publisher.publish(OperatorEvent::TaskCreated {
+            task_id: "task_1".to_string(),
+            orders_hash: "0xabc".to_string(),
+            deadline: 100,
+        });
+
+        let envelope = receiver.recv().await.unwrap();
+        assert_eq!(envelope.event.topic(), "task_created");
+    }
+}
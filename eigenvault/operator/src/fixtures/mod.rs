@@ -0,0 +1,82 @@
+use serde::{Deserialize, Deserializer};
+use std::path::PathBuf;
+
+#[cfg(feature = "chain")]
+use crate::matching::privacy::EncryptedOrderData;
+use crate::matching::OrderMatch;
+#[cfg(feature = "full")]
+use crate::replay::ReplayRecord;
+
+// Canonical encodings checked in under `fixtures/`. Every operator must produce and accept
+// exactly these bytes, so a failing golden test means a compatibility break: fix the code,
+// never the fixture, unless the encoding is being versioned on purpose. The trader-side
+// `wasm` crate checks its encryption against `encrypted_order.json` as well.
+
+/// An order encrypted by a client, with the key pair and plaintext it was encrypted from
+#[cfg(feature = "chain")]
+#[derive(Debug, Deserialize)]
+pub struct EncryptedOrderFixture {
+    /// PKCS#1 DER committee keys
+    #[serde(deserialize_with = "hex_bytes")]
+    pub public_key: Vec<u8>,
+    #[serde(deserialize_with = "hex_bytes")]
+    pub private_key: Vec<u8>,
+    pub order_id: String,
+    pub order: EncryptedOrderData,
+    /// RSA-OAEP wrapped order key, then the nonce and the AES-GCM ciphertext
+    #[serde(deserialize_with = "hex_bytes")]
+    pub ciphertext: Vec<u8>,
+}
+
+/// A matching proof with the witness and key it was generated from
+#[derive(Debug, Deserialize)]
+pub struct ProofFixture {
+    #[serde(deserialize_with = "hex_bytes")]
+    pub signing_key: Vec<u8>,
+    pub pool_key: String,
+    /// Proving time, which deadlines are checked against
+    pub now: u64,
+    pub witness: Vec<OrderMatch>,
+    #[serde(deserialize_with = "hex_bytes")]
+    pub proof_data: Vec<u8>,
+    #[serde(deserialize_with = "hex_bytes")]
+    pub public_inputs: Vec<u8>,
+    #[serde(deserialize_with = "hex_bytes")]
+    pub operator_signature: Vec<u8>,
+}
+
+/// Encoded contract events and gossip messages, each paired with its line
+#[cfg(feature = "full")]
+pub fn records() -> Vec<(String, ReplayRecord)> {
+    read("events.jsonl")
+        .lines()
+        .map(|line| {
+            let record = serde_json::from_str(line)
+                .unwrap_or_else(|e| panic!("Invalid fixture record {}: {}", line, e));
+            (line.to_string(), record)
+        })
+        .collect()
+}
+
+#[cfg(feature = "chain")]
+pub fn encrypted_order() -> EncryptedOrderFixture {
+    parse("encrypted_order.json")
+}
+
+pub fn proof() -> ProofFixture {
+    parse("proof.json")
+}
+
+fn parse<T: serde::de::DeserializeOwned>(name: &str) -> T {
+    serde_json::from_str(&read(name)).unwrap_or_else(|e| panic!("Invalid fixture {}: {}", name, e))
+}
+
+fn read(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("Failed to read fixture {:?}: {}", path, e))
+}
+
+fn hex_bytes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    hex::decode(encoded).map_err(serde::de::Error::custom)
+}
@@ -0,0 +1,8 @@
+pub mod service;
+
+/// Types and service traits generated from `proto/eigenvault/operator/v1/admin.proto`
+pub mod proto {
+    tonic::include_proto!("eigenvault.operator.v1");
+}
+
+pub use service::{serve, AdminService};
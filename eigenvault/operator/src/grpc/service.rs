@@ -0,0 +1,167 @@
+use anyhow::Result;
+use std::net::SocketAddr;
+use tonic::{
+    transport::{Identity, Server, ServerTlsConfig},
+    Request, Response, Status,
+};
+use tracing::info;
+
+use crate::api::{auth, ApiState, Caller};
+use crate::config::GrpcConfig;
+use super::proto::{
+    operator_admin_server::{OperatorAdmin, OperatorAdminServer},
+    GetStatsRequest, GetStatsResponse, ListPeersRequest, ListPeersResponse,
+    ListPendingTasksRequest, ListPendingTasksResponse, MatchingState, PauseMatchingRequest, Peer,
+    PoolStats, ResumeMatchingRequest, Task, TriggerCheckpointRequest, TriggerCheckpointResponse,
+};
+
+/// gRPC control plane over the same shared state as the HTTP API
+pub struct AdminService {
+    state: ApiState,
+}
+
+impl AdminService {
+    pub fn new(state: ApiState) -> Self {
+        Self { state }
+    }
+
+    fn matching_state(&self) -> Response<MatchingState> {
+        Response::new(MatchingState {
+            paused: self.state.control.is_matching_paused(),
+        })
+    }
+}
+
+/// Fail unless the interceptor attached a caller with the control role
+fn require_control<T>(request: &Request<T>) -> Result<(), Status> {
+    match request.extensions().get::<Caller>() {
+        Some(caller) if caller.can_control() => Ok(()),
+        Some(_) => Err(Status::permission_denied("API key is read-only")),
+        None => Err(Status::unauthenticated("Missing API key")),
+    }
+}
+
+#[tonic::async_trait]
+impl OperatorAdmin for AdminService {
+    async fn pause_matching(&self, request: Request<PauseMatchingRequest>) -> Result<Response<MatchingState>, Status> {
+        require_control(&request)?;
+        self.state.control.pause_matching();
+        Ok(self.matching_state())
+    }
+
+    async fn resume_matching(&self, request: Request<ResumeMatchingRequest>) -> Result<Response<MatchingState>, Status> {
+        require_control(&request)?;
+        self.state.control.resume_matching();
+        Ok(self.matching_state())
+    }
+
+    async fn list_peers(&self, _request: Request<ListPeersRequest>) -> Result<Response<ListPeersResponse>, Status> {
+        let peers = self.state.status.snapshot().await.peers
+            .into_iter()
+            .map(|peer| Peer {
+                peer_id: peer.peer_id,
+                active: peer.is_active,
+                messages_sent: peer.messages_sent,
+                reputation: peer.reputation,
+            })
+            .collect();
+
+        Ok(Response::new(ListPeersResponse { peers }))
+    }
+
+    async fn list_pending_tasks(&self, _request: Request<ListPendingTasksRequest>) -> Result<Response<ListPendingTasksResponse>, Status> {
+        let tasks = self.state.status.snapshot().await.pending_tasks
+            .into_iter()
+            .map(|task| Task {
+                task_id: task.task_id,
+                orders_set_hash: task.orders_set_hash,
+                deadline: task.deadline,
+                assigned_operators: task.assigned_operators,
+                // The proto field is a uint64; no stake requirement comes near it
+                minimum_stake: u64::try_from(task.minimum_stake).unwrap_or(u64::MAX),
+                created_at: task.created_at,
+            })
+            .collect();
+
+        Ok(Response::new(ListPendingTasksResponse { tasks }))
+    }
+
+    async fn trigger_checkpoint(&self, request: Request<TriggerCheckpointRequest>) -> Result<Response<TriggerCheckpointResponse>, Status> {
+        require_control(&request)?;
+        let store = self.state.store.clone();
+
+        // RocksDB flush and checkpoint are blocking I/O
+        let snapshot_path = tokio::task::spawn_blocking(move || store.snapshot())
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .map_err(|e| Status::internal(e.to_string()))?;
+
+        Ok(Response::new(TriggerCheckpointResponse {
+            snapshot_path: snapshot_path.to_string_lossy().to_string(),
+        }))
+    }
+
+    async fn get_stats(&self, _request: Request<GetStatsRequest>) -> Result<Response<GetStatsResponse>, Status> {
+        let status = self.state.status.snapshot().await;
+
+        Ok(Response::new(GetStatsResponse {
+            updated_at: status.updated_at,
+            last_processed_block: status.last_processed_block,
+            proof_queue_depth: status.proof_queue_depth,
+            matching_paused: self.state.control.is_matching_paused(),
+            active_peers: status.peers.iter().filter(|peer| peer.is_active).count() as u64,
+            pools: status.pools.into_iter()
+                .map(|pool| PoolStats {
+                    pool_key: pool.pool_key,
+                    active_buy_orders: pool.active_buy_orders as u64,
+                    active_sell_orders: pool.active_sell_orders as u64,
+                    spread: pool.spread,
+                })
+                .collect(),
+            recent_matches: status.recent_matches.len() as u64,
+            unconfirmed_submissions: status.recent_submissions.iter()
+                .filter(|submission| !submission.confirmed)
+                .count() as u64,
+        }))
+    }
+}
+
+/// Serve the gRPC admin API, requiring an API key as bearer token
+pub async fn serve(config: GrpcConfig, state: ApiState) -> Result<()> {
+    if state.keys.is_empty() {
+        return Err(anyhow::anyhow!("gRPC admin API requires api.admin_token or api.api_keys"));
+    }
+    let keys = state.keys.clone();
+
+    let service = OperatorAdminServer::with_interceptor(AdminService::new(state), move |mut request: Request<()>| {
+        let caller = request.metadata().get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .and_then(|token| keys.authenticate(token))
+            .cloned()
+            .ok_or_else(|| Status::unauthenticated("Invalid or missing API key"))?;
+
+        request.extensions_mut().insert(caller);
+        Ok(request)
+    });
+
+    let address: SocketAddr = config.listen_address.parse()?;
+    auth::warn_if_exposed("gRPC admin API", &address, config.tls.is_some());
+
+    let mut server = Server::builder();
+    if let Some(tls) = &config.tls {
+        let identity = Identity::from_pem(
+            tokio::fs::read(&tls.cert_path).await?,
+            tokio::fs::read(&tls.key_path).await?,
+        );
+        server = server.tls_config(ServerTlsConfig::new().identity(identity))?;
+    }
+
+    info!("gRPC admin API listening on {}{}", address, if config.tls.is_some() { " (TLS)" } else { "" });
+    server
+        .add_service(service)
+        .serve(address)
+        .await?;
+
+    Ok(())
+}
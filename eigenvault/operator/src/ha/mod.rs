@@ -0,0 +1,241 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::config::HaConfig;
+use crate::metrics::metrics;
+use crate::sim::clock;
+
+/// Operator task name of the lease heartbeat
+pub const LEASE_TASK: &str = "ha_lease";
+
+/// Contents of the lease file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LeaseRecord {
+    pub holder: String,
+    /// Unix secs
+    pub expires_at: u64,
+}
+
+/// File lease electing the one active instance among a primary and its standbys.
+///
+/// The holder renews it every heartbeat and a standby takes it over once it expires. Each check
+/// and write of the lease happens under an exclusive lock on a file beside it, so two standbys
+/// can't both take it. A holder that finds its lease taken, or can't renew it before it runs
+/// out, must stop at once so two instances never respond to the same task; until it has, it
+/// submits nothing past the expiry it last wrote.
+pub struct Lease {
+    config: HaConfig,
+    /// Unix secs this instance's lease runs until, as last written; 0 before it is acquired
+    expires_at: AtomicU64,
+}
+
+impl Lease {
+    pub fn new(config: HaConfig) -> Self {
+        Self { config, expires_at: AtomicU64::new(0) }
+    }
+
+    /// The lease as last written, if any instance ever took it
+    pub fn read(&self) -> Result<Option<LeaseRecord>> {
+        match std::fs::read(&self.config.lease_path) {
+            Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes).context("Malformed lease file")?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e).with_context(|| format!("Failed to read lease {}", self.config.lease_path)),
+        }
+    }
+
+    /// Take the lease if it is free, expired or already ours
+    pub fn try_acquire(&self) -> Result<bool> {
+        let _lock = self.lock()?;
+        if let Some(lease) = self.read()? {
+            if lease.holder != self.config.instance_id && lease.expires_at > clock::unix_secs() {
+                return Ok(false);
+            }
+        }
+        self.write()?;
+        Ok(true)
+    }
+
+    /// Extend the lease; `false` when another instance has taken it over
+    pub fn renew(&self) -> Result<bool> {
+        let _lock = self.lock()?;
+        if self.read()?.is_some_and(|lease| lease.holder != self.config.instance_id) {
+            return Ok(false);
+        }
+        self.write()?;
+        Ok(true)
+    }
+
+    /// Whether this instance's lease is still running; a standby may take it over once it
+    /// isn't, so nothing may be submitted to the chain then
+    pub fn is_held(&self) -> bool {
+        clock::unix_secs() < self.expires_at.load(Ordering::SeqCst)
+    }
+
+    /// Stand by until the lease is ours
+    pub async fn acquire(&self) {
+        metrics().ha_active.set(0);
+        let mut interval = tokio::time::interval(self.heartbeat());
+        let mut standing_by_for = None;
+
+        loop {
+            interval.tick().await;
+            match self.try_acquire() {
+                Ok(true) => break,
+                Ok(false) => {
+                    let holder = self.read().ok().flatten().map(|lease| lease.holder);
+                    if holder != standing_by_for {
+                        info!("Standing by: {} holds the failover lease", holder.as_deref().unwrap_or("another instance"));
+                        standing_by_for = holder;
+                    }
+                }
+                Err(e) => warn!("Failover lease check failed: {:?}", e),
+            }
+        }
+
+        info!("Instance {} holds the failover lease; taking over task processing", self.config.instance_id);
+        metrics().ha_active.set(1);
+    }
+
+    /// Renew every heartbeat until shut down; fails as soon as the lease is lost
+    pub async fn hold(self: Arc<Self>, shutdown: CancellationToken) -> Result<()> {
+        let mut interval = tokio::time::interval(self.heartbeat());
+
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = shutdown.cancelled() => return Ok(()),
+            }
+
+            let lost = match self.renew() {
+                Ok(true) => continue,
+                Ok(false) => "taken over by another instance",
+                Err(e) if !self.is_held() => {
+                    error!("Failover lease could not be renewed before expiring: {:?}", e);
+                    "expired"
+                }
+                Err(e) => {
+                    warn!("Failover lease renewal failed, retrying: {:?}", e);
+                    continue;
+                }
+            };
+
+            metrics().ha_active.set(0);
+            return Err(anyhow!("Failover lease {}", lost));
+        }
+    }
+
+    /// Exclusive lock on the file beside the lease, held until the returned file is dropped.
+    /// The system releases it when the process dies, so a crashed holder can't leave it taken.
+    fn lock(&self) -> Result<File> {
+        let path = format!("{}.lock", self.config.lease_path);
+        if let Some(parent) = Path::new(&path).parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open lease lock {}", path))?;
+        file.lock().with_context(|| format!("Failed to lock {}", path))?;
+        Ok(file)
+    }
+
+    /// Write the lease as ours; the caller holds the lock
+    fn write(&self) -> Result<()> {
+        let lease = LeaseRecord {
+            holder: self.config.instance_id.clone(),
+            expires_at: clock::unix_secs() + self.config.lease_seconds,
+        };
+
+        // Rename is atomic, so a reader never sees half a lease
+        let path = Path::new(&self.config.lease_path);
+        let staging = path.with_extension(format!("{}.tmp", self.config.instance_id));
+        std::fs::write(&staging, serde_json::to_vec(&lease)?)?;
+        std::fs::rename(&staging, path)
+            .with_context(|| format!("Failed to write lease {}", self.config.lease_path))?;
+        self.expires_at.store(lease.expires_at, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn heartbeat(&self) -> Duration {
+        Duration::from_secs(self.config.heartbeat_seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn lease(dir: &TempDir, instance_id: &str, lease_seconds: u64) -> Lease {
+        Lease::new(HaConfig {
+            enabled: true,
+            instance_id: instance_id.to_string(),
+            lease_path: dir.path().join("ha.lease").display().to_string(),
+            lease_seconds,
+            heartbeat_seconds: 1,
+        })
+    }
+
+    #[test]
+    fn test_standby_waits_for_live_lease() -> Result<()> {
+        let dir = TempDir::new()?;
+        let primary = lease(&dir, "primary", 60);
+        let standby = lease(&dir, "standby", 60);
+
+        assert!(primary.try_acquire()?);
+        assert!(!standby.try_acquire()?);
+        assert!(primary.renew()?);
+        assert_eq!(standby.read()?.unwrap().holder, "primary");
+        Ok(())
+    }
+
+    #[test]
+    fn test_standby_takes_over_expired_lease() -> Result<()> {
+        let dir = TempDir::new()?;
+        let primary = lease(&dir, "primary", 0);
+        let standby = lease(&dir, "standby", 0);
+
+        assert!(primary.try_acquire()?);
+        assert!(standby.try_acquire()?);
+        // The old primary must stop rather than renew
+        assert!(!primary.renew()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_one_of_racing_standbys_takes_the_lease() -> Result<()> {
+        let dir = TempDir::new()?;
+        let standbys: Vec<Lease> = (0..8).map(|i| lease(&dir, &format!("standby_{}", i), 60)).collect();
+
+        let acquired = std::thread::scope(|scope| {
+            let racers: Vec<_> = standbys.iter().map(|standby| scope.spawn(|| standby.try_acquire())).collect();
+            racers.into_iter().map(|racer| racer.join().unwrap()).collect::<Result<Vec<_>>>()
+        })?;
+        assert_eq!(acquired.iter().filter(|acquired| **acquired).count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_lease_is_held_only_until_it_expires() -> Result<()> {
+        let dir = TempDir::new()?;
+        let primary = lease(&dir, "primary", 60);
+        assert!(!primary.is_held());
+        assert!(primary.try_acquire()?);
+        assert!(primary.is_held());
+
+        let dir = TempDir::new()?;
+        let expiring = lease(&dir, "expiring", 0);
+        assert!(expiring.try_acquire()?);
+        assert!(!expiring.is_held());
+        Ok(())
+    }
+}
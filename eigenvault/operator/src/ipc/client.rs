@@ -0,0 +1,32 @@
+use anyhow::{anyhow, Result};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+use super::{IpcStatus, STATUS_COMMAND};
+
+/// Ask a running operator for its status over the local IPC endpoint
+pub async fn request_status(endpoint: &str) -> Result<IpcStatus> {
+    let stream = connect(endpoint).await
+        .map_err(|e| anyhow!("Cannot reach operator at {} (is it running?): {}", endpoint, e))?;
+
+    let response = exchange(stream, STATUS_COMMAND).await?;
+    Ok(serde_json::from_str(&response)?)
+}
+
+#[cfg(unix)]
+async fn connect(endpoint: &str) -> std::io::Result<tokio::net::UnixStream> {
+    tokio::net::UnixStream::connect(endpoint).await
+}
+
+#[cfg(windows)]
+async fn connect(endpoint: &str) -> std::io::Result<tokio::net::windows::named_pipe::NamedPipeClient> {
+    tokio::net::windows::named_pipe::ClientOptions::new().open(endpoint)
+}
+
+async fn exchange<S: AsyncRead + AsyncWrite + Unpin>(stream: S, command: &str) -> Result<String> {
+    let mut stream = BufReader::new(stream);
+    stream.get_mut().write_all(format!("{}\n", command).as_bytes()).await?;
+
+    let mut response = String::new();
+    stream.read_line(&mut response).await?;
+    Ok(response)
+}
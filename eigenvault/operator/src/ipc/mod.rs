@@ -0,0 +1,24 @@
+pub mod client;
+pub mod server;
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::status::SubmissionSummary;
+use crate::api::HealthReport;
+
+pub use client::request_status;
+pub use server::serve;
+
+/// Request line understood by the IPC server
+pub const STATUS_COMMAND: &str = "status";
+
+/// Quick health summary returned over the local socket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpcStatus {
+    pub health: HealthReport,
+    pub last_processed_block: u64,
+    pub active_peers: usize,
+    pub pending_orders: usize,
+    pub matching_paused: bool,
+    pub last_submission: Option<SubmissionSummary>,
+}
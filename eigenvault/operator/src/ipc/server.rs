@@ -0,0 +1,94 @@
+use anyhow::Result;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tracing::{debug, info, warn};
+
+use crate::api::ApiState;
+use crate::config::IpcConfig;
+use super::{IpcStatus, STATUS_COMMAND};
+
+/// Answer `status` requests on the local socket (named pipe on Windows)
+pub async fn serve(config: IpcConfig, state: ApiState) -> Result<()> {
+    listen(&config.endpoint, state).await
+}
+
+#[cfg(unix)]
+async fn listen(endpoint: &str, state: ApiState) -> Result<()> {
+    use tokio::net::UnixListener;
+
+    // A socket left behind by an unclean shutdown would make bind fail
+    if std::path::Path::new(endpoint).exists() {
+        std::fs::remove_file(endpoint)?;
+    }
+
+    let listener = UnixListener::bind(endpoint)?;
+    info!("IPC status socket listening on {}", endpoint);
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, &state).await {
+                warn!("IPC connection failed: {:?}", e);
+            }
+        });
+    }
+}
+
+#[cfg(windows)]
+async fn listen(endpoint: &str, state: ApiState) -> Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let mut server = ServerOptions::new().first_pipe_instance(true).create(endpoint)?;
+    info!("IPC status pipe listening on {}", endpoint);
+
+    loop {
+        server.connect().await?;
+        let connected = server;
+        // Create the next instance before serving so clients never see the pipe missing
+        server = ServerOptions::new().create(endpoint)?;
+
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(connected, &state).await {
+                warn!("IPC connection failed: {:?}", e);
+            }
+        });
+    }
+}
+
+async fn handle_connection<S: AsyncRead + AsyncWrite + Unpin>(stream: S, state: &ApiState) -> Result<()> {
+    let mut stream = BufReader::new(stream);
+    let mut line = String::new();
+    stream.read_line(&mut line).await?;
+
+    let response = match line.trim() {
+        STATUS_COMMAND => serde_json::to_string(&collect_status(state).await?)?,
+        other => {
+            debug!("Unknown IPC command: {:?}", other);
+            serde_json::json!({ "error": format!("unknown command: {}", other) }).to_string()
+        }
+    };
+
+    let stream = stream.get_mut();
+    stream.write_all(response.as_bytes()).await?;
+    stream.write_all(b"\n").await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+async fn collect_status(state: &ApiState) -> Result<IpcStatus> {
+    let status = state.status.snapshot().await;
+    let pending_orders = state.matching.get_order_book_stats().await?
+        .iter()
+        .map(|pool| pool.total_orders)
+        .sum();
+
+    Ok(IpcStatus {
+        health: state.health.snapshot().await,
+        last_processed_block: status.last_processed_block,
+        active_peers: status.peers.iter().filter(|peer| peer.is_active).count(),
+        pending_orders,
+        matching_paused: state.control.is_matching_paused(),
+        last_submission: status.recent_submissions.front().cloned(),
+    })
+}
@@ -0,0 +1,95 @@
+//! Off-chain operator for the EigenVault AVS.
+//!
+//! The `eigenvault-operator` binary is a command-line wrapper around this crate. Embedders can
+//! drive the pieces on their own: [`MatchingEngine`] and [`OrderBook`] match decrypted orders,
+//! [`ZKProver`] proves the results, [`P2PNetwork`] gossips with the rest of the committee and
+//! [`EthereumClient`] talks to the AVS contracts. [`Operator`] wires them together the way the
+//! binary runs them, configured by [`Settings`].
+//!
+//! Components beyond matching sit behind Cargo features: `prover`, `telemetry`, `networking`
+//! (which includes `telemetry`), `chain` (which includes `prover`) and `full`, the default, for
+//! everything the binary needs. Building with `--no-default-features --features matching-only`
+//! leaves just the matching engine, order books, the order journal and their configuration.
+
+#[cfg(all(feature = "matching-only", any(feature = "prover", feature = "networking", feature = "chain")))]
+compile_error!("`matching-only` can't be combined with `prover`, `networking` or `chain`; build it with `--no-default-features`");
+
+#[cfg(feature = "full")]
+pub mod accounting;
+#[cfg(feature = "full")]
+pub mod api;
+#[cfg(feature = "chain")]
+pub mod audit;
+#[cfg(feature = "full")]
+pub mod backpressure;
+#[cfg(feature = "full")]
+pub mod bench;
+#[cfg(feature = "full")]
+pub mod bootstrap;
+pub mod cache;
+#[cfg(any(feature = "chain", feature = "networking"))]
+pub mod chaos;
+#[cfg(feature = "full")]
+pub mod client;
+pub mod config;
+#[cfg(feature = "full")]
+pub mod devnet;
+#[cfg(feature = "full")]
+pub mod doctor;
+#[cfg(feature = "chain")]
+pub mod ethereum;
+#[cfg(feature = "full")]
+pub mod eventbus;
+/// Golden encodings and their loaders for compatibility tests
+#[cfg(all(test, feature = "prover"))]
+mod fixtures;
+#[cfg(feature = "full")]
+pub mod grpc;
+#[cfg(feature = "full")]
+pub mod ha;
+#[cfg(feature = "full")]
+pub mod ipc;
+#[cfg(feature = "full")]
+pub mod loadgen;
+pub mod matching;
+pub mod metrics;
+#[cfg(feature = "networking")]
+pub mod networking;
+#[cfg(feature = "full")]
+pub mod notify;
+#[cfg(feature = "full")]
+pub mod operator;
+#[cfg(feature = "prover")]
+pub mod proofs;
+#[cfg(feature = "full")]
+pub mod quorum;
+#[cfg(feature = "full")]
+pub mod registration;
+#[cfg(feature = "full")]
+pub mod replay;
+#[cfg(feature = "full")]
+pub mod risk;
+#[cfg(feature = "full")]
+pub mod scheduler;
+pub mod sim;
+#[cfg(feature = "full")]
+pub mod staking;
+#[cfg(feature = "full")]
+pub mod storage;
+#[cfg(feature = "full")]
+pub mod supervisor;
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
+#[cfg(feature = "full")]
+pub mod tui;
+
+pub use config::{Config, EthereumConfig, MatchingConfig, NetworkingConfig, ProofConfig, Settings};
+#[cfg(feature = "chain")]
+pub use ethereum::EthereumClient;
+pub use matching::{MatchingEngine, OrderBook};
+#[cfg(feature = "networking")]
+pub use networking::P2PNetwork;
+#[cfg(feature = "full")]
+pub use operator::Operator;
+#[cfg(feature = "prover")]
+pub use proofs::ZKProver;
@@ -0,0 +1,294 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::ethereum::{EthereumEvent, MockChain};
+use crate::matching::privacy::{EncryptedOrderData, EncryptionKeys};
+use crate::matching::signing::{self, OrderMessage};
+use crate::matching::{EncryptionManager, OrderDomain, OrderType};
+use crate::networking::{P2PMessage, P2PNetwork};
+use crate::sim::entropy;
+
+/// Where generated orders are injected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum LoadTarget {
+    /// Gossip to a running operator as a peer would
+    Gossip,
+    /// Emit `OrderStored` from a mock hook into an in-process operator
+    Hook,
+}
+
+/// Orders per second, parsed from `500/s`, `30/m` or a bare number
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rate(f64);
+
+impl Rate {
+    pub fn per_second(&self) -> f64 {
+        self.0
+    }
+}
+
+impl FromStr for Rate {
+    type Err = anyhow::Error;
+
+    fn from_str(raw: &str) -> Result<Self> {
+        let (count, seconds) = match raw.trim().split_once('/') {
+            Some((count, "s")) => (count, 1.0),
+            Some((count, "m")) => (count, 60.0),
+            Some((_, unit)) => return Err(anyhow::anyhow!("Unknown rate unit {:?}, expected s or m", unit)),
+            None => (raw.trim(), 1.0),
+        };
+        let count: f64 = count.parse().map_err(|_| anyhow::anyhow!("Invalid rate {:?}", raw))?;
+        if !count.is_finite() || count <= 0.0 {
+            return Err(anyhow::anyhow!("Rate must be positive, got {:?}", raw));
+        }
+        Ok(Self(count / seconds))
+    }
+}
+
+/// An encrypted order ready to inject
+pub struct SyntheticOrder {
+    pub order_id: String,
+    pub trader: String,
+    pub encrypted_data: Vec<u8>,
+}
+
+/// Number of synthetic traders orders are spread over
+const TRADERS: usize = 100;
+
+/// Alternating buy and sell orders around a mid price, encrypted and signed the way traders
+/// encrypt and sign them
+pub struct OrderGenerator {
+    pool: String,
+    encryption: EncryptionManager,
+    domain: OrderDomain,
+    /// Address and signing key of each synthetic trader, fresh for every run
+    traders: Vec<(String, String)>,
+    run_id: String,
+    sequence: u64,
+    mid_price: f64,
+}
+
+impl OrderGenerator {
+    /// Generator encrypting for the committee key `keys`, as published to traders, and signing
+    /// for the OrderVault of `domain`
+    pub fn new(pool: &str, mid_price: f64, keys: EncryptionKeys, domain: OrderDomain) -> Result<Self> {
+        let traders = (0..TRADERS)
+            .map(|_| {
+                let private_key = format!("0x{}", hex::encode(entropy::bytes::<32>()));
+                Ok((signing::signer_address(&private_key)?, private_key))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            pool: pool.to_string(),
+            encryption: EncryptionManager::from_keys(keys)?,
+            domain,
+            traders,
+            run_id: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+            sequence: 0,
+            mid_price,
+        })
+    }
+
+    pub fn next_order(&mut self) -> Result<SyntheticOrder> {
+        let mut rng = rand::thread_rng();
+        self.sequence += 1;
+
+        // Buys sit slightly above mid and sells slightly below, so most neighbours cross
+        let (order_type, skew) = if self.sequence % 2 == 1 { (OrderType::Buy, 1.0) } else { (OrderType::Sell, -1.0) };
+        let price = self.mid_price * (1.0 + skew * rng.gen_range(0.0..0.005));
+        let amount: f64 = rng.gen_range(0.1..5.0);
+
+        let (trader, private_key) = &self.traders[self.sequence as usize % TRADERS];
+        let mut order = EncryptedOrderData {
+            trader: trader.clone(),
+            pool_key: self.pool.clone(),
+            order_type,
+            // Cut to cents and 0.0001 units like real orders
+            amount: Decimal::try_from((amount * 1e4).round() / 1e4)?,
+            price: Decimal::try_from((price * 100.0).round() / 100.0)?,
+            deadline: chrono::Utc::now().timestamp() as u64 + 3600,
+            nonce: rand::random::<[u8; 16]>().to_vec(),
+            commitment: String::new(),
+            signature: None,
+            // Each trader's orders count up from 1
+            trader_nonce: Some(self.sequence.div_ceil(TRADERS as u64)),
+            time_in_force: None,
+        };
+        order.commitment = self.encryption.generate_commitment(&order)?;
+        order.signature = Some(self.domain.sign(&OrderMessage::from(&order), private_key)?);
+
+        Ok(SyntheticOrder {
+            order_id: format!("loadgen_{}_{}", self.run_id, self.sequence),
+            encrypted_data: self.encryption.encrypt_order(&order)?,
+            trader: order.trader,
+        })
+    }
+}
+
+/// Destination of generated orders
+pub enum LoadSink {
+    Gossip { network: P2PNetwork, peer_id: String },
+    Hook(MockChain),
+}
+
+impl LoadSink {
+    /// Gossip to the first peer `network` connected to
+    pub fn gossip(network: P2PNetwork) -> Result<Self> {
+        let peer_id = network.get_active_peers().first()
+            .map(|peer| peer.peer_id.clone())
+            .ok_or_else(|| anyhow::anyhow!("Not connected to the target operator"))?;
+        Ok(Self::Gossip { network, peer_id })
+    }
+
+    async fn send(&mut self, order: SyntheticOrder) -> Result<()> {
+        match self {
+            Self::Gossip { network, peer_id } => {
+                let message = P2PMessage::OrderGossip {
+                    order_id: order.order_id,
+                    encrypted_data: order.encrypted_data,
+                    signature: Vec::new(),
+                };
+                network.send_message_to_peer(peer_id, &message).await
+            }
+            Self::Hook(chain) => {
+                chain.push_event(EthereumEvent::OrderStored {
+                    order_id: order.order_id,
+                    trader: order.trader,
+                    encrypted_order: order.encrypted_data,
+                });
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Totals for one load generation run
+#[derive(Debug, Default)]
+pub struct LoadReport {
+    pub sent: u64,
+    pub failed: u64,
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.elapsed.as_secs_f64().max(f64::EPSILON);
+        write!(
+            f,
+            "{} orders sent, {} failed in {:.1}s ({:.1}/s achieved)",
+            self.sent, self.failed, seconds, self.sent as f64 / seconds
+        )
+    }
+}
+
+/// Inject orders at `rate` until `duration` elapses or `shutdown` is cancelled
+pub async fn run(
+    mut generator: OrderGenerator,
+    mut sink: LoadSink,
+    rate: Rate,
+    duration: Option<Duration>,
+    shutdown: CancellationToken,
+) -> Result<LoadReport> {
+    info!("Generating {:.1} orders/s for pool {}", rate.per_second(), generator.pool);
+
+    let started = Instant::now();
+    let deadline = duration.map(|duration| started + duration);
+    // Missed ticks burst to catch up, keeping the average rate when a send stalls
+    let mut ticks = tokio::time::interval(Duration::from_secs_f64(1.0 / rate.per_second()));
+    let mut progress = tokio::time::interval(Duration::from_secs(10));
+    progress.tick().await;
+    let mut report = LoadReport::default();
+
+    loop {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
+
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = progress.tick() => {
+                report.elapsed = started.elapsed();
+                info!("{}", report);
+            }
+            _ = ticks.tick() => {
+                let result = match generator.next_order() {
+                    Ok(order) => sink.send(order).await,
+                    Err(e) => Err(e),
+                };
+                match result {
+                    Ok(()) => report.sent += 1,
+                    Err(e) => {
+                        report.failed += 1;
+                        warn!("Failed to inject order: {:?}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    report.elapsed = started.elapsed();
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethereum::ChainClient;
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!("500/s".parse::<Rate>().unwrap().per_second(), 500.0);
+        assert_eq!("30/m".parse::<Rate>().unwrap().per_second(), 0.5);
+        assert_eq!("20".parse::<Rate>().unwrap().per_second(), 20.0);
+        assert!("0/s".parse::<Rate>().is_err());
+        assert!("5/h".parse::<Rate>().is_err());
+    }
+
+    fn domain() -> OrderDomain {
+        OrderDomain { chain_id: 31337, verifying_contract: "0x3456789012345678901234567890123456789012".to_string() }
+    }
+
+    #[test]
+    fn test_orders_decrypt_with_the_committee_key() -> Result<()> {
+        let operator = EncryptionManager::new()?;
+        let published = EncryptionKeys { public_key: operator.get_public_key()?, private_key: Vec::new() };
+        let mut generator = OrderGenerator::new("ETH_USDC_3000", 2000.0, published, domain())?;
+
+        let orders = (0..=TRADERS)
+            .map(|_| {
+                let order = generator.next_order()?;
+                let decrypted = operator.decrypt_order(&order.encrypted_data, order.order_id)?;
+                domain().verify(&OrderMessage::from(&decrypted), decrypted.signature.as_deref().unwrap())?;
+                Ok(decrypted)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        // The first trader comes round again with its next nonce
+        assert_eq!(orders[TRADERS].trader, orders[0].trader);
+        assert_eq!((orders[0].trader_nonce, orders[TRADERS].trader_nonce), (Some(1), Some(2)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hook_injection() -> Result<()> {
+        let keys = EncryptionManager::new()?.export_keys()?;
+        let generator = OrderGenerator::new("ETH_USDC_3000", 2000.0, keys, domain())?;
+        let chain = MockChain::new();
+        let shutdown = CancellationToken::new();
+
+        let report = run(generator, LoadSink::Hook(chain.clone()), "200/s".parse()?, Some(Duration::from_millis(100)), shutdown).await?;
+
+        let events = chain.listen_for_events().await?;
+        assert!(report.sent > 0);
+        assert_eq!(report.failed, 0);
+        assert_eq!(events.len() as u64, report.sent);
+        assert!(matches!(&events[0].event, EthereumEvent::OrderStored { encrypted_order, .. } if !encrypted_order.is_empty()));
+        Ok(())
+    }
+}
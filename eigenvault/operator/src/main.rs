@@ -1,21 +1,30 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 use tracing::{info, warn, error};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use uuid;
 
-mod config;
-mod ethereum;
-mod matching;
-mod networking;
-mod proofs;
-
-use config::{Config, KeyManager, EthereumConfig, MatchingConfig, NetworkingConfig, ProofConfig};
-use ethereum::EthereumClient;
-use matching::MatchingEngine;
-use networking::P2PNetwork;
-use proofs::ZKProver;
+use eigenvault_operator::{accounting, api, bench, bootstrap, chaos, client, config, devnet, doctor, ethereum, grpc, ipc, loadgen, matching, metrics, replay, sim, storage, telemetry, tui};
+use eigenvault_operator::accounting::{Accountant, EarningsReport};
+use eigenvault_operator::api::{ApiKeys, ApiState, HealthRegistry, OperatorControl, StatusBoard};
+use eigenvault_operator::audit::{AuditLog, AuditOutcome, TransactionKind};
+use eigenvault_operator::config::{Config, Layered, Profile, KeyManager};
+use eigenvault_operator::ethereum::{EthereumClient, MockChain, OperatorIdentity, OperatorMetadata};
+use eigenvault_operator::eventbus::EventPublisher;
+use eigenvault_operator::ha::Lease;
+use eigenvault_operator::matching::privacy::EncryptionKeys;
+use eigenvault_operator::matching::journal::{self, JournalEntry, OrderJournal};
+use eigenvault_operator::matching::{DecryptedOrder, EncryptionManager, MatchingEngine, OrderKeyDocument};
+use eigenvault_operator::metrics::LatencyTracker;
+use eigenvault_operator::networking::{P2PNetwork, PeerList};
+use eigenvault_operator::notify::WebhookNotifier;
+use eigenvault_operator::operator::{wait_for_signal, Operator};
+use eigenvault_operator::proofs::ZKProver;
+use eigenvault_operator::staking::StakeTopUp;
+use eigenvault_operator::storage::{StateCheckpoint, Store, UpgradeContext, BINARY_VERSION, UPGRADE_HOOKS};
 
 #[derive(Parser)]
 #[command(name = "eigenvault-operator")]
@@ -32,12 +41,29 @@ enum Commands {
         /// Configuration file path
         #[arg(short, long, default_value = "config.yaml")]
         config: PathBuf,
+        /// Preset to write instead of the defaults
+        #[arg(long, value_enum)]
+        profile: Option<Profile>,
     },
     /// Start the operator
     Start {
         /// Configuration file path
         #[arg(short, long, default_value = "config.yaml")]
         config: PathBuf,
+        /// Run the full pipeline but log transactions instead of broadcasting them
+        #[arg(long)]
+        dry_run: bool,
+        /// Preset applied underneath the config file and environment overrides
+        #[arg(long, value_enum)]
+        profile: Option<Profile>,
+        /// Override a config value, e.g. `--set matching.matching_interval_ms=250`
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        overrides: Vec<String>,
+    },
+    /// Inspect the effective configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
     },
     /// Generate operator keys
     Keygen {
@@ -50,50 +76,505 @@ enum Commands {
         /// Configuration file path
         #[arg(short, long, default_value = "config.yaml")]
         config: PathBuf,
+        /// Register only this identity instead of all configured ones
+        #[arg(long)]
+        identity: Option<String>,
+    },
+    /// Deregister from the AVS, wait out the withdrawal delay and archive local state
+    Deregister {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Deregister only this identity; local state is kept while others remain registered
+        #[arg(long)]
+        identity: Option<String>,
+        /// Deregister without asking
+        #[arg(long)]
+        yes: bool,
+        /// Log the deregistration instead of broadcasting it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Fund, stake and register a new operator on Holesky in one step
+    BootstrapTestnet {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Faucet endpoint accepting `{"address": ...}`; without it, fund the operator address by hand
+        #[arg(long)]
+        faucet_url: Option<String>,
+        /// EigenLayer test strategy to deposit the stake into
+        #[arg(long)]
+        strategy: String,
+        /// Stake to deposit, in ETH
+        #[arg(long, default_value = "1", value_parser = bootstrap::parse_ether)]
+        stake: u128,
+        /// How long to wait for the operator address to be funded
+        #[arg(long, default_value_t = 600)]
+        funding_timeout_secs: u64,
+        /// Log the deposit and registration instead of broadcasting them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Publish the operator metadata from the config through the AVS metadata URI
+    PublishMetadata {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Write the metadata JSON here, to be hosted at `metadata.uri`
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Update only this identity instead of all configured ones
+        #[arg(long)]
+        identity: Option<String>,
+        /// Publish even if the document at `metadata.uri` doesn't match the config
+        #[arg(long)]
+        skip_check: bool,
+        /// Log the transaction instead of broadcasting it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Point traders at the committee order key at `order_key.uri` through the AVS
+    PublishOrderKey {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Publish even if the document at `order_key.uri` doesn't match `order_key.path`
+        #[arg(long)]
+        skip_check: bool,
+        /// Log the transaction instead of broadcasting it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Deposit stake from the `stake_top_up` funding account if the operator is below its target
+    TopUpStake {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Deposit without asking
+        #[arg(long)]
+        yes: bool,
+        /// Log the deposit instead of broadcasting it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print the status of a running operator
+    Status {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+    },
+    /// Manage peer connectivity of a running operator
+    Peers {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        #[command(subcommand)]
+        action: PeerAction,
+    },
+    /// Join or leave pools on a running operator
+    Pools {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        #[command(subcommand)]
+        action: PoolAction,
+    },
+    /// Print aggregated order book levels for a pool from a running operator
+    Orderbook {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Pool key, e.g. ETH_USDC_3000
+        #[arg(short, long)]
+        pool: String,
+        /// Number of price levels per side
+        #[arg(short, long, default_value_t = 10)]
+        depth: usize,
+    },
+    /// Encrypt an order for the operator committee and store it in the OrderVault
+    SubmitOrder {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// URL of the committee key document; defaults to `ethereum.committee_key_url`, then to
+        /// the order key published on chain
+        #[arg(long)]
+        key_url: Option<String>,
+        /// Trader address the order is placed for; defaults to the operator address, whose orders are signed
+        #[arg(long)]
+        trader: Option<String>,
+        /// Pool key, e.g. ETH_USDC_3000
+        #[arg(short, long)]
+        pool: String,
+        #[arg(long, value_enum)]
+        side: client::Side,
+        #[arg(long)]
+        amount: matching::Decimal,
+        #[arg(long)]
+        price: matching::Decimal,
+        /// Seconds until the order expires
+        #[arg(long, default_value_t = 3600)]
+        ttl_secs: u64,
+        /// Replay nonce, unique per trader; defaults to the current time in milliseconds
+        #[arg(long)]
+        nonce: Option<u64>,
+        /// How long the order stays in the book; good til the deadline by default
+        #[arg(long, value_enum)]
+        time_in_force: Option<client::Tif>,
+        /// Print the encrypted order instead of storing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Cancel a pending order placed as the operator address, signed with its key
+    CancelOrder {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Public listener of the operator holding the order, e.g. http://127.0.0.1:8081
+        #[arg(long)]
+        operator_url: String,
+        /// Commitment of the order, as printed by `submit-order`
+        #[arg(long)]
+        commitment: String,
+    },
+    /// Live terminal dashboard for a running operator
+    Top {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Refresh interval in milliseconds
+        #[arg(long, default_value_t = 1000)]
+        refresh_ms: u64,
+    },
+    /// Verify the transaction audit log and export it as JSON
+    AuditExport {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Output file for the exported entries
+        #[arg(short, long, default_value = "audit-export.json")]
+        output: PathBuf,
+    },
+    /// Report task rewards, matching fees and gas spent from the earnings ledger
+    Earnings {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// How far back to report, e.g. 12h, 30d or 2w
+        #[arg(long, default_value = "30d", value_parser = accounting::parse_period)]
+        period: u64,
+    },
+    /// Check the config, RPC, contracts and files and print a pass/warn/fail report
+    ValidateConfig {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Preset the config file is layered over
+        #[arg(long, value_enum)]
+        profile: Option<Profile>,
+        /// Override a config value, e.g. `--set matching.matching_interval_ms=250`
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        overrides: Vec<String>,
+    },
+    /// Measure matching throughput, proof latency per batch size and serialization costs
+    Bench {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Synthetic orders to match
+        #[arg(long, default_value_t = 10_000)]
+        orders: usize,
+        /// Matches per proof, comma-separated
+        #[arg(long, value_delimiter = ',', default_value = "1,10,100")]
+        batch_sizes: Vec<usize>,
+        /// Proofs generated per batch size
+        #[arg(long, default_value_t = 5)]
+        iterations: usize,
+    },
+    /// Run the operator against a local anvil chain with stub contracts and seeded orders
+    Devnet {
+        /// Attach to a running anvil-compatible node instead of starting one
+        #[arg(long)]
+        rpc_url: Option<String>,
+        /// Port for the spawned anvil
+        #[arg(long, default_value_t = 8545)]
+        port: u16,
+        /// Directory for the generated config, state and audit log
+        #[arg(long, default_value = "devnet")]
+        dir: PathBuf,
+        /// Number of crossing buy/sell order pairs to seed
+        #[arg(long, default_value_t = 10)]
+        order_pairs: usize,
+    },
+    /// Inject encrypted synthetic orders at a fixed rate for capacity planning and soak tests
+    Loadgen {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Pool key the orders are placed in, e.g. ETH_USDC_3000
+        #[arg(short, long)]
+        pool: String,
+        /// Orders per second or minute, e.g. `500/s` or `30/m`
+        #[arg(short, long, default_value = "100/s")]
+        rate: loadgen::Rate,
+        /// Gossip to a running operator, or drive an in-process operator through a mock hook
+        #[arg(long, value_enum, default_value = "gossip")]
+        target: loadgen::LoadTarget,
+        /// Operator to gossip to as `host:port`; the config's listen port on localhost by default
+        #[arg(long)]
+        peer: Option<String>,
+        /// Mid price the generated orders are spread around
+        #[arg(long, default_value_t = 2000.0)]
+        mid_price: f64,
+        /// Stop after this many seconds instead of at Ctrl-C
+        #[arg(long)]
+        duration_secs: Option<u64>,
+        /// URL of the committee key document to encrypt for when gossiping; defaults to
+        /// `ethereum.committee_key_url`, then to the order key published on chain
+        #[arg(long)]
+        key_url: Option<String>,
+    },
+    /// Replay recorded inputs through several operators with seeded randomness and virtual
+    /// time, and report where their submitted transactions diverge
+    Simulate {
+        /// Configuration of each simulated operator; repeat to simulate several
+        #[arg(short, long, required = true)]
+        config: Vec<PathBuf>,
+        /// Recorded Ethereum events and gossip messages, one JSON record per line
+        #[arg(short, long)]
+        events: PathBuf,
+        /// Seed for every id, nonce, key and peer selection
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Virtual start time, in seconds since the epoch
+        #[arg(long, default_value_t = 1_700_000_000)]
+        start_time: u64,
+        /// Virtual time that passes per replayed input
+        #[arg(long, default_value_t = 1000)]
+        step_ms: u64,
+    },
+    /// Feed recorded events through the pipeline offline, without broadcasting
+    Replay {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Recorded Ethereum events and gossip messages, one JSON record per line
+        #[arg(short, long)]
+        events: PathBuf,
+        /// State directory for the replay; a fresh temporary directory by default
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
     },
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "eigenvault_operator=debug,info".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Print every effective value after defaults, profile, file, environment and `--set` are layered
+    Show {
+        /// Configuration file path
+        #[arg(short, long, default_value = "config.yaml")]
+        config: PathBuf,
+        /// Preset the config file is layered over
+        #[arg(long, value_enum)]
+        profile: Option<Profile>,
+        /// Override a config value, e.g. `--set matching.matching_interval_ms=250`
+        #[arg(long = "set", value_name = "KEY=VALUE")]
+        overrides: Vec<String>,
+        /// Show which layer each value came from
+        #[arg(long)]
+        origin: bool,
+    },
+}
 
+#[derive(Subcommand)]
+enum PeerAction {
+    /// List connected and banned peers
+    List,
+    /// Disconnect a peer and refuse reconnects
+    Ban {
+        peer_id: String,
+    },
+    /// Allow a banned peer to connect again
+    Unban {
+        peer_id: String,
+    },
+    /// Connect to a peer by `host:port`
+    Connect {
+        address: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum PoolAction {
+    /// Show the allowed and denied pools
+    List,
+    /// Start gossiping and matching orders for a pool
+    Join {
+        pool: String,
+    },
+    /// Stop gossiping and matching orders for a pool
+    Leave {
+        pool: String,
+    },
+}
+
+fn main() -> Result<ExitCode> {
     let cli = Cli::parse();
 
+    // The operator config selects the runtime's threads, log format, file output and OTLP export
+    let settings = match &cli.command {
+        Commands::Start { config, profile, overrides, .. } => load_config(config, *profile, overrides).ok(),
+        _ => None,
+    };
+    let runtime = build_runtime(&settings.as_ref().map(|settings| settings.runtime.clone()).unwrap_or_default())?;
+    runtime.block_on(run(cli, settings))
+}
+
+/// Multi-threaded runtime sized by the `runtime` config section. Proofs run on a pool of
+/// their own (`proofs.proving_threads`), so these workers stay free for networking.
+fn build_runtime(runtime: &config::RuntimeConfig) -> Result<tokio::runtime::Runtime> {
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    builder
+        .enable_all()
+        .thread_name("eigenvault-worker")
+        .max_blocking_threads(runtime.max_blocking_threads);
+    if runtime.worker_threads > 0 {
+        builder.worker_threads(runtime.worker_threads);
+    }
+    Ok(builder.build()?)
+}
+
+async fn run(cli: Cli, settings: Option<Config>) -> Result<ExitCode> {
+    // The dashboard owns the terminal and the benchmark times hot paths that log, so both
+    // run without console logging
+    let _telemetry_guard = match &cli.command {
+        Commands::Top { .. } | Commands::Bench { .. } => None,
+        _ => Some(telemetry::init(settings.as_ref())?),
+    };
+
+    let mut exit_code = ExitCode::SUCCESS;
     match cli.command {
-        Commands::Init { config } => {
+        Commands::Init { config, profile } => {
             info!("Initializing operator configuration at {:?}", config);
-            init_config(config).await?;
+            init_config(config, profile).await?;
         }
-        Commands::Start { config } => {
+        Commands::Start { config, dry_run, profile, overrides } => {
             info!("Starting EigenVault operator with config {:?}", config);
-            start_operator(config).await?;
+            exit_code = start_operator(config, dry_run, profile, overrides).await?;
+        }
+        Commands::Config { action: ConfigAction::Show { config, profile, overrides, origin } } => {
+            show_config(config, profile, overrides, origin)?;
         }
         Commands::Keygen { output } => {
             info!("Generating operator keys in {:?}", output);
             generate_keys(output).await?;
         }
-        Commands::Register { config } => {
+        Commands::Register { config, identity } => {
             info!("Registering operator with config {:?}", config);
-            register_operator(config).await?;
+            register_operator(config, identity).await?;
+        }
+        Commands::Deregister { config, identity, yes, dry_run } => {
+            info!("Deregistering operator with config {:?}", config);
+            deregister_operator(config, identity, yes, dry_run).await?;
+        }
+        Commands::BootstrapTestnet { config: config_path, faucet_url, strategy, stake, funding_timeout_secs, dry_run } => {
+            info!("Bootstrapping testnet operator with config {:?}", config_path);
+            let config = Config::load(&config_path)?;
+            let options = bootstrap::BootstrapOptions {
+                faucet_url,
+                strategy,
+                stake_wei: stake,
+                funding_timeout: std::time::Duration::from_secs(funding_timeout_secs),
+                dry_run,
+            };
+            bootstrap::run(&config, &config_path, &options).await?;
+        }
+        Commands::PublishMetadata { config, output, identity, skip_check, dry_run } => {
+            info!("Publishing operator metadata with config {:?}", config);
+            publish_metadata(config, output, identity, skip_check, dry_run).await?;
+        }
+        Commands::TopUpStake { config, yes, dry_run } => {
+            top_up_stake(config, yes, dry_run).await?;
+        }
+        Commands::PublishOrderKey { config, skip_check, dry_run } => {
+            info!("Publishing order key with config {:?}", config);
+            publish_order_key(config, skip_check, dry_run).await?;
+        }
+        Commands::Status { config } => {
+            print_status(config).await?;
+        }
+        Commands::Peers { config, action } => {
+            manage_peers(config, action).await?;
+        }
+        Commands::Pools { config, action } => {
+            manage_pools(config, action).await?;
+        }
+        Commands::Orderbook { config, pool, depth } => {
+            print_order_book(config, pool, depth).await?;
+        }
+        Commands::SubmitOrder { config, key_url, trader, pool, side, amount, price, ttl_secs, nonce, time_in_force, dry_run } => {
+            let config = Config::load(config)?;
+            let order = client::PlainOrder {
+                trader: trader.unwrap_or_else(|| config.ethereum.operator_address.clone()),
+                pool_key: pool,
+                order_type: side.into(),
+                amount,
+                price,
+                deadline: sim::clock::unix_secs() + ttl_secs,
+                trader_nonce: Some(nonce.unwrap_or_else(sim::clock::unix_millis)),
+                time_in_force: time_in_force.map(Into::into),
+            };
+            submit_order(&config, key_url, &order, dry_run).await?;
+        }
+        Commands::CancelOrder { config, operator_url, commitment } => {
+            cancel_order(config, &operator_url, &commitment).await?;
+        }
+        Commands::Top { config, refresh_ms } => {
+            run_dashboard(config, refresh_ms).await?;
+        }
+        Commands::AuditExport { config, output } => {
+            info!("Exporting audit log to {:?}", output);
+            export_audit_log(config, output).await?;
+        }
+        Commands::Earnings { config, period } => {
+            report_earnings(config, period)?;
+        }
+        Commands::ValidateConfig { config, profile, overrides } => {
+            exit_code = validate_config(config, profile, overrides).await;
+        }
+        Commands::Bench { config, orders, batch_sizes, iterations } => {
+            let config = Config::load(config)?;
+            let report = bench::run(&config, &bench::BenchOptions { orders, batch_sizes, iterations }).await?;
+            report.print();
+        }
+        Commands::Devnet { rpc_url, port, dir, order_pairs } => {
+            info!("Starting local devnet in {:?}", dir);
+            exit_code = run_devnet(devnet::DevnetOptions { rpc_url, port, dir, order_pairs }).await?;
+        }
+        Commands::Loadgen { config, pool, rate, target, peer, mid_price, duration_secs, key_url } => {
+            info!("Generating load for pool {} through {:?}", pool, target);
+            run_loadgen(config, pool, rate, target, peer, mid_price, duration_secs, key_url).await?;
+        }
+        Commands::Simulate { config, events, seed, start_time, step_ms } => {
+            info!("Simulating {} operators with seed {}", config.len(), seed);
+            exit_code = simulate(config, events, seed, start_time, step_ms).await?;
+        }
+        Commands::Replay { config, events, data_dir } => {
+            info!("Replaying recorded events from {:?}", events);
+            replay_events(config, events, data_dir).await?;
         }
     }
 
-    Ok(())
+    telemetry::shutdown();
+    Ok(exit_code)
 }
 
-async fn init_config(config_path: PathBuf) -> Result<()> {
-    let default_config = Config::default();
-    let config_str = toml::to_string_pretty(&default_config)?;
+async fn init_config(config_path: PathBuf, profile: Option<Profile>) -> Result<()> {
+    let default_config = profile.map(Config::for_profile).unwrap_or_default();
     
     tokio::fs::create_dir_all(config_path.parent().unwrap_or(&PathBuf::from("."))).await?;
-    tokio::fs::write(&config_path, config_str).await?;
+    default_config.save(&config_path)?;
     
     info!("Configuration initialized at {:?}", config_path);
     info!("Please edit the configuration file and add your private keys and RPC URLs");
@@ -101,31 +582,263 @@ async fn init_config(config_path: PathBuf) -> Result<()> {
     Ok(())
 }
 
-async fn start_operator(config_path: PathBuf) -> Result<()> {
+/// Load the config file with every layer: defaults, `profile`, file, environment and `--set` overrides
+fn load_config(config_path: &std::path::Path, profile: Option<Profile>, overrides: &[String]) -> Result<Config> {
+    Ok(Layered::load(config_path, profile, overrides)?.settings)
+}
+
+fn show_config(config_path: PathBuf, profile: Option<Profile>, overrides: Vec<String>, origin: bool) -> Result<()> {
+    let layered = Layered::load(&config_path, profile, &overrides)?;
+    let values = layered.values()?;
+    let width = values.iter().map(|(path, _)| path.len()).max().unwrap_or(0);
+
+    for (path, value) in &values {
+        let value = value.to_string();
+        match layered.origins.get(path).filter(|_| origin) {
+            Some(origin) => println!("{:width$} = {:40} # {}", path, value, origin, width = width),
+            None => println!("{:width$} = {}", path, value, width = width),
+        }
+    }
+    Ok(())
+}
+
+async fn start_operator(config_path: PathBuf, dry_run: bool, profile: Option<Profile>, overrides: Vec<String>) -> Result<ExitCode> {
     info!("Loading configuration from {:?}", config_path);
-    let config = Config::load(config_path)?;
+    let config = load_config(&config_path, profile, &overrides)?;
+    chaos::configure(&config.chaos);
     
     info!("Starting EigenVault operator...");
     
+    if config.metrics.enabled {
+        let metrics_config = config.metrics.clone();
+        tokio::spawn(async move {
+            if let Err(e) = metrics::serve(metrics_config).await {
+                error!("Metrics exporter stopped: {:?}", e);
+            }
+        });
+    }
+    
+    let audit_log = if config.audit.enabled {
+        Some(Arc::new(AuditLog::open(&config.audit.path)?))
+    } else {
+        None
+    };
+    
+    // A standby waits here, before touching the shared state, until the primary's lease lapses
+    let lease = if config.ha.enabled {
+        let lease = Arc::new(Lease::new(config.ha.clone()));
+        tokio::select! {
+            _ = lease.acquire() => {}
+            signal = wait_for_signal() => {
+                info!("Received {} while standing by", signal);
+                return Ok(ExitCode::SUCCESS);
+            }
+        }
+        Some(lease)
+    } else {
+        None
+    };
+    
+    // Recover persisted state, upgrading it first if a different binary wrote it
+    let fresh = !std::path::Path::new(&config.storage.data_dir).exists();
+    let store = Arc::new(Store::open(&config.storage)?);
+    let context = UpgradeContext { db: store.db(), config: &config };
+    if let Some(upgrade) = storage::upgrades::upgrade(&context, UPGRADE_HOOKS, BINARY_VERSION, fresh)? {
+        if let Some(audit_log) = &audit_log {
+            audit_log.record_or_log(
+                TransactionKind::StateUpgrade,
+                &format!("{} -> {}", upgrade.from, upgrade.to),
+                upgrade.hooks.join("\n").as_bytes(),
+                None,
+                AuditOutcome::Applied,
+            );
+        }
+    }
+    let recovered = store.recover()?;
+    
+    let matching_engine = Arc::new(MatchingEngine::new(config.matching.clone()).await?);
+    matching_engine.update_pool_overrides(config.pools.clone()).await;
+    matching_engine.update_routes(config.routes.clone()).await;
+    matching_engine.update_pool_filter(config.pool_filter.clone()).await;
+    matching_engine.update_screen(matching::screening::from_config(&config.screening)?).await;
+    if let Some(order_key) = load_order_decryption_key(&config)? {
+        matching_engine.update_order_key(order_key).await;
+    }
+    matching_engine.nonces().restore(recovered.claimed_nonces);
+    let (order_journal, pending_orders) = open_order_journal(&config, &store, recovered.pending_orders)?;
+    matching_engine.restore_pending_orders(pending_orders).await;
+    matching_engine.set_journal(order_journal);
+    matching_engine.nonces().set_store(store.clone());
+    
+    let event_publisher = EventPublisher::start(
+        config.event_bus.clone(),
+        config.ethereum.operator_address.clone(),
+    ).await?;
+    
+    let health = Arc::new(HealthRegistry::for_operator(config.api.health_check_interval_seconds));
+    let status = Arc::new(StatusBoard::new());
+    let control = Arc::new(OperatorControl::new());
+    let api_state = ApiState {
+        health: health.clone(),
+        status: status.clone(),
+        control: control.clone(),
+        matching: matching_engine.clone(),
+        events: event_publisher.clone(),
+        store: store.clone(),
+        keys: Arc::new(ApiKeys::from_config(&config.api)),
+        settings: Arc::new(tokio::sync::RwLock::new(config.clone())),
+        order_key: load_order_key(&config)?.map(Arc::new),
+        notifier: config.notifications.enabled.then(|| Arc::new(WebhookNotifier::new(config.notifications.clone()))),
+    };
+    
+    if let Some(notifier) = api_state.notifier.clone() {
+        tokio::spawn(notifier.run(matching_engine.tracker().subscribe()));
+    }
+    
+    if config.api.enabled {
+        let api_config = config.api.clone();
+        let api_state = api_state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = api::serve(api_config, api_state).await {
+                error!("Operator API stopped: {:?}", e);
+            }
+        });
+    }
+    
+    if config.public_stats.enabled {
+        let public_config = config.public_stats.clone();
+        let api_state = api_state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = api::public::serve(public_config, api_state).await {
+                error!("Public stats endpoint stopped: {:?}", e);
+            }
+        });
+    }
+    
+    if config.ipc.enabled {
+        let ipc_config = config.ipc.clone();
+        let api_state = api_state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = ipc::serve(ipc_config, api_state).await {
+                error!("IPC status endpoint stopped: {:?}", e);
+            }
+        });
+    }
+    
+    if config.grpc.enabled {
+        let grpc_config = config.grpc.clone();
+        let api_state = api_state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = grpc::serve(grpc_config, api_state).await {
+                error!("gRPC admin API stopped: {:?}", e);
+            }
+        });
+    }
+    
+    if config.stake_top_up.enabled {
+        let top_up = StakeTopUp::connect(&config, audit_log.clone(), dry_run).await?.with_store(store.clone())?;
+        tokio::spawn(Arc::new(top_up).run());
+    }
+    
     // Initialize components
-    let ethereum_client = EthereumClient::new(config.ethereum.clone()).await?;
-    let matching_engine = MatchingEngine::new(config.matching.clone()).await?;
-    let p2p_network = P2PNetwork::new(config.networking.clone()).await?;
+    let mut identities = OperatorIdentity::connect_all(&config, audit_log).await?;
+    if dry_run {
+        warn!("Dry-run mode: transactions will be logged, not broadcast");
+        for identity in &mut identities {
+            identity.client.set_dry_run(true);
+        }
+    }
+    
+    // The primary identity also drives event ingestion
+    let mut ethereum_client = identities.remove(0).client;
+    if let Some(block) = recovered.last_processed_block {
+        ethereum_client.resume_from_block(block);
+    }
+    matching_engine.update_order_domain(matching::OrderDomain {
+        chain_id: ethereum_client.chain_id().await?,
+        verifying_contract: config.ethereum.order_vault_address.clone(),
+    }).await;
+    matching_engine.update_receipt_key(config.ethereum.private_key.clone()).await;
+    
+    for submission in &recovered.unconfirmed_submissions {
+        warn!(
+            "Submission for task {} ({}) is still unconfirmed",
+            submission.task_id, submission.tx_hash
+        );
+    }
+    
+    let p2p_network = P2PNetwork::new(config.networking.clone(), config.pool_filter.clone()).await?;
     let zk_prover = ZKProver::new(config.proofs.clone()).await?;
 
+    let latency = Arc::new(LatencyTracker::new(config.latency.clone()));
+
     // Create operator instance
-    let operator = Operator::new(
+    let mut operator = Operator::new(
         ethereum_client,
+        identities,
         matching_engine,
-        p2p_network,
         zk_prover,
+        event_publisher,
+        store,
+        health,
+        status,
+        control,
+        latency,
+        Accountant::open(config.accounting.clone())?,
+        CancellationToken::new(),
         config.clone(),
     );
+    if let Some(lease) = lease {
+        operator.set_lease(lease);
+    }
+    operator.schedule_tasks(recovered.scheduled_tasks);
 
-    // Start operator
-    operator.run().await?;
+    // Start operator; the P2P loop owns the network
+    operator.run(p2p_network).await
+}
 
-    Ok(())
+/// Open the order journal beside the state store, with the orders it still holds. Orders kept
+/// in the store itself, as releases before the journal did, are moved into it first.
+fn open_order_journal(config: &Config, store: &Store, stored: Vec<DecryptedOrder>) -> Result<(OrderJournal, Vec<DecryptedOrder>)> {
+    let path = journal::journal_path(std::path::Path::new(&config.storage.data_dir));
+    let (mut order_journal, mut orders) = OrderJournal::open(&path, config.storage.sync_writes)?;
+    if stored.is_empty() {
+        return Ok((order_journal, orders));
+    }
+
+    info!("Moving {} pending orders from the state store into {}", stored.len(), path.display());
+    let entries: Vec<JournalEntry> = stored.iter().map(|order| JournalEntry::Add { order: order.clone() }).collect();
+    order_journal.append(&entries)?;
+    let mut checkpoint = StateCheckpoint::new();
+    for order in &stored {
+        checkpoint.remove_order(order.id.clone());
+    }
+    store.commit(checkpoint)?;
+    orders.extend(stored);
+    Ok((order_journal, orders))
+}
+
+/// Committee order key to decrypt orders with, if one is configured
+fn load_order_decryption_key(config: &Config) -> Result<Option<EncryptionManager>> {
+    if config.order_key.path.is_empty() {
+        warn!("No order_key.path configured; stored and gossiped orders can't be decrypted");
+        return Ok(None);
+    }
+    let keys = EncryptionKeys::load(&config.order_key.path)?;
+    if keys.private_key.is_empty() {
+        return Err(anyhow::anyhow!("Order key file {} has no private key", config.order_key.path));
+    }
+    Ok(Some(EncryptionManager::from_keys(keys)?))
+}
+
+/// Committee order key to serve to traders, if one is configured
+fn load_order_key(config: &Config) -> Result<Option<OrderKeyDocument>> {
+    if config.order_key.path.is_empty() {
+        return Ok(None);
+    }
+    let document = OrderKeyDocument::load(&config.order_key.path, config.order_key.version)?;
+    info!("Serving order key version {} from {}", document.version, config.order_key.path);
+    Ok(Some(document))
 }
 
 async fn generate_keys(output_path: PathBuf) -> Result<()> {
@@ -134,248 +847,721 @@ async fn generate_keys(output_path: PathBuf) -> Result<()> {
     let key_manager = KeyManager::new();
     key_manager.generate_keys(&output_path).await?;
     
+    // A committee shares one order key: generate it once and copy it to every member
+    let order_key = EncryptionManager::new()?.export_keys()?;
+    let order_key_path = output_path.join("order_key.json");
+    tokio::fs::write(&order_key_path, serde_json::to_string_pretty(&order_key)?).await?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(&order_key_path, std::fs::Permissions::from_mode(0o600)).await?;
+    }
+    
     info!("Keys generated successfully in {:?}", output_path);
     info!("Please secure your private keys and update your configuration");
     
     Ok(())
 }
 
-async fn register_operator(config_path: PathBuf) -> Result<()> {
+async fn register_operator(config_path: PathBuf, only: Option<String>) -> Result<()> {
     let config = Config::load(config_path)?;
-    let ethereum_client = EthereumClient::new(config.ethereum.clone()).await?;
+    if let Some(name) = &only {
+        if !config.identity_configs().iter().any(|(identity, _)| identity == name) {
+            return Err(anyhow::anyhow!("Unknown identity '{}'", name));
+        }
+    }
+    
+    let audit_log = if config.audit.enabled {
+        Some(Arc::new(AuditLog::open(&config.audit.path)?))
+    } else {
+        None
+    };
     
-    info!("Registering operator with EigenLayer...");
-    ethereum_client.register_operator().await?;
+    // Each identity is a separate EigenLayer registration signed with its own key
+    for identity in OperatorIdentity::connect_all(&config, audit_log).await? {
+        if only.as_ref().is_some_and(|name| *name != identity.name) {
+            continue;
+        }
+        
+        info!("Registering identity '{}' with EigenLayer...", identity.name);
+        let operator_id = identity.client.register_operator().await?;
+        info!("Identity '{}' registered with operator ID {}", identity.name, operator_id);
+    }
     
     info!("Operator registration completed!");
     
     Ok(())
 }
 
-/// Main operator struct that coordinates all components
-pub struct Operator {
-    ethereum_client: EthereumClient,
-    matching_engine: MatchingEngine,
-    p2p_network: P2PNetwork,
-    zk_prover: ZKProver,
-    config: Config,
-}
-
-impl Operator {
-    pub fn new(
-        ethereum_client: EthereumClient,
-        matching_engine: MatchingEngine,
-        p2p_network: P2PNetwork,
-        zk_prover: ZKProver,
-        config: Config,
-    ) -> Self {
-        Self {
-            ethereum_client,
-            matching_engine,
-            p2p_network,
-            zk_prover,
-            config,
+async fn deregister_operator(config_path: PathBuf, only: Option<String>, yes: bool, dry_run: bool) -> Result<()> {
+    let config = Config::load(config_path)?;
+    if let Some(name) = &only {
+        if !config.identity_configs().iter().any(|(identity, _)| identity == name) {
+            return Err(anyhow::anyhow!("Unknown identity '{}'", name));
         }
     }
-
-    pub async fn run(self) -> Result<()> {
-        info!("EigenVault operator starting...");
-
-        // Start background tasks
-        let ethereum_handle = tokio::spawn(self.run_ethereum_listener());
-        let p2p_handle = tokio::spawn(self.run_p2p_network());
-        let matching_handle = tokio::spawn(self.run_matching_engine());
-        let health_check_handle = tokio::spawn(self.run_health_check());
-
-        // Wait for any task to complete (or fail)
-        tokio::select! {
-            result = ethereum_handle => {
-                error!("Ethereum listener stopped: {:?}", result);
-            }
-            result = p2p_handle => {
-                error!("P2P network stopped: {:?}", result);
-            }
-            result = matching_handle => {
-                error!("Matching engine stopped: {:?}", result);
-            }
-            result = health_check_handle => {
-                error!("Health check stopped: {:?}", result);
-            }
+    
+    // Holding the store fails fast if the operator is still running and keeps it from
+    // starting again mid-exit
+    let store = if only.is_none() && !dry_run && std::path::Path::new(&config.storage.data_dir).exists() {
+        Some(Store::open(&config.storage)?)
+    } else {
+        None
+    };
+    
+    if !yes && !dry_run {
+        print!(
+            "Deregister {} from the AVS? Tasks stop being assigned immediately{} [y/N] ",
+            only.as_deref().map_or("all identities".to_string(), |name| format!("identity '{}'", name)),
+            if store.is_some() { " and local state is archived after the withdrawal delay." } else { "." },
+        );
+        std::io::Write::flush(&mut std::io::stdout())?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            println!("Not deregistered");
+            return Ok(());
         }
-
-        warn!("Operator shutting down...");
-        Ok(())
     }
-
-    async fn run_ethereum_listener(self) -> Result<()> {
-        info!("Starting Ethereum event listener...");
+    
+    let audit_log = if config.audit.enabled {
+        Some(Arc::new(AuditLog::open(&config.audit.path)?))
+    } else {
+        None
+    };
+    
+    let mut exits = Vec::new();
+    for mut identity in OperatorIdentity::connect_all(&config, audit_log).await? {
+        if only.as_ref().is_some_and(|name| *name != identity.name) {
+            continue;
+        }
         
-        loop {
-            match self.ethereum_client.listen_for_events().await {
-                Ok(events) => {
-                    for event in events {
-                        if let Err(e) = self.handle_ethereum_event(event).await {
-                            error!("Failed to handle Ethereum event: {:?}", e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("Error listening for Ethereum events: {:?}", e);
-                    tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
-                }
-            }
+        info!("Deregistering identity '{}' from EigenLayer...", identity.name);
+        identity.client.set_dry_run(dry_run);
+        let tx_hash = identity.client.deregister_operator().await?;
+        info!("Identity '{}' deregistered: {}", identity.name, tx_hash);
+        exits.push((identity, tx_hash));
+    }
+    
+    // Identities stay slashable until the delay has passed, so their state is kept until then
+    if !dry_run {
+        for (identity, tx_hash) in &exits {
+            identity.client.wait_for_withdrawal_delay(tx_hash).await?;
         }
     }
-
-    async fn run_p2p_network(mut self) -> Result<()> {
-        info!("Starting P2P network...");
-        
-        loop {
-            match self.p2p_network.listen_for_messages().await {
-                Ok(message) => {
-                    if let Err(e) = self.handle_p2p_message(message).await {
-                        error!("Failed to handle P2P message: {:?}", e);
-                    }
-                }
-                Err(e) => {
-                    error!("Error in P2P network: {:?}", e);
-                    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-                }
-            }
+    
+    if let Some(store) = store {
+        let archived = store.archive()?;
+        // The order journal goes along, so a later start doesn't bring back its orders
+        let order_journal = journal::journal_path(std::path::Path::new(&config.storage.data_dir));
+        if order_journal.exists() {
+            std::fs::rename(&order_journal, journal::journal_path(&archived))?;
         }
+        println!("Operator state archived to {}", archived.display());
     }
+    
+    info!("Operator deregistration completed!");
+    Ok(())
+}
 
-    async fn run_matching_engine(self) -> Result<()> {
-        info!("Starting matching engine...");
-        
-        loop {
-            match self.matching_engine.process_pending_orders().await {
-                Ok(matches) => {
-                    for order_match in matches {
-                        if let Err(e) = self.handle_order_match(order_match).await {
-                            error!("Failed to handle order match: {:?}", e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("Error in matching engine: {:?}", e);
-                }
+async fn run_devnet(options: devnet::DevnetOptions) -> Result<ExitCode> {
+    let devnet = devnet::Devnet::start(options).await?;
+    
+    // The spawned anvil is stopped when `devnet` is dropped after the operator exits
+    let exit_code = start_operator(devnet.config_path.clone(), false, None, Vec::new()).await;
+    drop(devnet);
+    exit_code
+}
+
+async fn top_up_stake(config_path: PathBuf, yes: bool, dry_run: bool) -> Result<()> {
+    let config = Config::load(config_path)?;
+    if !config.stake_top_up.enabled {
+        return Err(anyhow::anyhow!("stake_top_up is not enabled in the config"));
+    }
+    
+    let audit_log = if config.audit.enabled {
+        Some(Arc::new(AuditLog::open(&config.audit.path)?))
+    } else {
+        None
+    };
+    let top_up = StakeTopUp::connect(&config, audit_log, dry_run).await?;
+    // Held by a running operator, the store is locked; this run then only knows its own deposit
+    let top_up = if std::path::Path::new(&config.storage.data_dir).exists() {
+        match Store::open(&config.storage) {
+            Ok(store) => top_up.with_store(Arc::new(store))?,
+            Err(e) => {
+                warn!("Deposits made today are unknown without the state store: {:?}", e);
+                top_up
             }
-            
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        }
+    } else {
+        top_up
+    };
+    
+    let Some(deposit) = top_up.shortfall().await? else {
+        println!("Operator stake is at or above its target; nothing to deposit");
+        return Ok(());
+    };
+    println!(
+        "Operator stake is {} ETH, below its target of {} ETH",
+        bootstrap::format_ether(deposit.stake_wei), bootstrap::format_ether(deposit.target_wei),
+    );
+    
+    if config.stake_top_up.confirm && !yes {
+        print!(
+            "Deposit {} ETH from {} into strategy {}? [y/N] ",
+            bootstrap::format_ether(deposit.amount_wei), top_up.funding_address(), top_up.strategy(),
+        );
+        std::io::Write::flush(&mut std::io::stdout())?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            println!("No deposit made");
+            return Ok(());
         }
     }
+    
+    let tx_hash = top_up.deposit(&deposit).await?;
+    println!("Deposited {} ETH: {}", bootstrap::format_ether(deposit.amount_wei), tx_hash);
+    
+    Ok(())
+}
 
-    async fn run_health_check(self) -> Result<()> {
-        info!("Starting health check...");
-        
-        loop {
-            // Perform health checks
-            let ethereum_healthy = self.ethereum_client.health_check().await.is_ok();
-            let p2p_healthy = self.p2p_network.health_check().await.is_ok();
-            let matching_healthy = self.matching_engine.health_check().await.is_ok();
-            
-            if !ethereum_healthy || !p2p_healthy || !matching_healthy {
-                warn!(
-                    "Health check failed - Ethereum: {}, P2P: {}, Matching: {}",
-                    ethereum_healthy, p2p_healthy, matching_healthy
-                );
-            }
-            
-            tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+async fn publish_metadata(
+    config_path: PathBuf,
+    output: Option<PathBuf>,
+    only: Option<String>,
+    skip_check: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let config = Config::load(config_path)?;
+    if let Some(name) = &only {
+        if !config.identity_configs().iter().any(|(identity, _)| identity == name) {
+            return Err(anyhow::anyhow!("Unknown identity '{}'", name));
         }
     }
-
-    async fn handle_ethereum_event(&self, event: ethereum::EthereumEvent) -> Result<()> {
-        use ethereum::EthereumEvent;
-        
-        match event {
-            EthereumEvent::TaskCreated { task_id, orders_hash, deadline } => {
-                info!("New task created: {} with deadline {}", task_id, deadline);
-                // Process the task
-                self.process_matching_task(task_id, orders_hash, deadline).await?;
-            }
-            EthereumEvent::OrderStored { order_id, trader, encrypted_order } => {
-                info!("New order stored: {} from trader {}", order_id, trader);
-                // Add order to matching engine
-                self.matching_engine.add_encrypted_order(order_id, encrypted_order).await?;
-            }
-            _ => {
-                // Handle other events
-            }
+    
+    let metadata = OperatorMetadata::from_config(&config.metadata)?;
+    if let Some(output) = &output {
+        std::fs::write(output, serde_json::to_string_pretty(&metadata)?)?;
+        info!("Wrote operator metadata to {:?}; host it at {}", output, config.metadata.uri);
+    }
+    
+    // Explorers fetch the URI, so publishing it before the document is in place would show stale data
+    if !skip_check {
+        match metadata.is_published_at(&config.metadata.uri).await {
+            Ok(true) => {}
+            Ok(false) => return Err(anyhow::anyhow!(
+                "The document at {} differs from the config; upload the new metadata first or pass --skip-check",
+                config.metadata.uri
+            )),
+            Err(e) => return Err(anyhow::anyhow!(
+                "Could not fetch metadata from {}: {}; upload it first or pass --skip-check",
+                config.metadata.uri, e
+            )),
+        }
+    }
+    
+    let audit_log = if config.audit.enabled {
+        Some(Arc::new(AuditLog::open(&config.audit.path)?))
+    } else {
+        None
+    };
+    
+    for mut identity in OperatorIdentity::connect_all(&config, audit_log).await? {
+        if only.as_ref().is_some_and(|name| *name != identity.name) {
+            continue;
         }
         
-        Ok(())
+        identity.client.set_dry_run(dry_run);
+        let tx_hash = identity.client.update_metadata_uri(&config.metadata.uri).await?;
+        info!("Identity '{}' metadata published: {}", identity.name, tx_hash);
     }
+    
+    Ok(())
+}
 
-    async fn handle_p2p_message(&self, message: networking::P2PMessage) -> Result<()> {
-        use networking::P2PMessage;
-        
-        match message {
-            P2PMessage::OrderGossip { order_id, encrypted_data, signature: _ } => {
-                info!("Received order gossip: {}", order_id);
-                self.matching_engine.add_encrypted_order(order_id, encrypted_data).await?;
-            }
-            P2PMessage::MatchingResult { task_id, result, signature } => {
-                info!("Received matching result for task: {}", task_id);
-                self.handle_matching_result(task_id, result, signature).await?;
-            }
-            _ => {
-                // Handle other message types
-            }
+async fn publish_order_key(config_path: PathBuf, skip_check: bool, dry_run: bool) -> Result<()> {
+    let config = Config::load(config_path)?;
+    if config.order_key.uri.is_empty() {
+        return Err(anyhow::anyhow!("`publish-order-key` requires order_key.uri"));
+    }
+    
+    // Traders encrypt to whatever the pointer leads to, so it must already serve this key
+    if let (Some(expected), false) = (load_order_key(&config)?, skip_check) {
+        let published = client::fetch_order_key(&config.order_key.uri).await
+            .map_err(|e| anyhow::anyhow!("{}; serve the key first or pass --skip-check", e))?;
+        if published != expected {
+            return Err(anyhow::anyhow!(
+                "The key at {} differs from {}; serve the new key first or pass --skip-check",
+                config.order_key.uri, config.order_key.path
+            ));
         }
-        
-        Ok(())
     }
+    
+    // The key is shared by the committee, so one pointer from the primary identity is enough
+    let mut client = EthereumClient::new(config.ethereum.clone()).await?;
+    if config.audit.enabled {
+        client.set_audit_log(Arc::new(AuditLog::open(&config.audit.path)?));
+    }
+    client.set_dry_run(dry_run);
+    let tx_hash = client.update_order_key_uri(&config.order_key.uri, config.order_key.version).await?;
+    info!("Order key version {} published: {}", config.order_key.version, tx_hash);
+    
+    Ok(())
+}
 
-    async fn handle_order_match(&self, order_match: matching::OrderMatch) -> Result<()> {
-        info!("Processing order match: {:?}", order_match);
-        
-        // Generate ZK proof for the match
-        let proof = self.zk_prover.generate_matching_proof(&[order_match], "default_pool").await?;
-        
-        // Submit proof to Ethereum - convert to expected format
-        let task_id = format!("task_{}", uuid::Uuid::new_v4());
-        self.ethereum_client.submit_matching_proof(&task_id, proof.proof_data, &proof.proof_id, vec![]).await?;
-        
-        Ok(())
+async fn print_status(config_path: PathBuf) -> Result<()> {
+    let config = Config::load(config_path)?;
+    let status = ipc::request_status(&config.ipc.endpoint).await?;
+    
+    println!("Status:            {}", status.health.status);
+    for (component, health) in &status.health.components {
+        let state = match health {
+            Some(health) if health.healthy => "healthy".to_string(),
+            Some(health) => format!("unhealthy ({})", health.error.as_deref().unwrap_or("unknown")),
+            None => "not checked yet".to_string(),
+        };
+        println!("  {:<16} {}", component, state);
+    }
+    println!("Block cursor:      {}", status.last_processed_block);
+    println!("Active peers:      {}", status.active_peers);
+    println!("Pending orders:    {}", status.pending_orders);
+    println!("Matching paused:   {}", status.matching_paused);
+    match &status.last_submission {
+        Some(submission) => println!(
+            "Last submission:   task {} tx {} ({})",
+            submission.task_id,
+            submission.tx_hash,
+            if submission.confirmed { "confirmed" } else { "pending" },
+        ),
+        None => println!("Last submission:   none"),
     }
+    
+    Ok(())
+}
 
-    async fn process_matching_task(&self, task_id: String, orders_hash: String, deadline: u64) -> Result<()> {
-        info!("Processing matching task: {}", task_id);
-        
-        // Get orders from vault
-        let orders = self.ethereum_client.retrieve_orders_for_task(&task_id).await?;
+async fn manage_peers(config_path: PathBuf, action: PeerAction) -> Result<()> {
+    let config = Config::load(config_path)?;
+    let token = config.api.control_token()
+        .ok_or_else(|| anyhow::anyhow!("`peers` requires api.admin_token or a control API key"))?;
+    let base_url = format!("{}/admin", api_url(&config));
+    
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?;
+    let request = match &action {
+        PeerAction::List => client.get(format!("{}/peers", base_url)),
+        PeerAction::Ban { peer_id } => client.post(format!("{}/peers/{}/ban", base_url, peer_id)),
+        PeerAction::Unban { peer_id } => client.delete(format!("{}/peers/{}/ban", base_url, peer_id)),
+        PeerAction::Connect { address } => client
+            .post(format!("{}/peers/connect", base_url))
+            .json(&api::peers::ConnectRequest { address: address.clone() }),
+    };
+    
+    let response = request.bearer_auth(token).send().await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow::anyhow!("Admin API returned {}: {}", status, body));
+    }
+    let peer_list: PeerList = response.json().await?;
+    
+    println!("{:<44} {:<22} {:>10} {:>7}", "PEER", "ADDRESS", "REPUTATION", "ACTIVE");
+    for peer in &peer_list.peers {
+        println!(
+            "{:<44} {:<22} {:>10.2} {:>7}",
+            peer.peer_id,
+            format!("{}:{}", peer.address, peer.port),
+            peer.reputation,
+            peer.is_active,
+        );
+    }
+    if !peer_list.banned.is_empty() {
+        println!("\nBanned: {}", peer_list.banned.join(", "));
+    }
+    
+    Ok(())
+}
+
+async fn manage_pools(config_path: PathBuf, action: PoolAction) -> Result<()> {
+    let config = Config::load(config_path)?;
+    let token = config.api.control_token()
+        .ok_or_else(|| anyhow::anyhow!("`pools` requires api.admin_token or a control API key"))?;
+    let base_url = format!("{}/admin/pools", api_url(&config));
+    
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?;
+    let request = match &action {
+        PoolAction::List => client.get(&base_url),
+        PoolAction::Join { pool } => client.post(format!("{}/{}", base_url, pool)),
+        PoolAction::Leave { pool } => client.delete(format!("{}/{}", base_url, pool)),
+    };
+    
+    let response = request.bearer_auth(token).send().await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow::anyhow!("Admin API returned {}: {}", status, body));
+    }
+    let pool_filter: config::PoolFilterConfig = response.json().await?;
+    
+    if pool_filter.allow.is_empty() {
+        println!("Allowed: every pool");
+    } else {
+        println!("Allowed: {}", pool_filter.allow.join(", "));
+    }
+    if !pool_filter.deny.is_empty() {
+        println!("Denied:  {}", pool_filter.deny.join(", "));
+    }
+    
+    Ok(())
+}
+
+async fn submit_order(config: &Config, key_url: Option<String>, order: &client::PlainOrder, dry_run: bool) -> Result<()> {
+    // Without a URL the key is discovered through the service manager's order key pointer
+    let key_url = key_url.or_else(|| config.ethereum.committee_key_url.clone());
+    let mut trader_client = client::TraderClient::connect(&config.ethereum, key_url.as_deref()).await?;
+    // Orders placed as the operator are signed with its key; other traders sign in their own wallet
+    if order.trader.eq_ignore_ascii_case(&config.ethereum.operator_address) {
+        trader_client = trader_client.with_signer(config.ethereum.private_key.clone());
+    }
+
+    if dry_run {
+        let sealed = trader_client.seal(order)?;
+        println!("Order {} (commitment {}), not stored:", sealed.order_id, sealed.commitment);
+        println!("0x{}", hex::encode(&sealed.encrypted_order));
+        return Ok(());
+    }
+
+    let (sealed, tx_hash) = trader_client.submit(order).await?;
+    println!("Order {} stored in {}", sealed.order_id, tx_hash);
+    println!("  {} {:?} {} @ {} until {}", order.pool_key, order.order_type, order.amount, order.price, order.deadline);
+    Ok(())
+}
+
+async fn cancel_order(config_path: PathBuf, operator_url: &str, commitment: &str) -> Result<()> {
+    let config = Config::load(config_path)?;
+    let ethereum_client = EthereumClient::new(config.ethereum.clone()).await?;
+    let domain = matching::OrderDomain {
+        chain_id: ethereum_client.chain_id().await?,
+        verifying_contract: config.ethereum.order_vault_address.clone(),
+    };
+    
+    let cancellation = domain.sign_cancellation(commitment, config.ethereum.private_key.expose())?;
+    client::cancel_order(operator_url, &cancellation).await?;
+    println!("Order {} cancelled at {}", commitment, operator_url);
+    Ok(())
+}
+
+async fn print_order_book(config_path: PathBuf, pool: String, depth: usize) -> Result<()> {
+    let config = Config::load(config_path)?;
+    let token = config.api.control_token()
+        .ok_or_else(|| anyhow::anyhow!("`orderbook` requires api.admin_token or a control API key"))?;
+    let pool_url = format!("{}/api/v1/pools/{}", api_url(&config), pool);
+    
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()?;
+    
+    // The API only serves aggregated levels, so no order, trader or size of a single order is shown
+    let response = client.get(format!("{}/depth", pool_url))
+        .query(&[("levels", depth)])
+        .bearer_auth(&token)
+        .send()
+        .await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        println!("No pending orders for pool {}", pool);
+        return Ok(());
+    }
+    let order_book: matching::OrderBookDepth = response.error_for_status()?.json().await?;
+    let stats: matching::OrderBookStats = client.get(format!("{}/stats", pool_url))
+        .bearer_auth(&token)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    
+    println!("Pool {}", order_book.pool_key);
+    // Decimals don't pad, so each column is formatted before it is aligned
+    let print_level = |level: &matching::DepthLevel, side: &str| {
+        let (price, amount) = (format!("{:.4}", level.price), format!("{:.4}", level.total_amount));
+        println!("{:>14} {:>16} {:>7}  {}", price, amount, level.order_count, side);
+    };
+    println!("{:>14} {:>16} {:>7}", "PRICE", "AMOUNT", "ORDERS");
+    for level in order_book.asks.iter().rev() {
+        print_level(level, "ask");
+    }
+    println!("{:-<40}", "");
+    for level in &order_book.bids {
+        print_level(level, "bid");
+    }
+    
+    let format_price = |price: Option<matching::Decimal>| price.map(|p| format!("{:.4}", p)).unwrap_or_else(|| "-".to_string());
+    println!();
+    println!("Active orders:  {} buy / {} sell", stats.active_buy_orders, stats.active_sell_orders);
+    println!("Best bid/ask:   {} / {}", format_price(stats.best_bid), format_price(stats.best_ask));
+    println!("Spread:         {}", stats.spread.map(|spread| format!("{:.4}", spread)).unwrap_or_else(|| "-".to_string()));
+    
+    Ok(())
+}
+
+/// Base URL of the local operator API; a wildcard listen address is reachable via loopback
+fn api_url(config: &Config) -> String {
+    let address = config.api.listen_address.replace("0.0.0.0", "127.0.0.1");
+    let scheme = if config.api.tls.is_some() { "https" } else { "http" };
+    format!("{}://{}", scheme, address)
+}
+
+async fn run_dashboard(config_path: PathBuf, refresh_ms: u64) -> Result<()> {
+    let config = Config::load(config_path)?;
+    let token = config.api.control_token()
+        .ok_or_else(|| anyhow::anyhow!("`top` requires api.admin_token or a control API key"))?;
+    let url = format!("{}/admin/status", api_url(&config));
+    
+    tui::run(url, token, std::time::Duration::from_millis(refresh_ms)).await
+}
+
+async fn export_audit_log(config_path: PathBuf, output_path: PathBuf) -> Result<()> {
+    let config = Config::load(config_path)?;
+    
+    let exported = AuditLog::export(&config.audit.path, &output_path)?;
+    info!("Verified and exported {} audit entries to {:?}", exported, output_path);
+    
+    Ok(())
+}
+
+fn report_earnings(config_path: PathBuf, period_secs: u64) -> Result<()> {
+    let config = Config::load(config_path)?;
+    
+    let entries = Accountant::read(&config.accounting.path)?;
+    EarningsReport::new(&entries, sim::clock::unix_secs().saturating_sub(period_secs)).print();
+    
+    Ok(())
+}
+
+async fn validate_config(config_path: PathBuf, profile: Option<Profile>, overrides: Vec<String>) -> ExitCode {
+    let report = match load_config(&config_path, profile, &overrides) {
+        Ok(config) => doctor::run(&config).await,
+        Err(e) => {
+            let mut report = doctor::Report::default();
+            report.fail("config", format!("Failed to load {:?}: {}", config_path, e));
+            report
+        }
+    };
+    
+    report.print();
+    if report.worst() == doctor::Level::Fail {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+async fn replay_events(config_path: PathBuf, events_path: PathBuf, data_dir: Option<PathBuf>) -> Result<()> {
+    let mut config = Config::load(config_path)?;
+    let records = replay::read_records(&events_path)?;
+    
+    // Never touch the live state store or publish to the production event bus
+    let data_dir = data_dir.unwrap_or_else(|| {
+        std::env::temp_dir().join(format!("eigenvault-replay-{}", uuid::Uuid::new_v4()))
+    });
+    config.storage.data_dir = data_dir.to_string_lossy().into_owned();
+    config.event_bus.enabled = false;
+    
+    // Submissions land on an in-memory chain instead of the configured node
+    let chain = MockChain::new();
+    let operator = offline_operator(&config, chain.clone()).await?;
+    
+    operator.replay(records).await?;
+    info!(
+        "Replay finished with {} transactions recorded; state written to {:?}",
+        chain.transactions().len(), data_dir
+    );
+    
+    Ok(())
+}
+
+async fn simulate(config_paths: Vec<PathBuf>, events_path: PathBuf, seed: u64, start_time: u64, step_ms: u64) -> Result<ExitCode> {
+    let records = replay::read_records(&events_path)?;
+    let mut transcripts = Vec::new();
+    
+    for config_path in &config_paths {
+        let mut config = Config::load(config_path)?;
+        // Drawn from the real RNG so the directory name doesn't consume the seeded sequence
+        let data_dir = std::env::temp_dir().join(format!("eigenvault-sim-{}", uuid::Uuid::new_v4()));
+        config.storage.data_dir = data_dir.to_string_lossy().into_owned();
+        config.event_bus.enabled = false;
         
-        // Decrypt orders
-        let decrypted_orders = self.decrypt_orders(orders).await?;
+        // Every operator starts from the same seed and instant, so any difference is divergence
+        sim::entropy::seed(seed);
+        sim::clock::virtualize(start_time * 1000, tokio::time::Duration::from_millis(step_ms));
         
-        // Find matches
-        let matches = self.matching_engine.find_matches(decrypted_orders).await?;
+        let chain = MockChain::new();
+        let operator = offline_operator(&config, chain.clone()).await?;
+        operator.replay(records.clone()).await?;
+        info!("Simulated {:?}; state written to {:?}", config_path, data_dir);
         
-        if !matches.is_empty() {
-            // Generate proof for matches
-            let proof = self.zk_prover.generate_batch_proof(&matches).await?;
-            
-            // Submit to contract
-            self.ethereum_client.submit_task_response(&task_id, matches, proof).await?;
-            
-            info!("Submitted {} matches for task {}", matches.len(), task_id);
+        transcripts.push(sim::Transcript {
+            operator: config_path.display().to_string(),
+            transactions: chain.transactions(),
+        });
+    }
+    
+    let mut exit_code = ExitCode::SUCCESS;
+    for transcript in &transcripts {
+        println!("{}: {} transactions, digest {}", transcript.operator, transcript.transactions.len(), transcript.digest());
+    }
+    for transcript in &transcripts[1..] {
+        if let Some(index) = transcripts[0].divergence(transcript) {
+            println!("{} diverges from {} at transaction {}", transcript.operator, transcripts[0].operator, index);
+            exit_code = ExitCode::FAILURE;
         }
-        
-        Ok(())
     }
+    
+    Ok(exit_code)
+}
 
-    async fn decrypt_orders(&self, encrypted_orders: Vec<Vec<u8>>) -> Result<Vec<matching::DecryptedOrder>> {
-        // Implementation would decrypt orders using operator's private key
-        // For now, return mock orders
-        Ok(vec![])
+/// Operator over `chain` with fresh components and no external services; the caller
+/// points `config` at a scratch state directory first
+async fn offline_operator(config: &Config, chain: MockChain) -> Result<Operator<MockChain>> {
+    chaos::configure(&config.chaos);
+    let store = Arc::new(Store::open(&config.storage)?);
+    let matching_engine = Arc::new(MatchingEngine::new(config.matching.clone()).await?);
+    matching_engine.update_pool_overrides(config.pools.clone()).await;
+    matching_engine.update_routes(config.routes.clone()).await;
+    matching_engine.update_pool_filter(config.pool_filter.clone()).await;
+    matching_engine.update_screen(matching::screening::from_config(&config.screening)?).await;
+    if let Some(order_key) = load_order_decryption_key(config)? {
+        matching_engine.update_order_key(order_key).await;
     }
+    matching_engine.update_order_domain(matching::OrderDomain {
+        chain_id: ethereum::ContractApi::get_chain_id(&chain).await?,
+        verifying_contract: config.ethereum.order_vault_address.clone(),
+    }).await;
+    matching_engine.update_receipt_key(config.ethereum.private_key.clone()).await;
+    let event_publisher = EventPublisher::start(
+        config.event_bus.clone(),
+        config.ethereum.operator_address.clone(),
+    ).await?;
+    
+    Ok(Operator::new(
+        chain,
+        Vec::new(),
+        matching_engine,
+        ZKProver::new(config.proofs.clone()).await?,
+        event_publisher,
+        store,
+        Arc::new(HealthRegistry::for_operator(config.api.health_check_interval_seconds)),
+        Arc::new(StatusBoard::new()),
+        Arc::new(OperatorControl::new()),
+        Arc::new(LatencyTracker::new(config.latency.clone())),
+        Accountant::disabled(),
+        CancellationToken::new(),
+        config.clone(),
+    ))
+}
 
-    async fn handle_matching_result(&self, task_id: String, result: Vec<u8>, signature: Vec<u8>) -> Result<()> {
-        // Verify signature and result
-        // Aggregate with other operator results
-        // Submit if threshold reached
-        Ok(())
-    }
-}
\ No newline at end of file
+async fn run_loadgen(
+    config_path: PathBuf,
+    pool: String,
+    rate: loadgen::Rate,
+    target: loadgen::LoadTarget,
+    peer: Option<String>,
+    mid_price: f64,
+    duration_secs: Option<u64>,
+    key_url: Option<String>,
+) -> Result<()> {
+    let mut config = Config::load(config_path)?;
+    let duration = duration_secs.map(tokio::time::Duration::from_secs);
+    
+    let shutdown = CancellationToken::new();
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            let signal = wait_for_signal().await;
+            info!("Received {}, stopping load generation", signal);
+            shutdown.cancel();
+        }
+    });
+    
+    // The generator's own node: an ephemeral port, never joining the configured network
+    let mut networking = config.networking.clone();
+    networking.listen_port = 0;
+    networking.bootstrap_peers = Vec::new();
+    
+    let report = match target {
+        loadgen::LoadTarget::Gossip => {
+            // Encrypted for the committee's published key and signed for the configured vault
+            let contracts = ethereum::EigenVaultContracts::new(
+                &config.ethereum.rpc_url,
+                &config.ethereum.eigenvault_hook_address,
+                &config.ethereum.service_manager_address,
+                &config.ethereum.order_vault_address,
+            ).await?;
+            let keys = match key_url.or_else(|| config.ethereum.committee_key_url.clone()) {
+                Some(key_url) => client::fetch_committee_key(&key_url).await?,
+                None => client::discover_committee_key(&contracts).await?,
+            };
+            let domain = matching::OrderDomain {
+                chain_id: ethereum::ContractApi::get_chain_id(&contracts).await?,
+                verifying_contract: config.ethereum.order_vault_address.clone(),
+            };
+            let generator = loadgen::OrderGenerator::new(&pool, mid_price, keys, domain)?;
+            
+            let peer = peer.unwrap_or_else(|| format!("127.0.0.1:{}", config.networking.listen_port));
+            networking.bootstrap_peers = vec![peer.clone()];
+            let network = P2PNetwork::new(networking, config.pool_filter.clone()).await?;
+            let sink = loadgen::LoadSink::gossip(network)
+                .map_err(|e| anyhow::anyhow!("Failed to connect to operator at {}: {}", peer, e))?;
+            loadgen::run(generator, sink, rate, duration, shutdown).await?
+        }
+        loadgen::LoadTarget::Hook => {
+            if !config.pool_filter.serves(&pool) {
+                warn!("Pool {} is not served by this config; the operator will drop every order", pool);
+            }
+            if config.metrics.enabled {
+                let metrics_config = config.metrics.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = metrics::serve(metrics_config).await {
+                        error!("Metrics exporter stopped: {:?}", e);
+                    }
+                });
+            }
+            
+            let data_dir = std::env::temp_dir().join(format!("eigenvault-loadgen-{}", uuid::Uuid::new_v4()));
+            config.storage.data_dir = data_dir.to_string_lossy().into_owned();
+            config.event_bus.enabled = false;
+            
+            // The in-process operator decrypts with order_key.path, so orders are encrypted for
+            // the part of it it would publish
+            if config.order_key.path.is_empty() {
+                return Err(anyhow::anyhow!("The hook target needs order_key.path for the operator to decrypt with"));
+            }
+            let keys = OrderKeyDocument::load(&config.order_key.path, config.order_key.version)?.into();
+            
+            let chain = MockChain::new();
+            let domain = matching::OrderDomain {
+                chain_id: ethereum::ContractApi::get_chain_id(&chain).await?,
+                verifying_contract: config.ethereum.order_vault_address.clone(),
+            };
+            let generator = loadgen::OrderGenerator::new(&pool, mid_price, keys, domain)?;
+            let operator = offline_operator(&config, chain.clone()).await?;
+            let network = P2PNetwork::new(networking, config.pool_filter.clone()).await?;
+            
+            let report = tokio::select! {
+                report = loadgen::run(generator, loadgen::LoadSink::Hook(chain.clone()), rate, duration, shutdown) => report?,
+                exit = operator.run(network) => {
+                    return Err(anyhow::anyhow!("In-process operator stopped during load generation: {:?}", exit?));
+                }
+            };
+            info!("Operator recorded {} transactions; state in {:?}", chain.transactions().len(), data_dir);
+            report
+        }
+    };
+    
+    info!("Load generation finished: {}", report);
+    Ok(())
+}
@@ -0,0 +1,333 @@
+use anyhow::Result;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::str::FromStr;
+
+const SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// Non-negative fixed-point number with 18 decimal places, for token amounts and prices.
+///
+/// Arithmetic is exact except where a method says it rounds, and rounding is always down.
+/// Human-readable formats carry it as a decimal string such as `"2000.5"`, and accept JSON
+/// numbers for state written before amounts were fixed-point; binary formats carry the raw
+/// integer. Addition and subtraction panic on overflow like the integer operators do.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Decimal(u128);
+
+impl Decimal {
+    /// Decimal places kept
+    pub const DECIMALS: u8 = 18;
+    pub const ZERO: Decimal = Decimal(0);
+    pub const ONE: Decimal = Decimal(SCALE);
+
+    /// The number `raw / 10^18`
+    pub const fn from_raw(raw: u128) -> Self {
+        Self(raw)
+    }
+
+    /// The number times 10^18
+    pub const fn raw(self) -> u128 {
+        self.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    /// Sum, capped at the largest representable number
+    pub fn saturating_add(self, other: Self) -> Self {
+        Self(self.0.saturating_add(other.0))
+    }
+
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    pub fn saturating_sub(self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0))
+    }
+
+    /// Product, rounded down to 18 decimals; `None` on overflow
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        // (ah·S + al)(bh·S + bl) / S without the full product, which needs 256 bits
+        let (ah, al) = (self.0 / SCALE, self.0 % SCALE);
+        let (bh, bl) = (other.0 / SCALE, other.0 % SCALE);
+        ah.checked_mul(bh)?.checked_mul(SCALE)?
+            .checked_add(ah.checked_mul(bl)?)?
+            .checked_add(al.checked_mul(bh)?)?
+            .checked_add(al * bl / SCALE)
+            .map(Self)
+    }
+
+    /// `bps` basis points of the number, rounded down; `None` on overflow
+    pub fn mul_bps(self, bps: u64) -> Option<Self> {
+        let (whole, part) = (self.0 / 10_000, self.0 % 10_000);
+        whole.checked_mul(bps as u128)?
+            .checked_add(part * bps as u128 / 10_000)
+            .map(Self)
+    }
+
+    /// The number times `numerator / denominator`, rounded down; `None` on overflow or a zero denominator
+    pub fn mul_ratio(self, numerator: u64, denominator: u64) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let denominator = denominator as u128;
+        let (whole, part) = (self.0 / denominator, self.0 % denominator);
+        whole.checked_mul(numerator as u128)?
+            .checked_add(part * numerator as u128 / denominator)
+            .map(Self)
+    }
+
+    /// The number split into `parts`, rounded down
+    pub fn div_int(self, parts: u64) -> Self {
+        Self(self.0 / parts.max(1) as u128)
+    }
+
+    /// Halfway between the two numbers, rounded down
+    pub fn midpoint(self, other: Self) -> Self {
+        Self((self.0 & other.0) + ((self.0 ^ other.0) >> 1))
+    }
+
+    /// The number in base units of a token with `decimals` decimals; `None` if it has more
+    /// precision than the token or doesn't fit
+    pub fn to_units(self, decimals: u8) -> Option<u128> {
+        if decimals >= Self::DECIMALS {
+            return self.0.checked_mul(10u128.checked_pow((decimals - Self::DECIMALS) as u32)?);
+        }
+        let unit = 10u128.pow((Self::DECIMALS - decimals) as u32);
+        self.0.is_multiple_of(unit).then_some(self.0 / unit)
+    }
+
+    /// For metrics and display only; anything that is settled stays fixed-point
+    pub fn to_f64(self) -> f64 {
+        (self.0 / SCALE) as f64 + (self.0 % SCALE) as f64 / SCALE as f64
+    }
+}
+
+impl From<u64> for Decimal {
+    fn from(value: u64) -> Self {
+        Self(value as u128 * SCALE)
+    }
+}
+
+/// The float's shortest decimal form, which is what a trader who typed it meant
+impl TryFrom<f64> for Decimal {
+    type Error = anyhow::Error;
+
+    fn try_from(value: f64) -> Result<Self> {
+        if !value.is_finite() {
+            return Err(anyhow::anyhow!("{} is not a number", value));
+        }
+        value.to_string().parse()
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || anyhow::anyhow!("invalid decimal {:?}", s);
+        let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+        if !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        if fraction.len() > Self::DECIMALS as usize {
+            return Err(anyhow::anyhow!("{} has more than {} decimal places", s, Self::DECIMALS));
+        }
+
+        let whole: u128 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| invalid())? };
+        let fraction: u128 = if fraction.is_empty() {
+            0
+        } else {
+            fraction.parse::<u128>().map_err(|_| invalid())? * 10u128.pow((Self::DECIMALS as usize - fraction.len()) as u32)
+        };
+        whole.checked_mul(SCALE)
+            .and_then(|whole| whole.checked_add(fraction))
+            .map(Self)
+            .ok_or_else(|| anyhow::anyhow!("{} is too large", s))
+    }
+}
+
+/// Shortest form, e.g. `2000` or `1.5`; a precision truncates to that many places
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (whole, fraction) = (self.0 / SCALE, self.0 % SCALE);
+        let mut fraction = format!("{:018}", fraction);
+        match f.precision() {
+            Some(precision) => fraction.truncate(precision.min(Self::DECIMALS as usize)),
+            None => fraction.truncate(fraction.trim_end_matches('0').len()),
+        }
+        if fraction.is_empty() {
+            write!(f, "{}", whole)
+        } else {
+            write!(f, "{}.{}", whole, fraction)
+        }
+    }
+}
+
+impl Add for Decimal {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(other).expect("decimal addition overflowed")
+    }
+}
+
+impl AddAssign for Decimal {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Decimal {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(other).expect("decimal subtraction underflowed")
+    }
+}
+
+impl SubAssign for Decimal {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl Sum for Decimal {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl Serialize for Decimal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_u128(self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Decimal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(DecimalVisitor)
+        } else {
+            u128::deserialize(deserializer).map(Self)
+        }
+    }
+}
+
+struct DecimalVisitor;
+
+impl<'de> Visitor<'de> for DecimalVisitor {
+    type Value = Decimal;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a non-negative decimal number or string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Decimal, E> {
+        value.parse().map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Decimal, E> {
+        Ok(Decimal::from(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Decimal, E> {
+        u64::try_from(value).map(Decimal::from).map_err(|_| E::custom(format!("{} is negative", value)))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Decimal, E> {
+        Decimal::try_from(value).map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(dec("2000").to_string(), "2000");
+        assert_eq!(dec("2000.50").to_string(), "2000.5");
+        assert_eq!(dec(".25").to_string(), "0.25");
+        assert_eq!(dec("0.000000000000000001").raw(), 1);
+        assert_eq!(format!("{:.2}", dec("1.239")), "1.23");
+        assert!("0.0000000000000000001".parse::<Decimal>().is_err());
+        assert!("-1".parse::<Decimal>().is_err());
+        assert!("1e3".parse::<Decimal>().is_err());
+        assert!(".".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn test_floats_convert_to_what_was_typed() -> Result<()> {
+        // Summing 0.1 ten times as f64 gives 0.9999999999999999
+        let tenth = Decimal::try_from(0.1)?;
+        assert_eq!((0..10).map(|_| tenth).sum::<Decimal>(), Decimal::ONE);
+        assert_eq!(Decimal::try_from(2000.0)?.to_string(), 2000.0f64.to_string());
+        assert_eq!(Decimal::try_from(1.5)?.to_string(), 1.5f64.to_string());
+        assert!(Decimal::try_from(-1.0).is_err());
+        assert!(Decimal::try_from(f64::NAN).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(dec("1.5").checked_mul(dec("2000.5")), Some(dec("3000.75")));
+        assert_eq!(dec("0.000000000000000001").checked_mul(dec("0.5")), Some(Decimal::ZERO));
+        // Products whose raw form would overflow before scaling down
+        assert_eq!(dec("1000000000").checked_mul(dec("1000000000")), Some(dec("1000000000000000000")));
+        assert_eq!(dec("1000000000000").checked_mul(dec("1000000000000")), None);
+        assert_eq!(dec("2000").mul_bps(30), Some(dec("6")));
+        assert_eq!(dec("2000").mul_bps(10_005), Some(dec("2001")));
+        assert_eq!(dec("2000").mul_ratio(3, 2), Some(dec("3000")));
+        assert_eq!(dec("1").mul_ratio(1, 3), Some(dec("0.333333333333333333")));
+        assert_eq!(dec("1").mul_ratio(1, 0), None);
+        assert_eq!(dec("2000").midpoint(dec("2001")), dec("2000.5"));
+        assert_eq!(Decimal::from_raw(u128::MAX).midpoint(Decimal::from_raw(u128::MAX)), Decimal::from_raw(u128::MAX));
+        assert_eq!(dec("7").div_int(2), dec("3.5"));
+        assert_eq!(dec("1").saturating_sub(dec("2")), Decimal::ZERO);
+        assert_eq!(Decimal::from_raw(u128::MAX).saturating_add(Decimal::ONE), Decimal::from_raw(u128::MAX));
+    }
+
+    #[test]
+    fn test_token_units() {
+        assert_eq!(dec("1.5").to_units(6), Some(1_500_000));
+        assert_eq!(dec("1.5").to_units(18), Some(1_500_000_000_000_000_000));
+        assert_eq!(dec("1.5").to_units(0), None);
+        assert_eq!(dec("0.0000001").to_units(6), None);
+        assert_eq!(dec("2").to_units(24), Some(2_000_000_000_000_000_000_000_000));
+    }
+
+    #[test]
+    fn test_serde() -> Result<()> {
+        assert_eq!(serde_json::to_string(&dec("2000.5"))?, "\"2000.5\"");
+        assert_eq!(serde_json::from_str::<Decimal>("\"2000.5\"")?, dec("2000.5"));
+        // State written while amounts were floats
+        assert_eq!(serde_json::from_str::<Decimal>("2000.5")?, dec("2000.5"));
+        assert_eq!(serde_json::from_str::<Decimal>("3")?, dec("3"));
+        assert!(serde_json::from_str::<Decimal>("-3").is_err());
+
+        let binary = bincode::serialize(&dec("1.5"))?;
+        assert_eq!(bincode::deserialize::<Decimal>(&binary)?, dec("1.5"));
+        Ok(())
+    }
+}
@@ -1,158 +1,406 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tracing::{info, debug, warn};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, debug, warn, error, instrument};
 use tokio::sync::RwLock;
-use uuid::Uuid;
 
-use super::{Order, OrderBook, OrderType, OrderStatus, DecryptedOrder};
-use crate::config::MatchingConfig;
+use super::{routing, Decimal, Order, OrderBook, OrderBookDepth, OrderBookStats, DecryptedOrder, TimeInForce};
+use super::signing::{OrderCancellation, OrderDomain, OrderMessage, OrderReceipt};
+use super::journal::{JournalEntry, OrderJournal};
+use super::nonces::NonceRegistry;
+use super::screening::{NoScreen, OrderScreen};
+use super::tracker::{OrderLifecycle, OrderTracker};
+use crate::cache::BoundedCache;
+use crate::config::{MatchingAlgorithm, MatchingConfig, PoolFilterConfig, PoolOverrides, RouteConfig, Secret};
+use crate::metrics::metrics;
+use crate::sim::{clock, entropy};
+#[cfg(feature = "chain")]
+use super::EncryptionManager;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderMatch {
     pub match_id: String,
-    pub buy_order: Order,
-    pub sell_order: Order,
-    pub matched_price: f64,
-    pub matched_amount: f64,
+    /// Shared with the order book and every other match the order fills in
+    pub buy_order: Arc<Order>,
+    pub sell_order: Arc<Order>,
+    pub matched_price: Decimal,
+    pub matched_amount: Decimal,
     pub timestamp: u64,
     pub pool_key: String,
+    /// Matching fee returned to the traders at settlement
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rebates: Vec<TraderRebate>,
+}
+
+/// Part of a match's fee returned to one of its traders, in the pool's quote token
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraderRebate {
+    pub trader: String,
+    pub amount: Decimal,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatchingResult {
     pub matches: Vec<OrderMatch>,
     pub unmatched_orders: Vec<Order>,
-    pub total_volume: f64,
-    pub average_price: f64,
+    pub total_volume: Decimal,
+    pub average_price: Decimal,
 }
 
+/// Matches decrypted orders in per-pool batches by price-time priority.
+///
+/// Orders queue through `add_encrypted_order` and are matched on `process_pending_orders`
+/// rounds; `find_matches` matches a task's orders directly. Runtime settings such as the pool
+/// filter and per-pool overrides can be swapped while running.
+///
+/// Each pool's queue keeps its order book between rounds and has its own lock, so orders for
+/// one pool are accepted while another pool is being matched. With cross-pool matching on, what
+/// the fee tiers of a pair leave over is then matched across them, their locks taken together.
 pub struct MatchingEngine {
-    config: MatchingConfig,
-    order_books: RwLock<HashMap<String, OrderBook>>,
-    pending_orders: RwLock<Vec<DecryptedOrder>>,
-    recent_matches: RwLock<Vec<OrderMatch>>,
+    config: RwLock<MatchingConfig>,
+    pool_overrides: RwLock<BTreeMap<String, PoolOverrides>>,
+    /// Cross-pool route settings by pair
+    routes: RwLock<BTreeMap<String, RouteConfig>>,
+    pool_filter: RwLock<PoolFilterConfig>,
+    /// When each pool was last matched, for pools on their own interval
+    last_matched: Mutex<HashMap<String, Duration>>,
+    /// Last batch window closed for each pool, when batch closes are jittered
+    closed_windows: Mutex<HashMap<String, u64>>,
+    /// Pending orders by pool; the map is only written to when a pool is first seen
+    pools: RwLock<BTreeMap<String, Arc<Mutex<PoolQueue>>>>,
+    /// Orders pending across all pools
+    pending_total: AtomicUsize,
+    /// Write-ahead log every change to the queues is appended to, once one is set
+    journal: Mutex<Option<OrderJournal>>,
+    /// Matched orders whose fills are journaled once their matches settle
+    unsettled: Mutex<HashMap<String, Unsettled>>,
+    /// Latest matches by ID, bounded by `recent_matches_limit` and `recent_matches_ttl_seconds`
+    recent_matches: RwLock<BoundedCache<String, OrderMatch>>,
+    /// Domain trader signatures are checked against; signed orders are rejected until it is set
+    order_domain: RwLock<Option<OrderDomain>>,
+    tracker: OrderTracker,
+    nonces: NonceRegistry,
+    /// Compliance screening plugin every decrypted order passes through
+    screen: RwLock<Arc<dyn OrderScreen>>,
+    /// Operator key accepted orders are receipted with
+    receipt_key: RwLock<Option<Secret>>,
+    /// Committee order key encrypted orders are decrypted with
+    #[cfg(feature = "chain")]
+    order_key: RwLock<Option<Arc<EncryptionManager>>>,
 }
 
 impl MatchingEngine {
+    /// Engine with an empty queue that serves every pool until a filter is set
     pub async fn new(config: MatchingConfig) -> Result<Self> {
         info!("Initializing matching engine with config: {:?}", config);
+        let recent_matches = BoundedCache::new(
+            "recent_matches",
+            config.recent_matches_limit,
+            Duration::from_secs(config.recent_matches_ttl_seconds),
+        );
         
         Ok(Self {
-            config,
-            order_books: RwLock::new(HashMap::new()),
-            pending_orders: RwLock::new(Vec::new()),
-            recent_matches: RwLock::new(Vec::new()),
+            config: RwLock::new(config),
+            pool_overrides: RwLock::new(BTreeMap::new()),
+            routes: RwLock::new(BTreeMap::new()),
+            pool_filter: RwLock::new(PoolFilterConfig::default()),
+            last_matched: Mutex::new(HashMap::new()),
+            closed_windows: Mutex::new(HashMap::new()),
+            pools: RwLock::new(BTreeMap::new()),
+            pending_total: AtomicUsize::new(0),
+            journal: Mutex::new(None),
+            unsettled: Mutex::new(HashMap::new()),
+            recent_matches: RwLock::new(recent_matches),
+            order_domain: RwLock::new(None),
+            tracker: OrderTracker::new(),
+            nonces: NonceRegistry::new(),
+            screen: RwLock::new(Arc::new(NoScreen)),
+            receipt_key: RwLock::new(None),
+            #[cfg(feature = "chain")]
+            order_key: RwLock::new(None),
         })
     }
 
-    /// Add encrypted order to pending queue
-    pub async fn add_encrypted_order(&self, order_id: String, encrypted_data: Vec<u8>) -> Result<()> {
+    /// Decrypt an order with the committee order key and add it to its pool's queue; `None` if it
+    /// doesn't decrypt, its pool isn't served or it is refused
+    #[cfg(feature = "chain")]
+    pub async fn add_encrypted_order(&self, order_id: String, encrypted_data: Vec<u8>) -> Result<Option<DecryptedOrder>> {
         info!("Adding encrypted order {} to pending queue", order_id);
         
-        // For now, we'll create a mock decrypted order
-        // In production, this would decrypt using operator private key
-        let decrypted_order = DecryptedOrder {
-            id: order_id.clone(),
-            trader: format!("trader_{}", order_id.chars().take(8).collect::<String>()),
-            pool_key: "ETH_USDC_3000".to_string(),
-            order_type: if order_id.len() % 2 == 0 { OrderType::Buy } else { OrderType::Buy },
-            amount: 1000.0 + (order_id.len() as f64 * 100.0),
-            price: 2000.0 + (order_id.len() as f64 * 10.0),
-            deadline: chrono::Utc::now().timestamp() as u64 + 3600, // 1 hour from now
-            encrypted_data,
+        let order_key = self.order_key.read().await.clone()
+            .ok_or_else(|| anyhow::anyhow!("No order key to decrypt order {} with", order_id))?;
+        match order_key.decrypt_order(&encrypted_data, order_id.clone()) {
+            Ok(decrypted_order) => self.add_order(decrypted_order).await,
+            Err(e) => {
+                warn!("Dropping order {} that doesn't decrypt: {}", order_id, e);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Decrypt the orders of task `task_id` with the committee order key, as `<task_id>/<index>`,
+    /// and authenticate them as queued orders are; those that don't decrypt or are refused are dropped
+    #[cfg(feature = "chain")]
+    pub async fn decrypt_task_orders(&self, task_id: &str, encrypted_orders: Vec<Vec<u8>>) -> Result<Vec<DecryptedOrder>> {
+        let order_key = self.order_key.read().await.clone()
+            .ok_or_else(|| anyhow::anyhow!("No order key to decrypt the orders of task {} with", task_id))?;
+        let encrypted_orders = encrypted_orders.into_iter().enumerate()
+            .map(|(index, encrypted_data)| (format!("{}/{}", task_id, index), encrypted_data))
+            .collect();
+
+        let mut authenticated = Vec::new();
+        for order in order_key.decrypt_orders_batch(encrypted_orders)? {
+            match self.authenticate(&order).await {
+                Ok(()) => authenticated.push(order),
+                Err(e) => warn!("Rejecting order {}: {}", order.id, e),
+            }
+        }
+        Ok(authenticated)
+    }
+
+    /// Add a decrypted order to its pool's queue; `None` if its pool isn't served or it is refused
+    pub async fn add_order(&self, decrypted_order: DecryptedOrder) -> Result<Option<DecryptedOrder>> {
+        let order_id = decrypted_order.id.clone();
+        if !self.serves(&decrypted_order.pool_key).await {
+            debug!("Ignoring order {} for unserved pool {}", order_id, decrypted_order.pool_key);
+            return Ok(None);
+        }
+        if let Err(e) = self.authenticate(&decrypted_order).await {
+            warn!("Rejecting order {}: {}", order_id, e);
+            return Ok(None);
+        }
+        let decrypted_order = DecryptedOrder { received_at: clock::unix_millis(), ..decrypted_order };
+
+        // Hard limit behind the backpressure high-water mark
+        let max_pending_orders = self.config.read().await.max_pending_orders;
+        let reserved = self.pending_total.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |pending| {
+            (pending < max_pending_orders).then_some(pending + 1)
+        });
+        if let Err(pending) = reserved {
+            return Err(anyhow::anyhow!(
+                "Pending order queue is full ({} orders), dropping {}",
+                pending, order_id
+            ));
+        }
+        let pool = self.pool(&decrypted_order.pool_key).await;
+        let queued = {
+            let mut queue = pool.lock().unwrap();
+            // An order seen again, as when its event is read again after a crash, is already queued
+            if queue.orders.iter().any(|order| order.id == order_id) {
+                None
+            } else {
+                // Journaled under the pool lock, so no matching round can fill it before it is recorded
+                let queued = queue.push(decrypted_order.clone())
+                    .and_then(|()| self.journal(&[JournalEntry::Add { order: decrypted_order.clone() }]));
+                if queued.is_err() {
+                    queue.take(|order| order.id == order_id);
+                }
+                Some(queued)
+            }
         };
+        match queued {
+            None => {
+                self.release_pending(1);
+                debug!("Order {} is already pending", order_id);
+                return Ok(None);
+            }
+            Some(Err(e)) => {
+                self.release_pending(1);
+                return Err(e);
+            }
+            Some(Ok(())) => {}
+        }
+        let pending = self.pending_total.load(Ordering::SeqCst);
+        metrics().orders_pending.set(pending as i64);
+        self.tracker.pending(&decrypted_order.id, decrypted_order.deadline);
+        if let Some(receipt) = self.issue_receipt(&decrypted_order.id).await {
+            self.tracker.set_receipt(receipt);
+        }
+        
+        debug!("Added order {} to pending queue. Total pending: {}", order_id, pending);
+        Ok(Some(decrypted_order))
+    }
 
-        let mut pending = self.pending_orders.write().await;
-        pending.push(decrypted_order);
+    /// Drop a pending order on its trader's signed request; `None` if no such order is pending
+    pub async fn cancel_order(&self, cancellation: &OrderCancellation) -> Result<Option<DecryptedOrder>> {
+        match self.order_domain.read().await.as_ref() {
+            Some(domain) => domain.verify_cancellation(cancellation)?,
+            None => return Err(anyhow::anyhow!("no order domain to verify the cancellation against")),
+        }
+
+        // An order not seen yet stays unmarked: there is nothing to check the signer against
+        self.withdraw(&cancellation.commitment, Some(&cancellation.trader)).await
+    }
+
+    /// Drop an order its trader cancelled in the vault, which only lets the trader do so.
+    /// An order still on its way is marked cancelled, so it is refused once it arrives.
+    pub async fn cancel_vault_order(&self, order_id: &str) -> Result<Option<DecryptedOrder>> {
+        let order = self.withdraw(order_id, None).await?;
+        if order.is_none() && self.tracker.cancel(order_id) {
+            info!("Order {} cancelled in the vault before it reached this operator", order_id);
+        }
+        Ok(order)
+    }
+
+    /// Take the pending order with `commitment` out of its pool, checking it belongs to `trader`
+    /// if given. The tracker is marked under the pool lock, so no matching round can claim the
+    /// order in between.
+    async fn withdraw(&self, commitment: &str, trader: Option<&str>) -> Result<Option<DecryptedOrder>> {
+        let commitment = commitment.trim_start_matches("0x");
+        for (_, pool) in self.pool_queues().await {
+            let mut pool = pool.lock().unwrap();
+            let Some(order) = pool.orders.iter().find(|order| order.id.trim_start_matches("0x").eq_ignore_ascii_case(commitment)) else {
+                continue;
+            };
+            if trader.is_some_and(|trader| !order.trader.eq_ignore_ascii_case(trader)) {
+                return Err(anyhow::anyhow!("order {} belongs to another trader", order.id));
+            }
+            if !self.tracker.cancel(&order.id) {
+                return Err(anyhow::anyhow!("order {} is already matched", order.id));
+            }
+
+            let order_id = order.id.clone();
+            let order = pool.take(|order| order.id == order_id).remove(0);
+            self.journal_or_log(&[JournalEntry::Remove { order_id }]);
+            drop(pool);
+            self.release_pending(1);
+            info!("Order {} cancelled by its trader", order.id);
+            return Ok(Some(order));
+        }
+        Ok(None)
+    }
+
+    /// Restore pending orders recovered from persistent storage
+    pub async fn restore_pending_orders(&self, orders: Vec<DecryptedOrder>) {
+        let pool_filter = self.pool_filter.read().await.clone();
+        let mut known = HashSet::new();
+        for (_, pool) in self.pool_queues().await {
+            known.extend(pool.lock().unwrap().orders.iter().map(|order| order.id.clone()));
+        }
+
+        for order in orders {
+            if !pool_filter.serves(&order.pool_key) {
+                info!("Dropping recovered order {} for unserved pool {}", order.id, order.pool_key);
+                continue;
+            }
+            if !known.insert(order.id.clone()) {
+                continue;
+            }
+            if let Some(nonce) = order.trader_nonce {
+                if let Err(e) = self.nonces.claim(&order.trader, nonce, &order.id) {
+                    warn!("Dropping recovered order {}: {}", order.id, e);
+                    continue;
+                }
+            }
+
+            let (order_id, deadline) = (order.id.clone(), order.deadline);
+            let pool = self.pool(&order.pool_key).await;
+            let queued = pool.lock().unwrap().push(order);
+            if let Err(e) = queued {
+                warn!("Dropping recovered order {}: {}", order_id, e);
+                self.journal_or_log(&[JournalEntry::Remove { order_id }]);
+                continue;
+            }
+            self.pending_total.fetch_add(1, Ordering::SeqCst);
+            self.tracker.pending(&order_id, deadline);
+        }
         
-        debug!("Added order {} to pending queue. Total pending: {}", order_id, pending.len());
-        Ok(())
+        let pending = self.pending_total.load(Ordering::SeqCst);
+        metrics().orders_pending.set(pending as i64);
+        info!("Restored pending queue with {} orders", pending);
     }
 
     /// Process pending orders and find matches
+    #[instrument(name = "order.match", skip(self))]
     pub async fn process_pending_orders(&self) -> Result<Vec<OrderMatch>> {
-        let mut pending = self.pending_orders.write().await;
-        if pending.is_empty() {
+        let pending = self.pending_total.load(Ordering::SeqCst);
+        if pending == 0 {
             return Ok(vec![]);
         }
 
-        info!("Processing {} pending orders", pending.len());
+        info!("Processing {} pending orders", pending);
         
+        let config = self.config.read().await.clone();
+        let pool_overrides = self.pool_overrides.read().await.clone();
+        let pool_filter = self.pool_filter.read().await.clone();
         let mut all_matches = Vec::new();
-        let mut processed_indices = Vec::new();
 
-        // Group orders by pool
-        let mut pool_orders: HashMap<String, Vec<(usize, &DecryptedOrder)>> = HashMap::new();
-        for (idx, order) in pending.iter().enumerate() {
-            pool_orders.entry(order.pool_key.clone())
-                      .or_insert_with(Vec::new)
-                      .push((idx, order));
-        }
+        // Immediate orders of each pool that had its round, dropped once the routes had theirs too
+        let mut immediate = Vec::new();
 
-        // Process each pool separately
-        for (pool_key, orders) in pool_orders {
-            if orders.len() < 2 {
-                debug!("Pool {} has only {} orders, skipping matching", pool_key, orders.len());
+        // Pools are locked one at a time, in a stable order so runs are reproducible
+        for (pool_key, queue) in self.pool_queues().await {
+            let mut pool = queue.lock().unwrap();
+
+            let expired: HashSet<String> = pool.book.cleanup_expired_orders().into_iter().collect();
+            if !expired.is_empty() {
+                self.expire(&mut pool, |order| expired.contains(&order.id));
+            }
+
+            // A lone immediate order still gets its round, if only to expire
+            if pool.orders.len() < 2 && !pool.orders.iter().any(|order| order.time_in_force.is_immediate()) {
+                debug!("Pool {} has only {} orders, skipping matching", pool_key, pool.orders.len());
                 continue;
             }
 
-            info!("Processing {} orders for pool {}", orders.len(), pool_key);
-            
-            // Convert to Order structs for matching
-            let mut pool_order_book = OrderBook::new(pool_key.clone());
-            
-            for (idx, decrypted_order) in &orders {
-                let order = Order {
-                    id: decrypted_order.id.clone(),
-                    trader: decrypted_order.trader.clone(),
-                    pool_key: decrypted_order.pool_key.clone(),
-                    order_type: decrypted_order.order_type.clone(),
-                    amount: decrypted_order.amount,
-                    price: decrypted_order.price,
-                    status: OrderStatus::Pending,
-                    timestamp: chrono::Utc::now().timestamp() as u64,
-                    deadline: decrypted_order.deadline,
-                };
-                
-                pool_order_book.add_order(order).await?;
+            if !pool_filter.serves(&pool_key) {
+                continue;
+            }
+
+            let pool_config = config.for_pool(pool_overrides.get(&pool_key));
+            if !self.interval_elapsed(&pool_key, &pool_config) {
+                continue;
             }
 
-            // Find matches in this pool
-            let matches = self.find_matches_in_pool(&pool_order_book).await?;
+            info!("Processing {} orders for pool {}", pool.orders.len(), pool_key);
+
+            // Find matches in this pool, claimed while the pool is still locked
+            let matches = self.tracker.matched(self.find_matches_in_pool(&pool.book, &pool_config));
+            metrics().pool_matches.with_label_values(&[&pool_key]).inc_by(matches.len() as u64);
             
-            // Track which orders were matched
-            for order_match in &matches {
-                for (idx, _) in &orders {
-                    if order_match.buy_order.id == pending[*idx].id || 
-                       order_match.sell_order.id == pending[*idx].id {
-                        processed_indices.push(*idx);
-                    }
-                }
+            // Filled orders leave the queue; partly filled ones stay with what is left of them
+            let (filled, dropped) = pool.fill(&matches);
+            self.hold_until_settled(&matches, dropped);
+            self.release_pending(filled);
+
+            let unfilled: HashSet<String> = pool.orders.iter()
+                .filter(|order| order.time_in_force.is_immediate())
+                .map(|order| order.id.clone())
+                .collect();
+            if !unfilled.is_empty() {
+                immediate.push((Arc::clone(&queue), unfilled));
             }
             
             all_matches.extend(matches);
         }
 
-        // Remove processed orders from pending (in reverse order to maintain indices)
-        processed_indices.sort_by(|a, b| b.cmp(a));
-        processed_indices.dedup();
-        
-        for idx in processed_indices {
-            pending.remove(idx);
+        if config.enable_cross_pool_matching {
+            all_matches.extend(self.match_routes(&config, &pool_overrides, &pool_filter).await);
         }
 
+        // Whatever is left of an immediate order after its round expires; orders that arrived
+        // since wait for the next one
+        for (queue, unfilled) in immediate {
+            let mut pool = queue.lock().unwrap();
+            let expired = self.expire(&mut pool, |order| unfilled.contains(&order.id));
+            if expired > 0 {
+                info!("Expired {} unfilled immediate orders in pool {}", expired, pool.book.pool_key);
+            }
+        }
+
+        metrics().matches_found.inc_by(all_matches.len() as u64);
+
         if !all_matches.is_empty() {
             info!("Found {} matches across all pools", all_matches.len());
             
             // Store recent matches
             let mut recent = self.recent_matches.write().await;
-            recent.extend(all_matches.clone());
-            
-            // Keep only last 100 matches
-            if recent.len() > 100 {
-                let overflow = recent.len() - 100;
-                recent.drain(0..overflow);
+            for order_match in &all_matches {
+                recent.insert(order_match.match_id.clone(), order_match.clone());
             }
         }
 
@@ -160,7 +408,17 @@ impl MatchingEngine {
     }
 
     /// Find matches for decrypted orders
+    #[instrument(name = "order.match", skip_all, fields(orders = orders.len()))]
     pub async fn find_matches(&self, orders: Vec<DecryptedOrder>) -> Result<Vec<OrderMatch>> {
+        let mut authenticated = Vec::with_capacity(orders.len());
+        for order in orders {
+            match self.authenticate(&order).await {
+                Ok(()) => authenticated.push(order),
+                Err(e) => warn!("Rejecting order {}: {}", order.id, e),
+            }
+        }
+        let orders = authenticated;
+
         if orders.len() < 2 {
             return Ok(vec![]);
         }
@@ -168,7 +426,7 @@ impl MatchingEngine {
         info!("Finding matches for {} decrypted orders", orders.len());
         
         // Group by pool key
-        let mut pool_groups: HashMap<String, Vec<DecryptedOrder>> = HashMap::new();
+        let mut pool_groups: BTreeMap<String, Vec<DecryptedOrder>> = BTreeMap::new();
         for order in orders {
             pool_groups.entry(order.pool_key.clone())
                       .or_insert_with(Vec::new)
@@ -176,152 +434,589 @@ impl MatchingEngine {
         }
 
         let mut all_matches = Vec::new();
+        let config = self.config.read().await.clone();
+        let pool_overrides = self.pool_overrides.read().await.clone();
+        let pool_filter = self.pool_filter.read().await.clone();
 
         for (pool_key, pool_orders) in pool_groups {
-            if pool_orders.len() < 2 {
+            if pool_orders.len() < 2 || !pool_filter.serves(&pool_key) {
                 continue;
             }
 
             // Create order book for this pool
+            let pool_config = config.for_pool(pool_overrides.get(&pool_key));
             let mut order_book = OrderBook::new(pool_key.clone());
             
-            for decrypted_order in pool_orders {
-                let order = Order {
-                    id: decrypted_order.id,
-                    trader: decrypted_order.trader,
-                    pool_key: decrypted_order.pool_key,
-                    order_type: decrypted_order.order_type,
-                    amount: decrypted_order.amount,
-                    price: decrypted_order.price,
-                    status: OrderStatus::Pending,
-                    timestamp: chrono::Utc::now().timestamp() as u64,
-                    deadline: decrypted_order.deadline,
-                };
-                
-                order_book.add_order(order).await?;
+            for decrypted_order in &pool_orders {
+                order_book.add_order(Order::from(decrypted_order))?;
             }
 
             // Find matches
-            let matches = self.find_matches_in_pool(&order_book).await?;
+            all_matches.extend(self.find_matches_in_pool(&order_book, &pool_config));
+        }
+
+        // Any order cancelled since it was authenticated is dropped here, with its matches
+        Ok(self.tracker.matched(all_matches))
+    }
+
+    /// Match the orders left in the pools of each route against each other, at limits restated
+    /// for the route's settlement pool, where the matches then settle
+    async fn match_routes(
+        &self,
+        config: &MatchingConfig,
+        pool_overrides: &BTreeMap<String, PoolOverrides>,
+        pool_filter: &PoolFilterConfig,
+    ) -> Vec<OrderMatch> {
+        let queues: BTreeMap<String, Arc<Mutex<PoolQueue>>> = self.pool_queues().await.into_iter()
+            .filter(|(pool_key, _)| pool_filter.serves(pool_key))
+            .collect();
+        let routes = routing::routes(queues.keys().map(String::as_str), &*self.routes.read().await);
+        let mut all_matches = Vec::new();
+
+        for route in routes {
+            let route_config = config.for_pool(pool_overrides.get(&route.settlement_pool)).for_pool(Some(&route.matching));
+            if !self.interval_elapsed(&route.pair, &route_config) {
+                continue;
+            }
+
+            // Locked in pool key order; everything else holds one pool lock at a time
+            let mut pools: Vec<_> = route.pools.iter().map(|(pool_key, _)| queues[pool_key].lock().unwrap()).collect();
+
+            // Restated orders keep their arrival time, so the combined book stays in price-time priority
+            let mut book = OrderBook::new(route.settlement_pool.clone());
+            let mut originals = HashMap::new();
+            for pool in &pools {
+                for order in pool.book.get_buy_orders().into_iter().chain(pool.book.get_sell_orders()) {
+                    if route.normalize(&order).is_some_and(|normalized| book.add_order(normalized).is_ok()) {
+                        originals.insert(order.id.clone(), order);
+                    }
+                }
+            }
+
+            let matches = self.find_matches_in_pool(&book, &route_config).into_iter()
+                .map(|order_match| OrderMatch {
+                    buy_order: Arc::clone(&originals[&order_match.buy_order.id]),
+                    sell_order: Arc::clone(&originals[&order_match.sell_order.id]),
+                    pool_key: route.settlement_pool.clone(),
+                    ..order_match
+                })
+                .collect();
+            let matches = self.tracker.matched(matches);
+            metrics().pool_matches.with_label_values(&[&route.settlement_pool]).inc_by(matches.len() as u64);
+
+            let matched_ids: HashSet<String> = matches.iter()
+                .flat_map(|order_match| [order_match.buy_order.id.clone(), order_match.sell_order.id.clone()])
+                .collect();
+            let mut dropped = Vec::new();
+            for pool in &mut pools {
+                let (filled, remainders) = pool.fill(&matches);
+                dropped.extend(remainders);
+                self.release_pending(filled);
+            }
+            self.hold_until_settled(&matches, dropped);
+            if !matches.is_empty() {
+                info!("Matched {} orders across the fee tiers of {}", matched_ids.len(), route.pair);
+            }
+
             all_matches.extend(matches);
         }
 
-        Ok(all_matches)
+        all_matches
     }
 
-    /// Find matches within a single pool's order book
-    async fn find_matches_in_pool(&self, order_book: &OrderBook) -> Result<Vec<OrderMatch>> {
-        let buy_orders = order_book.get_buy_orders().await;
-        let sell_orders = order_book.get_sell_orders().await;
+    /// Cross a pool's book, leaving out fill-or-kill orders that would only fill in part. Leaving
+    /// one out can change the fills of others, so the book is crossed again until every
+    /// fill-or-kill order left either fills in full or not at all.
+    fn find_matches_in_pool(&self, order_book: &OrderBook, config: &MatchingConfig) -> Vec<OrderMatch> {
+        let mut killed = HashSet::new();
+        loop {
+            let matches = self.cross_book(order_book, config, &killed);
+
+            let mut filled: HashMap<&str, (Decimal, Decimal)> = HashMap::new();
+            for order_match in &matches {
+                for order in [&order_match.buy_order, &order_match.sell_order] {
+                    if order.time_in_force == TimeInForce::Fok {
+                        filled.entry(order.id.as_str()).or_insert((order.amount, Decimal::ZERO)).1 += order_match.matched_amount;
+                    }
+                }
+            }
+            let partial: Vec<String> = filled.into_iter()
+                .filter(|(_, (amount, total))| total < amount)
+                .map(|(order_id, _)| order_id.to_string())
+                .collect();
+            if partial.is_empty() {
+                return matches;
+            }
+            debug!("Killing {} fill-or-kill orders that would only fill in part", partial.len());
+            killed.extend(partial);
+        }
+    }
+
+    /// Cross a pool's book in price-time priority: each bid, best first, takes the best asks it
+    /// crosses until it is filled or none are left in reach. Fills draw down what is left of
+    /// both orders, so no unit of quantity is matched twice. Orders in `excluded` sit out.
+    fn cross_book(&self, order_book: &OrderBook, config: &MatchingConfig, excluded: &HashSet<String>) -> Vec<OrderMatch> {
+        let now = clock::unix_secs();
+        // Orders below the pool's minimum size stay in the book but aren't matched
+        let open = |orders: Vec<Arc<Order>>| -> Vec<(Arc<Order>, Decimal)> {
+            orders.into_iter()
+                .filter(|order| order.amount >= config.min_order_size && order.deadline > now && !excluded.contains(&order.id))
+                .map(|order| {
+                    let remaining = order.amount;
+                    (order, remaining)
+                })
+                .collect()
+        };
+        let bids = open(order_book.get_buy_orders());
+        let mut asks = open(order_book.get_sell_orders());
         
-        if buy_orders.is_empty() || sell_orders.is_empty() {
+        if bids.is_empty() || asks.is_empty() {
             debug!("No matching possible: {} buy orders, {} sell orders", 
-                   buy_orders.len(), sell_orders.len());
-            return Ok(vec![]);
+                   bids.len(), asks.len());
+            return vec![];
         }
 
         let mut matches = Vec::new();
+        // Asks before this one are used up
+        let mut best_ask = 0;
         
-        // Simple price-time priority matching
-        for buy_order in &buy_orders {
-            for sell_order in &sell_orders {
-                if self.can_match(buy_order, sell_order) {
-                    let matched_price = self.calculate_match_price(buy_order, sell_order);
-                    let matched_amount = self.calculate_match_amount(buy_order, sell_order);
-                    
-                    let order_match = OrderMatch {
-                        match_id: Uuid::new_v4().to_string(),
-                        buy_order: buy_order.clone(),
-                        sell_order: sell_order.clone(),
-                        matched_price,
-                        matched_amount,
-                        timestamp: chrono::Utc::now().timestamp() as u64,
-                        pool_key: buy_order.pool_key.clone(),
-                    };
-                    
-                    matches.push(order_match);
-                    info!("Found match: {} units at price {}", matched_amount, matched_price);
+        for (bid, mut bid_remaining) in bids {
+            while asks.get(best_ask).is_some_and(|(_, remaining)| remaining.is_zero()) {
+                best_ask += 1;
+            }
+            // Bids only get lower, so once the best open ask is out of reach no later bid crosses
+            if !asks.get(best_ask).is_some_and(|(ask, _)| self.crosses(&bid, ask, config)) {
+                break;
+            }
+            
+            for (ask, ask_remaining) in &mut asks[best_ask..] {
+                // A remainder below the minimum fill is left unmatched rather than proved as dust
+                if bid_remaining.is_zero() || bid_remaining < config.min_fill_amount || !self.crosses(&bid, ask, config) {
+                    break;
                 }
+                // Dust asks and a trader's own asks are passed over, not consumed
+                if ask_remaining.is_zero() || *ask_remaining < config.min_fill_amount || ask.trader == bid.trader {
+                    continue;
+                }
+                // Later asks are pricier, so their prices would slip further
+                let Some(matched_price) = self.calculate_match_price(&bid, ask, config) else {
+                    break;
+                };
+                let matched_amount = bid_remaining.min(*ask_remaining);
+                bid_remaining -= matched_amount;
+                *ask_remaining -= matched_amount;
+                
+                matches.push(OrderMatch {
+                    match_id: entropy::uuid().to_string(),
+                    buy_order: Arc::clone(&bid),
+                    sell_order: Arc::clone(ask),
+                    matched_price,
+                    matched_amount,
+                    timestamp: now,
+                    pool_key: bid.pool_key.clone(),
+                    rebates: Vec::new(),
+                });
+                info!("Found match: {} units at price {}", matched_amount, matched_price);
             }
         }
 
-        Ok(matches)
+        matches
     }
 
-    /// Check if two orders can be matched
-    fn can_match(&self, buy_order: &Order, sell_order: &Order) -> bool {
-        // Basic matching criteria
-        buy_order.pool_key == sell_order.pool_key &&
-        buy_order.price >= sell_order.price &&
-        buy_order.status == OrderStatus::Pending &&
-        sell_order.status == OrderStatus::Pending &&
-        buy_order.trader != sell_order.trader &&
-        buy_order.deadline > chrono::Utc::now().timestamp() as u64 &&
-        sell_order.deadline > chrono::Utc::now().timestamp() as u64
+    /// Whether a buy's limit, widened by `price_tolerance_bps`, reaches a sell's
+    fn crosses(&self, buy_order: &Order, sell_order: &Order, config: &MatchingConfig) -> bool {
+        // A limit too large to scale is above any sell price
+        buy_order.price.mul_bps(10_000 + config.price_tolerance_bps)
+            .is_none_or(|limit| limit >= sell_order.price)
     }
 
-    /// Calculate the execution price for a match
-    fn calculate_match_price(&self, buy_order: &Order, sell_order: &Order) -> f64 {
-        // Use mid-point pricing
-        (buy_order.price + sell_order.price) / 2.0
+    /// Execution price for a match; `None` if it is more than `max_slippage_bps` worse than
+    /// either limit, as it can be when `price_tolerance_bps` lets the limits cross loosely
+    fn calculate_match_price(&self, buy_order: &Order, sell_order: &Order, config: &MatchingConfig) -> Option<Decimal> {
+        let price = match config.algorithm {
+            MatchingAlgorithm::Midpoint => buy_order.price.midpoint(sell_order.price),
+            MatchingAlgorithm::BuyPrice => buy_order.price,
+            MatchingAlgorithm::SellPrice => sell_order.price,
+        };
+
+        // Limits or prices too large to scale are out of reach of any slippage
+        let slipped = |price: Decimal| price.mul_bps(10_000 + config.max_slippage_bps).unwrap_or(Decimal::from_raw(u128::MAX));
+        (price <= slipped(buy_order.price) && slipped(price) >= sell_order.price).then_some(price)
     }
 
-    /// Calculate the execution amount for a match
-    fn calculate_match_amount(&self, buy_order: &Order, sell_order: &Order) -> f64 {
-        // Use minimum of both amounts
-        buy_order.amount.min(sell_order.amount)
+    /// Whether `pool_key` is due for matching, recording the run if so
+    fn interval_elapsed(&self, pool_key: &str, config: &MatchingConfig) -> bool {
+        if config.batch_jitter_ms > 0 {
+            return self.batch_closed(pool_key, config);
+        }
+
+        let now = clock::elapsed();
+        let interval = Duration::from_millis(config.matching_interval_ms);
+        let mut last_matched = self.last_matched.lock().unwrap();
+        
+        match last_matched.get(pool_key) {
+            Some(last) if now.saturating_sub(*last) < interval => false,
+            _ => {
+                last_matched.insert(pool_key.to_string(), now);
+                true
+            }
+        }
+    }
+
+    /// Whether the current window of `pool_key` reached its jittered close and hasn't been
+    /// matched yet, recording the close if so
+    fn batch_closed(&self, pool_key: &str, config: &MatchingConfig) -> bool {
+        let now = clock::unix_millis();
+        let window = now / config.matching_interval_ms;
+        let mut closed_windows = self.closed_windows.lock().unwrap();
+
+        if closed_windows.get(pool_key).is_some_and(|closed| *closed >= window) || now < batch_close(config, pool_key, window) {
+            return false;
+        }
+        closed_windows.insert(pool_key.to_string(), window);
+        true
     }
 
     /// Get recent matching statistics
     pub async fn get_matching_stats(&self) -> Result<MatchingResult> {
-        let recent_matches = self.recent_matches.read().await;
-        let pending_orders = self.pending_orders.read().await;
+        let recent_matches: Vec<OrderMatch> = self.recent_matches.read().await.values().cloned().collect();
         
+        // Stats never panic on overflow; the volume saturates
         let total_volume = recent_matches.iter()
-            .map(|m| m.matched_amount)
-            .sum::<f64>();
+            .fold(Decimal::ZERO, |total, m| total.saturating_add(m.matched_amount));
             
-        let average_price = if recent_matches.is_empty() {
-            0.0
-        } else {
-            recent_matches.iter()
-                .map(|m| m.matched_price)
-                .sum::<f64>() / recent_matches.len() as f64
-        };
+        let count = recent_matches.len() as u64;
+        let average_price = recent_matches.iter()
+            .try_fold(Decimal::ZERO, |total, m| total.checked_add(m.matched_price))
+            .map(|total| total.div_int(count))
+            // Past the largest total, each price's share is added up instead
+            .unwrap_or_else(|| recent_matches.iter().fold(Decimal::ZERO, |total, m| total.saturating_add(m.matched_price.div_int(count))));
 
         // Convert pending orders to unmatched orders
-        let unmatched_orders: Vec<Order> = pending_orders.iter()
-            .map(|decrypted| Order {
-                id: decrypted.id.clone(),
-                trader: decrypted.trader.clone(),
-                pool_key: decrypted.pool_key.clone(),
-                order_type: decrypted.order_type.clone(),
-                amount: decrypted.amount,
-                price: decrypted.price,
-                status: OrderStatus::Pending,
-                timestamp: chrono::Utc::now().timestamp() as u64,
-                deadline: decrypted.deadline,
-            })
-            .collect();
+        let mut unmatched_orders = Vec::new();
+        for (_, pool) in self.pool_queues().await {
+            unmatched_orders.extend(pool.lock().unwrap().orders.iter().map(Order::from));
+        }
 
         Ok(MatchingResult {
-            matches: recent_matches.clone(),
+            matches: recent_matches,
             unmatched_orders,
             total_volume,
             average_price,
         })
     }
 
+    /// Per-pool order book statistics over the pending queue
+    pub async fn get_order_book_stats(&self) -> Result<Vec<OrderBookStats>> {
+        let mut stats = Vec::new();
+        for (pool_key, _) in self.pool_queues().await {
+            stats.extend(self.inspect_book(&pool_key, OrderBook::get_stats).await);
+        }
+        
+        Ok(stats)
+    }
+
+    /// Aggregated depth of the pending queue for one pool, if it has any orders
+    pub async fn get_depth(&self, pool_key: &str, levels: usize) -> Result<Option<OrderBookDepth>> {
+        Ok(self.inspect_book(pool_key, |order_book| order_book.get_depth(levels)).await)
+    }
+
+    /// Order book statistics for one pool, if it has any pending orders
+    pub async fn get_pool_stats(&self, pool_key: &str) -> Result<Option<OrderBookStats>> {
+        Ok(self.inspect_book(pool_key, OrderBook::get_stats).await)
+    }
+
+    /// Apply `inspect` to the book of `pool_key`, if the pool has any pending orders
+    async fn inspect_book<T>(&self, pool_key: &str, inspect: impl FnOnce(&OrderBook) -> T) -> Option<T> {
+        let pool = self.pools.read().await.get(pool_key).cloned()?;
+        let pool = pool.lock().unwrap();
+        (!pool.orders.is_empty()).then(|| inspect(&pool.book))
+    }
+
+    /// Queue of `pool_key`, created the first time the pool is seen
+    async fn pool(&self, pool_key: &str) -> Arc<Mutex<PoolQueue>> {
+        if let Some(pool) = self.pools.read().await.get(pool_key) {
+            return Arc::clone(pool);
+        }
+        let mut pools = self.pools.write().await;
+        Arc::clone(pools.entry(pool_key.to_string()).or_insert_with(|| Arc::new(Mutex::new(PoolQueue::new(pool_key)))))
+    }
+
+    /// Every pool's queue, in pool order
+    async fn pool_queues(&self) -> Vec<(String, Arc<Mutex<PoolQueue>>)> {
+        self.pools.read().await.iter()
+            .map(|(pool_key, pool)| (pool_key.clone(), Arc::clone(pool)))
+            .collect()
+    }
+
+    /// Take the orders matching `expired` out of `pool` unmatched and mark them expired; returns how many
+    fn expire(&self, pool: &mut PoolQueue, expired: impl Fn(&DecryptedOrder) -> bool) -> usize {
+        let order_ids: Vec<String> = pool.take(expired).into_iter().map(|order| order.id).collect();
+        let entries: Vec<JournalEntry> = order_ids.iter().map(|order_id| JournalEntry::Remove { order_id: order_id.clone() }).collect();
+        self.journal_or_log(&entries);
+        self.release_pending(order_ids.len());
+        self.tracker.expired(&order_ids);
+        order_ids.len()
+    }
+
+    /// Journal every change to the queues from now on; set once the journal's orders are restored
+    pub fn set_journal(&self, journal: OrderJournal) {
+        *self.journal.lock().unwrap() = Some(journal);
+    }
+
+    /// Append `entries` to the journal, if one is set
+    fn journal(&self, entries: &[JournalEntry]) -> Result<()> {
+        match self.journal.lock().unwrap().as_mut() {
+            Some(journal) => journal.append(entries),
+            None => Ok(()),
+        }
+    }
+
+    /// Journal a change already made to a queue. If that fails, the order comes back as it
+    /// was before the change on restart.
+    fn journal_or_log(&self, entries: &[JournalEntry]) {
+        if let Err(e) = self.journal(entries) {
+            error!("Failed to journal {} order book changes: {:?}", entries.len(), e);
+        }
+    }
+
+    /// Hold off journaling the fills of `matches` until they settle, so orders matched but not
+    /// settled before a crash come back to be matched again. The remainders `dropped` from the
+    /// queue are journaled as removed once all of their order's matches have settled.
+    fn hold_until_settled(&self, matches: &[OrderMatch], dropped: Vec<String>) {
+        let mut unsettled = self.unsettled.lock().unwrap();
+        for order_match in matches {
+            for order in [&order_match.buy_order, &order_match.sell_order] {
+                unsettled.entry(order.id.clone()).or_default().matches += 1;
+            }
+        }
+        for order_id in dropped {
+            unsettled.entry(order_id).or_default().dropped = true;
+        }
+    }
+
+    /// Journal the fills of `matches` from `process_pending_orders` once they have settled
+    pub fn settle(&self, matches: &[OrderMatch]) {
+        let mut entries = Vec::new();
+        {
+            let mut unsettled = self.unsettled.lock().unwrap();
+            for order_match in matches {
+                for order in [&order_match.buy_order, &order_match.sell_order] {
+                    entries.push(JournalEntry::Fill { order_id: order.id.clone(), amount: order_match.matched_amount });
+                    let Some(held) = unsettled.get_mut(&order.id) else {
+                        continue;
+                    };
+                    held.matches = held.matches.saturating_sub(1);
+                    if held.matches == 0 && unsettled.remove(&order.id).is_some_and(|held| held.dropped) {
+                        entries.push(JournalEntry::Remove { order_id: order.id.clone() });
+                    }
+                }
+            }
+        }
+        self.journal_or_log(&entries);
+    }
+
+    /// Account for `count` orders leaving the queue
+    fn release_pending(&self, count: usize) {
+        let pending = self.pending_total.fetch_sub(count, Ordering::SeqCst) - count;
+        metrics().orders_pending.set(pending as i64);
+    }
+
+    /// Lifecycle of every order seen, for trader status queries
+    pub fn tracker(&self) -> &OrderTracker {
+        &self.tracker
+    }
+
+    /// Trader nonces claimed by accepted orders
+    pub fn nonces(&self) -> &NonceRegistry {
+        &self.nonces
+    }
+
+    /// Orders waiting for the next matching round
+    pub async fn pending_count(&self) -> usize {
+        self.pending_total.load(Ordering::SeqCst)
+    }
+
+    pub async fn config(&self) -> MatchingConfig {
+        self.config.read().await.clone()
+    }
+
+    /// Delay between matching rounds; the shortest of the global and per-pool intervals or, with
+    /// batch jitter, until the next batch close of a pool that has orders to match
+    pub async fn matching_interval(&self) -> std::time::Duration {
+        let config = self.config.read().await.clone();
+        let overrides = self.pool_overrides.read().await.clone();
+        let shortest = overrides.values()
+            .filter_map(|overrides| overrides.matching_interval_ms)
+            .fold(config.matching_interval_ms, u64::min);
+        let shortest = std::time::Duration::from_millis(shortest);
+        if config.batch_jitter_ms == 0 {
+            return shortest;
+        }
+
+        // Only pools that `process_pending_orders` would consider close their batches
+        let pool_filter = self.pool_filter.read().await.clone();
+        let mut pool_orders: BTreeMap<String, usize> = BTreeMap::new();
+        for (pool_key, pool) in self.pool_queues().await {
+            pool_orders.insert(pool_key, pool.lock().unwrap().orders.len());
+        }
+
+        let now = clock::unix_millis();
+        let closed_windows = self.closed_windows.lock().unwrap();
+        pool_orders.iter()
+            .filter(|(pool_key, orders)| **orders >= 2 && pool_filter.serves(pool_key))
+            .map(|(pool_key, _)| {
+                let config = config.for_pool(overrides.get(pool_key));
+                let window = now / config.matching_interval_ms;
+                let next = if closed_windows.get(pool_key).is_some_and(|closed| *closed >= window) { window + 1 } else { window };
+                std::time::Duration::from_millis(batch_close(&config, pool_key, next).saturating_sub(now))
+            })
+            .fold(shortest, std::time::Duration::min)
+    }
+
+    /// Effective matching settings for a pool
+    pub async fn pool_config(&self, pool_key: &str) -> MatchingConfig {
+        let overrides = self.pool_overrides.read().await;
+        self.config.read().await.for_pool(overrides.get(pool_key))
+    }
+
+    /// Whether orders for `pool_key` are accepted and matched
+    pub async fn serves(&self, pool_key: &str) -> bool {
+        self.pool_filter.read().await.serves(pool_key)
+    }
+
+    pub async fn pool_filter(&self) -> PoolFilterConfig {
+        self.pool_filter.read().await.clone()
+    }
+
+    pub async fn update_pool_filter(&self, pool_filter: PoolFilterConfig) {
+        info!("Pool filter updated: allow {:?}, deny {:?}", pool_filter.allow, pool_filter.deny);
+        *self.pool_filter.write().await = pool_filter;
+    }
+
+    /// Screen decrypted orders with `screen` from now on
+    pub async fn update_screen(&self, screen: Arc<dyn OrderScreen>) {
+        info!("Screening orders with the {} plugin", screen.name());
+        *self.screen.write().await = screen;
+    }
+
+    /// Domain of the OrderVault trader signatures are made for
+    pub async fn update_order_domain(&self, domain: OrderDomain) {
+        info!("Verifying trader signatures for chain {} vault {}", domain.chain_id, domain.verifying_contract);
+        *self.order_domain.write().await = Some(domain);
+    }
+
+    /// Sign receipts for accepted orders with the operator key `private_key`
+    pub async fn update_receipt_key(&self, private_key: Secret) {
+        *self.receipt_key.write().await = Some(private_key);
+    }
+
+    /// Decrypt orders with the committee order key `order_key`, which must hold its private part
+    #[cfg(feature = "chain")]
+    pub async fn update_order_key(&self, order_key: EncryptionManager) {
+        *self.order_key.write().await = Some(Arc::new(order_key));
+    }
+
+    async fn issue_receipt(&self, order_id: &str) -> Option<OrderReceipt> {
+        let domain = self.order_domain.read().await.clone()?;
+        let receipt_key = self.receipt_key.read().await;
+        match domain.sign_receipt(order_id, clock::unix_secs(), receipt_key.as_ref()?.expose()) {
+            Ok(receipt) => Some(receipt),
+            Err(e) => {
+                warn!("No receipt for order {}: {}", order_id, e);
+                None
+            }
+        }
+    }
+
+    /// Check a decrypted order's signature against the trader it names and screen it, then claim
+    /// its trader nonce so a replay of the same order under another id is refused
+    async fn authenticate(&self, order: &DecryptedOrder) -> Result<()> {
+        if self.tracker.status(&order.id) == Some(OrderLifecycle::Cancelled) {
+            return Err(anyhow::anyhow!("order was cancelled by its trader"));
+        }
+        let pool_config = self.pool_config(&order.pool_key).await;
+        if order.amount.to_units(pool_config.base_decimals).is_none() {
+            return Err(anyhow::anyhow!(
+                "amount {} is finer than the {} decimals of the pool's base token",
+                order.amount, pool_config.base_decimals
+            ));
+        }
+        if order.price.to_units(pool_config.quote_decimals).is_none() {
+            return Err(anyhow::anyhow!(
+                "price {} is finer than the {} decimals of the pool's quote token",
+                order.price, pool_config.quote_decimals
+            ));
+        }
+        match &order.signature {
+            Some(signature) => match self.order_domain.read().await.as_ref() {
+                Some(domain) => domain.verify(&OrderMessage::from(order), signature)?,
+                None => return Err(anyhow::anyhow!("no order domain to verify the trader signature against")),
+            },
+            None if self.config.read().await.require_trader_signatures => {
+                return Err(anyhow::anyhow!("order is not signed by its trader"));
+            }
+            None => {}
+        }
+        let screen = self.screen.read().await.clone();
+        screen.screen(order).await?;
+
+        match order.trader_nonce {
+            Some(nonce) => self.nonces.claim(&order.trader, nonce, &order.id),
+            // Without a nonce, a signed order could be replayed under another id
+            None if self.config.read().await.require_trader_signatures => {
+                Err(anyhow::anyhow!("order carries no trader nonce"))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Replace every per-pool override
+    pub async fn update_pool_overrides(&self, pool_overrides: BTreeMap<String, PoolOverrides>) {
+        info!("Pool overrides updated for {} pools", pool_overrides.len());
+        *self.pool_overrides.write().await = pool_overrides;
+    }
+
+    /// Replace every cross-pool route setting
+    pub async fn update_routes(&self, routes: BTreeMap<String, RouteConfig>) {
+        info!("Cross-pool routes updated for {} pairs", routes.len());
+        *self.routes.write().await = routes;
+    }
+
+    /// Swap in new matching parameters; rejected if the queue already exceeds the new order limit
+    pub async fn update_config(&self, config: MatchingConfig) -> Result<()> {
+        let pending_count = self.pending_total.load(Ordering::SeqCst);
+        if pending_count > config.max_pending_orders {
+            return Err(anyhow::anyhow!(
+                "{} orders are pending, above the requested limit of {}",
+                pending_count, config.max_pending_orders
+            ));
+        }
+        
+        self.restore_config(config).await;
+        Ok(())
+    }
+
+    /// Swap in matching parameters unconditionally, e.g. to roll back a rejected update
+    pub async fn restore_config(&self, config: MatchingConfig) {
+        info!("Matching config updated: {:?}", config);
+        self.recent_matches.write().await.resize(
+            config.recent_matches_limit,
+            Duration::from_secs(config.recent_matches_ttl_seconds),
+        );
+        *self.config.write().await = config;
+    }
+
     /// Health check for the matching engine
     pub async fn health_check(&self) -> Result<()> {
-        let pending_count = self.pending_orders.read().await.len();
+        let pending_count = self.pending_total.load(Ordering::SeqCst);
         let recent_matches_count = self.recent_matches.read().await.len();
         
         debug!("Matching engine health: {} pending orders, {} recent matches", 
                pending_count, recent_matches_count);
         
         // Check if engine is responsive
-        if pending_count > self.config.max_pending_orders {
+        if pending_count > self.config.read().await.max_pending_orders {
             warn!("High number of pending orders: {}", pending_count);
         }
         
@@ -329,9 +1024,101 @@ impl MatchingEngine {
     }
 }
 
+/// A matched order's matches still waiting to settle
+#[derive(Default)]
+struct Unsettled {
+    matches: usize,
+    /// What was left of the order was dropped from its queue
+    dropped: bool,
+}
+
+/// One pool's pending orders in arrival order, and the book they rest in between matching rounds
+struct PoolQueue {
+    orders: Vec<DecryptedOrder>,
+    book: OrderBook,
+}
+
+impl PoolQueue {
+    fn new(pool_key: &str) -> Self {
+        Self {
+            orders: Vec::new(),
+            book: OrderBook::new(pool_key.to_string()),
+        }
+    }
+
+    fn push(&mut self, order: DecryptedOrder) -> Result<()> {
+        self.book.add_order(Order::from(&order))?;
+        self.orders.push(order);
+        Ok(())
+    }
+
+    /// Take the orders matching `taken` out of the queue and its book
+    fn take(&mut self, taken: impl Fn(&DecryptedOrder) -> bool) -> Vec<DecryptedOrder> {
+        let (removed, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.orders).into_iter().partition(|order| taken(order));
+        self.orders = kept;
+        for order in &removed {
+            self.book.remove_order(&order.id);
+        }
+        removed
+    }
+
+    /// Take the orders of this pool `matches` fill out of the queue. What is left of a partly
+    /// filled order goes back in at its arrival time, unless it is an immediate order, whose
+    /// round is over. Returns how many orders left the queue, and the orders whose remainder
+    /// was dropped.
+    fn fill(&mut self, matches: &[OrderMatch]) -> (usize, Vec<String>) {
+        let mut filled: BTreeMap<&str, Decimal> = BTreeMap::new();
+        for order_match in matches {
+            for order in [&order_match.buy_order, &order_match.sell_order] {
+                *filled.entry(order.id.as_str()).or_insert(Decimal::ZERO) += order_match.matched_amount;
+            }
+        }
+
+        let taken = self.take(|order| filled.contains_key(order.id.as_str()));
+        let mut left = taken.len();
+        let mut dropped = Vec::new();
+        for order in taken {
+            let remaining = order.amount.saturating_sub(filled[order.id.as_str()]);
+            if remaining.is_zero() {
+                continue;
+            }
+            let order_id = order.id.clone();
+            if order.time_in_force.is_immediate() {
+                dropped.push(order_id);
+                continue;
+            }
+            match self.push(DecryptedOrder { amount: remaining, ..order }) {
+                Ok(()) => {
+                    left -= 1;
+                    debug!("Order {} rests with {} left unfilled", order_id, remaining);
+                }
+                Err(e) => {
+                    warn!("Dropping the remainder of order {}: {}", order_id, e);
+                    dropped.push(order_id);
+                }
+            }
+        }
+        (left, dropped)
+    }
+}
+
+/// When batch `window` of `pool_key` closes, in unix millis: the window start plus a jitter every
+/// committee member derives alike from the shared seed, but outside observers can't predict
+fn batch_close(config: &MatchingConfig, pool_key: &str, window: u64) -> u64 {
+    let mut hasher = Sha256::new();
+    hasher.update(config.batch_jitter_seed.expose().as_bytes());
+    hasher.update(pool_key.as_bytes());
+    hasher.update(window.to_be_bytes());
+    let digest = hasher.finalize();
+    let draw = u64::from_be_bytes(digest[..8].try_into().expect("8 bytes"));
+
+    window * config.matching_interval_ms + draw % (config.batch_jitter_ms + 1)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::matching::{testing, DenylistScreen, OrderType};
     
     #[tokio::test]
     async fn test_matching_engine_creation() {
@@ -340,16 +1127,798 @@ mod tests {
         assert!(engine.is_ok());
     }
     
+    /// Settings for tests whose orders carry no trader signatures
+    fn unsigned() -> MatchingConfig {
+        MatchingConfig { require_trader_signatures: false, ..MatchingConfig::default() }
+    }
+
+    fn pending_order(id: &str, order_type: OrderType) -> DecryptedOrder {
+        DecryptedOrder {
+            id: id.to_string(),
+            trader: format!("trader_{}", id),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type,
+            amount: Decimal::ONE,
+            price: Decimal::from(2000),
+            deadline: clock::unix_secs() + 3600,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        }
+    }
+
+    #[cfg(feature = "chain")]
     #[tokio::test]
-    async fn test_add_encrypted_order() {
-        let config = crate::config::MatchingConfig::default();
-        let engine = MatchingEngine::new(config).await.unwrap();
+    async fn test_add_encrypted_order() -> Result<()> {
+        use crate::matching::privacy::{EncryptedOrderData, EncryptionManager};
+
+        let engine = MatchingEngine::new(unsigned()).await?;
+        let order_key = EncryptionManager::new()?;
+        let payload = EncryptedOrderData {
+            trader: "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+            pool_key: "ETH_USDC_500".to_string(),
+            order_type: OrderType::Sell,
+            amount: "2.5".parse()?,
+            price: Decimal::from(2001),
+            deadline: clock::unix_secs() + 3600,
+            nonce: EncryptionManager::generate_nonce(),
+            commitment: String::new(),
+            signature: None,
+            trader_nonce: Some(7),
+            time_in_force: Some(TimeInForce::Ioc),
+        };
+        let encrypted = order_key.encrypt_order(&payload)?;
+
+        // Nothing is accepted before the order key is loaded
+        assert!(engine.add_encrypted_order("order_1".to_string(), encrypted.clone()).await.is_err());
+
+        engine.update_order_key(EncryptionManager::from_keys(order_key.export_keys()?)?).await;
+        let order = engine.add_encrypted_order("order_1".to_string(), encrypted).await?.unwrap();
+        assert_eq!(order.trader, payload.trader);
+        assert_eq!(order.pool_key, "ETH_USDC_500");
+        assert_eq!(order.order_type, OrderType::Sell);
+        assert_eq!(order.amount, "2.5".parse::<Decimal>()?);
+        assert_eq!(order.trader_nonce, Some(7));
+        assert_eq!(order.time_in_force, TimeInForce::Ioc);
+        assert_eq!(engine.pending_count().await, 1);
+
+        // Orders for another committee's key, or garbage, are dropped
+        let foreign = EncryptionManager::new()?.encrypt_order(&payload)?;
+        assert!(engine.add_encrypted_order("order_2".to_string(), foreign).await?.is_none());
+        assert!(engine.add_encrypted_order("order_3".to_string(), vec![1, 2, 3, 4]).await?.is_none());
+        assert_eq!(engine.pending_count().await, 1);
+
+        // Unsigned orders are refused by default
+        let engine = MatchingEngine::new(MatchingConfig::default()).await?;
+        let encrypted = order_key.encrypt_order(&payload)?;
+        engine.update_order_key(order_key).await;
+        assert!(engine.add_encrypted_order("order_1".to_string(), encrypted).await?.is_none());
+        Ok(())
+    }
+
+    #[cfg(feature = "chain")]
+    #[tokio::test]
+    async fn test_decrypt_task_orders() -> Result<()> {
+        use crate::matching::privacy::{EncryptedOrderData, EncryptionManager};
+
+        let engine = MatchingEngine::new(unsigned()).await?;
+        let order_key = EncryptionManager::new()?;
+        let payload = |trader_nonce: u64| EncryptedOrderData {
+            trader: "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+            pool_key: "ETH_USDC_500".to_string(),
+            order_type: OrderType::Sell,
+            amount: "2.5".parse().unwrap(),
+            price: Decimal::from(2001),
+            deadline: clock::unix_secs() + 3600,
+            nonce: EncryptionManager::generate_nonce(),
+            commitment: String::new(),
+            signature: None,
+            trader_nonce: Some(trader_nonce),
+            time_in_force: None,
+        };
+        let encrypted = vec![
+            order_key.encrypt_order(&payload(7))?,
+            vec![1, 2, 3, 4],
+            // The same trader nonce again, as a replayed order would carry
+            order_key.encrypt_order(&payload(7))?,
+            order_key.encrypt_order(&payload(8))?,
+        ];
+
+        assert!(engine.decrypt_task_orders("task_1", encrypted.clone()).await.is_err());
+
+        engine.update_order_key(order_key).await;
+        let orders = engine.decrypt_task_orders("task_1", encrypted).await?;
+        let order_ids: Vec<&str> = orders.iter().map(|order| order.id.as_str()).collect();
+        assert_eq!(order_ids, vec!["task_1/0", "task_1/3"]);
+        assert_eq!(orders[0].amount, "2.5".parse::<Decimal>()?);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unserved_pool_is_ignored() {
+        let engine = MatchingEngine::new(unsigned()).await.unwrap();
+        engine.update_pool_filter(PoolFilterConfig {
+            deny: vec!["ETH_USDC_3000".to_string()],
+            ..PoolFilterConfig::default()
+        }).await;
+        
+        let order = engine.add_order(pending_order("order_1", OrderType::Buy)).await.unwrap();
+        assert!(order.is_none());
+        assert_eq!(engine.pending_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_update_config_rejects_limit_below_queue() {
+        let engine = MatchingEngine::new(unsigned()).await.unwrap();
+        engine.add_order(pending_order("order_1", OrderType::Buy)).await.unwrap();
+        engine.add_order(pending_order("order_2", OrderType::Buy)).await.unwrap();
+        
+        let mut config = engine.config().await;
+        config.max_pending_orders = 1;
+        assert!(engine.update_config(config.clone()).await.is_err());
+        
+        config.max_pending_orders = 2;
+        config.matching_interval_ms = 250;
+        engine.update_config(config).await.unwrap();
+        assert_eq!(engine.matching_interval().await.as_millis(), 250);
+    }
+
+    #[test]
+    fn test_batch_close_jitter() {
+        let config = MatchingConfig {
+            matching_interval_ms: 1000,
+            batch_jitter_ms: 400,
+            batch_jitter_seed: "committee seed".into(),
+            ..MatchingConfig::default()
+        };
+        let closes: Vec<u64> = (0..50).map(|window| batch_close(&config, "ETH_USDC_3000", window)).collect();
+
+        // Each close falls in its window's jitter range, and not always at the same offset
+        for (window, close) in closes.iter().enumerate() {
+            assert!((window as u64 * 1000..=window as u64 * 1000 + 400).contains(close));
+        }
+        assert!(closes.iter().any(|close| close % 1000 != closes[0] % 1000));
+
+        // Committee members sharing the seed agree; other seeds don't
+        assert_eq!(batch_close(&config, "ETH_USDC_3000", 7), closes[7]);
+        let other = MatchingConfig { batch_jitter_seed: "other seed".into(), ..config.clone() };
+        assert!((0..50).any(|window| batch_close(&other, "ETH_USDC_3000", window) != closes[window as usize]));
+    }
+
+    #[tokio::test]
+    async fn test_pool_overrides() {
+        let engine = MatchingEngine::new(crate::config::MatchingConfig::default()).await.unwrap();
+        let overrides = PoolOverrides {
+            matching_interval_ms: Some(50),
+            algorithm: Some(MatchingAlgorithm::SellPrice),
+            ..PoolOverrides::default()
+        };
+        engine.update_pool_overrides(BTreeMap::from([("ETH_USDC".to_string(), overrides)])).await;
+        
+        assert_eq!(engine.matching_interval().await.as_millis(), 50);
+        assert_eq!(engine.pool_config("ETH_USDC").await.algorithm, MatchingAlgorithm::SellPrice);
+        assert_eq!(engine.pool_config("WBTC_USDC").await.algorithm, MatchingAlgorithm::Midpoint);
+    }
+
+    #[tokio::test]
+    async fn test_partial_fills_share_quantity() {
+        let engine = MatchingEngine::new(unsigned()).await.unwrap();
+        let deadline = clock::unix_secs() + 3600;
+        let order = |id: &str, order_type: OrderType, amount: u64| DecryptedOrder {
+            id: id.to_string(),
+            trader: format!("trader_{}", id),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type,
+            amount: Decimal::from(amount),
+            price: Decimal::from(2000),
+            deadline,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        };
+        let orders = vec![
+            order("buy", OrderType::Buy, 3),
+            order("sell_1", OrderType::Sell, 2),
+            order("sell_2", OrderType::Sell, 2),
+        ];
         
-        let result = engine.add_encrypted_order(
-            "test_order_1".to_string(), 
-            vec![1, 2, 3, 4]
-        ).await;
+        let matches = engine.find_matches(orders.clone()).await.unwrap();
         
-        assert!(result.is_ok());
+        assert_eq!(matches.iter().map(|m| m.matched_amount).sum::<Decimal>(), Decimal::from(3));
+        assert_eq!(testing::check_invariants(&orders, &matches, &engine.config().await), Ok(()));
+    }
+
+    #[tokio::test]
+    async fn test_dust_and_slipped_fills_are_refused() -> Result<()> {
+        let deadline = clock::unix_secs() + 3600;
+        let order = |id: &str, order_type: OrderType, amount: u64, price: u64| DecryptedOrder {
+            id: id.to_string(),
+            trader: format!("trader_{}", id),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type,
+            amount: Decimal::from(amount),
+            price: Decimal::from(price),
+            deadline,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        };
+
+        // The buy's last unit would be a fill below the minimum
+        let config = MatchingConfig { min_fill_amount: Decimal::from(2), ..unsigned() };
+        let engine = MatchingEngine::new(config.clone()).await?;
+        let orders = vec![
+            order("buy", OrderType::Buy, 3, 2000),
+            order("sell_1", OrderType::Sell, 2, 2000),
+            order("sell_2", OrderType::Sell, 2, 2000),
+        ];
+        let matches = engine.find_matches(orders.clone()).await?;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].matched_amount, Decimal::from(2));
+        assert_eq!(testing::check_invariants(&orders, &matches, &config), Ok(()));
+
+        // Within the tolerance, but the buyer's price is 0.75% under the seller's limit
+        let config = MatchingConfig {
+            price_tolerance_bps: 100,
+            max_slippage_bps: 10,
+            algorithm: MatchingAlgorithm::BuyPrice,
+            ..unsigned()
+        };
+        let orders = vec![order("buy", OrderType::Buy, 1, 2000), order("sell", OrderType::Sell, 1, 2015)];
+        let engine = MatchingEngine::new(config.clone()).await?;
+        assert!(engine.find_matches(orders.clone()).await?.is_empty());
+
+        let engine = MatchingEngine::new(MatchingConfig { max_slippage_bps: 100, ..config }).await?;
+        assert_eq!(engine.find_matches(orders).await?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_best_bid_takes_best_asks_first() -> Result<()> {
+        let engine = MatchingEngine::new(unsigned()).await?;
+        let deadline = clock::unix_secs() + 3600;
+        let order = |id: &str, trader: &str, order_type: OrderType, amount: u64, price: u64| DecryptedOrder {
+            id: id.to_string(),
+            trader: trader.to_string(),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type,
+            amount: Decimal::from(amount),
+            price: Decimal::from(price),
+            deadline,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        };
+        let orders = vec![
+            order("buy_low", "trader_b", OrderType::Buy, 2, 2001),
+            order("sell_far", "trader_e", OrderType::Sell, 1, 2010),
+            order("sell_mid", "trader_d", OrderType::Sell, 2, 2001),
+            order("buy_high", "trader_a", OrderType::Buy, 2, 2003),
+            order("sell_cheap", "trader_c", OrderType::Sell, 1, 2000),
+            order("sell_own", "trader_a", OrderType::Sell, 1, 1999),
+        ];
+
+        let matches = engine.find_matches(orders.clone()).await?;
+
+        // buy_high passes over its own trader's ask; buy_low picks it up afterwards
+        let fills: Vec<(&str, &str, Decimal)> = matches.iter()
+            .map(|m| (m.buy_order.id.as_str(), m.sell_order.id.as_str(), m.matched_amount))
+            .collect();
+        assert_eq!(fills, vec![
+            ("buy_high", "sell_cheap", Decimal::ONE),
+            ("buy_high", "sell_mid", Decimal::ONE),
+            ("buy_low", "sell_own", Decimal::ONE),
+            ("buy_low", "sell_mid", Decimal::ONE),
+        ]);
+        assert_eq!(testing::check_invariants(&orders, &matches, &engine.config().await), Ok(()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pending_books_persist_between_rounds() -> Result<()> {
+        let engine = MatchingEngine::new(MatchingConfig::default()).await?;
+        let deadline = clock::unix_secs() + 3600;
+        let order = |id: &str, pool_key: &str, order_type: OrderType| DecryptedOrder {
+            id: id.to_string(),
+            trader: format!("trader_{}", id),
+            pool_key: pool_key.to_string(),
+            order_type,
+            amount: Decimal::ONE,
+            price: Decimal::from(2000),
+            deadline,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        };
+        engine.restore_pending_orders(vec![
+            order("buy", "ETH_USDC_3000", OrderType::Buy),
+            order("sell", "ETH_USDC_3000", OrderType::Sell),
+            order("lone", "WBTC_USDC_3000", OrderType::Buy),
+        ]).await;
+        assert_eq!(engine.get_depth("ETH_USDC_3000", 5).await?.unwrap().bids.len(), 1);
+
+        let matches = engine.process_pending_orders().await?;
+        assert_eq!(matches.len(), 1);
+        // Matched orders leave their pool's book; the other pool keeps its order
+        assert!(engine.get_depth("ETH_USDC_3000", 5).await?.is_none());
+        assert_eq!(engine.pending_count().await, 1);
+        assert_eq!(engine.get_order_book_stats().await?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partly_filled_orders_rest_with_their_remainder() -> Result<()> {
+        let engine = MatchingEngine::new(MatchingConfig { matching_interval_ms: 1, ..unsigned() }).await?;
+        let order = |id: &str, order_type: OrderType, amount: u64| DecryptedOrder {
+            amount: Decimal::from(amount),
+            ..pending_order(id, order_type)
+        };
+        engine.restore_pending_orders(vec![order("buy", OrderType::Buy, 5), order("sell_1", OrderType::Sell, 2)]).await;
+
+        let matches = engine.process_pending_orders().await?;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].matched_amount, Decimal::from(2));
+        // The buy stays in the book with what it has left to fill
+        assert_eq!(engine.pending_count().await, 1);
+        let depth = engine.get_depth("ETH_USDC_3000", 5).await?.unwrap();
+        assert_eq!(depth.bids[0].total_amount, Decimal::from(3));
+
+        engine.add_order(order("sell_2", OrderType::Sell, 4)).await?;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let matches = engine.process_pending_orders().await?;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].buy_order.id, "buy");
+        assert_eq!(matches[0].matched_amount, Decimal::from(3));
+        // Now the sell is the one left over
+        let depth = engine.get_depth("ETH_USDC_3000", 5).await?.unwrap();
+        assert!(depth.bids.is_empty());
+        assert_eq!(depth.asks[0].total_amount, Decimal::from(1));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_remainder_keeps_its_time_priority() -> Result<()> {
+        let order = |id: &str, order_type: OrderType, amount: u64, received_at: u64| DecryptedOrder {
+            amount: Decimal::from(amount),
+            received_at,
+            ..pending_order(id, order_type)
+        };
+
+        let engine = MatchingEngine::new(MatchingConfig { matching_interval_ms: 1, ..unsigned() }).await?;
+        engine.restore_pending_orders(vec![
+            order("early", OrderType::Buy, 3, 1_000),
+            order("sell_1", OrderType::Sell, 1, 1_000),
+            order("late", OrderType::Buy, 1, 2_000),
+        ]).await;
+        let matches = engine.process_pending_orders().await?;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].buy_order.id, "early");
+
+        // What is left of the early buy is still ahead of the later one at the same price
+        engine.add_order(order("sell_2", OrderType::Sell, 1, 0)).await?;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let matches = engine.process_pending_orders().await?;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].buy_order.id, "early");
+        assert_eq!(matches[0].buy_order.amount, Decimal::from(2));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partly_filled_order_can_be_cancelled() -> Result<()> {
+        let engine = MatchingEngine::new(unsigned()).await?;
+        let order = |id: &str, order_type: OrderType, amount: u64| DecryptedOrder {
+            amount: Decimal::from(amount),
+            ..pending_order(id, order_type)
+        };
+        engine.restore_pending_orders(vec![order("buy", OrderType::Buy, 5), order("sell", OrderType::Sell, 2)]).await;
+        assert_eq!(engine.process_pending_orders().await?.len(), 1);
+        assert_eq!(engine.tracker().status("buy"), Some(OrderLifecycle::PartiallyMatched));
+        assert_eq!(engine.tracker().status("sell"), Some(OrderLifecycle::Matched));
+
+        // The remainder leaves the book; the filled sell can't be cancelled any more
+        let cancelled = engine.cancel_vault_order("buy").await?.unwrap();
+        assert_eq!(cancelled.amount, Decimal::from(3));
+        assert_eq!(engine.tracker().status("buy"), Some(OrderLifecycle::Cancelled));
+        assert_eq!(engine.pending_count().await, 0);
+        assert!(engine.get_depth("ETH_USDC_3000", 5).await?.is_none());
+        assert!(engine.cancel_vault_order("sell").await?.is_none());
+        assert_eq!(engine.tracker().status("sell"), Some(OrderLifecycle::Matched));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_journal_rebuilds_the_queues() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("orders.wal");
+        let order = |id: &str, order_type: OrderType, amount: u64, time_in_force: TimeInForce| DecryptedOrder {
+            amount: Decimal::from(amount),
+            time_in_force,
+            ..pending_order(id, order_type)
+        };
+
+        let engine = MatchingEngine::new(unsigned()).await?;
+        let (journal, orders) = OrderJournal::open(&path, false)?;
+        engine.restore_pending_orders(orders).await;
+        engine.set_journal(journal);
+        for order in [
+            order("buy", OrderType::Buy, 5, TimeInForce::Gtd),
+            order("ioc", OrderType::Sell, 1, TimeInForce::Ioc),
+            order("sell", OrderType::Sell, 2, TimeInForce::Gtd),
+            order("cancelled", OrderType::Sell, 1, TimeInForce::Gtd),
+        ] {
+            engine.add_order(order).await?;
+        }
+        assert!(engine.cancel_vault_order("cancelled").await?.is_some());
+        // Read again, as after a crash before the event was checkpointed
+        assert!(engine.add_order(order("sell", OrderType::Sell, 2, TimeInForce::Gtd)).await?.is_none());
+        let matches = engine.process_pending_orders().await?;
+        assert_eq!(matches.len(), 2);
+        engine.settle(&matches);
+
+        // The buy is left with 2 to fill, and that is all a restart brings back
+        let (_, orders) = OrderJournal::open(&path, false)?;
+        let pending: Vec<_> = orders.iter().map(|order| (order.id.as_str(), order.amount)).collect();
+        assert_eq!(pending, vec![("buy", Decimal::from(2))]);
+
+        let restarted = MatchingEngine::new(unsigned()).await?;
+        restarted.restore_pending_orders(orders).await;
+        assert_eq!(restarted.pending_count().await, 1);
+        assert_eq!(restarted.get_depth("ETH_USDC_3000", 5).await?.unwrap().bids[0].total_amount, Decimal::from(2));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unsettled_route_matches_are_matched_again_after_a_crash() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("orders.wal");
+        let order = |id: &str, pool_key: &str, order_type: OrderType, amount: u64, price: u64, time_in_force: TimeInForce| DecryptedOrder {
+            pool_key: pool_key.to_string(),
+            amount: Decimal::from(amount),
+            price: Decimal::from(price),
+            time_in_force,
+            ..pending_order(id, order_type)
+        };
+        let resting = |orders: &[DecryptedOrder]| -> Vec<(String, Decimal)> {
+            orders.iter().map(|order| (order.id.clone(), order.amount)).collect()
+        };
+
+        let engine = MatchingEngine::new(unsigned()).await?;
+        let (journal, _) = OrderJournal::open(&path, false)?;
+        engine.set_journal(journal);
+        // Only cross once the buyer's limit is restated for the cheaper tier
+        for order in [
+            order("buy", "ETH_USDC_3000", OrderType::Buy, 7, 2001, TimeInForce::Gtd),
+            order("bid", "ETH_USDC_500", OrderType::Buy, 1, 1990, TimeInForce::Gtd),
+            order("sell", "ETH_USDC_500", OrderType::Sell, 2, 2005, TimeInForce::Gtd),
+            order("ioc", "ETH_USDC_500", OrderType::Sell, 4, 2005, TimeInForce::Ioc),
+        ] {
+            engine.add_order(order).await?;
+        }
+        let matches = engine.process_pending_orders().await?;
+        assert_eq!(matches.len(), 2);
+        assert!(matches.iter().all(|order_match| order_match.pool_key == "ETH_USDC_500"));
+
+        // Gone before the matches settled: every order comes back as it arrived
+        drop(engine);
+        let (journal, orders) = OrderJournal::open(&path, false)?;
+        assert_eq!(resting(&orders), vec![
+            ("buy".to_string(), Decimal::from(7)),
+            ("bid".to_string(), Decimal::ONE),
+            ("sell".to_string(), Decimal::from(2)),
+            ("ioc".to_string(), Decimal::from(4)),
+        ]);
+
+        // Matched again and settled, each fill counts once although the route spans two pools
+        let restarted = MatchingEngine::new(unsigned()).await?;
+        restarted.restore_pending_orders(orders).await;
+        restarted.set_journal(journal);
+        let matches = restarted.process_pending_orders().await?;
+        assert_eq!(matches.len(), 2);
+        restarted.settle(&matches);
+        drop(restarted);
+        let (_, orders) = OrderJournal::open(&path, false)?;
+        assert_eq!(resting(&orders), vec![("buy".to_string(), Decimal::ONE), ("bid".to_string(), Decimal::ONE)]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_time_in_force() -> Result<()> {
+        let deadline = clock::unix_secs() + 3600;
+        let order = |id: &str, order_type: OrderType, amount: u64, price: u64, time_in_force: TimeInForce| DecryptedOrder {
+            id: id.to_string(),
+            trader: format!("trader_{}", id),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type,
+            amount: Decimal::from(amount),
+            price: Decimal::from(price),
+            deadline,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force,
+            received_at: 0,
+        };
+
+        // The fill-or-kill buy would only get 2 of its 3 and the other bids don't reach the ask
+        let engine = MatchingEngine::new(unsigned()).await?;
+        engine.restore_pending_orders(vec![
+            order("fok", OrderType::Buy, 3, 2000, TimeInForce::Fok),
+            order("ioc", OrderType::Buy, 1, 1990, TimeInForce::Ioc),
+            order("gtd", OrderType::Buy, 1, 1980, TimeInForce::Gtd),
+            order("sell", OrderType::Sell, 2, 2000, TimeInForce::Gtd),
+        ]).await;
+        assert!(engine.process_pending_orders().await?.is_empty());
+
+        // Both immediate orders expire after their round, the others rest
+        assert_eq!(engine.pending_count().await, 2);
+        assert_eq!(engine.tracker().status("fok"), Some(OrderLifecycle::Expired));
+        assert_eq!(engine.tracker().status("ioc"), Some(OrderLifecycle::Expired));
+        assert_eq!(engine.tracker().status("gtd"), Some(OrderLifecycle::Pending));
+
+        // With enough on offer it fills in full
+        let engine = MatchingEngine::new(unsigned()).await?;
+        let orders = vec![
+            order("fok", OrderType::Buy, 3, 2000, TimeInForce::Fok),
+            order("sell_1", OrderType::Sell, 2, 2000, TimeInForce::Gtd),
+            order("sell_2", OrderType::Sell, 1, 2000, TimeInForce::Gtd),
+        ];
+        let matches = engine.find_matches(orders.clone()).await?;
+        assert_eq!(matches.iter().map(|m| m.matched_amount).sum::<Decimal>(), Decimal::from(3));
+        assert_eq!(testing::check_invariants(&orders, &matches, &engine.config().await), Ok(()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cross_pool_matching() -> Result<()> {
+        let deadline = clock::unix_secs() + 3600;
+        let order = |id: &str, pool_key: &str, order_type: OrderType, price: u64| DecryptedOrder {
+            id: id.to_string(),
+            trader: format!("trader_{}", id),
+            pool_key: pool_key.to_string(),
+            order_type,
+            amount: Decimal::ONE,
+            price: Decimal::from(price),
+            deadline,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        };
+        // Only cross once the buyer's limit is restated for the cheaper tier
+        let orders = || vec![
+            order("buy", "ETH_USDC_3000", OrderType::Buy, 2001),
+            order("sell", "ETH_USDC_500", OrderType::Sell, 2005),
+        ];
+
+        let engine = MatchingEngine::new(MatchingConfig::default()).await?;
+        engine.restore_pending_orders(orders()).await;
+        let matches = engine.process_pending_orders().await?;
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].pool_key, "ETH_USDC_500");
+        assert_eq!(matches[0].buy_order.price, Decimal::from(2001));
+        assert_eq!(matches[0].matched_price, "2005.5".parse()?);
+        assert_eq!(engine.pending_count().await, 0);
+
+        let config = MatchingConfig { enable_cross_pool_matching: false, ..MatchingConfig::default() };
+        let engine = MatchingEngine::new(config).await?;
+        engine.restore_pending_orders(orders()).await;
+        assert!(engine.process_pending_orders().await?.is_empty());
+
+        let engine = MatchingEngine::new(MatchingConfig::default()).await?;
+        engine.update_routes(BTreeMap::from([
+            ("ETH_USDC".to_string(), RouteConfig { disabled: true, ..RouteConfig::default() }),
+        ])).await;
+        engine.restore_pending_orders(orders()).await;
+        assert!(engine.process_pending_orders().await?.is_empty());
+        assert_eq!(engine.pending_count().await, 2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_trader_signatures() {
+        // Second and third anvil development accounts
+        let buyer = ("0x70997970c51812dc3a010c7d01b50e0d17dc79c8", "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d");
+        let seller = ("0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc", "0x5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a");
+        let domain = OrderDomain { chain_id: 31337, verifying_contract: "0x3456789012345678901234567890123456789012".to_string() };
+        let engine = MatchingEngine::new(MatchingConfig::default()).await.unwrap();
+        engine.update_order_domain(domain.clone()).await;
+
+        let deadline = clock::unix_secs() + 3600;
+        let order = |id: &str, trader: &str, signing_key: &str, order_type: OrderType| {
+            let mut order = DecryptedOrder {
+                id: id.to_string(),
+                trader: trader.to_string(),
+                pool_key: "ETH_USDC_3000".to_string(),
+                order_type,
+                amount: Decimal::ONE,
+                price: Decimal::from(2000),
+                deadline,
+                encrypted_data: Vec::new(),
+                signature: None,
+                trader_nonce: Some(1),
+                time_in_force: TimeInForce::Gtd,
+                received_at: 0,
+            };
+            order.signature = Some(domain.sign(&OrderMessage::from(&order), signing_key).unwrap());
+            order
+        };
+
+        let signed = vec![order("buy", buyer.0, buyer.1, OrderType::Buy), order("sell", seller.0, seller.1, OrderType::Sell)];
+        let matches = engine.find_matches(signed.clone()).await.unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!((matches[0].buy_order.id.as_str(), matches[0].sell_order.id.as_str()), ("buy", "sell"));
+
+        // Signed by the buyer in the seller's name
+        let forged = vec![signed[0].clone(), order("sell", seller.0, buyer.1, OrderType::Sell)];
+        assert!(engine.find_matches(forged).await.unwrap().is_empty());
+
+        // Signatures are required by default
+        let unsigned = vec![signed[0].clone(), DecryptedOrder { signature: None, ..signed[1].clone() }];
+        assert!(engine.find_matches(unsigned).await.unwrap().is_empty());
+
+        // So are trader nonces, even on signed orders
+        let mut nonceless = DecryptedOrder { id: "sell_2".to_string(), trader_nonce: None, ..signed[1].clone() };
+        nonceless.signature = Some(domain.sign(&OrderMessage::from(&nonceless), seller.1).unwrap());
+        assert!(engine.find_matches(vec![signed[0].clone(), nonceless]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_signed_cancellation() {
+        // Second and third anvil development accounts
+        let trader_key = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+        let impostor_key = "0x5de4111afa1a4b94908f83103eb1f1706367c2e68ca870fc3fb9a804cdab365a";
+        let domain = OrderDomain { chain_id: 31337, verifying_contract: "0x3456789012345678901234567890123456789012".to_string() };
+        let engine = MatchingEngine::new(unsigned()).await.unwrap();
+        engine.update_order_domain(domain.clone()).await;
+
+        let commitment = "0x9008a7e368a1514130247c9b0845bd213abe4667a54af2ac02e2abe919610af6";
+        let order = DecryptedOrder {
+            id: commitment.to_string(),
+            trader: "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type: OrderType::Buy,
+            amount: Decimal::ONE,
+            price: Decimal::from(2000),
+            deadline: clock::unix_secs() + 3600,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        };
+        engine.restore_pending_orders(vec![order.clone()]).await;
+
+        let forged = domain.sign_cancellation(commitment, impostor_key).unwrap();
+        assert!(engine.cancel_order(&forged).await.is_err());
+
+        let cancellation = domain.sign_cancellation(commitment, trader_key).unwrap();
+        let cancelled = engine.cancel_order(&cancellation).await.unwrap();
+        assert_eq!(cancelled.map(|order| order.id), Some(commitment.to_string()));
+        assert!(engine.cancel_order(&cancellation).await.unwrap().is_none());
+
+        // A cancelled order stays out of matching when a task brings it back
+        let sell = DecryptedOrder { id: "sell".to_string(), order_type: OrderType::Sell, ..order.clone() };
+        assert!(engine.find_matches(vec![order, sell]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_vault_cancellation() -> Result<()> {
+        let engine = MatchingEngine::new(unsigned()).await?;
+        let deadline = clock::unix_secs() + 3600;
+        let order = |id: &str, order_type: OrderType| DecryptedOrder {
+            id: id.to_string(),
+            trader: format!("trader_{}", id),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type,
+            amount: Decimal::ONE,
+            price: Decimal::from(2000),
+            deadline,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        };
+        engine.restore_pending_orders(vec![order("0xaa", OrderType::Buy), order("0xbb", OrderType::Sell)]).await;
+
+        let cancelled = engine.cancel_vault_order("0xAA").await?;
+        assert_eq!(cancelled.map(|order| order.id), Some("0xaa".to_string()));
+        assert_eq!(engine.pending_count().await, 1);
+        assert!(engine.process_pending_orders().await?.is_empty());
+
+        // Cancelled before it reached this operator: refused when a task brings it in
+        assert!(engine.cancel_vault_order("0xcc").await?.is_none());
+        assert_eq!(engine.tracker().status("0xcc"), Some(OrderLifecycle::Cancelled));
+        assert!(engine.find_matches(vec![order("0xcc", OrderType::Buy), order("0xbb", OrderType::Sell)]).await?.is_empty());
+
+        // Once matched, the order is claimed and the cancellation comes too late
+        engine.restore_pending_orders(vec![order("0xdd", OrderType::Buy)]).await;
+        assert_eq!(engine.process_pending_orders().await?.len(), 1);
+        assert!(engine.cancel_vault_order("0xdd").await?.is_none());
+        assert_eq!(engine.tracker().status("0xdd"), Some(OrderLifecycle::Matched));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replayed_orders_are_refused() {
+        let engine = MatchingEngine::new(unsigned()).await.unwrap();
+        let deadline = clock::unix_secs() + 3600;
+        let order = |id: &str, trader: &str, order_type: OrderType, trader_nonce: u64| DecryptedOrder {
+            id: id.to_string(),
+            trader: trader.to_string(),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type,
+            amount: Decimal::ONE,
+            price: Decimal::from(2000),
+            deadline,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: Some(trader_nonce),
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        };
+        let orders = vec![order("buy", "trader_1", OrderType::Buy, 1), order("sell", "trader_2", OrderType::Sell, 1)];
+
+        assert_eq!(engine.find_matches(orders.clone()).await.unwrap().len(), 1);
+        // The same orders seen again in a later task are not replays
+        assert_eq!(engine.find_matches(orders.clone()).await.unwrap().len(), 1);
+
+        let replayed = DecryptedOrder { id: "sell_copy".to_string(), ..orders[1].clone() };
+        assert!(engine.find_matches(vec![orders[0].clone(), replayed]).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_screened_orders_are_refused() {
+        let engine = MatchingEngine::new(unsigned()).await.unwrap();
+        let sanctioned = "0x8589427373d6d84e98730d7795d8f6f8731fda16";
+        let order = |id: &str, trader: &str, order_type: OrderType| DecryptedOrder {
+            id: id.to_string(),
+            trader: trader.to_string(),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type,
+            amount: Decimal::ONE,
+            price: Decimal::from(2000),
+            deadline: clock::unix_secs() + 3600,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        };
+        let orders = vec![order("buy", "0x70997970c51812dc3a010c7d01b50e0d17dc79c8", OrderType::Buy), order("sell", sanctioned, OrderType::Sell)];
+        assert_eq!(engine.find_matches(orders.clone()).await.unwrap().len(), 1);
+
+        engine.update_screen(Arc::new(DenylistScreen::new([sanctioned]).unwrap())).await;
+        assert!(engine.find_matches(orders).await.unwrap().is_empty());
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_matches_uphold_invariants(
+            orders in testing::order_set(40),
+            algorithm in testing::algorithm(),
+            price_tolerance_bps in 0u64..50,
+        ) {
+            let config = MatchingConfig { algorithm, price_tolerance_bps, ..unsigned() };
+            let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+            let matches = runtime.block_on(async {
+                let engine = MatchingEngine::new(config.clone()).await.unwrap();
+                engine.find_matches(orders.clone()).await.unwrap()
+            });
+            
+            proptest::prop_assert_eq!(testing::check_invariants(&orders, &matches, &config), Ok(()));
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,219 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use super::{Decimal, DecryptedOrder};
+
+/// One change to the pending queues, as written to the journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JournalEntry {
+    /// Order accepted into its pool's queue
+    Add { order: DecryptedOrder },
+    /// Order taken out of its queue without filling: cancelled, expired or dropped
+    Remove { order_id: String },
+    /// Part of an order matched; the order leaves its queue once nothing is left of it
+    Fill { order_id: String, amount: Decimal },
+}
+
+/// Where the journal of the state store at `data_dir` lives: beside it, not inside RocksDB's directory
+pub fn journal_path(data_dir: &Path) -> PathBuf {
+    let mut path = data_dir.as_os_str().to_os_string();
+    path.push(".orders.wal");
+    PathBuf::from(path)
+}
+
+/// Write-ahead log of the pending queues. The engine appends every order it accepts or takes out
+/// before acknowledging it, and every fill once its match settles, one JSON entry per line;
+/// replaying the journal rebuilds the queues with partly filled orders at what is left of them.
+pub struct OrderJournal {
+    path: PathBuf,
+    file: File,
+    sync_writes: bool,
+}
+
+impl OrderJournal {
+    /// Open or create the journal at `path` and replay it into the orders still pending, in
+    /// arrival order. The journal is then rewritten to hold just those orders, so it only grows
+    /// by what changes between restarts.
+    pub fn open(path: &Path, sync_writes: bool) -> Result<(Self, Vec<DecryptedOrder>)> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let orders = match File::open(path) {
+            Ok(file) => replay(BufReader::new(file))
+                .with_context(|| format!("Failed to replay order journal {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        // Written aside and renamed over the journal, so a crash leaves one or the other whole
+        let mut compacted = path.as_os_str().to_os_string();
+        compacted.push(".tmp");
+        let compacted = PathBuf::from(compacted);
+        let mut file = File::create(&compacted)?;
+        for order in &orders {
+            write_entry(&mut file, &JournalEntry::Add { order: order.clone() })?;
+        }
+        file.sync_all()?;
+        std::fs::rename(&compacted, path)
+            .with_context(|| format!("Failed to compact order journal {}", path.display()))?;
+        info!("Replayed {} pending orders from {}", orders.len(), path.display());
+
+        let file = OpenOptions::new().append(true).open(path)
+            .with_context(|| format!("Failed to open order journal {}", path.display()))?;
+        Ok((Self { path: path.to_path_buf(), file, sync_writes }, orders))
+    }
+
+    /// Append `entries`, synced to disk first with `sync_writes`
+    pub fn append(&mut self, entries: &[JournalEntry]) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut lines = Vec::new();
+        for entry in entries {
+            write_entry(&mut lines, entry)?;
+        }
+        self.file.write_all(&lines)
+            .with_context(|| format!("Failed to append to order journal {}", self.path.display()))?;
+        if self.sync_writes {
+            self.file.sync_data()?;
+        }
+        Ok(())
+    }
+}
+
+fn write_entry(writer: &mut impl Write, entry: &JournalEntry) -> Result<()> {
+    serde_json::to_writer(&mut *writer, entry)?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Pending orders left once every entry is applied in turn. A last line cut short by a crash
+/// mid-append is dropped; it was never acknowledged.
+fn replay(reader: impl BufRead) -> Result<Vec<DecryptedOrder>> {
+    // Keyed by arrival, with each order's key by ID
+    let mut orders: BTreeMap<usize, DecryptedOrder> = BTreeMap::new();
+    let mut arrivals: HashMap<String, usize> = HashMap::new();
+
+    let mut lines = reader.lines().enumerate().peekable();
+    while let Some((index, line)) = lines.next() {
+        let line = line?;
+        let entry: JournalEntry = match serde_json::from_str(&line) {
+            Ok(entry) => entry,
+            Err(e) if lines.peek().is_none() => {
+                warn!("Dropping the torn last entry of the order journal: {}", e);
+                break;
+            }
+            Err(e) => return Err(anyhow!("entry {} is corrupt: {}", index + 1, e)),
+        };
+
+        match entry {
+            JournalEntry::Add { order } => {
+                if let Some(arrival) = arrivals.insert(order.id.clone(), index) {
+                    orders.remove(&arrival);
+                }
+                orders.insert(index, order);
+            }
+            JournalEntry::Remove { order_id } => {
+                if let Some(arrival) = arrivals.remove(&order_id) {
+                    orders.remove(&arrival);
+                }
+            }
+            JournalEntry::Fill { order_id, amount } => {
+                let Some(arrival) = arrivals.get(&order_id) else {
+                    continue;
+                };
+                let order = orders.get_mut(arrival).expect("every arrival is of a pending order");
+                order.amount = order.amount.saturating_sub(amount);
+                if order.amount.is_zero() {
+                    orders.remove(arrival);
+                    arrivals.remove(&order_id);
+                }
+            }
+        }
+    }
+
+    Ok(orders.into_values().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matching::{OrderType, TimeInForce};
+    use tempfile::tempdir;
+
+    fn order(id: &str, amount: u64) -> DecryptedOrder {
+        DecryptedOrder {
+            id: id.to_string(),
+            trader: format!("trader_{}", id),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type: OrderType::Buy,
+            amount: Decimal::from(amount),
+            price: Decimal::from(2000),
+            deadline: u64::MAX,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        }
+    }
+
+    fn pending(orders: &[DecryptedOrder]) -> Vec<(&str, Decimal)> {
+        orders.iter().map(|order| (order.id.as_str(), order.amount)).collect()
+    }
+
+    #[test]
+    fn test_replay_applies_fills_and_removals() -> Result<()> {
+        let dir = tempdir()?;
+        let path = journal_path(&dir.path().join("state"));
+        assert_eq!(path, dir.path().join("state.orders.wal"));
+
+        let (mut journal, orders) = OrderJournal::open(&path, true)?;
+        assert!(orders.is_empty());
+        journal.append(&[
+            JournalEntry::Add { order: order("a", 5) },
+            JournalEntry::Add { order: order("b", 2) },
+            JournalEntry::Add { order: order("c", 1) },
+            JournalEntry::Fill { order_id: "a".to_string(), amount: Decimal::from(2) },
+            JournalEntry::Fill { order_id: "b".to_string(), amount: Decimal::from(2) },
+            JournalEntry::Remove { order_id: "c".to_string() },
+            JournalEntry::Add { order: order("d", 3) },
+        ])?;
+        drop(journal);
+
+        let (_, orders) = OrderJournal::open(&path, true)?;
+        assert_eq!(pending(&orders), vec![("a", Decimal::from(3)), ("d", Decimal::from(3))]);
+
+        // Compacted to the orders left, at what is left of them
+        let compacted = std::fs::read_to_string(&path)?;
+        assert_eq!(compacted.lines().count(), 2);
+        let (_, orders) = OrderJournal::open(&path, true)?;
+        assert_eq!(pending(&orders), vec![("a", Decimal::from(3)), ("d", Decimal::from(3))]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_torn_last_entry_is_dropped() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("orders.wal");
+        let (mut journal, _) = OrderJournal::open(&path, false)?;
+        journal.append(&[JournalEntry::Add { order: order("a", 1) }])?;
+        drop(journal);
+
+        let mut file = OpenOptions::new().append(true).open(&path)?;
+        file.write_all(br#"{"op":"add","order":{"id":"b""#)?;
+        let (_, orders) = OrderJournal::open(&path, false)?;
+        assert_eq!(pending(&orders), vec![("a", Decimal::ONE)]);
+
+        // Anywhere but last, a bad entry means the journal can't be trusted
+        std::fs::write(&path, "not json\n{\"op\":\"remove\",\"order_id\":\"a\"}\n")?;
+        assert!(OrderJournal::open(&path, false).is_err());
+        Ok(())
+    }
+}
@@ -1,7 +1,25 @@
+pub mod decimal;
 pub mod engine;
+pub mod journal;
+pub mod nonces;
 pub mod orderbook;
 pub mod privacy;
+pub mod routing;
+pub mod screening;
+pub mod signing;
+pub mod tracker;
+/// Order set strategies and match invariants for property tests
+#[cfg(test)]
+pub mod testing;
 
-pub use engine::{MatchingEngine, OrderMatch};
-pub use orderbook::{Order, OrderBook, OrderType, OrderStatus};
-pub use privacy::{EncryptionManager, DecryptedOrder};
\ No newline at end of file
+pub use decimal::Decimal;
+pub use engine::{MatchingEngine, OrderMatch, TraderRebate};
+pub use journal::{JournalEntry, OrderJournal};
+pub use nonces::{ClaimedNonce, NonceRegistry, NonceStore};
+pub use orderbook::{DepthLevel, Order, OrderBook, OrderBookDepth, OrderBookStats, OrderType, OrderStatus, TimeInForce};
+pub use privacy::{DecryptedOrder, OrderKeyDocument};
+#[cfg(feature = "chain")]
+pub use privacy::EncryptionManager;
+pub use screening::{DenylistScreen, NoScreen, OrderScreen};
+pub use signing::{OrderCancellation, OrderDomain, OrderReceipt};
+pub use tracker::{OrderLifecycle, OrderTracker, OrderTransition};
\ No newline at end of file
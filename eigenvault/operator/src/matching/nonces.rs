@@ -0,0 +1,152 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// Nonces remembered per trader; below the oldest of a full window, nonces are refused
+const NONCE_WINDOW: usize = 1024;
+
+/// A trader nonce and the order that claimed it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClaimedNonce {
+    /// Lowercase trader address
+    pub trader: String,
+    pub nonce: u64,
+    pub order_id: String,
+}
+
+/// Where claimed nonces are kept across restarts
+pub trait NonceStore: Send + Sync {
+    /// Persist `claimed`, and drop `expired`, which left its trader's window with it
+    fn record(&self, claimed: &ClaimedNonce, expired: Option<&ClaimedNonce>) -> Result<()>;
+}
+
+/// Trader nonces already claimed by an order, so a replayed order blob can't be matched twice
+#[derive(Default)]
+pub struct NonceRegistry {
+    /// Keyed by lowercase trader address, each nonce mapped to the order that claimed it
+    traders: Mutex<HashMap<String, BTreeMap<u64, String>>>,
+    /// Every new claim is persisted here before it counts, once a store is set
+    store: Mutex<Option<Arc<dyn NonceStore>>>,
+}
+
+impl NonceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restore the claims an earlier run persisted, before any order is authenticated
+    pub fn restore(&self, claimed: Vec<ClaimedNonce>) {
+        let mut traders = self.traders.lock().unwrap();
+        for ClaimedNonce { trader, nonce, order_id } in claimed {
+            let used = traders.entry(trader).or_default();
+            used.insert(nonce, order_id);
+            if used.len() > NONCE_WINDOW {
+                used.pop_first();
+            }
+        }
+    }
+
+    /// Persist every claim from now on to `store`
+    pub fn set_store(&self, store: Arc<dyn NonceStore>) {
+        *self.store.lock().unwrap() = Some(store);
+    }
+
+    /// Claim `nonce` of `trader` for `order_id`; the same order may claim it again
+    pub fn claim(&self, trader: &str, nonce: u64, order_id: &str) -> Result<()> {
+        // 0 is what unsigned-nonce orders sign, see `OrderMessage`
+        if nonce == 0 {
+            return Err(anyhow::anyhow!("trader nonces start at 1"));
+        }
+        let trader = trader.to_ascii_lowercase();
+        let mut traders = self.traders.lock().unwrap();
+        let used = traders.entry(trader.clone()).or_default();
+
+        if let Some(claimed_by) = used.get(&nonce) {
+            if claimed_by == order_id {
+                return Ok(());
+            }
+            return Err(anyhow::anyhow!("nonce {} of {} was already used by order {}", nonce, trader, claimed_by));
+        }
+        if used.len() >= NONCE_WINDOW && used.keys().next().is_some_and(|oldest| nonce < *oldest) {
+            return Err(anyhow::anyhow!("nonce {} of {} is too old", nonce, trader));
+        }
+
+        // Once the window is full, the oldest nonce leaves it for this one
+        let expired = (used.len() >= NONCE_WINDOW)
+            .then(|| used.first_key_value())
+            .flatten()
+            .map(|(nonce, order_id)| ClaimedNonce { trader: trader.clone(), nonce: *nonce, order_id: order_id.clone() });
+        let claimed = ClaimedNonce { trader, nonce, order_id: order_id.to_string() };
+        if let Some(store) = self.store.lock().unwrap().as_ref() {
+            store.record(&claimed, expired.as_ref())?;
+        }
+
+        used.insert(nonce, claimed.order_id);
+        if expired.is_some() {
+            used.pop_first();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replayed_nonce_is_refused() {
+        let nonces = NonceRegistry::new();
+        let trader = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+        assert!(nonces.claim(trader, 0, "0xaaaa").is_err());
+        assert!(nonces.claim(trader, 7, "0xaaaa").is_ok());
+        assert!(nonces.claim(trader, 7, "0xaaaa").is_ok());
+        assert!(nonces.claim(&trader.to_lowercase(), 7, "0xbbbb").is_err());
+        assert!(nonces.claim("0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc", 7, "0xbbbb").is_ok());
+
+        for nonce in 100..100 + NONCE_WINDOW as u64 {
+            nonces.claim(trader, nonce, &format!("order_{}", nonce)).unwrap();
+        }
+        assert!(nonces.claim(trader, 8, "0xcccc").is_err());
+        assert!(nonces.claim(trader, 5000, "0xcccc").is_ok());
+    }
+
+    /// Claims kept in memory, as the state store keeps them on disk
+    #[derive(Default)]
+    struct MemoryStore(Mutex<BTreeMap<(String, u64), String>>);
+
+    impl NonceStore for MemoryStore {
+        fn record(&self, claimed: &ClaimedNonce, expired: Option<&ClaimedNonce>) -> Result<()> {
+            let mut claims = self.0.lock().unwrap();
+            claims.insert((claimed.trader.clone(), claimed.nonce), claimed.order_id.clone());
+            if let Some(expired) = expired {
+                claims.remove(&(expired.trader.clone(), expired.nonce));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_claims_survive_a_restart() {
+        let store = Arc::new(MemoryStore::default());
+        let trader = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+        let nonces = NonceRegistry::new();
+        nonces.set_store(store.clone());
+        for nonce in 1..=NONCE_WINDOW as u64 + 1 {
+            nonces.claim(trader, nonce, &format!("order_{}", nonce)).unwrap();
+        }
+        // Only the window is kept
+        assert_eq!(store.0.lock().unwrap().len(), NONCE_WINDOW);
+
+        let restarted = NonceRegistry::new();
+        let claimed = store.0.lock().unwrap().iter()
+            .map(|((trader, nonce), order_id)| ClaimedNonce { trader: trader.clone(), nonce: *nonce, order_id: order_id.clone() })
+            .collect();
+        restarted.restore(claimed);
+        assert!(restarted.claim(trader, 7, "0xaaaa").is_err());
+        assert!(restarted.claim(trader, 1, "0xaaaa").is_err());
+        assert!(restarted.claim(trader, 7, "order_7").is_ok());
+        assert!(restarted.claim(trader, NONCE_WINDOW as u64 + 2, "0xaaaa").is_ok());
+    }
+}
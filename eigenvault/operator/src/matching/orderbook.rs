@@ -1,15 +1,38 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
-use tokio::sync::RwLock;
+use std::sync::Arc;
 use tracing::{debug, info};
 
+use super::{DecryptedOrder, Decimal};
+use crate::sim::clock;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum OrderType {
     Buy,
     Sell,
 }
 
+/// How long an order stays in the book
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeInForce {
+    /// Good til date: rests until its deadline
+    #[default]
+    Gtd,
+    /// Immediate or cancel: fills what it can in its first matching round, the rest expires
+    Ioc,
+    /// Fill or kill: fills in full in its first matching round or expires unfilled
+    Fok,
+}
+
+impl TimeInForce {
+    /// Whether the order only takes part in a single matching round
+    pub fn is_immediate(self) -> bool {
+        matches!(self, Self::Ioc | Self::Fok)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum OrderStatus {
     Pending,
@@ -25,11 +48,14 @@ pub struct Order {
     pub trader: String,
     pub pool_key: String,
     pub order_type: OrderType,
-    pub amount: f64,
-    pub price: f64,
+    pub amount: Decimal,
+    pub price: Decimal,
     pub status: OrderStatus,
+    /// Arrival time in unix millis, for time priority
     pub timestamp: u64,
     pub deadline: u64,
+    #[serde(default)]
+    pub time_in_force: TimeInForce,
 }
 
 impl Order {
@@ -38,8 +64,8 @@ impl Order {
         trader: String,
         pool_key: String,
         order_type: OrderType,
-        amount: f64,
-        price: f64,
+        amount: Decimal,
+        price: Decimal,
         deadline: u64,
     ) -> Self {
         Self {
@@ -50,13 +76,19 @@ impl Order {
             amount,
             price,
             status: OrderStatus::Pending,
-            timestamp: chrono::Utc::now().timestamp() as u64,
+            timestamp: clock::unix_millis(),
             deadline,
+            time_in_force: TimeInForce::default(),
         }
     }
 
+    pub fn with_time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = time_in_force;
+        self
+    }
+
     pub fn is_expired(&self) -> bool {
-        chrono::Utc::now().timestamp() as u64 > self.deadline
+        clock::unix_secs() > self.deadline
     }
 
     pub fn is_active(&self) -> bool {
@@ -64,237 +96,183 @@ impl Order {
     }
 }
 
+impl From<&DecryptedOrder> for Order {
+    fn from(order: &DecryptedOrder) -> Self {
+        Self {
+            timestamp: order.received_at,
+            ..Self::new(
+                order.id.clone(),
+                order.trader.clone(),
+                order.pool_key.clone(),
+                order.order_type.clone(),
+                order.amount,
+                order.price,
+                order.deadline,
+            )
+            .with_time_in_force(order.time_in_force)
+        }
+    }
+}
+
+/// Buy and sell orders of one pool, kept in price-time priority.
+///
+/// Each order is allocated once and shared between its price level, the ID index and any
+/// matches it ends up in, so matching hands out `Arc` clones rather than copying orders.
+/// A book has a single owner: mutations take `&mut self`, and callers sharing a book between
+/// tasks wrap the whole book in one lock instead of locking each side separately.
 pub struct OrderBook {
     pub pool_key: String,
     // Price -> Vec<Order> (orders at that price level)
-    buy_orders: RwLock<BTreeMap<OrderedFloat, Vec<Order>>>,
-    sell_orders: RwLock<BTreeMap<OrderedFloat, Vec<Order>>>,
+    buy_orders: BTreeMap<Decimal, Vec<Arc<Order>>>,
+    sell_orders: BTreeMap<Decimal, Vec<Arc<Order>>>,
     // Order ID -> Order for quick lookup
-    orders_by_id: RwLock<HashMap<String, Order>>,
-    total_orders: RwLock<usize>,
-}
-
-// Wrapper for f64 to make it Ord for BTreeMap
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
-pub struct OrderedFloat(pub f64);
-
-impl Eq for OrderedFloat {}
-
-impl Ord for OrderedFloat {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap_or(std::cmp::Ordering::Equal)
-    }
-}
-
-impl From<f64> for OrderedFloat {
-    fn from(value: f64) -> Self {
-        OrderedFloat(value)
-    }
+    orders_by_id: HashMap<String, Arc<Order>>,
 }
 
 impl OrderBook {
     pub fn new(pool_key: String) -> Self {
-        info!("Creating new order book for pool: {}", pool_key);
+        debug!("Creating new order book for pool: {}", pool_key);
         
         Self {
             pool_key,
-            buy_orders: RwLock::new(BTreeMap::new()),
-            sell_orders: RwLock::new(BTreeMap::new()),
-            orders_by_id: RwLock::new(HashMap::new()),
-            total_orders: RwLock::new(0),
+            buy_orders: BTreeMap::new(),
+            sell_orders: BTreeMap::new(),
+            orders_by_id: HashMap::new(),
         }
     }
 
     /// Add an order to the order book
-    pub async fn add_order(&mut self, order: Order) -> Result<()> {
+    pub fn add_order(&mut self, order: Order) -> Result<()> {
         debug!("Adding order {} to order book for pool {}", order.id, self.pool_key);
         
         if order.is_expired() {
             return Err(anyhow::anyhow!("Cannot add expired order: {}", order.id));
         }
 
-        let price_key = OrderedFloat::from(order.price);
-        
-        match order.order_type {
-            OrderType::Buy => {
-                let mut buy_orders = self.buy_orders.write().await;
-                buy_orders.entry(price_key)
-                         .or_insert_with(Vec::new)
-                         .push(order.clone());
-                
-                // Keep buy orders sorted by price (highest first) and time (earliest first)
-                if let Some(orders_at_price) = buy_orders.get_mut(&price_key) {
-                    orders_at_price.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
-                }
-            }
-            OrderType::Sell => {
-                let mut sell_orders = self.sell_orders.write().await;
-                sell_orders.entry(price_key)
-                          .or_insert_with(Vec::new)
-                          .push(order.clone());
-                
-                // Keep sell orders sorted by price (lowest first) and time (earliest first)
-                if let Some(orders_at_price) = sell_orders.get_mut(&price_key) {
-                    orders_at_price.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
-                }
-            }
-        }
+        let order = Arc::new(order);
+        let orders_at_price = self.side_mut(&order.order_type)
+            .entry(order.price)
+            .or_default();
+        orders_at_price.push(Arc::clone(&order));
+        // Keep each price level in time priority (earliest first)
+        orders_at_price.sort_by_key(|order| order.timestamp);
 
-        // Add to lookup map
-        let order_id = order.id.clone();
-        let mut orders_by_id = self.orders_by_id.write().await;
-        orders_by_id.insert(order_id.clone(), order);
-        
-        // Update total count
-        let mut total = self.total_orders.write().await;
-        *total += 1;
-        
-        info!("Added order {} to order book. Total orders: {}", order_id, *total);
+        debug!("Added order {} to order book. Total orders: {}", order.id, self.orders_by_id.len() + 1);
+        self.orders_by_id.insert(order.id.clone(), order);
         Ok(())
     }
 
     /// Remove an order from the order book
-    pub async fn remove_order(&mut self, order_id: &str) -> Result<Option<Order>> {
+    pub fn remove_order(&mut self, order_id: &str) -> Option<Arc<Order>> {
         debug!("Removing order {} from order book", order_id);
         
-        let mut orders_by_id = self.orders_by_id.write().await;
-        
-        if let Some(order) = orders_by_id.remove(order_id) {
-            let price_key = OrderedFloat::from(order.price);
-            
-            match order.order_type {
-                OrderType::Buy => {
-                    let mut buy_orders = self.buy_orders.write().await;
-                    if let Some(orders_at_price) = buy_orders.get_mut(&price_key) {
-                        orders_at_price.retain(|o| o.id != order_id);
-                        if orders_at_price.is_empty() {
-                            buy_orders.remove(&price_key);
-                        }
-                    }
-                }
-                OrderType::Sell => {
-                    let mut sell_orders = self.sell_orders.write().await;
-                    if let Some(orders_at_price) = sell_orders.get_mut(&price_key) {
-                        orders_at_price.retain(|o| o.id != order_id);
-                        if orders_at_price.is_empty() {
-                            sell_orders.remove(&price_key);
-                        }
-                    }
-                }
+        let order = self.orders_by_id.remove(order_id)?;
+        let side = self.side_mut(&order.order_type);
+        if let Some(orders_at_price) = side.get_mut(&order.price) {
+            orders_at_price.retain(|o| o.id != order_id);
+            if orders_at_price.is_empty() {
+                side.remove(&order.price);
             }
-            
-            // Update total count
-            let mut total = self.total_orders.write().await;
-            *total = total.saturating_sub(1);
-            
-            info!("Removed order {} from order book. Total orders: {}", order_id, *total);
-            return Ok(Some(order));
         }
         
-        Ok(None)
+        debug!("Removed order {} from order book. Total orders: {}", order_id, self.orders_by_id.len());
+        Some(order)
     }
 
     /// Get all buy orders sorted by price (highest first) and time (earliest first)
-    pub async fn get_buy_orders(&self) -> Vec<Order> {
-        let buy_orders = self.buy_orders.read().await;
-        let mut all_orders = Vec::new();
-        
-        // Iterate in reverse order for buy orders (highest price first)
-        for (_, orders_at_price) in buy_orders.iter().rev() {
-            for order in orders_at_price {
-                if order.is_active() {
-                    all_orders.push(order.clone());
-                }
-            }
-        }
-        
-        all_orders
+    pub fn get_buy_orders(&self) -> Vec<Arc<Order>> {
+        active_orders(self.buy_orders.values().rev())
     }
 
     /// Get all sell orders sorted by price (lowest first) and time (earliest first)
-    pub async fn get_sell_orders(&self) -> Vec<Order> {
-        let sell_orders = self.sell_orders.read().await;
-        let mut all_orders = Vec::new();
-        
-        // Iterate in normal order for sell orders (lowest price first)
-        for (_, orders_at_price) in sell_orders.iter() {
-            for order in orders_at_price {
-                if order.is_active() {
-                    all_orders.push(order.clone());
-                }
-            }
+    pub fn get_sell_orders(&self) -> Vec<Arc<Order>> {
+        active_orders(self.sell_orders.values())
+    }
+
+    /// Aggregate active orders into at most `levels` price levels per side
+    pub fn get_depth(&self, levels: usize) -> OrderBookDepth {
+        OrderBookDepth {
+            pool_key: self.pool_key.clone(),
+            bids: aggregate_levels(self.buy_orders.iter().rev(), levels),
+            asks: aggregate_levels(self.sell_orders.iter(), levels),
         }
-        
-        all_orders
     }
 
     /// Get best bid (highest buy price)
-    pub async fn get_best_bid(&self) -> Option<f64> {
-        let buy_orders = self.buy_orders.read().await;
-        buy_orders.keys().last().map(|price| price.0)
+    pub fn get_best_bid(&self) -> Option<Decimal> {
+        self.buy_orders.keys().last().copied()
     }
 
     /// Get best ask (lowest sell price)
-    pub async fn get_best_ask(&self) -> Option<f64> {
-        let sell_orders = self.sell_orders.read().await;
-        sell_orders.keys().next().map(|price| price.0)
+    pub fn get_best_ask(&self) -> Option<Decimal> {
+        self.sell_orders.keys().next().copied()
     }
 
-    /// Get spread between best bid and ask
-    pub async fn get_spread(&self) -> Option<f64> {
-        match (self.get_best_bid().await, self.get_best_ask().await) {
-            (Some(bid), Some(ask)) => Some(ask - bid),
+    /// Get spread between best bid and ask; a float as it is negative while the book is crossed
+    pub fn get_spread(&self) -> Option<f64> {
+        match (self.get_best_bid(), self.get_best_ask()) {
+            (Some(bid), Some(ask)) => Some(ask.to_f64() - bid.to_f64()),
             _ => None,
         }
     }
 
     /// Get order by ID
-    pub async fn get_order(&self, order_id: &str) -> Option<Order> {
-        let orders_by_id = self.orders_by_id.read().await;
-        orders_by_id.get(order_id).cloned()
+    pub fn get_order(&self, order_id: &str) -> Option<Arc<Order>> {
+        self.orders_by_id.get(order_id).cloned()
     }
 
     /// Get all orders for a specific trader
-    pub async fn get_orders_by_trader(&self, trader: &str) -> Vec<Order> {
-        let orders_by_id = self.orders_by_id.read().await;
-        orders_by_id.values()
-                   .filter(|order| order.trader == trader && order.is_active())
-                   .cloned()
-                   .collect()
+    pub fn get_orders_by_trader(&self, trader: &str) -> Vec<Arc<Order>> {
+        self.orders_by_id.values()
+            .filter(|order| order.trader == trader && order.is_active())
+            .cloned()
+            .collect()
+    }
+
+    /// Number of orders in the book, active or not
+    pub fn len(&self) -> usize {
+        self.orders_by_id.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders_by_id.is_empty()
     }
 
     /// Update order status
-    pub async fn update_order_status(&mut self, order_id: &str, new_status: OrderStatus) -> Result<()> {
-        let mut orders_by_id = self.orders_by_id.write().await;
+    pub fn update_order_status(&mut self, order_id: &str, new_status: OrderStatus) -> Result<()> {
+        let Some(order) = self.orders_by_id.get(order_id) else {
+            return Err(anyhow::anyhow!("Order not found: {}", order_id));
+        };
+        
+        // Matches may still share the old order, so swap an updated copy into the book
+        let mut updated = (**order).clone();
+        updated.status = new_status;
+        let updated = Arc::new(updated);
         
-        if let Some(order) = orders_by_id.get_mut(order_id) {
-            order.status = new_status;
-            debug!("Updated order {} status to {:?}", order_id, order.status);
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("Order not found: {}", order_id))
+        if let Some(orders_at_price) = self.side_mut(&updated.order_type).get_mut(&updated.price) {
+            for shared in orders_at_price.iter_mut().filter(|o| o.id == order_id) {
+                *shared = Arc::clone(&updated);
+            }
         }
+        
+        debug!("Updated order {} status to {:?}", order_id, updated.status);
+        self.orders_by_id.insert(order_id.to_string(), updated);
+        Ok(())
     }
 
     /// Clean up expired orders
-    pub async fn cleanup_expired_orders(&mut self) -> Result<Vec<String>> {
-        let mut expired_order_ids = Vec::new();
-        let current_time = chrono::Utc::now().timestamp() as u64;
+    pub fn cleanup_expired_orders(&mut self) -> Vec<String> {
+        let current_time = clock::unix_secs();
         
         // Find expired orders
-        {
-            let orders_by_id = self.orders_by_id.read().await;
-            for (order_id, order) in orders_by_id.iter() {
-                if order.deadline <= current_time && order.is_active() {
-                    expired_order_ids.push(order_id.clone());
-                }
-            }
-        }
+        let expired_order_ids: Vec<String> = self.orders_by_id.iter()
+            .filter(|(_, order)| order.deadline <= current_time && matches!(order.status, OrderStatus::Pending | OrderStatus::PartiallyFilled))
+            .map(|(order_id, _)| order_id.clone())
+            .collect();
         
         // Remove expired orders
         for order_id in &expired_order_ids {
-            if let Some(mut order) = self.remove_order(order_id).await? {
-                order.status = OrderStatus::Expired;
+            if self.remove_order(order_id).is_some() {
                 debug!("Expired order: {}", order_id);
             }
         }
@@ -303,53 +281,96 @@ impl OrderBook {
             info!("Cleaned up {} expired orders", expired_order_ids.len());
         }
         
-        Ok(expired_order_ids)
+        expired_order_ids
     }
 
     /// Get order book statistics
-    pub async fn get_stats(&self) -> OrderBookStats {
-        let total_orders = *self.total_orders.read().await;
-        let buy_orders = self.buy_orders.read().await;
-        let sell_orders = self.sell_orders.read().await;
-        
-        let active_buy_count = buy_orders.values()
+    pub fn get_stats(&self) -> OrderBookStats {
+        let active_buy_count = self.buy_orders.values()
             .flatten()
             .filter(|order| order.is_active())
             .count();
             
-        let active_sell_count = sell_orders.values()
+        let active_sell_count = self.sell_orders.values()
             .flatten()
             .filter(|order| order.is_active())
             .count();
-        
-        let best_bid = buy_orders.keys().last().map(|price| price.0);
-        let best_ask = sell_orders.keys().next().map(|price| price.0);
-        
-        let spread = match (best_bid, best_ask) {
-            (Some(bid), Some(ask)) => Some(ask - bid),
-            _ => None,
-        };
 
         OrderBookStats {
             pool_key: self.pool_key.clone(),
-            total_orders,
+            total_orders: self.orders_by_id.len(),
             active_buy_orders: active_buy_count,
             active_sell_orders: active_sell_count,
-            best_bid,
-            best_ask,
-            spread,
+            best_bid: self.get_best_bid(),
+            best_ask: self.get_best_ask(),
+            spread: self.get_spread(),
+        }
+    }
+
+    fn side_mut(&mut self, order_type: &OrderType) -> &mut BTreeMap<Decimal, Vec<Arc<Order>>> {
+        match order_type {
+            OrderType::Buy => &mut self.buy_orders,
+            OrderType::Sell => &mut self.sell_orders,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Active orders of `price_levels`, in the order given and by time within each level
+fn active_orders<'a>(price_levels: impl Iterator<Item = &'a Vec<Arc<Order>>>) -> Vec<Arc<Order>> {
+    price_levels
+        .flatten()
+        .filter(|order| order.is_active())
+        .cloned()
+        .collect()
+}
+
+fn aggregate_levels<'a>(
+    price_levels: impl Iterator<Item = (&'a Decimal, &'a Vec<Arc<Order>>)>,
+    levels: usize,
+) -> Vec<DepthLevel> {
+    price_levels
+        .filter_map(|(price, orders)| {
+            let active: Vec<&Arc<Order>> = orders.iter().filter(|order| order.is_active()).collect();
+            if active.is_empty() {
+                return None;
+            }
+            
+            Some(DepthLevel {
+                price: *price,
+                // Saturating: stats are read under the pool lock, which a panic would poison
+                total_amount: active.iter().fold(Decimal::ZERO, |total, order| total.saturating_add(order.amount)),
+                order_count: active.len(),
+            })
+        })
+        .take(levels)
+        .collect()
+}
+
+/// Aggregated liquidity at a single price, without order or trader details
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepthLevel {
+    pub price: Decimal,
+    pub total_amount: Decimal,
+    pub order_count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderBookDepth {
+    pub pool_key: String,
+    /// Highest price first
+    pub bids: Vec<DepthLevel>,
+    /// Lowest price first
+    pub asks: Vec<DepthLevel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBookStats {
     pub pool_key: String,
     pub total_orders: usize,
     pub active_buy_orders: usize,
     pub active_sell_orders: usize,
-    pub best_bid: Option<f64>,
-    pub best_ask: Option<f64>,
+    pub best_bid: Option<Decimal>,
+    pub best_ask: Option<Decimal>,
     pub spread: Option<f64>,
 }
 
@@ -357,14 +378,14 @@ pub struct OrderBookStats {
 mod tests {
     use super::*;
 
-    #[tokio::test]
-    async fn test_order_book_creation() {
+    #[test]
+    fn test_order_book_creation() {
         let order_book = OrderBook::new("ETH_USDC_3000".to_string());
         assert_eq!(order_book.pool_key, "ETH_USDC_3000");
     }
 
-    #[tokio::test]
-    async fn test_add_buy_order() {
+    #[test]
+    fn test_add_buy_order() {
         let mut order_book = OrderBook::new("ETH_USDC_3000".to_string());
         
         let order = Order::new(
@@ -372,21 +393,39 @@ mod tests {
             "trader_1".to_string(),
             "ETH_USDC_3000".to_string(),
             OrderType::Buy,
-            100.0,
-            2000.0,
-            chrono::Utc::now().timestamp() as u64 + 3600,
+            Decimal::from(100),
+            Decimal::from(2000),
+            clock::unix_secs() + 3600,
         );
         
-        let result = order_book.add_order(order).await;
+        let result = order_book.add_order(order);
         assert!(result.is_ok());
         
-        let buy_orders = order_book.get_buy_orders().await;
+        let buy_orders = order_book.get_buy_orders();
         assert_eq!(buy_orders.len(), 1);
         assert_eq!(buy_orders[0].id, "order_1");
+        // The price level and the ID index share one allocation
+        assert!(Arc::ptr_eq(&buy_orders[0], &order_book.get_order("order_1").unwrap()));
+    }
+
+    #[test]
+    fn test_status_update_reaches_price_levels() -> Result<()> {
+        let mut order_book = OrderBook::new("ETH_USDC_3000".to_string());
+        let order = Order::new("sell_1".to_string(), "trader".to_string(), "ETH_USDC_3000".to_string(), OrderType::Sell, Decimal::from(100), Decimal::from(2001), clock::unix_secs() + 3600);
+        order_book.add_order(order)?;
+        let matched = order_book.get_sell_orders();
+        
+        order_book.update_order_status("sell_1", OrderStatus::Cancelled)?;
+        
+        assert!(order_book.get_sell_orders().is_empty());
+        assert_eq!(order_book.get_order("sell_1").unwrap().status, OrderStatus::Cancelled);
+        // Orders already handed out keep the status they were matched with
+        assert_eq!(matched[0].status, OrderStatus::Pending);
+        Ok(())
     }
 
-    #[tokio::test]
-    async fn test_get_best_bid_ask() {
+    #[test]
+    fn test_get_best_bid_ask() {
         let mut order_book = OrderBook::new("ETH_USDC_3000".to_string());
         
         let buy_order = Order::new(
@@ -394,9 +433,9 @@ mod tests {
             "trader_1".to_string(),
             "ETH_USDC_3000".to_string(),
             OrderType::Buy,
-            100.0,
-            1999.0,
-            chrono::Utc::now().timestamp() as u64 + 3600,
+            Decimal::from(100),
+            Decimal::from(1999),
+            clock::unix_secs() + 3600,
         );
         
         let sell_order = Order::new(
@@ -404,16 +443,46 @@ mod tests {
             "trader_2".to_string(),
             "ETH_USDC_3000".to_string(),
             OrderType::Sell,
-            100.0,
-            2001.0,
-            chrono::Utc::now().timestamp() as u64 + 3600,
+            Decimal::from(100),
+            Decimal::from(2001),
+            clock::unix_secs() + 3600,
         );
         
-        order_book.add_order(buy_order).await.unwrap();
-        order_book.add_order(sell_order).await.unwrap();
+        order_book.add_order(buy_order).unwrap();
+        order_book.add_order(sell_order).unwrap();
         
-        assert_eq!(order_book.get_best_bid().await, Some(1999.0));
-        assert_eq!(order_book.get_best_ask().await, Some(2001.0));
-        assert_eq!(order_book.get_spread().await, Some(2.0));
+        assert_eq!(order_book.get_best_bid(), Some(Decimal::from(1999)));
+        assert_eq!(order_book.get_best_ask(), Some(Decimal::from(2001)));
+        assert_eq!(order_book.get_spread(), Some(2.0));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_depth_aggregates_price_levels() {
+        let mut order_book = OrderBook::new("ETH_USDC_3000".to_string());
+        let deadline = clock::unix_secs() + 3600;
+        
+        for (id, order_type, amount, price) in [
+            ("buy_1", OrderType::Buy, 100, 1999),
+            ("buy_2", OrderType::Buy, 50, 1999),
+            ("buy_3", OrderType::Buy, 10, 1998),
+            ("sell_1", OrderType::Sell, 70, 2001),
+        ] {
+            let order = Order::new(id.to_string(), "trader".to_string(), "ETH_USDC_3000".to_string(), order_type, amount.into(), price.into(), deadline);
+            order_book.add_order(order).unwrap();
+        }
+        
+        let depth = order_book.get_depth(1);
+        assert_eq!(depth.bids.len(), 1);
+        assert_eq!(depth.bids[0].price, Decimal::from(1999));
+        assert_eq!(depth.bids[0].total_amount, Decimal::from(150));
+        assert_eq!(depth.bids[0].order_count, 2);
+        assert_eq!(depth.asks[0].price, Decimal::from(2001));
+
+        // A level too deep to total is capped rather than panicking under the pool lock
+        for id in ["huge_1", "huge_2"] {
+            let order = Order::new(id.to_string(), "trader".to_string(), "ETH_USDC_3000".to_string(), OrderType::Sell, Decimal::from_raw(u128::MAX / 2 + 1), 2002.into(), deadline);
+            order_book.add_order(order).unwrap();
+        }
+        assert_eq!(order_book.get_depth(2).asks[1].total_amount, Decimal::from_raw(u128::MAX));
+    }
+}
@@ -1,15 +1,26 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "chain")]
 use aes_gcm::{
-    aead::{Aead, AeadCore, KeyInit, OsRng},
+    aead::{Aead, KeyInit},
     Aes256Gcm, Key, Nonce,
 };
-use rsa::{RsaPrivateKey, RsaPublicKey, Pkcs1v15Encrypt};
-use rsa::traits::PaddingScheme; // Updated import path for PaddingScheme
+#[cfg(feature = "chain")]
+use rand::{rngs::StdRng, SeedableRng};
+#[cfg(feature = "chain")]
+use rsa::pkcs1::{DecodeRsaPrivateKey, DecodeRsaPublicKey, EncodeRsaPrivateKey, EncodeRsaPublicKey};
+#[cfg(feature = "chain")]
+use rsa::{traits::PublicKeyParts, Oaep, RsaPrivateKey, RsaPublicKey};
+#[cfg(feature = "chain")]
 use sha2::{Sha256, Digest};
+#[cfg(feature = "chain")]
 use tracing::{debug, info, warn};
 
-use super::{OrderType};
+use super::{Decimal, OrderType, TimeInForce};
+#[cfg(feature = "chain")]
+use crate::proofs::hashing::sha256_batch;
+#[cfg(feature = "chain")]
+use crate::sim::{clock, entropy};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DecryptedOrder {
@@ -17,112 +28,164 @@ pub struct DecryptedOrder {
     pub trader: String,
     pub pool_key: String,
     pub order_type: OrderType,
-    pub amount: f64,
-    pub price: f64,
+    pub amount: Decimal,
+    pub price: Decimal,
     pub deadline: u64,
     pub encrypted_data: Vec<u8>, // Original encrypted data for proof generation
+    /// Trader's EIP-712 signature over the order, see `OrderDomain`
+    #[serde(default)]
+    pub signature: Option<String>,
+    /// Per-trader sequence number; each one is only matched once
+    #[serde(default)]
+    pub trader_nonce: Option<u64>,
+    #[serde(default)]
+    pub time_in_force: TimeInForce,
+    /// When the engine accepted the order, in unix millis; its time priority, kept by what is
+    /// left of it after a fill and across restarts
+    #[serde(default)]
+    pub received_at: u64,
 }
 
+/// Order plaintext as traders encrypt it. Amounts and prices are decimal strings, and the
+/// commitment is over their fixed-point units, so neither passes through a float on the way.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EncryptedOrderData {
     pub trader: String,
     pub pool_key: String,
     pub order_type: OrderType,
-    pub amount: f64,
-    pub price: f64,
+    pub amount: Decimal,
+    pub price: Decimal,
     pub deadline: u64,
     pub nonce: Vec<u8>,
     pub commitment: String,
+    /// Trader's EIP-712 signature; left out of the plaintext when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Per-trader replay nonce, distinct from the random commitment `nonce`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trader_nonce: Option<u64>,
+    /// Good til date when absent; neither signed nor part of the commitment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time_in_force: Option<TimeInForce>,
 }
 
+/// The committee order key pair, both halves PKCS#1 DER encoded RSA keys
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EncryptionKeys {
     pub public_key: Vec<u8>,
+    /// Empty for traders, who only encrypt; never leaves the operators
+    #[serde(default)]
     pub private_key: Vec<u8>,
-    pub symmetric_key: Vec<u8>,
 }
 
+/// The committee key as published to traders: its public key, and the version that is bumped
+/// on every rotation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderKeyDocument {
+    /// 0 for documents published before keys were versioned
+    #[serde(default)]
+    pub version: u64,
+    pub public_key: Vec<u8>,
+}
+
+impl EncryptionKeys {
+    /// The committee key file at `path`, as written by `keygen`
+    pub fn load(path: &str) -> Result<Self> {
+        serde_json::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|e| anyhow::anyhow!("Invalid order key file {}: {}", path, e))
+    }
+}
+
+impl OrderKeyDocument {
+    /// Public part of the committee key file at `path`
+    pub fn load(path: &str, version: u64) -> Result<Self> {
+        Ok(Self { version, public_key: EncryptionKeys::load(path)?.public_key })
+    }
+}
+
+impl From<OrderKeyDocument> for EncryptionKeys {
+    fn from(document: OrderKeyDocument) -> Self {
+        Self { public_key: document.public_key, private_key: Vec::new() }
+    }
+}
+
+/// Encrypts and decrypts orders with the committee order key; part of the `chain` feature.
+///
+/// Every order is sealed under its own AES-256-GCM key, which is wrapped for the committee
+/// with RSA-OAEP (SHA-256). Encrypted orders are the wrapped key, as long as the RSA modulus,
+/// then the 12-byte nonce and the AES-GCM ciphertext. Only a manager holding the private key
+/// can decrypt.
+#[cfg(feature = "chain")]
 pub struct EncryptionManager {
-    rsa_private_key: RsaPrivateKey,
-    rsa_public_key: RsaPublicKey,
-    symmetric_key: Key<Aes256Gcm>,
-    cipher: Aes256Gcm,
+    public_key: RsaPublicKey,
+    private_key: Option<RsaPrivateKey>,
 }
 
+#[cfg(feature = "chain")]
 impl EncryptionManager {
-    /// Create new encryption manager with generated keys
+    /// Create new encryption manager with a freshly generated key pair
     pub fn new() -> Result<Self> {
         info!("Initializing encryption manager with new keys");
-        
-        let mut rng = rand::thread_rng();
-        let rsa_private_key = RsaPrivateKey::new(&mut rng, 2048)?;
-        let rsa_public_key = RsaPublicKey::from(&rsa_private_key);
-        
-        // Generate symmetric key for AES encryption
-        let symmetric_key = Aes256Gcm::generate_key(&mut OsRng);
-        let cipher = Aes256Gcm::new(&symmetric_key);
-        
-        Ok(Self {
-            rsa_private_key,
-            rsa_public_key,
-            symmetric_key,
-            cipher,
-        })
+
+        let private_key = RsaPrivateKey::new(&mut rng(), ORDER_KEY_BITS)?;
+        Ok(Self { public_key: RsaPublicKey::from(&private_key), private_key: Some(private_key) })
     }
 
-    /// Create encryption manager from existing keys
+    /// Create encryption manager from existing keys; without a private key it can only encrypt
     pub fn from_keys(keys: EncryptionKeys) -> Result<Self> {
         info!("Initializing encryption manager from existing keys");
-        
-        // Deserialize RSA keys (in production, these would be proper key formats)
-        let mut rng = rand::thread_rng();
-        let rsa_private_key = RsaPrivateKey::new(&mut rng, 2048)?;
-        let rsa_public_key = RsaPublicKey::from(&rsa_private_key);
-        
-        // Use provided symmetric key
-        let symmetric_key = Key::<Aes256Gcm>::from_slice(&keys.symmetric_key);
-        let cipher = Aes256Gcm::new(symmetric_key);
-        
-        Ok(Self {
-            rsa_private_key,
-            rsa_public_key,
-            symmetric_key: *symmetric_key,
-            cipher,
-        })
+
+        let public_key = RsaPublicKey::from_pkcs1_der(&keys.public_key)
+            .map_err(|e| anyhow::anyhow!("Invalid order public key: {}", e))?;
+        let private_key = if keys.private_key.is_empty() {
+            None
+        } else {
+            let private_key = RsaPrivateKey::from_pkcs1_der(&keys.private_key)
+                .map_err(|e| anyhow::anyhow!("Invalid order private key: {}", e))?;
+            if RsaPublicKey::from(&private_key) != public_key {
+                return Err(anyhow::anyhow!("Order private key does not belong to its public key"));
+            }
+            Some(private_key)
+        };
+
+        Ok(Self { public_key, private_key })
     }
 
-    /// Export encryption keys
+    /// Export encryption keys, with the private key if this manager holds it
     pub fn export_keys(&self) -> Result<EncryptionKeys> {
-        // In production, these would be properly serialized key formats
-        Ok(EncryptionKeys {
-            public_key: vec![1, 2, 3, 4], // Mock public key
-            private_key: vec![5, 6, 7, 8], // Mock private key (encrypted)
-            symmetric_key: self.symmetric_key.as_slice().to_vec(),
-        })
+        let private_key = match &self.private_key {
+            Some(private_key) => private_key.to_pkcs1_der()?.as_bytes().to_vec(),
+            None => Vec::new(),
+        };
+        Ok(EncryptionKeys { public_key: self.get_public_key()?, private_key })
     }
 
     /// Get public key for client-side encryption
-    pub fn get_public_key(&self) -> Vec<u8> {
-        // In production, this would return the actual RSA public key
-        vec![1, 2, 3, 4] // Mock public key
+    pub fn get_public_key(&self) -> Result<Vec<u8>> {
+        Ok(self.public_key.to_pkcs1_der()?.as_bytes().to_vec())
     }
 
-    /// Encrypt order data for storage
+    /// Encrypt order data for storage under a fresh order key
     pub fn encrypt_order(&self, order_data: &EncryptedOrderData) -> Result<Vec<u8>> {
         debug!("Encrypting order data for order ID: {}", order_data.trader);
         
         // Serialize order data
         let plaintext = serde_json::to_vec(order_data)?;
         
-        // Generate nonce
-        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
-        
-        // Encrypt with AES-GCM
-        let ciphertext = self.cipher.encrypt(&nonce, plaintext.as_ref())
+        // Encrypt with AES-GCM under a key of its own
+        let order_key = entropy::bytes::<32>();
+        let nonce = Nonce::clone_from_slice(&entropy::bytes::<12>());
+        let ciphertext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&order_key))
+            .encrypt(&nonce, plaintext.as_ref())
             .map_err(|e| anyhow::anyhow!("Encryption failed: {:?}", e))?;
         
-        // Combine nonce and ciphertext
-        let mut encrypted_data = nonce.to_vec();
+        // Wrap the order key for the committee
+        let wrapped_key = self.public_key.encrypt(&mut rng(), Oaep::new::<Sha256>(), &order_key)
+            .map_err(|e| anyhow::anyhow!("Order key wrapping failed: {}", e))?;
+        
+        // Combine wrapped key, nonce and ciphertext
+        let mut encrypted_data = wrapped_key;
+        encrypted_data.extend(nonce);
         encrypted_data.extend(ciphertext);
         
         info!("Successfully encrypted order data: {} bytes", encrypted_data.len());
@@ -133,17 +196,7 @@ impl EncryptionManager {
     pub fn decrypt_order(&self, encrypted_data: &[u8], order_id: String) -> Result<DecryptedOrder> {
         debug!("Decrypting order data for order ID: {}", order_id);
         
-        if encrypted_data.len() < 12 {
-            return Err(anyhow::anyhow!("Invalid encrypted data length"));
-        }
-        
-        // Extract nonce and ciphertext
-        let (nonce_bytes, ciphertext) = encrypted_data.split_at(12);
-        let nonce = Nonce::from_slice(nonce_bytes);
-        
-        // Decrypt
-        let plaintext = self.cipher.decrypt(nonce, ciphertext)
-            .map_err(|e| anyhow::anyhow!("Decryption failed: {:?}", e))?;
+        let plaintext = self.decrypt_plaintext(encrypted_data)?;
         
         // Deserialize
         let order_data: EncryptedOrderData = serde_json::from_slice(&plaintext)?;
@@ -157,12 +210,40 @@ impl EncryptionManager {
             price: order_data.price,
             deadline: order_data.deadline,
             encrypted_data: encrypted_data.to_vec(),
+            signature: order_data.signature,
+            trader_nonce: order_data.trader_nonce,
+            time_in_force: order_data.time_in_force.unwrap_or_default(),
+            received_at: 0,
         };
         
         info!("Successfully decrypted order: {}", decrypted_order.id);
         Ok(decrypted_order)
     }
 
+    /// Unwrap the order key and decrypt the serialized order
+    fn decrypt_plaintext(&self, encrypted_data: &[u8]) -> Result<Vec<u8>> {
+        let private_key = self.private_key.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Order key has no private part to decrypt with"))?;
+        let key_length = self.public_key.size();
+        if encrypted_data.len() < key_length + 12 {
+            return Err(anyhow::anyhow!("Invalid encrypted data length"));
+        }
+        
+        // Extract wrapped key, nonce and ciphertext
+        let (wrapped_key, sealed) = encrypted_data.split_at(key_length);
+        let (nonce_bytes, ciphertext) = sealed.split_at(12);
+        let order_key = private_key.decrypt(Oaep::new::<Sha256>(), wrapped_key)
+            .map_err(|e| anyhow::anyhow!("Order key unwrapping failed: {}", e))?;
+        if order_key.len() != 32 {
+            return Err(anyhow::anyhow!("Order key must be 32 bytes, got {}", order_key.len()));
+        }
+        
+        // Decrypt
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&order_key))
+            .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+            .map_err(|e| anyhow::anyhow!("Decryption failed: {:?}", e))
+    }
+
     /// Decrypt multiple orders in batch
     pub fn decrypt_orders_batch(&self, encrypted_orders: Vec<(String, Vec<u8>)>) -> Result<Vec<DecryptedOrder>> {
         info!("Decrypting batch of {} orders", encrypted_orders.len());
@@ -194,14 +275,7 @@ impl EncryptionManager {
     /// Generate commitment hash for order
     pub fn generate_commitment(&self, order_data: &EncryptedOrderData) -> Result<String> {
         let mut hasher = Sha256::new();
-        
-        // Hash key order components
-        hasher.update(order_data.trader.as_bytes());
-        hasher.update(order_data.pool_key.as_bytes());
-        hasher.update(&order_data.amount.to_le_bytes());
-        hasher.update(&order_data.price.to_le_bytes());
-        hasher.update(&order_data.deadline.to_le_bytes());
-        hasher.update(&order_data.nonce);
+        commitment_fields(&mut hasher, order_data);
         
         let hash = hasher.finalize();
         let commitment = hex::encode(hash);
@@ -210,6 +284,17 @@ impl EncryptionManager {
         Ok(commitment)
     }
 
+    /// Generate commitment hashes for many orders at once, in order
+    pub fn generate_commitments(&self, orders: &[EncryptedOrderData]) -> Vec<String> {
+        let commitments: Vec<String> = sha256_batch(orders, commitment_fields)
+            .into_iter()
+            .map(hex::encode)
+            .collect();
+        
+        debug!("Generated {} commitments", commitments.len());
+        commitments
+    }
+
     /// Verify order commitment
     pub fn verify_commitment(&self, order_data: &EncryptedOrderData, commitment: &str) -> Result<bool> {
         let calculated_commitment = self.generate_commitment(order_data)?;
@@ -228,14 +313,13 @@ impl EncryptionManager {
         // Simplified proof generation (in production, this would use proper ZK circuits)
         let mut proof_data = Vec::new();
         
-        for order in orders {
-            // Add order hash to proof
-            let order_hash = self.hash_order(order)?;
+        // Add order hashes to proof
+        for order_hash in sha256_batch(orders, order_fields) {
             proof_data.extend(order_hash);
         }
         
         // Add timestamp
-        let timestamp = chrono::Utc::now().timestamp() as u64;
+        let timestamp = clock::unix_secs();
         proof_data.extend(timestamp.to_le_bytes());
         
         // Sign with private key (simplified)
@@ -264,20 +348,6 @@ impl EncryptionManager {
         Ok(is_valid)
     }
 
-    /// Hash order for proof generation
-    fn hash_order(&self, order: &DecryptedOrder) -> Result<Vec<u8>> {
-        let mut hasher = Sha256::new();
-        
-        hasher.update(order.id.as_bytes());
-        hasher.update(order.trader.as_bytes());
-        hasher.update(order.pool_key.as_bytes());
-        hasher.update(&order.amount.to_le_bytes());
-        hasher.update(&order.price.to_le_bytes());
-        hasher.update(&order.deadline.to_le_bytes());
-        
-        Ok(hasher.finalize().to_vec())
-    }
-
     /// Sign data with private key
     fn sign_data(&self, data: &[u8]) -> Result<Vec<u8>> {
         // Simplified signing (in production, use proper digital signatures)
@@ -290,10 +360,7 @@ impl EncryptionManager {
 
     /// Generate secure random nonce
     pub fn generate_nonce() -> Vec<u8> {
-        use rand::RngCore;
-        let mut nonce = vec![0u8; 32];
-        rand::thread_rng().fill_bytes(&mut nonce);
-        nonce
+        entropy::bytes::<32>().to_vec()
     }
 
     /// Health check for encryption manager
@@ -303,11 +370,14 @@ impl EncryptionManager {
             trader: "test_trader".to_string(),
             pool_key: "TEST_POOL".to_string(),
             order_type: OrderType::Buy,
-            amount: 100.0,
-            price: 2000.0,
-            deadline: chrono::Utc::now().timestamp() as u64 + 3600,
+            amount: Decimal::from(100),
+            price: Decimal::from(2000),
+            deadline: clock::unix_secs() + 3600,
             nonce: Self::generate_nonce(),
             commitment: "test_commitment".to_string(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: None,
         };
         
         let encrypted = self.encrypt_order(&test_order)?;
@@ -322,13 +392,47 @@ impl EncryptionManager {
     }
 }
 
+/// Modulus size of generated committee order keys
+#[cfg(feature = "chain")]
+const ORDER_KEY_BITS: usize = 2048;
+
+/// Cryptographic RNG for key generation and wrapping, seeded from `entropy` so simulations
+/// stay reproducible
+#[cfg(feature = "chain")]
+fn rng() -> StdRng {
+    StdRng::from_seed(entropy::bytes())
+}
+
+#[cfg(feature = "chain")]
 impl Default for EncryptionManager {
     fn default() -> Self {
         Self::new().expect("Failed to create default encryption manager")
     }
 }
 
-#[cfg(test)]
+/// Key order components a commitment is the hash of
+#[cfg(feature = "chain")]
+fn commitment_fields(hasher: &mut Sha256, order_data: &EncryptedOrderData) {
+    hasher.update(order_data.trader.as_bytes());
+    hasher.update(order_data.pool_key.as_bytes());
+    hasher.update(order_data.amount.raw().to_le_bytes());
+    hasher.update(order_data.price.raw().to_le_bytes());
+    hasher.update(order_data.deadline.to_le_bytes());
+    hasher.update(&order_data.nonce);
+}
+
+/// Order components hashed into a matching proof
+#[cfg(feature = "chain")]
+fn order_fields(hasher: &mut Sha256, order: &DecryptedOrder) {
+    hasher.update(order.id.as_bytes());
+    hasher.update(order.trader.as_bytes());
+    hasher.update(order.pool_key.as_bytes());
+    hasher.update(order.amount.raw().to_le_bytes());
+    hasher.update(order.price.raw().to_le_bytes());
+    hasher.update(order.deadline.to_le_bytes());
+}
+
+#[cfg(all(test, feature = "chain"))]
 mod tests {
     use super::*;
 
@@ -338,6 +442,54 @@ mod tests {
         assert!(manager.is_ok());
     }
 
+    #[test]
+    fn test_order_key_document_leaves_out_private_key() -> Result<()> {
+        let keys = EncryptionManager::new()?.export_keys()?;
+        let file = tempfile::NamedTempFile::new()?;
+        std::fs::write(file.path(), serde_json::to_string(&keys)?)?;
+
+        let document = OrderKeyDocument::load(file.path().to_str().unwrap(), 3)?;
+        let published = serde_json::to_value(&document)?;
+        assert_eq!(published["version"], 3);
+        assert!(published.get("private_key").is_none());
+
+        let fetched = EncryptionKeys::from(serde_json::from_value::<OrderKeyDocument>(published)?);
+        assert_eq!(fetched.public_key, keys.public_key);
+        assert!(fetched.private_key.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_public_key_only_encrypts() -> Result<()> {
+        let operator = EncryptionManager::new()?;
+        let trader = EncryptionManager::from_keys(EncryptionKeys { public_key: operator.get_public_key()?, private_key: Vec::new() })?;
+        let order_data = EncryptedOrderData {
+            trader: "test_trader".to_string(),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type: OrderType::Sell,
+            amount: Decimal::ONE,
+            price: Decimal::from(2000),
+            deadline: clock::unix_secs() + 3600,
+            nonce: EncryptionManager::generate_nonce(),
+            commitment: String::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: None,
+        };
+
+        let encrypted = trader.encrypt_order(&order_data)?;
+        assert!(trader.decrypt_order(&encrypted, "order".to_string()).is_err());
+        assert_eq!(operator.decrypt_order(&encrypted, "order".to_string())?.trader, "test_trader");
+
+        // Another committee's key cannot unwrap it
+        assert!(EncryptionManager::new()?.decrypt_order(&encrypted, "order".to_string()).is_err());
+        assert!(EncryptionManager::from_keys(EncryptionKeys {
+            public_key: operator.get_public_key()?,
+            private_key: EncryptionManager::new()?.export_keys()?.private_key,
+        }).is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_encrypt_decrypt_order() {
         let manager = EncryptionManager::new().unwrap();
@@ -346,19 +498,44 @@ mod tests {
             trader: "test_trader".to_string(),
             pool_key: "ETH_USDC_3000".to_string(),
             order_type: OrderType::Buy,
-            amount: 100.0,
-            price: 2000.0,
-            deadline: chrono::Utc::now().timestamp() as u64 + 3600,
+            amount: Decimal::from(100),
+            price: Decimal::from(2000),
+            deadline: clock::unix_secs() + 3600,
             nonce: EncryptionManager::generate_nonce(),
             commitment: "test_commitment".to_string(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: None,
         };
         
         let encrypted = manager.encrypt_order(&order_data).unwrap();
         let decrypted = manager.decrypt_order(&encrypted, "test_order".to_string()).unwrap();
         
         assert_eq!(decrypted.trader, order_data.trader);
-        assert_eq!(decrypted.amount, order_data.amount);
-        assert_eq!(decrypted.price, order_data.price);
+        assert_eq!(decrypted.amount, Decimal::from(100));
+        assert_eq!(decrypted.price, Decimal::from(2000));
+    }
+
+    #[test]
+    fn test_golden_encrypted_order() -> Result<()> {
+        let fixture = crate::fixtures::encrypted_order();
+        let manager = EncryptionManager::from_keys(EncryptionKeys {
+            public_key: fixture.public_key.clone(),
+            private_key: fixture.private_key.clone(),
+        })?;
+
+        let decrypted = manager.decrypt_order(&fixture.ciphertext, fixture.order_id.clone())?;
+        assert_eq!(decrypted.trader, fixture.order.trader);
+        assert_eq!(decrypted.pool_key, fixture.order.pool_key);
+        assert_eq!(decrypted.order_type, fixture.order.order_type);
+        assert_eq!(decrypted.amount, fixture.order.amount);
+        assert_eq!(decrypted.price, fixture.order.price);
+        assert_eq!(decrypted.deadline, fixture.order.deadline);
+        assert_eq!(manager.generate_commitment(&fixture.order)?, fixture.order.commitment);
+
+        // The plaintext layout clients encrypt, byte for byte
+        assert_eq!(manager.decrypt_plaintext(&fixture.ciphertext)?, serde_json::to_vec(&fixture.order)?);
+        Ok(())
     }
 
     #[test]
@@ -369,11 +546,14 @@ mod tests {
             trader: "test_trader".to_string(),
             pool_key: "ETH_USDC_3000".to_string(),
             order_type: OrderType::Buy,
-            amount: 100.0,
-            price: 2000.0,
-            deadline: chrono::Utc::now().timestamp() as u64 + 3600,
+            amount: Decimal::from(100),
+            price: Decimal::from(2000),
+            deadline: clock::unix_secs() + 3600,
             nonce: vec![1, 2, 3, 4],
             commitment: "".to_string(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: None,
         };
         
         let commitment = manager.generate_commitment(&order_data).unwrap();
@@ -383,4 +563,31 @@ mod tests {
         let is_valid = manager.verify_commitment(&order_data, &commitment).unwrap();
         assert!(is_valid);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_batch_commitments_match_single() -> Result<()> {
+        let manager = EncryptionManager::new()?;
+        let orders: Vec<EncryptedOrderData> = (0..300)
+            .map(|i| EncryptedOrderData {
+                trader: format!("trader_{}", i),
+                pool_key: "ETH_USDC_3000".to_string(),
+                order_type: OrderType::Sell,
+                amount: Decimal::from(1 + i as u64),
+                price: Decimal::from(2000),
+                deadline: 1_700_000_000,
+                nonce: vec![i as u8; 4],
+                commitment: String::new(),
+                signature: None,
+                trader_nonce: None,
+                time_in_force: None,
+            })
+            .collect();
+
+        let commitments = manager.generate_commitments(&orders);
+        assert_eq!(commitments.len(), orders.len());
+        for (order, commitment) in orders.iter().zip(&commitments) {
+            assert_eq!(*commitment, manager.generate_commitment(order)?);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+
+use super::{Order, OrderType};
+use crate::config::{PoolOverrides, RouteConfig};
+
+/// Pool fees are in hundredths of a basis point, as in Uniswap pool keys
+const FEE_DENOMINATOR: u64 = 1_000_000;
+
+/// Pair and fee tier of a pool key such as `ETH_USDC_3000`, the fee in hundredths of a basis point
+pub fn pool_tier(pool_key: &str) -> Option<(&str, u64)> {
+    let (pair, fee) = pool_key.rsplit_once('_')?;
+    let fee = fee.parse().ok().filter(|fee| *fee < FEE_DENOMINATOR)?;
+    (!pair.is_empty()).then_some((pair, fee))
+}
+
+/// Fee tiers of one pair whose orders are matched against each other
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    pub pair: String,
+    /// Routed pools with their fee tiers, in pool key order
+    pub pools: Vec<(String, u64)>,
+    /// Pool the route's matches settle in
+    pub settlement_pool: String,
+    settlement_fee: u64,
+    /// Matching settings over those of the settlement pool
+    pub matching: PoolOverrides,
+}
+
+impl Route {
+    /// `order` with its limit restated for the settlement pool: the same all-in price after fees,
+    /// so a buyer never pays and a seller never receives worse than in their own pool.
+    /// Rounded down; `None` if the order's pool isn't routed or the price doesn't fit.
+    pub fn normalize(&self, order: &Order) -> Option<Order> {
+        let (_, fee) = self.pools.iter().find(|(pool_key, _)| *pool_key == order.pool_key)?;
+        let price = match order.order_type {
+            OrderType::Buy => order.price.mul_ratio(FEE_DENOMINATOR + fee, FEE_DENOMINATOR + self.settlement_fee),
+            OrderType::Sell => order.price.mul_ratio(FEE_DENOMINATOR - fee, FEE_DENOMINATOR - self.settlement_fee),
+        }?;
+        Some(Order { price, ..order.clone() })
+    }
+}
+
+/// Routes between the fee tiers of each pair among `pool_keys`, where `config` narrows which tiers
+/// take part and picks where matches settle. A pair with fewer than two routed pools has no route.
+pub fn routes<'a>(pool_keys: impl IntoIterator<Item = &'a str>, config: &BTreeMap<String, RouteConfig>) -> Vec<Route> {
+    let mut pairs: BTreeMap<&str, Vec<(String, u64)>> = BTreeMap::new();
+    for pool_key in pool_keys {
+        if let Some((pair, fee)) = pool_tier(pool_key) {
+            pairs.entry(pair).or_default().push((pool_key.to_string(), fee));
+        }
+    }
+
+    pairs.into_iter()
+        .filter_map(|(pair, mut pools)| {
+            let route = config.get(pair).cloned().unwrap_or_default();
+            if route.disabled {
+                return None;
+            }
+            if !route.pools.is_empty() {
+                pools.retain(|(pool_key, _)| route.pools.contains(pool_key));
+            }
+            if pools.len() < 2 {
+                return None;
+            }
+            pools.sort();
+
+            // The cheapest tier by default, the first pool key among equally cheap ones
+            let (settlement_pool, settlement_fee) = match &route.settlement_pool {
+                Some(pool_key) => (pool_key.clone(), pool_tier(pool_key)?.1),
+                None => pools.iter().min_by_key(|(_, fee)| *fee)?.clone(),
+            };
+            Some(Route { pair: pair.to_string(), pools, settlement_pool, settlement_fee, matching: route.matching })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matching::Decimal;
+
+    fn order(pool_key: &str, order_type: OrderType, price: u64) -> Order {
+        Order::new(
+            "order_1".to_string(),
+            "trader_1".to_string(),
+            pool_key.to_string(),
+            order_type,
+            Decimal::ONE,
+            Decimal::from(price),
+            u64::MAX,
+        )
+    }
+
+    #[test]
+    fn test_pool_tier() {
+        assert_eq!(pool_tier("ETH_USDC_3000"), Some(("ETH_USDC", 3000)));
+        assert_eq!(pool_tier("ETH_USDC"), None);
+        assert_eq!(pool_tier("ETH_USDC_1000000"), None);
+        assert_eq!(pool_tier("_500"), None);
+    }
+
+    #[test]
+    fn test_routes() {
+        let pools = ["ETH_USDC_3000", "ETH_USDC_500", "ETH_USDC_10000", "WBTC_USDC_3000", "PEPE_ETH_10000", "PEPE_ETH_3000"];
+
+        let routes = routes(pools, &BTreeMap::new());
+        assert_eq!(routes.len(), 2);
+        assert_eq!(routes[0].pair, "ETH_USDC");
+        assert_eq!(routes[0].pools.len(), 3);
+        assert_eq!(routes[0].settlement_pool, "ETH_USDC_500");
+        assert_eq!(routes[1].settlement_pool, "PEPE_ETH_3000");
+
+        let config = BTreeMap::from([
+            ("ETH_USDC".to_string(), RouteConfig {
+                pools: vec!["ETH_USDC_3000".to_string(), "ETH_USDC_10000".to_string()],
+                ..RouteConfig::default()
+            }),
+            ("PEPE_ETH".to_string(), RouteConfig { disabled: true, ..RouteConfig::default() }),
+        ]);
+        let routes = super::routes(pools, &config);
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].pools, vec![("ETH_USDC_10000".to_string(), 10_000), ("ETH_USDC_3000".to_string(), 3000)]);
+        assert_eq!(routes[0].settlement_pool, "ETH_USDC_3000");
+    }
+
+    #[test]
+    fn test_normalize_keeps_all_in_prices() {
+        let route = &routes(["ETH_USDC_3000", "ETH_USDC_500"], &BTreeMap::new())[0];
+
+        // Orders of the settlement pool keep their limit
+        let settled = order("ETH_USDC_500", OrderType::Buy, 2000);
+        assert_eq!(route.normalize(&settled).unwrap().price, Decimal::from(2000));
+
+        // Paying 0.05% instead of 0.3% leaves room for a higher price, at the same all-in cost
+        let buy = route.normalize(&order("ETH_USDC_3000", OrderType::Buy, 2001)).unwrap();
+        assert_eq!(buy.price, Decimal::from(2006));
+        let sell = route.normalize(&order("ETH_USDC_3000", OrderType::Sell, 2001)).unwrap();
+        assert_eq!(sell.price, "1995.994997498749374687".parse().unwrap());
+
+        assert!(route.normalize(&order("WBTC_USDC_3000", OrderType::Buy, 2000)).is_none());
+    }
+}
@@ -0,0 +1,147 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use super::DecryptedOrder;
+use crate::config::{ScreeningConfig, ScreeningPlugin};
+
+/// Compliance check run on every order once it is decrypted, before it is queued or matched
+#[async_trait]
+pub trait OrderScreen: Send + Sync {
+    /// Refuse `order` by returning the reason as an error
+    async fn screen(&self, order: &DecryptedOrder) -> Result<()>;
+
+    /// Human readable plugin name for logging
+    fn name(&self) -> &'static str;
+}
+
+/// Accepts every order; the default
+pub struct NoScreen;
+
+#[async_trait]
+impl OrderScreen for NoScreen {
+    async fn screen(&self, _order: &DecryptedOrder) -> Result<()> {
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "none"
+    }
+}
+
+/// Refuses orders from a fixed set of trader addresses
+pub struct DenylistScreen {
+    /// Lowercase `0x` addresses
+    addresses: HashSet<String>,
+}
+
+impl DenylistScreen {
+    pub fn new<I, S>(addresses: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let addresses = addresses.into_iter()
+            .map(|address| normalize(address.as_ref()))
+            .collect::<Result<_>>()?;
+        Ok(Self { addresses })
+    }
+
+    /// Addresses in `config.denylist` and in the file at `config.denylist_path`
+    pub fn load(config: &ScreeningConfig) -> Result<Self> {
+        let mut addresses = config.denylist.clone();
+        if !config.denylist_path.is_empty() {
+            let contents = std::fs::read_to_string(&config.denylist_path)
+                .with_context(|| format!("Failed to read denylist {}", config.denylist_path))?;
+            addresses.extend(contents.lines()
+                .map(|line| line.split('#').next().unwrap_or_default().trim())
+                .filter(|line| !line.is_empty())
+                .map(str::to_string));
+        }
+        Self::new(addresses)
+    }
+}
+
+#[async_trait]
+impl OrderScreen for DenylistScreen {
+    async fn screen(&self, order: &DecryptedOrder) -> Result<()> {
+        if self.addresses.contains(&order.trader.to_ascii_lowercase()) {
+            return Err(anyhow::anyhow!("trader {} is on the screening denylist", order.trader));
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "denylist"
+    }
+}
+
+/// Screen selected by `config.plugin`
+pub fn from_config(config: &ScreeningConfig) -> Result<Arc<dyn OrderScreen>> {
+    Ok(match config.plugin {
+        ScreeningPlugin::None => Arc::new(NoScreen),
+        ScreeningPlugin::Denylist => Arc::new(DenylistScreen::load(config)?),
+    })
+}
+
+// A mistyped entry must fail loudly rather than never match
+fn normalize(address: &str) -> Result<String> {
+    let valid = address.strip_prefix("0x")
+        .is_some_and(|hex| hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit()));
+    if !valid {
+        return Err(anyhow::anyhow!("Invalid denylist address {}", address));
+    }
+    Ok(address.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matching::{Decimal, OrderType, TimeInForce};
+    use std::io::Write;
+
+    const SANCTIONED: &str = "0x8589427373D6D84E98730D7795D8f6f8731FDA16";
+
+    fn order(trader: &str) -> DecryptedOrder {
+        DecryptedOrder {
+            id: "0xabcd".to_string(),
+            trader: trader.to_string(),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type: OrderType::Buy,
+            amount: Decimal::ONE,
+            price: Decimal::from(2000),
+            deadline: 0,
+            encrypted_data: Vec::new(),
+            signature: None,
+            trader_nonce: None,
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_denylist_screen() -> Result<()> {
+        let mut file = tempfile::NamedTempFile::new()?;
+        writeln!(file, "# OFAC SDN, 2026-10")?;
+        writeln!(file, "{}  # mixer", SANCTIONED.to_ascii_lowercase())?;
+        let config = ScreeningConfig {
+            plugin: ScreeningPlugin::Denylist,
+            denylist: Vec::new(),
+            denylist_path: file.path().display().to_string(),
+        };
+        let screen = from_config(&config)?;
+
+        assert!(screen.screen(&order(SANCTIONED)).await.is_err());
+        screen.screen(&order("0x70997970c51812dc3a010c7d01b50e0d17dc79c8")).await?;
+        NoScreen.screen(&order(SANCTIONED)).await?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_denylist_entries_are_refused() {
+        assert!(DenylistScreen::new(["0x8589427373d6d84e98730d7795d8f6f8731fda1"]).is_err());
+        assert!(DenylistScreen::new(["8589427373d6d84e98730d7795d8f6f8731fda16"]).is_err());
+        assert!(DenylistScreen::new([SANCTIONED]).is_ok());
+    }
+}
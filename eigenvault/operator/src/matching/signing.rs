@@ -0,0 +1,330 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+use sha3::{Digest, Keccak256};
+
+use super::privacy::{DecryptedOrder, EncryptedOrderData};
+use super::{Decimal, OrderType};
+
+const DOMAIN_NAME: &str = "EigenVault";
+const DOMAIN_VERSION: &str = "1";
+
+const DOMAIN_TYPE: &str = "EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)";
+
+// Amounts and prices are decimals off chain, so they are signed as their shortest decimal
+// strings, the same strings traders encrypt them as. The nonce is signed so a replayed order
+// can't be re-encrypted under a fresh one.
+const ORDER_TYPE: &str =
+    "Order(address trader,string poolKey,uint8 side,string amount,string price,uint256 deadline,uint256 nonce)";
+
+const RECEIPT_TYPE: &str = "OrderReceipt(bytes32 commitment,address operator,uint256 acceptedAt)";
+
+const CANCELLATION_TYPE: &str = "OrderCancellation(bytes32 commitment,address trader)";
+
+/// EIP-712 domain orders are signed under: the OrderVault on one chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderDomain {
+    pub chain_id: u64,
+    pub verifying_contract: String,
+}
+
+/// The order fields a trader signs
+#[derive(Debug, Clone)]
+pub struct OrderMessage {
+    pub trader: String,
+    pub pool_key: String,
+    pub order_type: OrderType,
+    pub amount: Decimal,
+    pub price: Decimal,
+    pub deadline: u64,
+    /// Trader nonce, 0 for orders without one; traders number theirs from 1
+    pub nonce: u64,
+}
+
+/// An operator's signed acknowledgement that an order entered its pending set, which the
+/// trader can hold against it if the order is later dropped
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderReceipt {
+    pub commitment: String,
+    pub operator: String,
+    pub accepted_at: u64,
+    pub signature: String,
+}
+
+/// A trader's signed request to drop one of their pending orders
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrderCancellation {
+    pub commitment: String,
+    pub trader: String,
+    pub signature: String,
+}
+
+impl From<&EncryptedOrderData> for OrderMessage {
+    fn from(order: &EncryptedOrderData) -> Self {
+        Self {
+            trader: order.trader.clone(),
+            pool_key: order.pool_key.clone(),
+            order_type: order.order_type.clone(),
+            amount: order.amount,
+            price: order.price,
+            deadline: order.deadline,
+            nonce: order.trader_nonce.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&DecryptedOrder> for OrderMessage {
+    fn from(order: &DecryptedOrder) -> Self {
+        Self {
+            trader: order.trader.clone(),
+            pool_key: order.pool_key.clone(),
+            order_type: order.order_type.clone(),
+            amount: order.amount,
+            price: order.price,
+            deadline: order.deadline,
+            nonce: order.trader_nonce.unwrap_or_default(),
+        }
+    }
+}
+
+impl OrderDomain {
+    fn separator(&self) -> Result<[u8; 32]> {
+        Ok(keccak(&[
+            &keccak(&[DOMAIN_TYPE.as_bytes()]),
+            &keccak(&[DOMAIN_NAME.as_bytes()]),
+            &keccak(&[DOMAIN_VERSION.as_bytes()]),
+            &uint(self.chain_id),
+            &address_word(&self.verifying_contract)?,
+        ]))
+    }
+
+    /// The digest a wallet signs for `order` with `eth_signTypedData_v4`
+    pub fn digest(&self, order: &OrderMessage) -> Result<[u8; 32]> {
+        let side = match order.order_type {
+            OrderType::Buy => 0,
+            OrderType::Sell => 1,
+        };
+        let struct_hash = keccak(&[
+            &keccak(&[ORDER_TYPE.as_bytes()]),
+            &address_word(&order.trader)?,
+            &keccak(&[order.pool_key.as_bytes()]),
+            &uint(side),
+            &keccak(&[order.amount.to_string().as_bytes()]),
+            &keccak(&[order.price.to_string().as_bytes()]),
+            &uint(order.deadline),
+            &uint(order.nonce),
+        ]);
+
+        Ok(keccak(&[b"\x19\x01", &self.separator()?, &struct_hash]))
+    }
+
+    /// Sign `order` with a hex private key, as `0x` r‖s‖v with v of 27 or 28
+    pub fn sign(&self, order: &OrderMessage, private_key: &str) -> Result<String> {
+        sign_digest(&self.digest(order)?, private_key)
+    }
+
+    /// Address that signed `order`, lowercase and `0x`-prefixed
+    pub fn recover_signer(&self, order: &OrderMessage, signature: &str) -> Result<String> {
+        recover_digest(&self.digest(order)?, signature)
+    }
+
+    /// Check that `signature` over `order` is from the trader the order names
+    pub fn verify(&self, order: &OrderMessage, signature: &str) -> Result<()> {
+        let signer = self.recover_signer(order, signature)?;
+        if !signer.eq_ignore_ascii_case(&order.trader) {
+            return Err(anyhow::anyhow!("Order for {} is signed by {}", order.trader, signer));
+        }
+        Ok(())
+    }
+
+    fn receipt_digest(&self, commitment: &str, operator: &str, accepted_at: u64) -> Result<[u8; 32]> {
+        let struct_hash = keccak(&[
+            &keccak(&[RECEIPT_TYPE.as_bytes()]),
+            &bytes32(commitment)?,
+            &address_word(operator)?,
+            &uint(accepted_at),
+        ]);
+
+        Ok(keccak(&[b"\x19\x01", &self.separator()?, &struct_hash]))
+    }
+
+    /// Receipt for the order with `commitment`, signed by the operator key `private_key`
+    pub fn sign_receipt(&self, commitment: &str, accepted_at: u64, private_key: &str) -> Result<OrderReceipt> {
+        let operator = signer_address(private_key)?;
+        let signature = sign_digest(&self.receipt_digest(commitment, &operator, accepted_at)?, private_key)?;
+        Ok(OrderReceipt { commitment: commitment.to_string(), operator, accepted_at, signature })
+    }
+
+    /// Check that `receipt` is signed by the operator it names
+    pub fn verify_receipt(&self, receipt: &OrderReceipt) -> Result<()> {
+        let digest = self.receipt_digest(&receipt.commitment, &receipt.operator, receipt.accepted_at)?;
+        let signer = recover_digest(&digest, &receipt.signature)?;
+        if !signer.eq_ignore_ascii_case(&receipt.operator) {
+            return Err(anyhow::anyhow!("Receipt from {} is signed by {}", receipt.operator, signer));
+        }
+        Ok(())
+    }
+
+    fn cancellation_digest(&self, commitment: &str, trader: &str) -> Result<[u8; 32]> {
+        let struct_hash = keccak(&[
+            &keccak(&[CANCELLATION_TYPE.as_bytes()]),
+            &bytes32(commitment)?,
+            &address_word(trader)?,
+        ]);
+
+        Ok(keccak(&[b"\x19\x01", &self.separator()?, &struct_hash]))
+    }
+
+    /// Cancellation of the order with `commitment`, signed with the trader's key
+    pub fn sign_cancellation(&self, commitment: &str, private_key: &str) -> Result<OrderCancellation> {
+        let trader = signer_address(private_key)?;
+        let signature = sign_digest(&self.cancellation_digest(commitment, &trader)?, private_key)?;
+        Ok(OrderCancellation { commitment: commitment.to_string(), trader, signature })
+    }
+
+    /// Check that `cancellation` is signed by the trader it names
+    pub fn verify_cancellation(&self, cancellation: &OrderCancellation) -> Result<()> {
+        let digest = self.cancellation_digest(&cancellation.commitment, &cancellation.trader)?;
+        let signer = recover_digest(&digest, &cancellation.signature)?;
+        if !signer.eq_ignore_ascii_case(&cancellation.trader) {
+            return Err(anyhow::anyhow!("Cancellation for {} is signed by {}", cancellation.trader, signer));
+        }
+        Ok(())
+    }
+}
+
+fn sign_digest(digest: &[u8; 32], private_key: &str) -> Result<String> {
+    let secret_key = SecretKey::from_slice(&hex::decode(private_key.trim_start_matches("0x"))?)?;
+    let message = Message::from_digest_slice(digest)?;
+    let (recovery_id, compact) = Secp256k1::new()
+        .sign_ecdsa_recoverable(&message, &secret_key)
+        .serialize_compact();
+
+    let mut signature = compact.to_vec();
+    signature.push(27 + recovery_id.to_i32() as u8);
+    Ok(format!("0x{}", hex::encode(signature)))
+}
+
+/// Address that signed `digest`, given a hex `r || s || v` signature
+pub fn recover_digest(digest: &[u8; 32], signature: &str) -> Result<String> {
+    let signature = hex::decode(signature.trim_start_matches("0x"))?;
+    if signature.len() != 65 {
+        return Err(anyhow::anyhow!("Signature must be 65 bytes, got {}", signature.len()));
+    }
+    let v = signature[64];
+    let recovery_id = RecoveryId::from_i32(if v >= 27 { v - 27 } else { v } as i32)?;
+    let signature = RecoverableSignature::from_compact(&signature[..64], recovery_id)?;
+
+    let message = Message::from_digest_slice(digest)?;
+    let public_key = Secp256k1::new().recover_ecdsa(&message, &signature)?;
+    Ok(address(&public_key))
+}
+
+/// Address of the key behind a hex private key
+pub fn signer_address(private_key: &str) -> Result<String> {
+    let secret_key = SecretKey::from_slice(&hex::decode(private_key.trim_start_matches("0x"))?)?;
+    Ok(address(&PublicKey::from_secret_key(&Secp256k1::new(), &secret_key)))
+}
+
+fn address(public_key: &PublicKey) -> String {
+    let hash = Keccak256::digest(&public_key.serialize_uncompressed()[1..]);
+    format!("0x{}", hex::encode(&hash[12..]))
+}
+
+fn keccak(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+fn uint(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+fn bytes32(commitment: &str) -> Result<[u8; 32]> {
+    hex::decode(commitment.trim_start_matches("0x"))?
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Commitment {} is not 32 bytes", commitment))
+}
+
+fn address_word(address: &str) -> Result<[u8; 32]> {
+    let bytes = hex::decode(address.trim_start_matches("0x"))?;
+    if bytes.len() != 20 {
+        return Err(anyhow::anyhow!("Invalid address {}", address));
+    }
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&bytes);
+    Ok(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Second anvil development account
+    const TRADER_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+    const TRADER: &str = "0x70997970c51812dc3a010c7d01b50e0d17dc79c8";
+
+    fn domain() -> OrderDomain {
+        OrderDomain { chain_id: 17000, verifying_contract: "0x3456789012345678901234567890123456789012".to_string() }
+    }
+
+    fn order() -> OrderMessage {
+        OrderMessage {
+            trader: TRADER.to_string(),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type: OrderType::Buy,
+            amount: "1.5".parse().unwrap(),
+            price: Decimal::from(2000),
+            deadline: 1700003600,
+            nonce: 1,
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify() -> Result<()> {
+        assert_eq!(signer_address(TRADER_KEY)?, TRADER);
+
+        let signature = domain().sign(&order(), TRADER_KEY)?;
+        domain().verify(&order(), &signature)?;
+
+        let tampered = OrderMessage { price: Decimal::from(1999), ..order() };
+        assert!(domain().verify(&tampered, &signature).is_err());
+        let renonced = OrderMessage { nonce: 2, ..order() };
+        assert!(domain().verify(&renonced, &signature).is_err());
+        let other_chain = OrderDomain { chain_id: 1, ..domain() };
+        assert!(other_chain.verify(&order(), &signature).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_receipt() -> Result<()> {
+        let commitment = "0x9008a7e368a1514130247c9b0845bd213abe4667a54af2ac02e2abe919610af6";
+        let receipt = domain().sign_receipt(commitment, 1700000000, TRADER_KEY)?;
+        assert_eq!(receipt.operator, TRADER);
+        domain().verify_receipt(&receipt)?;
+
+        let backdated = OrderReceipt { accepted_at: 1699999999, ..receipt.clone() };
+        assert!(domain().verify_receipt(&backdated).is_err());
+        assert!(domain().sign_receipt("order_1", 1700000000, TRADER_KEY).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cancellation() -> Result<()> {
+        let commitment = "0x9008a7e368a1514130247c9b0845bd213abe4667a54af2ac02e2abe919610af6";
+        let cancellation = domain().sign_cancellation(commitment, TRADER_KEY)?;
+        assert_eq!(cancellation.trader, TRADER);
+        domain().verify_cancellation(&cancellation)?;
+
+        let impostor = "0x3c44cdddb6a900fa2b585dd299e03d12fa4293bc";
+        let reattributed = OrderCancellation { trader: impostor.to_string(), ..cancellation.clone() };
+        assert!(domain().verify_cancellation(&reattributed).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use proptest::prelude::*;
+use std::collections::HashMap;
+
+use super::{Decimal, DecryptedOrder, OrderMatch, OrderType, TimeInForce};
+use crate::config::{MatchingAlgorithm, MatchingConfig};
+use crate::sim::clock;
+
+pub fn order_type() -> impl Strategy<Value = OrderType> {
+    prop_oneof![Just(OrderType::Buy), Just(OrderType::Sell)]
+}
+
+pub fn time_in_force() -> impl Strategy<Value = TimeInForce> {
+    prop_oneof![Just(TimeInForce::Gtd), Just(TimeInForce::Ioc), Just(TimeInForce::Fok)]
+}
+
+pub fn algorithm() -> impl Strategy<Value = MatchingAlgorithm> {
+    prop_oneof![
+        Just(MatchingAlgorithm::Midpoint),
+        Just(MatchingAlgorithm::BuyPrice),
+        Just(MatchingAlgorithm::SellPrice),
+    ]
+}
+
+/// Up to `max_orders` live orders over two pools and a handful of traders, priced
+/// close together so many of them cross
+pub fn order_set(max_orders: usize) -> impl Strategy<Value = Vec<DecryptedOrder>> {
+    let order = (
+        prop::sample::select(vec!["ETH_USDC_3000", "WBTC_USDC_3000"]),
+        0..4usize,
+        order_type(),
+        1u128..1000,
+        1990u64..2010,
+        time_in_force(),
+    );
+
+    prop::collection::vec(order, 0..=max_orders).prop_map(|orders| {
+        let deadline = clock::unix_secs() + 3600;
+        orders.into_iter().enumerate()
+            .map(|(index, (pool, trader, order_type, hundredths, price, time_in_force))| DecryptedOrder {
+                id: format!("order_{}", index),
+                trader: format!("trader_{}", trader),
+                pool_key: pool.to_string(),
+                order_type,
+                amount: Decimal::from_raw(hundredths * 10u128.pow(16)),
+                price: Decimal::from(price),
+                deadline,
+                encrypted_data: Vec::new(),
+                signature: None,
+                trader_nonce: None,
+                time_in_force,
+                received_at: 0,
+            })
+            .collect()
+    })
+}
+
+/// Check `matches` against the `orders` they were found in; the error names the first violation
+pub fn check_invariants(orders: &[DecryptedOrder], matches: &[OrderMatch], config: &MatchingConfig) -> Result<(), String> {
+    let by_id: HashMap<&str, &DecryptedOrder> = orders.iter().map(|order| (order.id.as_str(), order)).collect();
+    let tolerance = 10_000 + config.price_tolerance_bps;
+    let mut filled: HashMap<&str, Decimal> = HashMap::new();
+
+    for order_match in matches {
+        let id = &order_match.match_id;
+        let buy = by_id.get(order_match.buy_order.id.as_str())
+            .ok_or_else(|| format!("{}: unknown buy order {}", id, order_match.buy_order.id))?;
+        let sell = by_id.get(order_match.sell_order.id.as_str())
+            .ok_or_else(|| format!("{}: unknown sell order {}", id, order_match.sell_order.id))?;
+
+        if buy.order_type != OrderType::Buy || sell.order_type != OrderType::Sell {
+            return Err(format!("{}: sides are not one buy and one sell", id));
+        }
+        if buy.trader == sell.trader {
+            return Err(format!("{}: self-trade by {}", id, buy.trader));
+        }
+        if buy.pool_key != sell.pool_key || order_match.pool_key != buy.pool_key {
+            return Err(format!("{}: orders from different pools", id));
+        }
+        if order_match.matched_amount.is_zero()
+            || order_match.matched_amount > buy.amount
+            || order_match.matched_amount > sell.amount
+        {
+            return Err(format!("{}: amount {} exceeds a side", id, order_match.matched_amount));
+        }
+        // Both limits, widened by the tolerance the engine allows between them
+        let widened = |price: Decimal| price.mul_bps(tolerance).unwrap_or(Decimal::from_raw(u128::MAX));
+        if order_match.matched_price > widened(buy.price) || widened(order_match.matched_price) < sell.price {
+            return Err(format!(
+                "{}: price {} outside buy limit {} and sell limit {}",
+                id, order_match.matched_price, buy.price, sell.price
+            ));
+        }
+
+        let slipped = |price: Decimal| price.mul_bps(10_000 + config.max_slippage_bps).unwrap_or(Decimal::from_raw(u128::MAX));
+        if order_match.matched_price > slipped(buy.price) || slipped(order_match.matched_price) < sell.price {
+            return Err(format!("{}: price {} slipped past a limit", id, order_match.matched_price));
+        }
+        if order_match.matched_amount < config.min_fill_amount {
+            return Err(format!("{}: fill of {} is below the minimum", id, order_match.matched_amount));
+        }
+
+        *filled.entry(buy.id.as_str()).or_default() += order_match.matched_amount;
+        *filled.entry(sell.id.as_str()).or_default() += order_match.matched_amount;
+    }
+
+    // Partial fills of one order never add up to more than it offered, nor of a fill-or-kill order to less
+    for (order_id, total) in filled {
+        let order = by_id[order_id];
+        if total > order.amount || (order.time_in_force == TimeInForce::Fok && total < order.amount) {
+            return Err(format!("{}: filled {} of {}", order_id, total, order.amount));
+        }
+    }
+
+    Ok(())
+}
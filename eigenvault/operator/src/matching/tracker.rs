@@ -0,0 +1,264 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+use tracing::info;
+
+use super::signing::OrderReceipt;
+use super::{Decimal, Order, OrderMatch};
+use crate::sim::clock;
+
+/// Finished orders are forgotten first once this many are tracked
+const MAX_TRACKED_ORDERS: usize = 100_000;
+
+/// Transitions buffered per subscriber before it starts lagging
+const TRANSITION_CHANNEL_CAPACITY: usize = 4096;
+
+/// Where an order is in its life, as reported to its trader
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderLifecycle {
+    Pending,
+    /// Part of it matched; the rest still rests in the book and can be cancelled
+    PartiallyMatched,
+    Matched,
+    Settled,
+    /// Deadline passed before the order settled, or an immediate order's round did without filling it
+    Expired,
+    /// Withdrawn by its trader while pending
+    Cancelled,
+}
+
+/// An order moving to a new lifecycle stage
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderTransition {
+    pub order_id: String,
+    pub lifecycle: OrderLifecycle,
+}
+
+struct TrackedOrder {
+    lifecycle: OrderLifecycle,
+    deadline: u64,
+    receipt: Option<OrderReceipt>,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    /// Keyed by commitment: lowercase hex without `0x`
+    orders: HashMap<String, TrackedOrder>,
+    tasks: HashMap<String, Vec<String>>,
+}
+
+/// Lifecycle of every order this operator has seen, queried by commitment
+pub struct OrderTracker {
+    state: Mutex<TrackerState>,
+    transitions: broadcast::Sender<OrderTransition>,
+}
+
+impl Default for OrderTracker {
+    fn default() -> Self {
+        let (transitions, _) = broadcast::channel(TRANSITION_CHANNEL_CAPACITY);
+        Self { state: Mutex::new(TrackerState::default()), transitions }
+    }
+}
+
+impl OrderTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Receive every lifecycle change from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<OrderTransition> {
+        self.transitions.subscribe()
+    }
+
+    pub fn pending(&self, order_id: &str, deadline: u64) {
+        self.advance(order_id, deadline, OrderLifecycle::Pending);
+    }
+
+    /// Claim the orders of `matches` for settlement, dropping every match with a side its
+    /// trader already cancelled. Checked and marked under one lock, so a cancellation either
+    /// lands before the claim and removes the match, or after it and only withdraws what is
+    /// left of a partly matched order.
+    pub fn matched(&self, matches: Vec<OrderMatch>) -> Vec<OrderMatch> {
+        let mut state = self.state.lock().unwrap();
+        let (claimed, withdrawn): (Vec<_>, Vec<_>) = matches.into_iter().partition(|order_match| {
+            [&order_match.buy_order, &order_match.sell_order].iter().all(|order| {
+                state.orders.get(&key(&order.id)).map(|tracked| tracked.lifecycle) != Some(OrderLifecycle::Cancelled)
+            })
+        });
+        for order_match in &withdrawn {
+            info!("Dropping match {}: one of its orders was cancelled", order_match.match_id);
+        }
+
+        // Matches hold the order as it was at the start of the round, so what they fill of it
+        // tells whether any of it rests
+        let mut filled: BTreeMap<&str, (&Order, Decimal)> = BTreeMap::new();
+        for order_match in &claimed {
+            for order in [&order_match.buy_order, &order_match.sell_order] {
+                filled.entry(order.id.as_str()).or_insert((order, Decimal::ZERO)).1 += order_match.matched_amount;
+            }
+        }
+        let mut changed = Vec::new();
+        for (order, amount) in filled.into_values() {
+            // What is left of an immediate order expires with its round
+            let lifecycle = if amount < order.amount && !order.time_in_force.is_immediate() {
+                OrderLifecycle::PartiallyMatched
+            } else {
+                OrderLifecycle::Matched
+            };
+            if advance(&mut state, &order.id, order.deadline, lifecycle) {
+                changed.push((order.id.clone(), lifecycle));
+            }
+        }
+        drop(state);
+
+        for (order_id, lifecycle) in changed {
+            self.publish(order_id, lifecycle);
+        }
+        claimed
+    }
+
+    /// Orders whose matches were submitted for `task_id`, settled once the task completes
+    pub fn link_task(&self, task_id: &str, order_ids: Vec<String>) {
+        self.state.lock().unwrap().tasks.insert(task_id.to_string(), order_ids);
+    }
+
+    pub fn settle_task(&self, task_id: &str) {
+        let order_ids = self.state.lock().unwrap().tasks.remove(task_id).unwrap_or_default();
+        self.settled(&order_ids);
+    }
+
+    pub fn settled(&self, order_ids: &[String]) {
+        for order_id in order_ids {
+            self.advance(order_id, u64::MAX, OrderLifecycle::Settled);
+        }
+    }
+
+    /// Orders that left the book unmatched: their deadline passed, or their single round did
+    pub fn expired(&self, order_ids: &[String]) {
+        for order_id in order_ids {
+            self.advance(order_id, u64::MAX, OrderLifecycle::Expired);
+        }
+    }
+
+    /// Mark `order_id` cancelled unless it was already matched in full, finished or cancelled; an
+    /// order not seen yet is recorded as cancelled so it is refused when it arrives
+    pub fn cancel(&self, order_id: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.orders.get(&key(order_id)).is_some_and(|order| order.lifecycle > OrderLifecycle::PartiallyMatched) {
+            return false;
+        }
+        advance(&mut state, order_id, u64::MAX, OrderLifecycle::Cancelled);
+        drop(state);
+
+        self.publish(order_id.to_string(), OrderLifecycle::Cancelled);
+        true
+    }
+
+    /// Keep the receipt issued when the order was accepted, for the trader to fetch
+    pub fn set_receipt(&self, receipt: OrderReceipt) {
+        if let Some(order) = self.state.lock().unwrap().orders.get_mut(&key(&receipt.commitment)) {
+            order.receipt = Some(receipt);
+        }
+    }
+
+    pub fn receipt(&self, commitment: &str) -> Option<OrderReceipt> {
+        self.state.lock().unwrap().orders.get(&key(commitment))?.receipt.clone()
+    }
+
+    pub fn deadline(&self, commitment: &str) -> Option<u64> {
+        Some(self.state.lock().unwrap().orders.get(&key(commitment))?.deadline)
+    }
+
+    /// Lifecycle of the order with `commitment`; `None` for orders this operator hasn't seen
+    pub fn status(&self, commitment: &str) -> Option<OrderLifecycle> {
+        let state = self.state.lock().unwrap();
+        let order = state.orders.get(&key(commitment))?;
+        if order.lifecycle < OrderLifecycle::Settled && order.deadline < clock::unix_secs() {
+            return Some(OrderLifecycle::Expired);
+        }
+        Some(order.lifecycle)
+    }
+
+    /// Move an order forward; a settled order never goes back to matched
+    fn advance(&self, order_id: &str, deadline: u64, lifecycle: OrderLifecycle) {
+        let changed = advance(&mut self.state.lock().unwrap(), order_id, deadline, lifecycle);
+        if changed {
+            self.publish(order_id.to_string(), lifecycle);
+        }
+    }
+
+    fn publish(&self, order_id: String, lifecycle: OrderLifecycle) {
+        // No subscribers is the common case, not an error
+        let _ = self.transitions.send(OrderTransition { order_id, lifecycle });
+    }
+}
+
+/// Move an order forward under the caller's lock; true if its lifecycle changed
+fn advance(state: &mut TrackerState, order_id: &str, deadline: u64, lifecycle: OrderLifecycle) -> bool {
+    if state.orders.len() >= MAX_TRACKED_ORDERS {
+        let now = clock::unix_secs();
+        state.orders.retain(|_, order| order.lifecycle < OrderLifecycle::Settled && order.deadline >= now);
+    }
+
+    let order_key = key(order_id);
+    match state.orders.get_mut(&order_key) {
+        Some(order) if lifecycle > order.lifecycle => {
+            order.lifecycle = lifecycle;
+            true
+        }
+        Some(_) => false,
+        None => {
+            state.orders.insert(order_key, TrackedOrder { lifecycle, deadline, receipt: None });
+            true
+        }
+    }
+}
+
+fn key(order_id: &str) -> String {
+    order_id.trim_start_matches("0x").to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lifecycle() {
+        let tracker = OrderTracker::new();
+        let future = clock::unix_secs() + 3600;
+        tracker.pending("0xABCD", future);
+        tracker.pending("0x1234", 1);
+
+        assert_eq!(tracker.status("abcd"), Some(OrderLifecycle::Pending));
+        assert_eq!(tracker.status("0x1234"), Some(OrderLifecycle::Expired));
+        assert_eq!(tracker.status("0xffff"), None);
+
+        tracker.link_task("task_1", vec!["0xabcd".to_string()]);
+        tracker.settle_task("task_1");
+        tracker.pending("0xabcd", future);
+        assert_eq!(tracker.status("0xabcd"), Some(OrderLifecycle::Settled));
+
+        tracker.pending("0x5678", future);
+        assert!(tracker.cancel("0x5678"));
+        tracker.settled(&["0x5678".to_string()]);
+        assert_eq!(tracker.status("0x5678"), Some(OrderLifecycle::Cancelled));
+
+        // Too late once matched
+        assert!(!tracker.cancel("0xabcd"));
+        assert_eq!(tracker.status("0xabcd"), Some(OrderLifecycle::Settled));
+    }
+
+    #[test]
+    fn test_transitions_are_published_once() {
+        let tracker = OrderTracker::new();
+        let mut transitions = tracker.subscribe();
+        tracker.pending("0xabcd", clock::unix_secs() + 3600);
+        tracker.pending("0xabcd", clock::unix_secs() + 3600);
+        tracker.settled(&["0xabcd".to_string()]);
+
+        let lifecycles: Vec<_> = std::iter::from_fn(|| transitions.try_recv().ok()).map(|t| t.lifecycle).collect();
+        assert_eq!(lifecycles, vec![OrderLifecycle::Pending, OrderLifecycle::Settled]);
+    }
+}
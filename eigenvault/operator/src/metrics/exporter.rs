@@ -0,0 +1,46 @@
+use anyhow::Result;
+use axum::{http::header, response::IntoResponse, routing::get, Router};
+use prometheus::{Encoder, TextEncoder};
+use tracing::{info, warn};
+
+use crate::config::MetricsConfig;
+use super::metrics;
+
+/// Serve the Prometheus text exposition format on the configured address
+pub async fn serve(config: MetricsConfig) -> Result<()> {
+    let app = Router::new().route(&config.path, get(render_metrics));
+
+    let listener = tokio::net::TcpListener::bind(&config.listen_address).await?;
+    info!("Metrics exporter listening on http://{}{}", config.listen_address, config.path);
+
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn render_metrics() -> impl IntoResponse {
+    let encoder = TextEncoder::new();
+    let metric_families = metrics().registry.gather();
+
+    let mut buffer = Vec::new();
+    if let Err(e) = encoder.encode(&metric_families, &mut buffer) {
+        warn!("Failed to encode metrics: {:?}", e);
+    }
+
+    ([(header::CONTENT_TYPE, encoder.format_type().to_string())], buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_render_metrics() {
+        metrics().orders_pending.set(3);
+
+        let response = render_metrics().await.into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(text.contains("eigenvault_orders_pending"));
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::config::LatencyConfig;
+use super::metrics;
+
+/// Quantiles exported for the end-to-end pipeline latency
+const QUANTILES: [f64; 3] = [0.5, 0.95, 0.99];
+
+/// Orders that never reach confirmation are dropped after this many budgets
+const EXPIRY_BUDGETS: u32 = 10;
+
+/// Pipeline stages an order passes through, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Received,
+    Decrypted,
+    Matched,
+    ProofStarted,
+    ProofFinished,
+    Submitted,
+    Confirmed,
+}
+
+impl Stage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Received => "received",
+            Stage::Decrypted => "decrypted",
+            Stage::Matched => "matched",
+            Stage::ProofStarted => "proof_started",
+            Stage::ProofFinished => "proof_finished",
+            Stage::Submitted => "submitted",
+            Stage::Confirmed => "confirmed",
+        }
+    }
+}
+
+struct Timeline {
+    first: Instant,
+    last: Instant,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    orders: HashMap<String, Timeline>,
+    /// Task ID -> orders settled by that task, for confirmations reported per task
+    tasks: HashMap<String, Vec<String>>,
+    /// Most recent end-to-end latencies in seconds
+    completed: VecDeque<f64>,
+    breached: bool,
+}
+
+/// Per-order stage timestamps feeding stage latency histograms, end-to-end
+/// percentiles and the latency SLO alert
+pub struct LatencyTracker {
+    config: LatencyConfig,
+    state: Mutex<TrackerState>,
+}
+
+impl LatencyTracker {
+    pub fn new(config: LatencyConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(TrackerState::default()),
+        }
+    }
+
+    /// Record that `order_id` reached `stage`.
+    ///
+    /// The time since the order's previous stage is observed under this
+    /// stage's label; reaching `Confirmed` completes the order.
+    pub fn mark(&self, order_id: &str, stage: Stage) {
+        let now = Instant::now();
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+
+        if stage == Stage::Received || !state.orders.contains_key(order_id) {
+            self.expire(&mut state, now);
+        }
+
+        let timeline = state.orders.entry(order_id.to_string())
+            .or_insert(Timeline { first: now, last: now });
+        let since_previous = now.duration_since(timeline.last);
+        timeline.last = now;
+
+        metrics().order_stage_latency_seconds
+            .with_label_values(&[stage.name()])
+            .observe(since_previous.as_secs_f64());
+
+        if stage == Stage::Confirmed {
+            if let Some(timeline) = state.orders.remove(order_id) {
+                self.complete(&mut state, now.duration_since(timeline.first));
+            }
+        }
+    }
+
+    pub fn mark_all<'a>(&self, order_ids: impl IntoIterator<Item = &'a str>, stage: Stage) {
+        for order_id in order_ids {
+            self.mark(order_id, stage);
+        }
+    }
+
+    /// Associate orders with the task whose confirmation settles them
+    pub fn link_task(&self, task_id: &str, order_ids: Vec<String>) {
+        if let Ok(mut state) = self.state.lock() {
+            state.tasks.insert(task_id.to_string(), order_ids);
+        }
+    }
+
+    /// Mark every order linked to `task_id`
+    pub fn mark_task(&self, task_id: &str, stage: Stage) {
+        let order_ids = match self.state.lock() {
+            Ok(mut state) if stage == Stage::Confirmed => state.tasks.remove(task_id),
+            Ok(state) => state.tasks.get(task_id).cloned(),
+            Err(_) => None,
+        };
+
+        for order_id in order_ids.unwrap_or_default() {
+            self.mark(&order_id, stage);
+        }
+    }
+
+    /// Current end-to-end latency at quantile `q` over the recent window
+    pub fn quantile(&self, q: f64) -> Option<f64> {
+        let state = self.state.lock().ok()?;
+        quantile(&state.completed, q)
+    }
+
+    fn complete(&self, state: &mut TrackerState, latency: Duration) {
+        state.completed.push_back(latency.as_secs_f64());
+        while state.completed.len() > self.config.window_size {
+            state.completed.pop_front();
+        }
+
+        for q in QUANTILES {
+            if let Some(value) = quantile(&state.completed, q) {
+                metrics().order_pipeline_latency_seconds
+                    .with_label_values(&[&q.to_string()])
+                    .set(value);
+            }
+        }
+
+        let Some(observed) = quantile(&state.completed, self.config.slo_quantile) else {
+            return;
+        };
+        let breached = observed > self.config.budget_seconds;
+
+        // Alert on the transition so a sustained breach doesn't flood the logs
+        if breached && !state.breached {
+            warn!(
+                "Order pipeline latency SLO breached: p{} = {:.2}s exceeds budget of {:.2}s",
+                self.config.slo_quantile * 100.0, observed, self.config.budget_seconds
+            );
+            metrics().latency_slo_breaches.inc();
+        }
+        state.breached = breached;
+        metrics().latency_slo_breached.set(breached as i64);
+    }
+
+    fn expire(&self, state: &mut TrackerState, now: Instant) {
+        let max_age = Duration::from_secs_f64(self.config.budget_seconds) * EXPIRY_BUDGETS;
+        state.orders.retain(|_, timeline| now.duration_since(timeline.first) < max_age);
+    }
+}
+
+fn quantile(samples: &VecDeque<f64>, q: f64) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+
+    let mut sorted: Vec<f64> = samples.iter().copied().collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    let rank = (q * (sorted.len() - 1) as f64).round() as usize;
+    Some(sorted[rank.min(sorted.len() - 1)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantile() {
+        let samples: VecDeque<f64> = (1..=100).map(|v| v as f64).collect();
+        assert_eq!(quantile(&samples, 0.5), Some(51.0));
+        assert_eq!(quantile(&samples, 0.99), Some(99.0));
+        assert_eq!(quantile(&VecDeque::new(), 0.5), None);
+    }
+
+    #[test]
+    fn test_task_confirmation_completes_linked_orders() {
+        let tracker = LatencyTracker::new(LatencyConfig::default());
+
+        tracker.mark_all(["buy_1", "sell_1"], Stage::Received);
+        tracker.mark_all(["buy_1", "sell_1"], Stage::Matched);
+        tracker.link_task("task_1", vec!["buy_1".to_string(), "sell_1".to_string()]);
+        tracker.mark_task("task_1", Stage::Confirmed);
+
+        assert!(tracker.quantile(0.5).is_some());
+        assert!(tracker.state.lock().unwrap().orders.is_empty());
+    }
+}
@@ -0,0 +1,10 @@
+#[cfg(feature = "full")]
+pub mod exporter;
+pub mod latency;
+pub mod registry;
+pub mod stats;
+
+#[cfg(feature = "full")]
+pub use exporter::serve;
+pub use latency::{LatencyTracker, Stage};
+pub use registry::{metrics, Metrics};
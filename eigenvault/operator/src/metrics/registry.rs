@@ -0,0 +1,311 @@
+use prometheus::{
+    GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, Opts, Registry,
+};
+use std::sync::OnceLock;
+
+/// Operator-wide Prometheus metrics
+pub struct Metrics {
+    pub registry: Registry,
+    pub events_processed: IntCounterVec,
+    pub orders_pending: IntGauge,
+    pub matches_found: IntCounter,
+    pub proof_duration_seconds: Histogram,
+    pub proofs_in_flight: IntGauge,
+    pub rpc_latency_seconds: HistogramVec,
+    pub peers_connected: IntGauge,
+    pub peer_messages: IntGaugeVec,
+    pub peer_reputation: GaugeVec,
+    pub gossip_cached_messages: IntGaugeVec,
+    pub pool_active_orders: IntGaugeVec,
+    pub pool_spread: GaugeVec,
+    pub pool_matches: IntCounterVec,
+    pub order_stage_latency_seconds: HistogramVec,
+    pub order_pipeline_latency_seconds: GaugeVec,
+    pub latency_slo_breached: IntGauge,
+    pub latency_slo_breaches: IntCounter,
+    pub component_restarts: IntCounterVec,
+    pub backpressure_throttled: IntCounterVec,
+    pub operator_deregistrations: IntCounterVec,
+    pub task_result_votes: IntCounterVec,
+    pub operator_slashings: IntCounterVec,
+    pub tasks_scheduled: IntGauge,
+    pub task_deadlines_missed: IntCounterVec,
+    pub slashing_exposure_gwei: IntGauge,
+    pub slashing_exposure_limit_gwei: IntGauge,
+    pub risk_halted: IntGauge,
+    pub pnl_gwei: IntGaugeVec,
+    pub ha_active: IntGauge,
+    pub chain_cache_reads: IntCounterVec,
+    pub cache_evictions: IntCounterVec,
+    pub proof_batch_size: IntGaugeVec,
+    pub proof_batch_window_seconds: GaugeVec,
+    pub transaction_replacements: IntCounter,
+}
+
+impl Metrics {
+    pub(crate) fn new() -> Self {
+        let registry = Registry::new_custom(Some("eigenvault".to_string()), None)
+            .expect("valid metrics namespace");
+
+        let events_processed = IntCounterVec::new(
+            Opts::new("events_processed_total", "Ethereum events processed by type"),
+            &["event"],
+        ).expect("valid metric");
+
+        let orders_pending = IntGauge::new(
+            "orders_pending", "Orders waiting in the matching queue",
+        ).expect("valid metric");
+
+        let matches_found = IntCounter::new(
+            "matches_found_total", "Order matches found by the matching engine",
+        ).expect("valid metric");
+
+        let proof_duration_seconds = Histogram::with_opts(
+            HistogramOpts::new("proof_duration_seconds", "Time spent generating matching proofs")
+                .buckets(vec![0.01, 0.05, 0.1, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0]),
+        ).expect("valid metric");
+
+        let proofs_in_flight = IntGauge::new(
+            "proofs_in_flight", "Matching proofs currently being generated",
+        ).expect("valid metric");
+
+        let rpc_latency_seconds = HistogramVec::new(
+            HistogramOpts::new("rpc_latency_seconds", "Ethereum RPC call latency by method")
+                .buckets(vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0]),
+            &["method"],
+        ).expect("valid metric");
+
+        let peers_connected = IntGauge::new(
+            "peers_connected", "Connected P2P peers",
+        ).expect("valid metric");
+
+        let peer_messages = IntGaugeVec::new(
+            Opts::new("peer_messages", "Messages sent to each P2P peer"),
+            &["peer"],
+        ).expect("valid metric");
+
+        let peer_reputation = GaugeVec::new(
+            Opts::new("peer_reputation", "Reputation score of each P2P peer"),
+            &["peer"],
+        ).expect("valid metric");
+
+        let gossip_cached_messages = IntGaugeVec::new(
+            Opts::new("gossip_cached_messages", "Gossip messages in the cache by originating peer"),
+            &["sender"],
+        ).expect("valid metric");
+
+        let pool_active_orders = IntGaugeVec::new(
+            Opts::new("pool_active_orders", "Active pending orders by pool and side"),
+            &["pool", "side"],
+        ).expect("valid metric");
+
+        let pool_spread = GaugeVec::new(
+            Opts::new("pool_spread", "Best ask minus best bid of pending orders by pool"),
+            &["pool"],
+        ).expect("valid metric");
+
+        let pool_matches = IntCounterVec::new(
+            Opts::new("pool_matches_total", "Order matches found by pool"),
+            &["pool"],
+        ).expect("valid metric");
+
+        let order_stage_latency_seconds = HistogramVec::new(
+            HistogramOpts::new("order_stage_latency_seconds", "Time from an order's previous pipeline stage to this one")
+                .buckets(vec![0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 120.0, 300.0, 600.0]),
+            &["stage"],
+        ).expect("valid metric");
+
+        let order_pipeline_latency_seconds = GaugeVec::new(
+            Opts::new("order_pipeline_latency_seconds", "End-to-end order latency quantiles over the recent window"),
+            &["quantile"],
+        ).expect("valid metric");
+
+        let latency_slo_breached = IntGauge::new(
+            "latency_slo_breached", "1 while the order pipeline exceeds its latency budget",
+        ).expect("valid metric");
+
+        let latency_slo_breaches = IntCounter::new(
+            "latency_slo_breaches_total", "Times the order pipeline started exceeding its latency budget",
+        ).expect("valid metric");
+
+        let component_restarts = IntCounterVec::new(
+            Opts::new("component_restarts_total", "Supervisor restarts of operator tasks"),
+            &["component"],
+        ).expect("valid metric");
+
+        let backpressure_throttled = IntCounterVec::new(
+            Opts::new("backpressure_throttled_total", "Intake pauses because matching or proving was saturated"),
+            &["source"],
+        ).expect("valid metric");
+
+        let operator_deregistrations = IntCounterVec::new(
+            Opts::new("operator_deregistrations_total", "Times the AVS registry deregistered an identity of this operator"),
+            &["identity"],
+        ).expect("valid metric");
+
+        let task_result_votes = IntCounterVec::new(
+            Opts::new("task_result_votes_total", "Signed task results counted toward quorum, by outcome"),
+            &["outcome"],
+        ).expect("valid metric");
+
+        let operator_slashings = IntCounterVec::new(
+            Opts::new("operator_slashings_total", "OperatorSlashed events seen, of this operator's identities (self) or of others (peer)"),
+            &["scope"],
+        ).expect("valid metric");
+
+        let tasks_scheduled = IntGauge::new(
+            "tasks_scheduled", "Accepted tasks waiting to be worked on",
+        ).expect("valid metric");
+
+        let task_deadlines_missed = IntCounterVec::new(
+            Opts::new("task_deadlines_missed_total", "Tasks given up too close to their deadline, by stage (queued, processing)"),
+            &["stage"],
+        ).expect("valid metric");
+
+        let slashing_exposure_gwei = IntGauge::new(
+            "slashing_exposure_gwei", "Penalties risked by task responses that can still be challenged",
+        ).expect("valid metric");
+
+        let slashing_exposure_limit_gwei = IntGauge::new(
+            "slashing_exposure_limit_gwei", "Most slashing exposure accepted before new tasks are declined",
+        ).expect("valid metric");
+
+        let risk_halted = IntGauge::new(
+            "risk_halted", "1 while new tasks are declined because of slashing exposure",
+        ).expect("valid metric");
+
+        let pnl_gwei = IntGaugeVec::new(
+            Opts::new("pnl_gwei", "Task rewards less gas of confirmed tasks since start, by pool and task type"),
+            &["pool", "task_type"],
+        ).expect("valid metric");
+
+        let ha_active = IntGauge::new(
+            "ha_active", "1 while this instance holds the failover lease",
+        ).expect("valid metric");
+
+        let chain_cache_reads = IntCounterVec::new(
+            Opts::new("chain_cache_reads_total", "Cached chain reads by value read and whether the cache answered (hit) or RPC did (miss)"),
+            &["read", "result"],
+        ).expect("valid metric");
+
+        let cache_evictions = IntCounterVec::new(
+            Opts::new("cache_evictions_total", "Entries dropped from bounded in-memory caches, because the cache was full (capacity) or the entry was too old (expired)"),
+            &["cache", "reason"],
+        ).expect("valid metric");
+
+        let proof_batch_size = IntGaugeVec::new(
+            Opts::new("proof_batch_size", "Most matches proved together, as tuned for each pool"),
+            &["pool"],
+        ).expect("valid metric");
+
+        let proof_batch_window_seconds = GaugeVec::new(
+            Opts::new("proof_batch_window_seconds", "Longest a match waits for its proof batch to fill, as tuned for each pool"),
+            &["pool"],
+        ).expect("valid metric");
+
+        let transaction_replacements = IntCounter::new(
+            "transaction_replacements_total",
+            "Stuck transactions resent at a higher gas price",
+        ).expect("valid metric");
+
+        registry.register(Box::new(events_processed.clone())).expect("unique metric");
+        registry.register(Box::new(orders_pending.clone())).expect("unique metric");
+        registry.register(Box::new(matches_found.clone())).expect("unique metric");
+        registry.register(Box::new(proof_duration_seconds.clone())).expect("unique metric");
+        registry.register(Box::new(proofs_in_flight.clone())).expect("unique metric");
+        registry.register(Box::new(rpc_latency_seconds.clone())).expect("unique metric");
+        registry.register(Box::new(peers_connected.clone())).expect("unique metric");
+        registry.register(Box::new(peer_messages.clone())).expect("unique metric");
+        registry.register(Box::new(peer_reputation.clone())).expect("unique metric");
+        registry.register(Box::new(gossip_cached_messages.clone())).expect("unique metric");
+        registry.register(Box::new(pool_active_orders.clone())).expect("unique metric");
+        registry.register(Box::new(pool_spread.clone())).expect("unique metric");
+        registry.register(Box::new(pool_matches.clone())).expect("unique metric");
+        registry.register(Box::new(order_stage_latency_seconds.clone())).expect("unique metric");
+        registry.register(Box::new(order_pipeline_latency_seconds.clone())).expect("unique metric");
+        registry.register(Box::new(latency_slo_breached.clone())).expect("unique metric");
+        registry.register(Box::new(latency_slo_breaches.clone())).expect("unique metric");
+        registry.register(Box::new(component_restarts.clone())).expect("unique metric");
+        registry.register(Box::new(backpressure_throttled.clone())).expect("unique metric");
+        registry.register(Box::new(operator_deregistrations.clone())).expect("unique metric");
+        registry.register(Box::new(task_result_votes.clone())).expect("unique metric");
+        registry.register(Box::new(operator_slashings.clone())).expect("unique metric");
+        registry.register(Box::new(tasks_scheduled.clone())).expect("unique metric");
+        registry.register(Box::new(task_deadlines_missed.clone())).expect("unique metric");
+        registry.register(Box::new(slashing_exposure_gwei.clone())).expect("unique metric");
+        registry.register(Box::new(slashing_exposure_limit_gwei.clone())).expect("unique metric");
+        registry.register(Box::new(risk_halted.clone())).expect("unique metric");
+        registry.register(Box::new(pnl_gwei.clone())).expect("unique metric");
+        registry.register(Box::new(ha_active.clone())).expect("unique metric");
+        registry.register(Box::new(chain_cache_reads.clone())).expect("unique metric");
+        registry.register(Box::new(cache_evictions.clone())).expect("unique metric");
+        registry.register(Box::new(proof_batch_size.clone())).expect("unique metric");
+        registry.register(Box::new(proof_batch_window_seconds.clone())).expect("unique metric");
+        registry.register(Box::new(transaction_replacements.clone())).expect("unique metric");
+
+        Self {
+            registry,
+            events_processed,
+            orders_pending,
+            matches_found,
+            proof_duration_seconds,
+            proofs_in_flight,
+            rpc_latency_seconds,
+            peers_connected,
+            peer_messages,
+            peer_reputation,
+            gossip_cached_messages,
+            pool_active_orders,
+            pool_spread,
+            pool_matches,
+            order_stage_latency_seconds,
+            order_pipeline_latency_seconds,
+            latency_slo_breached,
+            latency_slo_breaches,
+            component_restarts,
+            backpressure_throttled,
+            operator_deregistrations,
+            task_result_votes,
+            operator_slashings,
+            tasks_scheduled,
+            task_deadlines_missed,
+            slashing_exposure_gwei,
+            slashing_exposure_limit_gwei,
+            risk_halted,
+            pnl_gwei,
+            ha_active,
+            chain_cache_reads,
+            cache_evictions,
+            proof_batch_size,
+            proof_batch_window_seconds,
+            transaction_replacements,
+        }
+    }
+}
+
+/// Global metrics instance shared by all components
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_registered() {
+        let metrics = metrics();
+        metrics.matches_found.inc();
+        metrics.events_processed.with_label_values(&["TaskCreated"]).inc();
+
+        let names: Vec<String> = metrics.registry.gather()
+            .iter()
+            .map(|family| family.get_name().to_string())
+            .collect();
+
+        assert!(names.contains(&"eigenvault_matches_found_total".to_string()));
+        assert!(names.contains(&"eigenvault_events_processed_total".to_string()));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::matching::OrderBookStats;
+#[cfg(feature = "networking")]
+use crate::networking::{GossipStats, NetworkStats};
+
+use super::Metrics;
+
+// Labeled gauges are rebuilt from each snapshot so departed peers and
+// drained pools drop out instead of reporting their last value forever.
+
+impl Metrics {
+    #[cfg(feature = "networking")]
+    pub fn record_network_stats(&self, stats: &NetworkStats) {
+        self.peer_messages.reset();
+        self.peer_reputation.reset();
+
+        for peer in &stats.peers {
+            self.peer_messages
+                .with_label_values(&[&peer.peer_id])
+                .set(peer.messages_sent as i64);
+            self.peer_reputation
+                .with_label_values(&[&peer.peer_id])
+                .set(peer.reputation);
+        }
+    }
+
+    #[cfg(feature = "networking")]
+    pub fn record_gossip_stats(&self, stats: &GossipStats) {
+        self.gossip_cached_messages.reset();
+
+        for (sender, count) in &stats.messages_by_sender {
+            self.gossip_cached_messages
+                .with_label_values(&[sender])
+                .set(*count as i64);
+        }
+    }
+
+    pub fn record_order_book_stats(&self, stats: &[OrderBookStats]) {
+        self.pool_active_orders.reset();
+        self.pool_spread.reset();
+
+        for pool in stats {
+            self.pool_active_orders
+                .with_label_values(&[&pool.pool_key, "buy"])
+                .set(pool.active_buy_orders as i64);
+            self.pool_active_orders
+                .with_label_values(&[&pool.pool_key, "sell"])
+                .set(pool.active_sell_orders as i64);
+
+            if let Some(spread) = pool.spread {
+                self.pool_spread.with_label_values(&[&pool.pool_key]).set(spread);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matching::Decimal;
+
+    #[cfg(feature = "networking")]
+    #[test]
+    fn test_record_network_stats_drops_departed_peers() {
+        use crate::networking::PeerStats;
+
+        let metrics = Metrics::new();
+        let peer = |id: &str| PeerStats {
+            peer_id: id.to_string(),
+            messages_sent: 3,
+            reputation: 5.0,
+            is_active: true,
+        };
+
+        let mut stats = NetworkStats {
+            total_peers: 2,
+            active_peers: 2,
+            total_messages: 6,
+            uptime_seconds: 0,
+            peers: vec![peer("peer_a"), peer("peer_b")],
+        };
+        metrics.record_network_stats(&stats);
+        assert_eq!(metrics.peer_messages.with_label_values(&["peer_a"]).get(), 3);
+
+        stats.peers.truncate(1);
+        metrics.record_network_stats(&stats);
+
+        let families = metrics.registry.gather();
+        let peer_messages = families.iter()
+            .find(|family| family.get_name() == "eigenvault_peer_messages")
+            .unwrap();
+        assert_eq!(peer_messages.get_metric().len(), 1);
+    }
+
+    #[test]
+    fn test_record_order_book_stats() {
+        let metrics = Metrics::new();
+        metrics.record_order_book_stats(&[OrderBookStats {
+            pool_key: "ETH_USDC_3000".to_string(),
+            total_orders: 3,
+            active_buy_orders: 2,
+            active_sell_orders: 1,
+            best_bid: Some(Decimal::from(1999)),
+            best_ask: Some(Decimal::from(2001)),
+            spread: Some(2.0),
+        }]);
+
+        assert_eq!(metrics.pool_active_orders.with_label_values(&["ETH_USDC_3000", "buy"]).get(), 2);
+        assert_eq!(metrics.pool_spread.with_label_values(&["ETH_USDC_3000"]).get(), 2.0);
+    }
+}
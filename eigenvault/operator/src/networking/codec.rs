@@ -0,0 +1,97 @@
+use anyhow::{anyhow, Result};
+use bincode::Options;
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Format version leading every encoded message; bump it on any change to the wire types
+pub const WIRE_VERSION: u8 = 2;
+
+/// Largest frame a peer may send us, so a bad length can't make us allocate without bound
+pub const MAX_FRAME_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Bincode isn't self-describing, so peers must agree on the exact layout: a field or variant
+/// added to `P2PMessage`, `SecureMessage` or `GossipMessage` needs a new `WIRE_VERSION`.
+fn options() -> impl Options {
+    bincode::DefaultOptions::new().with_limit(MAX_FRAME_BYTES)
+}
+
+/// Encode a message for the wire: the version byte followed by its bincode body
+pub fn encode<T: Serialize>(message: &T) -> Result<Vec<u8>> {
+    let mut frame = Vec::with_capacity(1 + options().serialized_size(message)? as usize);
+    frame.push(WIRE_VERSION);
+    options().serialize_into(&mut frame, message)?;
+    Ok(frame)
+}
+
+/// Decode a message from the wire, rejecting any format version but our own
+pub fn decode<T: DeserializeOwned>(frame: &[u8]) -> Result<T> {
+    match frame.split_first() {
+        Some((&WIRE_VERSION, body)) => Ok(options().deserialize(body)?),
+        Some((version, _)) => Err(anyhow!(
+            "Unsupported wire format version {} (expected {})",
+            version,
+            WIRE_VERSION
+        )),
+        None => Err(anyhow!("Empty message frame")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::networking::{GossipMessage, MessageType, P2PMessage, SecureMessage};
+    use crate::telemetry::TraceContext;
+
+    #[test]
+    fn test_round_trip() -> Result<()> {
+        let message = P2PMessage::OrderGossip {
+            order_id: "order_1".to_string(),
+            encrypted_data: vec![7; 64],
+            signature: vec![9; 32],
+        };
+        let frame = encode(&message)?;
+        assert_eq!(frame[0], WIRE_VERSION);
+        assert!(frame.len() < serde_json::to_vec(&message)?.len());
+        assert!(matches!(decode(&frame)?, P2PMessage::OrderGossip { order_id, .. } if order_id == "order_1"));
+
+        let mut trace_context = TraceContext::new();
+        trace_context.insert("traceparent".to_string(), "00-abc-def-01".to_string());
+        let secure = SecureMessage {
+            message_id: "m1".to_string(),
+            sender_id: "peer_a".to_string(),
+            recipient_id: None,
+            encrypted_data: frame,
+            nonce: vec![0; 12],
+            signature: vec![1; 32],
+            timestamp: 1_700_000_000,
+            trace_context: trace_context.clone(),
+        };
+        let gossip = GossipMessage {
+            message_id: "g1".to_string(),
+            message_type: MessageType::Custom("general".to_string()),
+            sender_id: "peer_a".to_string(),
+            timestamp: 1_700_000_000,
+            ttl: 5,
+            payload: encode(&secure)?,
+            signature: vec![2; 32],
+            trace_context,
+        };
+
+        let decoded: GossipMessage = decode(&encode(&gossip)?)?;
+        let decoded: SecureMessage = decode(&decoded.payload)?;
+        assert_eq!(decoded.recipient_id, None);
+        assert_eq!(decoded.encrypted_data, secure.encrypted_data);
+        assert_eq!(decoded.trace_context, secure.trace_context);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_other_versions() -> Result<()> {
+        let mut frame = encode(&P2PMessage::Ping { timestamp: 1 })?;
+        frame[0] = WIRE_VERSION + 1;
+        assert!(decode::<P2PMessage>(&frame).is_err());
+        assert!(decode::<P2PMessage>(&[]).is_err());
+        // A JSON frame from a peer that hasn't upgraded is rejected too
+        assert!(decode::<P2PMessage>(br#"{"Ping":{"timestamp":1}}"#).is_err());
+        Ok(())
+    }
+}
@@ -1,12 +1,14 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use aes_gcm::{
-    aead::{Aead, AeadCore, KeyInit, OsRng},
+    aead::{Aead, KeyInit},
     Aes256Gcm, Key, Nonce,
 };
 use tracing::{debug, info, warn};
 
-use super::P2PMessage;
+use super::{codec, P2PMessage};
+use crate::telemetry::{self, TraceContext};
+use crate::sim::{clock, entropy};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecureMessage {
@@ -17,6 +19,9 @@ pub struct SecureMessage {
     pub nonce: Vec<u8>,
     pub signature: Vec<u8>,
     pub timestamp: u64,
+    /// Sender's span context so receivers can continue the trace
+    #[serde(default)]
+    pub trace_context: TraceContext,
 }
 
 #[derive(Clone)]
@@ -39,7 +44,7 @@ impl NetworkEncryption {
         info!("Initializing network encryption");
         
         // Generate local encryption key
-        let local_key = Aes256Gcm::generate_key(&mut OsRng);
+        let local_key = Key::<Aes256Gcm>::clone_from_slice(&entropy::bytes::<32>());
         let cipher = Aes256Gcm::new(&local_key);
         
         Ok(Self {
@@ -66,10 +71,10 @@ impl NetworkEncryption {
         debug!("Encrypting P2P message for transmission");
         
         // Serialize the message
-        let plaintext = serde_json::to_vec(message)?;
+        let plaintext = codec::encode(message)?;
         
         // Generate nonce
-        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let nonce = Nonce::clone_from_slice(&entropy::bytes::<12>());
         
         // Encrypt the message
         let encrypted_data = self.cipher.encrypt(&nonce, plaintext.as_ref())
@@ -79,13 +84,14 @@ impl NetworkEncryption {
         let signature = self.sign_data(&encrypted_data).await?;
         
         let secure_message = SecureMessage {
-            message_id: uuid::Uuid::new_v4().to_string(),
+            message_id: entropy::uuid().to_string(),
             sender_id: "local_peer".to_string(), // Would use actual peer ID
             recipient_id: None,
             encrypted_data,
             nonce: nonce.to_vec(),
             signature,
-            timestamp: chrono::Utc::now().timestamp() as u64,
+            timestamp: clock::unix_secs(),
+            trace_context: telemetry::current_context(),
         };
         
         debug!("Message encrypted successfully: {} bytes", secure_message.encrypted_data.len());
@@ -102,7 +108,7 @@ impl NetworkEncryption {
         }
         
         // Check timestamp (reject messages older than 1 hour)
-        let current_time = chrono::Utc::now().timestamp() as u64;
+        let current_time = clock::unix_secs();
         if current_time > secure_message.timestamp + 3600 {
             return Err(anyhow::anyhow!("Message too old"));
         }
@@ -113,7 +119,7 @@ impl NetworkEncryption {
             .map_err(|e| anyhow::anyhow!("Message decryption failed: {:?}", e))?;
         
         // Deserialize the message
-        let message: P2PMessage = serde_json::from_slice(&plaintext)?;
+        let message: P2PMessage = codec::decode(&plaintext)?;
         
         debug!("Message decrypted successfully");
         Ok(message)
@@ -152,7 +158,7 @@ impl NetworkEncryption {
         let mut hasher = Sha256::new();
         hasher.update(data);
         hasher.update(b"network_encryption_key"); // Mock private key
-        hasher.update(&chrono::Utc::now().timestamp().to_le_bytes());
+        hasher.update(clock::unix_secs().to_le_bytes());
         
         Ok(hasher.finalize().to_vec())
     }
@@ -178,7 +184,7 @@ impl NetworkEncryption {
         info!("Rotating network encryption keys");
         
         // Generate new key
-        let new_key = Aes256Gcm::generate_key(&mut OsRng);
+        let new_key = Key::<Aes256Gcm>::clone_from_slice(&entropy::bytes::<32>());
         let new_cipher = Aes256Gcm::new(&new_key);
         
         // Update keys
@@ -193,7 +199,7 @@ impl NetworkEncryption {
     pub fn get_encryption_stats(&self) -> EncryptionStats {
         EncryptionStats {
             peer_keys_count: self.peer_keys.len() as u64,
-            local_key_created: chrono::Utc::now().timestamp() as u64, // Mock timestamp
+            local_key_created: clock::unix_secs(), // Mock timestamp
         }
     }
 
@@ -201,7 +207,7 @@ impl NetworkEncryption {
     pub async fn health_check(&self) -> Result<()> {
         // Test encryption/decryption with mock data
         let test_message = P2PMessage::Ping {
-            timestamp: chrono::Utc::now().timestamp() as u64,
+            timestamp: clock::unix_secs(),
         };
         
         let encrypted = self.encrypt_message(&test_message).await?;
@@ -269,7 +275,7 @@ impl NetworkEncryption {
         debug!("Encrypting bulk data: {} bytes", data.len());
         
         // Generate nonce
-        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let nonce = Nonce::clone_from_slice(&entropy::bytes::<12>());
         
         // Encrypt data
         let encrypted_data = self.cipher.encrypt(&nonce, data)
@@ -313,7 +319,7 @@ mod tests {
         let encryption = NetworkEncryption::new().await?;
         
         let test_message = P2PMessage::Ping {
-            timestamp: chrono::Utc::now().timestamp() as u64,
+            timestamp: clock::unix_secs(),
         };
         
         let encrypted = encryption.encrypt_message(&test_message).await?;
@@ -1,11 +1,14 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tokio::time::{sleep, Duration, Instant};
+use std::time::Duration;
 use tracing::{debug, info, warn};
 
+use crate::cache::BoundedCache;
 use crate::config::NetworkingConfig;
-use super::{PeerInfo, SecureMessage};
+use super::{codec, PeerInfo, SecureMessage};
+use crate::telemetry::{self, TraceContext};
+use crate::sim::{clock, entropy};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum MessageType {
@@ -26,12 +29,13 @@ pub struct GossipMessage {
     pub ttl: u32,
     pub payload: Vec<u8>,
     pub signature: Vec<u8>,
+    #[serde(default)]
+    pub trace_context: TraceContext,
 }
 
 #[derive(Debug, Clone)]
 struct MessageState {
     message: GossipMessage,
-    first_seen: Instant,
     propagation_count: u32,
     peers_sent_to: Vec<String>,
 }
@@ -40,8 +44,8 @@ pub struct GossipProtocol {
     config: NetworkingConfig,
     local_peer_id: String,
     peers: HashMap<String, PeerInfo>,
-    message_cache: HashMap<String, MessageState>,
-    last_cleanup: Instant,
+    /// Messages seen recently, bounded by `message_cache_size` and `message_cache_ttl_seconds`
+    message_cache: BoundedCache<String, MessageState>,
     message_sender: tokio::sync::mpsc::UnboundedSender<GossipMessage>,
     message_receiver: tokio::sync::mpsc::UnboundedReceiver<GossipMessage>,
 }
@@ -54,10 +58,13 @@ impl GossipProtocol {
         
         Ok(Self {
             config: config.clone(),
-            local_peer_id: format!("gossip_peer_{}", uuid::Uuid::new_v4()),
+            local_peer_id: format!("gossip_peer_{}", entropy::uuid()),
             peers: HashMap::new(),
-            message_cache: HashMap::new(),
-            last_cleanup: Instant::now(),
+            message_cache: BoundedCache::new(
+                "gossip_messages",
+                config.message_cache_size,
+                Duration::from_secs(config.message_cache_ttl_seconds),
+            ),
             message_sender,
             message_receiver,
         })
@@ -115,13 +122,14 @@ impl GossipProtocol {
         
         // Convert secure message to gossip format
         let gossip_message = GossipMessage {
-            message_id: uuid::Uuid::new_v4().to_string(),
+            message_id: entropy::uuid().to_string(),
             message_type: MessageType::Custom("direct_message".to_string()),
             sender_id: self.local_peer_id.clone(),
-            timestamp: chrono::Utc::now().timestamp() as u64,
+            timestamp: clock::unix_secs(),
             ttl: 1, // Direct message, no propagation
             payload: message.encrypted_data.clone(),
             signature: message.signature.clone(),
+            trace_context: message.trace_context.clone(),
         };
         
         self.send_gossip_message(peer_id, &gossip_message).await?;
@@ -138,17 +146,18 @@ impl GossipProtocol {
             _ => MessageType::Custom("general".to_string()),
         };
         
-        let payload = serde_json::to_vec(message)?;
+        let payload = codec::encode(message)?;
         let signature = self.sign_message(&payload).await?;
         
         let gossip_message = GossipMessage {
-            message_id: uuid::Uuid::new_v4().to_string(),
+            message_id: entropy::uuid().to_string(),
             message_type,
             sender_id: self.local_peer_id.clone(),
-            timestamp: chrono::Utc::now().timestamp() as u64,
+            timestamp: clock::unix_secs(),
             ttl: 5, // Allow 5 hops
             payload,
             signature,
+            trace_context: telemetry::current_context(),
         };
         
         Ok(gossip_message)
@@ -158,19 +167,11 @@ impl GossipProtocol {
     async fn add_to_cache(&mut self, message: GossipMessage) -> Result<()> {
         let message_state = MessageState {
             message: message.clone(),
-            first_seen: Instant::now(),
             propagation_count: 0,
             peers_sent_to: Vec::new(),
         };
         
         self.message_cache.insert(message.message_id.clone(), message_state);
-        
-        // Cleanup old messages periodically
-        if self.last_cleanup.elapsed() > Duration::from_secs(300) { // 5 minutes
-            self.cleanup_message_cache().await?;
-            self.last_cleanup = Instant::now();
-        }
-        
         Ok(())
     }
 
@@ -182,10 +183,11 @@ impl GossipProtocol {
         let target_count = (self.peers.len() as f64).sqrt().ceil() as usize;
         let target_count = target_count.max(1).min(self.peers.len());
         
-        // Get all peer IDs except the sender
-        let available_peers: Vec<&String> = self.peers.keys()
+        // Get all peer IDs except the sender, sorted so a seeded selection is reproducible
+        let mut available_peers: Vec<&String> = self.peers.keys()
             .filter(|&peer_id| peer_id != &message.sender_id)
             .collect();
+        available_peers.sort();
         
         if available_peers.is_empty() {
             return Ok(targets);
@@ -193,8 +195,9 @@ impl GossipProtocol {
         
         // Select random peers
         use rand::seq::SliceRandom;
-        let mut rng = rand::thread_rng();
-        let selected_peers = available_peers.choose_multiple(&mut rng, target_count);
+        let selected_peers: Vec<&&String> = entropy::with_rng(|rng| {
+            available_peers.choose_multiple(rng, target_count).collect()
+        });
         
         for peer_id in selected_peers {
             targets.push((*peer_id).clone());
@@ -208,8 +211,10 @@ impl GossipProtocol {
     async fn send_gossip_message(&mut self, peer_id: &str, message: &GossipMessage) -> Result<()> {
         debug!("Sending gossip message {} to peer {}", message.message_id, peer_id);
         
-        // In production, this would use actual network transport
+        // In production, this would write the frame to the peer's transport
         // For now, we'll simulate successful sending
+        let frame = codec::encode(message)?;
+        debug!("Encoded gossip message {} as {} bytes", message.message_id, frame.len());
         
         // Update message state
         if let Some(message_state) = self.message_cache.get_mut(&message.message_id) {
@@ -269,29 +274,6 @@ impl GossipProtocol {
         Ok(true)
     }
 
-    /// Cleanup old messages from cache
-    async fn cleanup_message_cache(&mut self) -> Result<()> {
-        let now = Instant::now();
-        let max_age = Duration::from_secs(3600); // 1 hour
-        
-        let mut expired_messages = Vec::new();
-        
-        for (message_id, message_state) in &self.message_cache {
-            if now.duration_since(message_state.first_seen) > max_age {
-                expired_messages.push(message_id.clone());
-            }
-        }
-        
-        for message_id in expired_messages {
-            self.message_cache.remove(&message_id);
-        }
-        
-        debug!("Cleaned up {} expired messages from cache", 
-               self.message_cache.len());
-        
-        Ok(())
-    }
-
     /// Sign message payload
     async fn sign_message(&self, payload: &[u8]) -> Result<Vec<u8>> {
         // Mock signature - in production, use actual cryptographic signing
@@ -299,7 +281,7 @@ impl GossipProtocol {
         let mut hasher = Sha256::new();
         hasher.update(payload);
         hasher.update(self.local_peer_id.as_bytes());
-        hasher.update(&chrono::Utc::now().timestamp().to_le_bytes());
+        hasher.update(clock::unix_secs().to_le_bytes());
         
         Ok(hasher.finalize().to_vec())
     }
@@ -330,11 +312,17 @@ impl GossipProtocol {
             propagation_counts.iter().sum::<u32>() as f64 / propagation_counts.len() as f64
         };
         
+        let mut messages_by_sender: HashMap<String, u64> = HashMap::new();
+        for state in self.message_cache.values() {
+            *messages_by_sender.entry(state.message.sender_id.clone()).or_insert(0) += 1;
+        }
+        
         GossipStats {
             total_messages: total_messages as u64,
             total_peers: total_peers as u64,
             average_propagation: avg_propagation,
             cache_size: total_messages as u64,
+            messages_by_sender,
         }
     }
 
@@ -344,11 +332,6 @@ impl GossipProtocol {
             return Err(anyhow::anyhow!("No peers in gossip network"));
         }
         
-        // Check message cache size
-        if self.message_cache.len() > 10000 {
-            warn!("Large message cache size: {}", self.message_cache.len());
-        }
-        
         debug!("Gossip protocol health check passed. Peers: {}, Messages: {}", 
                self.peers.len(), self.message_cache.len());
         
@@ -377,6 +360,8 @@ pub struct GossipStats {
     pub total_peers: u64,
     pub average_propagation: f64,
     pub cache_size: u64,
+    /// Cached messages keyed by originating peer
+    pub messages_by_sender: HashMap<String, u64>,
 }
 
 #[cfg(test)]
@@ -401,7 +386,7 @@ mod tests {
             address: "127.0.0.1".to_string(),
             port: 8080,
             public_key: vec![1, 2, 3, 4],
-            last_seen: chrono::Utc::now().timestamp() as u64,
+            last_seen: clock::unix_secs(),
             stake: 1000,
             is_active: true,
             reputation: 5.0,
@@ -425,10 +410,11 @@ mod tests {
             message_id: "test_message".to_string(),
             message_type: MessageType::Heartbeat,
             sender_id: "test_sender".to_string(),
-            timestamp: chrono::Utc::now().timestamp() as u64,
+            timestamp: clock::unix_secs(),
             ttl: 3,
             payload: vec![1, 2, 3, 4],
             signature: vec![5, 6, 7, 8],
+            trace_context: TraceContext::new(),
         };
         
         gossip.add_to_cache(message.clone()).await?;
@@ -1,7 +1,8 @@
 pub mod p2p;
 pub mod gossip;
 pub mod encryption;
+pub mod codec;
 
-pub use p2p::{P2PNetwork, P2PMessage, PeerInfo};
-pub use gossip::{GossipProtocol, GossipMessage, MessageType};
+pub use p2p::{P2PNetwork, P2PMessage, PeerInfo, PeerList, InboundMessage, NetworkStats, PeerStats};
+pub use gossip::{GossipProtocol, GossipMessage, GossipStats, MessageType};
 pub use encryption::{NetworkEncryption, SecureMessage};
\ No newline at end of file
@@ -1,12 +1,17 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::time::{sleep, Duration, Instant};
 use tracing::{debug, info, warn, error};
 
-use crate::config::NetworkingConfig;
-use super::{GossipProtocol, NetworkEncryption, SecureMessage};
+use crate::config::{NetworkingConfig, PoolFilterConfig};
+use crate::matching::OrderCancellation;
+use crate::metrics::metrics;
+use super::{codec, GossipProtocol, GossipStats, NetworkEncryption, SecureMessage};
+use crate::telemetry::TraceContext;
+use crate::chaos;
+use crate::sim::{clock, entropy};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum P2PMessage {
@@ -27,6 +32,8 @@ pub enum P2PMessage {
         task_id: String,
         result: Vec<u8>,
         signature: Vec<u8>,
+        /// Signer's BLS signature of the result, when it has a BLS key
+        bls_signature: Option<Vec<u8>>,
     },
     /// Ping message for keepalive
     Ping {
@@ -55,6 +62,10 @@ pub enum P2PMessage {
         proof_data: Vec<u8>,
         signature: Vec<u8>,
     },
+    /// A trader's signed cancellation, so every operator drops the order
+    OrderCancellation {
+        cancellation: OrderCancellation,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +80,15 @@ pub struct PeerInfo {
     pub reputation: f64,
 }
 
+/// Message received from a peer along with the sender's trace context
+#[derive(Debug, Clone)]
+pub struct InboundMessage {
+    pub message: P2PMessage,
+    /// Peer the message originated from
+    pub sender: String,
+    pub trace_context: TraceContext,
+}
+
 #[derive(Debug)]
 struct PeerConnection {
     peer_info: PeerInfo,
@@ -78,6 +98,7 @@ struct PeerConnection {
     message_count: u64,
 }
 
+/// Encrypted TCP gossip between committee operators, with peer reputation and bans
 pub struct P2PNetwork {
     config: NetworkingConfig,
     local_peer_id: String,
@@ -87,17 +108,22 @@ pub struct P2PNetwork {
     network_encryption: NetworkEncryption,
     listener: Option<TcpListener>,
     is_running: bool,
-    message_queue: tokio::sync::mpsc::UnboundedReceiver<P2PMessage>,
-    message_sender: tokio::sync::mpsc::UnboundedSender<P2PMessage>,
+    message_queue: tokio::sync::mpsc::UnboundedReceiver<InboundMessage>,
+    message_sender: tokio::sync::mpsc::UnboundedSender<InboundMessage>,
+    banned_peers: HashSet<String>,
+    /// Advertised in handshakes so peers can skip gossip for pools we don't serve
+    capabilities: Vec<String>,
 }
 
 impl P2PNetwork {
-    pub async fn new(config: NetworkingConfig) -> Result<Self> {
+    /// Start listening on `config.listen_port` and dial the bootstrap peers
+    pub async fn new(config: NetworkingConfig, pool_filter: PoolFilterConfig) -> Result<Self> {
         info!("Initializing P2P network on port {}", config.listen_port);
         
         let (message_sender, message_queue) = tokio::sync::mpsc::unbounded_channel();
         
-        let local_peer_id = format!("peer_{}", uuid::Uuid::new_v4());
+        let local_peer_id = format!("peer_{}", entropy::uuid());
+        let capabilities = Self::capabilities(&pool_filter);
         
         let gossip_protocol = GossipProtocol::new(&config).await?;
         let network_encryption = NetworkEncryption::new().await?;
@@ -113,6 +139,8 @@ impl P2PNetwork {
             is_running: false,
             message_queue,
             message_sender,
+            banned_peers: HashSet::new(),
+            capabilities,
         };
         
         // Start listening for connections
@@ -125,6 +153,19 @@ impl P2PNetwork {
         Ok(network)
     }
 
+    fn capabilities(pool_filter: &PoolFilterConfig) -> Vec<String> {
+        ["order_matching", "gossip"].iter()
+            .map(|capability| capability.to_string())
+            .chain(pool_filter.capabilities())
+            .collect()
+    }
+
+    /// Advertise `pool_filter` in handshakes from now on
+    pub fn update_pool_filter(&mut self, pool_filter: &PoolFilterConfig) {
+        self.capabilities = Self::capabilities(pool_filter);
+        info!("Gossip subscriptions updated: {:?}", pool_filter.capabilities());
+    }
+
     /// Start TCP listener for incoming connections
     async fn start_listener(&mut self) -> Result<()> {
         let listen_addr = format!("0.0.0.0:{}", self.local_port);
@@ -166,7 +207,7 @@ impl P2PNetwork {
         let handshake = P2PMessage::Handshake {
             peer_id: self.local_peer_id.clone(),
             version: "1.0.0".to_string(),
-            capabilities: vec!["order_matching".to_string(), "gossip".to_string()],
+            capabilities: self.capabilities.clone(),
         };
         
         self.send_message_to_stream(&stream, &handshake).await?;
@@ -181,8 +222,8 @@ impl P2PNetwork {
                     address: peer_addr.split(':').next().unwrap_or("unknown").to_string(),
                     port: peer_addr.split(':').nth(1).unwrap_or("0").parse().unwrap_or(0),
                     public_key: vec![0u8; 32], // Mock public key
-                    last_seen: chrono::Utc::now().timestamp() as u64,
-                    stake: 32_000_000_000_000_000_000u64, // Mock 32 ETH
+                    last_seen: clock::unix_secs(),
+                    stake: 0, // Weighed by operator address in the stake table, not per peer
                     is_active: true,
                     reputation: 1.0,
                 };
@@ -196,6 +237,14 @@ impl P2PNetwork {
 
     /// Add peer to the network
     async fn add_peer(&mut self, peer_info: PeerInfo) -> Result<()> {
+        if self.banned_peers.contains(&peer_info.peer_id) {
+            return Err(anyhow::anyhow!("Peer {} is banned", peer_info.peer_id));
+        }
+        
+        if self.peers.len() >= self.config.max_peers && !self.peers.contains_key(&peer_info.peer_id) {
+            return Err(anyhow::anyhow!("Peer limit of {} reached", self.config.max_peers));
+        }
+        
         debug!("Adding peer: {}", peer_info.peer_id);
         
         let peer_connection = PeerConnection {
@@ -207,6 +256,7 @@ impl P2PNetwork {
         };
         
         self.peers.insert(peer_info.peer_id.clone(), peer_connection);
+        metrics().peers_connected.set(self.peers.len() as i64);
         let peer_id = peer_info.peer_id.clone();
         
         // Notify gossip protocol about new peer
@@ -217,10 +267,17 @@ impl P2PNetwork {
     }
 
     /// Listen for incoming messages
-    pub async fn listen_for_messages(&mut self) -> Result<P2PMessage> {
+    pub async fn listen_for_messages(&mut self) -> Result<InboundMessage> {
         loop {
             // Check for queued messages first
             if let Ok(message) = self.message_queue.try_recv() {
+                if chaos::reorder_message() {
+                    if let Ok(next) = self.message_queue.try_recv() {
+                        // Requeue behind everything already waiting
+                        let _ = self.message_sender.send(message);
+                        return Ok(next);
+                    }
+                }
                 return Ok(message);
             }
             
@@ -237,6 +294,19 @@ impl P2PNetwork {
         }
     }
 
+    /// Decrypt a secure message from a peer and queue it for the operator
+    pub async fn receive_secure_message(&self, secure_message: SecureMessage) -> Result<()> {
+        let message = self.network_encryption.decrypt_message(&secure_message).await?;
+        
+        self.message_sender.send(InboundMessage {
+            message,
+            sender: secure_message.sender_id,
+            trace_context: secure_message.trace_context,
+        })?;
+        
+        Ok(())
+    }
+
     /// Accept incoming connections
     async fn accept_connections(&mut self) -> Result<()> {
         if let Some(listener) = &mut self.listener {
@@ -266,7 +336,7 @@ impl P2PNetwork {
                 let response = P2PMessage::Handshake {
                     peer_id: self.local_peer_id.clone(),
                     version: "1.0.0".to_string(),
-                    capabilities: vec!["order_matching".to_string(), "gossip".to_string()],
+                    capabilities: self.capabilities.clone(),
                 };
                 
                 self.send_message_to_stream(&stream, &response).await?;
@@ -277,8 +347,8 @@ impl P2PNetwork {
                     address: "unknown".to_string(), // Would extract from stream
                     port: 0,
                     public_key: vec![0u8; 32],
-                    last_seen: chrono::Utc::now().timestamp() as u64,
-                    stake: 32_000_000_000_000_000_000u64,
+                    last_seen: clock::unix_secs(),
+                    stake: 0,
                     is_active: true,
                     reputation: 1.0,
                 };
@@ -308,7 +378,7 @@ impl P2PNetwork {
                 } else {
                     // Send ping
                     let ping = P2PMessage::Ping {
-                        timestamp: chrono::Utc::now().timestamp() as u64,
+                        timestamp: clock::unix_secs(),
                     };
                     
                     if let Err(e) = self.send_message_to_peer(&peer_id, &ping).await {
@@ -336,7 +406,8 @@ impl P2PNetwork {
     /// Remove peer from network
     async fn remove_peer(&mut self, peer_id: &str) -> Result<()> {
         if let Some(_) = self.peers.remove(peer_id) {
-            info!("Removed inactive peer: {}", peer_id);
+            metrics().peers_connected.set(self.peers.len() as i64);
+            info!("Removed peer: {}", peer_id);
             self.gossip_protocol.remove_peer(peer_id).await?;
         }
         Ok(())
@@ -381,6 +452,12 @@ impl P2PNetwork {
         debug!("Sending message to peer: {}", peer_id);
         
         if let Some(connection) = self.peers.get_mut(peer_id) {
+            chaos::network_delay().await;
+            if chaos::drop_message() {
+                debug!("Dropping message to {} (fault injection)", peer_id);
+                return Ok(());
+            }
+            
             // Encrypt message
             let secure_message = self.network_encryption.encrypt_message(message).await?;
             
@@ -388,7 +465,7 @@ impl P2PNetwork {
             self.gossip_protocol.send_message_to_peer(peer_id, &secure_message).await?;
             
             connection.message_count += 1;
-            connection.peer_info.last_seen = chrono::Utc::now().timestamp() as u64;
+            connection.peer_info.last_seen = clock::unix_secs();
             
             Ok(())
         } else {
@@ -398,7 +475,7 @@ impl P2PNetwork {
 
     /// Send message to TCP stream
     async fn send_message_to_stream(&self, stream: &TcpStream, message: &P2PMessage) -> Result<()> {
-        let serialized = serde_json::to_vec(message)?;
+        let serialized = codec::encode(message)?;
         
         // In production, this would use proper framing and error handling
         // For now, we'll simulate successful sending
@@ -411,7 +488,7 @@ impl P2PNetwork {
     async fn receive_message_from_stream(&self, stream: &TcpStream) -> Result<P2PMessage> {
         // Mock message reception
         let mock_handshake = P2PMessage::Handshake {
-            peer_id: format!("peer_{}", uuid::Uuid::new_v4()),
+            peer_id: format!("peer_{}", entropy::uuid()),
             version: "1.0.0".to_string(),
             capabilities: vec!["order_matching".to_string()],
         };
@@ -440,14 +517,29 @@ impl P2PNetwork {
             .map(|conn| conn.message_count)
             .sum();
         
+        let peers = self.peers.values()
+            .map(|conn| PeerStats {
+                peer_id: conn.peer_info.peer_id.clone(),
+                messages_sent: conn.message_count,
+                reputation: conn.peer_info.reputation,
+                is_active: conn.peer_info.is_active,
+            })
+            .collect();
+        
         NetworkStats {
             total_peers: total_peers as u64,
             active_peers: active_peers as u64,
             total_messages,
             uptime_seconds: 0, // Would track actual uptime
+            peers,
         }
     }
 
+    /// Get gossip protocol statistics
+    pub fn get_gossip_stats(&self) -> GossipStats {
+        self.gossip_protocol.get_gossip_stats()
+    }
+
     /// Health check for P2P network
     pub async fn health_check(&self) -> Result<()> {
         let active_peers = self.get_active_peers();
@@ -475,6 +567,74 @@ impl P2PNetwork {
         &self.local_peer_id
     }
 
+    /// Connected peers and the current ban list
+    pub fn list_peers(&self) -> PeerList {
+        let mut peers: Vec<PeerInfo> = self.peers.values()
+            .map(|conn| conn.peer_info.clone())
+            .collect();
+        peers.sort_by(|a, b| a.peer_id.cmp(&b.peer_id));
+        
+        let mut banned: Vec<String> = self.banned_peers.iter().cloned().collect();
+        banned.sort();
+        
+        PeerList { peers, banned }
+    }
+
+    /// Dial a peer by address outside of the bootstrap list
+    pub async fn connect(&mut self, peer_addr: &str) -> Result<PeerInfo> {
+        let peer_info = self.connect_to_peer(peer_addr).await?;
+        self.add_peer(peer_info.clone()).await?;
+        
+        info!("Connected to peer {} at {}", peer_info.peer_id, peer_addr);
+        Ok(peer_info)
+    }
+
+    /// Disconnect a peer and refuse it until unbanned
+    pub async fn ban_peer(&mut self, peer_id: &str) -> Result<()> {
+        self.banned_peers.insert(peer_id.to_string());
+        self.remove_peer(peer_id).await?;
+        
+        warn!("Banned peer {}", peer_id);
+        Ok(())
+    }
+
+    /// Lift a ban; returns false if the peer was not banned
+    pub fn unban_peer(&mut self, peer_id: &str) -> bool {
+        let removed = self.banned_peers.remove(peer_id);
+        if removed {
+            info!("Unbanned peer {}", peer_id);
+        }
+        removed
+    }
+
+    /// Stop accepting connections and drop all peers
+    pub async fn shutdown(&mut self) -> Result<()> {
+        self.listener = None;
+        self.is_running = false;
+        
+        for peer_id in self.peers.keys().cloned().collect::<Vec<_>>() {
+            self.remove_peer(&peer_id).await?;
+        }
+        
+        info!("P2P network shut down");
+        Ok(())
+    }
+
+    /// Change peer limits at runtime; rejected if more peers are connected than the new maximum
+    pub fn update_peer_limits(&mut self, min_peers: usize, max_peers: usize) -> Result<()> {
+        if self.peers.len() > max_peers {
+            return Err(anyhow::anyhow!(
+                "{} peers are connected, above the requested maximum of {}",
+                self.peers.len(), max_peers
+            ));
+        }
+        
+        self.config.min_peers = min_peers;
+        self.config.max_peers = max_peers;
+        info!("Peer limits updated: min {}, max {}", min_peers, max_peers);
+        Ok(())
+    }
+
     /// Update peer reputation
     pub fn update_peer_reputation(&mut self, peer_id: &str, delta: f64) {
         if let Some(connection) = self.peers.get_mut(peer_id) {
@@ -490,6 +650,23 @@ pub struct NetworkStats {
     pub active_peers: u64,
     pub total_messages: u64,
     pub uptime_seconds: u64,
+    pub peers: Vec<PeerStats>,
+}
+
+/// Peer listing served to the admin API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerList {
+    pub peers: Vec<PeerInfo>,
+    pub banned: Vec<String>,
+}
+
+/// Per-peer breakdown of `NetworkStats`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerStats {
+    pub peer_id: String,
+    pub messages_sent: u64,
+    pub reputation: f64,
+    pub is_active: bool,
 }
 
 #[cfg(test)]
@@ -512,7 +689,7 @@ mod tests {
             address: "127.0.0.1".to_string(),
             port: 8080,
             public_key: vec![1, 2, 3, 4],
-            last_seen: chrono::Utc::now().timestamp() as u64,
+            last_seen: clock::unix_secs(),
             stake: 1000,
             is_active: true,
             reputation: 5.0,
@@ -0,0 +1,179 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::{debug, info, warn};
+
+use crate::config::NotificationConfig;
+use crate::matching::{OrderLifecycle, OrderTransition};
+use crate::sim::clock;
+
+/// Webhooks one order can have, so a single commitment can't fan out requests
+const MAX_WEBHOOKS_PER_ORDER: usize = 3;
+
+/// Body POSTed to a trader's webhook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillNotification {
+    pub commitment: String,
+    pub status: OrderLifecycle,
+    pub timestamp: u64,
+}
+
+struct Registration {
+    urls: Vec<String>,
+    deadline: u64,
+}
+
+/// Webhooks registered by commitment, called when the order is matched and when it settles
+pub struct WebhookNotifier {
+    config: NotificationConfig,
+    /// Keyed by commitment: lowercase hex without `0x`
+    registrations: Mutex<HashMap<String, Registration>>,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(config: NotificationConfig) -> Self {
+        Self {
+            config,
+            registrations: Mutex::new(HashMap::new()),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Call `url` for the order with `commitment`, which expires at `deadline`
+    pub fn register(&self, commitment: &str, url: &str, deadline: u64) -> Result<()> {
+        let parsed = reqwest::Url::parse(url).map_err(|e| anyhow::anyhow!("Invalid webhook URL {}: {}", url, e))?;
+        match parsed.scheme() {
+            "https" => {}
+            "http" if self.config.allow_http => {}
+            scheme => return Err(anyhow::anyhow!("Webhook scheme {} is not allowed", scheme)),
+        }
+
+        let mut registrations = self.registrations.lock().unwrap();
+        if registrations.values().map(|registration| registration.urls.len()).sum::<usize>() >= self.config.max_webhooks {
+            let now = clock::unix_secs();
+            registrations.retain(|_, registration| registration.deadline >= now);
+            if registrations.values().map(|registration| registration.urls.len()).sum::<usize>() >= self.config.max_webhooks {
+                return Err(anyhow::anyhow!("Webhook limit of {} reached", self.config.max_webhooks));
+            }
+        }
+
+        let registration = registrations.entry(key(commitment)).or_insert(Registration { urls: Vec::new(), deadline });
+        if registration.urls.iter().any(|existing| existing == url) {
+            return Ok(());
+        }
+        if registration.urls.len() >= MAX_WEBHOOKS_PER_ORDER {
+            return Err(anyhow::anyhow!("Order {} already has {} webhooks", commitment, MAX_WEBHOOKS_PER_ORDER));
+        }
+        registration.urls.push(url.to_string());
+        debug!("Registered webhook for order {}", commitment);
+        Ok(())
+    }
+
+    /// Notify webhooks of order transitions until the tracker goes away
+    pub async fn run(self: Arc<Self>, mut transitions: broadcast::Receiver<OrderTransition>) {
+        info!("Delivering order notifications to trader webhooks");
+
+        loop {
+            let transition = match transitions.recv().await {
+                Ok(transition) => transition,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!("Webhook notifier lagging, skipped {} order transitions", skipped);
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            };
+
+            for url in self.take_urls(&transition) {
+                let notification = FillNotification {
+                    commitment: transition.order_id.clone(),
+                    status: transition.lifecycle,
+                    timestamp: clock::unix_secs(),
+                };
+                tokio::spawn(self.clone().deliver(url, notification));
+            }
+        }
+    }
+
+    /// Webhooks to call for `transition`; an order's webhooks are dropped once it is finished
+    fn take_urls(&self, transition: &OrderTransition) -> Vec<String> {
+        let mut registrations = self.registrations.lock().unwrap();
+        match transition.lifecycle {
+            OrderLifecycle::PartiallyMatched | OrderLifecycle::Matched => registrations.get(&key(&transition.order_id))
+                .map(|registration| registration.urls.clone())
+                .unwrap_or_default(),
+            OrderLifecycle::Settled => registrations.remove(&key(&transition.order_id))
+                .map(|registration| registration.urls)
+                .unwrap_or_default(),
+            OrderLifecycle::Expired | OrderLifecycle::Cancelled => {
+                registrations.remove(&key(&transition.order_id));
+                Vec::new()
+            }
+            OrderLifecycle::Pending => Vec::new(),
+        }
+    }
+
+    async fn deliver(self: Arc<Self>, url: String, notification: FillNotification) {
+        for attempt in 1..=self.config.max_attempts {
+            let result = self.client
+                .post(&url)
+                .json(&notification)
+                .timeout(Duration::from_millis(self.config.timeout_ms))
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+
+            match result {
+                Ok(_) => {
+                    debug!("Notified webhook of order {} {:?}", notification.commitment, notification.status);
+                    return;
+                }
+                Err(e) if attempt < self.config.max_attempts => {
+                    debug!("Webhook for order {} failed (attempt {}): {}", notification.commitment, attempt, e);
+                    tokio::time::sleep(Duration::from_secs(u64::from(attempt))).await;
+                }
+                Err(e) => warn!("Dropping notification for order {}: {}", notification.commitment, e),
+            }
+        }
+    }
+}
+
+fn key(commitment: &str) -> String {
+    commitment.trim_start_matches("0x").to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_limits() {
+        let notifier = WebhookNotifier::new(NotificationConfig { enabled: true, max_webhooks: 4, ..NotificationConfig::default() });
+        let deadline = clock::unix_secs() + 3600;
+
+        assert!(notifier.register("0xabcd", "http://10.0.0.1/hook", deadline).is_err());
+        assert!(notifier.register("0xabcd", "not a url", deadline).is_err());
+        for n in 0..MAX_WEBHOOKS_PER_ORDER {
+            notifier.register("0xABCD", &format!("https://trader.example.com/{}", n), deadline).unwrap();
+        }
+        assert!(notifier.register("0xabcd", "https://trader.example.com/0", deadline).is_ok());
+        assert!(notifier.register("0xabcd", "https://trader.example.com/extra", deadline).is_err());
+
+        notifier.register("0x1234", "https://trader.example.com/other", deadline).unwrap();
+        assert!(notifier.register("0x5678", "https://trader.example.com/full", deadline).is_err());
+    }
+
+    #[test]
+    fn test_webhooks_are_dropped_once_settled() {
+        let notifier = WebhookNotifier::new(NotificationConfig::default());
+        notifier.register("0xabcd", "https://trader.example.com/hook", clock::unix_secs() + 3600).unwrap();
+        let transition = |lifecycle| OrderTransition { order_id: "0xabcd".to_string(), lifecycle };
+
+        assert_eq!(notifier.take_urls(&transition(OrderLifecycle::Matched)).len(), 1);
+        assert_eq!(notifier.take_urls(&transition(OrderLifecycle::Settled)).len(), 1);
+        assert!(notifier.take_urls(&transition(OrderLifecycle::Settled)).is_empty());
+    }
+}
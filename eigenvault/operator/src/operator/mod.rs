@@ -0,0 +1,1345 @@
+use anyhow::Result;
+use futures::FutureExt;
+use std::collections::{HashMap, HashSet};
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn, error, instrument, Instrument};
+
+use crate::{api, bootstrap, config, ethereum, matching, metrics, networking, quorum, replay, sim, supervisor, telemetry};
+use crate::accounting::{Accountant, SubmittedTask};
+use crate::api::{HealthRegistry, OperatorControl, StatusBoard};
+use crate::api::control::PeerCommand;
+use crate::backpressure::FlowControl;
+use crate::config::{Config, KeyManager};
+use crate::ethereum::client::SlashingEvent;
+use crate::ethereum::{ChainClient, ChainEvent, EthereumClient, OperatorIdentity};
+use crate::eventbus::{EventPublisher, OperatorEvent};
+use crate::ha::{self, Lease};
+use crate::matching::MatchingEngine;
+use crate::metrics::{LatencyTracker, Stage};
+use crate::networking::{P2PMessage, P2PNetwork, PeerList};
+use crate::proofs::{tuning, MatchingProof, ProofBatcher, ZKProver};
+use crate::quorum::{BlsCommittee, StakeTable, TaskAggregator, Vote};
+use crate::registration::Reregistration;
+use crate::risk::RiskMonitor;
+use crate::scheduler::TaskScheduler;
+use crate::storage::{PendingSubmission, ScheduledTask, StateCheckpoint, Store, SubmissionKind};
+use crate::supervisor::{Notifier, RestartPolicy};
+
+async fn apply_peer_command(p2p_network: &mut P2PNetwork, command: PeerCommand) -> Result<PeerList> {
+    match command {
+        PeerCommand::List => {}
+        PeerCommand::Ban(peer_id) => p2p_network.ban_peer(&peer_id).await?,
+        PeerCommand::Unban(peer_id) => {
+            if !p2p_network.unban_peer(&peer_id) {
+                return Err(anyhow::anyhow!("Peer {} is not banned", peer_id));
+            }
+        }
+        PeerCommand::Connect(address) => {
+            p2p_network.connect(&address).await?;
+        }
+        PeerCommand::SetLimits { min_peers, max_peers } => {
+            p2p_network.update_peer_limits(min_peers, max_peers)?;
+        }
+        PeerCommand::SetPools(pool_filter) => {
+            p2p_network.update_pool_filter(&pool_filter);
+        }
+        PeerCommand::Penalize { peer_id, penalty } => {
+            p2p_network.update_peer_reputation(&peer_id, -penalty);
+        }
+    }
+    
+    Ok(p2p_network.list_peers())
+}
+
+/// Resolves on SIGINT or, on Unix, SIGTERM
+pub async fn wait_for_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => tokio::select! {
+                _ = tokio::signal::ctrl_c() => "SIGINT",
+                _ = sigterm.recv() => "SIGTERM",
+            },
+            Err(e) => {
+                warn!("Failed to install SIGTERM handler: {:?}", e);
+                let _ = tokio::signal::ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+    
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "Ctrl-C"
+    }
+}
+
+/// This operator's response to a task, submitted once the committee agrees on its result
+struct TaskResponse {
+    matches: Vec<matching::OrderMatch>,
+    proof: MatchingProof,
+    deadline: u64,
+}
+
+/// Main operator struct that coordinates all components; shared by the operator tasks
+pub struct Operator<C = EthereumClient> {
+    ethereum_client: C,
+    /// Identities besides the primary one in `ethereum_client`; they submit but don't ingest events
+    identities: Vec<OperatorIdentity<C>>,
+    matching_engine: Arc<MatchingEngine>,
+    zk_prover: ZKProver,
+    /// Matches from the matching loop waiting to be proved together
+    proof_batches: ProofBatcher,
+    event_publisher: EventPublisher,
+    store: Arc<Store>,
+    health: Arc<HealthRegistry>,
+    status: Arc<StatusBoard>,
+    control: Arc<OperatorControl>,
+    latency: Arc<LatencyTracker>,
+    accountant: Accountant,
+    reregistration: Reregistration,
+    risk: RiskMonitor,
+    flow: FlowControl,
+    /// Accepted tasks waiting for the task scheduler, earliest deadline first
+    scheduler: TaskScheduler,
+    /// Live stake of this operator's identities and the peers whose results it has seen
+    stakes: StakeTable,
+    results: TaskAggregator<TaskResponse>,
+    /// Keys the BLS signatures on an agreed result are checked with before it is submitted
+    bls_committee: BlsCommittee,
+    /// Peers that sent results signed by each operator, penalized if that operator is slashed
+    result_peers: Mutex<HashMap<String, HashSet<String>>>,
+    /// Last block checked for slashings; kept across restarts of the monitor
+    slashings_checked: Mutex<Option<u64>>,
+    /// Messages for the P2P task to broadcast; a restarted task reacquires the receiver
+    gossip_sender: mpsc::UnboundedSender<P2PMessage>,
+    gossip_receiver: tokio::sync::Mutex<mpsc::UnboundedReceiver<P2PMessage>>,
+    /// Failover lease this instance holds as the active operator
+    lease: Option<Arc<Lease>>,
+    shutdown: CancellationToken,
+    config: Config,
+}
+
+impl<C: ChainClient> Operator<C> {
+    pub fn new(
+        ethereum_client: C,
+        identities: Vec<OperatorIdentity<C>>,
+        matching_engine: Arc<MatchingEngine>,
+        zk_prover: ZKProver,
+        event_publisher: EventPublisher,
+        store: Arc<Store>,
+        health: Arc<HealthRegistry>,
+        status: Arc<StatusBoard>,
+        control: Arc<OperatorControl>,
+        latency: Arc<LatencyTracker>,
+        accountant: Accountant,
+        shutdown: CancellationToken,
+        config: Config,
+    ) -> Self {
+        let flow = FlowControl::new(config.backpressure.clone(), matching_engine.clone());
+        let reregistration = Reregistration::new(&config);
+        let risk = RiskMonitor::new(config.risk.clone());
+        let proof_batches = ProofBatcher::new(config.proof_tuning.clone());
+        let scheduler = TaskScheduler::new(config.scheduler.clone());
+        let stakes = StakeTable::new(config.identity_configs().into_iter().map(|(_, ethereum)| ethereum.operator_address));
+        let results = TaskAggregator::new(&config.quorum);
+        let bls_committee = BlsCommittee::new(&config.quorum);
+        let (gossip_sender, gossip_receiver) = mpsc::unbounded_channel();
+        
+        Self {
+            ethereum_client,
+            identities,
+            matching_engine,
+            zk_prover,
+            proof_batches,
+            event_publisher,
+            store,
+            health,
+            status,
+            control,
+            latency,
+            accountant,
+            reregistration,
+            risk,
+            flow,
+            scheduler,
+            stakes,
+            results,
+            bls_committee,
+            result_peers: Mutex::new(HashMap::new()),
+            slashings_checked: Mutex::new(None),
+            gossip_sender,
+            gossip_receiver: tokio::sync::Mutex::new(gossip_receiver),
+            lease: None,
+            shutdown,
+            config,
+        }
+    }
+
+    /// Keep renewing `lease` while running, stopping at once if it is lost
+    pub fn set_lease(&mut self, lease: Arc<Lease>) {
+        self.lease = Some(lease);
+    }
+
+    /// Queue tasks for the task scheduler, including those recovered from before a restart
+    pub fn schedule_tasks(&self, tasks: Vec<ScheduledTask>) {
+        for task in tasks {
+            self.scheduler.schedule(task);
+        }
+    }
+
+    pub async fn run(self, p2p_network: P2PNetwork) -> Result<ExitCode> {
+        info!("EigenVault operator starting...");
+        let operator = Arc::new(self);
+        let drain_timeout = tokio::time::Duration::from_secs(operator.config.shutdown.drain_timeout_seconds);
+        // Held for the lifetime of each P2P loop run so a restarted loop picks up the same network
+        let p2p_network = Arc::new(tokio::sync::Mutex::new(p2p_network));
+        // Weigh the first tasks' results against synced stake rather than none
+        if let Err(e) = operator.sync_stakes().await {
+            warn!("Failed to sync stake weights: {:?}", e);
+        }
+
+        // Start background tasks under supervision
+        let mut tasks = JoinSet::new();
+        operator.supervise(&mut tasks, "ethereum_listener", {
+            let operator = operator.clone();
+            move || operator.clone().run_ethereum_listener()
+        });
+        operator.supervise(&mut tasks, "p2p_network", {
+            let operator = operator.clone();
+            move || operator.clone().run_p2p_network(p2p_network.clone())
+        });
+        operator.supervise(&mut tasks, "task_scheduler", {
+            let operator = operator.clone();
+            move || operator.clone().run_task_scheduler()
+        });
+        operator.supervise(&mut tasks, "matching_engine", {
+            let operator = operator.clone();
+            move || operator.clone().run_matching_engine()
+        });
+        operator.supervise(&mut tasks, "health_check", {
+            let operator = operator.clone();
+            move || operator.clone().run_health_check()
+        });
+        operator.supervise(&mut tasks, "stake_sync", {
+            let operator = operator.clone();
+            move || operator.clone().run_stake_sync()
+        });
+        operator.supervise(&mut tasks, "slashing_monitor", {
+            let operator = operator.clone();
+            move || operator.clone().run_slashing_monitor()
+        });
+        // Not restarted: a lost lease means another instance is already active
+        if let Some(lease) = operator.lease.clone() {
+            tasks.spawn(lease.hold(operator.shutdown.clone()).map(|result| (ha::LEASE_TASK, result)));
+        }
+
+        // Outside the task set: it returns immediately when not running under systemd
+        let notifier = Arc::new(Notifier::from_env());
+        tokio::spawn(operator.clone().notify_systemd(notifier.clone()));
+
+        // Run until a signal arrives or a task exhausts its restarts
+        let mut exit_code = ExitCode::SUCCESS;
+        let mut fenced = false;
+        tokio::select! {
+            signal = wait_for_signal() => {
+                info!("Received {}, shutting down gracefully", signal);
+            }
+            Some(joined) = tasks.join_next() => {
+                match joined {
+                    Ok((task, result)) => {
+                        error!("{} stopped: {:?}", task, result);
+                        fenced = task == ha::LEASE_TASK;
+                    }
+                    Err(e) => error!("Operator task panicked: {:?}", e),
+                }
+                exit_code = ExitCode::FAILURE;
+            }
+        }
+
+        // Stop intake; the loops finish in-flight matching and proving before returning
+        notifier.stopping();
+        operator.shutdown.cancel();
+        if fenced {
+            // Another instance is active: nothing in flight may reach the chain
+            warn!("Failover lease lost; aborting in-flight work without draining");
+            tasks.shutdown().await;
+        } else {
+            let drained = tokio::time::timeout(drain_timeout, async {
+                while let Some(joined) = tasks.join_next().await {
+                    match joined {
+                        Ok((task, Ok(()))) => info!("{} stopped", task),
+                        Ok((task, Err(e))) => error!("{} stopped with error: {:?}", task, e),
+                        Err(e) => error!("Operator task panicked: {:?}", e),
+                    }
+                }
+            }).await;
+            
+            if drained.is_err() {
+                warn!("In-flight work did not drain within {:?}; aborting remaining tasks", drain_timeout);
+                tasks.shutdown().await;
+                exit_code = ExitCode::FAILURE;
+            }
+        }
+
+        if let Err(e) = operator.store.flush() {
+            error!("Failed to flush state store: {:?}", e);
+            exit_code = ExitCode::FAILURE;
+        }
+
+        info!("Operator stopped");
+        Ok(exit_code)
+    }
+
+    /// Spawn a restartable operator task; `start` is called again after each failure
+    fn supervise<F, Fut>(self: &Arc<Self>, tasks: &mut JoinSet<(&'static str, Result<()>)>, name: &'static str, start: F)
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+    {
+        let policy = RestartPolicy::from(&self.config.supervisor);
+        let supervised = supervisor::supervise(name, policy, self.health.clone(), self.shutdown.clone(), start);
+        tasks.spawn(supervised.map(move |result| (name, result)));
+    }
+
+    /// Report readiness to systemd once every component has been checked, then send watchdog
+    /// keepalives for as long as health results keep arriving
+    async fn notify_systemd(self: Arc<Self>, notifier: Arc<Notifier>) {
+        if !notifier.is_enabled() {
+            return;
+        }
+        
+        let period = notifier.watchdog_interval().unwrap_or(tokio::time::Duration::from_secs(1));
+        let mut ready = false;
+        
+        while !self.shutdown.is_cancelled() {
+            let report = self.health.snapshot().await;
+            if !ready && report.status != "starting" {
+                info!("Operator ready, notifying systemd");
+                notifier.ready();
+                ready = true;
+            }
+            
+            // A wedged health loop goes stale; withholding keepalives lets systemd restart us
+            if report.stale {
+                warn!("Health results are stale, withholding systemd watchdog keepalive");
+            } else if notifier.watchdog_interval().is_some() {
+                notifier.watchdog();
+            }
+            
+            self.idle(period).await;
+        }
+    }
+
+    /// Sleep for `duration`, waking early on shutdown
+    async fn idle(&self, duration: tokio::time::Duration) {
+        tokio::select! {
+            _ = tokio::time::sleep(duration) => {}
+            _ = self.shutdown.cancelled() => {}
+        }
+    }
+
+    async fn run_stake_sync(self: Arc<Self>) -> Result<()> {
+        info!("Starting stake sync...");
+        let interval = tokio::time::Duration::from_secs(self.config.quorum.sync_interval_seconds);
+        
+        while !self.shutdown.is_cancelled() {
+            if let Err(e) = self.sync_stakes().await {
+                warn!("Failed to sync stake weights: {:?}", e);
+            }
+            
+            self.idle(interval).await;
+        }
+        
+        Ok(())
+    }
+
+    /// Refresh every tracked operator's weight and the total from the stake registry
+    async fn sync_stakes(&self) -> Result<()> {
+        let mut weights = HashMap::new();
+        for operator in self.stakes.operators() {
+            let weight = self.ethereum_client.stake_of(&operator).await?;
+            weights.insert(operator, weight);
+        }
+        let total = self.ethereum_client.total_stake().await?;
+        debug!("Synced stake of {} operators, total {}", weights.len(), total);
+        self.stakes.update(weights, total);
+        Ok(())
+    }
+
+    async fn run_slashing_monitor(self: Arc<Self>) -> Result<()> {
+        info!("Starting slashing monitor...");
+        let interval = tokio::time::Duration::from_secs(self.config.slashing.poll_interval_seconds);
+        
+        while !self.shutdown.is_cancelled() {
+            if let Err(e) = self.check_slashings().await {
+                warn!("Failed to check for slashings: {:?}", e);
+            }
+            
+            self.idle(interval).await;
+        }
+        
+        Ok(())
+    }
+
+    /// Respond to slashings in the blocks since the last check; the first check starts at the
+    /// latest block
+    async fn check_slashings(&self) -> Result<()> {
+        let latest = self.ethereum_client.latest_block().await?;
+        let checked = *self.slashings_checked.lock().unwrap();
+        let mut from = checked.map_or(latest, |block| block + 1);
+        let mut slashed = false;
+        
+        while from <= latest {
+            let to = latest.min(from + ethereum::events::MAX_LOG_RANGE - 1);
+            for event in self.ethereum_client.slashing_events(from, to).await? {
+                self.handle_slashing(event).await;
+                slashed = true;
+            }
+            *self.slashings_checked.lock().unwrap() = Some(to);
+            from = to + 1;
+        }
+        
+        // Weigh results against the reduced stake without waiting for the next sync
+        if slashed {
+            self.sync_stakes().await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_slashing(&self, event: SlashingEvent) {
+        let identity = self.config.identity_configs().into_iter()
+            .find(|(_, ethereum)| ethereum.operator_address.eq_ignore_ascii_case(&event.operator))
+            .map(|(name, _)| name);
+        let amount = bootstrap::format_ether(event.amount_wei);
+        
+        if let Some(identity) = &identity {
+            metrics::metrics().operator_slashings.with_label_values(&["self"]).inc();
+            error!(
+                "CRITICAL: identity '{}' ({}) was slashed {} ETH in {}: {}",
+                identity, event.operator, amount, event.transaction_hash, event.reason,
+            );
+            if self.config.slashing.pause_on_self_slash {
+                self.risk.suspend();
+            }
+        } else {
+            metrics::metrics().operator_slashings.with_label_values(&["peer"]).inc();
+            warn!("Operator {} was slashed {} ETH in {}: {}", event.operator, amount, event.transaction_hash, event.reason);
+            
+            let peers = self.result_peers.lock().unwrap().get(&event.operator).cloned().unwrap_or_default();
+            for peer_id in peers {
+                let command = PeerCommand::Penalize {
+                    peer_id: peer_id.clone(),
+                    penalty: self.config.slashing.peer_reputation_penalty,
+                };
+                if let Err(e) = self.control.manage_peers(command).await {
+                    warn!("Failed to lower the reputation of peer {}: {:?}", peer_id, e);
+                }
+            }
+        }
+        
+        self.event_publisher.publish(OperatorEvent::OperatorSlashed {
+            identity,
+            operator: event.operator,
+            amount_wei: event.amount_wei,
+            reason: event.reason,
+        });
+    }
+
+    async fn run_ethereum_listener(self: Arc<Self>) -> Result<()> {
+        info!("Starting Ethereum event listener...");
+        let mut proof_submissions = self.control.proof_submissions().await;
+        // Events handled again on later ticks, in log order: those that failed, and new orders
+        // held back until matching and proving catch up. The persisted cursor stays below the
+        // first of them.
+        let mut deferred: Vec<ChainEvent> = Vec::new();
+        let mut holding_orders = false;
+        
+        while !self.shutdown.is_cancelled() {
+            // Relay proofs submitted through the admin APIs
+            while let Ok(submission) = proof_submissions.try_recv() {
+                self.relay_proof_submission(submission).await;
+            }
+            
+            // Blocks past the persisted cursor are re-read after a restart
+            let events = tokio::select! {
+                events = self.ethereum_client.listen_for_events() => events,
+                _ = self.shutdown.cancelled() => break,
+            };
+            
+            match events {
+                Ok(events) => {
+                    // Each event's effects are persisted with its log id, so a restart partway
+                    // through the range skips what was already handled
+                    let mut committed = true;
+                    let mut holding = false;
+                    for ChainEvent { log, event } in std::mem::take(&mut deferred).into_iter().chain(events) {
+                        match self.store.is_log_processed(&log) {
+                            Ok(true) => {
+                                debug!("Skipping already processed log {:?}", log);
+                                continue;
+                            }
+                            Ok(false) => {}
+                            Err(e) => error!("Failed to read processed logs: {:?}", e),
+                        }
+                        
+                        if event.is_order_intake() {
+                            // Once one order waits, the later ones wait behind it
+                            if !holding {
+                                if let Some(saturation) = self.flow.saturation().await {
+                                    if !holding_orders {
+                                        warn!("Holding back new orders from chain: {:?}", saturation);
+                                    }
+                                    holding = true;
+                                }
+                            }
+                            if holding {
+                                metrics::metrics().backpressure_throttled.with_label_values(&["ethereum"]).inc();
+                                deferred.push(ChainEvent { log, event });
+                                continue;
+                            }
+                        }
+                        
+                        metrics::metrics().events_processed.with_label_values(&[event.name()]).inc();
+                        let retry = ChainEvent { log, event: event.clone() };
+                        let mut checkpoint = StateCheckpoint::for_log(log);
+                        let mut processed = true;
+                        if let Err(e) = self.handle_ethereum_event(event, &mut checkpoint).await {
+                            // Left unprocessed so it is retried on the next tick
+                            error!("Failed to handle Ethereum event: {:?}", e);
+                            checkpoint.processed_logs.clear();
+                            processed = false;
+                        }
+                        // Queued once persisted, so finishing a task can't precede scheduling it on disk
+                        let scheduled = checkpoint.scheduled_tasks.clone();
+                        if let Err(e) = self.store.commit(checkpoint) {
+                            error!("Failed to commit state checkpoint: {:?}", e);
+                            processed = false;
+                            committed = false;
+                        }
+                        self.schedule_tasks(scheduled);
+                        
+                        if !processed {
+                            deferred.push(retry);
+                        }
+                    }
+                    
+                    if holding_orders && !holding {
+                        debug!("Taking new orders from chain again");
+                    }
+                    holding_orders = holding;
+                    
+                    // The cursor only moves once every event of its range is persisted, and
+                    // never past a log that wasn't or an order still held back
+                    if committed {
+                        let block = self.ethereum_client.last_processed_block();
+                        let block = deferred.first().map_or(block, |held| held.log.block_number.saturating_sub(1).min(block));
+                        let checkpoint = StateCheckpoint::at_block(block);
+                        if let Err(e) = self.store.commit(checkpoint) {
+                            error!("Failed to commit state checkpoint: {:?}", e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    error!("Error listening for Ethereum events: {:?}", e);
+                    self.idle(tokio::time::Duration::from_secs(5)).await;
+                }
+            }
+        }
+        
+        Ok(())
+    }
+
+    /// Sole owner of the P2P network; other tasks reach it through the control channel
+    async fn run_p2p_network(self: Arc<Self>, p2p_network: Arc<tokio::sync::Mutex<P2PNetwork>>) -> Result<()> {
+        info!("Starting P2P network...");
+        let mut p2p_network = p2p_network.lock().await;
+        let mut peer_commands = self.control.peer_commands().await;
+        let mut cancellations = self.control.cancellations().await;
+        let mut gossip = self.gossip_receiver.lock().await;
+        let mut recorder = match &self.config.networking.record_path {
+            Some(path) => {
+                info!("Recording inbound peer messages to {}", path);
+                Some(replay::Recorder::open(path)?)
+            }
+            None => None,
+        };
+        let mut health_interval = tokio::time::interval(tokio::time::Duration::from_secs(
+            self.config.api.health_check_interval_seconds,
+        ));
+        
+        loop {
+            // Stop reading from peers while saturated; peer commands and health reports keep flowing
+            let accepting = self.flow.saturation().await.is_none();
+            if !accepting {
+                metrics::metrics().backpressure_throttled.with_label_values(&["gossip"]).inc();
+            }
+            
+            tokio::select! {
+                _ = self.shutdown.cancelled() => break,
+                // Peer commands from the admin API must not wait for the next inbound message
+                Some(request) = peer_commands.recv() => {
+                    let result = apply_peer_command(&mut p2p_network, request.command).await;
+                    let _ = request.reply.send(result);
+                }
+                Some(cancellation) = cancellations.recv() => {
+                    let message = P2PMessage::OrderCancellation { cancellation };
+                    if let Err(e) = p2p_network.broadcast_message(&message).await {
+                        warn!("Failed to broadcast order cancellation: {:?}", e);
+                    }
+                }
+                Some(message) = gossip.recv() => {
+                    if let Err(e) = p2p_network.broadcast_message(&message).await {
+                        warn!("Failed to broadcast P2P message: {:?}", e);
+                    }
+                }
+                _ = health_interval.tick() => {
+                    self.report_network_health(&p2p_network).await;
+                }
+                _ = tokio::time::sleep(self.flow.pause()), if !accepting => {}
+                result = p2p_network.listen_for_messages(), if accepting => match result {
+                    Ok(inbound) => {
+                        if let Some(recorder) = recorder.as_mut() {
+                            let record = replay::ReplayRecord::Gossip { message: inbound.message.clone() };
+                            if let Err(e) = recorder.record(&record) {
+                                warn!("Failed to record P2P message: {:?}", e);
+                            }
+                        }
+                        
+                        // Continue the sender's trace so cross-operator flows show up as one trace
+                        let span = tracing::info_span!("p2p.receive");
+                        telemetry::set_parent(&span, &inbound.trace_context);
+                        
+                        if let Err(e) = self.handle_p2p_message(inbound.message, Some(&inbound.sender)).instrument(span).await {
+                            error!("Failed to handle P2P message: {:?}", e);
+                        }
+                    }
+                    Err(e) => {
+                        error!("Error in P2P network: {:?}", e);
+                        self.idle(tokio::time::Duration::from_secs(1)).await;
+                    }
+                },
+            }
+        }
+        
+        p2p_network.shutdown().await
+    }
+
+    /// Publish P2P health, peer metrics and the peer list from the task that owns the network
+    async fn report_network_health(&self, p2p_network: &P2PNetwork) {
+        let result = p2p_network.health_check().await;
+        if let Err(e) = &result {
+            warn!("P2P health check failed: {:?}", e);
+        }
+        self.health.report(api::health::P2P, &result).await;
+        
+        let network_stats = p2p_network.get_network_stats();
+        metrics::metrics().record_network_stats(&network_stats);
+        metrics::metrics().record_gossip_stats(&p2p_network.get_gossip_stats());
+        self.status.update_peers(network_stats.peers).await;
+    }
+
+    async fn run_task_scheduler(self: Arc<Self>) -> Result<()> {
+        info!("Starting task scheduler...");
+        
+        while !self.shutdown.is_cancelled() {
+            self.work_scheduled_tasks().await;
+            tokio::select! {
+                _ = self.scheduler.wait() => {}
+                _ = self.shutdown.cancelled() => {}
+            }
+        }
+        
+        // Tasks still queued stay persisted and are picked up again after a restart
+        Ok(())
+    }
+
+    /// Work through the scheduled tasks earliest deadline first, dropping those that can no
+    /// longer be answered in time
+    async fn work_scheduled_tasks(&self) {
+        while !self.shutdown.is_cancelled() {
+            let popped = self.scheduler.pop(sim::clock::unix_secs());
+            let mut checkpoint = StateCheckpoint::new();
+            for task in popped.expired {
+                metrics::metrics().task_deadlines_missed.with_label_values(&["queued"]).inc();
+                warn!("Dropping task {}: its deadline {} is too close to start work", task.task_id, task.deadline);
+                checkpoint.finish_task(task.task_id);
+            }
+            
+            let Some(task) = popped.next else {
+                if let Err(e) = self.store.commit(checkpoint) {
+                    error!("Failed to commit state checkpoint: {:?}", e);
+                }
+                break;
+            };
+            self.work_task(task, &mut checkpoint).await;
+            if let Err(e) = self.store.commit(checkpoint) {
+                error!("Failed to commit state checkpoint: {:?}", e);
+            }
+        }
+    }
+
+    /// Answer `task`, cancelling the work once it runs past the task's latest start
+    async fn work_task(&self, task: ScheduledTask, checkpoint: &mut StateCheckpoint) {
+        // Responding is what risks slashing, so decline while exposure is at its limit
+        if let Err(e) = self.risk.check(self.identities.len() + 1) {
+            warn!("Declining task {}: {}", task.task_id, e);
+            checkpoint.finish_task(task.task_id);
+            return;
+        }
+        
+        let time_left = tokio::time::Duration::from_secs(
+            self.scheduler.latest_start(&task).saturating_sub(sim::clock::unix_secs()),
+        );
+        let ScheduledTask { task_id, orders_hash, deadline } = task;
+        let processed = tokio::time::timeout(
+            time_left,
+            self.process_matching_task(task_id.clone(), orders_hash, deadline, checkpoint),
+        ).await;
+        
+        match processed {
+            Ok(Ok(())) => checkpoint.finish_task(task_id),
+            // Left scheduled so it is retried after a restart
+            Ok(Err(e)) => error!("Failed to process task {}: {:?}", task_id, e),
+            Err(_) => {
+                metrics::metrics().task_deadlines_missed.with_label_values(&["processing"]).inc();
+                warn!(
+                    "Cancelled task {}: still processing {}s before its deadline {}",
+                    task_id, self.config.scheduler.deadline_margin_seconds, deadline,
+                );
+                checkpoint.finish_task(task_id);
+            }
+        }
+    }
+
+    async fn run_matching_engine(self: Arc<Self>) -> Result<()> {
+        info!("Starting matching engine...");
+        
+        while !self.shutdown.is_cancelled() {
+            if self.control.is_matching_paused() {
+                self.idle(tokio::time::Duration::from_millis(500)).await;
+                continue;
+            }
+            
+            self.run_matching_round().await;
+            self.idle(self.matching_engine.matching_interval().await).await;
+        }
+        
+        // Prove what is still waiting for its batch to fill
+        self.settle_proof_batches(true).await;
+        Ok(())
+    }
+
+    /// Match pending orders once, queue the matches for proving and settle the batches that are due
+    async fn run_matching_round(&self) {
+        match self.matching_engine.process_pending_orders().await {
+            Ok(matches) => {
+                for order_match in &matches {
+                    self.latency.mark_all([order_match.buy_order.id.as_str(), order_match.sell_order.id.as_str()], Stage::Matched);
+                }
+                self.proof_batches.push(matches);
+            }
+            Err(e) => {
+                error!("Error in matching engine: {:?}", e);
+            }
+        }
+        self.settle_proof_batches(false).await;
+    }
+
+    /// Prove and settle queued matches whose batches are due, or all of them with `flush`
+    async fn settle_proof_batches(&self, flush: bool) {
+        for (pool_key, matches) in self.proof_batches.due(flush) {
+            let settled = matches.clone();
+            if let Err(e) = self.handle_order_matches(&pool_key, matches).await {
+                error!("Failed to handle order matches in pool {}: {:?}", pool_key, e);
+            }
+            // Fills are only journaled now, so orders matched but not settled before a crash are
+            // matched again on restart. A failed batch isn't retried, so its fills count as well.
+            self.matching_engine.settle(&settled);
+        }
+    }
+
+    /// Process recorded inputs one at a time, running a matching round after each
+    /// so the outcome doesn't depend on timers
+    pub async fn replay(&self, records: Vec<replay::ReplayRecord>) -> Result<()> {
+        use replay::ReplayRecord;
+        
+        info!("Replaying {} recorded inputs", records.len());
+        
+        for (index, record) in records.into_iter().enumerate() {
+            sim::clock::tick();
+            let result = match record {
+                ReplayRecord::Ethereum { block, event } => {
+                    let mut checkpoint = StateCheckpoint::at_block(block);
+                    metrics::metrics().events_processed.with_label_values(&[event.name()]).inc();
+                    let handled = self.handle_ethereum_event(event, &mut checkpoint).await;
+                    let scheduled = checkpoint.scheduled_tasks.clone();
+                    self.store.commit(checkpoint)?;
+                    self.schedule_tasks(scheduled);
+                    handled
+                }
+                ReplayRecord::Gossip { message } => self.handle_p2p_message(message, None).await,
+            };
+            
+            if let Err(e) = result {
+                error!("Replayed input {} failed: {:?}", index + 1, e);
+            }
+            
+            self.work_scheduled_tasks().await;
+            self.run_matching_round().await;
+            self.settle_proof_batches(true).await;
+        }
+        
+        self.store.flush()
+    }
+
+    /// Checks every component except the P2P network, which reports from its own loop
+    async fn run_health_check(self: Arc<Self>) -> Result<()> {
+        info!("Starting health check...");
+        
+        while !self.shutdown.is_cancelled() {
+            // Perform health checks
+            let ethereum_result = self.ethereum_client.health_check().await;
+            let matching_result = self.matching_engine.health_check().await;
+            let prover_result = self.zk_prover.health_check().await;
+            
+            // Publish results for the /healthz and /readyz endpoints
+            self.health.report(api::health::ETHEREUM, &ethereum_result).await;
+            self.health.report(api::health::MATCHING, &matching_result).await;
+            self.health.report(api::health::PROVER, &prover_result).await;
+            
+            // Refresh per-pool metric breakdowns and the status board
+            let pools = match self.matching_engine.get_order_book_stats().await {
+                Ok(stats) => {
+                    metrics::metrics().record_order_book_stats(&stats);
+                    stats
+                }
+                Err(e) => {
+                    warn!("Failed to collect order book stats: {:?}", e);
+                    vec![]
+                }
+            };
+            self.status.update(
+                self.ethereum_client.last_processed_block(),
+                pools,
+                metrics::metrics().proofs_in_flight.get() as u64,
+                self.ethereum_client.get_pending_tasks().await.unwrap_or_default(),
+            ).await;
+            
+            if ethereum_result.is_err() || matching_result.is_err() || prover_result.is_err() {
+                warn!(
+                    "Health check failed - Ethereum: {}, Matching: {}, Prover: {}",
+                    ethereum_result.is_ok(), matching_result.is_ok(), prover_result.is_ok()
+                );
+            } else if self.health.snapshot().await.ready {
+                self.reregister().await;
+            }
+            
+            // The operator's stake bounds how much slashing exposure it takes on
+            if self.risk.is_enabled() {
+                match self.ethereum_client.operator_stake().await {
+                    Ok(stake) => self.risk.update_bond(stake),
+                    Err(e) => warn!("Failed to read operator stake for risk limits: {:?}", e),
+                }
+            }
+            
+            self.idle(tokio::time::Duration::from_secs(
+                self.config.api.health_check_interval_seconds,
+            )).await;
+        }
+        
+        Ok(())
+    }
+
+    /// Re-register deregistered identities whose cooldown has passed
+    async fn reregister(&self) {
+        for name in self.reregistration.due() {
+            info!("Re-registering identity '{}' with the AVS", name);
+            let result = if name == config::PRIMARY_IDENTITY {
+                self.ethereum_client.register_operator().await
+            } else {
+                match self.identities.iter().find(|identity| identity.name == name) {
+                    Some(identity) => identity.client.register_operator().await,
+                    None => continue,
+                }
+            };
+
+            match &result {
+                Ok(operator_id) => info!("Identity '{}' is registered again as operator {}", name, operator_id),
+                Err(e) => warn!("Re-registering identity '{}' failed, retrying after the cooldown: {:?}", name, e),
+            }
+            self.reregistration.attempted(&name, result.is_ok());
+        }
+    }
+
+    async fn relay_proof_submission(&self, submission: api::control::ProofSubmission) {
+        info!("Relaying externally submitted proof for task {}", submission.task_id);
+        
+        if let Err(e) = self.ensure_lease() {
+            let _ = submission.reply.send(Err(e));
+            return;
+        }
+        let result = self.ethereum_client.submit_matching_proof(
+            &submission.task_id,
+            submission.proof,
+            &submission.result_hash,
+            submission.signatures,
+        ).await;
+        
+        if let Ok(tx_hash) = &result {
+            self.status.record_submission(&submission.task_id, tx_hash, SubmissionKind::MatchingProof).await;
+        }
+        
+        // The caller may have given up waiting
+        let _ = submission.reply.send(result);
+    }
+
+    #[instrument(name = "event.ingest", skip_all, fields(event = event.name()))]
+    async fn handle_ethereum_event(
+        &self,
+        event: ethereum::EthereumEvent,
+        checkpoint: &mut StateCheckpoint,
+    ) -> Result<()> {
+        use ethereum::EthereumEvent;
+        
+        match event {
+            EthereumEvent::TaskCreated { task_id, orders_hash, deadline } => {
+                info!("New task created: {} with deadline {}", task_id, deadline);
+                self.event_publisher.publish(OperatorEvent::TaskCreated {
+                    task_id: task_id.clone(),
+                    orders_hash: orders_hash.clone(),
+                    deadline,
+                });
+                // Worked on by the task scheduler, most urgent first
+                checkpoint.schedule_task(ScheduledTask { task_id, orders_hash, deadline });
+            }
+            EthereumEvent::OrderStored { order_id, trader, encrypted_order } => {
+                info!("New order stored: {} from trader {}", order_id, trader);
+                self.event_publisher.publish(OperatorEvent::OrderReceived {
+                    order_id: order_id.clone(),
+                    source: "ethereum".to_string(),
+                });
+                // Add order to matching engine
+                self.latency.mark(&order_id, Stage::Received);
+                if let Some(order) = self.matching_engine.add_encrypted_order(order_id, encrypted_order).await? {
+                    self.latency.mark(&order.id, Stage::Decrypted);
+                    self.publish_receipt(&order.id);
+                }
+            }
+            EthereumEvent::OrderCancelled { order_id, trader } => {
+                info!("Order {} cancelled in the vault by trader {}", order_id, trader);
+                if let Some(order) = self.matching_engine.cancel_vault_order(&order_id).await? {
+                    self.event_publisher.publish(OperatorEvent::OrderCancelled { order_id: order.id });
+                }
+            }
+            EthereumEvent::TaskCompleted { task_id, result_hash } => {
+                info!("Task completed: {}", task_id);
+                checkpoint.confirm_submission(task_id.clone());
+                self.status.confirm_submission(&task_id).await;
+                self.latency.mark_task(&task_id, Stage::Confirmed);
+                self.matching_engine.tracker().settle_task(&task_id);
+                self.book_earnings(&task_id).await;
+                self.risk.closed(&task_id);
+                self.event_publisher.publish(OperatorEvent::TaskCompleted { task_id, result_hash });
+            }
+            EthereumEvent::OperatorDeregistered { operator } => {
+                // Deregistrations of other operators are of no concern
+                if let Some(identity) = self.reregistration.deregistered(&operator) {
+                    metrics::metrics().operator_deregistrations.with_label_values(&[&identity]).inc();
+                    if self.reregistration.is_enabled() {
+                        warn!(
+                            "Identity '{}' ({}) was deregistered from the AVS; re-registering once healthy, in {}s at the earliest",
+                            identity, operator, self.reregistration.cooldown_seconds(),
+                        );
+                    } else {
+                        warn!(
+                            "Identity '{}' ({}) was deregistered from the AVS; run `register --identity {}` to rejoin",
+                            identity, operator, identity,
+                        );
+                    }
+                    self.event_publisher.publish(OperatorEvent::OperatorDeregistered { identity, operator });
+                }
+            }
+            _ => {
+                // Handle other events
+            }
+        }
+        
+        Ok(())
+    }
+
+    /// Handle a message from peer `sender`, which is unknown for replayed messages
+    async fn handle_p2p_message(&self, message: networking::P2PMessage, sender: Option<&str>) -> Result<()> {
+        use networking::P2PMessage;
+        
+        match message {
+            P2PMessage::OrderGossip { order_id, encrypted_data, signature: _ } => {
+                info!("Received order gossip: {}", order_id);
+                self.event_publisher.publish(OperatorEvent::OrderReceived {
+                    order_id: order_id.clone(),
+                    source: "p2p".to_string(),
+                });
+                self.latency.mark(&order_id, Stage::Received);
+                if let Some(order) = self.matching_engine.add_encrypted_order(order_id, encrypted_data).await? {
+                    self.latency.mark(&order.id, Stage::Decrypted);
+                    self.publish_receipt(&order.id);
+                }
+            }
+            P2PMessage::MatchingResult { task_id, result, signature, bls_signature } => {
+                info!("Received matching result for task: {}", task_id);
+                self.handle_matching_result(task_id, result, signature, bls_signature, sender).await?;
+            }
+            P2PMessage::OrderCancellation { cancellation } => {
+                // Verified against the trader's signature, so a peer can't cancel orders it doesn't own
+                match self.matching_engine.cancel_order(&cancellation).await {
+                    Ok(Some(order)) => {
+                        self.event_publisher.publish(OperatorEvent::OrderCancelled { order_id: order.id });
+                    }
+                    Ok(None) => debug!("Gossiped cancellation of {} is for no pending order", cancellation.commitment),
+                    Err(e) => warn!("Refused gossiped cancellation of {}: {}", cancellation.commitment, e),
+                }
+            }
+            _ => {
+                // Handle other message types
+            }
+        }
+        
+        Ok(())
+    }
+
+    #[instrument(name = "order.settle", skip_all, fields(pool = %pool_key, matches = matches.len()))]
+    async fn handle_order_matches(&self, pool_key: &str, mut matches: Vec<matching::OrderMatch>) -> Result<()> {
+        info!("Processing {} order matches in pool {}", matches.len(), pool_key);
+        self.accountant.apply_rebates(&mut matches);
+        for order_match in &matches {
+            self.publish_match_found(order_match).await;
+        }
+        let order_ids: Vec<String> = matches.iter()
+            .flat_map(|m| [m.buy_order.id.clone(), m.sell_order.id.clone()])
+            .collect();
+        let fees = self.accountant.fees(&matches);
+        
+        // Generate one ZK proof for the batch, timed against its earliest deadline to tune the pool's batching
+        self.latency.mark_all(order_ids.iter().map(String::as_str), Stage::ProofStarted);
+        let deadline = matches.iter().map(tuning::earliest_deadline).min().unwrap_or_default();
+        let time_left = std::time::Duration::from_secs(deadline.saturating_sub(sim::clock::unix_secs()));
+        let started = sim::clock::elapsed();
+        let proof = self.zk_prover.generate_matching_proof(&matches, pool_key).await?;
+        self.proof_batches.observe(pool_key, matches.len(), sim::clock::elapsed().saturating_sub(started), time_left);
+        self.latency.mark_all(order_ids.iter().map(String::as_str), Stage::ProofFinished);
+        
+        // Submit proof to Ethereum - convert to expected format
+        self.ensure_lease()?;
+        let task_id = format!("task_{}", sim::entropy::uuid());
+        let mut tx_hashes = Vec::new();
+        for identity in &self.identities {
+            match identity.client.submit_matching_proof(&task_id, proof.proof_data.clone(), &proof.proof_id, vec![]).await {
+                Ok(tx_hash) => tx_hashes.push(tx_hash),
+                Err(e) => error!("Identity '{}' failed to submit proof for {}: {:?}", identity.name, task_id, e),
+            }
+        }
+        let tx_hash = self.ethereum_client.submit_matching_proof(&task_id, proof.proof_data, &proof.proof_id, vec![]).await?;
+        tx_hashes.push(tx_hash.clone());
+        
+        // submit_matching_proof only returns once the transaction is confirmed
+        self.latency.mark_all(order_ids.iter().map(String::as_str), Stage::Submitted);
+        self.latency.mark_all(order_ids.iter().map(String::as_str), Stage::Confirmed);
+        self.matching_engine.tracker().settled(&order_ids);
+        self.status.record_submission(&task_id, &tx_hash, SubmissionKind::MatchingProof).await;
+        if !self.ethereum_client.is_dry_run() {
+            self.accountant.submitted(&task_id, SubmittedTask::new(SubmissionKind::MatchingProof, tx_hashes, fees));
+            self.book_earnings(&task_id).await;
+        }
+        
+        self.event_publisher.publish(OperatorEvent::ProofSubmitted {
+            task_id,
+            proof_id: proof.proof_id,
+            tx_hash,
+        });
+        
+        Ok(())
+    }
+
+    #[instrument(name = "task.process", skip(self, checkpoint))]
+    async fn process_matching_task(
+        &self,
+        task_id: String,
+        orders_hash: String,
+        deadline: u64,
+        checkpoint: &mut StateCheckpoint,
+    ) -> Result<()> {
+        info!("Processing matching task: {}", task_id);
+        
+        // Get orders from vault
+        let orders = self.ethereum_client.retrieve_orders_for_task(&task_id).await?;
+        
+        // Decrypt orders, keeping only pools we serve
+        let mut decrypted_orders = self.decrypt_orders(&task_id, orders).await?;
+        let total_orders = decrypted_orders.len();
+        let pool_filter = self.matching_engine.pool_filter().await;
+        decrypted_orders.retain(|order| pool_filter.serves(&order.pool_key));
+        if total_orders > 0 && decrypted_orders.is_empty() {
+            info!("Declining task {}: none of its {} orders are in served pools", task_id, total_orders);
+            return Ok(());
+        }
+        self.latency.mark_all(decrypted_orders.iter().map(|order| order.id.as_str()), Stage::Decrypted);
+        
+        // Find matches
+        let mut matches = self.matching_engine.find_matches(decrypted_orders).await?;
+        self.accountant.apply_rebates(&mut matches);
+        
+        if !matches.is_empty() {
+            let order_ids: Vec<String> = matches.iter()
+                .flat_map(|m| [m.buy_order.id.clone(), m.sell_order.id.clone()])
+                .collect();
+            
+            self.latency.mark_all(order_ids.iter().map(String::as_str), Stage::Matched);
+            for order_match in &matches {
+                self.publish_match_found(order_match).await;
+            }
+            
+            // Generate proof for matches
+            self.latency.mark_all(order_ids.iter().map(String::as_str), Stage::ProofStarted);
+            let proof = self.zk_prover.generate_batch_proof(&matches).await?;
+            self.latency.mark_all(order_ids.iter().map(String::as_str), Stage::ProofFinished);
+            
+            // Submitted once operators holding the quorum's stake have signed the same result
+            let result = quorum::matches_result(&matches);
+            let digest = quorum::result_digest(&task_id, &result);
+            let mut response = self.results.propose(&task_id, digest, TaskResponse { matches, proof, deadline })
+                .and_then(|response| self.verify_agreed(&task_id, &result, response));
+            for (name, ethereum) in self.config.identity_configs() {
+                let signature = KeyManager::new().sign_digest(&digest, ethereum.private_key.expose())?;
+                let bls_signature = ethereum.bls_private_key.as_ref()
+                    .map(|bls_private_key| quorum::sign_result_bls(&task_id, &result, bls_private_key.expose()))
+                    .transpose()?;
+                if let Some(agreed) = self.count_vote(&task_id, &result, &ethereum.operator_address, bls_signature.clone()) {
+                    response = Some(agreed);
+                }
+                let message = P2PMessage::MatchingResult {
+                    task_id: task_id.clone(),
+                    result: result.clone(),
+                    signature,
+                    bls_signature,
+                };
+                if self.gossip_sender.send(message).is_err() {
+                    warn!("Identity '{}' result for task {} not gossiped: P2P task stopped", name, task_id);
+                }
+            }
+            if let Some(response) = response {
+                self.submit_task_response(task_id, response, checkpoint).await?;
+            }
+        }
+        
+        Ok(())
+    }
+
+    /// Fail once this instance's failover lease has run out: a standby may be active by then,
+    /// and the lease heartbeat stops this one only at its next tick
+    fn ensure_lease(&self) -> Result<()> {
+        match &self.lease {
+            Some(lease) if !lease.is_held() => Err(anyhow::anyhow!("Failover lease expired; not submitting")),
+            _ => Ok(()),
+        }
+    }
+
+    /// Submit this operator's agreed response to `task_id` from every identity
+    async fn submit_task_response(&self, task_id: String, response: TaskResponse, checkpoint: &mut StateCheckpoint) -> Result<()> {
+        self.ensure_lease()?;
+        let TaskResponse { matches, proof, deadline } = response;
+        let order_ids: Vec<String> = matches.iter()
+            .flat_map(|m| [m.buy_order.id.clone(), m.sell_order.id.clone()])
+            .collect();
+        let proof_id = proof.proof_id.clone();
+        let match_count = matches.len();
+        let fees = self.accountant.fees(&matches);
+        
+        // Submit to contract; every registration responds to the task with the same result
+        let mut tx_hashes = Vec::new();
+        for identity in &self.identities {
+            match identity.client.submit_task_response(&task_id, matches.clone(), proof.clone()).await {
+                Ok(tx_hash) => {
+                    info!("Identity '{}' submitted task {}: {}", identity.name, task_id, tx_hash);
+                    tx_hashes.push(tx_hash);
+                }
+                Err(e) => error!("Identity '{}' failed to submit task {}: {:?}", identity.name, task_id, e),
+            }
+        }
+        let tx_hash = self.ethereum_client.submit_task_response(&task_id, matches, proof).await?;
+        tx_hashes.push(tx_hash.clone());
+        
+        info!("Submitted {} matches for task {}", match_count, task_id);
+        // Nothing was broadcast in dry-run mode, so there is no confirmation to wait for
+        if !self.ethereum_client.is_dry_run() {
+            checkpoint.record_submission(PendingSubmission::new(
+                task_id.clone(),
+                tx_hash.clone(),
+                SubmissionKind::TaskResponse,
+            ));
+            self.risk.opened(&task_id, tx_hashes.len(), deadline);
+            self.accountant.submitted(&task_id, SubmittedTask::new(SubmissionKind::TaskResponse, tx_hashes, fees));
+        }
+        self.status.record_submission(&task_id, &tx_hash, SubmissionKind::TaskResponse).await;
+        
+        // Confirmation arrives later as a TaskCompleted event
+        self.matching_engine.tracker().link_task(&task_id, order_ids.clone());
+        self.latency.link_task(&task_id, order_ids);
+        self.latency.mark_task(&task_id, Stage::Submitted);
+        self.event_publisher.publish(OperatorEvent::ProofSubmitted {
+            task_id,
+            proof_id,
+            tx_hash,
+        });
+        
+        Ok(())
+    }
+
+    /// Book a confirmed task in the earnings ledger with the gas its transactions cost
+    async fn book_earnings(&self, task_id: &str) {
+        let Some(task) = self.accountant.take_submitted(task_id) else {
+            return;
+        };
+
+        let mut gas_wei = 0;
+        for tx_hash in &task.tx_hashes {
+            match self.ethereum_client.transaction_fee(tx_hash).await {
+                Ok(fee) => gas_wei += fee,
+                Err(e) => warn!("Gas for {} of task {} not booked: {:?}", tx_hash, task_id, e),
+            }
+        }
+        match self.accountant.book(task_id, task, gas_wei) {
+            Ok(entry) => info!("Booked task {}: reward {} wei, gas {} wei", task_id, entry.reward_wei, entry.gas_wei),
+            Err(e) => error!("Failed to book earnings for task {}: {:?}", task_id, e),
+        }
+    }
+
+    /// Hand the trader the receipt for an accepted order through the event bus
+    fn publish_receipt(&self, order_id: &str) {
+        if let Some(receipt) = self.matching_engine.tracker().receipt(order_id) {
+            self.event_publisher.publish(OperatorEvent::OrderAccepted { receipt });
+        }
+    }
+
+    async fn publish_match_found(&self, order_match: &matching::OrderMatch) {
+        self.status.record_match(order_match).await;
+        self.event_publisher.publish(OperatorEvent::MatchFound {
+            match_id: order_match.match_id.clone(),
+            pool_key: order_match.pool_key.clone(),
+            buy_order_id: order_match.buy_order.id.clone(),
+            sell_order_id: order_match.sell_order.id.clone(),
+            matched_amount: order_match.matched_amount,
+            matched_price: order_match.matched_price,
+        });
+    }
+
+    #[instrument(name = "order.decrypt", skip_all, fields(orders = encrypted_orders.len()))]
+    async fn decrypt_orders(&self, task_id: &str, encrypted_orders: Vec<Vec<u8>>) -> Result<Vec<matching::DecryptedOrder>> {
+        self.matching_engine.decrypt_task_orders(task_id, encrypted_orders).await
+    }
+
+    async fn handle_matching_result(
+        &self,
+        task_id: String,
+        result: Vec<u8>,
+        signature: Vec<u8>,
+        bls_signature: Option<Vec<u8>>,
+        sender: Option<&str>,
+    ) -> Result<()> {
+        let digest = quorum::result_digest(&task_id, &result);
+        let signer = matching::signing::recover_digest(&digest, &format!("0x{}", hex::encode(&signature)))?;
+        if !self.is_known_signer(&signer).await? {
+            debug!("Dropping result for task {} signed by unknown operator {}", task_id, signer);
+            return Ok(());
+        }
+        // Weighed from the next sync on; until then the signer counts for nothing
+        self.stakes.track(&signer);
+        if let Some(sender) = sender {
+            self.result_peers.lock().unwrap().entry(signer.clone()).or_default().insert(sender.to_string());
+        }
+        
+        if let Some(response) = self.count_vote(&task_id, &result, &signer, bls_signature) {
+            let mut checkpoint = StateCheckpoint::new();
+            self.submit_task_response(task_id, response, &mut checkpoint).await?;
+            self.store.commit(checkpoint)?;
+        }
+        Ok(())
+    }
+
+    /// Whether results signed by `signer` are counted: operators already weighed, committee
+    /// members and operators the stake registry weighs, whose reads are cached
+    async fn is_known_signer(&self, signer: &str) -> Result<bool> {
+        if self.stakes.is_tracked(signer)
+            || self.config.quorum.committee.iter().any(|member| member.operator.eq_ignore_ascii_case(signer))
+        {
+            return Ok(true);
+        }
+        Ok(self.ethereum_client.stake_of(signer).await? > 0)
+    }
+
+    /// Count `signer`'s vote for `result` as the answer to `task_id`, returning this operator's
+    /// response once the result it proposed is agreed and its signers' BLS signatures aggregate
+    fn count_vote(&self, task_id: &str, result: &[u8], signer: &str, bls_signature: Option<Vec<u8>>) -> Option<TaskResponse> {
+        let digest = quorum::result_digest(task_id, result);
+        let vote = self.results.record(task_id, digest, signer, bls_signature, &self.stakes);
+        let outcome = match &vote {
+            Vote::Duplicate => "duplicate",
+            Vote::Equivocation { .. } => "equivocation",
+            Vote::Counted { conflicting: true, .. } => "conflicting",
+            Vote::Counted { .. } => "counted",
+            Vote::Agreed { .. } => "agreed",
+        };
+        metrics::metrics().task_result_votes.with_label_values(&[outcome]).inc();
+        
+        match vote {
+            Vote::Duplicate => {
+                debug!("Ignoring repeated result from {} for task {}", signer, task_id);
+                None
+            }
+            Vote::Equivocation { previous } => {
+                warn!(
+                    "{} signed conflicting results for task {}; keeping its first, 0x{}",
+                    signer, task_id, hex::encode(previous)
+                );
+                None
+            }
+            Vote::Counted { support, conflicting } => {
+                if conflicting {
+                    warn!("Operators disagree on the result of task {}", task_id);
+                }
+                debug!("Result for task {} has {} of {} stake", task_id, support, self.stakes.total());
+                None
+            }
+            Vote::Agreed { support, response } => {
+                info!("Result for task {} reached quorum with {} of {} stake", task_id, support, self.stakes.total());
+                if response.is_none() {
+                    warn!("Task {} was agreed on a result this operator did not propose; not responding", task_id);
+                }
+                response.and_then(|response| self.verify_agreed(task_id, result, response))
+            }
+        }
+    }
+
+    /// `response` to `task_id` if the BLS signatures of the operators that agreed on `result`
+    /// aggregate into one their committee keys verify
+    fn verify_agreed(&self, task_id: &str, result: &[u8], response: TaskResponse) -> Option<TaskResponse> {
+        let signers = self.results.signers(task_id, quorum::result_digest(task_id, result));
+        match self.bls_committee.verify(task_id, result, &signers) {
+            Ok(true) => Some(response),
+            Ok(false) => {
+                error!("BLS signatures on the agreed result of task {} don't aggregate; not responding", task_id);
+                None
+            }
+            Err(e) => {
+                error!("BLS signatures on the agreed result of task {} are invalid: {}; not responding", task_id, e);
+                None
+            }
+        }
+    }
+}
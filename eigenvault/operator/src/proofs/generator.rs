@@ -1,12 +1,17 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Digest};
-use tracing::{debug, info, warn};
+use tracing::{debug, info, instrument};
 use ed25519_dalek::{SigningKey, VerifyingKey, Signature, Signer, Verifier};
 use rand::rngs::OsRng;
+use std::sync::Arc;
 
+use super::hashing::sha256_batch;
+use super::pool::ProvingPool;
 use crate::config::ProofConfig;
-use crate::matching::{OrderMatch, DecryptedOrder};
+use crate::matching::{Decimal, OrderMatch};
+use crate::metrics::metrics;
+use crate::sim::{clock, entropy};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatchingProof {
@@ -37,25 +42,37 @@ pub struct ProofCircuit {
     pub proving_key: Vec<u8>,
 }
 
+/// Proves matching results and signs the proofs with the operator's ed25519 key.
+///
+/// Proofs are built on the `ProvingPool` set by `proofs.proving_threads`, so a large batch
+/// doesn't stall the async workers gossip and matching run on.
+#[derive(Clone)]
 pub struct ZKProver {
     config: ProofConfig,
-    circuits: std::collections::HashMap<String, ProofCircuit>,
-    proving_keys: std::collections::HashMap<String, Vec<u8>>,
+    circuits: Arc<std::collections::HashMap<String, ProofCircuit>>,
+    proving_keys: Arc<std::collections::HashMap<String, Vec<u8>>>,
     signing_key: SigningKey,
+    pool: ProvingPool,
 }
 
 impl ZKProver {
+    /// Prover with a freshly generated signing key
     pub async fn new(config: ProofConfig) -> Result<Self> {
-        info!("Initializing ZK prover with config: {:?}", config);
-        
         // Generate a new signing key for this operator
-        let signing_key = SigningKey::from_bytes(&rand::random::<[u8; 32]>());
+        Self::with_signing_key(config, SigningKey::from_bytes(&entropy::bytes::<32>())).await
+    }
+
+    /// Prover that signs with an existing operator key
+    pub async fn with_signing_key(config: ProofConfig, signing_key: SigningKey) -> Result<Self> {
+        info!("Initializing ZK prover with config: {:?}", config);
         
+        let pool = ProvingPool::new(config.proving_threads)?;
         let mut prover = Self {
             config,
-            circuits: std::collections::HashMap::new(),
-            proving_keys: std::collections::HashMap::new(),
+            circuits: Arc::new(std::collections::HashMap::new()),
+            proving_keys: Arc::new(std::collections::HashMap::new()),
             signing_key,
+            pool,
         };
         
         // Load default circuits
@@ -76,12 +93,12 @@ impl ZKProver {
             proving_key: self.generate_proving_key("order_matching")?,
         };
         
-        self.circuits.insert(
+        Arc::make_mut(&mut self.circuits).insert(
             "order_matching".to_string(), 
             order_matching_circuit.clone()
         );
         
-        self.proving_keys.insert(
+        Arc::make_mut(&mut self.proving_keys).insert(
             "order_matching".to_string(),
             order_matching_circuit.proving_key.clone()
         );
@@ -94,11 +111,11 @@ impl ZKProver {
             proving_key: self.generate_proving_key("privacy_proof")?,
         };
         
-        self.circuits.insert(
+        Arc::make_mut(&mut self.circuits).insert(
             "privacy_proof".to_string(), privacy_circuit.clone()
         );
         
-        self.proving_keys.insert(
+        Arc::make_mut(&mut self.proving_keys).insert(
             "privacy_proof".to_string(),
             privacy_circuit.proving_key.clone()
         );
@@ -108,6 +125,7 @@ impl ZKProver {
     }
 
     /// Generate a proof for order matching
+    #[instrument(name = "proof.generate", skip(self, order_matches), fields(matches = order_matches.len()))]
     pub async fn generate_matching_proof(
         &self,
         order_matches: &[OrderMatch],
@@ -115,11 +133,32 @@ impl ZKProver {
     ) -> Result<MatchingProof> {
         info!("Generating matching proof for {} matches in pool {}", order_matches.len(), pool_key);
         
+        metrics().proofs_in_flight.inc();
+        let prover = self.clone();
+        let order_matches = order_matches.to_vec();
+        let pool_key = pool_key.to_string();
+        let result = self.pool
+            .run(move || prover.build_matching_proof(&order_matches, &pool_key))
+            .await
+            .and_then(|proof| proof);
+        metrics().proofs_in_flight.dec();
+        
+        result
+    }
+
+    fn build_matching_proof(
+        &self,
+        order_matches: &[OrderMatch],
+        pool_key: &str,
+    ) -> Result<MatchingProof> {
+        let timer = metrics().proof_duration_seconds.start_timer();
+        
         // Create proof ID
-        let proof_id = uuid::Uuid::new_v4().to_string();
+        let proof_id = entropy::uuid().to_string();
         
         // Generate actual proof data using the order matching circuit
-        let proof_data = self.generate_order_matching_proof(order_matches, pool_key).await?;
+        let now = clock::unix_secs();
+        let proof_data = self.generate_order_matching_proof(order_matches, pool_key, now)?;
         
         // Generate public inputs
         let public_inputs = self.generate_public_inputs(order_matches, pool_key)?;
@@ -138,10 +177,11 @@ impl ZKProver {
             proof_data,
             public_inputs,
             verification_key,
-            timestamp: chrono::Utc::now().timestamp() as u64,
+            timestamp: now,
             operator_signature,
         };
         
+        timer.observe_duration();
         info!("Generated proof {} with {} bytes", proof_id, proof.proof_data.len());
         Ok(proof)
     }
@@ -154,19 +194,20 @@ impl ZKProver {
         self.generate_matching_proof(order_matches, "batch_pool").await
     }
 
-    /// Generate actual order matching proof data
-    async fn generate_order_matching_proof(
+    /// Generate actual order matching proof data as of `now`
+    fn generate_order_matching_proof(
         &self,
         order_matches: &[OrderMatch],
         pool_key: &str,
+        now: u64,
     ) -> Result<Vec<u8>> {
         // In a real implementation, this would use a ZK-SNARK proving system
         // For now, we'll create a structured proof that can be verified
         
         let mut proof_data = Vec::new();
         
-        // Add circuit identifier
-        proof_data.extend_from_slice(b"ORDER_MATCHING_V1");
+        // Add circuit identifier; V2 public inputs carry fixed-point volumes instead of floats
+        proof_data.extend_from_slice(b"ORDER_MATCHING_V2");
         
         // Add pool key hash
         let pool_key_hash = self.hash_data(pool_key.as_bytes())?;
@@ -175,15 +216,15 @@ impl ZKProver {
         // Add match count
         proof_data.extend_from_slice(&(order_matches.len() as u32).to_le_bytes());
         
-        // Add each match's proof data
-        for match_data in order_matches {
-            let match_proof = self.generate_single_match_proof(match_data)?;
+        // Add each match's proof data, with the match IDs hashed as one batch
+        let match_id_hashes = sha256_batch(order_matches, |hasher, m| hasher.update(m.match_id.as_bytes()));
+        for (match_data, match_id_hash) in order_matches.iter().zip(&match_id_hashes) {
+            let match_proof = self.generate_single_match_proof(match_data, match_id_hash, now);
             proof_data.extend_from_slice(&match_proof);
         }
         
         // Add timestamp
-        let timestamp = chrono::Utc::now().timestamp() as u64;
-        proof_data.extend_from_slice(&timestamp.to_le_bytes());
+        proof_data.extend_from_slice(&now.to_le_bytes());
         
         // Add proof hash for verification
         let proof_hash = self.hash_data(&proof_data)?;
@@ -193,12 +234,11 @@ impl ZKProver {
     }
 
     /// Generate proof for a single order match
-    fn generate_single_match_proof(&self, order_match: &OrderMatch) -> Result<Vec<u8>> {
+    fn generate_single_match_proof(&self, order_match: &OrderMatch, match_id_hash: &[u8], now: u64) -> Vec<u8> {
         let mut proof = Vec::new();
         
         // Add match ID hash
-        let match_id_hash = self.hash_data(order_match.match_id.as_bytes())?;
-        proof.extend_from_slice(&match_id_hash);
+        proof.extend_from_slice(match_id_hash);
         
         // Add price validation (buy price >= sell price)
         let price_valid = order_match.matched_price >= 
@@ -216,12 +256,11 @@ impl ZKProver {
         proof.extend_from_slice(&[if buy_type_valid && sell_type_valid { 1 } else { 0 }]);
         
         // Add deadline validation
-        let now = chrono::Utc::now().timestamp() as u64;
         let deadline_valid = order_match.buy_order.deadline > now && 
                            order_match.sell_order.deadline > now;
         proof.extend_from_slice(&[if deadline_valid { 1 } else { 0 }]);
         
-        Ok(proof)
+        proof
     }
 
     /// Generate public inputs for the proof
@@ -238,19 +277,19 @@ impl ZKProver {
         // Add match count
         inputs.extend_from_slice(&(order_matches.len() as u32).to_le_bytes());
         
-        // Add total volume
-        let total_volume: f64 = order_matches.iter()
+        // Add total base volume, as 18-decimal fixed point
+        let base_volume: Decimal = order_matches.iter()
             .map(|m| m.matched_amount)
             .sum();
-        inputs.extend_from_slice(&total_volume.to_le_bytes());
+        inputs.extend_from_slice(&base_volume.raw().to_le_bytes());
         
-        // Add average price
-        let avg_price: f64 = if order_matches.is_empty() { 0.0 } else {
-            order_matches.iter()
-                .map(|m| m.matched_price * m.matched_amount)
-                .sum::<f64>() / total_volume
-        };
-        inputs.extend_from_slice(&avg_price.to_le_bytes());
+        // Add total quote volume, which the average price is derived from on chain
+        let quote_volume = order_matches.iter()
+            .try_fold(Decimal::ZERO, |total, m| {
+                m.matched_amount.checked_mul(m.matched_price).and_then(|quote| total.checked_add(quote))
+            })
+            .ok_or_else(|| anyhow::anyhow!("Quote volume of the batch overflows"))?;
+        inputs.extend_from_slice(&quote_volume.raw().to_le_bytes());
         
         Ok(inputs)
     }
@@ -290,6 +329,25 @@ impl ZKProver {
         Ok(hasher.finalize().to_vec())
     }
 
+    /// Health check for the ZK prover
+    pub async fn health_check(&self) -> Result<()> {
+        for circuit in ["order_matching", "privacy_proof"] {
+            if !self.circuits.contains_key(circuit) || !self.proving_keys.contains_key(circuit) {
+                return Err(anyhow::anyhow!("Circuit {} is not loaded", circuit));
+            }
+        }
+        
+        debug!("ZK prover health check passed. Circuits: {}", self.circuits.len());
+        Ok(())
+    }
+
+    /// Names of the loaded circuits, sorted
+    pub fn list_circuits(&self) -> Vec<String> {
+        let mut circuits: Vec<String> = self.circuits.keys().cloned().collect();
+        circuits.sort();
+        circuits
+    }
+
     /// Get the operator's public key
     pub fn get_public_key(&self) -> VerifyingKey {
         self.signing_key.verifying_key()
@@ -339,4 +397,18 @@ mod tests {
         assert!(circuits.contains(&"order_matching".to_string()));
         assert!(circuits.contains(&"privacy_proof".to_string()));
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_golden_proof() -> Result<()> {
+        let fixture = crate::fixtures::proof();
+        let signing_key: [u8; 32] = fixture.signing_key.as_slice().try_into()?;
+        let prover = ZKProver::with_signing_key(ProofConfig::default(), SigningKey::from_bytes(&signing_key)).await?;
+
+        let proof_data = prover.generate_order_matching_proof(&fixture.witness, &fixture.pool_key, fixture.now)?;
+        let public_inputs = prover.generate_public_inputs(&fixture.witness, &fixture.pool_key)?;
+        assert_eq!(hex::encode(&proof_data), hex::encode(&fixture.proof_data));
+        assert_eq!(hex::encode(&public_inputs), hex::encode(&fixture.public_inputs));
+        assert_eq!(prover.sign_proof(&proof_data, &public_inputs)?, fixture.operator_signature);
+        Ok(())
+    }
+}
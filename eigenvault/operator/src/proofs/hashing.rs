@@ -0,0 +1,62 @@
+use rayon::prelude::*;
+use sha2::{Digest, Sha256};
+
+/// Batches at least this large are split into chunks of this size and hashed on the rayon pool
+pub const PARALLEL_THRESHOLD: usize = 256;
+
+/// SHA-256 of every item, in order, with `feed` writing an item's fields into the hasher.
+///
+/// Each chunk reuses one hasher through `finalize_reset` rather than allocating one per item,
+/// and batches from `PARALLEL_THRESHOLD` up are hashed chunk by chunk in parallel. The digests
+/// are the same as hashing each item on its own.
+pub fn sha256_batch<T, F>(items: &[T], feed: F) -> Vec<Vec<u8>>
+where
+    T: Sync,
+    F: Fn(&mut Sha256, &T) + Sync,
+{
+    if items.len() < PARALLEL_THRESHOLD {
+        return hash_chunk(items, &feed);
+    }
+    items.par_chunks(PARALLEL_THRESHOLD)
+        .flat_map_iter(|chunk| hash_chunk(chunk, &feed))
+        .collect()
+}
+
+fn hash_chunk<T, F: Fn(&mut Sha256, &T)>(items: &[T], feed: &F) -> Vec<Vec<u8>> {
+    let mut hasher = Sha256::new();
+    items.iter()
+        .map(|item| {
+            feed(&mut hasher, item);
+            hasher.finalize_reset().to_vec()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sha256(data: &[u8]) -> Vec<u8> {
+        Sha256::digest(data).to_vec()
+    }
+
+    #[test]
+    fn test_batch_matches_individual_hashes() {
+        for count in [0, 3, PARALLEL_THRESHOLD * 2 + 7] {
+            let items: Vec<String> = (0..count).map(|i| format!("match_{}", i)).collect();
+            let hashes = sha256_batch(&items, |hasher, item| hasher.update(item.as_bytes()));
+            let expected: Vec<Vec<u8>> = items.iter().map(|item| sha256(item.as_bytes())).collect();
+            assert_eq!(hashes, expected);
+        }
+    }
+
+    #[test]
+    fn test_fields_fed_in_order() {
+        let items = [("a", 1u64), ("b", 2u64)];
+        let hashes = sha256_batch(&items, |hasher, (name, value)| {
+            hasher.update(name.as_bytes());
+            hasher.update(value.to_le_bytes());
+        });
+        assert_eq!(hashes[1], sha256(&[b"b".as_slice(), &2u64.to_le_bytes()].concat()));
+    }
+}
@@ -1,5 +1,10 @@
 pub mod generator;
+pub mod hashing;
+pub mod pool;
+pub mod tuning;
 pub mod verifier;
 
 pub use generator::{ZKProver, MatchingProof, BatchProof};
+pub use pool::ProvingPool;
+pub use tuning::{ProofBatcher, ProofBatching};
 pub use verifier::{ProofVerifier, VerificationResult};
\ No newline at end of file
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+
+/// Where proofs are generated, off the async workers that networking and matching run on
+#[derive(Clone)]
+pub enum ProvingPool {
+    /// The async runtime's blocking thread pool
+    Blocking,
+    /// Threads of its own, so proving never holds blocking threads other work waits on.
+    /// Batched hashing inside a proof runs on the same threads.
+    Dedicated(Arc<rayon::ThreadPool>),
+}
+
+impl ProvingPool {
+    /// Dedicated pool of `threads` threads, or the blocking pool if `threads` is 0
+    pub fn new(threads: usize) -> Result<Self> {
+        if threads == 0 {
+            return Ok(Self::Blocking);
+        }
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|index| format!("eigenvault-prover-{}", index))
+            .build()?;
+        Ok(Self::Dedicated(Arc::new(pool)))
+    }
+
+    /// Run `job` on the pool and wait for its result without blocking the caller's worker
+    pub async fn run<T, F>(&self, job: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        match self {
+            Self::Blocking => Ok(tokio::task::spawn_blocking(job).await?),
+            Self::Dedicated(pool) => {
+                let (result_tx, result_rx) = oneshot::channel();
+                pool.spawn(move || {
+                    let _ = result_tx.send(job());
+                });
+                result_rx.await.map_err(|_| anyhow::anyhow!("Proving job panicked"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_jobs_run_off_the_runtime() -> Result<()> {
+        let dedicated = ProvingPool::new(1)?;
+        let thread = dedicated.run(|| std::thread::current().name().map(str::to_string)).await?;
+        assert_eq!(thread.as_deref(), Some("eigenvault-prover-0"));
+
+        let caller = std::thread::current().id();
+        let blocking = ProvingPool::new(0)?;
+        assert_ne!(blocking.run(move || std::thread::current().id()).await?, caller);
+        Ok(())
+    }
+}
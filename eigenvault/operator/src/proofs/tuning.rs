@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::config::ProofTuningConfig;
+use crate::matching::OrderMatch;
+use crate::metrics::metrics;
+use crate::sim::clock;
+
+/// Weight of the newest measurement in a pool's smoothed proving time per match
+const SMOOTHING: f64 = 0.3;
+
+/// How a pool's matches are grouped into proofs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProofBatching {
+    /// Most matches proved together
+    pub batch_size: usize,
+    /// Longest a match waits for others to fill its batch
+    pub window: Duration,
+}
+
+struct PoolQueue {
+    batching: ProofBatching,
+    /// Smoothed proving time per match, once a proof has been measured
+    seconds_per_match: Option<f64>,
+    matches: Vec<OrderMatch>,
+    /// When the oldest queued match arrived
+    since: Duration,
+}
+
+/// Groups matches into per-pool proof batches and tunes each pool's batching from how long
+/// its proofs take against the deadlines they have to meet.
+///
+/// A proof that used more than `deadline_share` of the time left before its earliest order
+/// deadline halves the pool's batch size and window. One that used less than half of that
+/// grows them by a step, as long as the measured proving time says the bigger batch still fits.
+/// With tuning disabled every pool keeps the initial batching.
+pub struct ProofBatcher {
+    config: ProofTuningConfig,
+    pools: Mutex<BTreeMap<String, PoolQueue>>,
+}
+
+impl ProofBatcher {
+    pub fn new(config: ProofTuningConfig) -> Self {
+        Self {
+            config,
+            pools: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn initial(&self) -> PoolQueue {
+        PoolQueue {
+            batching: ProofBatching {
+                batch_size: self.config.initial_batch_size,
+                window: Duration::from_millis(self.config.initial_window_ms),
+            },
+            seconds_per_match: None,
+            matches: Vec::new(),
+            since: Duration::ZERO,
+        }
+    }
+
+    /// Current batching of `pool_key`
+    pub fn batching(&self, pool_key: &str) -> ProofBatching {
+        self.pools.lock().unwrap().get(pool_key)
+            .map(|pool| pool.batching)
+            .unwrap_or_else(|| self.initial().batching)
+    }
+
+    /// Queue matches to be proved with others from their pool
+    pub fn push(&self, matches: Vec<OrderMatch>) {
+        let now = clock::elapsed();
+        let mut pools = self.pools.lock().unwrap();
+        for order_match in matches {
+            let pool = pools.entry(order_match.pool_key.clone()).or_insert_with(|| self.initial());
+            if pool.matches.is_empty() {
+                pool.since = now;
+            }
+            pool.matches.push(order_match);
+        }
+    }
+
+    /// Queued matches whose proofs should start now, by pool and in batches of at most the
+    /// pool's batch size: a pool is due once it fills a batch, once its window has passed, or
+    /// once waiting out the window would leave its proof too little time. `flush` takes all.
+    pub fn due(&self, flush: bool) -> Vec<(String, Vec<OrderMatch>)> {
+        let now = clock::elapsed();
+        let unix_now = clock::unix_secs();
+        let mut due = Vec::new();
+
+        for (pool_key, pool) in self.pools.lock().unwrap().iter_mut() {
+            if pool.matches.is_empty() {
+                continue;
+            }
+            let wait_left = pool.batching.window.saturating_sub(now.saturating_sub(pool.since));
+            let proving = pool.seconds_per_match.unwrap_or(0.0) * pool.matches.len() as f64;
+            let time_left = pool.matches.iter().map(earliest_deadline).min().unwrap_or(u64::MAX).saturating_sub(unix_now);
+            let deadline_close = wait_left.as_secs_f64() + proving > time_left as f64 * self.config.deadline_share;
+
+            if !(flush || pool.matches.len() >= pool.batching.batch_size || wait_left.is_zero() || deadline_close) {
+                continue;
+            }
+            let mut matches = std::mem::take(&mut pool.matches);
+            while !matches.is_empty() {
+                let rest = matches.split_off(pool.batching.batch_size.min(matches.len()));
+                due.push((pool_key.clone(), std::mem::replace(&mut matches, rest)));
+            }
+        }
+        due
+    }
+
+    /// Record that proving `matches` matches of `pool_key` took `elapsed` with `time_left`
+    /// before the earliest of their deadlines, and retune the pool
+    pub fn observe(&self, pool_key: &str, matches: usize, elapsed: Duration, time_left: Duration) {
+        let mut pools = self.pools.lock().unwrap();
+        let pool = pools.entry(pool_key.to_string()).or_insert_with(|| self.initial());
+
+        let sample = elapsed.as_secs_f64() / matches.max(1) as f64;
+        let seconds_per_match = match pool.seconds_per_match {
+            Some(previous) => SMOOTHING * sample + (1.0 - SMOOTHING) * previous,
+            None => sample,
+        };
+        pool.seconds_per_match = Some(seconds_per_match);
+        if !self.config.enabled {
+            return;
+        }
+
+        let batching = retune(&self.config, pool.batching, seconds_per_match, elapsed, time_left);
+        if batching != pool.batching {
+            debug!("Proof batching for pool {} now {:?} ({:.3}s per match)", pool_key, batching, seconds_per_match);
+            pool.batching = batching;
+        }
+        metrics().proof_batch_size.with_label_values(&[pool_key]).set(batching.batch_size as i64);
+        metrics().proof_batch_window_seconds.with_label_values(&[pool_key]).set(batching.window.as_secs_f64());
+    }
+}
+
+/// Unix time by which a match has to be settled
+pub fn earliest_deadline(order_match: &OrderMatch) -> u64 {
+    order_match.buy_order.deadline.min(order_match.sell_order.deadline)
+}
+
+/// Batching after a proof that took `elapsed` with `time_left` before its deadline
+fn retune(
+    config: &ProofTuningConfig,
+    current: ProofBatching,
+    seconds_per_match: f64,
+    elapsed: Duration,
+    time_left: Duration,
+) -> ProofBatching {
+    let budget = time_left.as_secs_f64() * config.deadline_share;
+    let max_window = Duration::from_millis(config.max_window_ms);
+
+    let mut next = if elapsed.as_secs_f64() > budget {
+        ProofBatching { batch_size: current.batch_size / 2, window: current.window / 2 }
+    } else if elapsed.as_secs_f64() < budget / 2.0 {
+        ProofBatching { batch_size: current.batch_size + 1, window: (current.window + max_window / 10).min(max_window) }
+    } else {
+        current
+    };
+
+    // Never more matches than the measured rate proves within the budget
+    if seconds_per_match > 0.0 {
+        next.batch_size = next.batch_size.min((budget / seconds_per_match) as usize);
+    }
+    next.batch_size = next.batch_size.clamp(config.min_batch_size, config.max_batch_size);
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matching::{Decimal, Order, OrderType};
+    use crate::sim::entropy;
+    use std::sync::Arc;
+
+    fn order_match(pool_key: &str, deadline: u64) -> OrderMatch {
+        let order = |order_type| Arc::new(Order::new(
+            entropy::uuid().to_string(),
+            "trader".to_string(),
+            pool_key.to_string(),
+            order_type,
+            Decimal::ONE,
+            Decimal::from(2000),
+            deadline,
+        ));
+        OrderMatch {
+            match_id: entropy::uuid().to_string(),
+            buy_order: order(OrderType::Buy),
+            sell_order: order(OrderType::Sell),
+            matched_price: Decimal::from(2000),
+            matched_amount: Decimal::ONE,
+            timestamp: clock::unix_secs(),
+            pool_key: pool_key.to_string(),
+            rebates: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_retune_shrinks_on_missed_budget_and_grows_with_slack() {
+        let config = ProofTuningConfig { max_window_ms: 1_000, ..ProofTuningConfig::default() };
+        let current = ProofBatching { batch_size: 8, window: Duration::from_millis(400) };
+        let time_left = Duration::from_secs(10);
+
+        let slow = retune(&config, current, 0.1, Duration::from_secs(6), time_left);
+        assert_eq!(slow, ProofBatching { batch_size: 4, window: Duration::from_millis(200) });
+
+        let fast = retune(&config, current, 0.1, Duration::from_secs(1), time_left);
+        assert_eq!(fast, ProofBatching { batch_size: 9, window: Duration::from_millis(500) });
+
+        // 9 matches at 1s each won't fit in a 5s budget however fast the last proof was
+        let capped = retune(&config, current, 1.0, Duration::from_secs(1), time_left);
+        assert_eq!(capped.batch_size, 5);
+    }
+
+    #[test]
+    fn test_matches_wait_for_batch_or_deadline() {
+        let config = ProofTuningConfig {
+            initial_batch_size: 2,
+            initial_window_ms: 60_000,
+            ..ProofTuningConfig::default()
+        };
+        let batcher = ProofBatcher::new(config);
+        let later = clock::unix_secs() + 3600;
+
+        batcher.push(vec![order_match("ETH_USDC", later)]);
+        assert!(batcher.due(false).is_empty());
+
+        // A match close to its deadline can't wait out the window
+        batcher.push(vec![order_match("WBTC_USDC", clock::unix_secs() + 10)]);
+        assert_eq!(batcher.due(false).len(), 1);
+
+        batcher.push(vec![order_match("ETH_USDC", later), order_match("ETH_USDC", later)]);
+        let due = batcher.due(false);
+        assert_eq!(due.iter().map(|(_, batch)| batch.len()).collect::<Vec<_>>(), vec![2, 1]);
+        assert!(batcher.due(true).is_empty());
+    }
+}
@@ -4,7 +4,9 @@ use sha2::{Sha256, Digest};
 use tracing::{debug, info, warn};
 
 use super::{MatchingProof, BatchProof};
+use super::hashing::sha256_batch;
 use crate::config::ProofConfig;
+use crate::sim::clock;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum VerificationResult {
@@ -122,7 +124,7 @@ impl ProofVerifier {
         let report = VerificationReport {
             proof_id: proof.proof_id.clone(),
             result,
-            verified_at: chrono::Utc::now().timestamp() as u64,
+            verified_at: clock::unix_secs(),
             verifier_id: "eigenvault_verifier_v1".to_string(),
             gas_cost_estimate: Some(gas_cost_estimate),
         };
@@ -199,7 +201,7 @@ impl ProofVerifier {
         let report = VerificationReport {
             proof_id: batch_proof.batch_id.clone(),
             result,
-            verified_at: chrono::Utc::now().timestamp() as u64,
+            verified_at: clock::unix_secs(),
             verifier_id: "eigenvault_batch_verifier_v1".to_string(),
             gas_cost_estimate: Some(gas_cost_estimate),
         };
@@ -245,7 +247,7 @@ impl ProofVerifier {
         }
         
         // Check timestamp is reasonable
-        let current_time = chrono::Utc::now().timestamp() as u64;
+        let current_time = clock::unix_secs();
         if proof.timestamp > current_time + 300 { // Allow 5 minutes clock skew
             return Ok(VerificationResult::Invalid {
                 reason: "Proof timestamp in future".to_string()
@@ -339,7 +341,7 @@ impl ProofVerifier {
 
     /// Verify timestamp validity
     async fn verify_timestamp(&self, timestamp: u64) -> Result<VerificationResult> {
-        let current_time = chrono::Utc::now().timestamp() as u64;
+        let current_time = clock::unix_secs();
         
         // Allow reasonable time window
         if timestamp > current_time + 300 { // 5 minutes future
@@ -486,8 +488,7 @@ impl ProofVerifier {
         aggregated.extend((individual_proofs.len() as u64).to_le_bytes());
         
         // Hash of all proofs
-        for proof in individual_proofs {
-            let proof_hash = self.hash_data(&proof.proof_data)?;
+        for proof_hash in sha256_batch(individual_proofs, |hasher, proof| hasher.update(&proof.proof_data)) {
             aggregated.extend(proof_hash);
         }
         
@@ -537,7 +538,7 @@ impl ProofVerifier {
             proof_data: vec![0u8; 1024],
             public_inputs: vec![1, 2, 3, 4],
             verification_key: vec![1, 2, 3, 4],
-            timestamp: chrono::Utc::now().timestamp() as u64,
+            timestamp: clock::unix_secs(),
             operator_signature: vec![5, 6, 7, 8],
         };
         
@@ -0,0 +1,384 @@
+use anyhow::Result;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::Mutex;
+
+use sha3::{Digest, Keccak256};
+
+use crate::config::keys::{aggregate_bls_signatures, verify_bls_aggregate};
+use crate::config::{KeyManager, QuorumConfig};
+use crate::matching::OrderMatch;
+
+/// Tasks whose results are kept; older ones are dropped as new tasks arrive
+const MAX_TRACKED_TASKS: usize = 1024;
+
+/// What operators agree on for a task's matches: the orders matched, at what amount and price.
+/// Match IDs and timestamps differ between operators, so they are left out.
+pub fn matches_result(matches: &[OrderMatch]) -> Vec<u8> {
+    let mut fills: Vec<_> = matches
+        .iter()
+        .map(|m| (m.buy_order.id.as_str(), m.sell_order.id.as_str(), m.matched_amount, m.matched_price))
+        .collect();
+    fills.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+    serde_json::to_vec(&fills).expect("serializable")
+}
+
+/// Digest an operator signs to vouch for `result` as its answer to `task_id`
+pub fn result_digest(task_id: &str, result: &[u8]) -> [u8; 32] {
+    Keccak256::new().chain_update(task_id.as_bytes()).chain_update(result).finalize().into()
+}
+
+/// BLS signature vouching for `result` as the answer to `task_id`. The committee's signatures on
+/// one result aggregate into a single one, checked with `verify_result_aggregate`.
+pub fn sign_result_bls(task_id: &str, result: &[u8], bls_private_key: &str) -> Result<Vec<u8>> {
+    KeyManager::new().sign_bls(&result_digest(task_id, result), bls_private_key)
+}
+
+/// Whether `signature` aggregates BLS signatures on `result` for `task_id` by every operator of
+/// `public_keys`, whose proofs of possession were checked when their keys were registered
+pub fn verify_result_aggregate(task_id: &str, result: &[u8], signature: &[u8], public_keys: &[String]) -> Result<bool> {
+    verify_bls_aggregate(&result_digest(task_id, result), signature, public_keys)
+}
+
+/// BLS public keys of the committee members listed with one, checked against their proofs of
+/// possession when the config was loaded
+pub struct BlsCommittee {
+    /// Public key by lowercase operator address
+    keys: HashMap<String, String>,
+}
+
+impl BlsCommittee {
+    pub fn new(config: &QuorumConfig) -> Self {
+        let keys = config.committee.iter()
+            .filter_map(|member| Some((member.operator.to_lowercase(), member.bls_public_key.clone()?)))
+            .collect();
+        Self { keys }
+    }
+
+    /// Whether the BLS signatures of `signers` on `result` for `task_id` aggregate into one that
+    /// verifies against their listed keys. Every signer listed with a key must have signed with
+    /// it; signers without one are left out, so the check passes when none is listed.
+    pub fn verify(&self, task_id: &str, result: &[u8], signers: &[(String, Option<Vec<u8>>)]) -> Result<bool> {
+        let mut public_keys = Vec::new();
+        let mut signatures = Vec::new();
+        for (signer, signature) in signers {
+            let Some(public_key) = self.keys.get(&signer.to_lowercase()) else {
+                continue;
+            };
+            let Some(signature) = signature else {
+                return Ok(false);
+            };
+            public_keys.push(public_key.clone());
+            signatures.push(signature.clone());
+        }
+        if signatures.is_empty() {
+            return Ok(true);
+        }
+        verify_result_aggregate(task_id, result, &aggregate_bls_signatures(&signatures)?, &public_keys)
+    }
+}
+
+/// Stake weights of this operator's identities and of the peers whose results it has seen.
+///
+/// The stake sync task refreshes every tracked operator from the stake registry; until an
+/// operator's first sync its weight is 0, so unknown signers never count toward a quorum.
+#[derive(Default)]
+pub struct StakeTable {
+    state: Mutex<StakeState>,
+}
+
+#[derive(Default)]
+struct StakeState {
+    /// Stake in wei by lowercase operator address
+    weights: HashMap<String, u128>,
+    total: u128,
+}
+
+impl StakeTable {
+    pub fn new(operators: impl IntoIterator<Item = String>) -> Self {
+        let table = Self::default();
+        for operator in operators {
+            table.track(&operator);
+        }
+        table
+    }
+
+    /// Include `operator` in the next sync
+    pub fn track(&self, operator: &str) {
+        self.state.lock().unwrap().weights.entry(operator.to_lowercase()).or_insert(0);
+    }
+
+    pub fn is_tracked(&self, operator: &str) -> bool {
+        self.state.lock().unwrap().weights.contains_key(&operator.to_lowercase())
+    }
+
+    pub fn operators(&self) -> Vec<String> {
+        self.state.lock().unwrap().weights.keys().cloned().collect()
+    }
+
+    /// Replace the synced weights and the registry's total
+    pub fn update(&self, weights: HashMap<String, u128>, total: u128) {
+        let mut state = self.state.lock().unwrap();
+        for (operator, weight) in weights {
+            state.weights.insert(operator.to_lowercase(), weight);
+        }
+        state.total = total;
+    }
+
+    pub fn weight(&self, operator: &str) -> u128 {
+        self.state.lock().unwrap().weights.get(&operator.to_lowercase()).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> u128 {
+        self.state.lock().unwrap().total
+    }
+}
+
+/// How a gossiped result was counted
+#[derive(Debug, PartialEq)]
+pub enum Vote<R> {
+    /// The signer already signed this result
+    Duplicate,
+    /// The signer already signed a different result for the task; its first vote stands
+    Equivocation { previous: [u8; 32] },
+    /// Counted toward the result, which has `support` stake; `conflicting` when operators have
+    /// signed different results for the task
+    Counted { support: u128, conflicting: bool },
+    /// Counted, and the result reached quorum with `support` stake. `response` is this
+    /// operator's own response to the task if it proposed the agreed result.
+    Agreed { support: u128, response: Option<R> },
+}
+
+/// Per-task state of the results the committee gossiped: which operators signed which result
+/// and the stake behind each. A result is agreed once its signers hold `threshold_bps` of the
+/// total stake; this operator's own response is held back until then.
+pub struct TaskAggregator<R> {
+    threshold_bps: u64,
+    tasks: Mutex<Tasks<R>>,
+}
+
+struct Tasks<R> {
+    states: HashMap<String, TaskState<R>>,
+    /// Task IDs oldest first
+    order: VecDeque<String>,
+}
+
+struct TaskState<R> {
+    /// Signers by result hash
+    results: HashMap<[u8; 32], BTreeSet<String>>,
+    /// Result hash each signer signed
+    votes: HashMap<String, [u8; 32]>,
+    /// BLS signature each signer gossiped with its counted vote
+    bls_signatures: HashMap<String, Vec<u8>>,
+    /// Result hash this operator proposed and the response to submit once it is agreed
+    local: Option<([u8; 32], R)>,
+    agreed: Option<[u8; 32]>,
+}
+
+impl<R> Default for TaskState<R> {
+    fn default() -> Self {
+        Self { results: HashMap::new(), votes: HashMap::new(), bls_signatures: HashMap::new(), local: None, agreed: None }
+    }
+}
+
+impl<R> TaskAggregator<R> {
+    pub fn new(config: &QuorumConfig) -> Self {
+        Self {
+            threshold_bps: config.threshold_bps,
+            tasks: Mutex::new(Tasks { states: HashMap::new(), order: VecDeque::new() }),
+        }
+    }
+
+    /// Hold this operator's `response` to `task_id`, whose result hashes to `result_hash`, until
+    /// that result is agreed. Returns it at once if it already is.
+    pub fn propose(&self, task_id: &str, result_hash: [u8; 32], response: R) -> Option<R> {
+        let mut tasks = self.tasks.lock().unwrap();
+        let task = tasks.state(task_id);
+        match task.agreed {
+            Some(agreed) if agreed == result_hash => Some(response),
+            Some(_) => None,
+            None => {
+                task.local = Some((result_hash, response));
+                None
+            }
+        }
+    }
+
+    /// Count `signer`'s signature on the result hashed to `result_hash` for `task_id`, weighed
+    /// by `stakes`, keeping the BLS signature it came with
+    pub fn record(
+        &self,
+        task_id: &str,
+        result_hash: [u8; 32],
+        signer: &str,
+        bls_signature: Option<Vec<u8>>,
+        stakes: &StakeTable,
+    ) -> Vote<R> {
+        let signer = signer.to_lowercase();
+        let mut tasks = self.tasks.lock().unwrap();
+        let task = tasks.state(task_id);
+        match task.votes.get(&signer) {
+            Some(previous) if *previous == result_hash => return Vote::Duplicate,
+            Some(previous) => return Vote::Equivocation { previous: *previous },
+            None => {}
+        }
+        task.votes.insert(signer.clone(), result_hash);
+        if let Some(bls_signature) = bls_signature {
+            task.bls_signatures.insert(signer.clone(), bls_signature);
+        }
+        let signers = task.results.entry(result_hash).or_default();
+        signers.insert(signer);
+
+        let support: u128 = signers.iter().map(|signer| stakes.weight(signer)).sum();
+        let conflicting = task.results.len() > 1;
+        let total = stakes.total();
+        let reached = total > 0
+            && support.saturating_mul(10_000) >= total.saturating_mul(self.threshold_bps as u128);
+        if task.agreed.is_some() || !reached {
+            return Vote::Counted { support, conflicting };
+        }
+
+        task.agreed = Some(result_hash);
+        let response = match task.local.take() {
+            Some((local, response)) if local == result_hash => Some(response),
+            _ => None,
+        };
+        Vote::Agreed { support, response }
+    }
+
+    /// Operators that signed the result hashed to `result_hash` for `task_id`, with the BLS
+    /// signature each gossiped
+    pub fn signers(&self, task_id: &str, result_hash: [u8; 32]) -> Vec<(String, Option<Vec<u8>>)> {
+        let tasks = self.tasks.lock().unwrap();
+        let Some(task) = tasks.states.get(task_id) else {
+            return Vec::new();
+        };
+        task.results.get(&result_hash).into_iter().flatten()
+            .map(|signer| (signer.clone(), task.bls_signatures.get(signer).cloned()))
+            .collect()
+    }
+}
+
+impl<R> Tasks<R> {
+    /// State of `task_id`, dropping the oldest task when a new one exceeds the limit
+    fn state(&mut self, task_id: &str) -> &mut TaskState<R> {
+        if !self.states.contains_key(task_id) {
+            self.order.push_back(task_id.to_string());
+            if self.order.len() > MAX_TRACKED_TASKS {
+                let oldest = self.order.pop_front().expect("over the limit");
+                self.states.remove(&oldest);
+            }
+        }
+        self.states.entry(task_id.to_string()).or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETH: u128 = 1_000_000_000_000_000_000;
+
+    fn stakes() -> StakeTable {
+        let stakes = StakeTable::new(["0xA".to_string(), "0xb".to_string(), "0xc".to_string()]);
+        stakes.update(
+            HashMap::from([("0xa".to_string(), 50 * ETH), ("0xb".to_string(), 20 * ETH), ("0xc".to_string(), 30 * ETH)]),
+            100 * ETH,
+        );
+        stakes
+    }
+
+    #[test]
+    fn test_result_signatures_aggregate() -> Result<()> {
+        use crate::config::keys::aggregate_bls_signatures;
+
+        let private_keys = [format!("0x{}", "11".repeat(32)), format!("0x{}", "22".repeat(32))];
+        let public_keys = private_keys.iter()
+            .map(|private_key| KeyManager::new().bls_public_key(private_key))
+            .collect::<Result<Vec<_>>>()?;
+        let signatures = private_keys.iter()
+            .map(|private_key| sign_result_bls("task_1", b"result", private_key))
+            .collect::<Result<Vec<_>>>()?;
+        let aggregate = aggregate_bls_signatures(&signatures)?;
+
+        assert!(verify_result_aggregate("task_1", b"result", &aggregate, &public_keys)?);
+        assert!(!verify_result_aggregate("task_2", b"result", &aggregate, &public_keys)?);
+        assert!(!verify_result_aggregate("task_1", b"other", &aggregate, &public_keys)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_committee_checks_the_signers_bls_aggregate() -> Result<()> {
+        use crate::config::CommitteeMember;
+
+        let private_keys = [format!("0x{}", "11".repeat(32)), format!("0x{}", "22".repeat(32))];
+        let committee = ["0xA", "0xb"].iter().zip(&private_keys)
+            .map(|(operator, private_key)| Ok(CommitteeMember {
+                operator: operator.to_string(),
+                bls_public_key: Some(KeyManager::new().bls_public_key(private_key)?),
+                bls_possession_proof: None,
+            }))
+            .collect::<Result<Vec<_>>>()?;
+        let committee = BlsCommittee::new(&QuorumConfig { committee, ..QuorumConfig::default() });
+        let signature = |private_key: &str| sign_result_bls("task_1", b"result", private_key).map(Some);
+
+        let signers = vec![
+            ("0xa".to_string(), signature(&private_keys[0])?),
+            ("0xb".to_string(), signature(&private_keys[1])?),
+            ("0xc".to_string(), None),
+        ];
+        assert!(committee.verify("task_1", b"result", &signers)?);
+        assert!(!committee.verify("task_1", b"other", &signers)?);
+        // A listed member that gossiped no BLS signature, or another member's, fails the check
+        assert!(!committee.verify("task_1", b"result", &[("0xa".to_string(), None)])?);
+        assert!(!committee.verify("task_1", b"result", &[("0xb".to_string(), signers[0].1.clone())])?);
+        // Nothing to check when no signer is listed with a key
+        assert!(committee.verify("task_1", b"result", &[("0xc".to_string(), None)])?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_quorum_is_reached_by_stake_not_signer_count() {
+        let aggregator = TaskAggregator::<()>::new(&QuorumConfig::default());
+        let stakes = stakes();
+
+        assert_eq!(aggregator.record("task_1", [1; 32], "0xb", None, &stakes), Vote::Counted { support: 20 * ETH, conflicting: false });
+        assert_eq!(aggregator.record("task_1", [1; 32], "0xc", None, &stakes), Vote::Counted { support: 50 * ETH, conflicting: false });
+        assert_eq!(aggregator.record("task_1", [1; 32], "0xA", None, &stakes), Vote::Agreed { support: 100 * ETH, response: None });
+    }
+
+    #[test]
+    fn test_repeated_and_changed_votes_are_not_counted() {
+        let aggregator = TaskAggregator::<()>::new(&QuorumConfig::default());
+        let stakes = stakes();
+
+        aggregator.record("task_1", [1; 32], "0xa", None, &stakes);
+        assert_eq!(aggregator.record("task_1", [1; 32], "0xA", None, &stakes), Vote::Duplicate);
+        assert_eq!(aggregator.record("task_1", [2; 32], "0xa", None, &stakes), Vote::Equivocation { previous: [1; 32] });
+        assert_eq!(aggregator.record("task_1", [1; 32], "0xb", None, &stakes), Vote::Agreed { support: 70 * ETH, response: None });
+    }
+
+    #[test]
+    fn test_conflicting_results_are_weighed_separately() {
+        let aggregator = TaskAggregator::<()>::new(&QuorumConfig { threshold_bps: 5_000, ..QuorumConfig::default() });
+        let stakes = stakes();
+
+        aggregator.record("task_1", [1; 32], "0xb", None, &stakes);
+        assert_eq!(aggregator.record("task_1", [2; 32], "0xc", None, &stakes), Vote::Counted { support: 30 * ETH, conflicting: true });
+        assert_eq!(aggregator.record("task_1", [2; 32], "0xunknown", None, &stakes), Vote::Counted { support: 30 * ETH, conflicting: true });
+        assert_eq!(aggregator.record("task_1", [1; 32], "0xa", None, &stakes), Vote::Agreed { support: 70 * ETH, response: None });
+    }
+
+    #[test]
+    fn test_own_response_is_released_only_for_the_agreed_result() {
+        let aggregator = TaskAggregator::new(&QuorumConfig::default());
+        let stakes = stakes();
+
+        assert_eq!(aggregator.propose("task_1", [1; 32], "response"), None);
+        aggregator.record("task_1", [1; 32], "0xa", None, &stakes);
+        assert_eq!(aggregator.record("task_1", [1; 32], "0xb", Some(vec![2]), &stakes), Vote::Agreed { support: 70 * ETH, response: Some("response") });
+        assert_eq!(aggregator.signers("task_1", [1; 32]), vec![("0xa".to_string(), None), ("0xb".to_string(), Some(vec![2]))]);
+        // Proposed after the committee already agreed
+        assert_eq!(aggregator.propose("task_1", [1; 32], "late"), Some("late"));
+        assert_eq!(aggregator.propose("task_1", [2; 32], "wrong"), None);
+    }
+}
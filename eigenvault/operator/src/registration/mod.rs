@@ -0,0 +1,106 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use crate::config::{ReregistrationConfig, Settings};
+use crate::sim::clock;
+
+/// Tracks identities the AVS registry deregistered until they are registered again.
+///
+/// A re-registration only becomes due `cooldown_seconds` after the deregistration or after the
+/// last failed attempt, so an operator that keeps being ejected isn't churned in and out.
+pub struct Reregistration {
+    config: ReregistrationConfig,
+    /// Identity name by lowercase operator address
+    identities: HashMap<String, String>,
+    /// Deregistered identities and when each may next be re-registered (unix secs)
+    pending: Mutex<BTreeMap<String, u64>>,
+}
+
+impl Reregistration {
+    pub fn new(settings: &Settings) -> Self {
+        let identities = settings.identity_configs().into_iter()
+            .map(|(name, ethereum)| (ethereum.operator_address.to_lowercase(), name))
+            .collect();
+
+        Self {
+            config: settings.reregistration.clone(),
+            identities,
+            pending: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Note that `operator` was deregistered, returning the identity it belongs to; `None` when
+    /// it isn't one of ours
+    pub fn deregistered(&self, operator: &str) -> Option<String> {
+        let name = self.identities.get(&operator.to_lowercase())?.clone();
+        self.pending.lock().unwrap().insert(name.clone(), clock::unix_secs() + self.config.cooldown_seconds);
+        Some(name)
+    }
+
+    /// Deregistered identities whose cooldown has passed; always empty unless automatic
+    /// re-registration is enabled
+    pub fn due(&self) -> Vec<String> {
+        if !self.config.enabled {
+            return Vec::new();
+        }
+
+        let now = clock::unix_secs();
+        self.pending.lock().unwrap().iter()
+            .filter(|(_, not_before)| **not_before <= now)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// Record an attempt to re-register `identity`; after a failure it waits out another cooldown
+    pub fn attempted(&self, identity: &str, registered: bool) {
+        let mut pending = self.pending.lock().unwrap();
+        if registered {
+            pending.remove(identity);
+        } else if let Some(not_before) = pending.get_mut(identity) {
+            *not_before = clock::unix_secs() + self.config.cooldown_seconds;
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    pub fn cooldown_seconds(&self) -> u64 {
+        self.config.cooldown_seconds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PRIMARY_IDENTITY;
+
+    fn tracker(enabled: bool, cooldown_seconds: u64) -> (Reregistration, String) {
+        let mut settings = Settings::default();
+        settings.ethereum.operator_address = "0xAbCdEf0000000000000000000000000000000001".to_string();
+        settings.reregistration = ReregistrationConfig { enabled, cooldown_seconds };
+        let operator = settings.ethereum.operator_address.to_lowercase();
+        (Reregistration::new(&settings), operator)
+    }
+
+    #[test]
+    fn test_reregistration_waits_for_cooldown() {
+        let (reregistration, operator) = tracker(true, 3600);
+        assert_eq!(reregistration.deregistered("0x0000000000000000000000000000000000000002"), None);
+        assert_eq!(reregistration.deregistered(&operator).as_deref(), Some(PRIMARY_IDENTITY));
+        assert!(reregistration.due().is_empty());
+
+        let (reregistration, operator) = tracker(true, 0);
+        reregistration.deregistered(&operator);
+        assert_eq!(reregistration.due(), vec![PRIMARY_IDENTITY.to_string()]);
+        reregistration.attempted(PRIMARY_IDENTITY, true);
+        assert!(reregistration.due().is_empty());
+    }
+
+    #[test]
+    fn test_deregistration_is_only_tracked_when_disabled() {
+        let (reregistration, operator) = tracker(false, 0);
+        assert!(reregistration.deregistered(&operator).is_some());
+        assert!(reregistration.due().is_empty());
+    }
+}
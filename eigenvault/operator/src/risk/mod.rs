@@ -0,0 +1,212 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::bootstrap::format_ether;
+use crate::config::RiskConfig;
+use crate::metrics::metrics;
+use crate::sim::clock;
+
+const WEI_PER_GWEI: u128 = 1_000_000_000;
+
+/// Slashing exposure of the task responses still open to a challenge
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Exposure {
+    pub open_responses: usize,
+    pub exposure_wei: u128,
+    /// The smaller of `max_exposure_gwei` and `max_bond_percent` of the operator's stake
+    pub limit_wei: u128,
+}
+
+/// A submitted task response that can still be slashed
+#[derive(Debug, Clone)]
+struct OpenTask {
+    responses: usize,
+    /// Unix secs after which the response is no longer counted
+    expires_at: u64,
+}
+
+/// Estimates slashing exposure as open task responses times the penalty each risks, and holds
+/// off new tasks while one more would take exposure past the operator's limit
+pub struct RiskMonitor {
+    config: RiskConfig,
+    open: Mutex<HashMap<String, OpenTask>>,
+    /// Operator stake as last read from chain; `None` until the first read
+    bond_wei: Mutex<Option<u128>>,
+    halted: AtomicBool,
+    /// Set once the operator is slashed; declines every task until restarted
+    suspended: AtomicBool,
+}
+
+impl RiskMonitor {
+    pub fn new(config: RiskConfig) -> Self {
+        Self {
+            config,
+            open: Mutex::new(HashMap::new()),
+            bond_wei: Mutex::new(None),
+            halted: AtomicBool::new(false),
+            suspended: AtomicBool::new(false),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Record the operator's current stake, which bounds the exposure limit
+    pub fn update_bond(&self, stake_wei: u128) {
+        *self.bond_wei.lock().unwrap() = Some(stake_wei);
+        self.publish();
+    }
+
+    /// Fail when responding to `responses` more task responses would exceed the exposure limit
+    pub fn check(&self, responses: usize) -> Result<()> {
+        if self.suspended.load(Ordering::SeqCst) {
+            return Err(anyhow::anyhow!("this operator was slashed; restart it to accept tasks again"));
+        }
+        if !self.config.enabled {
+            return Ok(());
+        }
+
+        let exposure = self.exposure();
+        let added_wei = self.penalty_wei() * responses as u128;
+        let exceeded = exposure.exposure_wei + added_wei > exposure.limit_wei;
+        self.set_halted(exceeded, &exposure);
+
+        if exceeded {
+            return Err(anyhow::anyhow!(
+                "slashing exposure of {} ETH over {} open responses would exceed the limit of {} ETH",
+                format_ether(exposure.exposure_wei + added_wei), exposure.open_responses + responses, format_ether(exposure.limit_wei),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Decline every new task until restarted, whatever the exposure; for after a slashing
+    pub fn suspend(&self) {
+        if !self.suspended.swap(true, Ordering::SeqCst) {
+            metrics().risk_halted.set(1);
+            warn!("Declining new tasks until the operator is restarted");
+        }
+    }
+
+    /// Count `responses` submitted for `task_id`, open to a challenge until `challenge_window_seconds`
+    /// after the task's `deadline`
+    pub fn opened(&self, task_id: &str, responses: usize, deadline: u64) {
+        if !self.config.enabled {
+            return;
+        }
+
+        let expires_at = deadline.max(clock::unix_secs()) + self.config.challenge_window_seconds;
+        self.open.lock().unwrap().insert(task_id.to_string(), OpenTask { responses, expires_at });
+        self.publish();
+    }
+
+    /// Stop counting `task_id` once its responses are confirmed
+    pub fn closed(&self, task_id: &str) {
+        if self.open.lock().unwrap().remove(task_id).is_some() {
+            self.publish();
+        }
+    }
+
+    pub fn exposure(&self) -> Exposure {
+        let now = clock::unix_secs();
+        let mut open = self.open.lock().unwrap();
+        open.retain(|_, task| task.expires_at > now);
+
+        let open_responses = open.values().map(|task| task.responses).sum::<usize>();
+        let mut limit_wei = u128::from(self.config.max_exposure_gwei) * WEI_PER_GWEI;
+        if let Some(bond_wei) = *self.bond_wei.lock().unwrap() {
+            limit_wei = limit_wei.min(bond_wei * u128::from(self.config.max_bond_percent) / 100);
+        }
+
+        Exposure {
+            open_responses,
+            exposure_wei: self.penalty_wei() * open_responses as u128,
+            limit_wei,
+        }
+    }
+
+    fn penalty_wei(&self) -> u128 {
+        u128::from(self.config.penalty_gwei) * WEI_PER_GWEI
+    }
+
+    fn publish(&self) {
+        let exposure = self.exposure();
+        metrics().slashing_exposure_gwei.set((exposure.exposure_wei / WEI_PER_GWEI) as i64);
+        metrics().slashing_exposure_limit_gwei.set((exposure.limit_wei / WEI_PER_GWEI) as i64);
+    }
+
+    /// Alert when new tasks stop or start being accepted
+    fn set_halted(&self, halted: bool, exposure: &Exposure) {
+        if self.halted.swap(halted, Ordering::SeqCst) == halted {
+            return;
+        }
+        metrics().risk_halted.set(halted as i64);
+
+        if halted {
+            warn!(
+                "Slashing exposure {} ETH is at its limit of {} ETH; declining new tasks",
+                format_ether(exposure.exposure_wei), format_ether(exposure.limit_wei),
+            );
+        } else {
+            info!("Slashing exposure {} ETH is back under its limit; accepting tasks", format_ether(exposure.exposure_wei));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETH: u128 = 1_000_000_000_000_000_000;
+
+    fn monitor() -> RiskMonitor {
+        RiskMonitor::new(RiskConfig {
+            enabled: true,
+            penalty_gwei: 1_000_000_000,
+            max_exposure_gwei: 3_000_000_000,
+            max_bond_percent: 50,
+            challenge_window_seconds: 3600,
+        })
+    }
+
+    #[test]
+    fn test_exposure_limits_new_tasks() {
+        let risk = monitor();
+        risk.check(2).unwrap();
+        risk.opened("task_1", 2, clock::unix_secs());
+        assert_eq!(risk.exposure().exposure_wei, 2 * ETH);
+
+        risk.check(1).unwrap();
+        assert!(risk.check(2).is_err());
+
+        risk.closed("task_1");
+        risk.check(2).unwrap();
+    }
+
+    #[test]
+    fn test_bond_caps_limit() {
+        let risk = monitor();
+        risk.update_bond(4 * ETH);
+        assert_eq!(risk.exposure().limit_wei, 2 * ETH);
+        assert!(risk.check(3).is_err());
+    }
+
+    #[test]
+    fn test_suspension_declines_tasks_even_when_disabled() {
+        let risk = RiskMonitor::new(RiskConfig { enabled: false, ..monitor().config });
+        risk.check(1).unwrap();
+        risk.suspend();
+        assert!(risk.check(1).is_err());
+    }
+
+    #[test]
+    fn test_expired_tasks_are_not_counted() {
+        let risk = RiskMonitor::new(RiskConfig { challenge_window_seconds: 0, ..monitor().config });
+        risk.opened("task_1", 1, 0);
+        assert_eq!(risk.exposure().open_responses, 0);
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+use crate::config::SchedulerConfig;
+use crate::metrics::metrics;
+use crate::storage::ScheduledTask;
+
+/// Tasks waiting to be processed, earliest deadline first
+pub struct TaskScheduler {
+    config: SchedulerConfig,
+    /// Keyed by deadline, then task ID so a task scheduled twice is queued once
+    queue: Mutex<BTreeMap<(u64, String), ScheduledTask>>,
+    scheduled: Notify,
+}
+
+/// What `TaskScheduler::pop` took off the queue
+#[derive(Debug, Default, PartialEq)]
+pub struct Popped {
+    /// Tasks too close to their deadline to be answered in time
+    pub expired: Vec<ScheduledTask>,
+    /// Task with the earliest deadline that can still be answered
+    pub next: Option<ScheduledTask>,
+}
+
+impl TaskScheduler {
+    pub fn new(config: SchedulerConfig) -> Self {
+        Self {
+            config,
+            queue: Mutex::new(BTreeMap::new()),
+            scheduled: Notify::new(),
+        }
+    }
+
+    pub fn schedule(&self, task: ScheduledTask) {
+        let mut queue = self.queue.lock().unwrap();
+        queue.insert((task.deadline, task.task_id.clone()), task);
+        metrics().tasks_scheduled.set(queue.len() as i64);
+        self.scheduled.notify_one();
+    }
+
+    /// Take the task with the earliest deadline at `now`, and every task ahead of it that is
+    /// past its latest start
+    pub fn pop(&self, now: u64) -> Popped {
+        let mut queue = self.queue.lock().unwrap();
+        let mut popped = Popped::default();
+        while let Some((_, task)) = queue.pop_first() {
+            if now < self.latest_start(&task) {
+                popped.next = Some(task);
+                break;
+            }
+            popped.expired.push(task);
+        }
+        metrics().tasks_scheduled.set(queue.len() as i64);
+        popped
+    }
+
+    /// Resolves once a task is scheduled, or at once if one was scheduled since the last call
+    pub async fn wait(&self) {
+        self.scheduled.notified().await
+    }
+
+    /// Unix secs after which work on `task` is cancelled, leaving `deadline_margin_seconds` to
+    /// submit the response
+    pub fn latest_start(&self, task: &ScheduledTask) -> u64 {
+        task.deadline.saturating_sub(self.config.deadline_margin_seconds)
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(task_id: &str, deadline: u64) -> ScheduledTask {
+        ScheduledTask { task_id: task_id.to_string(), orders_hash: "0x00".to_string(), deadline }
+    }
+
+    #[test]
+    fn test_earliest_deadline_first() {
+        let scheduler = TaskScheduler::new(SchedulerConfig { deadline_margin_seconds: 10 });
+        scheduler.schedule(task("late", 300));
+        scheduler.schedule(task("soon", 100));
+        scheduler.schedule(task("soon", 100));
+
+        assert_eq!(scheduler.len(), 2);
+        assert_eq!(scheduler.pop(0).next, Some(task("soon", 100)));
+        assert_eq!(scheduler.pop(0).next, Some(task("late", 300)));
+        assert_eq!(scheduler.pop(0), Popped::default());
+    }
+
+    #[test]
+    fn test_tasks_past_their_latest_start_expire() {
+        let scheduler = TaskScheduler::new(SchedulerConfig { deadline_margin_seconds: 10 });
+        scheduler.schedule(task("missed", 100));
+        scheduler.schedule(task("too_close", 205));
+        scheduler.schedule(task("open", 300));
+
+        let popped = scheduler.pop(195);
+        assert_eq!(popped.expired, vec![task("missed", 100), task("too_close", 205)]);
+        assert_eq!(popped.next, Some(task("open", 300)));
+    }
+}
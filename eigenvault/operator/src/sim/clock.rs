@@ -0,0 +1,44 @@
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+struct VirtualClock {
+    start_ms: u64,
+    now_ms: u64,
+    step: Duration,
+}
+
+/// Set in simulation mode; wall-clock time then only moves through `tick`
+static VIRTUAL: Mutex<Option<VirtualClock>> = Mutex::new(None);
+
+static PROCESS_START: OnceLock<Instant> = OnceLock::new();
+
+/// Freeze time at `start_ms` since the epoch, advancing by `step` on every `tick`
+pub fn virtualize(start_ms: u64, step: Duration) {
+    *VIRTUAL.lock().unwrap() = Some(VirtualClock { start_ms, now_ms: start_ms, step });
+}
+
+/// Advance virtual time by one step; a no-op on the real clock
+pub fn tick() {
+    if let Some(clock) = VIRTUAL.lock().unwrap().as_mut() {
+        clock.now_ms += clock.step.as_millis() as u64;
+    }
+}
+
+pub fn unix_millis() -> u64 {
+    match VIRTUAL.lock().unwrap().as_ref() {
+        Some(clock) => clock.now_ms,
+        None => chrono::Utc::now().timestamp_millis() as u64,
+    }
+}
+
+pub fn unix_secs() -> u64 {
+    unix_millis() / 1000
+}
+
+/// Monotonic time since startup, for measuring intervals
+pub fn elapsed() -> Duration {
+    match VIRTUAL.lock().unwrap().as_ref() {
+        Some(clock) => Duration::from_millis(clock.now_ms - clock.start_ms),
+        None => PROCESS_START.get_or_init(Instant::now).elapsed(),
+    }
+}
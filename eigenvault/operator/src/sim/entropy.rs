@@ -0,0 +1,36 @@
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Seeded generator used instead of the thread RNG in simulation mode
+static SEEDED: Mutex<Option<StdRng>> = Mutex::new(None);
+
+/// Draw every later id, nonce and peer selection from `seed`; calling it again restarts the sequence
+pub fn seed(seed: u64) {
+    *SEEDED.lock().unwrap() = Some(StdRng::seed_from_u64(seed));
+}
+
+/// Run `f` with the seeded RNG in simulation mode, the thread RNG otherwise
+pub fn with_rng<T>(f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+    match SEEDED.lock().unwrap().as_mut() {
+        Some(rng) => f(rng),
+        None => f(&mut rand::thread_rng()),
+    }
+}
+
+pub fn fill(bytes: &mut [u8]) {
+    with_rng(|rng| rng.fill_bytes(bytes));
+}
+
+pub fn bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    fill(&mut bytes);
+    bytes
+}
+
+/// Random (v4) UUID
+pub fn uuid() -> Uuid {
+    uuid::Builder::from_random_bytes(bytes()).into_uuid()
+}
+
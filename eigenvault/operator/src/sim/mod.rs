@@ -0,0 +1,71 @@
+#[cfg(feature = "chain")]
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "chain")]
+use crate::ethereum::mock::MockTransaction;
+
+pub mod clock;
+pub mod entropy;
+
+/// Transactions one simulated operator submitted, in submission order
+#[cfg(feature = "chain")]
+pub struct Transcript {
+    pub operator: String,
+    pub transactions: Vec<MockTransaction>,
+}
+
+#[cfg(feature = "chain")]
+impl Transcript {
+    pub fn digest(&self) -> String {
+        let mut hasher = Sha256::new();
+        for tx in &self.transactions {
+            hasher.update(format!("{:?}", tx.kind).as_bytes());
+            hasher.update(tx.subject.as_bytes());
+            hasher.update((tx.payload.len() as u64).to_le_bytes());
+            hasher.update(&tx.payload);
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    /// Index of the first transaction that differs from `other`, counting a missing one
+    pub fn divergence(&self, other: &Transcript) -> Option<usize> {
+        let differs = |(a, b): (&MockTransaction, &MockTransaction)| {
+            a.kind != b.kind || a.subject != b.subject || a.payload != b.payload
+        };
+        self.transactions.iter().zip(&other.transactions)
+            .position(differs)
+            .or_else(|| {
+                let (mine, theirs) = (self.transactions.len(), other.transactions.len());
+                (mine != theirs).then(|| mine.min(theirs))
+            })
+    }
+}
+
+#[cfg(all(test, feature = "chain"))]
+mod tests {
+    use super::*;
+    use crate::audit::TransactionKind;
+
+    fn transcript(payloads: &[&[u8]]) -> Transcript {
+        Transcript {
+            operator: "op".to_string(),
+            transactions: payloads.iter().enumerate().map(|(i, payload)| MockTransaction {
+                kind: TransactionKind::TaskResponse,
+                subject: format!("task_{}", i),
+                payload: payload.to_vec(),
+                tx_hash: format!("0x{:x}", i),
+            }).collect(),
+        }
+    }
+
+    #[test]
+    fn test_transcript_divergence() {
+        let base = transcript(&[b"a", b"b"]);
+
+        assert_eq!(base.divergence(&transcript(&[b"a", b"b"])), None);
+        assert_eq!(base.digest(), transcript(&[b"a", b"b"]).digest());
+        assert_eq!(base.divergence(&transcript(&[b"a", b"c"])), Some(1));
+        assert_eq!(base.divergence(&transcript(&[b"a"])), Some(1));
+        assert_ne!(base.digest(), transcript(&[b"a", b"c"]).digest());
+    }
+}
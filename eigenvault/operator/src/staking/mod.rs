@@ -0,0 +1,240 @@
+use anyhow::Result;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::audit::AuditLog;
+use crate::bootstrap::format_ether;
+use crate::config::{Settings, StakeTopUpConfig};
+use crate::ethereum::contracts::EigenVaultContracts;
+use crate::ethereum::{CachedContracts, ContractApi, EthereumClient};
+use crate::sim::clock;
+use crate::storage::{StakeDeposit, StateCheckpoint, Store};
+
+const WEI_PER_GWEI: u128 = 1_000_000_000;
+
+const DAY_SECS: u64 = 24 * 3600;
+
+/// A deposit that brings operator stake back to its target
+#[derive(Debug, Clone, PartialEq)]
+pub struct TopUp {
+    pub stake_wei: u128,
+    /// AVS minimum plus the buffer
+    pub target_wei: u128,
+    /// What is deposited, after the caps
+    pub amount_wei: u128,
+}
+
+/// Watches operator stake and deposits into the configured strategy from the funding account
+pub struct StakeTopUp<A = CachedContracts<EigenVaultContracts>> {
+    config: StakeTopUpConfig,
+    operator: String,
+    funding: EthereumClient<A>,
+    /// Deposits of the last 24 hours: (unix secs, wei)
+    deposits: Mutex<VecDeque<(u64, u128)>>,
+    /// Where deposits are recorded, so the daily cap holds across restarts and failover
+    store: Option<Arc<Store>>,
+}
+
+impl StakeTopUp {
+    /// Connect the funding account in `settings.stake_top_up`
+    pub async fn connect(settings: &Settings, audit_log: Option<Arc<AuditLog>>, dry_run: bool) -> Result<Self> {
+        let mut funding = EthereumClient::new(settings.funding_config()).await?;
+        if let Some(audit_log) = audit_log {
+            funding.set_audit_log(audit_log);
+        }
+        funding.set_dry_run(dry_run);
+
+        Ok(Self::new(settings.stake_top_up.clone(), settings.ethereum.operator_address.clone(), funding))
+    }
+}
+
+impl<A: ContractApi> StakeTopUp<A> {
+    pub fn new(config: StakeTopUpConfig, operator: String, funding: EthereumClient<A>) -> Self {
+        Self { config, operator, funding, deposits: Mutex::new(VecDeque::new()), store: None }
+    }
+
+    /// Record deposits in `store`, counting those it holds from the last 24 hours against the cap
+    pub fn with_store(mut self, store: Arc<Store>) -> Result<Self> {
+        let cutoff = clock::unix_secs().saturating_sub(DAY_SECS);
+        let deposits = store.stake_deposits_since(cutoff)?;
+        *self.deposits.get_mut().unwrap() =
+            deposits.iter().map(|deposit| (deposit.deposited_at, deposit.amount_wei)).collect();
+        self.store = Some(store);
+        Ok(self)
+    }
+
+    /// The deposit needed to bring stake back to the AVS minimum plus the buffer, capped per
+    /// deposit and per day; `None` while stake is at or above that
+    pub async fn shortfall(&self) -> Result<Option<TopUp>> {
+        let stake_wei = self.funding.get_operator_stake(&self.operator).await?;
+        let target_wei = self.funding.minimum_stake().await? + gwei(self.config.buffer_gwei);
+        if stake_wei >= target_wei {
+            return Ok(None);
+        }
+
+        let remaining_today = gwei(self.config.max_daily_gwei).saturating_sub(self.deposited_today());
+        if remaining_today == 0 {
+            return Err(anyhow::anyhow!(
+                "Stake {} ETH is below {} ETH but the daily top-up cap of {} ETH is spent",
+                format_ether(stake_wei), format_ether(target_wei), format_ether(gwei(self.config.max_daily_gwei)),
+            ));
+        }
+        let amount_wei = (target_wei - stake_wei).min(gwei(self.config.max_deposit_gwei)).min(remaining_today);
+
+        Ok(Some(TopUp { stake_wei, target_wei, amount_wei }))
+    }
+
+    /// Deposit `top_up` from the funding account, returning the transaction hash
+    pub async fn deposit(&self, top_up: &TopUp) -> Result<String> {
+        let tx_hash = self.funding.deposit_stake(&self.config.strategy, top_up.amount_wei).await?;
+        let deposited_at = clock::unix_secs();
+        self.deposits.lock().unwrap().push_back((deposited_at, top_up.amount_wei));
+        if let Some(store) = self.store.as_ref().filter(|_| !self.funding.is_dry_run()) {
+            let mut checkpoint = StateCheckpoint::new();
+            checkpoint.record_stake_deposit(StakeDeposit {
+                deposited_at,
+                amount_wei: top_up.amount_wei,
+                tx_hash: tx_hash.clone(),
+            });
+            // The deposit is made either way; only a restart before the next one loses count of it
+            if let Err(e) = store.commit(checkpoint) {
+                error!("Failed to record stake deposit {}: {:?}", tx_hash, e);
+            }
+        }
+        info!(
+            "Topped up operator stake from {} ETH with {} ETH: {}",
+            format_ether(top_up.stake_wei), format_ether(top_up.amount_wei), tx_hash,
+        );
+        Ok(tx_hash)
+    }
+
+    /// Check stake every `check_interval_seconds`; deposits are made unattended only when
+    /// `confirm` is off, otherwise a shortfall is just reported
+    pub async fn run(self: Arc<Self>) {
+        info!(
+            "Watching stake of {} against the AVS minimum plus {} ETH",
+            self.operator, format_ether(gwei(self.config.buffer_gwei)),
+        );
+        let mut interval = tokio::time::interval(Duration::from_secs(self.config.check_interval_seconds));
+
+        loop {
+            interval.tick().await;
+            let top_up = match self.shortfall().await {
+                Ok(Some(top_up)) => top_up,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("Stake top-up check failed: {:?}", e);
+                    continue;
+                }
+            };
+
+            if self.config.confirm {
+                warn!(
+                    "Operator stake {} ETH is below {} ETH; run `top-up-stake` to deposit {} ETH",
+                    format_ether(top_up.stake_wei), format_ether(top_up.target_wei), format_ether(top_up.amount_wei),
+                );
+            } else if let Err(e) = self.deposit(&top_up).await {
+                warn!("Stake top-up deposit failed: {:?}", e);
+            }
+        }
+    }
+
+    pub fn funding_address(&self) -> &str {
+        &self.config.funding_address
+    }
+
+    pub fn strategy(&self) -> &str {
+        &self.config.strategy
+    }
+
+    fn deposited_today(&self) -> u128 {
+        let cutoff = clock::unix_secs().saturating_sub(DAY_SECS);
+        let mut deposits = self.deposits.lock().unwrap();
+        while deposits.front().is_some_and(|(at, _)| *at < cutoff) {
+            deposits.pop_front();
+        }
+        deposits.iter().map(|(_, amount)| amount).sum()
+    }
+}
+
+fn gwei(amount: u64) -> u128 {
+    u128::from(amount) * WEI_PER_GWEI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EthereumConfig;
+    use crate::ethereum::MockChain;
+
+    const ETH: u128 = 1_000_000_000_000_000_000;
+
+    async fn top_up(chain: &MockChain, config: StakeTopUpConfig) -> StakeTopUp<MockChain> {
+        let ethereum = EthereumConfig { operator_address: config.funding_address.clone(), ..Settings::default().ethereum };
+        let funding = EthereumClient::with_contracts(ethereum, chain.clone()).await.unwrap();
+        StakeTopUp::new(config, "0x1234567890123456789012345678901234567890".to_string(), funding)
+    }
+
+    #[tokio::test]
+    async fn test_deposits_are_capped() -> Result<()> {
+        let chain = MockChain::new();
+        chain.set_minimum_stake(32 * ETH);
+        let config = StakeTopUpConfig {
+            enabled: true,
+            strategy: "0x00000000000000000000000000000000000000aa".to_string(),
+            max_deposit_gwei: 20_000_000_000,
+            max_daily_gwei: 30_000_000_000,
+            ..StakeTopUpConfig::default()
+        };
+        let top_up = top_up(&chain, config).await;
+
+        let first = top_up.shortfall().await?.unwrap();
+        assert_eq!(first.target_wei, 33 * ETH);
+        assert_eq!(first.amount_wei, 20 * ETH);
+        top_up.deposit(&first).await?;
+
+        // Only 10 ETH of the daily cap is left for the 13 ETH still missing
+        let second = top_up.shortfall().await?.unwrap();
+        assert_eq!(second.amount_wei, 10 * ETH);
+        top_up.deposit(&second).await?;
+
+        assert!(top_up.shortfall().await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_daily_cap_survives_a_restart() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let chain = MockChain::new();
+        chain.set_minimum_stake(32 * ETH);
+        let config = StakeTopUpConfig {
+            enabled: true,
+            strategy: "0x00000000000000000000000000000000000000aa".to_string(),
+            max_deposit_gwei: 20_000_000_000,
+            max_daily_gwei: 30_000_000_000,
+            ..StakeTopUpConfig::default()
+        };
+        {
+            let store = Arc::new(Store::open_path(dir.path(), false)?);
+            let top_up = top_up(&chain, config.clone()).await.with_store(store)?;
+            let first = top_up.shortfall().await?.unwrap();
+            top_up.deposit(&first).await?;
+        }
+
+        let store = Arc::new(Store::open_path(dir.path(), false)?);
+        let top_up = top_up(&chain, config).await.with_store(store)?;
+        assert_eq!(top_up.shortfall().await?.unwrap().amount_wei, 10 * ETH);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_no_deposit_above_target() -> Result<()> {
+        let chain = MockChain::new();
+        let top_up = top_up(&chain, StakeTopUpConfig { buffer_gwei: 0, ..StakeTopUpConfig::default() }).await;
+
+        assert_eq!(top_up.shortfall().await?, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use serde::{Deserialize, Serialize};
+
+use crate::ethereum::LogId;
+use crate::matching::{ClaimedNonce, DecryptedOrder};
+use crate::sim::clock;
+
+/// Kind of on-chain submission awaiting confirmation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SubmissionKind {
+    TaskResponse,
+    MatchingProof,
+    VaultExecution,
+}
+
+impl SubmissionKind {
+    /// Task type label for metrics and reports
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::TaskResponse => "task_response",
+            Self::MatchingProof => "matching_proof",
+            Self::VaultExecution => "vault_execution",
+        }
+    }
+}
+
+/// A transaction the operator has sent but not yet seen confirmed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingSubmission {
+    pub task_id: String,
+    pub tx_hash: String,
+    pub kind: SubmissionKind,
+    pub submitted_at: u64,
+}
+
+impl PendingSubmission {
+    pub fn new(task_id: String, tx_hash: String, kind: SubmissionKind) -> Self {
+        Self {
+            task_id,
+            tx_hash,
+            kind,
+            submitted_at: clock::unix_secs(),
+        }
+    }
+}
+
+/// A task the operator accepted and has not yet answered or given up on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledTask {
+    pub task_id: String,
+    pub orders_hash: String,
+    /// Unix secs by which the response must be on chain
+    pub deadline: u64,
+}
+
+/// Stake the top-up deposited for the operator, counted against its daily cap
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StakeDeposit {
+    /// Unix secs
+    pub deposited_at: u64,
+    pub amount_wei: u128,
+    pub tx_hash: String,
+}
+
+/// A set of state changes that must be persisted together.
+///
+/// Everything recorded here is written in a single RocksDB write batch, so
+/// the Ethereum cursor can never be persisted ahead of the submissions and
+/// tasks derived from the blocks it covers; orders are journaled before it. A log recorded as
+/// processed is likewise only persisted together with the effects of its event.
+#[derive(Debug, Clone, Default)]
+pub struct StateCheckpoint {
+    pub last_processed_block: Option<u64>,
+    pub processed_logs: Vec<LogId>,
+    pub upserted_orders: Vec<DecryptedOrder>,
+    pub removed_orders: Vec<String>,
+    pub upserted_submissions: Vec<PendingSubmission>,
+    pub confirmed_submissions: Vec<String>,
+    pub scheduled_tasks: Vec<ScheduledTask>,
+    pub finished_tasks: Vec<String>,
+    pub stake_deposits: Vec<StakeDeposit>,
+    pub claimed_nonces: Vec<ClaimedNonce>,
+    pub expired_nonces: Vec<ClaimedNonce>,
+}
+
+impl StateCheckpoint {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checkpoint that advances the Ethereum cursor to `block`
+    pub fn at_block(block: u64) -> Self {
+        Self {
+            last_processed_block: Some(block),
+            ..Self::default()
+        }
+    }
+
+    /// Checkpoint for the effects of handling the event logged at `log`
+    pub fn for_log(log: LogId) -> Self {
+        Self {
+            processed_logs: vec![log],
+            ..Self::default()
+        }
+    }
+
+    pub fn upsert_order(&mut self, order: DecryptedOrder) {
+        self.upserted_orders.push(order);
+    }
+
+    pub fn remove_order(&mut self, order_id: String) {
+        self.removed_orders.push(order_id);
+    }
+
+    pub fn record_submission(&mut self, submission: PendingSubmission) {
+        self.upserted_submissions.push(submission);
+    }
+
+    pub fn confirm_submission(&mut self, task_id: String) {
+        self.confirmed_submissions.push(task_id);
+    }
+
+    pub fn schedule_task(&mut self, task: ScheduledTask) {
+        self.scheduled_tasks.push(task);
+    }
+
+    pub fn finish_task(&mut self, task_id: String) {
+        self.finished_tasks.push(task_id);
+    }
+
+    pub fn record_stake_deposit(&mut self, deposit: StakeDeposit) {
+        self.stake_deposits.push(deposit);
+    }
+
+    pub fn claim_nonce(&mut self, claimed: ClaimedNonce) {
+        self.claimed_nonces.push(claimed);
+    }
+
+    pub fn expire_nonce(&mut self, expired: ClaimedNonce) {
+        self.expired_nonces.push(expired);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.last_processed_block.is_none()
+            && self.processed_logs.is_empty()
+            && self.upserted_orders.is_empty()
+            && self.removed_orders.is_empty()
+            && self.upserted_submissions.is_empty()
+            && self.confirmed_submissions.is_empty()
+            && self.scheduled_tasks.is_empty()
+            && self.finished_tasks.is_empty()
+            && self.stake_deposits.is_empty()
+            && self.claimed_nonces.is_empty()
+            && self.expired_nonces.is_empty()
+    }
+}
@@ -0,0 +1,224 @@
+use anyhow::Result;
+use rocksdb::{checkpoint::Checkpoint, IteratorMode, WriteBatch, DB};
+use std::path::Path;
+use tracing::{info, warn};
+
+use super::store::{CF_META, CF_PENDING_ORDERS, CF_PROCESSED_LOGS};
+
+const SCHEMA_VERSION_KEY: &[u8] = b"schema_version";
+
+/// A single forward migration of the on-disk schema.
+///
+/// `apply` stages its changes into the batch; the batch is written together
+/// with the new schema version so a migration is either fully applied or not
+/// at all.
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub apply: fn(&DB, &mut WriteBatch) -> Result<()>,
+}
+
+/// Every schema version this binary knows about, in ascending order
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Initial schema: checkpoints, pending orders, submissions",
+        apply: initial_schema,
+    },
+    Migration {
+        version: 2,
+        description: "Processed log ids",
+        apply: processed_logs,
+    },
+];
+
+/// Schema version written by this binary
+pub fn current_schema_version() -> u32 {
+    latest_version(MIGRATIONS)
+}
+
+/// Schema version recorded on disk, if any
+pub fn schema_version(db: &DB) -> Result<Option<u32>> {
+    let meta = db.cf_handle(CF_META)
+        .ok_or_else(|| anyhow::anyhow!("Missing column family: {}", CF_META))?;
+
+    match db.get_cf(meta, SCHEMA_VERSION_KEY)? {
+        Some(bytes) => {
+            let bytes: [u8; 4] = bytes.as_slice().try_into()
+                .map_err(|_| anyhow::anyhow!("Corrupt schema version"))?;
+            Ok(Some(u32::from_be_bytes(bytes)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Bring the database up to the latest version in `migrations`.
+///
+/// If `backup_dir` is set and there is work to do on an existing database, a
+/// RocksDB checkpoint is taken there first so the upgrade can be rolled back
+/// by hand.
+pub fn migrate(db: &DB, migrations: &[Migration], backup_dir: Option<&Path>) -> Result<u32> {
+    let target = latest_version(migrations);
+    let current = schema_version(db)?.unwrap_or(0);
+
+    if current > target {
+        return Err(anyhow::anyhow!(
+            "On-disk schema v{} is newer than this binary supports (v{}); refusing to downgrade",
+            current, target
+        ));
+    }
+
+    let pending: Vec<&Migration> = migrations.iter()
+        .filter(|migration| migration.version > current)
+        .collect();
+
+    if pending.is_empty() {
+        return Ok(current);
+    }
+
+    if current > 0 {
+        if let Some(backup_dir) = backup_dir {
+            let backup_path = backup_dir.join(format!(
+                "schema_v{}_{}",
+                current,
+                chrono::Utc::now().timestamp()
+            ));
+            info!("Backing up state store to {:?} before migrating", backup_path);
+            Checkpoint::new(db)?.create_checkpoint(&backup_path)?;
+        }
+    }
+
+    let meta = db.cf_handle(CF_META)
+        .ok_or_else(|| anyhow::anyhow!("Missing column family: {}", CF_META))?;
+
+    for migration in pending {
+        info!("Migrating state store to schema v{}: {}", migration.version, migration.description);
+
+        let mut batch = WriteBatch::default();
+        (migration.apply)(db, &mut batch)?;
+        batch.put_cf(meta, SCHEMA_VERSION_KEY, migration.version.to_be_bytes());
+        db.write(batch)?;
+    }
+
+    info!("State store schema is at v{}", target);
+    Ok(target)
+}
+
+/// Rewrite every JSON record in a column family.
+///
+/// Helper for migrations that add, rename or drop fields of persisted records.
+pub fn rewrite_json_records<F>(db: &DB, batch: &mut WriteBatch, cf_name: &str, mut rewrite: F) -> Result<usize>
+where
+    F: FnMut(&mut serde_json::Value) -> Result<()>,
+{
+    let cf = db.cf_handle(cf_name)
+        .ok_or_else(|| anyhow::anyhow!("Missing column family: {}", cf_name))?;
+
+    let mut rewritten = 0;
+    for entry in db.iterator_cf(cf, IteratorMode::Start) {
+        let (key, value) = entry?;
+        let mut record: serde_json::Value = match serde_json::from_slice(&value) {
+            Ok(record) => record,
+            Err(e) => {
+                warn!("Skipping undecodable record in {}: {:?}", cf_name, e);
+                continue;
+            }
+        };
+
+        rewrite(&mut record)?;
+        batch.put_cf(cf, key, serde_json::to_vec(&record)?);
+        rewritten += 1;
+    }
+
+    Ok(rewritten)
+}
+
+fn latest_version(migrations: &[Migration]) -> u32 {
+    migrations.last().map(|migration| migration.version).unwrap_or(0)
+}
+
+/// v1: the layout introduced with atomic checkpointing. Stores created before
+/// versioning already use it, so this only stamps the version.
+fn initial_schema(db: &DB, _batch: &mut WriteBatch) -> Result<()> {
+    db.cf_handle(CF_PENDING_ORDERS)
+        .ok_or_else(|| anyhow::anyhow!("Missing column family: {}", CF_PENDING_ORDERS))?;
+    Ok(())
+}
+
+/// v2: logs whose events were handled, so a restart inside a block range
+/// doesn't handle them twice. The column family starts out empty.
+fn processed_logs(db: &DB, _batch: &mut WriteBatch) -> Result<()> {
+    db.cf_handle(CF_PROCESSED_LOGS)
+        .ok_or_else(|| anyhow::anyhow!("Missing column family: {}", CF_PROCESSED_LOGS))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Store;
+    use tempfile::tempdir;
+
+    fn add_priority_field(db: &DB, batch: &mut WriteBatch) -> Result<()> {
+        rewrite_json_records(db, batch, CF_PENDING_ORDERS, |record| {
+            record["priority"] = serde_json::json!(0);
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    const TEST_MIGRATIONS: &[Migration] = &[
+        Migration {
+            version: 1,
+            description: "Initial schema",
+            apply: initial_schema,
+        },
+        Migration {
+            version: 2,
+            description: "Processed log ids",
+            apply: processed_logs,
+        },
+        Migration {
+            version: 3,
+            description: "Add order priority",
+            apply: add_priority_field,
+        },
+    ];
+
+    #[test]
+    fn test_fresh_store_is_stamped() -> Result<()> {
+        let dir = tempdir()?;
+        let store = Store::open_path(dir.path(), false)?;
+        assert_eq!(schema_version(store.db())?, Some(current_schema_version()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_forward_migration_rewrites_records() -> Result<()> {
+        let dir = tempdir()?;
+        let store = Store::open_path(dir.path(), false)?;
+
+        let cf = store.db().cf_handle(CF_PENDING_ORDERS).unwrap();
+        store.db().put_cf(cf, b"order_1", br#"{"id":"order_1"}"#)?;
+
+        let version = migrate(store.db(), TEST_MIGRATIONS, None)?;
+        assert_eq!(version, 3);
+
+        let record: serde_json::Value = serde_json::from_slice(&store.db().get_cf(cf, b"order_1")?.unwrap())?;
+        assert_eq!(record["priority"], 0);
+
+        // Running again is a no-op
+        assert_eq!(migrate(store.db(), TEST_MIGRATIONS, None)?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_refuses_downgrade() -> Result<()> {
+        let dir = tempdir()?;
+        let store = Store::open_path(dir.path(), false)?;
+        migrate(store.db(), TEST_MIGRATIONS, None)?;
+
+        assert!(migrate(store.db(), &TEST_MIGRATIONS[..1], None).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,8 @@
+pub mod checkpoint;
+pub mod migrations;
+pub mod store;
+pub mod upgrades;
+
+pub use checkpoint::{PendingSubmission, ScheduledTask, StakeDeposit, StateCheckpoint, SubmissionKind};
+pub use store::{RecoveredState, Store};
+pub use upgrades::{UpgradeContext, UpgradeRecord, BINARY_VERSION, UPGRADE_HOOKS};
@@ -0,0 +1,485 @@
+use anyhow::Result;
+use rocksdb::{checkpoint::Checkpoint, ColumnFamily, ColumnFamilyDescriptor, Direction, IteratorMode, Options, WriteBatch, WriteOptions, DB};
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+use crate::config::StorageConfig;
+use crate::ethereum::LogId;
+use crate::matching::{ClaimedNonce, DecryptedOrder, NonceStore};
+use super::migrations::{self, MIGRATIONS};
+use super::{PendingSubmission, ScheduledTask, StakeDeposit, StateCheckpoint};
+
+pub const CF_META: &str = "meta";
+pub const CF_CHECKPOINTS: &str = "checkpoints";
+pub const CF_PENDING_ORDERS: &str = "pending_orders";
+pub const CF_SUBMISSIONS: &str = "submissions";
+pub const CF_PROCESSED_LOGS: &str = "processed_logs";
+pub const CF_SCHEDULED_TASKS: &str = "scheduled_tasks";
+pub const CF_STAKE_DEPOSITS: &str = "stake_deposits";
+pub const CF_TRADER_NONCES: &str = "trader_nonces";
+
+const COLUMN_FAMILIES: [&str; 8] = [
+    CF_META, CF_CHECKPOINTS, CF_PENDING_ORDERS, CF_SUBMISSIONS, CF_PROCESSED_LOGS, CF_SCHEDULED_TASKS,
+    CF_STAKE_DEPOSITS, CF_TRADER_NONCES,
+];
+const LAST_PROCESSED_BLOCK_KEY: &[u8] = b"last_processed_block";
+
+/// Processed log key, ordered by position on chain so a block range is a key range
+fn log_key(log: &LogId) -> [u8; 16] {
+    let mut key = [0u8; 16];
+    key[..8].copy_from_slice(&log.block_number.to_be_bytes());
+    key[8..].copy_from_slice(&log.log_index.to_be_bytes());
+    key
+}
+
+/// Stake deposit key, ordered by time so the last day of deposits is a key range
+fn deposit_key(deposit: &StakeDeposit) -> Vec<u8> {
+    let mut key = deposit.deposited_at.to_be_bytes().to_vec();
+    key.extend_from_slice(deposit.tx_hash.as_bytes());
+    key
+}
+
+/// Claimed nonce key, the trader followed by the nonce
+fn nonce_key(claimed: &ClaimedNonce) -> Vec<u8> {
+    let mut key = claimed.trader.as_bytes().to_vec();
+    key.extend_from_slice(&claimed.nonce.to_be_bytes());
+    key
+}
+
+/// Where pre-migration backups of the store at `path` go: `<data_dir>.backups`, a sibling of it
+fn backup_dir(path: &Path) -> PathBuf {
+    let mut backup_dir = path.as_os_str().to_os_string();
+    backup_dir.push(".backups");
+    PathBuf::from(backup_dir)
+}
+
+/// State recovered from disk at startup
+#[derive(Debug, Clone, Default)]
+pub struct RecoveredState {
+    pub last_processed_block: Option<u64>,
+    pub pending_orders: Vec<DecryptedOrder>,
+    pub unconfirmed_submissions: Vec<PendingSubmission>,
+    pub scheduled_tasks: Vec<ScheduledTask>,
+    pub claimed_nonces: Vec<ClaimedNonce>,
+}
+
+/// Persistent operator state backed by RocksDB column families
+pub struct Store {
+    db: DB,
+    path: PathBuf,
+    sync_writes: bool,
+}
+
+impl Store {
+    pub fn open(config: &StorageConfig) -> Result<Self> {
+        Self::open_path(&config.data_dir, config.sync_writes)
+    }
+
+    pub fn open_path<P: AsRef<Path>>(path: P, sync_writes: bool) -> Result<Self> {
+        let path = path.as_ref();
+        info!("Opening operator state store at {:?}", path);
+
+        let mut options = Options::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+
+        let descriptors = COLUMN_FAMILIES
+            .iter()
+            .map(|name| ColumnFamilyDescriptor::new(*name, Options::default()))
+            .collect::<Vec<_>>();
+
+        let db = DB::open_cf_descriptors(&options, path, descriptors)?;
+
+        // Upgrade the on-disk schema before anything reads from it, backed up next to the store
+        // rather than inside it so the backups outlive the database directory
+        migrations::migrate(&db, MIGRATIONS, Some(&backup_dir(path)))?;
+
+        Ok(Self { db, path: path.to_path_buf(), sync_writes })
+    }
+
+    /// Raw database handle for migrations and maintenance tooling
+    pub fn db(&self) -> &DB {
+        &self.db
+    }
+
+    /// Atomically apply a checkpoint across all column families
+    pub fn commit(&self, checkpoint: StateCheckpoint) -> Result<()> {
+        if checkpoint.is_empty() {
+            return Ok(());
+        }
+
+        let mut batch = WriteBatch::default();
+
+        let logs_cf = self.cf(CF_PROCESSED_LOGS)?;
+        for log in &checkpoint.processed_logs {
+            batch.put_cf(logs_cf, log_key(log), []);
+        }
+
+        if let Some(block) = checkpoint.last_processed_block {
+            batch.put_cf(self.cf(CF_CHECKPOINTS)?, LAST_PROCESSED_BLOCK_KEY, block.to_be_bytes());
+            // The cursor covers these blocks now, so their logs are never read again
+            let covered = LogId { block_number: block.saturating_add(1), log_index: 0 };
+            batch.delete_range_cf(logs_cf, log_key(&LogId { block_number: 0, log_index: 0 }), log_key(&covered));
+        }
+
+        let orders_cf = self.cf(CF_PENDING_ORDERS)?;
+        for order in &checkpoint.upserted_orders {
+            batch.put_cf(orders_cf, order.id.as_bytes(), serde_json::to_vec(order)?);
+        }
+        for order_id in &checkpoint.removed_orders {
+            batch.delete_cf(orders_cf, order_id.as_bytes());
+        }
+
+        let submissions_cf = self.cf(CF_SUBMISSIONS)?;
+        for submission in &checkpoint.upserted_submissions {
+            batch.put_cf(submissions_cf, submission.task_id.as_bytes(), serde_json::to_vec(submission)?);
+        }
+        for task_id in &checkpoint.confirmed_submissions {
+            batch.delete_cf(submissions_cf, task_id.as_bytes());
+        }
+
+        let tasks_cf = self.cf(CF_SCHEDULED_TASKS)?;
+        for task in &checkpoint.scheduled_tasks {
+            batch.put_cf(tasks_cf, task.task_id.as_bytes(), serde_json::to_vec(task)?);
+        }
+        for task_id in &checkpoint.finished_tasks {
+            batch.delete_cf(tasks_cf, task_id.as_bytes());
+        }
+
+        let deposits_cf = self.cf(CF_STAKE_DEPOSITS)?;
+        for deposit in &checkpoint.stake_deposits {
+            batch.put_cf(deposits_cf, deposit_key(deposit), serde_json::to_vec(deposit)?);
+        }
+
+        let nonces_cf = self.cf(CF_TRADER_NONCES)?;
+        for claimed in &checkpoint.claimed_nonces {
+            batch.put_cf(nonces_cf, nonce_key(claimed), serde_json::to_vec(claimed)?);
+        }
+        for expired in &checkpoint.expired_nonces {
+            batch.delete_cf(nonces_cf, nonce_key(expired));
+        }
+
+        let mut write_options = WriteOptions::default();
+        write_options.set_sync(self.sync_writes);
+        self.db.write_opt(batch, &write_options)?;
+
+        debug!(
+            "Committed checkpoint: block {:?}, {} logs, +{} / -{} orders, +{} / -{} submissions, +{} / -{} tasks, {} stake deposits, +{} / -{} nonces",
+            checkpoint.last_processed_block,
+            checkpoint.processed_logs.len(),
+            checkpoint.upserted_orders.len(),
+            checkpoint.removed_orders.len(),
+            checkpoint.upserted_submissions.len(),
+            checkpoint.confirmed_submissions.len(),
+            checkpoint.scheduled_tasks.len(),
+            checkpoint.finished_tasks.len(),
+            checkpoint.stake_deposits.len(),
+            checkpoint.claimed_nonces.len(),
+            checkpoint.expired_nonces.len(),
+        );
+        Ok(())
+    }
+
+    /// Flush memtables to disk, e.g. before shutting down
+    pub fn flush(&self) -> Result<()> {
+        self.db.flush()?;
+        Ok(())
+    }
+
+    /// Flush memtables and write a consistent on-disk snapshot under `<data_dir>/snapshots`
+    pub fn snapshot(&self) -> Result<PathBuf> {
+        let snapshot_dir = self.path.join("snapshots");
+        std::fs::create_dir_all(&snapshot_dir)?;
+
+        let snapshot_path = snapshot_dir.join(format!("checkpoint_{}", chrono::Utc::now().timestamp_millis()));
+        self.db.flush()?;
+        Checkpoint::new(&self.db)?.create_checkpoint(&snapshot_path)?;
+
+        info!("State store snapshot written to {:?}", snapshot_path);
+        Ok(snapshot_path)
+    }
+
+    /// Flush and close the store, then move its directory aside to `<data_dir>.archived-<millis>`
+    /// so the next start begins from empty state
+    pub fn archive(self) -> Result<PathBuf> {
+        self.db.flush()?;
+        let path = self.path.clone();
+        drop(self);
+
+        let mut archived = path.clone().into_os_string();
+        archived.push(format!(".archived-{}", chrono::Utc::now().timestamp_millis()));
+        let archived = PathBuf::from(archived);
+        std::fs::rename(&path, &archived)?;
+
+        info!("State store archived to {:?}", archived);
+        Ok(archived)
+    }
+
+    /// Last block whose effects have been durably applied
+    pub fn last_processed_block(&self) -> Result<Option<u64>> {
+        match self.db.get_cf(self.cf(CF_CHECKPOINTS)?, LAST_PROCESSED_BLOCK_KEY)? {
+            Some(bytes) => {
+                let bytes: [u8; 8] = bytes.as_slice().try_into()
+                    .map_err(|_| anyhow::anyhow!("Corrupt block checkpoint"))?;
+                Ok(Some(u64::from_be_bytes(bytes)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Whether the effects of the event logged at `log` have been durably applied
+    pub fn is_log_processed(&self, log: &LogId) -> Result<bool> {
+        Ok(self.db.get_cf(self.cf(CF_PROCESSED_LOGS)?, log_key(log))?.is_some())
+    }
+
+    pub fn pending_orders(&self) -> Result<Vec<DecryptedOrder>> {
+        self.load_all(CF_PENDING_ORDERS)
+    }
+
+    pub fn unconfirmed_submissions(&self) -> Result<Vec<PendingSubmission>> {
+        self.load_all(CF_SUBMISSIONS)
+    }
+
+    pub fn scheduled_tasks(&self) -> Result<Vec<ScheduledTask>> {
+        self.load_all(CF_SCHEDULED_TASKS)
+    }
+
+    /// Stake deposits made at or after unix secs `since`, oldest first
+    pub fn stake_deposits_since(&self, since: u64) -> Result<Vec<StakeDeposit>> {
+        let start = since.to_be_bytes();
+        let mut deposits = Vec::new();
+        for entry in self.db.iterator_cf(self.cf(CF_STAKE_DEPOSITS)?, IteratorMode::From(&start, Direction::Forward)) {
+            let (_, value) = entry?;
+            deposits.push(serde_json::from_slice(&value)?);
+        }
+        Ok(deposits)
+    }
+
+    /// Trader nonces claimed by accepted orders, within each trader's window
+    pub fn claimed_nonces(&self) -> Result<Vec<ClaimedNonce>> {
+        self.load_all(CF_TRADER_NONCES)
+    }
+
+    /// Load everything needed to resume after a restart
+    pub fn recover(&self) -> Result<RecoveredState> {
+        let state = RecoveredState {
+            last_processed_block: self.last_processed_block()?,
+            pending_orders: self.pending_orders()?,
+            unconfirmed_submissions: self.unconfirmed_submissions()?,
+            scheduled_tasks: self.scheduled_tasks()?,
+            claimed_nonces: self.claimed_nonces()?,
+        };
+
+        info!(
+            "Recovered state: block {:?}, {} pending orders, {} unconfirmed submissions, {} scheduled tasks, {} trader nonces",
+            state.last_processed_block,
+            state.pending_orders.len(),
+            state.unconfirmed_submissions.len(),
+            state.scheduled_tasks.len(),
+            state.claimed_nonces.len(),
+        );
+        Ok(state)
+    }
+
+    fn load_all<T: serde::de::DeserializeOwned>(&self, cf_name: &str) -> Result<Vec<T>> {
+        let mut values = Vec::new();
+        for entry in self.db.iterator_cf(self.cf(cf_name)?, IteratorMode::Start) {
+            let (_, value) = entry?;
+            values.push(serde_json::from_slice(&value)?);
+        }
+        Ok(values)
+    }
+
+    fn cf(&self, name: &str) -> Result<&ColumnFamily> {
+        self.db.cf_handle(name)
+            .ok_or_else(|| anyhow::anyhow!("Missing column family: {}", name))
+    }
+}
+
+impl NonceStore for Store {
+    fn record(&self, claimed: &ClaimedNonce, expired: Option<&ClaimedNonce>) -> Result<()> {
+        let mut checkpoint = StateCheckpoint::new();
+        checkpoint.claim_nonce(claimed.clone());
+        if let Some(expired) = expired {
+            checkpoint.expire_nonce(expired.clone());
+        }
+        self.commit(checkpoint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matching::{Decimal, OrderType, TimeInForce};
+    use crate::storage::SubmissionKind;
+    use tempfile::tempdir;
+
+    fn test_order(id: &str) -> DecryptedOrder {
+        DecryptedOrder {
+            id: id.to_string(),
+            trader: "trader_1".to_string(),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type: OrderType::Buy,
+            amount: Decimal::from(100),
+            price: Decimal::from(2000),
+            deadline: chrono::Utc::now().timestamp() as u64 + 3600,
+            encrypted_data: vec![1, 2, 3],
+            signature: None,
+            trader_nonce: None,
+            time_in_force: TimeInForce::Gtd,
+            received_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_archive_moves_state_aside() -> Result<()> {
+        let dir = tempdir()?;
+        let data_dir = dir.path().join("data");
+        let store = Store::open_path(&data_dir, false)?;
+        store.commit(StateCheckpoint::at_block(7))?;
+
+        let archived = Store::open_path(store.archive()?, false)?;
+        assert_eq!(archived.last_processed_block()?, Some(7));
+        assert!(!data_dir.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_backups_sit_beside_the_store() {
+        let backups = backup_dir(Path::new("/var/lib/eigenvault/state"));
+        assert_eq!(backups, PathBuf::from("/var/lib/eigenvault/state.backups"));
+    }
+
+    #[test]
+    fn test_commit_and_recover() -> Result<()> {
+        let dir = tempdir()?;
+        let store = Store::open_path(dir.path(), false)?;
+
+        let mut checkpoint = StateCheckpoint::at_block(42);
+        checkpoint.upsert_order(test_order("order_1"));
+        checkpoint.upsert_order(test_order("order_2"));
+        checkpoint.record_submission(PendingSubmission::new(
+            "task_1".to_string(),
+            "0xabc".to_string(),
+            SubmissionKind::TaskResponse,
+        ));
+        store.commit(checkpoint)?;
+
+        let state = store.recover()?;
+        assert_eq!(state.last_processed_block, Some(42));
+        assert_eq!(state.pending_orders.len(), 2);
+        assert_eq!(state.unconfirmed_submissions.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_scheduled_tasks_survive_until_finished() -> Result<()> {
+        let dir = tempdir()?;
+        let task = |task_id: &str| ScheduledTask {
+            task_id: task_id.to_string(),
+            orders_hash: "0x01".to_string(),
+            deadline: 1_000,
+        };
+        {
+            let store = Store::open_path(dir.path(), false)?;
+            let mut checkpoint = StateCheckpoint::new();
+            checkpoint.schedule_task(task("task_1"));
+            checkpoint.schedule_task(task("task_2"));
+            store.commit(checkpoint)?;
+
+            let mut checkpoint = StateCheckpoint::new();
+            checkpoint.finish_task("task_1".to_string());
+            store.commit(checkpoint)?;
+        }
+
+        let store = Store::open_path(dir.path(), false)?;
+        assert_eq!(store.recover()?.scheduled_tasks, vec![task("task_2")]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_removals_and_reopen() -> Result<()> {
+        let dir = tempdir()?;
+        {
+            let store = Store::open_path(dir.path(), false)?;
+            let mut checkpoint = StateCheckpoint::at_block(10);
+            checkpoint.upsert_order(test_order("order_1"));
+            store.commit(checkpoint)?;
+
+            let mut checkpoint = StateCheckpoint::new();
+            checkpoint.remove_order("order_1".to_string());
+            store.commit(checkpoint)?;
+        }
+
+        let store = Store::open_path(dir.path(), false)?;
+        assert_eq!(store.last_processed_block()?, Some(10));
+        assert!(store.pending_orders()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_processed_logs_until_cursor_passes() -> Result<()> {
+        let dir = tempdir()?;
+        let log = |block_number, log_index| LogId { block_number, log_index };
+        {
+            let store = Store::open_path(dir.path(), false)?;
+            store.commit(StateCheckpoint::at_block(10))?;
+            store.commit(StateCheckpoint::for_log(log(11, 0)))?;
+            store.commit(StateCheckpoint::for_log(log(12, 3)))?;
+        }
+
+        // A restart mid-range still knows which logs of the range were handled
+        let store = Store::open_path(dir.path(), false)?;
+        assert_eq!(store.last_processed_block()?, Some(10));
+        assert!(store.is_log_processed(&log(11, 0))?);
+        assert!(!store.is_log_processed(&log(11, 1))?);
+
+        store.commit(StateCheckpoint::at_block(11))?;
+        assert!(!store.is_log_processed(&log(11, 0))?);
+        assert!(store.is_log_processed(&log(12, 3))?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stake_deposits_since() -> Result<()> {
+        let dir = tempdir()?;
+        let deposit = |deposited_at, tx_hash: &str| StakeDeposit {
+            deposited_at,
+            amount_wei: 1_000,
+            tx_hash: tx_hash.to_string(),
+        };
+        {
+            let store = Store::open_path(dir.path(), false)?;
+            let mut checkpoint = StateCheckpoint::new();
+            checkpoint.record_stake_deposit(deposit(100, "0x01"));
+            checkpoint.record_stake_deposit(deposit(300, "0x03"));
+            checkpoint.record_stake_deposit(deposit(200, "0x02"));
+            store.commit(checkpoint)?;
+        }
+
+        let store = Store::open_path(dir.path(), false)?;
+        assert_eq!(store.stake_deposits_since(200)?, vec![deposit(200, "0x02"), deposit(300, "0x03")]);
+        assert!(store.stake_deposits_since(301)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_claimed_nonces_survive_a_restart() -> Result<()> {
+        let dir = tempdir()?;
+        let claimed = |nonce, order_id: &str| ClaimedNonce {
+            trader: "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+            nonce,
+            order_id: order_id.to_string(),
+        };
+        {
+            let store = Store::open_path(dir.path(), false)?;
+            store.record(&claimed(1, "order_1"), None)?;
+            store.record(&claimed(2, "order_2"), None)?;
+            store.record(&claimed(3, "order_3"), Some(&claimed(1, "order_1")))?;
+        }
+
+        let store = Store::open_path(dir.path(), false)?;
+        assert_eq!(store.recover()?.claimed_nonces, vec![claimed(2, "order_2"), claimed(3, "order_3")]);
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+use anyhow::Result;
+use rocksdb::DB;
+use tracing::{info, warn};
+
+use crate::config::Settings;
+use super::store::CF_META;
+
+const BINARY_VERSION_KEY: &[u8] = b"binary_version";
+
+/// Version of the running binary, recorded in the state store on every start
+pub const BINARY_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// State visible to an upgrade hook
+pub struct UpgradeContext<'a> {
+    pub db: &'a DB,
+    pub config: &'a Settings,
+}
+
+/// One-off work needed when crossing a release, e.g. rewriting key files or
+/// config. Runs when the previous binary is older than `version` and the
+/// current one is at least `version`.
+///
+/// On-disk schema changes belong in `migrations::MIGRATIONS`, which run when
+/// the store is opened, before any hook.
+pub struct UpgradeHook {
+    pub version: &'static str,
+    pub description: &'static str,
+    pub apply: fn(&UpgradeContext) -> Result<()>,
+}
+
+/// Every upgrade hook this binary knows about, in ascending version order
+pub const UPGRADE_HOOKS: &[UpgradeHook] = &[];
+
+/// Upgrade performed at startup
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpgradeRecord {
+    pub from: String,
+    pub to: String,
+    pub hooks: Vec<&'static str>,
+}
+
+/// Binary version that last ran against this store, if any
+pub fn previous_version(db: &DB) -> Result<Option<String>> {
+    let meta = db.cf_handle(CF_META)
+        .ok_or_else(|| anyhow::anyhow!("Missing column family: {}", CF_META))?;
+
+    match db.get_cf(meta, BINARY_VERSION_KEY)? {
+        Some(bytes) => Ok(Some(String::from_utf8(bytes)
+            .map_err(|_| anyhow::anyhow!("Corrupt binary version"))?)),
+        None => Ok(None),
+    }
+}
+
+/// Run the hooks between the previously recorded binary version and `current`,
+/// then record `current`. Returns the upgrade if one happened.
+///
+/// A fresh store has nothing to upgrade. Stores written before versions were
+/// recorded are treated as coming from 0.0.0, so every hook runs once.
+pub fn upgrade(context: &UpgradeContext, hooks: &[UpgradeHook], current: &str, fresh: bool) -> Result<Option<UpgradeRecord>> {
+    let previous = match previous_version(context.db)? {
+        Some(previous) => previous,
+        None if fresh => {
+            record_version(context.db, current)?;
+            return Ok(None);
+        }
+        None => "0.0.0".to_string(),
+    };
+
+    let from = parse_version(&previous)?;
+    let to = parse_version(current)?;
+
+    if from == to {
+        return Ok(None);
+    }
+    if from > to {
+        // Schema downgrades are already refused by the migrations; hooks can't be undone
+        warn!("State was last written by v{}, now running older v{}", previous, current);
+        record_version(context.db, current)?;
+        return Ok(None);
+    }
+
+    let mut applied = Vec::new();
+    for hook in hooks {
+        let version = parse_version(hook.version)?;
+        if version > from && version <= to {
+            info!("Running upgrade hook for v{}: {}", hook.version, hook.description);
+            (hook.apply)(context)?;
+            applied.push(hook.description);
+        }
+    }
+
+    // Only recorded once every hook succeeded, so a failed upgrade is retried on next start
+    record_version(context.db, current)?;
+    info!("Upgraded operator state from v{} to v{}", previous, current);
+
+    Ok(Some(UpgradeRecord {
+        from: previous,
+        to: current.to_string(),
+        hooks: applied,
+    }))
+}
+
+fn record_version(db: &DB, version: &str) -> Result<()> {
+    let meta = db.cf_handle(CF_META)
+        .ok_or_else(|| anyhow::anyhow!("Missing column family: {}", CF_META))?;
+    db.put_cf(meta, BINARY_VERSION_KEY, version.as_bytes())?;
+    Ok(())
+}
+
+/// `major.minor.patch`, ignoring any pre-release or build suffix
+fn parse_version(version: &str) -> Result<(u64, u64, u64)> {
+    let core = version.split(|c| c == '-' || c == '+').next().unwrap_or(version);
+    let parts = core.split('.')
+        .map(|part| part.parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| anyhow::anyhow!("Invalid version: {}", version))?;
+
+    match parts.as_slice() {
+        [major, minor, patch] => Ok((*major, *minor, *patch)),
+        _ => Err(anyhow::anyhow!("Invalid version: {}", version)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Store;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::tempdir;
+
+    static RUNS: AtomicUsize = AtomicUsize::new(0);
+
+    fn count_run(_context: &UpgradeContext) -> Result<()> {
+        RUNS.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    const HOOKS: &[UpgradeHook] = &[
+        UpgradeHook { version: "0.2.0", description: "v0.2.0 hook", apply: count_run },
+        UpgradeHook { version: "0.3.0", description: "v0.3.0 hook", apply: count_run },
+    ];
+
+    #[test]
+    fn test_upgrade_runs_hooks_once() -> Result<()> {
+        let dir = tempdir()?;
+        let store = Store::open_path(dir.path(), false)?;
+        let config = Settings::default();
+        let context = UpgradeContext { db: store.db(), config: &config };
+
+        // A fresh store only records the version
+        assert_eq!(upgrade(&context, HOOKS, "0.1.0", true)?, None);
+        assert_eq!(previous_version(store.db())?.as_deref(), Some("0.1.0"));
+
+        let record = upgrade(&context, HOOKS, "0.2.1", false)?.unwrap();
+        assert_eq!(record.from, "0.1.0");
+        assert_eq!(record.hooks, vec!["v0.2.0 hook"]);
+
+        // Same version again is a no-op
+        assert_eq!(upgrade(&context, HOOKS, "0.2.1", false)?, None);
+        assert_eq!(RUNS.load(Ordering::SeqCst), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("1.2.3").unwrap(), (1, 2, 3));
+        assert_eq!(parse_version("1.2.3-rc.1").unwrap(), (1, 2, 3));
+        assert!(parse_version("1.2").is_err());
+    }
+}
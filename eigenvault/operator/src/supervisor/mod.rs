@@ -0,0 +1,7 @@
+pub mod policy;
+pub mod runner;
+pub mod systemd;
+
+pub use policy::{RestartPolicy, RestartTracker};
+pub use runner::supervise;
+pub use systemd::Notifier;
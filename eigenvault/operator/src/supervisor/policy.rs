@@ -0,0 +1,114 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::config::SupervisorConfig;
+
+/// How a failed component is restarted and when the supervisor gives up
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+    pub max_restarts: usize,
+    pub window: Duration,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl From<&SupervisorConfig> for RestartPolicy {
+    fn from(config: &SupervisorConfig) -> Self {
+        Self {
+            max_restarts: config.max_restarts,
+            window: Duration::from_secs(config.restart_window_seconds),
+            initial_backoff: Duration::from_millis(config.initial_backoff_ms),
+            max_backoff: Duration::from_millis(config.max_backoff_ms),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Exponential backoff for the `attempt`th restart within the window, starting at 1
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16) as u32;
+        self.initial_backoff
+            .saturating_mul(2u32.pow(exponent))
+            .min(self.max_backoff)
+    }
+}
+
+/// Recent failures of one component
+#[derive(Debug)]
+pub struct RestartTracker {
+    policy: RestartPolicy,
+    failures: VecDeque<Instant>,
+}
+
+impl RestartTracker {
+    pub fn new(policy: RestartPolicy) -> Self {
+        Self {
+            policy,
+            failures: VecDeque::new(),
+        }
+    }
+
+    /// Record a failure; returns the delay before restarting, or `None` once restarts are exhausted
+    pub fn record_failure(&mut self, now: Instant) -> Option<Duration> {
+        while let Some(oldest) = self.failures.front() {
+            if now.duration_since(*oldest) > self.policy.window {
+                self.failures.pop_front();
+            } else {
+                break;
+            }
+        }
+        self.failures.push_back(now);
+
+        if self.failures.len() > self.policy.max_restarts {
+            None
+        } else {
+            Some(self.policy.backoff(self.failures.len()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RestartPolicy {
+        RestartPolicy {
+            max_restarts: 3,
+            window: Duration::from_secs(60),
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(3),
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = policy();
+        assert_eq!(policy.backoff(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff(3), Duration::from_secs(3));
+        assert_eq!(policy.backoff(50), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_escalates_after_max_restarts_in_window() {
+        let mut tracker = RestartTracker::new(policy());
+        let start = Instant::now();
+
+        for i in 0..3 {
+            assert!(tracker.record_failure(start + Duration::from_secs(i)).is_some());
+        }
+        assert!(tracker.record_failure(start + Duration::from_secs(3)).is_none());
+    }
+
+    #[test]
+    fn test_old_failures_leave_the_window() {
+        let mut tracker = RestartTracker::new(policy());
+        let start = Instant::now();
+
+        for i in 0..3 {
+            tracker.record_failure(start + Duration::from_secs(i));
+        }
+        let delay = tracker.record_failure(start + Duration::from_secs(120));
+        assert_eq!(delay, Some(Duration::from_secs(1)));
+    }
+}
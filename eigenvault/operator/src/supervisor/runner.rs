@@ -0,0 +1,122 @@
+use anyhow::{anyhow, Result};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::task::AbortHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, warn};
+
+use crate::api::HealthRegistry;
+use crate::metrics::metrics;
+use super::{RestartPolicy, RestartTracker};
+
+/// Aborts the component task if the supervisor itself is dropped or aborted
+struct AbortOnDrop(AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Run a component, restarting it with backoff whenever it fails or panics.
+///
+/// Returns `Ok` once the component stops after shutdown, and an error when it
+/// keeps failing beyond the policy so the operator can shut down.
+pub async fn supervise<F, Fut>(
+    name: &'static str,
+    policy: RestartPolicy,
+    health: Arc<HealthRegistry>,
+    shutdown: CancellationToken,
+    mut start: F,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let mut tracker = RestartTracker::new(policy.clone());
+
+    loop {
+        // A separate task turns panics into ordinary failures
+        let mut handle = tokio::spawn(start());
+        let _guard = AbortOnDrop(handle.abort_handle());
+
+        let error = match (&mut handle).await {
+            Ok(Ok(())) if shutdown.is_cancelled() => return Ok(()),
+            Ok(Ok(())) => anyhow!("{} exited unexpectedly", name),
+            Ok(Err(e)) => e,
+            Err(e) => anyhow!("{} panicked: {}", name, e),
+        };
+
+        if shutdown.is_cancelled() {
+            return Err(error);
+        }
+
+        let delay = match tracker.record_failure(Instant::now()) {
+            Some(delay) => delay,
+            None => {
+                error!("{} failed more than {} times within {:?}; giving up", name, policy.max_restarts, policy.window);
+                return Err(error);
+            }
+        };
+
+        warn!("{} failed: {:?}; restarting in {:?}", name, error, delay);
+        metrics().component_restarts.with_label_values(&[name]).inc();
+        health.record_restart(name).await;
+
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            _ = shutdown.cancelled() => return Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn policy(max_restarts: usize) -> RestartPolicy {
+        RestartPolicy {
+            max_restarts,
+            window: Duration::from_secs(60),
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restarts_until_component_succeeds() {
+        let health = Arc::new(HealthRegistry::new(&[], 90));
+        let shutdown = CancellationToken::new();
+        let attempts = Arc::new(AtomicUsize::new(0));
+
+        let result = supervise("flaky", policy(5), health.clone(), shutdown.clone(), || {
+            let attempts = attempts.clone();
+            let shutdown = shutdown.clone();
+            async move {
+                if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                    panic!("boom");
+                }
+                shutdown.cancel();
+                Ok(())
+            }
+        }).await;
+
+        assert!(result.is_ok());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(health.snapshot().await.restarts["flaky"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_escalates_after_repeated_failures() {
+        let health = Arc::new(HealthRegistry::new(&[], 90));
+
+        let result = supervise("broken", policy(2), health, CancellationToken::new(), || async {
+            Err(anyhow!("always fails"))
+        }).await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// sd_notify client; a no-op unless started by systemd with `NOTIFY_SOCKET` set
+pub struct Notifier {
+    #[cfg(unix)]
+    socket: Option<(std::os::unix::net::UnixDatagram, std::os::unix::net::SocketAddr)>,
+    watchdog_interval: Option<Duration>,
+}
+
+impl Notifier {
+    pub fn from_env() -> Self {
+        let watchdog_interval = watchdog_interval(
+            std::env::var("WATCHDOG_USEC").ok().as_deref(),
+            std::env::var("WATCHDOG_PID").ok().as_deref(),
+            std::process::id(),
+        );
+
+        #[cfg(unix)]
+        {
+            let socket = std::env::var("NOTIFY_SOCKET").ok().and_then(|path| match connect(&path) {
+                Ok(socket) => Some(socket),
+                Err(e) => {
+                    warn!("Ignoring NOTIFY_SOCKET {}: {:?}", path, e);
+                    None
+                }
+            });
+            if socket.is_some() {
+                info!("systemd notifications enabled, watchdog keepalive every {:?}", watchdog_interval);
+            }
+            Self { socket, watchdog_interval }
+        }
+
+        #[cfg(not(unix))]
+        Self { watchdog_interval }
+    }
+
+    /// How often to send `WATCHDOG=1`, if systemd expects keepalives
+    pub fn watchdog_interval(&self) -> Option<Duration> {
+        if self.is_enabled() {
+            self.watchdog_interval
+        } else {
+            None
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        #[cfg(unix)]
+        return self.socket.is_some();
+        #[cfg(not(unix))]
+        return false;
+    }
+
+    pub fn ready(&self) {
+        self.notify("READY=1");
+    }
+
+    pub fn watchdog(&self) {
+        self.notify("WATCHDOG=1");
+    }
+
+    pub fn stopping(&self) {
+        self.notify("STOPPING=1");
+    }
+
+    fn notify(&self, state: &str) {
+        #[cfg(unix)]
+        if let Some((socket, address)) = &self.socket {
+            match socket.send_to_addr(state.as_bytes(), address) {
+                Ok(_) => debug!("sd_notify {}", state),
+                Err(e) => warn!("Failed to send {} to systemd: {:?}", state, e),
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = state;
+    }
+}
+
+#[cfg(unix)]
+fn connect(path: &str) -> std::io::Result<(std::os::unix::net::UnixDatagram, std::os::unix::net::SocketAddr)> {
+    use std::os::unix::net::{SocketAddr, UnixDatagram};
+
+    // A leading '@' names a Linux abstract socket
+    let address = match path.strip_prefix('@') {
+        #[cfg(target_os = "linux")]
+        Some(name) => {
+            use std::os::linux::net::SocketAddrExt;
+            SocketAddr::from_abstract_name(name)?
+        }
+        _ => SocketAddr::from_pathname(path)?,
+    };
+
+    Ok((UnixDatagram::unbound()?, address))
+}
+
+/// Half the `WATCHDOG_USEC` timeout, as sd_watchdog_enabled(3) recommends; None when the
+/// watchdog is off or meant for another process
+fn watchdog_interval(usec: Option<&str>, pid: Option<&str>, own_pid: u32) -> Option<Duration> {
+    if let Some(pid) = pid {
+        if pid.parse::<u32>().ok()? != own_pid {
+            return None;
+        }
+    }
+
+    let usec = usec?.parse::<u64>().ok().filter(|usec| *usec > 0)?;
+    Some(Duration::from_micros(usec / 2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchdog_interval() {
+        assert_eq!(watchdog_interval(Some("30000000"), None, 7), Some(Duration::from_secs(15)));
+        assert_eq!(watchdog_interval(Some("30000000"), Some("7"), 7), Some(Duration::from_secs(15)));
+        assert_eq!(watchdog_interval(Some("30000000"), Some("8"), 7), None);
+        assert_eq!(watchdog_interval(Some("0"), None, 7), None);
+        assert_eq!(watchdog_interval(None, None, 7), None);
+    }
+}
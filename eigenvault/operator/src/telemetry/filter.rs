@@ -0,0 +1,61 @@
+use anyhow::{anyhow, Result};
+use std::sync::OnceLock;
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+pub const DEFAULT_DIRECTIVES: &str = "eigenvault_operator=debug,info";
+
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Build the reloadable filter layer from `RUST_LOG` (or the defaults)
+/// and keep its handle for runtime updates.
+pub fn reloadable() -> reload::Layer<EnvFilter, Registry> {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_DIRECTIVES));
+    let (layer, handle) = reload::Layer::new(filter);
+
+    let _ = FILTER_HANDLE.set(handle);
+    layer
+}
+
+/// Currently active filter directives
+pub fn current() -> Result<String> {
+    handle()?
+        .with_current(|filter| filter.to_string())
+        .map_err(|e| anyhow!("Failed to read log filter: {}", e))
+}
+
+/// Replace the active filter, e.g. `eigenvault_operator::networking=trace,info`.
+///
+/// Directives are validated before anything is swapped, so a typo leaves the
+/// existing filter in place.
+pub fn update(directives: &str) -> Result<()> {
+    let filter = parse(directives)?;
+
+    handle()?
+        .reload(filter)
+        .map_err(|e| anyhow!("Failed to reload log filter: {}", e))
+}
+
+fn parse(directives: &str) -> Result<EnvFilter> {
+    if directives.trim().is_empty() {
+        return Err(anyhow!("Log filter directives cannot be empty"));
+    }
+
+    EnvFilter::try_new(directives).map_err(|e| anyhow!("Invalid log filter directives: {}", e))
+}
+
+fn handle() -> Result<&'static reload::Handle<EnvFilter, Registry>> {
+    FILTER_HANDLE.get().ok_or_else(|| anyhow!("Tracing subscriber not initialized"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_directives() {
+        assert!(parse("eigenvault_operator::networking=trace,info").is_ok());
+        assert!(parse("").is_err());
+        assert!(parse("networking=loud").is_err());
+    }
+}
@@ -0,0 +1,88 @@
+use anyhow::Result;
+use rolling_file::{BasicRollingFileAppender, RollingConditionBasic};
+use std::path::Path;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::Layer;
+
+use crate::config::{LogFormat, LogRotation, LoggingConfig};
+
+use super::BoxedLayer;
+
+/// Console output layer in the configured format
+pub fn stdout_layer(config: &LoggingConfig) -> BoxedLayer {
+    match config.format {
+        LogFormat::Text => tracing_subscriber::fmt::layer().boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed(),
+    }
+}
+
+/// Rotating file output layer.
+///
+/// Writes go through a background thread; the returned guard flushes it on drop.
+pub fn file_layer(config: &LoggingConfig) -> Result<(BoxedLayer, WorkerGuard)> {
+    std::fs::create_dir_all(&config.directory)?;
+
+    let appender = BasicRollingFileAppender::new(
+        Path::new(&config.directory).join(&config.file_name),
+        rolling_condition(config),
+        config.max_files,
+    )?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    let layer = match config.format {
+        LogFormat::Text => tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_ansi(false)
+            .boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(false)
+            .with_writer(writer)
+            .boxed(),
+    };
+
+    Ok((layer, guard))
+}
+
+fn rolling_condition(config: &LoggingConfig) -> RollingConditionBasic {
+    let mut condition = RollingConditionBasic::new();
+
+    condition = match config.rotation {
+        LogRotation::Hourly => condition.hourly(),
+        LogRotation::Daily => condition.daily(),
+        LogRotation::Never => condition,
+    };
+
+    if config.max_file_size_mb > 0 {
+        condition = condition.max_size(config.max_file_size_mb * 1024 * 1024);
+    }
+
+    condition
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_file_layer_creates_log_directory() -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let config = LoggingConfig {
+            file_enabled: true,
+            directory: temp_dir.path().join("logs").to_string_lossy().to_string(),
+            format: LogFormat::Json,
+            ..LoggingConfig::default()
+        };
+
+        let (_layer, _guard) = file_layer(&config)?;
+        assert!(temp_dir.path().join("logs").exists());
+
+        Ok(())
+    }
+}
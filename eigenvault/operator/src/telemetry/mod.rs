@@ -0,0 +1,88 @@
+pub mod filter;
+pub mod logging;
+pub mod propagation;
+
+use anyhow::Result;
+use opentelemetry::{global, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::{propagation::TraceContextPropagator, trace::Sampler, Resource};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{
+    layer::{Layered, SubscriberExt},
+    reload,
+    util::SubscriberInitExt,
+    EnvFilter, Layer, Registry,
+};
+
+use crate::config::{Settings, TelemetryConfig};
+
+pub use propagation::{current_context, set_parent, TraceContext};
+
+/// Subscriber that output layers are stacked on top of
+pub type FilteredRegistry = Layered<reload::Layer<EnvFilter, Registry>, Registry>;
+
+pub type BoxedLayer = Box<dyn Layer<FilteredRegistry> + Send + Sync>;
+
+/// Keeps background log writers alive; drop it only when the process exits
+pub struct TelemetryGuard {
+    _file_writer: Option<WorkerGuard>,
+}
+
+/// Install the global tracing subscriber.
+///
+/// Without `settings` (e.g. for CLI subcommands) only text console output is
+/// installed. Otherwise the logging and telemetry sections select the log
+/// format, rotating file output and OTLP span export.
+pub fn init(settings: Option<&Settings>) -> Result<TelemetryGuard> {
+    // W3C trace context is used for P2P propagation even when export is off
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let logging_config = settings.map(|s| s.logging.clone()).unwrap_or_default();
+
+    let mut layers: Vec<BoxedLayer> = vec![logging::stdout_layer(&logging_config)];
+    let mut file_writer = None;
+
+    if logging_config.file_enabled {
+        let (layer, guard) = logging::file_layer(&logging_config)?;
+        layers.push(layer);
+        file_writer = Some(guard);
+    }
+
+    if let Some(config) = settings.map(|s| &s.telemetry).filter(|c| c.otlp_enabled) {
+        layers.push(otlp_layer(config)?);
+    }
+
+    tracing_subscriber::registry()
+        .with(filter::reloadable())
+        .with(layers)
+        .init();
+
+    Ok(TelemetryGuard { _file_writer: file_writer })
+}
+
+fn otlp_layer(config: &TelemetryConfig) -> Result<BoxedLayer> {
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(&config.otlp_endpoint),
+        )
+        .with_trace_config(
+            opentelemetry_sdk::trace::config()
+                .with_sampler(Sampler::ParentBased(Box::new(
+                    Sampler::TraceIdRatioBased(config.sample_ratio),
+                )))
+                .with_resource(Resource::new(vec![
+                    KeyValue::new("service.name", config.service_name.clone()),
+                ])),
+        )
+        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer).boxed())
+}
+
+/// Flush pending spans before exit
+pub fn shutdown() {
+    global::shutdown_tracer_provider();
+}
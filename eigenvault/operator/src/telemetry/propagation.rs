@@ -0,0 +1,38 @@
+use opentelemetry::global;
+use std::collections::HashMap;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// W3C trace context headers (`traceparent`, `tracestate`) carried in P2P envelopes
+pub type TraceContext = HashMap<String, String>;
+
+/// Serialize the current span's context for an outgoing message
+pub fn current_context() -> TraceContext {
+    let mut carrier = TraceContext::new();
+    let context = tracing::Span::current().context();
+    global::get_text_map_propagator(|propagator| propagator.inject_context(&context, &mut carrier));
+    carrier
+}
+
+/// Make `span` a child of the remote span described by `carrier`
+pub fn set_parent(span: &tracing::Span, carrier: &TraceContext) {
+    if carrier.is_empty() {
+        return;
+    }
+
+    let parent = global::get_text_map_propagator(|propagator| propagator.extract(carrier));
+    span.set_parent(parent);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_context_without_active_span() {
+        let carrier = current_context();
+        assert!(!carrier.contains_key("traceparent"));
+
+        // Attaching an empty carrier is a no-op
+        set_parent(&tracing::Span::none(), &carrier);
+    }
+}
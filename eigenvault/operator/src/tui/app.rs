@@ -0,0 +1,86 @@
+use anyhow::{anyhow, Result};
+use crossterm::{
+    event::{self, Event, KeyCode},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+use std::io::{stdout, Stdout};
+use std::time::{Duration, Instant};
+
+use crate::api::OperatorStatus;
+use super::ui;
+
+/// What the dashboard currently shows
+pub struct View {
+    pub url: String,
+    pub status: Option<OperatorStatus>,
+    pub error: Option<String>,
+}
+
+/// Run the live dashboard against a running operator's `/admin/status` endpoint
+/// until the user presses `q`.
+pub async fn run(url: String, token: String, refresh: Duration) -> Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()?;
+
+    enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen)?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+
+    let result = event_loop(&mut terminal, &client, url, &token, refresh).await;
+
+    // Always restore the terminal, even if the loop failed
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+
+    result
+}
+
+async fn event_loop(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    client: &reqwest::Client,
+    url: String,
+    token: &str,
+    refresh: Duration,
+) -> Result<()> {
+    let mut view = View { url, status: None, error: None };
+    let mut last_fetch: Option<Instant> = None;
+
+    loop {
+        if last_fetch.map(|at| at.elapsed() >= refresh).unwrap_or(true) {
+            match fetch_status(client, &view.url, token).await {
+                Ok(status) => {
+                    view.status = Some(status);
+                    view.error = None;
+                }
+                Err(e) => view.error = Some(e.to_string()),
+            }
+            last_fetch = Some(Instant::now());
+        }
+
+        terminal.draw(|frame| ui::render(frame, &view))?;
+
+        if event::poll(Duration::from_millis(200))? {
+            if let Event::Key(key) = event::read()? {
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('r') => last_fetch = None,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+async fn fetch_status(client: &reqwest::Client, url: &str, token: &str) -> Result<OperatorStatus> {
+    let response = client.get(url).bearer_auth(token).send().await?;
+
+    if !response.status().is_success() {
+        return Err(anyhow!("Operator API returned {}", response.status()));
+    }
+
+    Ok(response.json().await?)
+}
@@ -0,0 +1,152 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph, Row, Table},
+    Frame,
+};
+
+use crate::api::OperatorStatus;
+use super::app::View;
+
+pub fn render(frame: &mut Frame, view: &View) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(4), Constraint::Percentage(50), Constraint::Min(6)])
+        .split(frame.size());
+
+    render_header(frame, rows[0], view);
+
+    let Some(status) = &view.status else {
+        return;
+    };
+
+    let middle = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[2]);
+
+    render_peers(frame, middle[0], status);
+    render_pools(frame, middle[1], status);
+    render_matches(frame, bottom[0], status);
+    render_submissions(frame, bottom[1], status);
+}
+
+fn render_header(frame: &mut Frame, area: Rect, view: &View) {
+    let mut lines = vec![Line::from(format!("{}    (q: quit, r: refresh)", view.url))];
+
+    if let Some(status) = &view.status {
+        lines.push(Line::from(format!(
+            "block {}  |  peers {}  |  proof queue {}  |  updated {}",
+            status.last_processed_block,
+            status.peers.iter().filter(|peer| peer.is_active).count(),
+            status.proof_queue_depth,
+            format_timestamp(status.updated_at),
+        )));
+    }
+    if let Some(error) = &view.error {
+        lines.push(Line::styled(format!("error: {}", error), Style::default().fg(Color::Red)));
+    }
+
+    let header = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(" eigenvault-operator top "));
+    frame.render_widget(header, area);
+}
+
+fn render_peers(frame: &mut Frame, area: Rect, status: &OperatorStatus) {
+    let rows = status.peers.iter().map(|peer| {
+        Row::new(vec![
+            peer.peer_id.clone(),
+            if peer.is_active { "up" } else { "down" }.to_string(),
+            peer.messages_sent.to_string(),
+            format!("{:.1}", peer.reputation),
+        ])
+    });
+
+    let table = Table::new(rows, [
+        Constraint::Percentage(45),
+        Constraint::Percentage(15),
+        Constraint::Percentage(20),
+        Constraint::Percentage(20),
+    ])
+    .header(header_row(&["peer", "state", "msgs", "rep"]))
+    .block(Block::default().borders(Borders::ALL).title(" Peers "));
+    frame.render_widget(table, area);
+}
+
+fn render_pools(frame: &mut Frame, area: Rect, status: &OperatorStatus) {
+    let rows = status.pools.iter().map(|pool| {
+        Row::new(vec![
+            pool.pool_key.clone(),
+            pool.active_buy_orders.to_string(),
+            pool.active_sell_orders.to_string(),
+            pool.spread.map(|spread| format!("{:.4}", spread)).unwrap_or_else(|| "-".to_string()),
+        ])
+    });
+
+    let table = Table::new(rows, [
+        Constraint::Percentage(40),
+        Constraint::Percentage(20),
+        Constraint::Percentage(20),
+        Constraint::Percentage(20),
+    ])
+    .header(header_row(&["pool", "buys", "sells", "spread"]))
+    .block(Block::default().borders(Borders::ALL).title(" Pending orders "));
+    frame.render_widget(table, area);
+}
+
+fn render_matches(frame: &mut Frame, area: Rect, status: &OperatorStatus) {
+    let rows = status.recent_matches.iter().map(|order_match| {
+        Row::new(vec![
+            format_timestamp(order_match.timestamp),
+            order_match.pool_key.clone(),
+            format!("{:.4}", order_match.matched_amount),
+            format!("{:.4}", order_match.matched_price),
+        ])
+    });
+
+    let table = Table::new(rows, [
+        Constraint::Percentage(20),
+        Constraint::Percentage(40),
+        Constraint::Percentage(20),
+        Constraint::Percentage(20),
+    ])
+    .header(header_row(&["time", "pool", "amount", "price"]))
+    .block(Block::default().borders(Borders::ALL).title(" Recent matches "));
+    frame.render_widget(table, area);
+}
+
+fn render_submissions(frame: &mut Frame, area: Rect, status: &OperatorStatus) {
+    let rows = status.recent_submissions.iter().map(|submission| {
+        Row::new(vec![
+            format_timestamp(submission.submitted_at),
+            submission.task_id.clone(),
+            submission.tx_hash.clone(),
+            if submission.confirmed { "confirmed" } else { "pending" }.to_string(),
+        ])
+    });
+
+    let table = Table::new(rows, [
+        Constraint::Percentage(15),
+        Constraint::Percentage(35),
+        Constraint::Percentage(30),
+        Constraint::Percentage(20),
+    ])
+    .header(header_row(&["time", "task", "tx", "state"]))
+    .block(Block::default().borders(Borders::ALL).title(" On-chain submissions "));
+    frame.render_widget(table, area);
+}
+
+fn header_row(titles: &[&'static str]) -> Row<'static> {
+    Row::new(titles.to_vec()).style(Style::default().add_modifier(Modifier::BOLD))
+}
+
+fn format_timestamp(timestamp: u64) -> String {
+    chrono::DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|time| time.format("%H:%M:%S").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
@@ -0,0 +1,14 @@
+use wasm_bindgen::prelude::*;
+
+use crate::PlainOrder;
+
+/// Encrypt an order for the committee.
+///
+/// `public_key` comes from the committee key document and `order` is a JSON `PlainOrder`.
+/// Returns a JSON `SealedOrder` with the id, commitment and bytes to pass to `storeOrder`.
+#[wasm_bindgen(js_name = sealOrder)]
+pub fn seal_order(public_key: &[u8], order: &str) -> Result<String, JsError> {
+    let order: PlainOrder = serde_json::from_str(order).map_err(|e| JsError::new(&e.to_string()))?;
+    let sealed = crate::seal(public_key, &order).map_err(|e| JsError::new(&e.to_string()))?;
+    serde_json::to_string(&sealed).map_err(|e| JsError::new(&e.to_string()))
+}
@@ -0,0 +1,233 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::Result;
+use rand_core::OsRng;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::{Oaep, RsaPublicKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "wasm")]
+mod bindings;
+
+// Mirrors the operator's `EncryptedOrderData` and `EncryptionManager` byte for byte: field
+// order, enum names and decimal formatting all end up in the ciphertext and commitment. The
+// golden test below checks this against the operator's own fixture.
+
+/// Decimal places of the operator's fixed-point amounts and prices
+const DECIMALS: usize = 18;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OrderType {
+    Buy,
+    Sell,
+}
+
+/// An order as the trader states it, before encryption
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlainOrder {
+    pub trader: String,
+    pub pool_key: String,
+    pub order_type: OrderType,
+    /// Decimal string such as `"1.5"`, at most 18 decimal places
+    pub amount: String,
+    pub price: String,
+    pub deadline: u64,
+    /// EIP-712 signature from the trader's wallet
+    #[serde(default)]
+    pub signature: Option<String>,
+    /// Replay nonce, unique per trader and counting from 1; signed along with the order
+    #[serde(default)]
+    pub trader_nonce: Option<u64>,
+}
+
+/// The plaintext operators decrypt
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EncryptedOrderData {
+    pub trader: String,
+    pub pool_key: String,
+    pub order_type: OrderType,
+    /// Shortest decimal string, as the operator's `Decimal` writes it
+    pub amount: String,
+    pub price: String,
+    pub deadline: u64,
+    pub nonce: Vec<u8>,
+    pub commitment: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trader_nonce: Option<u64>,
+}
+
+/// An encrypted order, ready for the OrderVault
+#[derive(Debug, Clone, Serialize)]
+pub struct SealedOrder {
+    /// bytes32 id, derived from the commitment
+    pub order_id: String,
+    pub commitment: String,
+    /// Wrapped order key, nonce and AES-GCM ciphertext, 0x-prefixed hex
+    pub encrypted_order: String,
+}
+
+/// Encrypt `order` for the committee under a fresh order key and nonces
+pub fn seal(public_key: &[u8], order: &PlainOrder) -> Result<SealedOrder> {
+    let (amount, price) = (units(&order.amount)?, units(&order.price)?);
+    if amount == 0 || price == 0 {
+        return Err(anyhow::anyhow!("Order amount and price must be positive"));
+    }
+
+    let mut payload = EncryptedOrderData {
+        trader: order.trader.clone(),
+        pool_key: order.pool_key.clone(),
+        order_type: order.order_type,
+        amount: decimal(amount),
+        price: decimal(price),
+        deadline: order.deadline,
+        nonce: random::<32>()?.to_vec(),
+        commitment: String::new(),
+        signature: order.signature.clone(),
+        trader_nonce: order.trader_nonce,
+    };
+    payload.commitment = commitment(&payload)?;
+    let encrypted_order = encrypt(public_key, random::<32>()?, random::<12>()?, &payload)?;
+
+    Ok(SealedOrder {
+        order_id: format!("0x{}", payload.commitment),
+        encrypted_order: format!("0x{}", hex::encode(encrypted_order)),
+        commitment: payload.commitment,
+    })
+}
+
+/// Same hash as `EncryptionManager::generate_commitment`
+pub fn commitment(order: &EncryptedOrderData) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(order.trader.as_bytes());
+    hasher.update(order.pool_key.as_bytes());
+    hasher.update(units(&order.amount)?.to_le_bytes());
+    hasher.update(units(&order.price)?.to_le_bytes());
+    hasher.update(order.deadline.to_le_bytes());
+    hasher.update(&order.nonce);
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// `value` in units of 10^-18, as the operator's `Decimal` parses it
+fn units(value: &str) -> Result<u128> {
+    let invalid = || anyhow::anyhow!("Invalid decimal {:?}", value);
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+        || fraction.len() > DECIMALS
+    {
+        return Err(invalid());
+    }
+
+    let whole: u128 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| invalid())? };
+    let fraction: u128 = format!("{:0<width$}", fraction, width = DECIMALS).parse().map_err(|_| invalid())?;
+    whole.checked_mul(10u128.pow(DECIMALS as u32))
+        .and_then(|whole| whole.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
+/// Shortest decimal string of `units`, e.g. `2000` or `1.5`
+fn decimal(units: u128) -> String {
+    let scale = 10u128.pow(DECIMALS as u32);
+    let fraction = format!("{:0width$}", units % scale, width = DECIMALS);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{}", units / scale)
+    } else {
+        format!("{}.{}", units / scale, fraction)
+    }
+}
+
+/// Same layout as `EncryptionManager::encrypt_order`: `order_key` wrapped with RSA-OAEP for the
+/// committee's PKCS#1 DER `public_key`, then the nonce and the ciphertext
+pub fn encrypt(public_key: &[u8], order_key: [u8; 32], nonce: [u8; 12], order: &EncryptedOrderData) -> Result<Vec<u8>> {
+    let public_key = RsaPublicKey::from_pkcs1_der(public_key)
+        .map_err(|e| anyhow::anyhow!("Invalid committee key: {}", e))?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&order_key));
+    let plaintext = serde_json::to_vec(order)?;
+    let ciphertext = cipher.encrypt(Nonce::from_slice(&nonce), plaintext.as_ref())
+        .map_err(|e| anyhow::anyhow!("Encryption failed: {:?}", e))?;
+
+    let mut encrypted = public_key.encrypt(&mut OsRng, Oaep::new::<Sha256>(), &order_key)
+        .map_err(|e| anyhow::anyhow!("Order key wrapping failed: {}", e))?;
+    encrypted.extend(nonce);
+    encrypted.extend(ciphertext);
+    Ok(encrypted)
+}
+
+fn random<const N: usize>() -> Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    getrandom::getrandom(&mut bytes).map_err(|e| anyhow::anyhow!("No randomness available: {}", e))?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rsa::pkcs1::DecodeRsaPrivateKey;
+    use rsa::traits::PublicKeyParts;
+    use rsa::RsaPrivateKey;
+    use serde_json::Value;
+
+    #[test]
+    fn test_golden_encrypted_order() -> Result<()> {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../fixtures/encrypted_order.json");
+        let fixture: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let public_key = hex::decode(fixture["public_key"].as_str().unwrap())?;
+        let private_key = RsaPrivateKey::from_pkcs1_der(&hex::decode(fixture["private_key"].as_str().unwrap())?)?;
+        let ciphertext = hex::decode(fixture["ciphertext"].as_str().unwrap())?;
+        let order: EncryptedOrderData = serde_json::from_value(fixture["order"].clone())?;
+
+        assert_eq!(commitment(&order)?, order.commitment);
+
+        // Wrapping is randomized, so only what follows the wrapped key is compared byte for byte
+        let (wrapped_key, sealed) = ciphertext.split_at(private_key.size());
+        let order_key: [u8; 32] = private_key.decrypt(Oaep::new::<Sha256>(), wrapped_key)?.as_slice().try_into()?;
+        let nonce: [u8; 12] = sealed[..12].try_into()?;
+        let encrypted = encrypt(&public_key, order_key, nonce, &order)?;
+        let (wrapped_key, resealed) = encrypted.split_at(private_key.size());
+        assert_eq!(resealed, sealed);
+        assert_eq!(private_key.decrypt(Oaep::new::<Sha256>(), wrapped_key)?, order_key);
+        Ok(())
+    }
+
+    #[test]
+    fn test_seal() -> Result<()> {
+        let order = PlainOrder {
+            trader: "0x70997970c51812dc3a010c7d01b50e0d17dc79c8".to_string(),
+            pool_key: "ETH_USDC_3000".to_string(),
+            order_type: OrderType::Buy,
+            amount: "1.50".to_string(),
+            price: "2000".to_string(),
+            deadline: 1700003600,
+            signature: None,
+            trader_nonce: Some(1),
+        };
+
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../fixtures/encrypted_order.json");
+        let fixture: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        let public_key = hex::decode(fixture["public_key"].as_str().unwrap())?;
+
+        let sealed = seal(&public_key, &order)?;
+        assert_eq!(sealed.order_id, format!("0x{}", sealed.commitment));
+        assert!(seal(&[7u8; 32], &order).is_err());
+        assert!(seal(&public_key, &PlainOrder { amount: "0".to_string(), ..order.clone() }).is_err());
+        assert!(seal(&public_key, &PlainOrder { amount: "1e3".to_string(), ..order }).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decimals_round_trip_as_the_operator_writes_them() -> Result<()> {
+        assert_eq!(units("1.5")?, 1_500_000_000_000_000_000);
+        assert_eq!(units(".5")?, 500_000_000_000_000_000);
+        assert_eq!(decimal(units("1.50")?), "1.5");
+        assert_eq!(decimal(units("2000.000")?), "2000");
+        assert_eq!(decimal(units("0.000000000000000001")?), "0.000000000000000001");
+        assert!(units("0.0000000000000000001").is_err());
+        assert!(units("-1").is_err());
+        assert!(units(".").is_err());
+        Ok(())
+    }
+}